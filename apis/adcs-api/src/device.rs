@@ -0,0 +1,44 @@
+/*
+ * Copyright (C) 2018 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::types::*;
+use crate::AdcsResult;
+
+/// Common interface for controlling an ADCS
+///
+/// Functions which aren't supported by a particular ADCS return `AdcsError::NotImplemented`.
+pub trait AdcsDevice {
+    /// Get the current estimated attitude of the spacecraft
+    fn get_attitude(&self) -> AdcsResult<Attitude>;
+
+    /// Get the current angular rates of the spacecraft, in the body frame (rad/s)
+    fn get_rates(&self) -> AdcsResult<Vector>;
+
+    /// Switch to a new control mode
+    ///
+    /// # Arguments
+    /// `mode` - Control mode to enter
+    fn set_mode(&self, mode: AdcsMode) -> AdcsResult<()>;
+
+    /// Set the attitude the ADCS should point towards
+    ///
+    /// # Arguments
+    /// `target` - Desired attitude. ADCSs may only support a subset of reference frames.
+    fn set_target(&self, target: Attitude) -> AdcsResult<()>;
+
+    /// Get the latest sensor measurements
+    fn get_sensors(&self) -> AdcsResult<SensorData>;
+}
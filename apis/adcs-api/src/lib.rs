@@ -17,6 +17,18 @@
 #![deny(missing_docs)]
 
 //! High level ADCS interfaces
+//!
+//! The [`AdcsDevice`] trait and its associated types provide a common interface for
+//! ADCS hardware, so that mission applications can be written without depending on a
+//! particular device.
+//!
+//! [`AdcsDevice`]: trait.AdcsDevice.html
+
+mod device;
+mod types;
+
+pub use crate::device::AdcsDevice;
+pub use crate::types::*;
 
 use failure::Fail;
 
@@ -0,0 +1,161 @@
+/*
+ * Copyright (C) 2018 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::f64::consts::FRAC_PI_2;
+
+/// Three-dimensional vector
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vector {
+    /// X-axis component
+    pub x: f64,
+    /// Y-axis component
+    pub y: f64,
+    /// Z-axis component
+    pub z: f64,
+}
+
+impl Vector {
+    /// Create a new vector
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Vector { x, y, z }
+    }
+
+    /// Length of the vector
+    pub fn magnitude(&self) -> f64 {
+        (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+    }
+}
+
+/// Attitude quaternion, with the scalar component last
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quaternion {
+    /// X-axis vector component
+    pub x: f64,
+    /// Y-axis vector component
+    pub y: f64,
+    /// Z-axis vector component
+    pub z: f64,
+    /// Scalar component
+    pub w: f64,
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Quaternion::identity()
+    }
+}
+
+impl Quaternion {
+    /// Create a new quaternion
+    pub fn new(x: f64, y: f64, z: f64, w: f64) -> Self {
+        Quaternion { x, y, z, w }
+    }
+
+    /// Quaternion representing no rotation
+    pub fn identity() -> Self {
+        Quaternion::new(0.0, 0.0, 0.0, 1.0)
+    }
+
+    /// Create a quaternion from roll, pitch and yaw angles (radians)
+    ///
+    /// The angles are applied in yaw, pitch, roll (Z-Y-X) order.
+    pub fn from_euler(roll: f64, pitch: f64, yaw: f64) -> Self {
+        let (sr, cr) = (roll / 2.0).sin_cos();
+        let (sp, cp) = (pitch / 2.0).sin_cos();
+        let (sy, cy) = (yaw / 2.0).sin_cos();
+
+        Quaternion {
+            x: sr * cp * cy - cr * sp * sy,
+            y: cr * sp * cy + sr * cp * sy,
+            z: cr * cp * sy - sr * sp * cy,
+            w: cr * cp * cy + sr * sp * sy,
+        }
+    }
+
+    /// Convert the quaternion into roll, pitch and yaw angles (radians)
+    ///
+    /// The angles use the same Z-Y-X order as [`from_euler`](#method.from_euler).
+    pub fn to_euler(&self) -> (f64, f64, f64) {
+        let Quaternion { x, y, z, w } = *self;
+
+        let roll = (2.0 * (w * x + y * z)).atan2(1.0 - 2.0 * (x * x + y * y));
+
+        // Guard against rounding error pushing the value just outside of asin's domain
+        let sin_pitch = 2.0 * (w * y - z * x);
+        let pitch = if sin_pitch.abs() >= 1.0 {
+            FRAC_PI_2.copysign(sin_pitch)
+        } else {
+            sin_pitch.asin()
+        };
+
+        let yaw = (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z));
+
+        (roll, pitch, yaw)
+    }
+
+    /// Length of the quaternion. Attitude quaternions should have a norm of 1
+    pub fn norm(&self) -> f64 {
+        (self.x * self.x + self.y * self.y + self.z * self.z + self.w * self.w).sqrt()
+    }
+}
+
+/// Reference frames used to describe an attitude
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Frame {
+    /// Earth-centered inertial frame
+    Inertial,
+    /// Orbit frame (Z-axis towards nadir, X-axis along the velocity vector)
+    Orbit,
+    /// Spacecraft body frame
+    Body,
+}
+
+/// Spacecraft attitude
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Attitude {
+    /// Rotation from the reference frame to the body frame
+    pub quaternion: Quaternion,
+    /// Reference frame the rotation is relative to
+    pub frame: Frame,
+}
+
+/// Common ADCS control modes
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AdcsMode {
+    /// No active attitude control
+    Idle,
+    /// Reduce the spacecraft's rotation rates
+    Detumble,
+    /// Point the spacecraft at nadir
+    NadirPointing,
+    /// Point the spacecraft at the sun
+    SunPointing,
+    /// Hold a commanded attitude relative to the inertial frame
+    InertialPointing,
+}
+
+/// Sensor measurements, in the body frame
+///
+/// Measurements which aren't available from a particular ADCS are `None`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SensorData {
+    /// Magnetic field (T)
+    pub magnetometer: Option<Vector>,
+    /// Unit vector towards the sun
+    pub sun_vector: Option<Vector>,
+    /// Angular rates measured by the gyroscope (rad/s)
+    pub gyro: Option<Vector>,
+}
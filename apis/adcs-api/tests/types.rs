@@ -0,0 +1,69 @@
+/*
+ * Copyright (C) 2018 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use adcs_api::*;
+use std::f64::consts::FRAC_PI_2;
+
+const EPSILON: f64 = 1e-9;
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < EPSILON,
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn vector_magnitude() {
+    assert_close(Vector::new(3.0, 4.0, 12.0).magnitude(), 13.0);
+}
+
+#[test]
+fn quaternion_identity() {
+    let quat = Quaternion::default();
+
+    assert_eq!(quat, Quaternion::new(0.0, 0.0, 0.0, 1.0));
+    assert_eq!(quat.to_euler(), (0.0, 0.0, 0.0));
+}
+
+#[test]
+fn quaternion_from_euler_yaw() {
+    let quat = Quaternion::from_euler(0.0, 0.0, FRAC_PI_2);
+
+    assert_close(quat.x, 0.0);
+    assert_close(quat.y, 0.0);
+    assert_close(quat.z, (0.5f64).sqrt());
+    assert_close(quat.w, (0.5f64).sqrt());
+    assert_close(quat.norm(), 1.0);
+}
+
+#[test]
+fn quaternion_euler_round_trip() {
+    let (roll, pitch, yaw) = Quaternion::from_euler(0.1, -0.4, 2.5).to_euler();
+
+    assert_close(roll, 0.1);
+    assert_close(pitch, -0.4);
+    assert_close(yaw, 2.5);
+}
+
+#[test]
+fn quaternion_to_euler_gimbal_lock() {
+    let (_, pitch, _) = Quaternion::from_euler(0.0, FRAC_PI_2, 0.0).to_euler();
+
+    assert_close(pitch, FRAC_PI_2);
+}
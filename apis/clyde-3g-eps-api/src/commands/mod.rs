@@ -14,6 +14,7 @@
  * limitations under the License.
  */
 
+mod pdm;
 mod reset;
 mod watchdog;
 
@@ -22,5 +23,6 @@ pub mod checksum;
 pub mod last_error;
 pub mod version;
 
+pub use crate::commands::pdm::*;
 pub use crate::commands::reset::*;
 pub use crate::commands::watchdog::*;
@@ -0,0 +1,120 @@
+/*
+ * Copyright (C) 2018 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use eps_api::{EpsError, EpsResult};
+use rust_i2c::Command;
+
+/// Number of Power Distribution Modules (switchable outputs) on the EPS
+pub const PDM_COUNT: u8 = 10;
+
+/// Switch PDM N On
+///
+/// Turns on the specified PDM. The data byte is the number of the PDM (1-10).
+pub mod switch_pdm_on {
+    use super::*;
+
+    pub fn command(pdm: u8) -> Command {
+        Command {
+            cmd: 0x50,
+            data: vec![pdm],
+        }
+    }
+}
+
+/// Switch PDM N Off
+///
+/// Turns off the specified PDM. The data byte is the number of the PDM (1-10).
+pub mod switch_pdm_off {
+    use super::*;
+
+    pub fn command(pdm: u8) -> Command {
+        Command {
+            cmd: 0x51,
+            data: vec![pdm],
+        }
+    }
+}
+
+/// Get PDM N Actual Status
+///
+/// Returns the actual state of the specified PDM. A value of 1 indicates that
+/// the PDM is on, 0 indicates that it is off.
+pub mod get_pdm_status {
+    use super::*;
+
+    pub fn parse(data: &[u8]) -> EpsResult<bool> {
+        if data.len() == 2 {
+            Ok(data[1] == 0x01)
+        } else {
+            Err(EpsError::parsing_failure("PDM Status"))
+        }
+    }
+
+    pub fn command(pdm: u8) -> (Command, usize) {
+        (
+            Command {
+                cmd: 0x54,
+                data: vec![pdm],
+            },
+            2,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switch_pdm_on() {
+        assert_eq!(
+            Command {
+                cmd: 0x50,
+                data: vec![0x03],
+            },
+            switch_pdm_on::command(3)
+        );
+    }
+
+    #[test]
+    fn test_switch_pdm_off() {
+        assert_eq!(
+            Command {
+                cmd: 0x51,
+                data: vec![0x0A],
+            },
+            switch_pdm_off::command(10)
+        );
+    }
+
+    #[test]
+    fn test_parse_pdm_on() {
+        assert_eq!(Ok(true), get_pdm_status::parse(&[0x00, 0x01]));
+    }
+
+    #[test]
+    fn test_parse_pdm_off() {
+        assert_eq!(Ok(false), get_pdm_status::parse(&[0x00, 0x00]));
+    }
+
+    #[test]
+    fn test_parse_pdm_bad() {
+        assert_eq!(
+            Err(EpsError::parsing_failure("PDM Status")),
+            get_pdm_status::parse(&[0x01])
+        );
+    }
+}
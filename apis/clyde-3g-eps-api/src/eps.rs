@@ -16,7 +16,8 @@
 
 use crate::commands::*;
 use crate::telemetry;
-use eps_api::EpsResult;
+use crate::telemetry::motherboard::Type as MotherboardTelemetry;
+use eps_api::{BatteryStatus, EpsDevice, EpsError, EpsResult, RailStatus};
 use rust_i2c::{Command, Connection};
 use std::thread;
 use std::time::Duration;
@@ -342,3 +343,262 @@ impl Clyde3gEps for Eps {
         Ok(())
     }
 }
+
+// Output voltage and current telemetry items for each PDM
+const PDM_TELEMETRY: [(MotherboardTelemetry, MotherboardTelemetry); PDM_COUNT as usize] = [
+    (
+        MotherboardTelemetry::OutputVoltageSwitch1,
+        MotherboardTelemetry::OutputCurrentSwitch1,
+    ),
+    (
+        MotherboardTelemetry::OutputVoltageSwitch2,
+        MotherboardTelemetry::OutputCurrentSwitch2,
+    ),
+    (
+        MotherboardTelemetry::OutputVoltageSwitch3,
+        MotherboardTelemetry::OutputCurrentSwitch3,
+    ),
+    (
+        MotherboardTelemetry::OutputVoltageSwitch4,
+        MotherboardTelemetry::OutputCurrentSwitch4,
+    ),
+    (
+        MotherboardTelemetry::OutputVoltageSwitch5,
+        MotherboardTelemetry::OutputCurrentSwitch5,
+    ),
+    (
+        MotherboardTelemetry::OutputVoltageSwitch6,
+        MotherboardTelemetry::OutputCurrentSwitch6,
+    ),
+    (
+        MotherboardTelemetry::OutputVoltageSwitch7,
+        MotherboardTelemetry::OutputCurrentSwitch7,
+    ),
+    (
+        MotherboardTelemetry::OutputVoltageSwitch8,
+        MotherboardTelemetry::OutputCurrentSwitch8,
+    ),
+    (
+        MotherboardTelemetry::OutputVoltageSwitch9,
+        MotherboardTelemetry::OutputCurrentSwitch9,
+    ),
+    (
+        MotherboardTelemetry::OutputVoltageSwitch10,
+        MotherboardTelemetry::OutputCurrentSwitch10,
+    ),
+];
+
+fn check_pdm(rail: u8) -> EpsResult<()> {
+    if rail == 0 || rail > PDM_COUNT {
+        Err(EpsError::InvalidInput {
+            description: format!("Rail {} out of range (1-{})", rail, PDM_COUNT),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Generic EPS interface
+///
+/// Rails correspond to the EPS's Power Distribution Modules (PDMs), numbered 1-10.
+/// The 3G EPS does not monitor the battery's state of charge, so `BatteryStatus::charge`
+/// is always `None`.
+impl EpsDevice for Eps {
+    fn rail_count(&self) -> u8 {
+        PDM_COUNT
+    }
+
+    fn set_rail(&self, rail: u8, enabled: bool) -> EpsResult<()> {
+        check_pdm(rail)?;
+        thread::sleep(INTER_COMMAND_DELAY);
+        if enabled {
+            self.connection.write(switch_pdm_on::command(rail))?;
+        } else {
+            self.connection.write(switch_pdm_off::command(rail))?;
+        }
+        Ok(())
+    }
+
+    fn rail_status(&self, rail: u8) -> EpsResult<RailStatus> {
+        check_pdm(rail)?;
+        thread::sleep(INTER_COMMAND_DELAY);
+        let (command, rx_len) = get_pdm_status::command(rail);
+        let enabled = get_pdm_status::parse(&self.connection.transfer(
+            command,
+            rx_len,
+            Duration::from_millis(2),
+        )?)?;
+
+        let (voltage, current) = PDM_TELEMETRY[rail as usize - 1];
+        Ok(RailStatus {
+            enabled,
+            voltage: self.get_motherboard_telemetry(voltage)?,
+            current: self.get_motherboard_telemetry(current)?,
+        })
+    }
+
+    fn battery_status(&self) -> EpsResult<BatteryStatus> {
+        Ok(BatteryStatus {
+            voltage: self.get_motherboard_telemetry(MotherboardTelemetry::OutputVoltageBattery)?,
+            current: self.get_motherboard_telemetry(MotherboardTelemetry::OutputCurrentBattery)?,
+            charge: None,
+        })
+    }
+
+    fn kick_watchdog(&self) -> EpsResult<()> {
+        self.reset_comms_watchdog()
+    }
+
+    fn reset(&self) -> EpsResult<()> {
+        self.manual_reset()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_i2c::Stream;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    // Records every command sent and answers reads with a fixed response
+    struct MockStream {
+        commands: Arc<Mutex<Vec<Command>>>,
+        response: Vec<u8>,
+    }
+
+    impl Stream for MockStream {
+        fn write(&self, command: Command) -> io::Result<()> {
+            self.commands.lock().unwrap().push(command);
+            Ok(())
+        }
+
+        fn read(&self, command: Command, _rx_len: usize) -> io::Result<Vec<u8>> {
+            self.commands.lock().unwrap().push(command);
+            Ok(self.response.clone())
+        }
+
+        fn transfer(
+            &self,
+            command: Command,
+            _rx_len: usize,
+            _delay: Duration,
+        ) -> io::Result<Vec<u8>> {
+            self.commands.lock().unwrap().push(command);
+            Ok(self.response.clone())
+        }
+    }
+
+    fn mock_eps(response: Vec<u8>) -> (Eps, Arc<Mutex<Vec<Command>>>) {
+        let commands = Arc::new(Mutex::new(vec![]));
+        let stream = MockStream {
+            commands: commands.clone(),
+            response,
+        };
+        (Eps::new(Connection::new(Box::new(stream))), commands)
+    }
+
+    #[test]
+    fn test_rail_on() {
+        let (eps, commands) = mock_eps(vec![]);
+
+        assert_eq!(eps.rail_on(4), Ok(()));
+        assert_eq!(
+            *commands.lock().unwrap(),
+            vec![Command {
+                cmd: 0x50,
+                data: vec![0x04],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rail_off() {
+        let (eps, commands) = mock_eps(vec![]);
+
+        assert_eq!(eps.rail_off(10), Ok(()));
+        assert_eq!(
+            *commands.lock().unwrap(),
+            vec![Command {
+                cmd: 0x51,
+                data: vec![0x0A],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rail_bad() {
+        let (eps, commands) = mock_eps(vec![]);
+
+        assert_eq!(
+            eps.rail_on(11),
+            Err(EpsError::InvalidInput {
+                description: "Rail 11 out of range (1-10)".to_owned(),
+            })
+        );
+        assert!(commands.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rail_status() {
+        let (eps, commands) = mock_eps(vec![0x00, 0x01]);
+
+        let status = eps.rail_status(2).unwrap();
+
+        assert!(status.enabled);
+        assert_eq!(
+            *commands.lock().unwrap(),
+            vec![
+                Command {
+                    cmd: 0x54,
+                    data: vec![0x02],
+                },
+                Command {
+                    cmd: 0x10,
+                    data: vec![0xE4, 0x20],
+                },
+                Command {
+                    cmd: 0x10,
+                    data: vec![0xE4, 0x24],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_battery_status() {
+        let (eps, commands) = mock_eps(vec![0x01, 0x00]);
+
+        let status = eps.battery_status().unwrap();
+
+        assert_eq!(status.voltage, 0.000_901_295_7 * 256.0);
+        assert_eq!(status.charge, None);
+        assert_eq!(
+            *commands.lock().unwrap(),
+            vec![
+                Command {
+                    cmd: 0x10,
+                    data: vec![0xE2, 0x20],
+                },
+                Command {
+                    cmd: 0x10,
+                    data: vec![0xE2, 0x24],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_kick_watchdog() {
+        let (eps, commands) = mock_eps(vec![]);
+
+        assert_eq!(eps.kick_watchdog(), Ok(()));
+        assert_eq!(
+            *commands.lock().unwrap(),
+            vec![Command {
+                cmd: 0x22,
+                data: vec![0x00],
+            }]
+        );
+    }
+}
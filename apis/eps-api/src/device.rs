@@ -0,0 +1,87 @@
+/*
+ * Copyright (C) 2018 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::EpsResult;
+
+/// Current state of a switchable power rail
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RailStatus {
+    /// Whether the rail is currently switched on
+    pub enabled: bool,
+    /// Output voltage of the rail (V)
+    pub voltage: f64,
+    /// Output current of the rail (mA)
+    pub current: f64,
+}
+
+/// Current state of the battery
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BatteryStatus {
+    /// Battery bus voltage (V)
+    pub voltage: f64,
+    /// Current drawn from the battery bus (mA)
+    pub current: f64,
+    /// Battery state of charge (%), if the EPS is able to report it
+    pub charge: Option<f64>,
+}
+
+/// Common interface for controlling an EPS
+///
+/// Power rails are numbered starting from 1, matching the numbering used by most EPS
+/// documentation. Functions which aren't supported by a particular EPS return
+/// `EpsError::NotImplemented`.
+pub trait EpsDevice {
+    /// Number of switchable power rails provided by the EPS
+    fn rail_count(&self) -> u8;
+
+    /// Switch a power rail on or off
+    ///
+    /// # Arguments
+    /// `rail` - Rail to switch (`1..=rail_count()`)
+    /// `enabled` - `true` to switch the rail on, `false` to switch it off
+    fn set_rail(&self, rail: u8, enabled: bool) -> EpsResult<()>;
+
+    /// Get the current state of a power rail
+    ///
+    /// # Arguments
+    /// `rail` - Rail to query (`1..=rail_count()`)
+    fn rail_status(&self, rail: u8) -> EpsResult<RailStatus>;
+
+    /// Switch a power rail on
+    ///
+    /// # Arguments
+    /// `rail` - Rail to switch (`1..=rail_count()`)
+    fn rail_on(&self, rail: u8) -> EpsResult<()> {
+        self.set_rail(rail, true)
+    }
+
+    /// Switch a power rail off
+    ///
+    /// # Arguments
+    /// `rail` - Rail to switch (`1..=rail_count()`)
+    fn rail_off(&self, rail: u8) -> EpsResult<()> {
+        self.set_rail(rail, false)
+    }
+
+    /// Get the current state of the battery
+    fn battery_status(&self) -> EpsResult<BatteryStatus>;
+
+    /// Kick the EPS's communications watchdog
+    fn kick_watchdog(&self) -> EpsResult<()>;
+
+    /// Reset the EPS
+    fn reset(&self) -> EpsResult<()>;
+}
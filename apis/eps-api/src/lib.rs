@@ -20,6 +20,18 @@
 //!
 //! This crate contains high level types and functions for use
 //! by other crates implementing EPS APIs.
+//!
+//! The [`EpsDevice`] trait provides a common interface for controlling power rails
+//! and reading battery state, so that mission applications can be written without
+//! depending on a particular EPS. A [`MockEps`] is provided for testing such applications.
+//!
+//! [`EpsDevice`]: trait.EpsDevice.html
+//! [`MockEps`]: mock/struct.MockEps.html
+
+mod device;
+pub mod mock;
+
+pub use crate::device::{BatteryStatus, EpsDevice, RailStatus};
 
 use failure::Fail;
 use std::error::Error;
@@ -28,7 +40,7 @@ use std::io;
 /// EpsError
 ///
 /// Describes various errors which may result from using EPS APIs
-#[derive(Debug, Eq, Fail, PartialEq)]
+#[derive(Clone, Debug, Eq, Fail, PartialEq)]
 #[fail(display = "Eps Error")]
 pub enum EpsError {
     /// Generic error condition
@@ -54,6 +66,15 @@ pub enum EpsError {
         /// EPS command which failed
         command: String,
     },
+    /// An invalid argument was given to an EPS function
+    #[fail(display = "Invalid input: {}", description)]
+    InvalidInput {
+        /// Description of the invalid argument
+        description: String,
+    },
+    /// Requested function is not supported by this EPS
+    #[fail(display = "Requested function has not been implemented")]
+    NotImplemented,
 }
 
 impl EpsError {
@@ -0,0 +1,213 @@
+/*
+ * Copyright (C) 2018 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Mock EPS for use when testing applications
+//!
+//! [`MockEps`] implements [`EpsDevice`] entirely in memory, so mission applications
+//! written against the trait can be tested without any EPS hardware.
+//!
+//! [`MockEps`]: struct.MockEps.html
+//! [`EpsDevice`]: ../trait.EpsDevice.html
+
+use crate::{BatteryStatus, EpsDevice, EpsError, EpsResult, RailStatus};
+use std::sync::Mutex;
+
+struct MockState {
+    rails: Vec<RailStatus>,
+    battery: BatteryStatus,
+    watchdog_kicks: u32,
+    resets: u32,
+    error: Option<EpsError>,
+}
+
+/// In-memory EPS implementing [`EpsDevice`]
+///
+/// All rails start switched off. Switching a rail on or off only changes its `enabled`
+/// flag, so tests which care about rail voltages or currents should set them with
+/// [`set_rail_status`].
+///
+/// [`EpsDevice`]: ../trait.EpsDevice.html
+/// [`set_rail_status`]: #method.set_rail_status
+///
+/// # Examples
+///
+/// ```
+/// use eps_api::*;
+/// use eps_api::mock::MockEps;
+///
+/// # fn func() -> EpsResult<()> {
+/// let eps = MockEps::new(4);
+///
+/// eps.rail_on(2)?;
+///
+/// assert!(eps.rail_status(2)?.enabled);
+/// assert!(!eps.rail_status(3)?.enabled);
+/// # Ok(())
+/// # }
+/// ```
+pub struct MockEps {
+    state: Mutex<MockState>,
+}
+
+impl MockEps {
+    /// Create a new mock EPS
+    ///
+    /// # Arguments
+    /// `rail_count` - Number of switchable power rails the mock should provide
+    pub fn new(rail_count: u8) -> Self {
+        let rail = RailStatus {
+            enabled: false,
+            voltage: 0.0,
+            current: 0.0,
+        };
+
+        MockEps {
+            state: Mutex::new(MockState {
+                rails: vec![rail; rail_count as usize],
+                battery: BatteryStatus {
+                    voltage: 8.2,
+                    current: 0.0,
+                    charge: Some(100.0),
+                },
+                watchdog_kicks: 0,
+                resets: 0,
+                error: None,
+            }),
+        }
+    }
+
+    /// Set the values returned by future `rail_status` calls for a rail
+    ///
+    /// # Arguments
+    /// `rail` - Rail to update (`1..=rail_count()`)
+    /// `status` - New status of the rail
+    pub fn set_rail_status(&self, rail: u8, status: RailStatus) -> EpsResult<()> {
+        let mut state = self.state.lock().unwrap();
+        let index = check_rail(rail, state.rails.len())?;
+        state.rails[index] = status;
+        Ok(())
+    }
+
+    /// Set the value returned by future `battery_status` calls
+    ///
+    /// # Arguments
+    /// `battery` - New battery status
+    pub fn set_battery_status(&self, battery: BatteryStatus) {
+        self.state.lock().unwrap().battery = battery;
+    }
+
+    /// Make all future `EpsDevice` calls fail with the given error
+    ///
+    /// # Arguments
+    /// `error` - Error to return, or `None` to resume normal operation
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eps_api::*;
+    /// use eps_api::mock::MockEps;
+    ///
+    /// let eps = MockEps::new(4);
+    ///
+    /// eps.set_error(Some(EpsError::GenericError));
+    ///
+    /// assert_eq!(eps.kick_watchdog(), Err(EpsError::GenericError));
+    /// ```
+    pub fn set_error(&self, error: Option<EpsError>) {
+        self.state.lock().unwrap().error = error;
+    }
+
+    /// Number of times the watchdog has been kicked
+    pub fn watchdog_kicks(&self) -> u32 {
+        self.state.lock().unwrap().watchdog_kicks
+    }
+
+    /// Number of times the EPS has been reset
+    pub fn resets(&self) -> u32 {
+        self.state.lock().unwrap().resets
+    }
+
+    fn with_state<T, F>(&self, func: F) -> EpsResult<T>
+    where
+        F: FnOnce(&mut MockState) -> EpsResult<T>,
+    {
+        let mut state = self.state.lock().unwrap();
+        if let Some(ref error) = state.error {
+            return Err(error.clone());
+        }
+        func(&mut state)
+    }
+}
+
+impl Default for MockEps {
+    fn default() -> Self {
+        MockEps::new(10)
+    }
+}
+
+// Convert a 1-based rail number into an index, if it's valid
+fn check_rail(rail: u8, count: usize) -> EpsResult<usize> {
+    if rail == 0 || rail as usize > count {
+        Err(EpsError::InvalidInput {
+            description: format!("Rail {} out of range (1-{})", rail, count),
+        })
+    } else {
+        Ok(rail as usize - 1)
+    }
+}
+
+impl EpsDevice for MockEps {
+    fn rail_count(&self) -> u8 {
+        self.state.lock().unwrap().rails.len() as u8
+    }
+
+    fn set_rail(&self, rail: u8, enabled: bool) -> EpsResult<()> {
+        self.with_state(|state| {
+            let index = check_rail(rail, state.rails.len())?;
+            state.rails[index].enabled = enabled;
+            Ok(())
+        })
+    }
+
+    fn rail_status(&self, rail: u8) -> EpsResult<RailStatus> {
+        self.with_state(|state| {
+            let index = check_rail(rail, state.rails.len())?;
+            Ok(state.rails[index])
+        })
+    }
+
+    fn battery_status(&self) -> EpsResult<BatteryStatus> {
+        self.with_state(|state| Ok(state.battery))
+    }
+
+    fn kick_watchdog(&self) -> EpsResult<()> {
+        self.with_state(|state| {
+            state.watchdog_kicks += 1;
+            Ok(())
+        })
+    }
+
+    /// Resetting the mock switches all of its rails off
+    fn reset(&self) -> EpsResult<()> {
+        self.with_state(|state| {
+            state.resets += 1;
+            for rail in state.rails.iter_mut() {
+                rail.enabled = false;
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,131 @@
+/*
+ * Copyright (C) 2018 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use eps_api::mock::MockEps;
+use eps_api::*;
+
+#[test]
+fn rails_start_off() {
+    let eps = MockEps::new(4);
+
+    assert_eq!(eps.rail_count(), 4);
+    for rail in 1..=4 {
+        assert!(!eps.rail_status(rail).unwrap().enabled);
+    }
+}
+
+#[test]
+fn rail_on_off() {
+    let eps = MockEps::new(4);
+
+    eps.rail_on(3).unwrap();
+    assert!(eps.rail_status(3).unwrap().enabled);
+
+    eps.rail_off(3).unwrap();
+    assert!(!eps.rail_status(3).unwrap().enabled);
+}
+
+#[test]
+fn rail_out_of_range() {
+    let eps = MockEps::new(4);
+
+    let expected = Err(EpsError::InvalidInput {
+        description: "Rail 5 out of range (1-4)".to_owned(),
+    });
+
+    assert_eq!(eps.rail_on(5), expected);
+    assert_eq!(
+        eps.rail_status(0),
+        Err(EpsError::InvalidInput {
+            description: "Rail 0 out of range (1-4)".to_owned(),
+        })
+    );
+}
+
+#[test]
+fn rail_status_set() {
+    let eps = MockEps::new(4);
+
+    let status = RailStatus {
+        enabled: true,
+        voltage: 5.02,
+        current: 120.0,
+    };
+    eps.set_rail_status(1, status).unwrap();
+
+    assert_eq!(eps.rail_status(1), Ok(status));
+}
+
+#[test]
+fn battery_status_set() {
+    let eps = MockEps::default();
+
+    let battery = BatteryStatus {
+        voltage: 7.4,
+        current: 350.0,
+        charge: Some(62.5),
+    };
+    eps.set_battery_status(battery);
+
+    assert_eq!(eps.battery_status(), Ok(battery));
+}
+
+#[test]
+fn watchdog_and_reset() {
+    let eps = MockEps::new(2);
+
+    eps.rail_on(1).unwrap();
+    eps.kick_watchdog().unwrap();
+    eps.kick_watchdog().unwrap();
+    eps.reset().unwrap();
+
+    assert_eq!(eps.watchdog_kicks(), 2);
+    assert_eq!(eps.resets(), 1);
+    assert!(!eps.rail_status(1).unwrap().enabled);
+}
+
+#[test]
+fn forced_error() {
+    let eps = MockEps::new(2);
+
+    eps.set_error(Some(EpsError::CommandFailure {
+        command: "Switch PDM".to_owned(),
+    }));
+    assert_eq!(
+        eps.rail_on(1),
+        Err(EpsError::CommandFailure {
+            command: "Switch PDM".to_owned(),
+        })
+    );
+
+    eps.set_error(None);
+    assert_eq!(eps.rail_on(1), Ok(()));
+}
+
+// Mission code is expected to be written against the trait, rather than a particular EPS
+fn power_cycle(eps: &dyn EpsDevice, rail: u8) -> EpsResult<()> {
+    eps.rail_off(rail)?;
+    eps.rail_on(rail)
+}
+
+#[test]
+fn trait_object() {
+    let eps = MockEps::new(2);
+
+    power_cycle(&eps, 2).unwrap();
+
+    assert!(eps.rail_status(2).unwrap().enabled);
+}
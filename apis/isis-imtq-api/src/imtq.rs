@@ -17,6 +17,34 @@
 use crate::ffi::*;
 use adcs_api::*;
 
+// iMTQ command codes
+const CANCEL_OP: u8 = 0x03;
+const START_MEASURE: u8 = 0x04;
+const START_BDOT: u8 = 0x09;
+const GET_MTM_CALIB: u8 = 0x43;
+
+// Response lengths
+const RESP_HEADER_LEN: i32 = 2;
+const RESP_MTM_LEN: i32 = 15;
+
+// There must be at least a 1ms delay in-between each I2C transfer
+const TRANSFER_DELAY_NS: i64 = 1_000_001;
+
+// Response status byte: the lower nibble is the return code, bit 7 flags unread data
+const STATUS_ERROR_MASK: u8 = 0x0F;
+const STATUS_NEW_DATA: u8 = 0x80;
+
+// Delay between checks for a finished magnetometer measurement, and the number of
+// checks to make. Covers the longest configurable MTM integration time
+const MEASURE_POLL_DELAY_NS: i64 = 10_000_000;
+const MEASURE_MAX_POLLS: u8 = 20;
+
+// Calibrated MTM scaling (T/lsb)
+const MTM_SCALE: f64 = 1.0e-9;
+
+// Duration used when entering detumble mode through `AdcsDevice::set_mode` (seconds)
+const DETUMBLE_DURATION: u16 = 0xFFFF;
+
 /// Structure for interacting with the ISIS iMTQ
 pub struct Imtq<T: ImtqFFI> {
     handle: T,
@@ -147,6 +175,88 @@ impl<T: ImtqFFI> Imtq<T> {
     }
 }
 
+/// Generic ADCS interface
+///
+/// The iMTQ is a magnetorquer board, so it has no attitude or rate estimates:
+///
+/// - Only the `Idle` and `Detumble` modes are supported. Detumbling continues for the
+///   maximum duration allowed by the iMTQ (65535 seconds), or until another mode is set
+/// - Only magnetometer data is returned by `get_sensors`. It waits for the measurement it
+///   starts to finish, returning `AdcsError::NoResponse` if no new data arrives
+/// - A non-zero return code in the iMTQ's reply is reported as `AdcsError::Internal`
+impl<T: ImtqFFI> AdcsDevice for Imtq<T> {
+    fn get_attitude(&self) -> AdcsResult<Attitude> {
+        Err(AdcsError::NotImplemented)
+    }
+
+    fn get_rates(&self) -> AdcsResult<Vector> {
+        Err(AdcsError::NotImplemented)
+    }
+
+    fn set_mode(&self, mode: AdcsMode) -> AdcsResult<()> {
+        let command = match mode {
+            AdcsMode::Idle => vec![CANCEL_OP],
+            AdcsMode::Detumble => vec![
+                START_BDOT,
+                (DETUMBLE_DURATION & 0xFF) as u8,
+                (DETUMBLE_DURATION >> 8) as u8,
+            ],
+            _ => return Err(AdcsError::NotImplemented),
+        };
+
+        let resp = self.passthrough(&command, RESP_HEADER_LEN, 0, TRANSFER_DELAY_NS)?;
+        check_status(&resp)
+    }
+
+    fn set_target(&self, _target: Attitude) -> AdcsResult<()> {
+        Err(AdcsError::NotImplemented)
+    }
+
+    fn get_sensors(&self) -> AdcsResult<SensorData> {
+        let resp = self.passthrough(&[START_MEASURE], RESP_HEADER_LEN, 0, TRANSFER_DELAY_NS)?;
+        check_status(&resp)?;
+
+        // The new data flag stays clear until the measurement's integration time has passed
+        let mut polls = 0;
+        let resp = loop {
+            let resp =
+                self.passthrough(&[GET_MTM_CALIB], RESP_MTM_LEN, 0, MEASURE_POLL_DELAY_NS)?;
+            check_status(&resp)?;
+            if resp[1] & STATUS_NEW_DATA != 0 {
+                break resp;
+            }
+
+            polls += 1;
+            if polls == MEASURE_MAX_POLLS {
+                return Err(AdcsError::NoResponse);
+            }
+        };
+
+        // Header, then the X, Y and Z axes as little-endian i32 values
+        let axis = |offset: usize| {
+            let raw = i32::from(resp[offset])
+                | i32::from(resp[offset + 1]) << 8
+                | i32::from(resp[offset + 2]) << 16
+                | i32::from(resp[offset + 3]) << 24;
+            f64::from(raw) * MTM_SCALE
+        };
+
+        Ok(SensorData {
+            magnetometer: Some(Vector::new(axis(2), axis(6), axis(10))),
+            ..Default::default()
+        })
+    }
+}
+
+// Convert the return code in a response's status byte into a result
+fn check_status(resp: &[u8]) -> AdcsResult<()> {
+    if resp[1] & STATUS_ERROR_MASK != 0 {
+        Err(AdcsError::Internal)
+    } else {
+        Ok(())
+    }
+}
+
 impl<T: ImtqFFI> Drop for Imtq<T> {
     fn drop(&mut self) {
         let _res = self.watchdog_stop();
@@ -249,6 +359,154 @@ mod tests {
         assert_eq!(Ok(()), imtq.reset());
     }
 
+    // Collect every command sent through passthrough, responding with `response`
+    fn record_commands(
+        mock: &MockImtq,
+        response: Vec<u8>,
+    ) -> ::std::sync::Arc<::std::sync::Mutex<Vec<Vec<u8>>>> {
+        let commands = ::std::sync::Arc::new(::std::sync::Mutex::new(vec![]));
+        let sent = commands.clone();
+        mock.k_adcs_passthrough.use_closure(Box::new(
+            move |(tx, tx_len, rx, rx_len, _delay): (
+                *const u8,
+                i32,
+                *mut u8,
+                i32,
+                *const timespec,
+            )| {
+                unsafe {
+                    let command = ::std::slice::from_raw_parts(tx, tx_len as usize);
+                    sent.lock().unwrap().push(command.to_vec());
+                    for (index, byte) in response.iter().take(rx_len as usize).enumerate() {
+                        *rx.add(index) = *byte;
+                    }
+                }
+                KADCSStatus::Ok
+            },
+        ));
+        commands
+    }
+
+    #[test]
+    fn test_adcs_set_mode_idle() {
+        let mock = MockImtq::default();
+        let commands = record_commands(&mock, vec![0x03, 0x00]);
+        let imtq = Imtq::new(&mock, "/dev/i2c-0", 0x40, 60).unwrap();
+
+        assert_eq!(Ok(()), imtq.set_mode(AdcsMode::Idle));
+        assert_eq!(vec![vec![0x03]], *commands.lock().unwrap());
+    }
+
+    #[test]
+    fn test_adcs_set_mode_detumble() {
+        let mock = MockImtq::default();
+        let commands = record_commands(&mock, vec![0x09, 0x00]);
+        let imtq = Imtq::new(&mock, "/dev/i2c-0", 0x40, 60).unwrap();
+
+        assert_eq!(Ok(()), imtq.set_mode(AdcsMode::Detumble));
+        assert_eq!(vec![vec![0x09, 0xFF, 0xFF]], *commands.lock().unwrap());
+    }
+
+    #[test]
+    fn test_adcs_set_mode_unsupported() {
+        let mock = MockImtq::default();
+        let imtq = Imtq::new(&mock, "/dev/i2c-0", 0x40, 60).unwrap();
+
+        assert_eq!(
+            Err(AdcsError::NotImplemented),
+            imtq.set_mode(AdcsMode::NadirPointing)
+        );
+        assert_eq!(0, mock.k_adcs_passthrough.num_calls());
+    }
+
+    #[test]
+    fn test_adcs_set_mode_error() {
+        let mock = MockImtq::default();
+        mock.k_adcs_passthrough
+            .return_value(KADCSStatus::ErrorInternal);
+        let imtq = Imtq::new(&mock, "/dev/i2c-0", 0x40, 60).unwrap();
+
+        assert_eq!(Err(AdcsError::Internal), imtq.set_mode(AdcsMode::Idle));
+    }
+
+    #[test]
+    fn test_adcs_set_mode_rejected() {
+        let mock = MockImtq::default();
+        // Command unavailable in current mode
+        record_commands(&mock, vec![0x09, 0x05]);
+        let imtq = Imtq::new(&mock, "/dev/i2c-0", 0x40, 60).unwrap();
+
+        assert_eq!(Err(AdcsError::Internal), imtq.set_mode(AdcsMode::Detumble));
+    }
+
+    #[test]
+    fn test_adcs_get_sensors() {
+        let mock = MockImtq::default();
+        // X: 25000 nT, Y: -1 nT, Z: 0x01020304 nT
+        let commands = record_commands(
+            &mock,
+            vec![
+                0x43, 0x80, 0xA8, 0x61, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x04, 0x03, 0x02, 0x01,
+                0x00,
+            ],
+        );
+        let imtq = Imtq::new(&mock, "/dev/i2c-0", 0x40, 60).unwrap();
+
+        let sensors = imtq.get_sensors().unwrap();
+
+        assert_eq!(vec![vec![0x04], vec![0x43]], *commands.lock().unwrap());
+        assert_eq!(
+            Some(Vector::new(
+                25000.0 * MTM_SCALE,
+                -MTM_SCALE,
+                f64::from(0x0102_0304) * MTM_SCALE
+            )),
+            sensors.magnetometer
+        );
+        assert_eq!(None, sensors.sun_vector);
+        assert_eq!(None, sensors.gyro);
+    }
+
+    #[test]
+    fn test_adcs_get_sensors_rejected() {
+        let mock = MockImtq::default();
+        // Internal error
+        let commands = record_commands(&mock, vec![0x04, 0x07]);
+        let imtq = Imtq::new(&mock, "/dev/i2c-0", 0x40, 60).unwrap();
+
+        assert_eq!(Err(AdcsError::Internal), imtq.get_sensors());
+        assert_eq!(vec![vec![0x04]], *commands.lock().unwrap());
+    }
+
+    #[test]
+    fn test_adcs_get_sensors_no_new_data() {
+        let mock = MockImtq::default();
+        let commands = record_commands(&mock, vec![0x43, 0x00]);
+        let imtq = Imtq::new(&mock, "/dev/i2c-0", 0x40, 60).unwrap();
+
+        assert_eq!(Err(AdcsError::NoResponse), imtq.get_sensors());
+        assert_eq!(
+            1 + MEASURE_MAX_POLLS as usize,
+            commands.lock().unwrap().len()
+        );
+    }
+
+    #[test]
+    fn test_adcs_not_implemented() {
+        let mock = MockImtq::default();
+        let imtq = Imtq::new(&mock, "/dev/i2c-0", 0x40, 60).unwrap();
+
+        assert_eq!(Err(AdcsError::NotImplemented), imtq.get_attitude());
+        assert_eq!(Err(AdcsError::NotImplemented), imtq.get_rates());
+        assert_eq!(
+            Err(AdcsError::NotImplemented),
+            imtq.set_target(Attitude {
+                quaternion: Quaternion::identity(),
+                frame: Frame::Inertial,
+            })
+        );
+    }
+
     #[test]
     fn test_watchdog_stop() {
         let mock = MockImtq::default();
@@ -15,6 +15,8 @@
  */
 
 //! Device level API for interacting with the ISIS iMTQ
+//!
+//! `Imtq` also implements the generic `AdcsDevice` trait from the `adcs-api` crate.
 
 #![deny(missing_docs)]
 #![deny(warnings)]
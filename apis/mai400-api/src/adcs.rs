@@ -0,0 +1,160 @@
+/*
+ * Copyright (C) 2018 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Generic ADCS interface for the MAI-400
+
+use crate::mai400::{MAIError, MAIResult, MAI400};
+use crate::messages::*;
+use adcs_api::*;
+
+// Quaternion and unit vector scaling (1/32767 per lsb)
+const UNIT_SCALE: f64 = 32767.0;
+// Magnetometer scaling (T/lsb)
+const MAG_SCALE: f64 = 1.0e-8;
+// Gyroscope scaling (deg/s per lsb)
+const GYRO_SCALE: f64 = 0.008_75;
+// Number of message sets to wait through for valid standard telemetry (one per 250ms)
+const MAX_READ_ATTEMPTS: u8 = 4;
+
+// ACS mode values
+const MODE_TEST: u8 = 0;
+const MODE_RATE_NULLING: u8 = 1;
+const MODE_NADIR: u8 = 3;
+const MODE_NORMAL_SUN: u8 = 7;
+const MODE_QINERTIAL: u8 = 9;
+
+fn to_adcs_error(error: MAIError) -> AdcsError {
+    match error {
+        MAIError::ThreadCommError => AdcsError::Mutex,
+        MAIError::InvalidArgument { .. } => AdcsError::Config,
+        MAIError::UartError { .. } => AdcsError::NoResponse,
+        MAIError::UnknownMessage { .. } => AdcsError::Internal,
+        MAIError::GenericError => AdcsError::Generic,
+    }
+}
+
+fn scale_vector(raw: [i16; 3], scale: f64) -> Vector {
+    Vector::new(
+        f64::from(raw[0]) * scale,
+        f64::from(raw[1]) * scale,
+        f64::from(raw[2]) * scale,
+    )
+}
+
+impl MAI400 {
+    // Wait for the next message set containing valid standard telemetry
+    fn get_standard_telemetry(&self) -> MAIResult<(StandardTelemetry, Option<RawIMU>)> {
+        for _ in 0..MAX_READ_ATTEMPTS {
+            if let (Some(std), imu, _) = self.get_message()? {
+                return Ok((std, imu));
+            }
+        }
+
+        Err(MAIError::GenericError)
+    }
+}
+
+/// Generic ADCS interface
+///
+/// - Attitudes are reported relative to the orbit frame, from the estimated orbit-to-body quaternion
+/// - Targets in the inertial frame are sent as the commanded inertial-to-body quaternion, and
+///   targets in the orbit frame are sent as roll, pitch and yaw angles
+/// - The sun vector is omitted while the device is eclipsed
+impl AdcsDevice for MAI400 {
+    fn get_attitude(&self) -> AdcsResult<Attitude> {
+        let (std, _) = self.get_standard_telemetry().map_err(to_adcs_error)?;
+
+        Ok(Attitude {
+            quaternion: Quaternion::new(
+                f64::from(std.qbo_hat[0]) / UNIT_SCALE,
+                f64::from(std.qbo_hat[1]) / UNIT_SCALE,
+                f64::from(std.qbo_hat[2]) / UNIT_SCALE,
+                f64::from(std.qbo_hat[3]) / UNIT_SCALE,
+            ),
+            frame: Frame::Orbit,
+        })
+    }
+
+    fn get_rates(&self) -> AdcsResult<Vector> {
+        let (std, _) = self.get_standard_telemetry().map_err(to_adcs_error)?;
+
+        Ok(Vector::new(
+            f64::from(std.omega_b[0]),
+            f64::from(std.omega_b[1]),
+            f64::from(std.omega_b[2]),
+        ))
+    }
+
+    fn set_mode(&self, mode: AdcsMode) -> AdcsResult<()> {
+        match mode {
+            AdcsMode::Idle => MAI400::set_mode(self, MODE_TEST, [0; 4]),
+            AdcsMode::Detumble => MAI400::set_mode(self, MODE_RATE_NULLING, [0; 4]),
+            AdcsMode::NadirPointing => MAI400::set_mode(self, MODE_NADIR, [0; 4]),
+            AdcsMode::SunPointing => self.set_mode_sun(MODE_NORMAL_SUN, 0, 0.0),
+            // Hold the identity attitude until a target is given with `set_target`
+            AdcsMode::InertialPointing => {
+                MAI400::set_mode(self, MODE_QINERTIAL, [0, 0, 0, UNIT_SCALE as i16])
+            }
+        }
+        .map_err(to_adcs_error)
+    }
+
+    fn set_target(&self, target: Attitude) -> AdcsResult<()> {
+        let norm = target.quaternion.norm();
+        if !norm.is_normal() {
+            return Err(AdcsError::Config);
+        }
+
+        match target.frame {
+            Frame::Inertial => {
+                let quat = target.quaternion;
+                let scale = UNIT_SCALE / norm;
+                self.set_qbi_cmd([
+                    (quat.x * scale).round() as i16,
+                    (quat.y * scale).round() as i16,
+                    (quat.z * scale).round() as i16,
+                    (quat.w * scale).round() as i16,
+                ])
+            }
+            Frame::Orbit => {
+                let (roll, pitch, yaw) = target.quaternion.to_euler();
+                self.set_attitude_target(
+                    roll.to_degrees() as f32,
+                    pitch.to_degrees() as f32,
+                    yaw.to_degrees() as f32,
+                )
+            }
+            Frame::Body => return Err(AdcsError::Config),
+        }
+        .map_err(to_adcs_error)
+    }
+
+    fn get_sensors(&self) -> AdcsResult<SensorData> {
+        let (std, imu) = self.get_standard_telemetry().map_err(to_adcs_error)?;
+
+        let sun_vector = if std.eclipse_flag == 0 {
+            Some(scale_vector(std.sun_vec_b, 1.0 / UNIT_SCALE))
+        } else {
+            None
+        };
+
+        Ok(SensorData {
+            magnetometer: Some(scale_vector(std.i_b_field_meas, MAG_SCALE)),
+            sun_vector,
+            gyro: imu.map(|imu| scale_vector(imu.gyro, GYRO_SCALE.to_radians())),
+        })
+    }
+}
@@ -40,12 +40,16 @@
 //! # }
 //! ```
 //!
+//! The [`MAI400`] struct also implements the generic [`AdcsDevice`] trait from the `adcs-api` crate.
+//!
 //! [`MAI400`]: struct.MAI400.html
+//! [`AdcsDevice`]: ../adcs_api/trait.AdcsDevice.html
 
 #![deny(missing_docs)]
 //Need a higher recursion limit for nom when parsing larger (>60 bytes) structures
 #![recursion_limit = "256"]
 
+mod adcs;
 mod mai400;
 mod messages;
 #[cfg(test)]
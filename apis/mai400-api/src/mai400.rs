@@ -239,6 +239,280 @@ impl MAI400 {
         self.send_message(&request)
     }
 
+    /// Set the commanded inertial-to-body quaternion
+    ///
+    /// # Arguments
+    ///
+    /// * qbi_cmd - Commanded quaternion [Q1, Q2, Q3, Q4], scaled by 32767
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`MAIError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mai400_api::*;
+    /// # fn func() -> MAIResult<()> {
+    /// let mai = MAI400::new("/dev/ttyS5")?;
+    /// mai.set_qbi_cmd([0, 0, 0, 32767])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`MAIError`]: enum.MAIError.html
+    pub fn set_qbi_cmd(&self, qbi_cmd: [i16; 4]) -> MAIResult<()> {
+        let request = SetQbiCmd {
+            qbi_cmd,
+            ..Default::default()
+        };
+
+        self.send_message(&request)
+    }
+
+    /// Set the attitude target, relative to the orbit frame
+    ///
+    /// # Arguments
+    ///
+    /// * roll - Target roll angle, in degrees
+    /// * pitch - Target pitch angle, in degrees
+    /// * yaw - Target yaw angle, in degrees
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`MAIError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mai400_api::*;
+    /// # fn func() -> MAIResult<()> {
+    /// let mai = MAI400::new("/dev/ttyS5")?;
+    /// mai.set_attitude_target(0.0, -15.0, 90.0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`MAIError`]: enum.MAIError.html
+    pub fn set_attitude_target(&self, roll: f32, pitch: f32, yaw: f32) -> MAIResult<()> {
+        let request = SetAttitudeTarget {
+            roll,
+            pitch,
+            yaw,
+            ..Default::default()
+        };
+
+        self.send_message(&request)
+    }
+
+    /// Command the reaction wheel speeds
+    ///
+    /// *Note: Only used while the ADACS is in Test Mode*
+    ///
+    /// # Arguments
+    ///
+    /// * speed - Commanded wheel speeds [X, Y, Z], in RPM
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`MAIError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mai400_api::*;
+    /// # fn func() -> MAIResult<()> {
+    /// let mai = MAI400::new("/dev/ttyS5")?;
+    /// mai.set_wheel_speed([1000, -1000, 0])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`MAIError`]: enum.MAIError.html
+    pub fn set_wheel_speed(&self, speed: [i16; 3]) -> MAIResult<()> {
+        let request = SetWheelSpeed {
+            speed,
+            ..Default::default()
+        };
+
+        self.send_message(&request)
+    }
+
+    /// Set the magnetometer calibration parameters
+    ///
+    /// # Arguments
+    ///
+    /// * bias - Magnetometer bias [X, Y, Z]
+    /// * gain - Magnetometer gain [X, Y, Z]
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`MAIError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mai400_api::*;
+    /// # fn func() -> MAIResult<()> {
+    /// let mai = MAI400::new("/dev/ttyS5")?;
+    /// mai.set_mag_params([12, -4, 7], [1.0, 1.0, 1.0])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`MAIError`]: enum.MAIError.html
+    pub fn set_mag_params(&self, bias: [i16; 3], gain: [f32; 3]) -> MAIResult<()> {
+        let request = SetMagParams {
+            bias,
+            gain,
+            ..Default::default()
+        };
+
+        self.send_message(&request)
+    }
+
+    /// Set the calibration parameters of a coarse sun sensor
+    ///
+    /// # Arguments
+    ///
+    /// * sensor - Sun sensor to configure (1-6)
+    /// * bias - Sensor bias
+    /// * gain - Sensor gain
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`MAIError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mai400_api::*;
+    /// # fn func() -> MAIResult<()> {
+    /// let mai = MAI400::new("/dev/ttyS5")?;
+    /// mai.set_css_params(2, 15, 1.02)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`MAIError`]: enum.MAIError.html
+    pub fn set_css_params(&self, sensor: u8, bias: i16, gain: f32) -> MAIResult<()> {
+        if !(1..=6).contains(&sensor) {
+            return Err(MAIError::InvalidArgument {
+                description: format!("Sun sensor {} does not exist", sensor),
+            });
+        }
+
+        let request = SetCssParams {
+            sensor,
+            bias,
+            gain,
+            ..Default::default()
+        };
+
+        self.send_message(&request)
+    }
+
+    /// Load the attitude table used by the Attitude Table ACS mode
+    ///
+    /// Each entry is sent as a separate message, in order, starting at table index 0
+    ///
+    /// # Arguments
+    ///
+    /// * entries - Table entries. Each entry contains the GPS time at which it becomes
+    ///   active and the commanded quaternion [Q1, Q2, Q3, Q4], scaled by 32767
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`MAIError`] variant will be returned.
+    /// Entries after the failed one will not be sent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mai400_api::*;
+    /// # fn func() -> MAIResult<()> {
+    /// let mai = MAI400::new("/dev/ttyS5")?;
+    /// mai.load_attitude_table(&[
+    ///     (1198800018, [0, 0, 0, 32767]),
+    ///     (1198800318, [0, 23170, 0, 23170]),
+    /// ])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`MAIError`]: enum.MAIError.html
+    pub fn load_attitude_table(&self, entries: &[(u32, [i16; 4])]) -> MAIResult<()> {
+        if entries.len() > 256 {
+            return Err(MAIError::InvalidArgument {
+                description: format!("Attitude table has {} entries. Max is 256", entries.len()),
+            });
+        }
+
+        for (index, &(gps_time, qbi_cmd)) in entries.iter().enumerate() {
+            let request = LoadAttitudeTable {
+                index: index as u8,
+                gps_time,
+                qbi_cmd,
+                ..Default::default()
+            };
+
+            self.send_message(&request)?;
+        }
+
+        Ok(())
+    }
+
+    /// Load a two-line element set for SGP4 orbit propagation
+    ///
+    /// # Arguments
+    ///
+    /// * line1 - First line of the element set. Must be 69 characters
+    /// * line2 - Second line of the element set. Must be 69 characters
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`MAIError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mai400_api::*;
+    /// # fn func() -> MAIResult<()> {
+    /// let mai = MAI400::new("/dev/ttyS5")?;
+    /// mai.load_tle(
+    ///     "1 25544U 98067A   18001.00000000  .00001764  00000-0  33869-4 0  9991",
+    ///     "2 25544  51.6424 156.2941 0003571 283.7914 173.8325 15.54253416 92702",
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`MAIError`]: enum.MAIError.html
+    pub fn load_tle(&self, line1: &str, line2: &str) -> MAIResult<()> {
+        for line in [line1, line2].iter() {
+            if line.len() != 69 || !line.is_ascii() {
+                return Err(MAIError::InvalidArgument {
+                    description: format!("Two-line element lines must be 69 characters: {}", line),
+                });
+            }
+        }
+
+        for (line_num, line) in [line1, line2].iter().enumerate() {
+            for (segment, text) in line.as_bytes().chunks(TLE_SEGMENT_LEN).enumerate() {
+                let request = LoadTLE {
+                    line: line_num as u8 + 1,
+                    segment: segment as u8,
+                    text: text.to_vec(),
+                    ..Default::default()
+                };
+
+                self.send_message(&request)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Directly send a message without formatting or checksum calculation
     ///
     /// # Arguments
@@ -418,6 +692,12 @@ pub enum MAIError {
     /// The thread reading messages from the device is no longer working
     #[fail(display = "Failed to communicate with read thread")]
     ThreadCommError,
+    /// A command argument was outside of the range accepted by the device
+    #[fail(display = "Invalid Argument: {}", description)]
+    InvalidArgument {
+        /// Description of the invalid argument
+        description: String,
+    },
     /// Received a valid message, but the message ID doesn't match any known message type
     #[fail(display = "Unknown Message Received: {:X}", id)]
     UnknownMessage {
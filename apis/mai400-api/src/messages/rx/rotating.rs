@@ -40,7 +40,7 @@ pub struct RotatingTelemetry {
     pub k_unload: [f32; 3],
     /// CSS{n} Bias (1, 2, 3, 4, 5, 6)
     pub css_bias: [i16; 6],
-    /// MAG Bias (X, Y, Z)
+    /// MAG Bias (X, Y, Z) (1e-8 T/lsb)
     pub mag_bias: [i16; 3],
     /// RWS Bus Voltage (0.00483516483 v/lsb)
     pub rws_volt: i16,
@@ -280,6 +280,201 @@ impl RotatingTelemetry {
             _ => {}
         }
     }
+
+    /// Get the attitude control gains
+    pub fn gains(&self) -> ControlGains {
+        ControlGains {
+            bdot: self.k_bdot,
+            proportional: self.kp,
+            derivative: self.kd,
+            unload: self.k_unload,
+            dipole: self.dipole_gain,
+            qb_x_filter: self.qb_x_filter_gain,
+            qb_x_dipole: self.qb_x_dipole_gain,
+        }
+    }
+
+    /// Get the sensor calibration parameters
+    pub fn calibration(&self) -> SensorCalibration {
+        SensorCalibration {
+            css_bias: self.css_bias,
+            css_gain: self.css_gain,
+            mag_bias: [
+                f32::from(self.mag_bias[0]) * MAG_BIAS_SCALE,
+                f32::from(self.mag_bias[1]) * MAG_BIAS_SCALE,
+                f32::from(self.mag_bias[2]) * MAG_BIAS_SCALE,
+            ],
+            mag_gain: self.mag_gain,
+            wheel_speed_bias: self.wheel_speed_bias,
+        }
+    }
+
+    /// Get the orbit propagation parameters
+    pub fn orbit(&self) -> OrbitParams {
+        OrbitParams {
+            prop_mode: self.orbit_prop_mode,
+            epoch: self.orbit_epoch,
+            true_anomaly_epoch: self.true_anomoly_epoch,
+            epoch_next: self.orbit_epoch_next,
+            pos_eci_epoch: self.sc_pos_eci_epoch,
+            vel_eci_epoch: self.sc_vel_eci_epoch,
+            kepler_elem: self.kepler_elem.clone(),
+        }
+    }
+
+    /// Get the attitude control limits and thresholds
+    pub fn limits(&self) -> ControlLimits {
+        ControlLimits {
+            // Out-of-range cosines would otherwise come back as NaN
+            sun_mag_align_thresh: self
+                .cos_sun_mag_align_thresh
+                .max(-1.0)
+                .min(1.0)
+                .acos()
+                .to_degrees(),
+            unload_ang_thresh: self.unload_ang_thresh,
+            q_sat: self.q_sat,
+            rwa_trq_max: self.rwa_trq_max,
+            qb_x_wheel_speed: self.qb_x_wheel_speed,
+        }
+    }
+
+    /// Get the reaction wheel status, converted from raw counts
+    pub fn wheels(&self) -> WheelStatus {
+        WheelStatus {
+            bus_voltage: f32::from(self.rws_volt) * RWS_VOLT_SCALE,
+            motor_current: [
+                f32::from(self.rws_motor_current[0]) * RWS_CURRENT_SCALE,
+                f32::from(self.rws_motor_current[1]) * RWS_CURRENT_SCALE,
+                f32::from(self.rws_motor_current[2]) * RWS_CURRENT_SCALE,
+            ],
+            motor_temp: f32::from(self.rws_motor_temp) * RWS_TEMP_SCALE + RWS_TEMP_OFFSET,
+            reset_cntr: self.rws_reset_cntr,
+        }
+    }
+
+    /// Get the current ADACS configuration
+    pub fn config(&self) -> ADACSConfig {
+        ADACSConfig {
+            gains: self.gains(),
+            calibration: self.calibration(),
+            orbit: self.orbit(),
+            limits: self.limits(),
+            major_version: self.major_version,
+            minor_version: self.minor_version,
+            mai_sn: self.mai_sn,
+        }
+    }
+}
+
+// RWS bus voltage (V/lsb)
+const RWS_VOLT_SCALE: f32 = 0.004_835_165;
+// RWS motor current (A/lsb)
+const RWS_CURRENT_SCALE: f32 = 0.000_366_300_36;
+// RWS motor temperature (degC/lsb, degC)
+const RWS_TEMP_SCALE: f32 = 0.040_293;
+const RWS_TEMP_OFFSET: f32 = -50.0;
+// Magnetometer bias (nT/lsb)
+const MAG_BIAS_SCALE: f32 = 10.0;
+
+/// ADACS configuration values, assembled from the rotating telemetry variables
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ADACSConfig {
+    /// Attitude control gains
+    pub gains: ControlGains,
+    /// Sensor calibration parameters
+    pub calibration: SensorCalibration,
+    /// Orbit propagation parameters
+    pub orbit: OrbitParams,
+    /// Attitude control limits and thresholds
+    pub limits: ControlLimits,
+    /// Software Major Version
+    pub major_version: u8,
+    /// Software Minor Version
+    pub minor_version: u8,
+    /// Software Unit Serial Number
+    pub mai_sn: u8,
+}
+
+/// Attitude control gains
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ControlGains {
+    /// Bdot Gain Acquisition Mode (X, Y, Z)
+    pub bdot: [f32; 3],
+    /// Proportional Gain Normal Mode (X, Y, Z)
+    pub proportional: [f32; 3],
+    /// Derivative Gain Normal Mode (X, Y, Z)
+    pub derivative: [f32; 3],
+    /// Unloading Gain Normal Mode (X, Y, Z)
+    pub unload: [f32; 3],
+    /// Dipole Gain (X, Y, Z)
+    pub dipole: [f32; 3],
+    /// QbX Filter Gain
+    pub qb_x_filter: f32,
+    /// QbX Dipole Gain
+    pub qb_x_dipole: f32,
+}
+
+/// Sensor calibration parameters
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SensorCalibration {
+    /// CSS{n} Bias (1, 2, 3, 4, 5, 6) (counts, on the same scale as the raw sun sensor outputs)
+    pub css_bias: [i16; 6],
+    /// CSS{n} Gain (1, 2, 3, 4, 5, 6)
+    pub css_gain: [f32; 6],
+    /// MAG Bias (X, Y, Z) (nT)
+    pub mag_bias: [f32; 3],
+    /// MAG Gain (X, Y, Z)
+    pub mag_gain: [f32; 3],
+    /// Wheel Speed Bias (X, Y, Z) (rpm)
+    pub wheel_speed_bias: [i16; 3],
+}
+
+/// Orbit propagation parameters
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OrbitParams {
+    /// Orbit Propagation Mode
+    pub prop_mode: u8,
+    /// Epoch of Current Orbit (GPS sec)
+    pub epoch: u32,
+    /// True Anomaly at Epoch – Kepler (deg)
+    pub true_anomaly_epoch: f32,
+    /// Epoch of Next Updated RV (GPS sec)
+    pub epoch_next: u32,
+    /// ECI Position at Next Epoch (X, Y, Z) (km)
+    pub pos_eci_epoch: [f32; 3],
+    /// ECI Velocity at Next Epoch (X, Y, Z) (km/sec)
+    pub vel_eci_epoch: [f32; 3],
+    /// Keplerian elements
+    pub kepler_elem: KeplerElem,
+}
+
+/// Attitude control limits and thresholds
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ControlLimits {
+    /// Sun/Mag Align Threshold Angle (deg)
+    pub sun_mag_align_thresh: f32,
+    /// Max AngleToGo for Unloading (rad)
+    pub unload_ang_thresh: f32,
+    /// Quaternion feedback saturation
+    pub q_sat: f32,
+    /// Maximum RWA Torque (mNm)
+    pub rwa_trq_max: f32,
+    /// QbX Wheel Speed Command (rpm)
+    pub qb_x_wheel_speed: i16,
+}
+
+/// Reaction wheel status
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WheelStatus {
+    /// RWS Bus Voltage (V)
+    pub bus_voltage: f32,
+    /// Reaction Wheel Motor Current (X, Y, Z) (A)
+    pub motor_current: [f32; 3],
+    /// RWS Motor Temperature (degC)
+    pub motor_temp: f32,
+    /// RWS Reset Counter (X, Y, Z)
+    pub reset_cntr: [u8; 3],
 }
 
 /// Structure for keplarian elements returned in the standard telemetry message
@@ -144,6 +144,224 @@ impl Message for SetRV {
     }
 }
 
+pub struct SetQbiCmd {
+    pub id: u8,
+    pub qbi_cmd: [i16; 4],
+}
+
+impl Default for SetQbiCmd {
+    fn default() -> Self {
+        SetQbiCmd {
+            id: 0x02,
+            qbi_cmd: [0; 4],
+        }
+    }
+}
+
+impl Message for SetQbiCmd {
+    fn serialize(&self) -> Vec<u8> {
+        let mut vec = SYNC.to_vec();
+
+        vec.push(self.id);
+        vec.write_i16::<LittleEndian>(self.qbi_cmd[0]).unwrap();
+        vec.write_i16::<LittleEndian>(self.qbi_cmd[1]).unwrap();
+        vec.write_i16::<LittleEndian>(self.qbi_cmd[2]).unwrap();
+        vec.write_i16::<LittleEndian>(self.qbi_cmd[3]).unwrap();
+        vec.append(&mut vec![0; 27]);
+        vec
+    }
+}
+
+pub struct SetAttitudeTarget {
+    pub id: u8,
+    pub roll: f32,
+    pub pitch: f32,
+    pub yaw: f32,
+}
+
+impl Default for SetAttitudeTarget {
+    fn default() -> Self {
+        SetAttitudeTarget {
+            id: 0x03,
+            roll: 0.0,
+            pitch: 0.0,
+            yaw: 0.0,
+        }
+    }
+}
+
+impl Message for SetAttitudeTarget {
+    fn serialize(&self) -> Vec<u8> {
+        let mut vec = SYNC.to_vec();
+
+        vec.push(self.id);
+        vec.write_f32::<LittleEndian>(self.roll).unwrap();
+        vec.write_f32::<LittleEndian>(self.pitch).unwrap();
+        vec.write_f32::<LittleEndian>(self.yaw).unwrap();
+        vec.append(&mut vec![0; 23]);
+        vec
+    }
+}
+
+pub struct SetWheelSpeed {
+    pub id: u8,
+    pub speed: [i16; 3],
+}
+
+impl Default for SetWheelSpeed {
+    fn default() -> Self {
+        SetWheelSpeed {
+            id: 0x06,
+            speed: [0; 3],
+        }
+    }
+}
+
+impl Message for SetWheelSpeed {
+    fn serialize(&self) -> Vec<u8> {
+        let mut vec = SYNC.to_vec();
+
+        vec.push(self.id);
+        vec.write_i16::<LittleEndian>(self.speed[0]).unwrap();
+        vec.write_i16::<LittleEndian>(self.speed[1]).unwrap();
+        vec.write_i16::<LittleEndian>(self.speed[2]).unwrap();
+        vec.append(&mut vec![0; 29]);
+        vec
+    }
+}
+
+pub struct SetMagParams {
+    pub id: u8,
+    pub bias: [i16; 3],
+    pub gain: [f32; 3],
+}
+
+impl Default for SetMagParams {
+    fn default() -> Self {
+        SetMagParams {
+            id: 0x12,
+            bias: [0; 3],
+            gain: [0.0; 3],
+        }
+    }
+}
+
+impl Message for SetMagParams {
+    fn serialize(&self) -> Vec<u8> {
+        let mut vec = SYNC.to_vec();
+
+        vec.push(self.id);
+        vec.write_i16::<LittleEndian>(self.bias[0]).unwrap();
+        vec.write_i16::<LittleEndian>(self.bias[1]).unwrap();
+        vec.write_i16::<LittleEndian>(self.bias[2]).unwrap();
+        vec.write_f32::<LittleEndian>(self.gain[0]).unwrap();
+        vec.write_f32::<LittleEndian>(self.gain[1]).unwrap();
+        vec.write_f32::<LittleEndian>(self.gain[2]).unwrap();
+        vec.append(&mut vec![0; 17]);
+        vec
+    }
+}
+
+pub struct SetCssParams {
+    pub id: u8,
+    pub sensor: u8,
+    pub bias: i16,
+    pub gain: f32,
+}
+
+impl Default for SetCssParams {
+    fn default() -> Self {
+        SetCssParams {
+            id: 0x13,
+            sensor: 0,
+            bias: 0,
+            gain: 0.0,
+        }
+    }
+}
+
+impl Message for SetCssParams {
+    fn serialize(&self) -> Vec<u8> {
+        let mut vec = SYNC.to_vec();
+
+        vec.push(self.id);
+        vec.push(self.sensor);
+        vec.write_i16::<LittleEndian>(self.bias).unwrap();
+        vec.write_f32::<LittleEndian>(self.gain).unwrap();
+        vec.append(&mut vec![0; 28]);
+        vec
+    }
+}
+
+pub struct LoadAttitudeTable {
+    pub id: u8,
+    pub index: u8,
+    pub gps_time: u32,
+    pub qbi_cmd: [i16; 4],
+}
+
+impl Default for LoadAttitudeTable {
+    fn default() -> Self {
+        LoadAttitudeTable {
+            id: 0x20,
+            index: 0,
+            gps_time: 0,
+            qbi_cmd: [0; 4],
+        }
+    }
+}
+
+impl Message for LoadAttitudeTable {
+    fn serialize(&self) -> Vec<u8> {
+        let mut vec = SYNC.to_vec();
+
+        vec.push(self.id);
+        vec.push(self.index);
+        vec.write_u32::<LittleEndian>(self.gps_time).unwrap();
+        vec.write_i16::<LittleEndian>(self.qbi_cmd[0]).unwrap();
+        vec.write_i16::<LittleEndian>(self.qbi_cmd[1]).unwrap();
+        vec.write_i16::<LittleEndian>(self.qbi_cmd[2]).unwrap();
+        vec.write_i16::<LittleEndian>(self.qbi_cmd[3]).unwrap();
+        vec.append(&mut vec![0; 22]);
+        vec
+    }
+}
+
+// Each two-line element line is 69 characters long, so is sent in three
+// segments of up to 33 characters
+pub const TLE_SEGMENT_LEN: usize = 33;
+
+pub struct LoadTLE {
+    pub id: u8,
+    pub line: u8,
+    pub segment: u8,
+    pub text: Vec<u8>,
+}
+
+impl Default for LoadTLE {
+    fn default() -> Self {
+        LoadTLE {
+            id: 0x42,
+            line: 0,
+            segment: 0,
+            text: vec![],
+        }
+    }
+}
+
+impl Message for LoadTLE {
+    fn serialize(&self) -> Vec<u8> {
+        let mut vec = SYNC.to_vec();
+
+        vec.push(self.id);
+        vec.push(self.line);
+        vec.push(self.segment);
+        vec.extend_from_slice(&self.text);
+        vec.resize(38, 0);
+        vec
+    }
+}
+
 pub struct RequestReset(pub [u8; 38]);
 
 impl Default for RequestReset {
@@ -0,0 +1,237 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+use crate::mai400::*;
+use adcs_api::*;
+use std::f64::consts::FRAC_1_SQRT_2;
+
+// A single message set containing standard telemetry (eclipsed, identity attitude)
+// and raw IMU data
+static RAW_READ: [u8; 238] = [
+    0x90, 0xEB, 0x3, 0x93, 0x3C, 0x74, 0x47, 0x0, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x44, 0x1, 0x0,
+    0x0, 0x4, 0x0, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x4, 0x0, 0x1, 0x1, 0x80, 0x1, 0x80, 0x1, 0x80,
+    0xA7, 0xFA, 0x69, 0x0, 0xEF, 0xFC, 0x7A, 0xFB, 0xE9, 0xB5, 0x37, 0xC0, 0xA, 0x34, 0x78, 0x27,
+    0x86, 0xB5, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1B, 0x2F, 0xDD, 0x3D, 0x8C, 0xB7,
+    0x53, 0xBC, 0xF9, 0xB3, 0xCC, 0x3D, 0x7F, 0xF1, 0x76, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFF, 0x7F,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFF, 0x7F, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0xFF, 0x7F, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xBF, 0xE9, 0x54, 0xBE,
+    0x34, 0x56, 0xAD, 0x40, 0x2A, 0x56, 0xAD, 0x40, 0x19, 0x7C, 0x19, 0x7C, 0x19, 0x7C, 0xE, 0x80,
+    0x8D, 0xFD, 0x8D, 0xFD, 0xBD, 0x26, 0x91, 0xEA, 0x34, 0x0, 0xA6, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x8, 0x8, 0x0,
+    0x0, 0x8, 0x8, 0x0, 0x0, 0x2A, 0xA1, 0x91, 0xEA, 0x11, 0x0, 0xC8, 0x0, 0x1, 0x0, 0xFB, 0xFF,
+    0x10, 0x1, 0x26, 0x0, 0x1D, 0x0, 0x16, 0x0, 0x13, 0x82, 0x93,
+];
+
+#[test]
+fn get_attitude_good() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(RAW_READ.to_vec());
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(
+        mai.get_attitude().unwrap(),
+        Attitude {
+            quaternion: Quaternion::identity(),
+            frame: Frame::Orbit,
+        }
+    );
+}
+
+#[test]
+fn get_attitude_bad() {
+    let mock = MockStream::default();
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(mai.get_attitude().unwrap_err(), AdcsError::Generic);
+}
+
+#[test]
+fn get_rates_good() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(RAW_READ.to_vec());
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(mai.get_rates().unwrap(), Vector::new(0.0, 0.0, 0.0));
+}
+
+#[test]
+fn get_sensors_good() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(RAW_READ.to_vec());
+
+    let mai = mock_new!(mock);
+
+    let gyro_scale = 0.008_75f64.to_radians();
+
+    assert_eq!(
+        mai.get_sensors().unwrap(),
+        SensorData {
+            magnetometer: Some(Vector::new(
+                -1369.0 * 1.0e-8,
+                105.0 * 1.0e-8,
+                -785.0 * 1.0e-8
+            )),
+            sun_vector: None,
+            gyro: Some(Vector::new(
+                38.0 * gyro_scale,
+                29.0 * gyro_scale,
+                22.0 * gyro_scale
+            )),
+        }
+    );
+}
+
+#[test]
+fn adcs_set_mode_detumble() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x0, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x7C, 0x1,
+    ]);
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(AdcsDevice::set_mode(&mai, AdcsMode::Detumble), Ok(()));
+}
+
+#[test]
+fn adcs_set_mode_inertial() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x0, 0x9, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFF, 0x7F, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x2, 0x3,
+    ]);
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(
+        AdcsDevice::set_mode(&mai, AdcsMode::InertialPointing),
+        Ok(())
+    );
+}
+
+#[test]
+fn adcs_set_mode_bad() {
+    let mock = MockStream::default();
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(
+        AdcsDevice::set_mode(&mai, AdcsMode::Idle),
+        Err(AdcsError::NoResponse)
+    );
+}
+
+#[test]
+fn set_target_inertial() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFF, 0x7F, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0xFB, 0x2,
+    ]);
+
+    let mai = mock_new!(mock);
+
+    let target = Attitude {
+        quaternion: Quaternion::identity(),
+        frame: Frame::Inertial,
+    };
+
+    assert_eq!(mai.set_target(target), Ok(()));
+}
+
+#[test]
+fn set_target_inertial_normalized() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x2, 0x0, 0x0, 0x0, 0x0, 0x82, 0x5A, 0x82, 0x5A, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x35, 0x3,
+    ]);
+
+    let mai = mock_new!(mock);
+
+    let target = Attitude {
+        quaternion: Quaternion::new(0.0, 0.0, 2.0, 2.0),
+        frame: Frame::Inertial,
+    };
+
+    assert_eq!(mai.set_target(target), Ok(()));
+}
+
+#[test]
+fn set_target_orbit() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xB4, 0x42, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x74, 0x2,
+    ]);
+
+    let mai = mock_new!(mock);
+
+    let target = Attitude {
+        quaternion: Quaternion::new(0.0, 0.0, FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        frame: Frame::Orbit,
+    };
+
+    assert_eq!(mai.set_target(target), Ok(()));
+}
+
+#[test]
+fn set_target_body() {
+    let mock = MockStream::default();
+
+    let mai = mock_new!(mock);
+
+    let target = Attitude {
+        quaternion: Quaternion::identity(),
+        frame: Frame::Body,
+    };
+
+    assert_eq!(mai.set_target(target), Err(AdcsError::Config));
+}
+
+#[test]
+fn set_target_zero() {
+    let mock = MockStream::default();
+
+    let mai = mock_new!(mock);
+
+    let target = Attitude {
+        quaternion: Quaternion::new(0.0, 0.0, 0.0, 0.0),
+        frame: Frame::Inertial,
+    };
+
+    assert_eq!(mai.set_target(target), Err(AdcsError::Config));
+}
@@ -46,6 +46,7 @@ fn mock_test() {
     );
 }
 
+mod adcs;
 mod rotating;
 mod rx;
 mod tx;
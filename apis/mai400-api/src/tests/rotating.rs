@@ -487,3 +487,165 @@ fn update_23() {
     assert_eq!(rotating.rws_motor_current[2], 0x090A);
     assert_eq!(rotating.rws_motor_temp, 0x0B0C);
 }
+
+#[test]
+fn decode_gains() {
+    let rotating = RotatingTelemetry {
+        k_bdot: [-100000.0, -100000.0, -100000.0],
+        kp: [-1.11, -1.1, -0.25],
+        kd: [-12.1, -12.0, -4.0],
+        k_unload: [-5000000.0, -5000000.0, -5000000.0],
+        dipole_gain: [1.0, 2.0, 3.0],
+        qb_x_filter_gain: 0.5,
+        qb_x_dipole_gain: 0.25,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        rotating.gains(),
+        ControlGains {
+            bdot: [-100000.0, -100000.0, -100000.0],
+            proportional: [-1.11, -1.1, -0.25],
+            derivative: [-12.1, -12.0, -4.0],
+            unload: [-5000000.0, -5000000.0, -5000000.0],
+            dipole: [1.0, 2.0, 3.0],
+            qb_x_filter: 0.5,
+            qb_x_dipole: 0.25,
+        }
+    );
+}
+
+#[test]
+fn decode_calibration() {
+    let rotating = RotatingTelemetry {
+        css_bias: [1, 2, 3, 4, 5, -6],
+        css_gain: [1.0, 1.5, 2.0, 2.5, 3.0, 3.5],
+        mag_bias: [1028, 0, -12],
+        mag_gain: [1.0, 0.5, 0.25],
+        wheel_speed_bias: [10, 20, -30],
+        ..Default::default()
+    };
+
+    assert_eq!(
+        rotating.calibration(),
+        SensorCalibration {
+            css_bias: [1, 2, 3, 4, 5, -6],
+            css_gain: [1.0, 1.5, 2.0, 2.5, 3.0, 3.5],
+            mag_bias: [10280.0, 0.0, -120.0],
+            mag_gain: [1.0, 0.5, 0.25],
+            wheel_speed_bias: [10, 20, -30],
+        }
+    );
+}
+
+#[test]
+fn decode_orbit() {
+    let kepler_elem = KeplerElem {
+        semi_major_axis: 6787.47,
+        inclination: 45.0,
+        ..Default::default()
+    };
+
+    let rotating = RotatingTelemetry {
+        orbit_prop_mode: 1,
+        orbit_epoch: 511358571,
+        true_anomoly_epoch: 12.5,
+        orbit_epoch_next: 511358871,
+        sc_pos_eci_epoch: [6787.47, 0.0, 0.0],
+        sc_vel_eci_epoch: [0.0, 5.41, 5.41],
+        kepler_elem: kepler_elem.clone(),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        rotating.orbit(),
+        OrbitParams {
+            prop_mode: 1,
+            epoch: 511358571,
+            true_anomaly_epoch: 12.5,
+            epoch_next: 511358871,
+            pos_eci_epoch: [6787.47, 0.0, 0.0],
+            vel_eci_epoch: [0.0, 5.41, 5.41],
+            kepler_elem,
+        }
+    );
+}
+
+#[test]
+fn decode_limits() {
+    let rotating = RotatingTelemetry {
+        cos_sun_mag_align_thresh: 0.5,
+        unload_ang_thresh: 0.05,
+        q_sat: 0.3,
+        rwa_trq_max: 0.2,
+        qb_x_wheel_speed: -1200,
+        ..Default::default()
+    };
+
+    let limits = rotating.limits();
+
+    assert!((limits.sun_mag_align_thresh - 60.0).abs() < 0.0001);
+    assert_eq!(limits.unload_ang_thresh, 0.05);
+    assert_eq!(limits.q_sat, 0.3);
+    assert_eq!(limits.rwa_trq_max, 0.2);
+    assert_eq!(limits.qb_x_wheel_speed, -1200);
+}
+
+#[test]
+fn decode_limits_out_of_range() {
+    let rotating = RotatingTelemetry {
+        cos_sun_mag_align_thresh: 1.000_001,
+        ..Default::default()
+    };
+    assert_eq!(rotating.limits().sun_mag_align_thresh, 0.0);
+
+    let rotating = RotatingTelemetry {
+        cos_sun_mag_align_thresh: -1.5,
+        ..Default::default()
+    };
+    assert!((rotating.limits().sun_mag_align_thresh - 180.0).abs() < 0.0001);
+}
+
+#[test]
+fn decode_wheels() {
+    let rotating = RotatingTelemetry {
+        rws_volt: 1820,
+        rws_motor_current: [0, 273, 2730],
+        rws_motor_temp: 1861,
+        rws_reset_cntr: [1, 1, 2],
+        ..Default::default()
+    };
+
+    let wheels = rotating.wheels();
+
+    assert!((wheels.bus_voltage - 8.8).abs() < 0.001);
+    assert_eq!(wheels.motor_current[0], 0.0);
+    assert!((wheels.motor_current[1] - 0.1).abs() < 0.0001);
+    assert!((wheels.motor_current[2] - 1.0).abs() < 0.0001);
+    assert!((wheels.motor_temp - 24.985).abs() < 0.001);
+    assert_eq!(wheels.reset_cntr, [1, 1, 2]);
+}
+
+#[test]
+fn decode_config() {
+    let rotating = RotatingTelemetry {
+        kp: [-1.11, -1.1, -0.25],
+        mag_bias: [1028, 0, 0],
+        orbit_epoch: 511358571,
+        q_sat: 0.3,
+        major_version: 2,
+        minor_version: 24,
+        mai_sn: 120,
+        ..Default::default()
+    };
+
+    let config = rotating.config();
+
+    assert_eq!(config.gains, rotating.gains());
+    assert_eq!(config.calibration, rotating.calibration());
+    assert_eq!(config.orbit, rotating.orbit());
+    assert_eq!(config.limits, rotating.limits());
+    assert_eq!(config.major_version, 2);
+    assert_eq!(config.minor_version, 24);
+    assert_eq!(config.mai_sn, 120);
+}
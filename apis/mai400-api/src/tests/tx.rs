@@ -202,3 +202,296 @@ fn passthrough_bad() {
         }
     );
 }
+
+#[test]
+fn set_qbi_cmd_good() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x2, 0x0, 0x0, 0x9F, 0x1B, 0x0, 0x0, 0x9F, 0x1B, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0xF1, 0x2,
+    ]);
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(mai.set_qbi_cmd([0, 7071, 0, 7071]).unwrap(), ());
+}
+
+#[test]
+fn set_qbi_cmd_bad() {
+    let mock = MockStream::default();
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(
+        mai.set_qbi_cmd([0, 0, 0, 10000]).unwrap_err(),
+        MAIError::UartError {
+            cause: UartError::GenericError,
+        }
+    );
+}
+
+#[test]
+fn set_attitude_target_good() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x3, 0x0, 0x0, 0xC0, 0x3F, 0x0, 0x0, 0x70, 0xC1, 0x0, 0x0, 0xB4, 0x42, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0xA4, 0x4,
+    ]);
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(mai.set_attitude_target(1.5, -15.0, 90.0).unwrap(), ());
+}
+
+#[test]
+fn set_attitude_target_bad() {
+    let mock = MockStream::default();
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(
+        mai.set_attitude_target(0.0, 0.0, 0.0).unwrap_err(),
+        MAIError::UartError {
+            cause: UartError::GenericError,
+        }
+    );
+}
+
+#[test]
+fn set_wheel_speed_good() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x6, 0xE8, 0x3, 0x18, 0xFC, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x80, 0x3,
+    ]);
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(mai.set_wheel_speed([1000, -1000, 0]).unwrap(), ());
+}
+
+#[test]
+fn set_wheel_speed_bad() {
+    let mock = MockStream::default();
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(
+        mai.set_wheel_speed([0, 0, 0]).unwrap_err(),
+        MAIError::UartError {
+            cause: UartError::GenericError,
+        }
+    );
+}
+
+#[test]
+fn set_mag_params_good() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x12, 0xC, 0x0, 0xFC, 0xFF, 0x7, 0x0, 0x0, 0x0, 0x80, 0x3F, 0x0, 0x0, 0xC0,
+        0x3F, 0x0, 0x0, 0x0, 0x3F, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x98, 0x5,
+    ]);
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(
+        mai.set_mag_params([12, -4, 7], [1.0, 1.5, 0.5]).unwrap(),
+        ()
+    );
+}
+
+#[test]
+fn set_mag_params_bad() {
+    let mock = MockStream::default();
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(
+        mai.set_mag_params([0, 0, 0], [1.0, 1.0, 1.0]).unwrap_err(),
+        MAIError::UartError {
+            cause: UartError::GenericError,
+        }
+    );
+}
+
+#[test]
+fn set_css_params_good() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x13, 0x2, 0xF, 0x0, 0x0, 0x0, 0xA0, 0x3F, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x7E, 0x2,
+    ]);
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(mai.set_css_params(2, 15, 1.25).unwrap(), ());
+}
+
+#[test]
+fn set_css_params_bad() {
+    let mock = MockStream::default();
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(
+        mai.set_css_params(2, 15, 1.25).unwrap_err(),
+        MAIError::UartError {
+            cause: UartError::GenericError,
+        }
+    );
+}
+
+#[test]
+fn set_css_params_bad_sensor() {
+    let mock = MockStream::default();
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(
+        mai.set_css_params(7, 15, 1.25).unwrap_err(),
+        MAIError::InvalidArgument {
+            description: "Sun sensor 7 does not exist".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn load_attitude_table_good() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x20, 0x0, 0x92, 0x3C, 0x74, 0x47, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x10, 0x27,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x5B, 0x3,
+    ]);
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x20, 0x1, 0xBE, 0x3D, 0x74, 0x47, 0x0, 0x0, 0x9F, 0x1B, 0x0, 0x0, 0x9F, 0x1B,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0xC6, 0x4,
+    ]);
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(
+        mai.load_attitude_table(&[
+            (1198800018, [0, 0, 0, 10000]),
+            (1198800318, [0, 7071, 0, 7071])
+        ])
+        .unwrap(),
+        ()
+    );
+}
+
+#[test]
+fn load_attitude_table_bad() {
+    let mock = MockStream::default();
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(
+        mai.load_attitude_table(&[(1198800018, [0, 0, 0, 10000])])
+            .unwrap_err(),
+        MAIError::UartError {
+            cause: UartError::GenericError,
+        }
+    );
+}
+
+#[test]
+fn load_tle_good() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x42, 0x1, 0x0, 0x31, 0x20, 0x32, 0x35, 0x35, 0x34, 0x34, 0x55, 0x20, 0x39,
+        0x38, 0x30, 0x36, 0x37, 0x41, 0x20, 0x20, 0x20, 0x31, 0x38, 0x30, 0x30, 0x31, 0x2E, 0x30,
+        0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x20, 0xFF, 0x7,
+    ]);
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x42, 0x1, 0x1, 0x20, 0x2E, 0x30, 0x30, 0x30, 0x30, 0x31, 0x37, 0x36, 0x34,
+        0x20, 0x20, 0x30, 0x30, 0x30, 0x30, 0x30, 0x2D, 0x30, 0x20, 0x20, 0x33, 0x33, 0x38, 0x36,
+        0x39, 0x2D, 0x34, 0x20, 0x30, 0x20, 0x20, 0x39, 0xA3, 0x7,
+    ]);
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x42, 0x1, 0x2, 0x39, 0x39, 0x31, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x63, 0x2,
+    ]);
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x42, 0x2, 0x0, 0x32, 0x20, 0x32, 0x35, 0x35, 0x34, 0x34, 0x20, 0x20, 0x35,
+        0x31, 0x2E, 0x36, 0x34, 0x32, 0x34, 0x20, 0x31, 0x35, 0x36, 0x2E, 0x32, 0x39, 0x34, 0x31,
+        0x20, 0x30, 0x30, 0x30, 0x33, 0x35, 0x37, 0x31, 0xF3, 0x7,
+    ]);
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x42, 0x2, 0x1, 0x20, 0x32, 0x38, 0x33, 0x2E, 0x37, 0x39, 0x31, 0x34, 0x20,
+        0x31, 0x37, 0x33, 0x2E, 0x38, 0x33, 0x32, 0x35, 0x20, 0x31, 0x35, 0x2E, 0x35, 0x34, 0x32,
+        0x35, 0x33, 0x34, 0x31, 0x36, 0x20, 0x39, 0x32, 0x18, 0x8,
+    ]);
+
+    mock.write.set_input(vec![
+        0x90, 0xEB, 0x42, 0x2, 0x2, 0x37, 0x30, 0x32, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x5A, 0x2,
+    ]);
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(
+        mai.load_tle(
+            "1 25544U 98067A   18001.00000000  .00001764  00000-0  33869-4 0  9991",
+            "2 25544  51.6424 156.2941 0003571 283.7914 173.8325 15.54253416 92702"
+        )
+        .unwrap(),
+        ()
+    );
+}
+
+#[test]
+fn load_tle_bad() {
+    let mock = MockStream::default();
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(
+        mai.load_tle(
+            "1 25544U 98067A   18001.00000000  .00001764  00000-0  33869-4 0  9991",
+            "2 25544  51.6424 156.2941 0003571 283.7914 173.8325 15.54253416 92702"
+        )
+        .unwrap_err(),
+        MAIError::UartError {
+            cause: UartError::GenericError,
+        }
+    );
+}
+
+#[test]
+fn load_tle_bad_length() {
+    let mock = MockStream::default();
+
+    let mai = mock_new!(mock);
+
+    assert_eq!(
+        mai.load_tle(
+            "1 25544U",
+            "2 25544  51.6424 156.2941 0003571 283.7914 173.8325 15.54253416 92702"
+        )
+        .unwrap_err(),
+        MAIError::InvalidArgument {
+            description: "Two-line element lines must be 69 characters: 1 25544U".to_owned(),
+        }
+    );
+}
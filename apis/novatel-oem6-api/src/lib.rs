@@ -75,8 +75,11 @@ mod oem6;
 #[cfg(test)]
 mod tests;
 
-pub use crate::messages::commands::ResponseID;
+pub use crate::messages::commands::{
+    ChannelState, ComPort, FixType, Handshake, InterfaceMode, Parity, ResetTarget, ResponseID,
+};
 pub use crate::messages::logs::*;
+pub use crate::messages::LogFormat;
 pub use crate::messages::MessageID;
 pub use crate::messages::ReceiverStatusFlags;
 pub use crate::oem6::*;
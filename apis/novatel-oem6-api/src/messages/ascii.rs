@@ -0,0 +1,342 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Support for NovAtel ASCII log messages
+//!
+//! ASCII logs have the form `#<NAME>A,<header fields>;<body fields>*<CRC>`.
+//! The header fields are converted into a standard [`Header`] so that the logs can be
+//! routed in the same way as binary messages, and the body is then converted into the
+//! matching [`Log`] variant.
+
+use super::*;
+use crate::crc32::calc_crc;
+use std::str::{self, FromStr, Split};
+
+/// Character marking the start of an ASCII message
+pub const ASCII_SYNC: u8 = b'#';
+
+const SOLUTION_STATUS: &[(&str, u32)] = &[
+    ("SOL_COMPUTED", 0),
+    ("INSUFFICIENT_OBS", 1),
+    ("NO_CONVERGENCE", 2),
+    ("SINGULARITY", 3),
+    ("COV_TRACE", 4),
+    ("TEST_DIST", 5),
+    ("COLD_START", 6),
+    ("V_H_LIMIT", 7),
+    ("VARIANCE", 8),
+    ("RESIDUALS", 9),
+    ("INTEGRITY_WARNING", 13),
+    ("PENDING", 18),
+    ("INVALID_FIX", 19),
+    ("UNAUTHORIZED", 20),
+];
+
+const POS_VEL_TYPE: &[(&str, u32)] = &[
+    ("NONE", 0),
+    ("FIXEDPOS", 1),
+    ("FIXEDHEIGHT", 2),
+    ("DOPPLER_VELOCITY", 8),
+    ("SINGLE", 16),
+    ("PSRDIFF", 17),
+    ("WAAS", 18),
+    ("PROPAGATED", 19),
+    ("OMNISTAR", 20),
+    ("L1_FLOAT", 32),
+    ("IONOFREE_FLOAT", 33),
+    ("NARROW_FLOAT", 34),
+    ("L1_INT", 48),
+    ("NARROW_INT", 50),
+    ("OMNISTAR_HP", 64),
+    ("OMNISTAR_XP", 65),
+    ("PPP_CONVERGING", 68),
+    ("PPP", 69),
+    ("OPERATIONAL", 70),
+    ("WARNING", 71),
+    ("OUT_OF_BOUNDS", 72),
+    ("PPP_BASIC_CONVERGING", 77),
+    ("PPP_BASIC", 78),
+];
+
+const TIME_STATUS: &[(&str, u32)] = &[
+    ("UNKNOWN", 20),
+    ("APPROXIMATE", 60),
+    ("COARSEADJUSTING", 80),
+    ("COARSE", 100),
+    ("COARSESTEERING", 120),
+    ("FREEWHEELING", 130),
+    ("FINEADJUSTING", 140),
+    ("FINE", 160),
+    ("FINEBACKUPSTEERING", 170),
+    ("FINESTEERING", 180),
+    ("SATTIME", 200),
+];
+
+const CLOCK_STATUS: &[(&str, u32)] = &[
+    ("VALID", 0),
+    ("CONVERGING", 1),
+    ("ITERATING", 2),
+    ("INVALID", 3),
+    ("ERROR", 4),
+];
+
+const UTC_STATUS: &[(&str, u32)] = &[("INVALID", 0), ("VALID", 1), ("WARNING", 2)];
+
+const DATUM: &[(&str, u32)] = &[("WGS84", 61), ("USER", 63)];
+
+/// Validate a received ASCII message and split it into its header and body
+///
+/// `raw` should contain everything following the leading `#`, up to and including the CRC
+pub fn parse_frame(raw: &[u8]) -> Option<(Header, Vec<u8>)> {
+    let text = str::from_utf8(raw).ok()?.trim_end();
+
+    let (message, crc) = split_at_char(text, '*')?;
+    if calc_crc(message.as_bytes()) != u32::from_str_radix(crc, 16).ok()? {
+        return None;
+    }
+
+    let (hdr, body) = split_at_char(message, ';')?;
+    let mut fields = Fields::new(hdr);
+
+    // Log names have an 'A' appended to indicate the ASCII format
+    let name = fields.text()?;
+    if !name.ends_with('A') {
+        return None;
+    }
+    let msg_id = match &name[..name.len() - 1] {
+        "BESTPOS" => MessageID::BestPos,
+        "BESTVEL" => MessageID::BestVel,
+        "BESTXYZ" => MessageID::BestXYZ,
+        "TIME" => MessageID::Time,
+        _ => MessageID::Unknown,
+    };
+
+    // Port
+    fields.text()?;
+    let seq = fields.num()?;
+    // Idle time is given as a percentage, but binary headers report it in 0.5% units
+    let idle_time = (fields.num::<f32>()? * 2.0) as u8;
+    let time_status = fields.lookup(TIME_STATUS)? as u8;
+    let week = fields.num()?;
+    let ms = (fields.num::<f64>()? * 1000.0).round() as i32;
+    let recv_status = ReceiverStatusFlags::from_bits_truncate(fields.hex()?);
+    // Reserved
+    fields.text()?;
+    let recv_ver = fields.num()?;
+
+    let hdr = Header {
+        msg_id,
+        msg_type: LogFormat::Ascii.msg_type(),
+        msg_len: body.len() as u16,
+        seq,
+        idle_time,
+        time_status,
+        week,
+        ms,
+        recv_status,
+        recv_ver,
+        ..Default::default()
+    };
+
+    Some((hdr, body.as_bytes().to_vec()))
+}
+
+/// Convert the body of an ASCII log message into a useable struct
+pub fn parse_log(hdr: &Header, raw: &[u8]) -> Option<Log> {
+    let mut fields = Fields::new(str::from_utf8(raw).ok()?);
+
+    match hdr.msg_id {
+        MessageID::BestPos => parse_bestpos(hdr, &mut fields).map(Log::BestPos),
+        MessageID::BestVel => parse_bestvel(hdr, &mut fields).map(Log::BestVel),
+        MessageID::BestXYZ => parse_bestxyz(hdr, &mut fields).map(Log::BestXYZ),
+        MessageID::Time => parse_time(hdr, &mut fields).map(Log::Time),
+        _ => None,
+    }
+}
+
+fn parse_bestpos(hdr: &Header, fields: &mut Fields) -> Option<BestPosLog> {
+    let pos_status = fields.lookup(SOLUTION_STATUS)?;
+    let pos_type = fields.lookup(POS_VEL_TYPE)?;
+    let lat = fields.num()?;
+    let lon = fields.num()?;
+    let height = fields.num()?;
+    let undulation = fields.num()?;
+    let datum_id = fields.lookup(DATUM)?;
+    let pos_deviation = [fields.num()?, fields.num()?, fields.num()?];
+    let station_id = fields.text()?.trim_matches('"').to_owned();
+    let diff_age = fields.num()?;
+    let sol_age = fields.num()?;
+    let num_sats = fields.num()?;
+    let num_sat_vehicles = fields.num()?;
+    let num_gg_l1 = fields.num()?;
+    let num_multi_sats = fields.num()?;
+    // Reserved
+    fields.text()?;
+    let ext_sol_stat = fields.hex()? as u8;
+    let gal_beidou_sig = fields.hex()? as u8;
+    let gps_glonass_sig = fields.hex()? as u8;
+
+    Some(BestPosLog {
+        recv_status: hdr.recv_status,
+        time_status: hdr.time_status,
+        week: hdr.week,
+        ms: hdr.ms,
+        pos_status,
+        pos_type,
+        lat,
+        lon,
+        height,
+        undulation,
+        datum_id,
+        pos_deviation,
+        station_id,
+        diff_age,
+        sol_age,
+        num_sats,
+        num_sat_vehicles,
+        num_gg_l1,
+        num_multi_sats,
+        ext_sol_stat,
+        gal_beidou_sig,
+        gps_glonass_sig,
+    })
+}
+
+fn parse_bestvel(hdr: &Header, fields: &mut Fields) -> Option<BestVelLog> {
+    Some(BestVelLog {
+        recv_status: hdr.recv_status,
+        time_status: hdr.time_status,
+        week: hdr.week,
+        ms: hdr.ms,
+        vel_status: fields.lookup(SOLUTION_STATUS)?,
+        vel_type: fields.lookup(POS_VEL_TYPE)?,
+        latency: fields.num()?,
+        diff_age: fields.num()?,
+        hor_speed: fields.num()?,
+        track_ground: fields.num()?,
+        vert_speed: fields.num()?,
+    })
+}
+
+fn parse_bestxyz(hdr: &Header, fields: &mut Fields) -> Option<BestXYZLog> {
+    let pos_status = fields.lookup(SOLUTION_STATUS)?;
+    let pos_type = fields.lookup(POS_VEL_TYPE)?;
+    let position = [fields.num()?, fields.num()?, fields.num()?];
+    let pos_deviation = [fields.num()?, fields.num()?, fields.num()?];
+    let vel_status = fields.lookup(SOLUTION_STATUS)?;
+    let vel_type = fields.lookup(POS_VEL_TYPE)?;
+    let velocity = [fields.num()?, fields.num()?, fields.num()?];
+    let vel_deviation = [fields.num()?, fields.num()?, fields.num()?];
+    let station_id = fields.text()?.trim_matches('"').to_owned();
+    let vel_time_latency = fields.num()?;
+    let diff_age = fields.num()?;
+    let sol_age = fields.num()?;
+    let num_sats = fields.num()?;
+    let num_sat_vehicles = fields.num()?;
+    let num_gg_l1 = fields.num()?;
+    let num_multi_sats = fields.num()?;
+    // Reserved
+    fields.text()?;
+    let ext_sol_stat = fields.hex()? as u8;
+    let gal_beidou_sig = fields.hex()? as u8;
+    let gps_glonass_sig = fields.hex()? as u8;
+
+    Some(BestXYZLog {
+        recv_status: hdr.recv_status,
+        time_status: hdr.time_status,
+        week: hdr.week,
+        ms: hdr.ms,
+        pos_status,
+        pos_type,
+        position,
+        pos_deviation,
+        vel_status,
+        vel_type,
+        velocity,
+        vel_deviation,
+        station_id,
+        vel_time_latency,
+        diff_age,
+        sol_age,
+        num_sats,
+        num_sat_vehicles,
+        num_gg_l1,
+        num_multi_sats,
+        ext_sol_stat,
+        gal_beidou_sig,
+        gps_glonass_sig,
+    })
+}
+
+fn parse_time(hdr: &Header, fields: &mut Fields) -> Option<TimeLog> {
+    Some(TimeLog {
+        recv_status: hdr.recv_status,
+        time_status: hdr.time_status,
+        week: hdr.week,
+        ms: hdr.ms,
+        clock_status: fields.lookup(CLOCK_STATUS)?,
+        offset: fields.num()?,
+        offset_std: fields.num()?,
+        utc_offset: fields.num()?,
+        utc_year: fields.num()?,
+        utc_month: fields.num()?,
+        utc_day: fields.num()?,
+        utc_hour: fields.num()?,
+        utc_min: fields.num()?,
+        utc_ms: fields.num()?,
+        utc_status: fields.lookup(UTC_STATUS)?,
+    })
+}
+
+// Split a string around the first instance of the given character
+fn split_at_char(text: &str, delim: char) -> Option<(&str, &str)> {
+    text.find(delim)
+        .map(|index| (&text[..index], &text[index + 1..]))
+}
+
+// Sequential reader for the comma-separated fields of a message
+struct Fields<'a> {
+    fields: Split<'a, char>,
+}
+
+impl<'a> Fields<'a> {
+    fn new(text: &'a str) -> Self {
+        Fields {
+            fields: text.split(','),
+        }
+    }
+
+    fn text(&mut self) -> Option<&'a str> {
+        self.fields.next()
+    }
+
+    fn num<T: FromStr>(&mut self) -> Option<T> {
+        self.text()?.parse().ok()
+    }
+
+    fn hex(&mut self) -> Option<u32> {
+        u32::from_str_radix(self.text()?, 16).ok()
+    }
+
+    // Convert an enumerated field into its binary value
+    fn lookup(&mut self, table: &[(&str, u32)]) -> Option<u32> {
+        let field = self.text()?;
+        table
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, value)| *value)
+    }
+}
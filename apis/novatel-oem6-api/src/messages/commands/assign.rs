@@ -0,0 +1,74 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+
+pub struct AssignCmd {
+    hdr: Header,
+    channel: u32,
+    state: ChannelState,
+    prn: u32,
+    doppler: i32,
+    doppler_window: u32,
+}
+
+impl AssignCmd {
+    pub fn new(
+        channel: u32,
+        state: ChannelState,
+        prn: u32,
+        doppler: i32,
+        doppler_window: u32,
+    ) -> Self {
+        AssignCmd {
+            hdr: Header::new(MessageID::Assign, 20),
+            channel,
+            state,
+            prn,
+            doppler,
+            doppler_window,
+        }
+    }
+}
+
+impl Message for AssignCmd {
+    fn serialize(&self) -> Vec<u8> {
+        let mut vec = vec![];
+
+        //Add header
+        vec.append(&mut self.hdr.serialize());
+
+        // Add ASSIGN message
+        vec.write_u32::<LittleEndian>(self.channel).unwrap();
+        vec.write_u32::<LittleEndian>(self.state as u32).unwrap();
+        vec.write_u32::<LittleEndian>(self.prn).unwrap();
+        vec.write_i32::<LittleEndian>(self.doppler).unwrap();
+        vec.write_u32::<LittleEndian>(self.doppler_window).unwrap();
+
+        vec
+    }
+}
+
+/// Tracking states which can be assigned to a receiver channel
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ChannelState {
+    /// Channel is idle and will not track any satellite
+    Idle = 0,
+    /// Channel will track the assigned satellite
+    Active = 1,
+    /// Channel is returned to automatic satellite selection
+    Auto = 2,
+}
@@ -0,0 +1,70 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+
+pub struct FixCmd {
+    hdr: Header,
+    fix_type: FixType,
+    params: [f64; 3],
+}
+
+impl FixCmd {
+    pub fn new(fix_type: FixType, lat: f64, lon: f64, height: f64) -> Self {
+        // The meaning of each parameter depends on the fix type
+        let params = match fix_type {
+            FixType::None | FixType::Auto => [0.0, 0.0, 0.0],
+            FixType::Height => [height, 0.0, 0.0],
+            FixType::Position => [lat, lon, height],
+        };
+
+        FixCmd {
+            hdr: Header::new(MessageID::Fix, 28),
+            fix_type,
+            params,
+        }
+    }
+}
+
+impl Message for FixCmd {
+    fn serialize(&self) -> Vec<u8> {
+        let mut vec = vec![];
+
+        //Add header
+        vec.append(&mut self.hdr.serialize());
+
+        // Add FIX message
+        vec.write_u32::<LittleEndian>(self.fix_type as u32).unwrap();
+        for param in self.params.iter() {
+            vec.write_f64::<LittleEndian>(*param).unwrap();
+        }
+
+        vec
+    }
+}
+
+/// Constraints which can be applied to the receiver's position solution
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FixType {
+    /// Remove any previous fix
+    None = 0,
+    /// Fix the height at the last calculated value, if the receiver is moving slowly
+    Auto = 1,
+    /// Fix the height to the given value
+    Height = 2,
+    /// Fix the position to the given value
+    Position = 3,
+}
@@ -0,0 +1,68 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+
+pub struct FResetCmd {
+    hdr: Header,
+    target: ResetTarget,
+}
+
+impl FResetCmd {
+    pub fn new(target: ResetTarget) -> Self {
+        FResetCmd {
+            hdr: Header::new(MessageID::FReset, 4),
+            target,
+        }
+    }
+}
+
+impl Message for FResetCmd {
+    fn serialize(&self) -> Vec<u8> {
+        let mut vec = vec![];
+
+        //Add header
+        vec.append(&mut self.hdr.serialize());
+
+        // Add FRESET message
+        vec.write_u32::<LittleEndian>(self.target as u32).unwrap();
+
+        vec
+    }
+}
+
+/// Data which can be cleared by a factory reset
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ResetTarget {
+    /// Clear all stored data and restore the factory default configuration
+    Standard = 0,
+    /// Clear the saved configuration
+    Command = 1,
+    /// Clear the stored GPS almanac
+    GpsAlmanac = 2,
+    /// Clear the stored GPS ephemerides
+    GpsEphemeris = 3,
+    /// Clear the stored GLONASS ephemerides
+    GlonassEphemeris = 4,
+    /// Clear the currently selected model
+    Model = 5,
+    /// Clear the stored clock calibration
+    ClockCalibration = 11,
+    /// Clear the stored SBAS almanac
+    SbasAlmanac = 20,
+    /// Clear the last known position
+    LastPosition = 21,
+}
@@ -0,0 +1,81 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+
+pub struct InterfaceModeCmd {
+    hdr: Header,
+    port: ComPort,
+    rx_mode: InterfaceMode,
+    tx_mode: InterfaceMode,
+    responses: bool,
+}
+
+impl InterfaceModeCmd {
+    pub fn new(
+        port: ComPort,
+        rx_mode: InterfaceMode,
+        tx_mode: InterfaceMode,
+        responses: bool,
+    ) -> Self {
+        InterfaceModeCmd {
+            hdr: Header::new(MessageID::InterfaceMode, 16),
+            port,
+            rx_mode,
+            tx_mode,
+            responses,
+        }
+    }
+}
+
+impl Message for InterfaceModeCmd {
+    fn serialize(&self) -> Vec<u8> {
+        let mut vec = vec![];
+
+        //Add header
+        vec.append(&mut self.hdr.serialize());
+
+        // Add INTERFACEMODE message
+        vec.write_u32::<LittleEndian>(self.port as u32).unwrap();
+        vec.write_u32::<LittleEndian>(self.rx_mode as u32).unwrap();
+        vec.write_u32::<LittleEndian>(self.tx_mode as u32).unwrap();
+        vec.write_u32::<LittleEndian>(self.responses as u32)
+            .unwrap();
+
+        vec
+    }
+}
+
+/// Protocols which a serial port can use to receive or transmit data
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum InterfaceMode {
+    /// No data will be accepted or sent
+    None = 0,
+    /// NovAtel ASCII or binary commands and logs
+    NovAtel = 1,
+    /// RTCM corrections
+    Rtcm = 2,
+    /// RTCA corrections
+    Rtca = 3,
+    /// CMR corrections
+    Cmr = 4,
+    /// RTCM corrections, without CR/LF appended
+    RtcmNoCr = 8,
+    /// NovAtel binary commands and logs only
+    NovAtelBinary = 14,
+    /// RTCM version 3 corrections
+    RtcmV3 = 19,
+}
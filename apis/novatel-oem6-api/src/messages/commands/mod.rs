@@ -17,11 +17,27 @@
 use super::*;
 use byteorder::LittleEndian;
 
+mod assign;
+mod ecutoff;
+mod fix;
+mod freset;
+mod interface_mode;
 mod log;
+mod reset;
+mod save_config;
+mod serial_config;
 mod unlog;
 mod unlog_all;
 
+pub use self::assign::*;
+pub use self::ecutoff::*;
+pub use self::fix::*;
+pub use self::freset::*;
+pub use self::interface_mode::*;
 pub use self::log::*;
+pub use self::reset::*;
+pub use self::save_config::*;
+pub use self::serial_config::*;
 pub use self::unlog::*;
 pub use self::unlog_all::*;
 
@@ -0,0 +1,36 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+
+pub struct SaveConfigCmd {
+    hdr: Header,
+}
+
+impl SaveConfigCmd {
+    pub fn new() -> Self {
+        SaveConfigCmd {
+            hdr: Header::new(MessageID::SaveConfig, 0),
+        }
+    }
+}
+
+impl Message for SaveConfigCmd {
+    fn serialize(&self) -> Vec<u8> {
+        // SAVECONFIG has no fields, so the message is just the header
+        self.hdr.serialize()
+    }
+}
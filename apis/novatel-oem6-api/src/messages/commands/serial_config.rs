@@ -0,0 +1,104 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+
+pub struct SerialConfigCmd {
+    hdr: Header,
+    port: ComPort,
+    baud: u32,
+    parity: Parity,
+    data_bits: u32,
+    stop_bits: u32,
+    handshake: Handshake,
+}
+
+impl SerialConfigCmd {
+    pub fn new(
+        port: ComPort,
+        baud: u32,
+        parity: Parity,
+        data_bits: u32,
+        stop_bits: u32,
+        handshake: Handshake,
+    ) -> Self {
+        SerialConfigCmd {
+            hdr: Header::new(MessageID::SerialConfig, 28),
+            port,
+            baud,
+            parity,
+            data_bits,
+            stop_bits,
+            handshake,
+        }
+    }
+}
+
+impl Message for SerialConfigCmd {
+    fn serialize(&self) -> Vec<u8> {
+        let mut vec = vec![];
+
+        //Add header
+        vec.append(&mut self.hdr.serialize());
+
+        // Add SERIALCONFIG message
+        vec.write_u32::<LittleEndian>(self.port as u32).unwrap();
+        vec.write_u32::<LittleEndian>(self.baud).unwrap();
+        vec.write_u32::<LittleEndian>(self.parity as u32).unwrap();
+        vec.write_u32::<LittleEndian>(self.data_bits).unwrap();
+        vec.write_u32::<LittleEndian>(self.stop_bits).unwrap();
+        vec.write_u32::<LittleEndian>(self.handshake as u32)
+            .unwrap();
+        vec.write_u32::<LittleEndian>(0).unwrap(); // Break detection off
+
+        vec
+    }
+}
+
+/// Serial ports which can be configured
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ComPort {
+    /// COM1
+    Com1 = 1,
+    /// COM2
+    Com2 = 2,
+    /// COM3
+    Com3 = 3,
+    /// The port the command is being sent through
+    ThisPort = 6,
+}
+
+/// Serial port parity settings
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Parity {
+    /// No parity
+    None = 0,
+    /// Even parity
+    Even = 1,
+    /// Odd parity
+    Odd = 2,
+}
+
+/// Serial port handshaking settings
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Handshake {
+    /// No handshaking
+    None = 0,
+    /// XON/XOFF software handshaking
+    XOnXOff = 1,
+    /// CTS/RTS hardware handshaking
+    CtsRts = 2,
+}
@@ -0,0 +1,141 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+use nom::*;
+
+/// Log message containing geodetic position information
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct BestPosLog {
+    /// Current status of receiver
+    pub recv_status: ReceiverStatusFlags,
+    /// Validity of the time information
+    pub time_status: u8,
+    /// GPS reference week
+    pub week: u16,
+    /// Milliseconds into GPS reference week
+    pub ms: i32,
+    /// Position solution status
+    pub pos_status: u32,
+    /// Position type
+    pub pos_type: u32,
+    /// Latitude (degrees)
+    pub lat: f64,
+    /// Longitude (degrees)
+    pub lon: f64,
+    /// Height above mean sea level (m)
+    pub height: f64,
+    /// Undulation - the relationship between the geoid and the WGS84 ellipsoid (m)
+    pub undulation: f32,
+    /// Datum ID number
+    pub datum_id: u32,
+    /// Standard deviation of {latitude, longitude, height} (m)
+    pub pos_deviation: [f32; 3],
+    /// Base station ID
+    pub station_id: String,
+    /// Differential age (seconds)
+    pub diff_age: f32,
+    /// Solution age (seconds)
+    pub sol_age: f32,
+    /// Number of satellites tracked
+    pub num_sats: u8,
+    /// Number of satellite vehicles used in solution
+    pub num_sat_vehicles: u8,
+    /// Number of GPS plus GLONASS plus BDS L1/B1 used in solution
+    pub num_gg_l1: u8,
+    /// Number of satellites with L1/E1/B1 signals used in solution
+    pub num_multi_sats: u8,
+    /// Extended solution status
+    pub ext_sol_stat: u8,
+    /// Galileo and BeiDou signals used mask
+    pub gal_beidou_sig: u8,
+    /// GPS and GLONASS signals used mask
+    pub gps_glonass_sig: u8,
+}
+
+impl BestPosLog {
+    /// Convert a raw data buffer into a useable struct
+    pub fn new(
+        recv_status: ReceiverStatusFlags,
+        time_status: u8,
+        week: u16,
+        ms: i32,
+        raw: &[u8],
+    ) -> Option<Self> {
+        let mut log = match parse_bestpos(&raw) {
+            Ok(conv) => conv.1,
+            _ => return None,
+        };
+
+        log.recv_status = recv_status;
+        log.time_status = time_status;
+        log.week = week;
+        log.ms = ms;
+
+        Some(log)
+    }
+}
+
+named!(parse_bestpos(&[u8]) -> BestPosLog,
+    do_parse!(
+        pos_status: le_u32 >>
+        pos_type: le_u32 >>
+        lat: le_f64 >>
+        lon: le_f64 >>
+        height: le_f64 >>
+        undulation: le_f32 >>
+        datum_id: le_u32 >>
+        lat_dev: le_f32 >>
+        lon_dev: le_f32 >>
+        height_dev: le_f32 >>
+        station_id: take!(4) >>
+        diff_age: le_f32 >>
+        sol_age: le_f32 >>
+        num_sats: le_u8 >>
+        num_sat_vehicles: le_u8 >>
+        num_gg_l1: le_u8 >>
+        num_multi_sats: le_u8 >>
+        le_u8 >>
+        ext_sol_stat: le_u8 >>
+        gal_beidou_sig: le_u8 >>
+        gps_glonass_sig: le_u8 >>
+        (BestPosLog {
+            recv_status: ReceiverStatusFlags::empty(),
+            time_status: 0,
+            week: 0,
+            ms: 0,
+            pos_status,
+            pos_type,
+            lat,
+            lon,
+            height,
+            undulation,
+            datum_id,
+            pos_deviation: [lat_dev, lon_dev, height_dev],
+            station_id: String::from_utf8_lossy(station_id).trim_end_matches('\u{0}').to_owned(),
+            diff_age,
+            sol_age,
+            num_sats,
+            num_sat_vehicles,
+            num_gg_l1,
+            num_multi_sats,
+            ext_sol_stat,
+            gal_beidou_sig,
+            gps_glonass_sig,
+            }
+        )
+    )
+);
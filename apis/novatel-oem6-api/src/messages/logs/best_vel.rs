@@ -0,0 +1,95 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+use nom::*;
+
+/// Log message containing velocity information
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct BestVelLog {
+    /// Current status of receiver
+    pub recv_status: ReceiverStatusFlags,
+    /// Validity of the time information
+    pub time_status: u8,
+    /// GPS reference week
+    pub week: u16,
+    /// Milliseconds into GPS reference week
+    pub ms: i32,
+    /// Velocity solution status
+    pub vel_status: u32,
+    /// Velocity type
+    pub vel_type: u32,
+    /// Latency of the velocity time tag (seconds)
+    pub latency: f32,
+    /// Differential age (seconds)
+    pub diff_age: f32,
+    /// Horizontal speed over ground (m/s)
+    pub hor_speed: f64,
+    /// Actual direction of motion over ground with respect to true north (degrees)
+    pub track_ground: f64,
+    /// Vertical speed, where positive values indicate increasing altitude (m/s)
+    pub vert_speed: f64,
+}
+
+impl BestVelLog {
+    /// Convert a raw data buffer into a useable struct
+    pub fn new(
+        recv_status: ReceiverStatusFlags,
+        time_status: u8,
+        week: u16,
+        ms: i32,
+        raw: &[u8],
+    ) -> Option<Self> {
+        let mut log = match parse_bestvel(&raw) {
+            Ok(conv) => conv.1,
+            _ => return None,
+        };
+
+        log.recv_status = recv_status;
+        log.time_status = time_status;
+        log.week = week;
+        log.ms = ms;
+
+        Some(log)
+    }
+}
+
+named!(parse_bestvel(&[u8]) -> BestVelLog,
+    do_parse!(
+        vel_status: le_u32 >>
+        vel_type: le_u32 >>
+        latency: le_f32 >>
+        diff_age: le_f32 >>
+        hor_speed: le_f64 >>
+        track_ground: le_f64 >>
+        vert_speed: le_f64 >>
+        le_f32 >>
+        (BestVelLog {
+            recv_status: ReceiverStatusFlags::empty(),
+            time_status: 0,
+            week: 0,
+            ms: 0,
+            vel_status,
+            vel_type,
+            latency,
+            diff_age,
+            hor_speed,
+            track_ground,
+            vert_speed,
+            }
+        )
+    )
+);
@@ -0,0 +1,43 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+
+/// Log message containing the dilution of precision and the satellites used in the
+/// position solution
+///
+/// Note: This information is only available from NMEA GSA sentences
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct GpsDopLog {
+    /// Current status of receiver
+    pub recv_status: ReceiverStatusFlags,
+    /// Validity of the time information
+    pub time_status: u8,
+    /// GPS reference week
+    pub week: u16,
+    /// Milliseconds into GPS reference week
+    pub ms: i32,
+    /// Fix mode. 1 = Fix not available, 2 = 2D, 3 = 3D
+    pub fix_mode: u8,
+    /// PRNs of the satellites used in the solution
+    pub prns: Vec<u16>,
+    /// Position dilution of precision
+    pub pdop: f32,
+    /// Horizontal dilution of precision
+    pub hdop: f32,
+    /// Vertical dilution of precision
+    pub vdop: f32,
+}
@@ -0,0 +1,228 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+use nom::*;
+
+/// Log message containing the decoded ephemeris parameters for a GPS satellite
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct GpsEphemLog {
+    /// Current status of receiver
+    pub recv_status: ReceiverStatusFlags,
+    /// Validity of the time information
+    pub time_status: u8,
+    /// GPS reference week
+    pub week: u16,
+    /// Milliseconds into GPS reference week
+    pub ms: i32,
+    /// Satellite PRN number
+    pub prn: u32,
+    /// Time stamp of subframe 1 (seconds)
+    pub tow: f64,
+    /// Health status - a 6-bit health code as defined in ICD-GPS-200
+    pub health: u32,
+    /// Issue of ephemeris data 1
+    pub iode1: u32,
+    /// Issue of ephemeris data 2
+    pub iode2: u32,
+    /// GPS reference week number of the ephemeris
+    pub ephem_week: u32,
+    /// Z count week number
+    pub z_week: u32,
+    /// Reference time for ephemeris (seconds)
+    pub toe: f64,
+    /// Semi-major axis (m)
+    pub semi_major_axis: f64,
+    /// Mean motion difference (radians/second)
+    pub delta_n: f64,
+    /// Mean anomaly of reference time (radians)
+    pub mean_anomaly: f64,
+    /// Eccentricity
+    pub eccentricity: f64,
+    /// Argument of perigee (radians)
+    pub arg_perigee: f64,
+    /// Amplitude of cosine harmonic correction term to the argument of latitude (radians)
+    pub cuc: f64,
+    /// Amplitude of sine harmonic correction term to the argument of latitude (radians)
+    pub cus: f64,
+    /// Amplitude of cosine harmonic correction term to the orbit radius (m)
+    pub crc: f64,
+    /// Amplitude of sine harmonic correction term to the orbit radius (m)
+    pub crs: f64,
+    /// Amplitude of cosine harmonic correction term to the angle of inclination (radians)
+    pub cic: f64,
+    /// Amplitude of sine harmonic correction term to the angle of inclination (radians)
+    pub cis: f64,
+    /// Inclination angle at reference time (radians)
+    pub inclination: f64,
+    /// Rate of inclination angle (radians/second)
+    pub inclination_rate: f64,
+    /// Right ascension (radians)
+    pub right_ascension: f64,
+    /// Rate of right ascension (radians/second)
+    pub right_ascension_rate: f64,
+    /// Issue of data clock
+    pub iodc: u32,
+    /// SV clock correction term (seconds)
+    pub toc: f64,
+    /// Estimated group delay difference (seconds)
+    pub tgd: f64,
+    /// Clock aging parameter (seconds)
+    pub af0: f64,
+    /// Clock aging parameter (seconds/second)
+    pub af1: f64,
+    /// Clock aging parameter (seconds/second/second)
+    pub af2: f64,
+    /// Anti-spoofing on
+    pub anti_spoofing: bool,
+    /// Corrected mean motion (radians/second)
+    pub corrected_mean_motion: f64,
+    /// User Range Accuracy variance (m^2)
+    pub ura: f64,
+}
+
+impl GpsEphemLog {
+    /// Convert a raw data buffer into a useable struct
+    pub fn new(
+        recv_status: ReceiverStatusFlags,
+        time_status: u8,
+        week: u16,
+        ms: i32,
+        raw: &[u8],
+    ) -> Option<Self> {
+        let mut log = match parse_gpsephem(raw) {
+            Ok(conv) => conv.1,
+            _ => return None,
+        };
+
+        log.recv_status = recv_status;
+        log.time_status = time_status;
+        log.week = week;
+        log.ms = ms;
+
+        Some(log)
+    }
+}
+
+// The log is split across multiple parsers in order to keep nom's macro recursion in check
+named!(parse_gpsephem(&[u8]) -> GpsEphemLog,
+    do_parse!(
+        prn: le_u32 >>
+        tow: le_f64 >>
+        health: le_u32 >>
+        iode1: le_u32 >>
+        iode2: le_u32 >>
+        ephem_week: le_u32 >>
+        z_week: le_u32 >>
+        orbit: parse_orbit >>
+        clock: parse_clock >>
+        (GpsEphemLog {
+            prn,
+            tow,
+            health,
+            iode1,
+            iode2,
+            ephem_week,
+            z_week,
+            toe: orbit.toe,
+            semi_major_axis: orbit.semi_major_axis,
+            delta_n: orbit.delta_n,
+            mean_anomaly: orbit.mean_anomaly,
+            eccentricity: orbit.eccentricity,
+            arg_perigee: orbit.arg_perigee,
+            cuc: orbit.cuc,
+            cus: orbit.cus,
+            crc: orbit.crc,
+            crs: orbit.crs,
+            cic: orbit.cic,
+            cis: orbit.cis,
+            inclination: orbit.inclination,
+            inclination_rate: orbit.inclination_rate,
+            right_ascension: orbit.right_ascension,
+            right_ascension_rate: orbit.right_ascension_rate,
+            ..clock
+            }
+        )
+    )
+);
+
+named!(parse_orbit(&[u8]) -> GpsEphemLog,
+    do_parse!(
+        toe: le_f64 >>
+        semi_major_axis: le_f64 >>
+        delta_n: le_f64 >>
+        mean_anomaly: le_f64 >>
+        eccentricity: le_f64 >>
+        arg_perigee: le_f64 >>
+        cuc: le_f64 >>
+        cus: le_f64 >>
+        crc: le_f64 >>
+        crs: le_f64 >>
+        cic: le_f64 >>
+        cis: le_f64 >>
+        inclination: le_f64 >>
+        inclination_rate: le_f64 >>
+        right_ascension: le_f64 >>
+        right_ascension_rate: le_f64 >>
+        (GpsEphemLog {
+            toe,
+            semi_major_axis,
+            delta_n,
+            mean_anomaly,
+            eccentricity,
+            arg_perigee,
+            cuc,
+            cus,
+            crc,
+            crs,
+            cic,
+            cis,
+            inclination,
+            inclination_rate,
+            right_ascension,
+            right_ascension_rate,
+            ..Default::default()
+            }
+        )
+    )
+);
+
+named!(parse_clock(&[u8]) -> GpsEphemLog,
+    do_parse!(
+        iodc: le_u32 >>
+        toc: le_f64 >>
+        tgd: le_f64 >>
+        af0: le_f64 >>
+        af1: le_f64 >>
+        af2: le_f64 >>
+        anti_spoofing: le_u32 >>
+        corrected_mean_motion: le_f64 >>
+        ura: le_f64 >>
+        (GpsEphemLog {
+            iodc,
+            toc,
+            tgd,
+            af0,
+            af1,
+            af2,
+            anti_spoofing: anti_spoofing != 0,
+            corrected_mean_motion,
+            ura,
+            ..Default::default()
+            }
+        )
+    )
+);
@@ -14,22 +14,46 @@
 // limitations under the License.
 //
 
+mod best_pos;
+mod best_vel;
 mod best_xyz;
+mod gps_dop;
+mod gps_ephem;
+mod range;
 mod rxstatusevent;
+mod time;
 mod version;
 
+pub use self::best_pos::*;
+pub use self::best_vel::*;
 pub use self::best_xyz::*;
+pub use self::gps_dop::*;
+pub use self::gps_ephem::*;
+pub use self::range::*;
 pub use self::rxstatusevent::*;
+pub use self::time::*;
 pub use self::version::*;
 use super::*;
 
 /// Supported log messages
 #[derive(Clone, Debug, PartialEq)]
 pub enum Log {
+    /// Best available geodetic position
+    BestPos(BestPosLog),
+    /// Best available velocity
+    BestVel(BestVelLog),
     /// Best available position and velocity in ECEF coordinates
     BestXYZ(BestXYZLog),
+    /// Dilution of precision and satellites used in the position solution
+    GpsDop(GpsDopLog),
+    /// Decoded GPS ephemeris parameters
+    GpsEphem(GpsEphemLog),
+    /// Channel range measurements
+    Range(RangeLog),
     /// Event and/or error message
     RxStatusEvent(RxStatusEventLog),
+    /// Receiver clock and UTC time information
+    Time(TimeLog),
     /// System version information
     Version(VersionLog),
 }
@@ -45,16 +69,38 @@ impl Log {
         raw: Vec<u8>,
     ) -> Option<Log> {
         match id {
+            MessageID::BestPos => match BestPosLog::new(recv_status, time_status, week, ms, &raw) {
+                Some(log) => Some(Log::BestPos(log)),
+                _ => None,
+            },
+            MessageID::BestVel => match BestVelLog::new(recv_status, time_status, week, ms, &raw) {
+                Some(log) => Some(Log::BestVel(log)),
+                _ => None,
+            },
             MessageID::BestXYZ => match BestXYZLog::new(recv_status, time_status, week, ms, &raw) {
                 Some(log) => Some(Log::BestXYZ(log)),
                 _ => None,
             },
+            MessageID::GpsEphem => {
+                match GpsEphemLog::new(recv_status, time_status, week, ms, &raw) {
+                    Some(log) => Some(Log::GpsEphem(log)),
+                    _ => None,
+                }
+            }
+            MessageID::Range => match RangeLog::new(recv_status, time_status, week, ms, raw) {
+                Some(log) => Some(Log::Range(log)),
+                _ => None,
+            },
             MessageID::RxStatusEvent => {
                 match RxStatusEventLog::new(recv_status, time_status, week, ms, &raw) {
                     Some(log) => Some(Log::RxStatusEvent(log)),
                     _ => None,
                 }
             }
+            MessageID::Time => match TimeLog::new(recv_status, time_status, week, ms, &raw) {
+                Some(log) => Some(Log::Time(log)),
+                _ => None,
+            },
             MessageID::Version => match VersionLog::new(recv_status, time_status, week, ms, raw) {
                 Some(log) => Some(Log::Version(log)),
                 _ => None,
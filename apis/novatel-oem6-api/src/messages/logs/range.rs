@@ -0,0 +1,129 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+use nom::*;
+
+const OBSERVATION_SIZE: usize = 44;
+
+/// Log message containing the channel measurements for each tracked satellite
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct RangeLog {
+    /// Current status of receiver
+    pub recv_status: ReceiverStatusFlags,
+    /// Validity of the time information
+    pub time_status: u8,
+    /// GPS reference week
+    pub week: u16,
+    /// Milliseconds into GPS reference week
+    pub ms: i32,
+    /// Number of observations present in this structure
+    pub num_obs: u32,
+    /// Range measurements for each observation
+    pub observations: Vec<RangeObservation>,
+}
+
+impl RangeLog {
+    /// Convert a raw data buffer into a useable struct
+    pub fn new(
+        recv_status: ReceiverStatusFlags,
+        time_status: u8,
+        week: u16,
+        ms: i32,
+        mut raw: Vec<u8>,
+    ) -> Option<Self> {
+        if raw.len() < 4 {
+            return None;
+        }
+
+        let raw_obs = raw.split_off(4);
+
+        let mut log = RangeLog {
+            recv_status,
+            time_status,
+            week,
+            ms,
+            num_obs: {
+                match le_u32(&raw) {
+                    Ok(v) => v.1,
+                    Err(_) => return None,
+                }
+            },
+            observations: vec![],
+        };
+
+        for elem in raw_obs.chunks(OBSERVATION_SIZE) {
+            if let Ok(conv) = parse_observation(elem) {
+                log.observations.push(conv.1);
+            }
+        }
+
+        Some(log)
+    }
+}
+
+/// Range measurements for a single satellite signal
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct RangeObservation {
+    /// Satellite PRN number of range measurement
+    pub prn: u16,
+    /// GLONASS frequency + 7. Zero for all other satellite systems
+    pub glonass_freq: u16,
+    /// Pseudorange measurement (m)
+    pub psr: f64,
+    /// Pseudorange measurement standard deviation (m)
+    pub psr_std: f32,
+    /// Carrier phase, in cycles (accumulated Doppler range)
+    pub adr: f64,
+    /// Estimated carrier phase standard deviation (cycles)
+    pub adr_std: f32,
+    /// Instantaneous carrier Doppler frequency (Hz)
+    pub doppler: f32,
+    /// Carrier to noise density ratio (dB-Hz)
+    pub carrier_noise: f32,
+    /// Number of seconds of continuous tracking (no cycle slipping)
+    pub lock_time: f32,
+    /// Tracking status
+    pub tracking_status: u32,
+}
+
+named!(parse_observation(&[u8]) -> RangeObservation,
+    do_parse!(
+        prn: le_u16 >>
+        glonass_freq: le_u16 >>
+        psr: le_f64 >>
+        psr_std: le_f32 >>
+        adr: le_f64 >>
+        adr_std: le_f32 >>
+        doppler: le_f32 >>
+        carrier_noise: le_f32 >>
+        lock_time: le_f32 >>
+        tracking_status: le_u32 >>
+        (RangeObservation {
+            prn,
+            glonass_freq,
+            psr,
+            psr_std,
+            adr,
+            adr_std,
+            doppler,
+            carrier_noise,
+            lock_time,
+            tracking_status,
+            }
+        )
+    )
+);
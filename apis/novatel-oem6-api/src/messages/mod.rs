@@ -14,8 +14,10 @@
 // limitations under the License.
 //
 
+pub mod ascii;
 pub mod commands;
 pub mod logs;
+pub mod nmea;
 
 pub use self::commands::*;
 pub use self::logs::*;
@@ -31,16 +33,50 @@ pub const HDR_LEN: u8 = 28;
 pub enum MessageID {
     /// Log command message
     Log = 1,
+    /// Interface mode command message
+    InterfaceMode = 3,
+    /// Decoded GPS ephemeris data log
+    GpsEphem = 7,
+    /// Reset command message
+    Reset = 18,
+    /// Save configuration command message
+    SaveConfig = 19,
+    /// Factory reset command message
+    FReset = 20,
+    /// Channel assignment command message
+    Assign = 27,
     /// Unlog command message
     Unlog = 36,
     /// Unlog all command message
     UnlogAll = 38,
     /// Version data log
     Version = 37,
+    /// Best geodetic position data log
+    BestPos = 42,
+    /// Satellite range measurement data log
+    Range = 43,
+    /// Position constraint command message
+    Fix = 44,
+    /// Elevation cutoff command message
+    ECutoff = 50,
     /// RX status event data log
     RxStatusEvent = 94,
+    /// Best velocity data log
+    BestVel = 99,
+    /// Time data log
+    Time = 101,
+    /// NMEA GPS fix data log
+    GpGga = 218,
+    /// NMEA GPS DOP and active satellites log
+    GpGsa = 221,
+    /// NMEA GPS specific information log
+    GpRmc = 225,
+    /// NMEA UTC time and date log
+    GpZda = 227,
     /// Best XYZ position/velocity data log
     BestXYZ = 241,
+    /// Serial port configuration command message
+    SerialConfig = 1246,
     /// Catch-all value for received messages with an unknown ID
     Unknown,
 }
@@ -55,16 +91,61 @@ impl From<u16> for MessageID {
     fn from(t: u16) -> MessageID {
         match t {
             1 => MessageID::Log,
+            3 => MessageID::InterfaceMode,
+            7 => MessageID::GpsEphem,
+            18 => MessageID::Reset,
+            19 => MessageID::SaveConfig,
+            20 => MessageID::FReset,
+            27 => MessageID::Assign,
             36 => MessageID::Unlog,
             37 => MessageID::Version,
             38 => MessageID::UnlogAll,
+            42 => MessageID::BestPos,
+            43 => MessageID::Range,
+            44 => MessageID::Fix,
+            50 => MessageID::ECutoff,
             94 => MessageID::RxStatusEvent,
+            99 => MessageID::BestVel,
+            101 => MessageID::Time,
+            218 => MessageID::GpGga,
+            221 => MessageID::GpGsa,
+            225 => MessageID::GpRmc,
+            227 => MessageID::GpZda,
             241 => MessageID::BestXYZ,
+            1246 => MessageID::SerialConfig,
             _ => MessageID::Unknown,
         }
     }
 }
 
+/// Formats which log messages can be received in
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LogFormat {
+    /// NovAtel binary messages
+    Binary,
+    /// NovAtel ASCII messages, starting with `#`
+    Ascii,
+    /// NMEA 0183 sentences, starting with `$`
+    Nmea,
+}
+
+impl Default for LogFormat {
+    fn default() -> LogFormat {
+        LogFormat::Binary
+    }
+}
+
+impl LogFormat {
+    // Value of the format bits within a header's message type field
+    fn msg_type(self) -> u8 {
+        match self {
+            LogFormat::Binary => 0x00,
+            LogFormat::Ascii => 0x20,
+            LogFormat::Nmea => 0x40,
+        }
+    }
+}
+
 /// Common header structure for all messages
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Header {
@@ -108,6 +189,15 @@ impl Header {
             _ => None,
         }
     }
+
+    /// Format the message body was received in
+    pub fn format(&self) -> LogFormat {
+        match self.msg_type & 0x60 {
+            0x20 => LogFormat::Ascii,
+            0x40 => LogFormat::Nmea,
+            _ => LogFormat::Binary,
+        }
+    }
 }
 
 impl Message for Header {
@@ -0,0 +1,259 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Support for NMEA 0183 sentences
+//!
+//! Sentences have the form `$<talker><type>,<fields>*<checksum>`. The GGA, RMC, GSA, and ZDA
+//! sentences are converted into the [`Log`] variant which carries the equivalent information.
+//!
+//! NMEA sentences do not contain a GPS reference time, so the week and milliseconds of all
+//! resulting logs will be zero and their time status will be `UNKNOWN`.
+
+use super::*;
+use std::str::{self, FromStr};
+
+/// Character marking the start of an NMEA sentence
+pub const NMEA_SYNC: u8 = b'$';
+
+// Binary equivalents of the status values used when converting sentences
+const SOL_COMPUTED: u32 = 0;
+const INSUFFICIENT_OBS: u32 = 1;
+const CLOCK_VALID: u32 = 0;
+const CLOCK_INVALID: u32 = 3;
+const UTC_INVALID: u32 = 0;
+const UTC_VALID: u32 = 1;
+const TIME_UNKNOWN: u8 = 20;
+const DATUM_WGS84: u32 = 61;
+
+const KNOTS_TO_MPS: f64 = 1852.0 / 3600.0;
+
+/// Validate a received NMEA sentence and split it into a header and body
+///
+/// `raw` should contain everything following the leading `$`, up to and including the checksum
+pub fn parse_frame(raw: &[u8]) -> Option<(Header, Vec<u8>)> {
+    let text = str::from_utf8(raw).ok()?.trim_end();
+
+    let star = text.find('*')?;
+    let (sentence, checksum) = (&text[..star], &text[star + 1..]);
+    let calc = sentence.bytes().fold(0, |acc, byte| acc ^ byte);
+    if calc != u8::from_str_radix(checksum, 16).ok()? {
+        return None;
+    }
+
+    let comma = sentence.find(',')?;
+    let (address, body) = (&sentence[..comma], &sentence[comma + 1..]);
+
+    // The first two characters of the address identify the talker (GP, GN, GL, etc),
+    // which doesn't affect how the sentence is handled
+    let msg_id = match address.get(2..) {
+        Some("GGA") => MessageID::GpGga,
+        Some("GSA") => MessageID::GpGsa,
+        Some("RMC") => MessageID::GpRmc,
+        Some("ZDA") => MessageID::GpZda,
+        _ => MessageID::Unknown,
+    };
+
+    let hdr = Header {
+        msg_id,
+        msg_type: LogFormat::Nmea.msg_type(),
+        msg_len: body.len() as u16,
+        time_status: TIME_UNKNOWN,
+        ..Default::default()
+    };
+
+    Some((hdr, body.as_bytes().to_vec()))
+}
+
+/// Convert the body of an NMEA sentence into a useable struct
+pub fn parse_log(hdr: &Header, raw: &[u8]) -> Option<Log> {
+    let fields: Vec<&str> = str::from_utf8(raw).ok()?.split(',').collect();
+
+    match hdr.msg_id {
+        MessageID::GpGga => parse_gga(hdr, &fields).map(Log::BestPos),
+        MessageID::GpGsa => parse_gsa(hdr, &fields).map(Log::GpsDop),
+        MessageID::GpRmc => parse_rmc(hdr, &fields).map(Log::BestVel),
+        MessageID::GpZda => parse_zda(hdr, &fields).map(Log::Time),
+        _ => None,
+    }
+}
+
+// GPS fix data
+fn parse_gga(hdr: &Header, fields: &[&str]) -> Option<BestPosLog> {
+    if fields.len() < 14 {
+        return None;
+    }
+
+    let quality: u8 = num(fields[5])?;
+    let num_sats = num(fields[6])?;
+
+    Some(BestPosLog {
+        recv_status: hdr.recv_status,
+        time_status: hdr.time_status,
+        week: hdr.week,
+        ms: hdr.ms,
+        pos_status: if quality == 0 {
+            INSUFFICIENT_OBS
+        } else {
+            SOL_COMPUTED
+        },
+        pos_type: match quality {
+            1 => 16, // SINGLE
+            2 => 17, // PSRDIFF
+            4 => 50, // NARROW_INT
+            5 => 34, // NARROW_FLOAT
+            6 => 19, // PROPAGATED
+            7 => 1,  // FIXEDPOS
+            9 => 18, // WAAS
+            _ => 0,  // NONE
+        },
+        lat: coord(fields[1], fields[2], 2)?,
+        lon: coord(fields[3], fields[4], 3)?,
+        height: num(fields[8])?,
+        undulation: num(fields[10])?,
+        datum_id: DATUM_WGS84,
+        pos_deviation: [0.0; 3],
+        station_id: fields[13].to_owned(),
+        diff_age: num(fields[12])?,
+        sol_age: 0.0,
+        num_sats,
+        num_sat_vehicles: num_sats,
+        ..Default::default()
+    })
+}
+
+// DOP and active satellites
+fn parse_gsa(hdr: &Header, fields: &[&str]) -> Option<GpsDopLog> {
+    if fields.len() < 17 {
+        return None;
+    }
+
+    let mut prns = vec![];
+    for field in fields[2..14].iter().filter(|field| !field.is_empty()) {
+        prns.push(field.parse().ok()?);
+    }
+
+    Some(GpsDopLog {
+        recv_status: hdr.recv_status,
+        time_status: hdr.time_status,
+        week: hdr.week,
+        ms: hdr.ms,
+        fix_mode: num(fields[1])?,
+        prns,
+        pdop: num(fields[14])?,
+        hdop: num(fields[15])?,
+        vdop: num(fields[16])?,
+    })
+}
+
+// Recommended minimum specific GPS data
+fn parse_rmc(hdr: &Header, fields: &[&str]) -> Option<BestVelLog> {
+    if fields.len() < 9 {
+        return None;
+    }
+
+    let valid = fields[1] == "A";
+    // The mode indicator was added in NMEA 2.3, so might not be present
+    let differential = fields.get(11) == Some(&"D");
+
+    Some(BestVelLog {
+        recv_status: hdr.recv_status,
+        time_status: hdr.time_status,
+        week: hdr.week,
+        ms: hdr.ms,
+        vel_status: if valid {
+            SOL_COMPUTED
+        } else {
+            INSUFFICIENT_OBS
+        },
+        vel_type: match (valid, differential) {
+            (false, _) => 0,     // NONE
+            (true, false) => 16, // SINGLE
+            (true, true) => 17,  // PSRDIFF
+        },
+        latency: 0.0,
+        diff_age: 0.0,
+        hor_speed: num::<f64>(fields[6])? * KNOTS_TO_MPS,
+        track_ground: num(fields[7])?,
+        vert_speed: 0.0,
+    })
+}
+
+// UTC time and date
+fn parse_zda(hdr: &Header, fields: &[&str]) -> Option<TimeLog> {
+    if fields.len() < 4 {
+        return None;
+    }
+
+    let valid = !fields[0].is_empty();
+    let (utc_hour, utc_min, utc_ms) = utc_time(fields[0])?;
+
+    Some(TimeLog {
+        recv_status: hdr.recv_status,
+        time_status: hdr.time_status,
+        week: hdr.week,
+        ms: hdr.ms,
+        clock_status: if valid { CLOCK_VALID } else { CLOCK_INVALID },
+        offset: 0.0,
+        offset_std: 0.0,
+        utc_offset: 0.0,
+        utc_year: num(fields[3])?,
+        utc_month: num(fields[2])?,
+        utc_day: num(fields[1])?,
+        utc_hour,
+        utc_min,
+        utc_ms,
+        utc_status: if valid { UTC_VALID } else { UTC_INVALID },
+    })
+}
+
+// Parse a numeric field. Fields are left empty when the receiver doesn't have a value for them
+fn num<T: FromStr + Default>(field: &str) -> Option<T> {
+    if field.is_empty() {
+        Some(T::default())
+    } else {
+        field.parse().ok()
+    }
+}
+
+// Convert a `(d)ddmm.mmmm` coordinate and its hemisphere into decimal degrees
+fn coord(value: &str, hemisphere: &str, degree_len: usize) -> Option<f64> {
+    if value.is_empty() {
+        return Some(0.0);
+    }
+
+    let degrees: f64 = value.get(..degree_len)?.parse().ok()?;
+    let minutes: f64 = value.get(degree_len..)?.parse().ok()?;
+    let coord = degrees + minutes / 60.0;
+
+    match hemisphere {
+        "N" | "E" => Some(coord),
+        "S" | "W" => Some(-coord),
+        _ => None,
+    }
+}
+
+// Convert an `hhmmss.ss` time into hours, minutes, and milliseconds into the minute
+fn utc_time(value: &str) -> Option<(u8, u8, u32)> {
+    if value.is_empty() {
+        return Some((0, 0, 0));
+    }
+
+    let hour = value.get(..2)?.parse().ok()?;
+    let min = value.get(2..4)?.parse().ok()?;
+    let sec: f64 = value.get(4..)?.parse().ok()?;
+
+    Some((hour, min, (sec * 1000.0).round() as u32))
+}
@@ -31,6 +31,8 @@ const PARITY: serial::Parity = serial::ParityNone;
 const STOP_BITS: serial::StopBits = serial::Stop1;
 const FLOW_CONTROL: serial::FlowControl = serial::FlowNone;
 const TIMEOUT: Duration = Duration::from_millis(60);
+// Longest text message we expect to receive
+const MAX_LINE_LEN: usize = 1024;
 
 /// Continually read messages from the OEM6 device
 ///
@@ -38,6 +40,9 @@ const TIMEOUT: Duration = Duration::from_millis(60);
 /// or a log message. The function will detect the type and then forward
 /// the message to the appropriate channel receiver.
 ///
+/// Only binary messages will be accepted. Use [`read_thread_with_format`] to
+/// receive ASCII or NMEA log messages.
+///
 /// # Arguments
 ///
 /// * rx_conn - UART connection stream to use for communication
@@ -66,11 +71,56 @@ const TIMEOUT: Duration = Duration::from_millis(60);
 /// # }
 /// ```
 ///
-
+/// [`read_thread_with_format`]: fn.read_thread_with_format.html
 pub fn read_thread(
     rx_conn: &Arc<Mutex<Connection>>,
     log_send: &SyncSender<(Header, Vec<u8>)>,
     response_send: &SyncSender<(Header, Vec<u8>)>,
+) {
+    read_thread_with_format(rx_conn, log_send, response_send, LogFormat::Binary)
+}
+
+/// Continually read messages from the OEM6 device, accepting log messages in the given format
+///
+/// Command responses are always sent in the binary format, so binary messages will be
+/// accepted regardless of the requested format.
+///
+/// # Arguments
+///
+/// * rx_conn - UART connection stream to use for communication
+/// * log_send - SyncSender for forwarding received log messages
+/// * response_send - SyncSender for forwarding recieved response messages
+/// * format - Format of the log messages being output by the device
+///
+/// # Examples
+///
+/// ```
+/// use novatel_oem6_api::*;
+/// use std::thread;
+/// use std::sync::mpsc::sync_channel;
+///
+/// # fn func() -> OEMResult<()> {
+/// let bus = "/dev/ttyS5";
+///
+/// let (log_send, log_recv) = sync_channel(5);
+/// let (response_send, response_recv) = sync_channel(5);
+///
+/// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+///
+/// let rx_conn = oem.conn.clone();
+///
+/// thread::spawn(move || {
+///     read_thread_with_format(&rx_conn, &log_send, &response_send, LogFormat::Nmea)
+/// });
+/// # Ok(())
+/// # }
+/// ```
+///
+pub fn read_thread_with_format(
+    rx_conn: &Arc<Mutex<Connection>>,
+    log_send: &SyncSender<(Header, Vec<u8>)>,
+    response_send: &SyncSender<(Header, Vec<u8>)>,
+    format: LogFormat,
 ) {
     let mut log_err = false;
     let mut response_err = false;
@@ -88,71 +138,10 @@ pub fn read_thread(
             // error caused the problem and will take error handling measures.
             let conn = rx_conn.lock().unwrap_or_else(|err| err.into_inner());
 
-            // Read SYNC bytes
-            let mut message = match conn.read(3, Duration::from_millis(250)) {
-                Ok(v) => v,
-                Err(err) => match err {
-                    #[cfg(test)]
-                    UartError::GenericError => continue,
-                    UartError::IoError {
-                        cause: ::std::io::ErrorKind::TimedOut,
-                        ..
-                    } => continue,
-                    _ => panic!(err),
-                },
-            };
-
-            if message != SYNC {
-                continue;
-            }
-
-            // Read the rest of the header
-            let mut hdr = match conn.read(25, TIMEOUT) {
-                Ok(v) => v,
-                Err(err) => match err {
-                    UartError::IoError {
-                        cause: ::std::io::ErrorKind::TimedOut,
-                        ..
-                    } => continue,
-                    _ => panic!(err),
-                },
-            };
-            message.append(&mut hdr);
-
-            let hdr = match Header::parse(&message) {
+            let (hdr, body) = match read_message(&conn, format) {
                 Some(v) => v,
-                None => {
-                    continue;
-                }
-            };
-
-            // Read body + CRC bytes
-            let mut body = match conn.read((hdr.msg_len + 4) as usize, TIMEOUT) {
-                Ok(v) => v,
-                Err(err) => match err {
-                    UartError::IoError {
-                        cause: ::std::io::ErrorKind::TimedOut,
-                        ..
-                    } => continue,
-                    _ => panic!(err),
-                },
+                None => continue,
             };
-            message.append(&mut body);
-
-            let len = message.len();
-
-            // Read CRC
-            let crc = nom::le_u32(message.split_off(len - 4).as_slice())
-                .unwrap()
-                .1;
-
-            // Verify CRC
-            let calc = calc_crc(&message);
-            if calc != crc {
-                continue;
-            }
-
-            let body = message.split_off(HDR_LEN.into());
 
             if hdr.msg_type & 0x80 == 0x80 {
                 response_send
@@ -190,6 +179,93 @@ pub fn read_thread(
     }
 }
 
+// Read the next message from the device.
+// Returns `None` if no message was available or if the message was invalid
+fn read_message(conn: &Connection, format: LogFormat) -> Option<(Header, Vec<u8>)> {
+    // Read SYNC bytes
+    if format == LogFormat::Binary {
+        let message = read_bytes(conn, 3, Duration::from_millis(250))?;
+        if message != SYNC {
+            return None;
+        }
+        return read_binary(conn, message);
+    }
+
+    // Text messages can start at any byte, so check each byte for the start of a new message
+    let mut message = read_bytes(conn, 1, Duration::from_millis(250))?;
+    match (message[0], format) {
+        // First SYNC byte of a binary message
+        (0xAA, _) => {
+            message.append(&mut read_bytes(conn, 2, TIMEOUT)?);
+            if message != SYNC {
+                return None;
+            }
+            read_binary(conn, message)
+        }
+        (ascii::ASCII_SYNC, LogFormat::Ascii) => ascii::parse_frame(&read_line(conn)?),
+        (nmea::NMEA_SYNC, LogFormat::Nmea) => nmea::parse_frame(&read_line(conn)?),
+        _ => None,
+    }
+}
+
+// Read the rest of a binary message, once the SYNC bytes have been received
+fn read_binary(conn: &Connection, mut message: Vec<u8>) -> Option<(Header, Vec<u8>)> {
+    // Read the rest of the header
+    message.append(&mut read_bytes(conn, 25, TIMEOUT)?);
+
+    let hdr = Header::parse(&message)?;
+
+    // Read body + CRC bytes
+    message.append(&mut read_bytes(conn, (hdr.msg_len + 4) as usize, TIMEOUT)?);
+
+    let len = message.len();
+
+    // Read CRC
+    let crc = nom::le_u32(message.split_off(len - 4).as_slice())
+        .unwrap()
+        .1;
+
+    // Verify CRC
+    let calc = calc_crc(&message);
+    if calc != crc {
+        return None;
+    }
+
+    let body = message.split_off(HDR_LEN.into());
+
+    Some((hdr, body))
+}
+
+// Read the rest of a text message, up to the terminating line feed
+fn read_line(conn: &Connection) -> Option<Vec<u8>> {
+    let mut line = vec![];
+
+    while line.len() < MAX_LINE_LEN {
+        let byte = read_bytes(conn, 1, TIMEOUT)?[0];
+        if byte == b'\n' {
+            return Some(line);
+        }
+        line.push(byte);
+    }
+
+    None
+}
+
+fn read_bytes(conn: &Connection, len: usize, timeout: Duration) -> Option<Vec<u8>> {
+    match conn.read(len, timeout) {
+        Ok(v) => Some(v),
+        Err(err) => match err {
+            #[cfg(test)]
+            UartError::GenericError => None,
+            UartError::IoError {
+                cause: ::std::io::ErrorKind::TimedOut,
+                ..
+            } => None,
+            _ => panic!(err),
+        },
+    }
+}
+
 /// Structure for OEM6 device instance
 #[derive(Clone)]
 #[allow(clippy::type_complexity)]
@@ -379,29 +455,17 @@ impl OEM6 {
     /// [`unlog_all`]: method.unlog_all.html
     /// [`OEMError`]: enum.OEMError.html
     pub fn request_position(&self, interval: f64, offset: f64, hold: bool) -> OEMResult<()> {
-        let trigger = if interval == 0.0 {
-            LogTrigger::Once
-        } else {
-            LogTrigger::OnTime
-        };
-
-        let request = LogCmd::new(
-            Port::COM1 as u32,
-            MessageID::BestXYZ as u16,
-            trigger,
-            interval,
-            offset,
-            hold,
-        );
-
-        self.send_message(&request)
-            .and_then(|_| self.get_response(MessageID::Log))
+        self.request_log(MessageID::BestXYZ, interval, offset, hold)
     }
 
-    /// Request that the device send error messages as they occur
+    /// Request BestPos geodetic position log/s from the device
+    ///
+    /// Note: Subsequent [`get_log()`] calls are required to fetch the information
     ///
     /// # Arguments
     ///
+    /// * interval - Frequency, in seconds, at which the OEM6 should emit log messages
+    /// * offset - Offset, in seconds, of the message emit frequency
     /// * hold - Whether the [`unlog_all`] command should be able to apply to this log. A value
     ///          of `true` will prevent [`unlog_all`] from applying to this log.
     ///
@@ -421,33 +485,39 @@ impl OEM6 {
     /// # let (log_send, log_recv) = sync_channel(5);
     /// # let (response_send, response_recv) = sync_channel(5);
     /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    /// let rx_conn = oem.conn.clone();
+    /// thread::spawn(move || read_thread(&rx_conn, &log_send, &response_send));
     ///
-    /// oem.request_errors(false)?;
+    /// oem.request_best_position(1.0, 0.0, false)?;
+    ///
+    /// match oem.get_log()? {
+    ///     Log::BestPos(log) => {
+    ///         println!("Latitude: {}, Longitude: {}", log.lat, log.lon);
+    ///         println!("Height: {}", log.height);
+    ///     }
+    ///     _ => {},
+    /// }
     /// # Ok(())
     /// # }
     /// ```
     ///
+    /// [`get_log()`]: method.get_log.html
     /// [`unlog_all`]: method.unlog_all.html
     /// [`OEMError`]: enum.OEMError.html
-    pub fn request_errors(&self, hold: bool) -> OEMResult<()> {
-        let request = LogCmd::new(
-            Port::COM1 as u32,
-            MessageID::RxStatusEvent as u16,
-            LogTrigger::OnChanged,
-            0.0,
-            0.0,
-            hold,
-        );
-
-        self.send_message(&request)
-            .and_then(|_| self.get_response(MessageID::Log))
+    pub fn request_best_position(&self, interval: f64, offset: f64, hold: bool) -> OEMResult<()> {
+        self.request_log(MessageID::BestPos, interval, offset, hold)
     }
 
-    /// Request that automatic logging for a particular log type be stopped
+    /// Request BestVel velocity log/s from the device
+    ///
+    /// Note: Subsequent [`get_log()`] calls are required to fetch the information
     ///
     /// # Arguments
     ///
-    /// * id - Message ID which should no longer be logged
+    /// * interval - Frequency, in seconds, at which the OEM6 should emit log messages
+    /// * offset - Offset, in seconds, of the message emit frequency
+    /// * hold - Whether the [`unlog_all`] command should be able to apply to this log. A value
+    ///          of `true` will prevent [`unlog_all`] from applying to this log.
     ///
     /// # Errors
     ///
@@ -465,26 +535,39 @@ impl OEM6 {
     /// # let (log_send, log_recv) = sync_channel(5);
     /// # let (response_send, response_recv) = sync_channel(5);
     /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    /// let rx_conn = oem.conn.clone();
+    /// thread::spawn(move || read_thread(&rx_conn, &log_send, &response_send));
     ///
-    /// oem.request_unlog(MessageID::BestXYZ)?;
+    /// oem.request_best_velocity(1.0, 0.0, false)?;
+    ///
+    /// match oem.get_log()? {
+    ///     Log::BestVel(log) => {
+    ///         println!("Horizontal speed: {}", log.hor_speed);
+    ///         println!("Vertical speed: {}", log.vert_speed);
+    ///     }
+    ///     _ => {},
+    /// }
     /// # Ok(())
     /// # }
     /// ```
     ///
+    /// [`get_log()`]: method.get_log.html
+    /// [`unlog_all`]: method.unlog_all.html
     /// [`OEMError`]: enum.OEMError.html
-    pub fn request_unlog(&self, id: MessageID) -> OEMResult<()> {
-        let request = UnlogCmd::new(Port::COM1 as u32, id as u16);
-
-        self.send_message(&request)
-            .and_then(|_| self.get_response(MessageID::Unlog))
+    pub fn request_best_velocity(&self, interval: f64, offset: f64, hold: bool) -> OEMResult<()> {
+        self.request_log(MessageID::BestVel, interval, offset, hold)
     }
 
-    /// Request that all automatic logging be stopped
+    /// Request receiver clock and UTC time log/s from the device
+    ///
+    /// Note: Subsequent [`get_log()`] calls are required to fetch the information
     ///
     /// # Arguments
     ///
-    /// * clear_holds - Specifies whether log messages which were set with the `hold` option
-    ///                 should also be unlogged
+    /// * interval - Frequency, in seconds, at which the OEM6 should emit log messages
+    /// * offset - Offset, in seconds, of the message emit frequency
+    /// * hold - Whether the [`unlog_all`] command should be able to apply to this log. A value
+    ///          of `true` will prevent [`unlog_all`] from applying to this log.
     ///
     /// # Errors
     ///
@@ -502,16 +585,46 @@ impl OEM6 {
     /// # let (log_send, log_recv) = sync_channel(5);
     /// # let (response_send, response_recv) = sync_channel(5);
     /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    /// let rx_conn = oem.conn.clone();
+    /// thread::spawn(move || read_thread(&rx_conn, &log_send, &response_send));
     ///
-    /// // Request position information every second and prevent unlogging with unlog_all()
-    /// oem.request_position(1.0, 0.0, true)?;
+    /// oem.request_time(1.0, 0.0, false)?;
     ///
-    /// // Unlog everything. Previous request for position information will remain intact
-    /// oem.request_unlog_all(false)?;
+    /// match oem.get_log()? {
+    ///     Log::Time(log) => {
+    ///         println!("Clock offset: {}", log.offset);
+    ///         println!("UTC offset: {}", log.utc_offset);
+    ///     }
+    ///     _ => {},
+    /// }
     /// # Ok(())
     /// # }
     /// ```
     ///
+    /// [`get_log()`]: method.get_log.html
+    /// [`unlog_all`]: method.unlog_all.html
+    /// [`OEMError`]: enum.OEMError.html
+    pub fn request_time(&self, interval: f64, offset: f64, hold: bool) -> OEMResult<()> {
+        self.request_log(MessageID::Time, interval, offset, hold)
+    }
+
+    /// Request satellite range measurement log/s from the device
+    ///
+    /// Note: Subsequent [`get_log()`] calls are required to fetch the information
+    ///
+    /// # Arguments
+    ///
+    /// * interval - Frequency, in seconds, at which the OEM6 should emit log messages
+    /// * offset - Offset, in seconds, of the message emit frequency
+    /// * hold - Whether the [`unlog_all`] command should be able to apply to this log. A value
+    ///          of `true` will prevent [`unlog_all`] from applying to this log.
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`OEMError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
     /// ```
     /// # use novatel_oem6_api::*;
     /// # use std::thread;
@@ -522,32 +635,38 @@ impl OEM6 {
     /// # let (log_send, log_recv) = sync_channel(5);
     /// # let (response_send, response_recv) = sync_channel(5);
     /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    /// let rx_conn = oem.conn.clone();
+    /// thread::spawn(move || read_thread(&rx_conn, &log_send, &response_send));
     ///
-    /// // Request position information every second and prevent unlogging with unlog_all()
-    /// oem.request_position(1.0, 0.0, true)?;
+    /// oem.request_range(1.0, 0.0, false)?;
     ///
-    /// // Unlog everything. This will override the position request's `hold=true` value and
-    /// // also unlog that request
-    /// oem.request_unlog_all(true)?;
+    /// match oem.get_log()? {
+    ///     Log::Range(log) => {
+    ///         for obs in log.observations.iter() {
+    ///             println!("PRN {}: {}", obs.prn, obs.psr);
+    ///         }
+    ///     }
+    ///     _ => {},
+    /// }
     /// # Ok(())
     /// # }
     /// ```
     ///
+    /// [`get_log()`]: method.get_log.html
+    /// [`unlog_all`]: method.unlog_all.html
     /// [`OEMError`]: enum.OEMError.html
-    pub fn request_unlog_all(&self, clear_holds: bool) -> OEMResult<()> {
-        let request = UnlogAllCmd::new(Port::COM1 as u32, clear_holds);
-
-        self.send_message(&request)
-            .and_then(|_| self.get_response(MessageID::UnlogAll))
+    pub fn request_range(&self, interval: f64, offset: f64, hold: bool) -> OEMResult<()> {
+        self.request_log(MessageID::Range, interval, offset, hold)
     }
 
-    /// Directly send a message without formatting or checksum calculation
+    /// Request that the device send GPS ephemeris data whenever it changes
     ///
-    /// Note: The message will not be verified by checking for a command response
+    /// Note: Subsequent [`get_log()`] calls are required to fetch the information
     ///
     /// # Arguments
     ///
-    /// * msg - Message to send
+    /// * hold - Whether the [`unlog_all`] command should be able to apply to this log. A value
+    ///          of `true` will prevent [`unlog_all`] from applying to this log.
     ///
     /// # Errors
     ///
@@ -565,49 +684,594 @@ impl OEM6 {
     /// # let (log_send, log_recv) = sync_channel(5);
     /// # let (response_send, response_recv) = sync_channel(5);
     /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    /// let rx_conn = oem.conn.clone();
+    /// thread::spawn(move || read_thread(&rx_conn, &log_send, &response_send));
     ///
-    /// let packet: [u8; 6] = [0, 1, 2, 3, 4, 5];
-    /// oem.passthrough(&packet)?;
+    /// oem.request_ephemeris(false)?;
+    ///
+    /// match oem.get_log()? {
+    ///     Log::GpsEphem(log) => {
+    ///         println!("PRN {} ephemeris: {:?}", log.prn, log);
+    ///     }
+    ///     _ => {},
+    /// }
     /// # Ok(())
     /// # }
     /// ```
     ///
-    ///
+    /// [`get_log()`]: method.get_log.html
+    /// [`unlog_all`]: method.unlog_all.html
     /// [`OEMError`]: enum.OEMError.html
-    pub fn passthrough(&self, msg: &[u8]) -> OEMResult<()> {
-        // If the mutex has been poisoned by the read thread,
-        // go ahead and attempt the write anyways, to try to
-        // preserve functionality, but inform the caller afterwards
-        match self.conn.lock() {
-            Ok(conn) => conn.write(msg).map_err(|err| err.into()),
-            Err(conn) => conn
-                .into_inner()
-                .write(msg)
-                .map_err(|err| err.into())
-                .and(Err(OEMError::ThreadCommError)),
-        }
-    }
-
-    fn send_message<T: Message>(&self, msg: &T) -> OEMResult<()> {
-        let mut raw = msg.serialize();
+    pub fn request_ephemeris(&self, hold: bool) -> OEMResult<()> {
+        let request = LogCmd::new(
+            Port::COM1 as u32,
+            MessageID::GpsEphem as u16,
+            LogTrigger::OnChanged,
+            0.0,
+            0.0,
+            hold,
+        );
 
-        // Get the calculated CRC
-        let crc = calc_crc(&raw);
-        raw.write_u32::<LittleEndian>(crc).unwrap();
+        self.send_message(&request)
+            .and_then(|_| self.get_response(MessageID::Log))
+    }
 
-        // If the mutex has been poisoned by the read thread,
-        // go ahead and attempt the write anyways, to try to
-        // preserve functionality, but inform the caller afterwards
-        match self.conn.lock() {
-            Ok(conn) => conn.write(raw.as_slice()).map_err(|err| err.into()),
-            Err(conn) => conn
-                .into_inner()
+    /// Request that the device send error messages as they occur
+    ///
+    /// # Arguments
+    ///
+    /// * hold - Whether the [`unlog_all`] command should be able to apply to this log. A value
+    ///          of `true` will prevent [`unlog_all`] from applying to this log.
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`OEMError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use novatel_oem6_api::*;
+    /// # use std::thread;
+    /// # use std::sync::mpsc::sync_channel;
+    ///
+    /// # fn func() -> OEMResult<()> {
+    /// # let bus = "/dev/ttyS5";
+    /// # let (log_send, log_recv) = sync_channel(5);
+    /// # let (response_send, response_recv) = sync_channel(5);
+    /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    ///
+    /// oem.request_errors(false)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`unlog_all`]: method.unlog_all.html
+    /// [`OEMError`]: enum.OEMError.html
+    pub fn request_errors(&self, hold: bool) -> OEMResult<()> {
+        let request = LogCmd::new(
+            Port::COM1 as u32,
+            MessageID::RxStatusEvent as u16,
+            LogTrigger::OnChanged,
+            0.0,
+            0.0,
+            hold,
+        );
+
+        self.send_message(&request)
+            .and_then(|_| self.get_response(MessageID::Log))
+    }
+
+    /// Request that automatic logging for a particular log type be stopped
+    ///
+    /// # Arguments
+    ///
+    /// * id - Message ID which should no longer be logged
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`OEMError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use novatel_oem6_api::*;
+    /// # use std::thread;
+    /// # use std::sync::mpsc::sync_channel;
+    ///
+    /// # fn func() -> OEMResult<()> {
+    /// # let bus = "/dev/ttyS5";
+    /// # let (log_send, log_recv) = sync_channel(5);
+    /// # let (response_send, response_recv) = sync_channel(5);
+    /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    ///
+    /// oem.request_unlog(MessageID::BestXYZ)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`OEMError`]: enum.OEMError.html
+    pub fn request_unlog(&self, id: MessageID) -> OEMResult<()> {
+        let request = UnlogCmd::new(Port::COM1 as u32, id as u16);
+
+        self.send_message(&request)
+            .and_then(|_| self.get_response(MessageID::Unlog))
+    }
+
+    /// Request that all automatic logging be stopped
+    ///
+    /// # Arguments
+    ///
+    /// * clear_holds - Specifies whether log messages which were set with the `hold` option
+    ///                 should also be unlogged
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`OEMError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use novatel_oem6_api::*;
+    /// # use std::thread;
+    /// # use std::sync::mpsc::sync_channel;
+    ///
+    /// # fn func() -> OEMResult<()> {
+    /// # let bus = "/dev/ttyS5";
+    /// # let (log_send, log_recv) = sync_channel(5);
+    /// # let (response_send, response_recv) = sync_channel(5);
+    /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    ///
+    /// // Request position information every second and prevent unlogging with unlog_all()
+    /// oem.request_position(1.0, 0.0, true)?;
+    ///
+    /// // Unlog everything. Previous request for position information will remain intact
+    /// oem.request_unlog_all(false)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ```
+    /// # use novatel_oem6_api::*;
+    /// # use std::thread;
+    /// # use std::sync::mpsc::sync_channel;
+    ///
+    /// # fn func() -> OEMResult<()> {
+    /// # let bus = "/dev/ttyS5";
+    /// # let (log_send, log_recv) = sync_channel(5);
+    /// # let (response_send, response_recv) = sync_channel(5);
+    /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    ///
+    /// // Request position information every second and prevent unlogging with unlog_all()
+    /// oem.request_position(1.0, 0.0, true)?;
+    ///
+    /// // Unlog everything. This will override the position request's `hold=true` value and
+    /// // also unlog that request
+    /// oem.request_unlog_all(true)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`OEMError`]: enum.OEMError.html
+    pub fn request_unlog_all(&self, clear_holds: bool) -> OEMResult<()> {
+        let request = UnlogAllCmd::new(Port::COM1 as u32, clear_holds);
+
+        self.send_message(&request)
+            .and_then(|_| self.get_response(MessageID::UnlogAll))
+    }
+
+    /// Constrain the receiver's position solution
+    ///
+    /// # Arguments
+    ///
+    /// * fix_type - Type of constraint to apply
+    /// * lat - Latitude to fix, in degrees. Only used with `FixType::Position`
+    /// * lon - Longitude to fix, in degrees. Only used with `FixType::Position`
+    /// * height - Height above mean sea level to fix, in meters. Only used with
+    ///            `FixType::Height` and `FixType::Position`
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`OEMError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use novatel_oem6_api::*;
+    /// # use std::sync::mpsc::sync_channel;
+    ///
+    /// # fn func() -> OEMResult<()> {
+    /// # let bus = "/dev/ttyS5";
+    /// # let (log_send, log_recv) = sync_channel(5);
+    /// # let (response_send, response_recv) = sync_channel(5);
+    /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    ///
+    /// // Fix the height, while leaving the horizontal position free
+    /// oem.set_fix(FixType::Height, 0.0, 0.0, 1064.5)?;
+    ///
+    /// // Remove the constraint
+    /// oem.set_fix(FixType::None, 0.0, 0.0, 0.0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`OEMError`]: enum.OEMError.html
+    pub fn set_fix(&self, fix_type: FixType, lat: f64, lon: f64, height: f64) -> OEMResult<()> {
+        let request = FixCmd::new(fix_type, lat, lon, height);
+
+        self.send_message(&request)
+            .and_then(|_| self.get_response(MessageID::Fix))
+    }
+
+    /// Assign a satellite to a receiver channel
+    ///
+    /// # Arguments
+    ///
+    /// * channel - Receiver channel number
+    /// * state - Tracking state to put the channel in
+    /// * prn - PRN of the satellite to track. Only used with `ChannelState::Active`
+    /// * doppler - Expected Doppler offset of the satellite, in Hz
+    /// * doppler_window - Search window around the expected Doppler offset, in Hz
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`OEMError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use novatel_oem6_api::*;
+    /// # use std::sync::mpsc::sync_channel;
+    ///
+    /// # fn func() -> OEMResult<()> {
+    /// # let bus = "/dev/ttyS5";
+    /// # let (log_send, log_recv) = sync_channel(5);
+    /// # let (response_send, response_recv) = sync_channel(5);
+    /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    ///
+    /// // Track PRN 3 on channel 0, searching +/-2000Hz
+    /// oem.assign_channel(0, ChannelState::Active, 3, 0, 2000)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`OEMError`]: enum.OEMError.html
+    pub fn assign_channel(
+        &self,
+        channel: u32,
+        state: ChannelState,
+        prn: u32,
+        doppler: i32,
+        doppler_window: u32,
+    ) -> OEMResult<()> {
+        let request = AssignCmd::new(channel, state, prn, doppler, doppler_window);
+
+        self.send_message(&request)
+            .and_then(|_| self.get_response(MessageID::Assign))
+    }
+
+    /// Configure one of the receiver's serial ports
+    ///
+    /// Note: If the port being configured is the one used by this connection, the response
+    /// will be sent using the new settings, so will likely not be received.
+    ///
+    /// # Arguments
+    ///
+    /// * port - Serial port to configure
+    /// * baud - Communication data rate
+    /// * parity - Parity setting
+    /// * data_bits - Number of data bits (7 or 8)
+    /// * stop_bits - Number of stop bits (1 or 2)
+    /// * handshake - Handshaking setting
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`OEMError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use novatel_oem6_api::*;
+    /// # use std::sync::mpsc::sync_channel;
+    ///
+    /// # fn func() -> OEMResult<()> {
+    /// # let bus = "/dev/ttyS5";
+    /// # let (log_send, log_recv) = sync_channel(5);
+    /// # let (response_send, response_recv) = sync_channel(5);
+    /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    ///
+    /// oem.set_serial_config(ComPort::Com2, 115200, Parity::None, 8, 1, Handshake::None)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`OEMError`]: enum.OEMError.html
+    pub fn set_serial_config(
+        &self,
+        port: ComPort,
+        baud: u32,
+        parity: Parity,
+        data_bits: u32,
+        stop_bits: u32,
+        handshake: Handshake,
+    ) -> OEMResult<()> {
+        let request = SerialConfigCmd::new(port, baud, parity, data_bits, stop_bits, handshake);
+
+        self.send_message(&request)
+            .and_then(|_| self.get_response(MessageID::SerialConfig))
+    }
+
+    /// Save the current configuration to non-volatile memory, so that it will be
+    /// restored after a reset
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`OEMError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use novatel_oem6_api::*;
+    /// # use std::sync::mpsc::sync_channel;
+    ///
+    /// # fn func() -> OEMResult<()> {
+    /// # let bus = "/dev/ttyS5";
+    /// # let (log_send, log_recv) = sync_channel(5);
+    /// # let (response_send, response_recv) = sync_channel(5);
+    /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    ///
+    /// oem.request_position(1.0, 0.0, false)?;
+    /// oem.save_config()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`OEMError`]: enum.OEMError.html
+    pub fn save_config(&self) -> OEMResult<()> {
+        let request = SaveConfigCmd::new();
+
+        self.send_message(&request)
+            .and_then(|_| self.get_response(MessageID::SaveConfig))
+    }
+
+    /// Reset the receiver
+    ///
+    /// # Arguments
+    ///
+    /// * delay - Number of seconds to wait before resetting
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`OEMError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use novatel_oem6_api::*;
+    /// # use std::sync::mpsc::sync_channel;
+    ///
+    /// # fn func() -> OEMResult<()> {
+    /// # let bus = "/dev/ttyS5";
+    /// # let (log_send, log_recv) = sync_channel(5);
+    /// # let (response_send, response_recv) = sync_channel(5);
+    /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    ///
+    /// oem.reset(0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`OEMError`]: enum.OEMError.html
+    pub fn reset(&self, delay: u32) -> OEMResult<()> {
+        let request = ResetCmd::new(delay);
+
+        self.send_message(&request)
+            .and_then(|_| self.get_response(MessageID::Reset))
+    }
+
+    /// Clear data stored in non-volatile memory and reset the receiver
+    ///
+    /// # Arguments
+    ///
+    /// * target - Data which should be cleared
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`OEMError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use novatel_oem6_api::*;
+    /// # use std::sync::mpsc::sync_channel;
+    ///
+    /// # fn func() -> OEMResult<()> {
+    /// # let bus = "/dev/ttyS5";
+    /// # let (log_send, log_recv) = sync_channel(5);
+    /// # let (response_send, response_recv) = sync_channel(5);
+    /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    ///
+    /// // Discard the saved configuration
+    /// oem.factory_reset(ResetTarget::Command)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`OEMError`]: enum.OEMError.html
+    pub fn factory_reset(&self, target: ResetTarget) -> OEMResult<()> {
+        let request = FResetCmd::new(target);
+
+        self.send_message(&request)
+            .and_then(|_| self.get_response(MessageID::FReset))
+    }
+
+    /// Set the elevation below which satellites will not be tracked
+    ///
+    /// # Arguments
+    ///
+    /// * angle - Elevation cutoff angle, in degrees (-90 to 90)
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`OEMError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use novatel_oem6_api::*;
+    /// # use std::sync::mpsc::sync_channel;
+    ///
+    /// # fn func() -> OEMResult<()> {
+    /// # let bus = "/dev/ttyS5";
+    /// # let (log_send, log_recv) = sync_channel(5);
+    /// # let (response_send, response_recv) = sync_channel(5);
+    /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    ///
+    /// oem.set_elevation_cutoff(5.0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`OEMError`]: enum.OEMError.html
+    pub fn set_elevation_cutoff(&self, angle: f32) -> OEMResult<()> {
+        let request = ECutoffCmd::new(angle);
+
+        self.send_message(&request)
+            .and_then(|_| self.get_response(MessageID::ECutoff))
+    }
+
+    /// Set the protocols used to receive and transmit data on one of the receiver's serial ports
+    ///
+    /// # Arguments
+    ///
+    /// * port - Serial port to configure
+    /// * rx_mode - Protocol for incoming data
+    /// * tx_mode - Protocol for outgoing data
+    /// * responses - Whether command responses should be sent from the port
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`OEMError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use novatel_oem6_api::*;
+    /// # use std::sync::mpsc::sync_channel;
+    ///
+    /// # fn func() -> OEMResult<()> {
+    /// # let bus = "/dev/ttyS5";
+    /// # let (log_send, log_recv) = sync_channel(5);
+    /// # let (response_send, response_recv) = sync_channel(5);
+    /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    ///
+    /// // Accept RTCM corrections on COM2
+    /// oem.set_interface_mode(ComPort::Com2, InterfaceMode::Rtcm, InterfaceMode::None, false)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`OEMError`]: enum.OEMError.html
+    pub fn set_interface_mode(
+        &self,
+        port: ComPort,
+        rx_mode: InterfaceMode,
+        tx_mode: InterfaceMode,
+        responses: bool,
+    ) -> OEMResult<()> {
+        let request = InterfaceModeCmd::new(port, rx_mode, tx_mode, responses);
+
+        self.send_message(&request)
+            .and_then(|_| self.get_response(MessageID::InterfaceMode))
+    }
+
+    /// Directly send a message without formatting or checksum calculation
+    ///
+    /// Note: The message will not be verified by checking for a command response
+    ///
+    /// # Arguments
+    ///
+    /// * msg - Message to send
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, an [`OEMError`] variant will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use novatel_oem6_api::*;
+    /// # use std::thread;
+    /// # use std::sync::mpsc::sync_channel;
+    ///
+    /// # fn func() -> OEMResult<()> {
+    /// # let bus = "/dev/ttyS5";
+    /// # let (log_send, log_recv) = sync_channel(5);
+    /// # let (response_send, response_recv) = sync_channel(5);
+    /// let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv).unwrap();
+    ///
+    /// let packet: [u8; 6] = [0, 1, 2, 3, 4, 5];
+    /// oem.passthrough(&packet)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    ///
+    /// [`OEMError`]: enum.OEMError.html
+    pub fn passthrough(&self, msg: &[u8]) -> OEMResult<()> {
+        // If the mutex has been poisoned by the read thread,
+        // go ahead and attempt the write anyways, to try to
+        // preserve functionality, but inform the caller afterwards
+        match self.conn.lock() {
+            Ok(conn) => conn.write(msg).map_err(|err| err.into()),
+            Err(conn) => conn
+                .into_inner()
+                .write(msg)
+                .map_err(|err| err.into())
+                .and(Err(OEMError::ThreadCommError)),
+        }
+    }
+
+    fn send_message<T: Message>(&self, msg: &T) -> OEMResult<()> {
+        let mut raw = msg.serialize();
+
+        // Get the calculated CRC
+        let crc = calc_crc(&raw);
+        raw.write_u32::<LittleEndian>(crc).unwrap();
+
+        // If the mutex has been poisoned by the read thread,
+        // go ahead and attempt the write anyways, to try to
+        // preserve functionality, but inform the caller afterwards
+        match self.conn.lock() {
+            Ok(conn) => conn.write(raw.as_slice()).map_err(|err| err.into()),
+            Err(conn) => conn
+                .into_inner()
                 .write(raw.as_slice())
                 .map_err(|err| err.into())
                 .and(Err(OEMError::ThreadCommError)),
         }
     }
 
+    // Request a log at a regular interval, or once if no interval is given
+    fn request_log(&self, id: MessageID, interval: f64, offset: f64, hold: bool) -> OEMResult<()> {
+        let trigger = if interval == 0.0 {
+            LogTrigger::Once
+        } else {
+            LogTrigger::OnTime
+        };
+
+        let request = LogCmd::new(
+            Port::COM1 as u32,
+            id as u16,
+            trigger,
+            interval,
+            offset,
+            hold,
+        );
+
+        self.send_message(&request)
+            .and_then(|_| self.get_response(MessageID::Log))
+    }
+
     fn get_response(&self, id: MessageID) -> OEMResult<()> {
         let (hdr, body) = self
             .response_recv
@@ -694,14 +1358,20 @@ impl OEM6 {
                 continue;
             }
 
-            match Log::new(
-                hdr.msg_id,
-                hdr.recv_status,
-                hdr.time_status,
-                hdr.week,
-                hdr.ms,
-                body,
-            ) {
+            let log = match hdr.format() {
+                LogFormat::Binary => Log::new(
+                    hdr.msg_id,
+                    hdr.recv_status,
+                    hdr.time_status,
+                    hdr.week,
+                    hdr.ms,
+                    body,
+                ),
+                LogFormat::Ascii => ascii::parse_log(&hdr, &body),
+                LogFormat::Nmea => nmea::parse_log(&hdr, &body),
+            };
+
+            match log {
                 Some(v) => return Ok(v),
                 None => {
                     continue;
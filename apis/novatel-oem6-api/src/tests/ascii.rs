@@ -0,0 +1,230 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+use crate::messages::ReceiverStatusFlags;
+use std::time::Duration;
+
+#[test]
+fn test_get_ascii_best_position() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(
+        b"#BESTPOSA,COM1,0,72.5,FINESTEERING,2025,334713.000,02000020,cdba,32768;\
+          SOL_COMPUTED,SINGLE,51.125,-114.0625,1064.5,-16.25,WGS84,1.5,1.25,3.0,\"\",\
+          0.000,0.000,10,9,9,0,0,06,00,01*c59e7d2b\r\n"
+            .to_vec(),
+    );
+
+    let oem = mock_new!(mock, LogFormat::Ascii);
+
+    let expected: Log = Log::BestPos(BestPosLog {
+        recv_status: ReceiverStatusFlags::ANTENNA_OPEN,
+        time_status: 180,
+        week: 2025,
+        ms: 334713000,
+        pos_status: 0,
+        pos_type: 16,
+        lat: 51.125,
+        lon: -114.0625,
+        height: 1064.5,
+        undulation: -16.25,
+        datum_id: 61,
+        pos_deviation: [1.5, 1.25, 3.0],
+        station_id: "".to_owned(),
+        diff_age: 0.0,
+        sol_age: 0.0,
+        num_sats: 10,
+        num_sat_vehicles: 9,
+        num_gg_l1: 9,
+        num_multi_sats: 0,
+        ext_sol_stat: 6,
+        gal_beidou_sig: 0,
+        gps_glonass_sig: 1,
+    });
+
+    assert_eq!(oem.get_log().unwrap(), expected);
+}
+
+#[test]
+fn test_get_ascii_best_velocity() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(
+        b"#BESTVELA,COM1,0,72.5,FINESTEERING,2025,334713.000,02000020,cdba,32768;\
+          SOL_COMPUTED,SINGLE,0.125,0.000,7612.25,312.5,-3.75,0.0*17845f54\r\n"
+            .to_vec(),
+    );
+
+    let oem = mock_new!(mock, LogFormat::Ascii);
+
+    let expected: Log = Log::BestVel(BestVelLog {
+        recv_status: ReceiverStatusFlags::ANTENNA_OPEN,
+        time_status: 180,
+        week: 2025,
+        ms: 334713000,
+        vel_status: 0,
+        vel_type: 16,
+        latency: 0.125,
+        diff_age: 0.0,
+        hor_speed: 7612.25,
+        track_ground: 312.5,
+        vert_speed: -3.75,
+    });
+
+    assert_eq!(oem.get_log().unwrap(), expected);
+}
+
+#[test]
+fn test_get_ascii_best_xyz() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(
+        b"#BESTXYZA,COM1,0,72.5,FINESTEERING,2025,334713.000,02000020,cdba,32768;\
+          SOL_COMPUTED,NARROW_INT,-1634531.5,-3664618.25,4942496.75,0.5,0.25,0.125,\
+          SOL_COMPUTED,NARROW_INT,0.5,-0.25,-0.125,0.0625,0.03125,0.015625,\"AAAA\",\
+          0.250,1.000,0.000,12,11,11,11,0,01,0,33*6d2543ad\r\n"
+            .to_vec(),
+    );
+
+    let oem = mock_new!(mock, LogFormat::Ascii);
+
+    let expected: Log = Log::BestXYZ(BestXYZLog {
+        recv_status: ReceiverStatusFlags::ANTENNA_OPEN,
+        time_status: 180,
+        week: 2025,
+        ms: 334713000,
+        pos_status: 0,
+        pos_type: 50,
+        position: [-1634531.5, -3664618.25, 4942496.75],
+        pos_deviation: [0.5, 0.25, 0.125],
+        vel_status: 0,
+        vel_type: 50,
+        velocity: [0.5, -0.25, -0.125],
+        vel_deviation: [0.0625, 0.03125, 0.015625],
+        station_id: "AAAA".to_owned(),
+        vel_time_latency: 0.25,
+        diff_age: 1.0,
+        sol_age: 0.0,
+        num_sats: 12,
+        num_sat_vehicles: 11,
+        num_gg_l1: 11,
+        num_multi_sats: 11,
+        ext_sol_stat: 1,
+        gal_beidou_sig: 0,
+        gps_glonass_sig: 0x33,
+    });
+
+    assert_eq!(oem.get_log().unwrap(), expected);
+}
+
+#[test]
+fn test_get_ascii_time() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(
+        b"#TIMEA,COM1,0,72.5,FINESTEERING,2025,334713.000,02000020,cdba,32768;\
+          VALID,-0.5,0.25,-18.00000000000,2018,10,31,20,58,15000,VALID*22becd4c\r\n"
+            .to_vec(),
+    );
+
+    let oem = mock_new!(mock, LogFormat::Ascii);
+
+    let expected: Log = Log::Time(TimeLog {
+        recv_status: ReceiverStatusFlags::ANTENNA_OPEN,
+        time_status: 180,
+        week: 2025,
+        ms: 334713000,
+        clock_status: 0,
+        offset: -0.5,
+        offset_std: 0.25,
+        utc_offset: -18.0,
+        utc_year: 2018,
+        utc_month: 10,
+        utc_day: 31,
+        utc_hour: 20,
+        utc_min: 58,
+        utc_ms: 15000,
+        utc_status: 1,
+    });
+
+    assert_eq!(oem.get_log().unwrap(), expected);
+}
+
+#[test]
+fn test_get_ascii_bad_crc() {
+    let mut mock = MockStream::default();
+
+    // The first message is corrupted, so should be skipped
+    mock.read.set_output(
+        b"#TIMEA,COM1,0,72.5,FINESTEERING,2025,334713.000,02000020,cdba,32768;\
+          VALID,-0.5,0.25,-18.00000000000,2018,10,31,20,58,15000,VALID*00000000\r\n\
+          #BESTVELA,COM1,0,72.5,FINESTEERING,2025,334713.000,02000020,cdba,32768;\
+          SOL_COMPUTED,SINGLE,0.125,0.000,7612.25,312.5,-3.75,0.0*17845f54\r\n"
+            .to_vec(),
+    );
+
+    let oem = mock_new!(mock, LogFormat::Ascii);
+
+    match oem.get_log().unwrap() {
+        Log::BestVel(_) => {}
+        other => panic!("Unexpected log: {:?}", other),
+    }
+}
+
+#[test]
+fn test_ascii_ignored_in_binary_mode() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(
+        b"#BESTVELA,COM1,0,72.5,FINESTEERING,2025,334713.000,02000020,cdba,32768;\
+          SOL_COMPUTED,SINGLE,0.125,0.000,7612.25,312.5,-3.75,0.0*17845f54\r\n"
+            .to_vec(),
+    );
+
+    let oem = mock_new!(mock);
+
+    let result = oem
+        .log_recv
+        .lock()
+        .unwrap()
+        .recv_timeout(Duration::from_millis(500));
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_request_position_ascii_mode() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x0, 0xC0, 0x20, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x20, 0x0, 0x0, 0x0, 0xF1, 0x0, 0x0,
+        0x0, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xF0, 0x3F, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xB9, 0x54, 0x12, 0x6F,
+    ]);
+
+    // Command responses are still sent in binary
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0xFF, 0x78, 0xD1, 0xB,
+        0x6, 0x67, 0xC9, 0x9, 0x0, 0x0, 0x0, 0x0, 0xFB, 0xFD, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x10, 0x9D, 0x33, 0xB1,
+    ]);
+
+    let oem = mock_new!(mock, LogFormat::Ascii);
+
+    assert_eq!(oem.request_position(1.0, 0.0, false), Ok(()));
+}
@@ -0,0 +1,127 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+use crate::messages::ReceiverStatusFlags;
+
+#[test]
+fn test_request_best_position() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x0, 0xC0, 0x20, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x20, 0x0, 0x0, 0x0, 0x2A, 0x0, 0x0,
+        0x0, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xF0, 0x3F, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x66, 0xA3, 0xBD, 0xCC,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0xFF, 0x78, 0xD1, 0xB,
+        0x6, 0x67, 0xC9, 0x9, 0x0, 0x0, 0x0, 0x0, 0xFB, 0xFD, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x10, 0x9D, 0x33, 0xB1,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(oem.request_best_position(1.0, 0.0, false), Ok(()));
+}
+
+#[test]
+fn test_request_best_position_once() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x0, 0xC0, 0x20, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x20, 0x0, 0x0, 0x0, 0x2A, 0x0, 0x0,
+        0x0, 0x4, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0xEE, 0xF2, 0x29, 0x8D,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0xFF, 0x78, 0xD1, 0xB,
+        0x6, 0x67, 0xC9, 0x9, 0x0, 0x0, 0x0, 0x0, 0xFB, 0xFD, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x10, 0x9D, 0x33, 0xB1,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(oem.request_best_position(0.0, 0.0, true), Ok(()));
+}
+
+#[test]
+fn test_get_best_position() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x2A, 0x0, 0x0, 0x20, 0x48, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x0, 0x0, 0x0, 0x0, 0x10,
+        0x0, 0x0, 0x0, 0x37, 0x89, 0x41, 0x60, 0xE5, 0x8E, 0x49, 0x40, 0xD8, 0x61, 0x4C, 0xFA,
+        0x7B, 0x82, 0x5C, 0xC0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xA2, 0x90, 0x40, 0xF6, 0x28, 0x82, 0xC1,
+        0x3D, 0x0, 0x0, 0x0, 0xCD, 0xCC, 0xCC, 0x3F, 0x66, 0x66, 0xA6, 0x3F, 0xCD, 0xCC, 0x4C,
+        0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xA, 0x9, 0x9, 0x0, 0x0,
+        0x6, 0x0, 0x1, 0x3E, 0xFF, 0xEA, 0x4B,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    let expected: Log = Log::BestPos(BestPosLog {
+        recv_status: ReceiverStatusFlags::empty(),
+        time_status: 180,
+        week: 2025,
+        ms: 334713000,
+        pos_status: 0,
+        pos_type: 16,
+        lat: 51.116375,
+        lon: -114.038817,
+        height: 1064.5,
+        undulation: -16.27,
+        datum_id: 61,
+        pos_deviation: [1.6, 1.3, 3.2],
+        station_id: "".to_owned(),
+        diff_age: 0.0,
+        sol_age: 0.0,
+        num_sats: 10,
+        num_sat_vehicles: 9,
+        num_gg_l1: 9,
+        num_multi_sats: 0,
+        ext_sol_stat: 6,
+        gal_beidou_sig: 0,
+        gps_glonass_sig: 1,
+    });
+
+    assert_eq!(oem.get_log().unwrap(), expected);
+}
+
+#[test]
+fn test_best_position_short() {
+    let raw = vec![
+        0x0, 0x0, 0x0, 0x0, 0x10, 0x0, 0x0, 0x0, 0x37, 0x89, 0x41, 0x60, 0xE5, 0x8E, 0x49, 0x40,
+        0xD8, 0x61, 0x4C, 0xFA, 0x7B, 0x82, 0x5C, 0xC0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xA2, 0x90, 0x40,
+        0xF6, 0x28, 0x82, 0xC1, 0x3D, 0x0, 0x0, 0x0,
+    ];
+
+    assert_eq!(
+        Log::new(
+            MessageID::BestPos,
+            ReceiverStatusFlags::empty(),
+            180,
+            2025,
+            334713000,
+            raw
+        ),
+        None
+    );
+}
@@ -0,0 +1,91 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+use crate::messages::ReceiverStatusFlags;
+
+#[test]
+fn test_request_best_velocity() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x0, 0xC0, 0x20, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x20, 0x0, 0x0, 0x0, 0x63, 0x0, 0x0,
+        0x0, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xF0, 0x3F, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0xE0, 0x3F, 0x0, 0x0, 0x0, 0x0, 0x6, 0xC5, 0xED, 0x98,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0xFF, 0x78, 0xD1, 0xB,
+        0x6, 0x67, 0xC9, 0x9, 0x0, 0x0, 0x0, 0x0, 0xFB, 0xFD, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x10, 0x9D, 0x33, 0xB1,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(oem.request_best_velocity(1.0, 0.5, false), Ok(()));
+}
+
+#[test]
+fn test_get_best_velocity() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x63, 0x0, 0x0, 0x20, 0x2C, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x0, 0x0, 0x0, 0x0, 0x10,
+        0x0, 0x0, 0x0, 0x9A, 0x99, 0x19, 0x3E, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x40, 0xBC,
+        0xBD, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x88, 0x73, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xE,
+        0xC0, 0x0, 0x0, 0x0, 0x0, 0x56, 0x7D, 0x69, 0x4B,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    let expected: Log = Log::BestVel(BestVelLog {
+        recv_status: ReceiverStatusFlags::empty(),
+        time_status: 180,
+        week: 2025,
+        ms: 334713000,
+        vel_status: 0,
+        vel_type: 16,
+        latency: 0.15,
+        diff_age: 0.0,
+        hor_speed: 7612.25,
+        track_ground: 312.5,
+        vert_speed: -3.75,
+    });
+
+    assert_eq!(oem.get_log().unwrap(), expected);
+}
+
+#[test]
+fn test_best_velocity_short() {
+    let raw = vec![
+        0x0, 0x0, 0x0, 0x0, 0x10, 0x0, 0x0, 0x0, 0x9A, 0x99, 0x19, 0x3E, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0,
+    ];
+
+    assert_eq!(
+        Log::new(
+            MessageID::BestVel,
+            ReceiverStatusFlags::empty(),
+            180,
+            2025,
+            334713000,
+            raw
+        ),
+        None
+    );
+}
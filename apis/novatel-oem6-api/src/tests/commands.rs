@@ -0,0 +1,303 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+
+#[test]
+fn test_set_fix_position() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x2C, 0x0, 0x0, 0xC0, 0x1C, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x90, 0x49, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x84, 0x5C, 0xC0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0xA2, 0x90, 0x40, 0x4A, 0x1D, 0xC6, 0xA7,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x2C, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x6E, 0xA1, 0x99, 0x7A,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(
+        oem.set_fix(FixType::Position, 51.125, -114.0625, 1064.5),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_set_fix_height() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x2C, 0x0, 0x0, 0xC0, 0x1C, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0xA2, 0x90, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0xBB, 0x18, 0xF1, 0x24,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x2C, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x6E, 0xA1, 0x99, 0x7A,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(
+        oem.set_fix(FixType::Height, 51.125, -114.0625, 1064.5),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_set_fix_none() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x2C, 0x0, 0x0, 0xC0, 0x1C, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x59, 0x95, 0x35, 0x32,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x2C, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x6E, 0xA1, 0x99, 0x7A,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(
+        oem.set_fix(FixType::None, 51.125, -114.0625, 1064.5),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_set_fix_error() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x2C, 0x0, 0x0, 0xC0, 0x1C, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0xC0, 0x56, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0xF, 0x53, 0xE6, 0x20,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x2C, 0x0, 0x80, 0x20, 0x1A, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0xB, 0x0, 0x0, 0x0, 0x46,
+        0x69, 0x65, 0x6C, 0x64, 0x20, 0x6C, 0x61, 0x74, 0x20, 0x6F, 0x75, 0x74, 0x20, 0x6F, 0x66,
+        0x20, 0x72, 0x61, 0x6E, 0x67, 0x65, 0x94, 0xC7, 0x2C, 0x60,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(
+        oem.set_fix(FixType::Position, 91.0, 0.0, 0.0),
+        Err(OEMError::CommandError {
+            id: ResponseID::ErrorField,
+            description: "Field lat out of range".to_owned(),
+        })
+    );
+}
+
+#[test]
+fn test_assign_channel() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1B, 0x0, 0x0, 0xC0, 0x14, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1, 0x0, 0x0,
+        0x0, 0x3, 0x0, 0x0, 0x0, 0xC, 0xFE, 0xFF, 0xFF, 0xD0, 0x7, 0x0, 0x0, 0x67, 0xC5, 0x7E,
+        0xBF,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1B, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x50, 0x4C, 0x8F, 0x5E,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(
+        oem.assign_channel(0, ChannelState::Active, 3, -500, 2000),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_set_serial_config() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0xDE, 0x4, 0x0, 0xC0, 0x1C, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x2, 0x0, 0x0, 0x0, 0x0, 0xC2, 0x1,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x8, 0x0, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0xEC, 0xFE, 0x4B, 0x3F,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0xDE, 0x4, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x72, 0x5B, 0x40, 0xED,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(
+        oem.set_serial_config(ComPort::Com2, 115200, Parity::None, 8, 1, Handshake::None),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_save_config() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x13, 0x0, 0x0, 0xC0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xD0, 0x17, 0xF9, 0xF0,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x13, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x51, 0x52, 0xF9, 0x66,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(oem.save_config(), Ok(()));
+}
+
+#[test]
+fn test_save_config_mismatch() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x13, 0x0, 0x0, 0xC0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xD0, 0x17, 0xF9, 0xF0,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x43, 0xA8, 0xD4, 0x6E,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(oem.save_config(), Err(OEMError::ResponseMismatch));
+}
+
+#[test]
+fn test_reset() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x12, 0x0, 0x0, 0xC0, 0x4, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x5, 0x0, 0x0, 0x0, 0xF4, 0x37,
+        0x93, 0x18,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x12, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x59, 0xB1, 0x99, 0x5A,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(oem.reset(5), Ok(()));
+}
+
+#[test]
+fn test_factory_reset() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x14, 0x0, 0x0, 0xC0, 0x4, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0xED, 0xAE,
+        0x4D, 0x92,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x14, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x69, 0xFB, 0xDB, 0xD3,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(oem.factory_reset(ResetTarget::Command), Ok(()));
+}
+
+#[test]
+fn test_set_elevation_cutoff() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x32, 0x0, 0x0, 0xC0, 0x4, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xA0, 0x40, 0x1F, 0x99,
+        0x29, 0xC1,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x32, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x5D, 0xC9, 0x41, 0xBB,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(oem.set_elevation_cutoff(5.0), Ok(()));
+}
+
+#[test]
+fn test_set_interface_mode() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x3, 0x0, 0x0, 0xC0, 0x10, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x2, 0x0, 0x0, 0x0, 0x2, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xEF, 0x95, 0x2E, 0xE8,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x3, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x53, 0x6E, 0x15, 0x16,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(
+        oem.set_interface_mode(
+            ComPort::Com2,
+            InterfaceMode::Rtcm,
+            InterfaceMode::None,
+            false
+        ),
+        Ok(())
+    );
+}
@@ -0,0 +1,139 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+use crate::messages::ReceiverStatusFlags;
+
+#[test]
+fn test_request_ephemeris() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x0, 0xC0, 0x20, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x20, 0x0, 0x0, 0x0, 0x7, 0x0, 0x0,
+        0x0, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xD2, 0x2C, 0xC4, 0xE1,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0xFF, 0x78, 0xD1, 0xB,
+        0x6, 0x67, 0xC9, 0x9, 0x0, 0x0, 0x0, 0x0, 0xFB, 0xFD, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x10, 0x9D, 0x33, 0xB1,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(oem.request_ephemeris(false), Ok(()));
+}
+
+#[test]
+fn test_get_ephemeris() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x7, 0x0, 0x0, 0x20, 0xE0, 0x0, 0x0, 0x0, 0x6E, 0xC8, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x3, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0xE0, 0x47, 0x18, 0x41, 0x0, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0x3, 0x0,
+        0x0, 0x0, 0xE9, 0x7, 0x0, 0x0, 0xE9, 0x7, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x9C, 0x18,
+        0x41, 0x0, 0x0, 0x0, 0x92, 0x6E, 0x54, 0x79, 0x41, 0x3C, 0x38, 0x2C, 0x12, 0x98, 0x1A,
+        0x34, 0x3E, 0xA7, 0x79, 0xC7, 0x29, 0x3A, 0x92, 0x0, 0xC0, 0x48, 0x50, 0xFC, 0x18, 0x73,
+        0xD7, 0x82, 0x3F, 0xA5, 0x4E, 0x40, 0x13, 0x61, 0xC3, 0xED, 0x3F, 0x42, 0x99, 0x4, 0x77,
+        0xB9, 0xEB, 0xC9, 0xBE, 0x89, 0xD8, 0x79, 0x7E, 0xC5, 0xD8, 0xE1, 0x3E, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x31, 0x6B, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0xE0, 0x4C, 0xC0, 0xEF, 0x71, 0xC7, 0x17,
+        0xC4, 0x72, 0x81, 0x3E, 0x85, 0x55, 0xEF, 0x82, 0x1E, 0xF0, 0x64, 0xBE, 0x27, 0xC2, 0x86,
+        0xA7, 0x57, 0xCA, 0xEE, 0x3F, 0x70, 0xB, 0x1B, 0xE9, 0x1F, 0x7E, 0xE0, 0x3D, 0x3F, 0x57,
+        0x5B, 0xB1, 0xBF, 0xEC, 0xFD, 0xBF, 0x5E, 0xC9, 0xE9, 0xE7, 0x6, 0x5A, 0x41, 0xBE, 0x3,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x9C, 0x18, 0x41, 0x29, 0x66, 0xA4, 0xD3, 0x5D,
+        0xF4, 0x1F, 0x3E, 0xFA, 0x9C, 0xBB, 0x5D, 0x2F, 0x4D, 0x1, 0xBF, 0x95, 0x64, 0x79, 0xE1,
+        0x7F, 0xFD, 0xA5, 0xBD, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x94,
+        0x7F, 0xD2, 0xF5, 0xE9, 0x1D, 0x23, 0x3F, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x10, 0x40, 0xDB,
+        0x49, 0x30, 0xA,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    let expected: Log = Log::GpsEphem(GpsEphemLog {
+        recv_status: ReceiverStatusFlags::empty(),
+        time_status: 200,
+        week: 2025,
+        ms: 334713000,
+        prn: 3,
+        tow: 397816.0,
+        health: 0,
+        iode1: 3,
+        iode2: 3,
+        ephem_week: 2025,
+        z_week: 2025,
+        toe: 403200.0,
+        semi_major_axis: 26560233.125,
+        delta_n: 4.6808e-09,
+        mean_anomaly: -2.0714,
+        eccentricity: 0.0092,
+        arg_perigee: 0.9301,
+        cuc: -3.09e-06,
+        cus: 8.51e-06,
+        crc: 217.53125,
+        crs: -57.75,
+        cic: 1.3e-07,
+        cis: -3.9e-08,
+        inclination: 0.9622,
+        inclination_rate: 1.2e-10,
+        right_ascension: -1.8703,
+        right_ascension_rate: -8.08e-09,
+        iodc: 3,
+        toc: 403200.0,
+        tgd: 1.86e-09,
+        af0: -3.3e-05,
+        af1: -1e-11,
+        af2: 0.0,
+        anti_spoofing: true,
+        corrected_mean_motion: 0.00014585,
+        ura: 4.0,
+    });
+
+    assert_eq!(oem.get_log().unwrap(), expected);
+}
+
+#[test]
+fn test_ephemeris_short() {
+    let raw = vec![
+        0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xE0, 0x47, 0x18, 0x41, 0x0, 0x0, 0x0, 0x0, 0x3,
+        0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0xE9, 0x7, 0x0, 0x0, 0xE9, 0x7, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x9C, 0x18, 0x41, 0x0, 0x0, 0x0, 0x92, 0x6E, 0x54, 0x79, 0x41, 0x3C, 0x38, 0x2C,
+        0x12, 0x98, 0x1A, 0x34, 0x3E, 0xA7, 0x79, 0xC7, 0x29, 0x3A, 0x92, 0x0, 0xC0, 0x48, 0x50,
+        0xFC, 0x18, 0x73, 0xD7, 0x82, 0x3F, 0xA5, 0x4E, 0x40, 0x13, 0x61, 0xC3, 0xED, 0x3F, 0x42,
+        0x99, 0x4, 0x77, 0xB9, 0xEB, 0xC9, 0xBE, 0x89, 0xD8, 0x79, 0x7E, 0xC5, 0xD8, 0xE1, 0x3E,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x31, 0x6B, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0xE0, 0x4C, 0xC0, 0xEF,
+        0x71, 0xC7, 0x17, 0xC4, 0x72, 0x81, 0x3E, 0x85, 0x55, 0xEF, 0x82, 0x1E, 0xF0, 0x64, 0xBE,
+        0x27, 0xC2, 0x86, 0xA7, 0x57, 0xCA, 0xEE, 0x3F, 0x70, 0xB, 0x1B, 0xE9, 0x1F, 0x7E, 0xE0,
+        0x3D, 0x3F, 0x57, 0x5B, 0xB1, 0xBF, 0xEC, 0xFD, 0xBF, 0x5E, 0xC9, 0xE9, 0xE7, 0x6, 0x5A,
+        0x41, 0xBE, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x9C, 0x18, 0x41, 0x29, 0x66,
+        0xA4, 0xD3, 0x5D, 0xF4, 0x1F, 0x3E, 0xFA, 0x9C, 0xBB, 0x5D, 0x2F, 0x4D, 0x1, 0xBF, 0x95,
+        0x64, 0x79, 0xE1, 0x7F, 0xFD, 0xA5, 0xBD, 0x0, 0x0, 0x0, 0x0,
+    ];
+
+    assert_eq!(
+        Log::new(
+            MessageID::GpsEphem,
+            ReceiverStatusFlags::empty(),
+            180,
+            2025,
+            334713000,
+            raw
+        ),
+        None
+    );
+}
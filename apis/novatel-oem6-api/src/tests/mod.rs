@@ -34,6 +34,25 @@ macro_rules! mock_new {
 
         thread::spawn(move || read_thread(&rx_conn, &log_send, &response_send));
 
+        OEM6 {
+            conn,
+            log_recv: Arc::new(Mutex::new(log_recv)),
+            response_recv: Arc::new(Mutex::new(response_recv)),
+        }
+    }};
+    ($mock:ident, $format:expr) => {{
+        let (log_send, log_recv) = sync_channel(5);
+        let (response_send, response_recv) = sync_channel(5);
+
+        let conn = Arc::new(Mutex::new(Connection {
+            stream: Box::new($mock),
+        }));
+        let rx_conn = conn.clone();
+
+        thread::spawn(move || {
+            read_thread_with_format(&rx_conn, &log_send, &response_send, $format)
+        });
+
         OEM6 {
             conn,
             log_recv: Arc::new(Mutex::new(log_recv)),
@@ -85,7 +104,15 @@ fn test_passthrough_good() {
     assert_eq!(oem.passthrough(&packet), Ok(()));
 }
 
+mod ascii;
+mod best_pos;
+mod best_vel;
+mod commands;
+mod ephemeris;
 mod errors;
+mod nmea;
 mod position;
+mod range;
+mod time;
 mod unlog;
 mod version;
@@ -0,0 +1,219 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+use crate::messages::ReceiverStatusFlags;
+
+#[test]
+fn test_get_nmea_gga() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(
+        b"$GPGGA,202530.00,5107.5000,N,11403.7500,W,2,9,0.9,1064.5,M,-16.25,M,2.0,0123*4F\r\n"
+            .to_vec(),
+    );
+
+    let oem = mock_new!(mock, LogFormat::Nmea);
+
+    let expected: Log = Log::BestPos(BestPosLog {
+        recv_status: ReceiverStatusFlags::empty(),
+        time_status: 20,
+        week: 0,
+        ms: 0,
+        pos_status: 0,
+        pos_type: 17,
+        lat: 51.125,
+        lon: -114.0625,
+        height: 1064.5,
+        undulation: -16.25,
+        datum_id: 61,
+        pos_deviation: [0.0, 0.0, 0.0],
+        station_id: "0123".to_owned(),
+        diff_age: 2.0,
+        sol_age: 0.0,
+        num_sats: 9,
+        num_sat_vehicles: 9,
+        num_gg_l1: 0,
+        num_multi_sats: 0,
+        ext_sol_stat: 0,
+        gal_beidou_sig: 0,
+        gps_glonass_sig: 0,
+    });
+
+    assert_eq!(oem.get_log().unwrap(), expected);
+}
+
+#[test]
+fn test_get_nmea_gga_no_fix() {
+    let mut mock = MockStream::default();
+
+    mock.read
+        .set_output(b"$GPGGA,202530.00,,,,,0,0,,,M,,M,,*7E\r\n".to_vec());
+
+    let oem = mock_new!(mock, LogFormat::Nmea);
+
+    let expected: Log = Log::BestPos(BestPosLog {
+        recv_status: ReceiverStatusFlags::empty(),
+        time_status: 20,
+        pos_status: 1,
+        datum_id: 61,
+        ..Default::default()
+    });
+
+    assert_eq!(oem.get_log().unwrap(), expected);
+}
+
+#[test]
+fn test_get_nmea_rmc() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(
+        b"$GPRMC,202530.00,A,5107.5000,N,11403.7500,W,10.0,312.5,311018,0.0,E,D*19\r\n".to_vec(),
+    );
+
+    let oem = mock_new!(mock, LogFormat::Nmea);
+
+    match oem.get_log().unwrap() {
+        Log::BestVel(log) => {
+            assert_eq!(log.vel_status, 0);
+            assert_eq!(log.vel_type, 17);
+            assert!((log.hor_speed - 5.144).abs() < 0.001);
+            assert_eq!(log.track_ground, 312.5);
+            assert_eq!(log.vert_speed, 0.0);
+        }
+        other => panic!("Unexpected log: {:?}", other),
+    }
+}
+
+#[test]
+fn test_get_nmea_gsa() {
+    let mut mock = MockStream::default();
+
+    mock.read
+        .set_output(b"$GNGSA,A,3,03,22,,,,,,,,,,,1.5,0.75,1.25*2F\r\n".to_vec());
+
+    let oem = mock_new!(mock, LogFormat::Nmea);
+
+    let expected: Log = Log::GpsDop(GpsDopLog {
+        recv_status: ReceiverStatusFlags::empty(),
+        time_status: 20,
+        week: 0,
+        ms: 0,
+        fix_mode: 3,
+        prns: vec![3, 22],
+        pdop: 1.5,
+        hdop: 0.75,
+        vdop: 1.25,
+    });
+
+    assert_eq!(oem.get_log().unwrap(), expected);
+}
+
+#[test]
+fn test_get_nmea_zda() {
+    let mut mock = MockStream::default();
+
+    mock.read
+        .set_output(b"$GPZDA,205815.00,31,10,2018,00,00*65\r\n".to_vec());
+
+    let oem = mock_new!(mock, LogFormat::Nmea);
+
+    let expected: Log = Log::Time(TimeLog {
+        recv_status: ReceiverStatusFlags::empty(),
+        time_status: 20,
+        week: 0,
+        ms: 0,
+        clock_status: 0,
+        offset: 0.0,
+        offset_std: 0.0,
+        utc_offset: 0.0,
+        utc_year: 2018,
+        utc_month: 10,
+        utc_day: 31,
+        utc_hour: 20,
+        utc_min: 58,
+        utc_ms: 15000,
+        utc_status: 1,
+    });
+
+    assert_eq!(oem.get_log().unwrap(), expected);
+}
+
+#[test]
+fn test_get_nmea_bad_checksum() {
+    let mut mock = MockStream::default();
+
+    // The first sentence is corrupted, so should be skipped
+    mock.read.set_output(
+        b"$GPZDA,205815.00,31,10,2018,00,00*00\r\n\
+          $GNGSA,A,3,03,22,,,,,,,,,,,1.5,0.75,1.25*2F\r\n"
+            .to_vec(),
+    );
+
+    let oem = mock_new!(mock, LogFormat::Nmea);
+
+    match oem.get_log().unwrap() {
+        Log::GpsDop(_) => {}
+        other => panic!("Unexpected log: {:?}", other),
+    }
+}
+
+#[test]
+fn test_get_nmea_mixed_binary() {
+    let mut mock = MockStream::default();
+
+    // Binary logs are still accepted alongside NMEA sentences
+    let mut output = b"$GPZDA,205815.00,31,10,2018,00,00*65\r\n".to_vec();
+    output.extend_from_slice(&[
+        0xAA, 0x44, 0x12, 0x1C, 0x63, 0x0, 0x0, 0x20, 0x2C, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x0, 0x0, 0x0, 0x0, 0x10,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x3E, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x40, 0xBC,
+        0xBD, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x88, 0x73, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xE,
+        0xC0, 0x0, 0x0, 0x0, 0x0, 0xD2, 0x17, 0xE0, 0xEA,
+    ]);
+    mock.read.set_output(output);
+
+    let oem = mock_new!(mock, LogFormat::Nmea);
+
+    match oem.get_log().unwrap() {
+        Log::Time(_) => {}
+        other => panic!("Unexpected log: {:?}", other),
+    }
+
+    match oem.get_log().unwrap() {
+        Log::BestVel(log) => assert_eq!(log.hor_speed, 7612.25),
+        other => panic!("Unexpected log: {:?}", other),
+    }
+}
+
+#[test]
+fn test_get_nmea_unsupported() {
+    let mut mock = MockStream::default();
+
+    // GSV sentences aren't supported, so should be skipped
+    mock.read.set_output(
+        b"$GPGSV,1,1,01,03,45,120,47*4A\r\n\
+          $GPZDA,205815.00,31,10,2018,00,00*65\r\n"
+            .to_vec(),
+    );
+
+    let oem = mock_new!(mock, LogFormat::Nmea);
+
+    match oem.get_log().unwrap() {
+        Log::Time(_) => {}
+        other => panic!("Unexpected log: {:?}", other),
+    }
+}
@@ -0,0 +1,111 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+use crate::messages::ReceiverStatusFlags;
+
+#[test]
+fn test_request_range() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x0, 0xC0, 0x20, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x20, 0x0, 0x0, 0x0, 0x2B, 0x0, 0x0,
+        0x0, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xF0, 0x3F, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x1E, 0x39, 0xB4, 0x75,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0xFF, 0x78, 0xD1, 0xB,
+        0x6, 0x67, 0xC9, 0x9, 0x0, 0x0, 0x0, 0x0, 0xFB, 0xFD, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x10, 0x9D, 0x33, 0xB1,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(oem.request_range(1.0, 0.0, true), Ok(()));
+}
+
+#[test]
+fn test_get_range() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x2B, 0x0, 0x0, 0x20, 0x5C, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x2, 0x0, 0x0, 0x0, 0x3,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xA2, 0xFB, 0xFE, 0x73, 0x41, 0x0, 0x0, 0x80, 0x3D, 0x0, 0x0,
+        0x0, 0x1E, 0x13, 0x45, 0x9A, 0xC1, 0x0, 0x0, 0x0, 0x3C, 0x0, 0xE8, 0x34, 0xC5, 0x0, 0x0,
+        0x3D, 0x42, 0x0, 0x70, 0xBE, 0x44, 0x4, 0x9C, 0x10, 0x8, 0x16, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x68, 0x15, 0x21, 0x76, 0x41, 0x0, 0x0, 0x0, 0x3E, 0x0, 0x0, 0x0, 0x1, 0x76, 0x12,
+        0x9D, 0xC1, 0x0, 0x0, 0x80, 0x3C, 0x0, 0x58, 0xAC, 0x44, 0x0, 0x0, 0x26, 0x42, 0x0, 0xC0,
+        0x4A, 0x44, 0x24, 0x9C, 0x10, 0x8, 0x8, 0x93, 0x8A, 0x1B,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    let expected: Log = Log::Range(RangeLog {
+        recv_status: ReceiverStatusFlags::empty(),
+        time_status: 180,
+        week: 2025,
+        ms: 334713000,
+        num_obs: 2,
+        observations: vec![
+            RangeObservation {
+                prn: 3,
+                glonass_freq: 0,
+                psr: 20967354.125,
+                psr_std: 0.0625,
+                adr: -110183623.5,
+                adr_std: 0.0078125,
+                doppler: -2894.5,
+                carrier_noise: 47.25,
+                lock_time: 1523.5,
+                tracking_status: 0x08109C04,
+            },
+            RangeObservation {
+                prn: 22,
+                glonass_freq: 0,
+                psr: 23204182.5,
+                psr_std: 0.125,
+                adr: -121937280.25,
+                adr_std: 0.015625,
+                doppler: 1378.75,
+                carrier_noise: 41.5,
+                lock_time: 811.0,
+                tracking_status: 0x08109C24,
+            },
+        ],
+    });
+
+    assert_eq!(oem.get_log().unwrap(), expected);
+}
+
+#[test]
+fn test_range_short() {
+    let raw = vec![0x2, 0x0];
+
+    assert_eq!(
+        Log::new(
+            MessageID::Range,
+            ReceiverStatusFlags::empty(),
+            180,
+            2025,
+            334713000,
+            raw
+        ),
+        None
+    );
+}
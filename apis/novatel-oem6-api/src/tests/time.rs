@@ -0,0 +1,95 @@
+//
+// Copyright (C) 2018 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+use crate::messages::ReceiverStatusFlags;
+
+#[test]
+fn test_request_time() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x0, 0xC0, 0x20, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x20, 0x0, 0x0, 0x0, 0x65, 0x0, 0x0,
+        0x0, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x24, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x48, 0xFD, 0x34, 0x88,
+    ]);
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0xFF, 0x78, 0xD1, 0xB,
+        0x6, 0x67, 0xC9, 0x9, 0x0, 0x0, 0x0, 0x0, 0xFB, 0xFD, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x4F,
+        0x4B, 0x10, 0x9D, 0x33, 0xB1,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    assert_eq!(oem.request_time(10.0, 0.0, false), Ok(()));
+}
+
+#[test]
+fn test_get_time() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(vec![
+        0xAA, 0x44, 0x12, 0x1C, 0x65, 0x0, 0x0, 0x20, 0x2C, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+        0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x0, 0x0, 0x0, 0x0, 0x48,
+        0xAF, 0xBC, 0x9A, 0xF2, 0xD7, 0x5A, 0xBE, 0xDF, 0x41, 0x3A, 0xDC, 0x11, 0xC5, 0x49, 0x3E,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x32, 0xC0, 0xE2, 0x7, 0x0, 0x0, 0xA, 0x1F, 0x14, 0x3A, 0x98,
+        0x3A, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0xC, 0x69, 0x2D, 0x53,
+    ]);
+
+    let oem = mock_new!(mock);
+
+    let expected: Log = Log::Time(TimeLog {
+        recv_status: ReceiverStatusFlags::empty(),
+        time_status: 180,
+        week: 2025,
+        ms: 334713000,
+        clock_status: 0,
+        offset: -2.5e-8,
+        offset_std: 1.2e-8,
+        utc_offset: -18.0,
+        utc_year: 2018,
+        utc_month: 10,
+        utc_day: 31,
+        utc_hour: 20,
+        utc_min: 58,
+        utc_ms: 15000,
+        utc_status: 1,
+    });
+
+    assert_eq!(oem.get_log().unwrap(), expected);
+}
+
+#[test]
+fn test_time_short() {
+    let raw = vec![
+        0x0, 0x0, 0x0, 0x0, 0x48, 0xAF, 0xBC, 0x9A, 0xF2, 0xD7, 0x5A, 0xBE, 0xDF, 0x41, 0x3A, 0xDC,
+        0x11, 0xC5, 0x49, 0x3E, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x32, 0xC0, 0xE2, 0x7,
+    ];
+
+    assert_eq!(
+        Log::new(
+            MessageID::Time,
+            ReceiverStatusFlags::empty(),
+            180,
+            2025,
+            334713000,
+            raw
+        ),
+        None
+    );
+}
@@ -26,5 +26,6 @@ mod serial_comm;
 pub use crate::duplex_d2::DuplexD2;
 pub use crate::messages::File;
 pub use crate::messages::GeoRecord;
+pub use crate::messages::Message;
 pub use crate::messages::StateOfHealth;
 pub use crate::serial_comm::serial_connection;
@@ -24,10 +24,14 @@ use std::str::from_utf8;
 #[derive(Debug, PartialEq)]
 /// Struct for storing geo-records.
 pub struct GeoRecord {
-    lon: f32,
-    lat: f32,
-    time: i64,
-    max_error: u32,
+    /// Longitude, in degrees (negative values are west)
+    pub lon: f32,
+    /// Latitude, in degrees
+    pub lat: f32,
+    /// Time of the estimate, in seconds since the UNIX epoch
+    pub time: i64,
+    /// Maximum error of the estimate, in meters
+    pub max_error: u32,
 }
 
 fn parse_coord(input: &[u8]) -> IResult<&[u8], f32> {
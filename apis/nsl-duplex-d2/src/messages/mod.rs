@@ -24,6 +24,7 @@ mod geo_record;
 mod state_of_health;
 
 pub use crate::messages::file::File;
+/// Messages are transferred in the same format as files
 pub type Message = File;
 pub use crate::messages::geo_record::GeoRecord;
 pub use crate::messages::state_of_health::StateOfHealth;
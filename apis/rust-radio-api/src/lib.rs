@@ -56,7 +56,8 @@ pub type RadioResult<T> = Result<T, Error>;
 pub type ParseFn<T> = fn(input: &[u8]) -> IResult<&[u8], T>;
 
 /// Connections expect a struct instance with this trait to represent streams.
-pub trait Stream {
+/// Streams must be `Send`, so that radios can be shared between threads.
+pub trait Stream: Send {
     /// Write raw bytes to the stream.
     fn write(&self, data: &[u8]) -> RadioResult<()>;
     /// Read raw bytes from the stream.
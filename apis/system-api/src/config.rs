@@ -13,12 +13,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 //
+use failure::Fail;
 use getopts::Options;
+use log::warn;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_derive::Deserialize;
 use std::env;
 use std::fs::File;
 use std::io;
 use std::io::prelude::*;
+use std::net::IpAddr;
 use toml;
 use toml::Value;
 
@@ -29,6 +34,47 @@ pub static DEFAULT_IP: &str = "127.0.0.1";
 /// The default port for service bindings
 pub const DEFAULT_PORT: u16 = 8080;
 
+/// Errors which can be encountered while loading a configuration file
+#[derive(Clone, Debug, Fail, PartialEq)]
+pub enum ConfigError {
+    /// The configuration file could not be read
+    #[fail(display = "Unable to read config file {}: {}", path, err)]
+    FileError {
+        /// Path to the configuration file
+        path: String,
+        /// Underlying error encountered
+        err: String,
+    },
+    /// The configuration data is not valid TOML
+    #[fail(display = "Unable to parse config: {}", err)]
+    ParseError {
+        /// Underlying error encountered
+        err: String,
+    },
+    /// The configuration data has no section for the requested name
+    #[fail(display = "No [{}] section found in config", name)]
+    MissingSection {
+        /// Category name which was requested
+        name: String,
+    },
+    /// The `addr` section is malformed
+    #[fail(display = "Invalid address for {}: {}", name, err)]
+    InvalidAddress {
+        /// Category name which was requested
+        name: String,
+        /// Description of the problem
+        err: String,
+    },
+    /// The section could not be converted into the requested type
+    #[fail(display = "Invalid [{}] section: {}", name, err)]
+    InvalidSection {
+        /// Category name which was requested
+        name: String,
+        /// Underlying error encountered
+        err: String,
+    },
+}
+
 #[derive(Clone, Debug, Deserialize)]
 /// A simple address consisting of an IP address and port number
 pub struct Address {
@@ -77,8 +123,15 @@ impl Address {
 ///
 /// When `addr`, `addr.ip`, or `addr.port` are not provided in the config file, the default IP
 /// `"127.0.0.1"` and default port `8080` are used instead.
+///
+/// The `new*` constructors fall back to the default configuration if the config can't be used.
+/// The `try_new*` constructors instead return a [`ConfigError`] describing what was wrong with it.
+///
+/// [`ConfigError`]: enum.ConfigError.html
 #[derive(Clone, Debug)]
 pub struct Config {
+    name: String,
+    path: Option<String>,
     addr: Address,
     raw: Value,
 }
@@ -86,6 +139,8 @@ pub struct Config {
 impl Default for Config {
     fn default() -> Self {
         Config {
+            name: "".to_string(),
+            path: None,
             addr: Address::default(),
             raw: Value::String("".to_string()),
         }
@@ -109,7 +164,11 @@ impl Config {
     /// `name` - Category name used as a key in the config file
     /// `path` - Path to configuration file
     pub fn new_from_path(name: &str, path: String) -> Self {
-        parse_config_file(name, path).unwrap_or_default()
+        let contents = get_file_data(path.clone()).unwrap_or_else(|_| "".to_string());
+        Config {
+            path: Some(path),
+            ..Self::new_from_str(name, &contents)
+        }
     }
 
     /// Creates and parses configuration data from the passed in configuration
@@ -118,7 +177,86 @@ impl Config {
     /// `name` - Category name used as a key in the config
     /// `config` - Config data as a string
     pub fn new_from_str(name: &str, config: &str) -> Self {
-        parse_config_str(name, config).unwrap_or_default()
+        parse_config_str(name, config).unwrap_or_else(|error| {
+            match error {
+                ConfigError::MissingSection { .. } => {}
+                _ => warn!("{}. Using default config", error),
+            }
+
+            Config {
+                name: name.to_owned(),
+                ..Default::default()
+            }
+        })
+    }
+
+    /// Creates and parses configuration data from the system configuration
+    /// file or the path passed as the '-c' or '--config' option to this
+    /// executable, failing if the configuration can't be used.
+    ///
+    /// # Arguments
+    /// `name` - Category name used as a key in the config file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or parsed, if it has no section
+    /// for `name`, or if the section's `addr` is invalid.
+    pub fn try_new(name: &str) -> Result<Self, ConfigError> {
+        Self::try_new_from_path(name, get_config_path())
+    }
+
+    /// Creates and parses configuration data from the passed in configuration
+    /// path, failing if the configuration can't be used.
+    ///
+    /// # Arguments
+    /// `name` - Category name used as a key in the config file
+    /// `path` - Path to configuration file
+    pub fn try_new_from_path(name: &str, path: String) -> Result<Self, ConfigError> {
+        let contents = get_file_data(path.clone()).map_err(|err| ConfigError::FileError {
+            path: path.clone(),
+            err: err.to_string(),
+        })?;
+        Ok(Config {
+            path: Some(path),
+            ..Self::try_new_from_str(name, &contents)?
+        })
+    }
+
+    /// Creates and parses configuration data from the passed in configuration
+    /// string, failing if the configuration can't be used.
+    ///
+    /// # Arguments
+    /// `name` - Category name used as a key in the config
+    /// `config` - Config data as a string
+    ///
+    /// ### Examples
+    ///
+    /// ```rust
+    /// use kubos_system::{Config, ConfigError};
+    ///
+    /// let config = Config::try_new_from_str("my-service", "[my-service.addr]\nport = 8181");
+    /// assert_eq!(config.unwrap().hosturl(), "127.0.0.1:8181");
+    ///
+    /// let config = Config::try_new_from_str("my-service", "[my-srevice.addr]\nport = 8181");
+    /// assert_eq!(
+    ///     config.unwrap_err(),
+    ///     ConfigError::MissingSection { name: "my-service".to_owned() }
+    /// );
+    /// ```
+    pub fn try_new_from_str(name: &str, config: &str) -> Result<Self, ConfigError> {
+        let config = parse_config_str(name, config)?;
+        validate_address(&config)?;
+        Ok(config)
+    }
+
+    /// Returns the category name used to look up this configuration
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the path of the file this configuration was loaded from, if any
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_ref().map(|path| path.as_str())
     }
 
     /// Returns the configured hosturl string in the following
@@ -155,6 +293,120 @@ impl Config {
             None => None,
         }
     }
+
+    /// Deserializes the category's configuration into a user-defined structure.
+    ///
+    /// The `addr` table is handled by `Config` itself, so it is ignored here.
+    /// Any other keys which aren't used by the structure are logged as warnings,
+    /// since they are usually typos. Use [`unknown_keys`] to fetch them instead.
+    ///
+    /// [`unknown_keys`]: #method.unknown_keys
+    ///
+    /// ### Examples
+    ///
+    /// ```rust
+    /// use kubos_system::Config;
+    /// use serde_derive::{Deserialize, Serialize};
+    ///
+    /// #[derive(Deserialize, Serialize)]
+    /// struct MyConfig {
+    ///     bus: String,
+    ///     timeout: Option<u32>,
+    /// }
+    ///
+    /// let config = Config::new_from_str("my-service", "[my-service]\nbus = \"/dev/ttyS1\"");
+    /// let section: MyConfig = config.section().unwrap();
+    /// assert_eq!(section.bus, "/dev/ttyS1");
+    /// ```
+    pub fn section<T>(&self) -> Result<T, ConfigError>
+    where
+        T: DeserializeOwned + Serialize,
+    {
+        let (section, unknown) = self.parse_section()?;
+        for key in unknown {
+            warn!("Unknown key in [{}] config: {}", self.name, key);
+        }
+        Ok(section)
+    }
+
+    /// Returns the keys in the category's configuration which aren't used by the
+    /// given structure, as dotted paths (e.g. `limits.max-temp`).
+    ///
+    /// Keys are compared against the structure's serialized form, so fields which
+    /// are skipped during serialization are also reported.
+    pub fn unknown_keys<T>(&self) -> Result<Vec<String>, ConfigError>
+    where
+        T: DeserializeOwned + Serialize,
+    {
+        self.parse_section::<T>().map(|(_, unknown)| unknown)
+    }
+
+    fn parse_section<T>(&self) -> Result<(T, Vec<String>), ConfigError>
+    where
+        T: DeserializeOwned + Serialize,
+    {
+        let invalid = |err: String| ConfigError::InvalidSection {
+            name: self.name.clone(),
+            err,
+        };
+
+        let mut raw = match self.raw {
+            Value::Table(ref table) => table.clone(),
+            _ => toml::value::Table::new(),
+        };
+        raw.remove("addr");
+        let raw = Value::Table(raw);
+
+        let section: T = raw.clone().try_into().map_err(|err| invalid(format!("{}", err)))?;
+        let known = Value::try_from(&section).map_err(|err| invalid(format!("{}", err)))?;
+
+        let mut unknown = vec![];
+        find_unknown_keys("", &raw, &known, &mut unknown);
+
+        Ok((section, unknown))
+    }
+}
+
+// Collect the keys of `raw` which don't appear in `known`
+fn find_unknown_keys(prefix: &str, raw: &Value, known: &Value, unknown: &mut Vec<String>) {
+    if let (Value::Table(raw), Value::Table(known)) = (raw, known) {
+        for (key, value) in raw.iter() {
+            let path = format!("{}{}", prefix, key);
+            match known.get(key) {
+                Some(known_value) => {
+                    find_unknown_keys(&format!("{}.", path), value, known_value, unknown)
+                }
+                None => unknown.push(path),
+            }
+        }
+    }
+}
+
+// Make sure the `addr` section describes a usable address
+fn validate_address(config: &Config) -> Result<(), ConfigError> {
+    let invalid = |err: String| ConfigError::InvalidAddress {
+        name: config.name.clone(),
+        err,
+    };
+
+    if let Some(addr) = config.raw.get("addr") {
+        let table = addr
+            .as_table()
+            .ok_or_else(|| invalid("addr must be a table".to_owned()))?;
+        for key in table.keys() {
+            if key != "ip" && key != "port" {
+                return Err(invalid(format!("unknown key {}", key)));
+            }
+        }
+    }
+
+    config
+        .addr
+        .ip()
+        .parse::<IpAddr>()
+        .map_err(|_| invalid(format!("{} is not an IP address", config.addr.ip())))?;
+
+    Ok(())
 }
 
 fn get_config_path() -> String {
@@ -182,21 +434,28 @@ fn get_file_data(path: String) -> Result<String, io::Error> {
     Ok(contents)
 }
 
-fn parse_config_file(name: &str, path: String) -> Result<Config, toml::de::Error> {
-    let contents = get_file_data(path).unwrap_or_else(|_| "".to_string());
-    parse_config_str(name, &contents)
-}
-
-fn parse_config_str(name: &str, contents: &str) -> Result<Config, toml::de::Error> {
-    let data: Value = toml::from_str(&contents)?;
-    let mut config = Config::default();
+fn parse_config_str(name: &str, contents: &str) -> Result<Config, ConfigError> {
+    let data: Value = toml::from_str(contents).map_err(|err| ConfigError::ParseError {
+        err: err.to_string(),
+    })?;
+    let mut config = Config {
+        name: name.to_owned(),
+        ..Default::default()
+    };
 
-    if let Some(data) = data.get(name) {
-        if let Some(address) = data.get("addr") {
-            config.addr = address.clone().try_into()?;
-        }
-        config.raw = data.clone();
+    let data = data.get(name).ok_or_else(|| ConfigError::MissingSection {
+        name: name.to_owned(),
+    })?;
+    if let Some(address) = data.get("addr") {
+        config.addr = address
+            .clone()
+            .try_into()
+            .map_err(|err| ConfigError::InvalidAddress {
+                name: name.to_owned(),
+                err: format!("{}", err),
+            })?;
     }
+    config.raw = data.clone();
 
     Ok(config)
 }
@@ -22,7 +22,7 @@ mod config;
 mod uboot;
 
 pub use crate::config::*;
-pub use crate::uboot::UBootVars;
+pub use crate::uboot::*;
 
 /// The name of the KubOS app service that can be used to derive service configuration
 pub const SERVICE_APP: &str = "app-service";
@@ -14,32 +14,132 @@
  * limitations under the License.
  */
 
-use failure::{format_err, Error};
-use std::process::Command;
+use failure::{bail, format_err, Error};
+use std::io::Write;
+use std::process::{Command, Stdio};
 use std::str::FromStr;
 
+/// The name of the kpack file that the current KubOS kernel and rootfs were loaded from
 pub const VAR_KUBOS_CURR_VERSION: &str = "kubos_curr_version";
+/// The name of the kpack file that the previous KubOS kernel and rootfs were loaded from
 pub const VAR_KUBOS_PREV_VERSION: &str = "kubos_prev_version";
+/// Whether the system has been marked as deployed
 pub const VAR_KUBOS_INITIAL_DEPLOY: &str = "kubos_initial_deploy";
+/// The name of the kpack file which should be installed at the next boot
+pub const VAR_KUBOS_UPDATE_FILE: &str = "kubos_updatefile";
+/// Whether reloading the current version has already been attempted
+pub const VAR_KUBOS_CURR_TRIED: &str = "kubos_curr_tried";
+/// The number of boots which have been attempted since the last successful boot
+pub const VAR_BOOT_COUNT: &str = "bootcount";
+/// The number of failed boots allowed before the alternate boot commands are used
+pub const VAR_BOOT_LIMIT: &str = "bootlimit";
+/// Whether recovery actions should be taken if the system fails to boot
+pub const VAR_RECOVERY_AVAILABLE: &str = "recovery_available";
 
 const PRINTENV_PATH: &str = "/usr/sbin/fw_printenv";
+const SETENV_PATH: &str = "/usr/sbin/fw_setenv";
 
-/// A convenience wrapper for fetching UBoot variables used by KubOS
-#[derive(Default)]
+/// A set of UBoot variable changes which should be written together
+///
+/// The changes are applied with a single `fw_setenv` call, so either all of them
+/// take effect or none of them do.
+///
+/// # Examples
+///
+/// ```no_run
+/// use kubos_system::{UBootUpdate, UBootVars};
+///
+/// let update = UBootUpdate::new()
+///     .set_str("kubos_updatefile", "kpack-2019.02.01.itb")
+///     .set_u32("bootcount", 0);
+///
+/// UBootVars::new().apply(&update).unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct UBootUpdate {
+    changes: Vec<(String, Option<String>)>,
+}
+
+impl UBootUpdate {
+    /// Create an empty set of changes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a UBoot variable to a string value
+    pub fn set_str(mut self, name: &str, value: &str) -> Self {
+        self.changes.push((name.to_owned(), Some(value.to_owned())));
+        self
+    }
+
+    /// Set a UBoot variable to a u32 value
+    pub fn set_u32(self, name: &str, value: u32) -> Self {
+        self.set_str(name, &value.to_string())
+    }
+
+    /// Set a UBoot variable to a bool value, encoded as `1` or `0`
+    pub fn set_bool(self, name: &str, value: bool) -> Self {
+        self.set_str(name, if value { "1" } else { "0" })
+    }
+
+    /// Remove a UBoot variable
+    pub fn unset(mut self, name: &str) -> Self {
+        self.changes.push((name.to_owned(), None));
+        self
+    }
+
+    /// Whether there are no changes to apply
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    // Convert the changes into an `fw_setenv` script: one variable per line,
+    // with a missing value meaning that the variable should be removed
+    fn script(&self) -> Result<String, Error> {
+        let mut script = String::new();
+        for (name, value) in self.changes.iter() {
+            if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == '=') {
+                bail!("Invalid variable name: {:?}", name);
+            }
+
+            match value {
+                Some(value) if value.contains('\n') => {
+                    bail!("Invalid value for {}: {:?}", name, value)
+                }
+                Some(value) => script.push_str(&format!("{} {}\n", name, value)),
+                None => script.push_str(&format!("{}\n", name)),
+            }
+        }
+
+        Ok(script)
+    }
+}
+
+/// A convenience wrapper for fetching and updating UBoot variables used by KubOS
+#[derive(Clone, Debug, Default)]
 pub struct UBootVars {
     cmd_path: String,
+    setenv_path: String,
 }
 
 impl UBootVars {
     /// Default constructor that fetches UBoot vars using `/usr/sbin/fw_printenv`
+    /// and updates them using `/usr/sbin/fw_setenv`
     pub fn new() -> Self {
-        Self::new_from_path(PRINTENV_PATH)
+        Self::new_from_paths(PRINTENV_PATH, SETENV_PATH)
     }
 
     /// Constructor that fetches UBoot vars with a custom path to `fw_printenv`
     pub fn new_from_path(path: &str) -> Self {
+        Self::new_from_paths(path, SETENV_PATH)
+    }
+
+    /// Constructor that fetches and updates UBoot vars with custom paths to
+    /// `fw_printenv` and `fw_setenv`
+    pub fn new_from_paths(printenv_path: &str, setenv_path: &str) -> Self {
         Self {
-            cmd_path: String::from(path),
+            cmd_path: String::from(printenv_path),
+            setenv_path: String::from(setenv_path),
         }
     }
 
@@ -85,4 +185,142 @@ impl UBootVars {
             Err(_) => None,
         }
     }
+
+    /// Write a set of changes to the UBoot environment in a single update
+    pub fn apply(&self, update: &UBootUpdate) -> Result<(), Error> {
+        if update.is_empty() {
+            return Ok(());
+        }
+
+        let script = update.script()?;
+
+        let mut child = Command::new(&self.setenv_path)
+            .args(&["-s", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|_| format_err!("Failed to execute: {}", self.setenv_path))?;
+
+        if let Some(stdin) = child.stdin.as_mut() {
+            stdin.write_all(script.as_bytes()).map_err(|err| {
+                format_err!("Failed to send variables to {}: {}", self.setenv_path, err)
+            })?;
+        }
+
+        let output = child
+            .wait_with_output()
+            .map_err(|_| format_err!("Failed to execute: {}", self.setenv_path))?;
+
+        if !output.status.success() {
+            bail!(
+                "Failed to update vars: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Sets the value of a UBoot variable to a u32
+    pub fn set_u32(&self, name: &str, value: u32) -> Result<(), Error> {
+        self.apply(&UBootUpdate::new().set_u32(name, value))
+    }
+
+    /// Sets the value of a UBoot variable to a String
+    pub fn set_str(&self, name: &str, value: &str) -> Result<(), Error> {
+        self.apply(&UBootUpdate::new().set_str(name, value))
+    }
+
+    /// Sets the value of a UBoot variable to a bool, encoded as `1` or `0`
+    pub fn set_bool(&self, name: &str, value: bool) -> Result<(), Error> {
+        self.apply(&UBootUpdate::new().set_bool(name, value))
+    }
+
+    /// Removes a UBoot variable
+    pub fn unset(&self, name: &str) -> Result<(), Error> {
+        self.apply(&UBootUpdate::new().unset(name))
+    }
+
+    /// Returns the number of boots which have been attempted since the last successful boot
+    pub fn boot_count(&self) -> Option<u32> {
+        self.get_u32(VAR_BOOT_COUNT)
+    }
+
+    /// Resets the boot count, marking the current boot as successful
+    pub fn reset_boot_count(&self) -> Result<(), Error> {
+        self.set_u32(VAR_BOOT_COUNT, 0)
+    }
+
+    /// Increments the boot count, returning the new value.
+    /// A missing boot count is treated as zero
+    pub fn increment_boot_count(&self) -> Result<u32, Error> {
+        let count = self.boot_count().unwrap_or(0).saturating_add(1);
+        self.set_u32(VAR_BOOT_COUNT, count)?;
+        Ok(count)
+    }
+
+    /// Returns the number of failed boots allowed before the alternate boot commands are used
+    pub fn boot_limit(&self) -> Option<u32> {
+        self.get_u32(VAR_BOOT_LIMIT)
+    }
+
+    /// Sets the number of failed boots allowed before the alternate boot commands are used
+    pub fn set_boot_limit(&self, limit: u32) -> Result<(), Error> {
+        self.set_u32(VAR_BOOT_LIMIT, limit)
+    }
+
+    /// Marks the system as deployed (or not)
+    pub fn set_initial_deploy(&self, deployed: bool) -> Result<(), Error> {
+        self.set_bool(VAR_KUBOS_INITIAL_DEPLOY, deployed)
+    }
+
+    /// Returns the kpack file which will be installed at the next boot, if any
+    pub fn next_boot(&self) -> Option<String> {
+        self.get_str(VAR_KUBOS_UPDATE_FILE).and_then(|file| {
+            if file.is_empty() {
+                None
+            } else {
+                Some(file)
+            }
+        })
+    }
+
+    /// Selects the kpack file to install at the next boot, resetting the
+    /// boot count so that the new image gets a full set of boot attempts
+    pub fn set_next_boot(&self, kpack: &str) -> Result<(), Error> {
+        self.apply(
+            &UBootUpdate::new()
+                .set_str(VAR_KUBOS_UPDATE_FILE, kpack)
+                .set_u32(VAR_BOOT_COUNT, 0),
+        )
+    }
+
+    /// Cancels a previously selected kpack file, so that the current image
+    /// keeps being used
+    pub fn clear_next_boot(&self) -> Result<(), Error> {
+        self.unset(VAR_KUBOS_UPDATE_FILE)
+    }
+
+    /// Returns the alternate image: the previous version of KubOS,
+    /// which the system can be rolled back to
+    pub fn alternate_version(&self) -> Option<String> {
+        self.get_str(VAR_KUBOS_PREV_VERSION).and_then(|file| {
+            if file.is_empty() {
+                None
+            } else {
+                Some(file)
+            }
+        })
+    }
+
+    /// Selects the alternate (previous) version of KubOS to be installed
+    /// at the next boot, returning its name
+    pub fn rollback(&self) -> Result<String, Error> {
+        let prev = self
+            .alternate_version()
+            .ok_or_else(|| format_err!("No previous version to roll back to"))?;
+        self.set_next_boot(&prev)?;
+        Ok(prev)
+    }
 }
@@ -20,7 +20,8 @@ use std::fs;
 use std::io::Write;
 use std::os::unix::fs::PermissionsExt;
 
-use kubos_system::UBootVars;
+use kubos_system::{UBootUpdate, UBootVars};
+use tempfile::TempDir;
 
 const DUMMY_PRINTENV: &'static str = r#"#!/bin/bash
 VAR="$2"
@@ -28,6 +29,54 @@ VAR="$2"
 echo ${!VAR}
 "#;
 
+// Fake printenv/setenv pair which keep their variables in a `name=value` file.
+// The setenv script only accepts `-s -` scripts, and refuses to change anything
+// if any of the variables is named `readonly`
+const FAKE_PRINTENV: &'static str = r#"#!/bin/bash
+ENV_FILE="$(dirname "$0")/env"
+LINE=$(grep "^$2=" "$ENV_FILE" 2>/dev/null) || exit 1
+echo "${LINE#*=}"
+"#;
+
+const FAKE_SETENV: &'static str = r#"#!/bin/bash
+ENV_FILE="$(dirname "$0")/env"
+[[ "$1" == "-s" && "$2" == "-" ]] || exit 1
+touch "$ENV_FILE"
+cp "$ENV_FILE" "$ENV_FILE.new"
+while read -r NAME VALUE; do
+    if [[ "$NAME" == "readonly" ]]; then
+        echo "Can't overwrite readonly" >&2
+        rm "$ENV_FILE.new"
+        exit 1
+    fi
+    grep -v "^$NAME=" "$ENV_FILE.new" > "$ENV_FILE.tmp"
+    [[ -n "$VALUE" ]] && echo "$NAME=$VALUE" >> "$ENV_FILE.tmp"
+    mv "$ENV_FILE.tmp" "$ENV_FILE.new"
+done
+mv "$ENV_FILE.new" "$ENV_FILE"
+"#;
+
+fn write_script(dir: &TempDir, name: &str, contents: &str) -> String {
+    let path = dir.path().join(name);
+
+    let mut file = fs::File::create(&path).unwrap();
+    file.write_all(contents.as_bytes())
+        .expect("Failed to write fake script");
+
+    let mut perms = file.metadata().unwrap().permissions();
+    perms.set_mode(0o755);
+    file.set_permissions(perms)
+        .expect("Failed to change file permissions");
+
+    path.to_str().unwrap().to_owned()
+}
+
+fn setup_fake_vars(dir: &TempDir) -> UBootVars {
+    let printenv = write_script(dir, "fw_printenv", FAKE_PRINTENV);
+    let setenv = write_script(dir, "fw_setenv", FAKE_SETENV);
+    UBootVars::new_from_paths(&printenv, &setenv)
+}
+
 fn setup_dummy_vars() -> UBootVars {
     let mut bin_dest = env::temp_dir();
     bin_dest.push("dummy-printenv");
@@ -85,3 +134,111 @@ fn str_vars() {
     env::set_var("currv", "");
     assert_eq!(vars.get_str("currv"), Some(String::from("")));
 }
+
+#[test]
+fn set_vars() {
+    let dir = TempDir::new().unwrap();
+    let vars = setup_fake_vars(&dir);
+
+    vars.set_u32("count", 5).unwrap();
+    assert_eq!(vars.get_u32("count"), Some(5));
+
+    vars.set_str("name", "kpack-2019.02.01.itb").unwrap();
+    assert_eq!(
+        vars.get_str("name"),
+        Some(String::from("kpack-2019.02.01.itb"))
+    );
+
+    vars.set_bool("flag", true).unwrap();
+    assert_eq!(vars.get_bool("flag"), Some(true));
+    vars.set_bool("flag", false).unwrap();
+    assert_eq!(vars.get_bool("flag"), Some(false));
+
+    vars.unset("flag").unwrap();
+    assert_eq!(vars.get_bool("flag"), None);
+
+    // Values which can't be passed to fw_setenv are refused
+    assert!(vars.set_str("bad name", "value").is_err());
+    assert!(vars.set_str("name", "two\nlines").is_err());
+}
+
+#[test]
+fn atomic_update() {
+    let dir = TempDir::new().unwrap();
+    let vars = setup_fake_vars(&dir);
+
+    vars.apply(
+        &UBootUpdate::new()
+            .set_u32("first", 1)
+            .set_str("second", "two")
+            .set_bool("third", true),
+    )
+    .unwrap();
+
+    assert_eq!(vars.get_u32("first"), Some(1));
+    assert_eq!(vars.get_str("second"), Some(String::from("two")));
+    assert_eq!(vars.get_bool("third"), Some(true));
+
+    // A failed update leaves every variable untouched
+    let result = vars.apply(
+        &UBootUpdate::new()
+            .set_u32("first", 10)
+            .set_str("readonly", "value")
+            .unset("second"),
+    );
+    assert!(result.is_err());
+
+    assert_eq!(vars.get_u32("first"), Some(1));
+    assert_eq!(vars.get_str("second"), Some(String::from("two")));
+
+    // Empty updates don't need to run fw_setenv at all
+    let broken = UBootVars::new_from_paths("/nonexistent/printenv", "/nonexistent/setenv");
+    assert!(broken.apply(&UBootUpdate::new()).is_ok());
+    assert!(broken.set_u32("first", 1).is_err());
+}
+
+#[test]
+fn boot_count_vars() {
+    let dir = TempDir::new().unwrap();
+    let vars = setup_fake_vars(&dir);
+
+    assert_eq!(vars.boot_count(), None);
+    assert_eq!(vars.increment_boot_count().unwrap(), 1);
+    assert_eq!(vars.increment_boot_count().unwrap(), 2);
+    assert_eq!(vars.boot_count(), Some(2));
+
+    vars.reset_boot_count().unwrap();
+    assert_eq!(vars.boot_count(), Some(0));
+
+    vars.set_boot_limit(3).unwrap();
+    assert_eq!(vars.boot_limit(), Some(3));
+
+    vars.set_initial_deploy(true).unwrap();
+    assert_eq!(vars.get_bool("kubos_initial_deploy"), Some(true));
+}
+
+#[test]
+fn next_boot_vars() {
+    let dir = TempDir::new().unwrap();
+    let vars = setup_fake_vars(&dir);
+
+    assert_eq!(vars.next_boot(), None);
+    assert!(vars.rollback().is_err());
+
+    vars.set_u32("bootcount", 2).unwrap();
+    vars.set_next_boot("kpack-upgrade2.itb").unwrap();
+    assert_eq!(vars.next_boot(), Some(String::from("kpack-upgrade2.itb")));
+    assert_eq!(vars.boot_count(), Some(0));
+
+    vars.clear_next_boot().unwrap();
+    assert_eq!(vars.next_boot(), None);
+
+    vars.set_str("kubos_prev_version", "kpack-upgrade1.itb")
+        .unwrap();
+    assert_eq!(
+        vars.alternate_version(),
+        Some(String::from("kpack-upgrade1.itb"))
+    );
+    assert_eq!(vars.rollback().unwrap(), "kpack-upgrade1.itb");
+    assert_eq!(vars.next_boot(), Some(String::from("kpack-upgrade1.itb")));
+}
@@ -15,8 +15,10 @@
  */
 #![deny(warnings)]
 
+use kubos_system::ConfigError;
+use serde_derive::{Deserialize, Serialize};
 use std::io::Write;
-use tempfile::NamedTempFile;
+use tempfile::{NamedTempFile, TempDir};
 use toml::Value;
 
 #[test]
@@ -93,6 +95,11 @@ fn new_from_file() {
     assert_eq!(config.get("b"), Some(Value::Integer(2)));
     assert_eq!(config.hosturl(), "1.2.3.4:1234");
     assert_eq!(config.get("root-a"), None);
+    assert_eq!(config.name(), "category-1");
+    assert_eq!(
+        config.path(),
+        Some(file.path().to_string_lossy().as_ref())
+    );
 }
 
 #[test]
@@ -165,3 +172,145 @@ fn only_category_config() {
     assert_eq!(config.get("c"), None);
     assert_eq!(config.get("d"), None);
 }
+
+#[test]
+fn try_new_from_str() {
+    let config = kubos_system::Config::try_new_from_str(
+        "category-1",
+        r#"
+    [category-1]
+    a = 1
+    [category-1.addr]
+    ip = "10.0.1.1"
+    port = 9876
+    "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.get("a"), Some(Value::Integer(1)));
+    assert_eq!(config.hosturl(), "10.0.1.1:9876");
+}
+
+#[test]
+fn try_new_parse_error() {
+    let result = kubos_system::Config::try_new_from_str(
+        "category-1",
+        r#"
+    [category-1]
+    a = 
+    "#,
+    );
+
+    match result {
+        Err(ConfigError::ParseError { .. }) => {}
+        other => panic!("Unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn try_new_missing_section() {
+    let result = kubos_system::Config::try_new_from_str(
+        "category-1",
+        r#"
+    [categroy-1]
+    a = 1
+    "#,
+    );
+
+    assert_eq!(
+        result.unwrap_err(),
+        ConfigError::MissingSection {
+            name: "category-1".to_owned()
+        }
+    );
+}
+
+#[test]
+fn try_new_bad_address() {
+    for addr in &[
+        r#"ip = "localhost""#,
+        r#"port = "8080""#,
+        r#"port = 100000"#,
+        r#"prot = 8080"#,
+    ] {
+        let config = format!("[category-1.addr]\n{}", addr);
+        match kubos_system::Config::try_new_from_str("category-1", &config) {
+            Err(ConfigError::InvalidAddress { ref name, .. }) if name == "category-1" => {}
+            other => panic!("Unexpected result for {}: {:?}", addr, other),
+        }
+    }
+}
+
+#[test]
+fn try_new_missing_file() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("config.toml").to_string_lossy().to_string();
+
+    match kubos_system::Config::try_new_from_path("category-1", path.clone()) {
+        Err(ConfigError::FileError { path: ref err_path, .. }) if *err_path == path => {}
+        other => panic!("Unexpected result: {:?}", other),
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Limits {
+    min: i32,
+    max: i32,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Section {
+    bus: String,
+    timeout: Option<u32>,
+    limits: Limits,
+}
+
+#[test]
+fn typed_section() {
+    let config = kubos_system::Config::try_new_from_str(
+        "category-1",
+        r#"
+    [category-1]
+    bus = "/dev/ttyS1"
+    buss = "/dev/ttyS2"
+    [category-1.limits]
+    min = -5
+    max = 5
+    mid = 0
+    [category-1.addr]
+    port = 9876
+    "#,
+    )
+    .unwrap();
+
+    let section: Section = config.section().unwrap();
+    assert_eq!(
+        section,
+        Section {
+            bus: "/dev/ttyS1".to_owned(),
+            timeout: None,
+            limits: Limits { min: -5, max: 5 },
+        }
+    );
+
+    assert_eq!(
+        config.unknown_keys::<Section>().unwrap(),
+        vec!["buss".to_owned(), "limits.mid".to_owned()]
+    );
+}
+
+#[test]
+fn typed_section_invalid() {
+    let config = kubos_system::Config::new_from_str(
+        "category-1",
+        r#"
+    [category-1]
+    bus = 1
+    "#,
+    );
+
+    match config.section::<Section>() {
+        Err(ConfigError::InvalidSection { ref name, .. }) if name == "category-1" => {}
+        other => panic!("Unexpected result: {:?}", other),
+    }
+}
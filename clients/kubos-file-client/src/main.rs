@@ -1,21 +1,59 @@
 use clap::{App, AppSettings, Arg, SubCommand};
 use failure::bail;
-use file_protocol::{FileProtocol, FileProtocolConfig, State};
+use file_protocol::{
+    parse_message, FileProtocol, FileProtocolConfig, Message, ProtocolError, QueueEntry, State,
+    TransferState, TransferStatus,
+};
 use log::{error, info};
 use simplelog::*;
 use std::path::Path;
 use std::time::Duration;
 
+// Width of the progress bar, in characters
+const PROGRESS_WIDTH: usize = 40;
+
+// Format a transfer rate for display
+fn format_rate(bytes_per_sec: u64) -> String {
+    if bytes_per_sec >= 1024 * 1024 {
+        format!("{:.1} MiB/s", bytes_per_sec as f64 / (1024.0 * 1024.0))
+    } else if bytes_per_sec >= 1024 {
+        format!("{:.1} KiB/s", bytes_per_sec as f64 / 1024.0)
+    } else {
+        format!("{} B/s", bytes_per_sec)
+    }
+}
+
+// Redraw the progress bar for the current transfer
+fn show_progress(status: &TransferStatus) {
+    let filled = if status.chunks_total > 0 {
+        PROGRESS_WIDTH * status.chunks_done as usize / status.chunks_total as usize
+    } else {
+        0
+    };
+
+    eprint!(
+        "\r[{}{}] {}/{} chunks {:>12} {:<12}",
+        "#".repeat(filled),
+        " ".repeat(PROGRESS_WIDTH - filled),
+        status.chunks_done,
+        status.chunks_total,
+        format_rate(status.bytes_per_sec),
+        status.state
+    );
+
+    if status.state == TransferState::Done || status.state == TransferState::Failed {
+        eprintln!();
+    }
+}
+
 fn upload(
     host_ip: &str,
     remote_addr: &str,
     source_path: &str,
     target_path: &str,
-    prefix: Option<String>,
-    chunk_size: usize,
-    hold_count: u16,
+    f_config: FileProtocolConfig,
+    recursive: bool,
 ) -> Result<(), failure::Error> {
-    let f_config = FileProtocolConfig::new(prefix, chunk_size, hold_count);
     let f_protocol = FileProtocol::new(host_ip, remote_addr, f_config);
 
     info!(
@@ -23,8 +61,12 @@ fn upload(
         &source_path, &target_path
     );
 
-    // Copy file to upload to temp storage. Calculate the hash and chunk info
-    let (hash, num_chunks, mode) = f_protocol.initialize_file(&source_path)?;
+    // Copy file (or directory contents) to upload to temp storage. Calculate the hash and chunk info
+    let (hash, num_chunks, mode) = if recursive {
+        f_protocol.initialize_directory(&source_path)?
+    } else {
+        f_protocol.initialize_file(&source_path)?
+    };
 
     // Generate channel id for transaction
     let channel = f_protocol.generate_channel()?;
@@ -33,7 +75,11 @@ fn upload(
     f_protocol.send_metadata(channel, &hash, num_chunks)?;
 
     // Send export command for file
-    f_protocol.send_export(channel, &hash, &target_path, mode)?;
+    if recursive {
+        f_protocol.send_export_dir(channel, &hash, &target_path, mode)?;
+    } else {
+        f_protocol.send_export(channel, &hash, &target_path, mode)?;
+    }
 
     // Start the engine to send the file data chunks
     f_protocol.message_engine(
@@ -49,11 +95,9 @@ fn download(
     remote_addr: &str,
     source_path: &str,
     target_path: &str,
-    prefix: Option<String>,
-    chunk_size: usize,
-    hold_count: u16,
+    f_config: FileProtocolConfig,
+    recursive: bool,
 ) -> Result<(), failure::Error> {
-    let f_config = FileProtocolConfig::new(prefix, chunk_size, hold_count);
     let f_protocol = FileProtocol::new(host_ip, remote_addr, f_config);
 
     info!(
@@ -66,7 +110,11 @@ fn download(
 
     // Send our file request to the remote addr and verify that it's
     // going to be able to send it
-    f_protocol.send_import(channel, source_path)?;
+    if recursive {
+        f_protocol.send_import_dir(channel, source_path)?;
+    } else {
+        f_protocol.send_import(channel, source_path)?;
+    }
 
     // Wait for the request reply.
     // Note/TODO: We don't use a timeout here because we don't know how long it will
@@ -77,12 +125,17 @@ fn download(
         Err(error) => bail!("Failed to import file: {}", error),
     };
 
-    let state = f_protocol.process_message(
-        reply,
-        &State::StartReceive {
+    let start_state = if recursive {
+        State::StartReceiveDir {
             path: target_path.to_string(),
-        },
-    )?;
+        }
+    } else {
+        State::StartReceive {
+            path: target_path.to_string(),
+        }
+    };
+
+    let state = f_protocol.process_message(reply, &start_state)?;
 
     f_protocol.message_engine(|d| f_protocol.recv(Some(d)), Duration::from_secs(2), &state)?;
     Ok(())
@@ -92,16 +145,13 @@ fn cleanup(
     host_ip: &str,
     remote_addr: &str,
     hash: Option<String>,
-    prefix: Option<String>,
-    chunk_size: usize,
-    hold_count: u16,
+    f_config: FileProtocolConfig,
 ) -> Result<(), failure::Error> {
     match &hash {
         Some(s) => info!("Requesting remote cleanup of temp storage for hash {}", s),
         None => info!("Requesting remote cleanup of all temp storage"),
     }
 
-    let f_config = FileProtocolConfig::new(prefix, chunk_size, hold_count);
     let f_protocol = FileProtocol::new(host_ip, remote_addr, f_config);
 
     // Generate channel ID for transaction
@@ -114,14 +164,139 @@ fn cleanup(
     Ok(())
 }
 
-fn main() {
-    CombinedLogger::init(vec![
-        TermLogger::new(LevelFilter::Info, Config::default()).unwrap()
-    ])
-    .unwrap();
+fn status(
+    host_ip: &str,
+    remote_addr: &str,
+    f_config: FileProtocolConfig,
+) -> Result<(), failure::Error> {
+    info!("Requesting remote transfer status");
 
-    info!("Starting file transfer client");
+    let f_protocol = FileProtocol::new(host_ip, remote_addr, f_config);
+
+    // Generate channel ID for transaction
+    let channel = f_protocol.generate_channel()?;
 
+    f_protocol.send_status_request(channel)?;
+
+    let transfers = match parse_message(f_protocol.recv(Some(Duration::from_secs(2)))?)? {
+        Message::Status(_, transfers) => transfers,
+        Message::Failure(_, error) => bail!("Failed to get status: {}", error),
+        _ => bail!("Unexpected reply to status request"),
+    };
+
+    if transfers.is_empty() {
+        info!("No active transfers");
+    }
+
+    for transfer in transfers.iter() {
+        info!(
+            "{} {} {} {}/{} chunks {} {} {}",
+            transfer.channel_id,
+            transfer.direction,
+            transfer.state,
+            transfer.chunks_done,
+            transfer.chunks_total,
+            format_rate(transfer.bytes_per_sec),
+            transfer.hash,
+            transfer.path
+        );
+    }
+
+    Ok(())
+}
+
+// Send a downlink queue operation and display the resulting queue
+fn queue<F>(
+    host_ip: &str,
+    remote_addr: &str,
+    f_config: FileProtocolConfig,
+    request: F,
+) -> Result<(), failure::Error>
+where
+    F: Fn(&FileProtocol, u32) -> Result<(), ProtocolError>,
+{
+    let f_protocol = FileProtocol::new(host_ip, remote_addr, f_config);
+
+    // Generate channel ID for transaction
+    let channel = f_protocol.generate_channel()?;
+
+    request(&f_protocol, channel)?;
+
+    let entries: Vec<QueueEntry> =
+        match parse_message(f_protocol.recv(Some(Duration::from_secs(2)))?)? {
+            Message::Queue(_, entries) => entries,
+            Message::Failure(_, error) => bail!("Queue request failed: {}", error),
+            _ => bail!("Unexpected reply to queue request"),
+        };
+
+    if entries.is_empty() {
+        info!("Downlink queue is empty");
+    }
+
+    for entry in entries.iter() {
+        match entry.expires {
+            Some(expires) => info!(
+                "{} priority {} expires {} {}",
+                entry.id, entry.priority, expires, entry.path
+            ),
+            None => info!("{} priority {} {}", entry.id, entry.priority, entry.path),
+        }
+    }
+
+    Ok(())
+}
+
+// Download every file in the remote target's downlink queue, in priority order
+fn downlink(
+    host_ip: &str,
+    remote_addr: &str,
+    target_dir: &str,
+    f_config: FileProtocolConfig,
+) -> Result<(), failure::Error> {
+    loop {
+        let f_protocol = FileProtocol::new(host_ip, remote_addr, f_config.clone());
+
+        // Generate channel id for transaction
+        let channel = f_protocol.generate_channel()?;
+
+        f_protocol.send_downlink_request(channel)?;
+
+        // Wait for the request reply. As with a download, there's no timeout
+        // since the file may take a while to prepare
+        let reply = f_protocol.recv(None)?;
+
+        let (id, path) = match parse_message(reply.clone())? {
+            Message::Downlink(_, id, path, ..) => (id, path),
+            Message::Failure(_, ref error) if error == "Downlink queue is empty" => {
+                info!("Downlink queue is empty");
+                return Ok(());
+            }
+            Message::Failure(_, error) => bail!("Failed to downlink file: {}", error),
+            _ => bail!("Unexpected reply to downlink request"),
+        };
+
+        let file_name = match Path::new(&path).file_name() {
+            Some(name) => name.to_owned(),
+            None => bail!("Invalid downlink path {}", path),
+        };
+        let target_path = Path::new(target_dir)
+            .join(file_name)
+            .to_string_lossy()
+            .into_owned();
+
+        info!(
+            "Downlinking queue entry {} remote: {} to local: {}",
+            id, path, target_path
+        );
+
+        let state =
+            f_protocol.process_message(reply, &State::StartReceive { path: target_path })?;
+
+        f_protocol.message_engine(|d| f_protocol.recv(Some(d)), Duration::from_secs(2), &state)?;
+    }
+}
+
+fn main() {
     let args = App::new("File transfer client")
         .subcommand(
             SubCommand::with_name("upload")
@@ -136,6 +311,12 @@ fn main() {
                     Arg::with_name("target_path")
                         .help("Destination path on remote target")
                         .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("recursive")
+                        .help("Transfer a whole directory")
+                        .short("R")
+                        .long("recursive"),
                 ),
         )
         .subcommand(
@@ -151,6 +332,78 @@ fn main() {
                     Arg::with_name("target_path")
                         .help("Local destination path")
                         .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("recursive")
+                        .help("Transfer a whole directory")
+                        .short("R")
+                        .long("recursive"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("status")
+                .about("Requests the status of the remote target's active transfers"),
+        )
+        .subcommand(
+            SubCommand::with_name("enqueue")
+                .about("Adds a remote file to the remote target's downlink queue")
+                .arg(
+                    Arg::with_name("source_path")
+                        .help("Remote file path to downlink")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("priority")
+                        .help("Higher priority files are downlinked first")
+                        .takes_value(true)
+                        .default_value("0"),
+                )
+                .arg(
+                    Arg::with_name("lifetime")
+                        .help("Number of seconds after which the file should be dropped from the queue")
+                        .short("e")
+                        .long("expires")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("reprioritize")
+                .about("Changes the priority of a downlink queue entry")
+                .arg(
+                    Arg::with_name("id")
+                        .help("Queue entry ID")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("priority")
+                        .help("New priority")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("cancel")
+                .about("Removes an entry from the downlink queue")
+                .arg(
+                    Arg::with_name("id")
+                        .help("Queue entry ID")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("queue").about("Lists the remote target's downlink queue"),
+        )
+        .subcommand(
+            SubCommand::with_name("downlink")
+                .about("Downloads every file in the remote target's downlink queue")
+                .arg(
+                    Arg::with_name("target_dir")
+                        .help("Local destination directory")
+                        .takes_value(true)
+                        .default_value("."),
                 ),
         )
         .subcommand(
@@ -198,10 +451,47 @@ fn main() {
                 .takes_value(true)
                 .default_value("6"),
         )
+        .arg(
+            Arg::with_name("transmit_rate")
+                .help("Target transmit rate, in bits per second")
+                .short("-b")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("window_size")
+                .help("Number of chunks to send before requesting the receiver's status")
+                .short("-w")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("progress")
+                .help("Show a progress bar instead of logging each chunk")
+                .short("-P")
+                .long("progress"),
+        )
+        .arg(
+            Arg::with_name("compression")
+                .help("Compress file data for transfer")
+                .short("-z")
+                .takes_value(true)
+                .possible_values(&["none", "gzip", "zstd", "lz4"]),
+        )
         .setting(AppSettings::SubcommandRequiredElseHelp)
         .setting(AppSettings::DeriveDisplayOrder)
         .get_matches();
 
+    // The progress bar replaces the per-message logging
+    let show_bar = args.is_present("progress");
+    let level = if show_bar {
+        LevelFilter::Warn
+    } else {
+        LevelFilter::Info
+    };
+
+    CombinedLogger::init(vec![TermLogger::new(level, Config::default()).unwrap()]).unwrap();
+
+    info!("Starting file transfer client");
+
     let host_ip = args.value_of("host_ip").unwrap();
     let remote_addr = format!(
         "{}:{}",
@@ -212,6 +502,20 @@ fn main() {
     let hold_count: u16 = args.value_of("hold_count").unwrap().parse().unwrap();
     let storage_prefix = args.value_of("storage_prefix").unwrap().to_string();
 
+    let mut f_config = FileProtocolConfig::new(Some(storage_prefix), chunk_size, hold_count);
+    if let Some(rate) = args.value_of("transmit_rate") {
+        f_config = f_config.transmit_rate(rate.parse().unwrap());
+    }
+    if let Some(window) = args.value_of("window_size") {
+        f_config = f_config.window_size(window.parse().unwrap());
+    }
+    if let Some(compression) = args.value_of("compression") {
+        f_config = f_config.compression(compression.parse().unwrap());
+    }
+    if show_bar {
+        f_config = f_config.progress(show_progress);
+    }
+
     let result = match args.subcommand_name() {
         Some("upload") => {
             let upload_args = args.subcommand_matches("upload").unwrap();
@@ -230,9 +534,8 @@ fn main() {
                 &remote_addr,
                 &source_path,
                 &target_path,
-                Some(storage_prefix),
-                chunk_size,
-                hold_count,
+                f_config,
+                upload_args.is_present("recursive"),
             )
         }
         Some("download") => {
@@ -252,9 +555,8 @@ fn main() {
                 &remote_addr,
                 &source_path,
                 &target_path,
-                Some(storage_prefix),
-                chunk_size,
-                hold_count,
+                f_config,
+                download_args.is_present("recursive"),
             )
         }
         Some("cleanup") => {
@@ -264,14 +566,57 @@ fn main() {
                 .value_of("hash")
                 .to_owned()
                 .map(|v| v.to_owned());
-            cleanup(
-                host_ip,
-                &remote_addr,
-                hash,
-                Some(storage_prefix),
-                chunk_size,
-                hold_count,
-            )
+            cleanup(host_ip, &remote_addr, hash, f_config)
+        }
+        Some("status") => status(host_ip, &remote_addr, f_config),
+        Some("enqueue") => {
+            let enqueue_args = args.subcommand_matches("enqueue").unwrap();
+            let source_path = enqueue_args.value_of("source_path").unwrap();
+            let priority: u32 = enqueue_args.value_of("priority").unwrap().parse().unwrap();
+            let lifetime: Option<u64> = enqueue_args
+                .value_of("lifetime")
+                .map(|lifetime| lifetime.parse().unwrap());
+
+            queue(host_ip, &remote_addr, f_config, |f_protocol, channel| {
+                f_protocol.send_enqueue(channel, source_path, priority, lifetime)
+            })
+        }
+        Some("reprioritize") => {
+            let reprioritize_args = args.subcommand_matches("reprioritize").unwrap();
+            let id: u32 = reprioritize_args.value_of("id").unwrap().parse().unwrap();
+            let priority: u32 = reprioritize_args
+                .value_of("priority")
+                .unwrap()
+                .parse()
+                .unwrap();
+
+            queue(host_ip, &remote_addr, f_config, |f_protocol, channel| {
+                f_protocol.send_reprioritize(channel, id, priority)
+            })
+        }
+        Some("cancel") => {
+            let id: u32 = args
+                .subcommand_matches("cancel")
+                .unwrap()
+                .value_of("id")
+                .unwrap()
+                .parse()
+                .unwrap();
+
+            queue(host_ip, &remote_addr, f_config, |f_protocol, channel| {
+                f_protocol.send_cancel(channel, id)
+            })
+        }
+        Some("queue") => queue(host_ip, &remote_addr, f_config, |f_protocol, channel| {
+            f_protocol.send_queue_request(channel)
+        }),
+        Some("downlink") => {
+            let target_dir = args
+                .subcommand_matches("downlink")
+                .unwrap()
+                .value_of("target_dir")
+                .unwrap();
+            downlink(host_ip, &remote_addr, target_dir, f_config)
         }
         _ => panic!("Invalid command"),
     };
@@ -0,0 +1,199 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+// Compression of file data for transfer
+
+use crate::error::ProtocolError;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+
+/// Compression algorithms which may be applied to a file's data prior to transfer
+///
+/// The file hash is always calculated over the original, uncompressed, data
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Compression {
+    /// Send the file data as-is
+    #[default]
+    None,
+    /// gzip (DEFLATE) compression
+    Gzip,
+    /// Zstandard compression
+    Zstd,
+    /// LZ4 frame compression
+    Lz4,
+}
+
+impl Compression {
+    /// The name used for this algorithm in file protocol messages
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Compression::None => "none",
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+            Compression::Lz4 => "lz4",
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Compression {
+    type Err = ProtocolError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "none" => Ok(Compression::None),
+            "gzip" => Ok(Compression::Gzip),
+            "zstd" => Ok(Compression::Zstd),
+            "lz4" => Ok(Compression::Lz4),
+            other => Err(ProtocolError::UnknownCompression(other.to_owned())),
+        }
+    }
+}
+
+// Compress the contents of `source` into `target`.
+// Returns the number of compressed bytes written
+pub fn compress_file(
+    compression: Compression,
+    source: &mut File,
+    target: File,
+) -> Result<u64, io::Error> {
+    let output = match compression {
+        Compression::None => {
+            let mut target = target;
+            io::copy(source, &mut target)?;
+            target
+        }
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(target, flate2::Compression::default());
+            io::copy(source, &mut encoder)?;
+            encoder.finish()?
+        }
+        Compression::Zstd => {
+            let mut encoder = zstd::stream::write::Encoder::new(target, 0)?;
+            io::copy(source, &mut encoder)?;
+            encoder.finish()?
+        }
+        Compression::Lz4 => {
+            let mut encoder = lz4::EncoderBuilder::new().build(target)?;
+            io::copy(source, &mut encoder)?;
+            let (output, result) = encoder.finish();
+            result?;
+            output
+        }
+    };
+
+    output.sync_all()?;
+    Ok(output.metadata()?.len())
+}
+
+// Wrap a reader so that the data read from it is decompressed
+pub fn decoder<'a, R: Read + 'a>(
+    compression: Compression,
+    source: R,
+) -> Result<Box<dyn Read + 'a>, io::Error> {
+    Ok(match compression {
+        Compression::None => Box::new(source),
+        Compression::Gzip => Box::new(GzDecoder::new(source)),
+        Compression::Zstd => Box::new(zstd::stream::read::Decoder::new(source)?),
+        Compression::Lz4 => Box::new(lz4::Decoder::new(source)?),
+    })
+}
+
+// Copy all of the decompressed data from `source` into `target`
+pub fn decompress<W: Write>(
+    compression: Compression,
+    source: File,
+    target: &mut W,
+) -> Result<u64, io::Error> {
+    let mut reader = decoder(compression, source)?;
+    io::copy(&mut reader, target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Seek, SeekFrom};
+    use tempfile::tempfile;
+
+    fn round_trip(compression: Compression, contents: &[u8]) -> (u64, Vec<u8>) {
+        let mut source = tempfile().unwrap();
+        source.write_all(contents).unwrap();
+        source.seek(SeekFrom::Start(0)).unwrap();
+
+        let mut compressed = tempfile().unwrap();
+        let length =
+            compress_file(compression, &mut source, compressed.try_clone().unwrap()).unwrap();
+        compressed.seek(SeekFrom::Start(0)).unwrap();
+
+        let mut output = vec![];
+        decompress(compression, compressed, &mut output).unwrap();
+
+        (length, output)
+    }
+
+    #[test]
+    fn round_trip_all() {
+        let contents: Vec<u8> = b"all work and no play makes jack a dull boy. "
+            .iter()
+            .cycle()
+            .take(10000)
+            .cloned()
+            .collect();
+
+        for compression in [
+            Compression::None,
+            Compression::Gzip,
+            Compression::Zstd,
+            Compression::Lz4,
+        ]
+        .iter()
+        {
+            let (length, output) = round_trip(*compression, &contents);
+            assert_eq!(output, contents, "{} round trip failed", compression);
+            if *compression != Compression::None {
+                assert!(length < contents.len() as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_names() {
+        for compression in [
+            Compression::None,
+            Compression::Gzip,
+            Compression::Zstd,
+            Compression::Lz4,
+        ]
+        .iter()
+        {
+            assert_eq!(
+                compression.as_str().parse::<Compression>().unwrap(),
+                *compression
+            );
+        }
+
+        assert!("bzip2".parse::<Compression>().is_err());
+    }
+}
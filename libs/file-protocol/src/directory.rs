@@ -0,0 +1,697 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+// Directory transfers
+//
+// A directory is sent as a manifest file plus a set of content blobs. Each blob is
+// a normal file in the chunk store, so it is identified by its hash and any blob
+// which the remote side already holds is not sent again.
+//
+// Files smaller than `PACK_THRESHOLD` chunks are concatenated into pack blobs
+// so that a directory full of small files doesn't need one round of NAKs per file.
+
+use crate::compression::Compression;
+use crate::error::ProtocolError;
+use crate::storage;
+use blake2_rfc::blake2s::Blake2s;
+use log::warn;
+use serde_cbor::{de, ser};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, Permissions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Component, Path, PathBuf};
+
+// Files smaller than this many chunks are packed together
+const PACK_THRESHOLD: usize = 1;
+// Maximum number of chunks a single pack blob will hold
+const PACK_MAX_CHUNKS: usize = 64;
+// Name of the stored copy of a directory's manifest
+const MANIFEST_FILE: &str = "manifest";
+// Name of a reassembled pack blob
+const PACK_FILE: &str = "pack";
+
+// A single piece of content which is transferred through the chunk store
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Blob {
+    pub hash: String,
+    pub num_chunks: u32,
+    pub packed: bool,
+}
+
+// A file or directory within the transferred tree
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Entry {
+    // Path relative to the root of the transfer, using '/' as the separator
+    pub path: String,
+    pub mode: u32,
+    pub directory: bool,
+    // Hash of the blob holding this file's data. `None` for directories and empty files
+    pub blob: Option<String>,
+    pub offset: u64,
+    pub length: u64,
+}
+
+// Description of a directory transfer
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Manifest {
+    pub blobs: Vec<Blob>,
+    pub entries: Vec<Entry>,
+}
+
+// Small files waiting to be written out as a pack blob
+struct Pack {
+    data: Vec<u8>,
+    // Index into the manifest entries of each file in this pack
+    entries: Vec<usize>,
+    // Offsets of files already in this pack, keyed by content hash
+    contents: HashMap<String, u64>,
+}
+
+impl Pack {
+    fn new() -> Self {
+        Pack {
+            data: vec![],
+            entries: vec![],
+            contents: HashMap::new(),
+        }
+    }
+}
+
+fn hash_bytes(data: &[u8]) -> String {
+    let mut hasher = Blake2s::new(storage::HASH_SIZE);
+    hasher.update(data);
+    hasher
+        .finalize()
+        .as_bytes()
+        .iter()
+        .map(|val| format!("{:02x}", val))
+        .collect()
+}
+
+fn storage_path(prefix: &str, hash: &str) -> PathBuf {
+    Path::new(&format!("{}/storage", prefix)).join(hash)
+}
+
+// Write a block of data into the chunk store
+fn initialize_data(
+    prefix: &str,
+    data: &[u8],
+    chunk_size: usize,
+) -> Result<(String, u32), ProtocolError> {
+    let storage_dir = format!("{}/storage", prefix);
+    fs::create_dir_all(&storage_dir).map_err(|err| ProtocolError::StorageError {
+        action: format!("create dir {}", storage_dir),
+        err,
+    })?;
+
+    let temp_path = Path::new(&storage_dir).join(format!(".dir.{}", time::get_time().nsec));
+    File::create(&temp_path)
+        .and_then(|mut file| file.write_all(data))
+        .map_err(|err| ProtocolError::StorageError {
+            action: format!("write {:?}", temp_path),
+            err,
+        })?;
+
+    // Directory contents are sent uncompressed, since the per-blob NAKs don't
+    // carry any compression information
+    let result = storage::initialize_file(
+        prefix,
+        &temp_path.to_string_lossy(),
+        chunk_size,
+        Compression::None,
+    );
+
+    if let Err(e) = fs::remove_file(&temp_path) {
+        warn!("Failed to remove temp file {:?} : {}", temp_path, e);
+    }
+
+    let (hash, num_chunks, _mode) = result?;
+    Ok((hash, num_chunks))
+}
+
+// Write out the current pack and point all of its entries at the new blob
+fn flush_pack(
+    prefix: &str,
+    chunk_size: usize,
+    pack: &mut Pack,
+    manifest: &mut Manifest,
+) -> Result<(), ProtocolError> {
+    if pack.data.is_empty() {
+        return Ok(());
+    }
+
+    let (hash, num_chunks) = initialize_data(prefix, &pack.data, chunk_size)?;
+
+    for index in pack.entries.iter() {
+        manifest.entries[*index].blob = Some(hash.clone());
+    }
+
+    if !manifest.blobs.iter().any(|blob| blob.hash == hash) {
+        manifest.blobs.push(Blob {
+            hash,
+            num_chunks,
+            packed: true,
+        });
+    }
+
+    *pack = Pack::new();
+    Ok(())
+}
+
+// Recursively add the contents of a directory to the manifest
+fn walk_directory(
+    prefix: &str,
+    root: &Path,
+    dir: &Path,
+    chunk_size: usize,
+    pack: &mut Pack,
+    manifest: &mut Manifest,
+) -> Result<(), ProtocolError> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|err| ProtocolError::StorageError {
+            action: format!("read directory {:?}", dir),
+            err,
+        })?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect();
+
+    // Sort so that the same directory always produces the same manifest (and hash)
+    entries.sort();
+
+    for path in entries {
+        let meta = fs::symlink_metadata(&path).map_err(|err| ProtocolError::StorageError {
+            action: format!("stat file {:?}", path),
+            err,
+        })?;
+
+        let rel_path = path
+            .strip_prefix(root)
+            .map_err(|_| ProtocolError::StorageParseError(format!("Invalid path {:?}", path)))?
+            .to_string_lossy()
+            .into_owned();
+
+        if meta.is_dir() {
+            manifest.entries.push(Entry {
+                path: rel_path,
+                mode: meta.mode(),
+                directory: true,
+                blob: None,
+                offset: 0,
+                length: 0,
+            });
+            walk_directory(prefix, root, &path, chunk_size, pack, manifest)?;
+        } else if meta.is_file() {
+            let length = meta.len();
+
+            if length == 0 {
+                manifest.entries.push(Entry {
+                    path: rel_path,
+                    mode: meta.mode(),
+                    directory: false,
+                    blob: None,
+                    offset: 0,
+                    length,
+                });
+            } else if (length as usize) < chunk_size * PACK_THRESHOLD {
+                let data = fs::read(&path).map_err(|err| ProtocolError::StorageError {
+                    action: format!("read {:?}", path),
+                    err,
+                })?;
+
+                if pack.data.len() + data.len() > chunk_size * PACK_MAX_CHUNKS {
+                    flush_pack(prefix, chunk_size, pack, manifest)?;
+                }
+
+                // Identical small files only need to be stored once per pack
+                let content_hash = hash_bytes(&data);
+                let offset = match pack.contents.get(&content_hash) {
+                    Some(offset) => *offset,
+                    None => {
+                        let offset = pack.data.len() as u64;
+                        pack.data.extend_from_slice(&data);
+                        pack.contents.insert(content_hash, offset);
+                        offset
+                    }
+                };
+
+                pack.entries.push(manifest.entries.len());
+                manifest.entries.push(Entry {
+                    path: rel_path,
+                    mode: meta.mode(),
+                    directory: false,
+                    blob: None,
+                    offset,
+                    length,
+                });
+            } else {
+                let (hash, num_chunks, mode) = storage::initialize_file(
+                    prefix,
+                    &path.to_string_lossy(),
+                    chunk_size,
+                    Compression::None,
+                )?;
+
+                if !manifest.blobs.iter().any(|blob| blob.hash == hash) {
+                    manifest.blobs.push(Blob {
+                        hash: hash.clone(),
+                        num_chunks,
+                        packed: false,
+                    });
+                }
+
+                manifest.entries.push(Entry {
+                    path: rel_path,
+                    mode,
+                    directory: false,
+                    blob: Some(hash),
+                    offset: 0,
+                    length,
+                });
+            }
+        } else {
+            warn!("Skipping {:?}: not a regular file or directory", path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Prepare a directory for transfer
+///
+/// Imports all of the directory's contents into temporary storage and creates
+/// the manifest describing them. Returns the manifest's hash and number of chunks,
+/// along with the mode of the source directory.
+pub fn initialize_directory(
+    prefix: &str,
+    source_path: &str,
+    chunk_size: usize,
+) -> Result<(String, u32, u32), ProtocolError> {
+    let root = Path::new(source_path);
+    let meta = fs::metadata(root).map_err(|err| ProtocolError::StorageError {
+        action: format!("stat directory {}", source_path),
+        err,
+    })?;
+
+    if !meta.is_dir() {
+        return Err(ProtocolError::StorageParseError(format!(
+            "{} is not a directory",
+            source_path
+        )));
+    }
+
+    let mut manifest = Manifest::default();
+    let mut pack = Pack::new();
+
+    walk_directory(prefix, root, root, chunk_size, &mut pack, &mut manifest)?;
+    flush_pack(prefix, chunk_size, &mut pack, &mut manifest)?;
+
+    let data = ser::to_vec_packed(&manifest)?;
+    let (hash, num_chunks) = initialize_data(prefix, &data, chunk_size)?;
+
+    // Keep a copy of the manifest alongside its chunks so we know which blobs
+    // belong to this transfer when it's cleaned up
+    let manifest_path = storage_path(prefix, &hash).join(MANIFEST_FILE);
+    fs::write(&manifest_path, &data).map_err(|err| ProtocolError::StorageError {
+        action: format!("write {:?}", manifest_path),
+        err,
+    })?;
+
+    Ok((hash, num_chunks, meta.mode()))
+}
+
+/// Load the manifest for a directory transfer from temporary storage
+///
+/// If it hasn't been reassembled yet, the manifest's chunks are stitched back together
+/// and verified against its hash.
+pub fn load_manifest(prefix: &str, hash: &str) -> Result<Manifest, ProtocolError> {
+    let manifest_path = storage_path(prefix, hash).join(MANIFEST_FILE);
+
+    if !manifest_path.exists() {
+        storage::finalize_file(prefix, hash, &manifest_path.to_string_lossy(), None)?;
+    }
+
+    let data = fs::read(&manifest_path).map_err(|err| ProtocolError::StorageError {
+        action: format!("read {:?}", manifest_path),
+        err,
+    })?;
+
+    de::from_slice(&data).map_err(|err| {
+        ProtocolError::StorageParseError(format!("Unable to parse manifest for {}: {}", hash, err))
+    })
+}
+
+/// Check which pieces of a directory transfer are missing from temporary storage
+///
+/// Returns the missing chunk ranges of each incomplete blob (or of the manifest itself).
+/// An empty list means the whole directory has been received.
+pub fn validate_directory(
+    prefix: &str,
+    hash: &str,
+    num_chunks: Option<u32>,
+) -> Result<Vec<(String, Vec<u32>)>, ProtocolError> {
+    let (complete, missing) = storage::validate_file(prefix, hash, num_chunks)?;
+    if !complete {
+        return Ok(vec![(hash.to_owned(), missing)]);
+    }
+
+    let manifest = load_manifest(prefix, hash)?;
+
+    let mut result = vec![];
+    for blob in manifest.blobs.iter() {
+        let (complete, missing) =
+            storage::validate_file(prefix, &blob.hash, Some(blob.num_chunks))?;
+        if !complete {
+            result.push((blob.hash.clone(), missing));
+        }
+    }
+
+    Ok(result)
+}
+
+// Make sure a manifest entry can't write outside of the target directory
+fn entry_path(target: &Path, entry: &Entry) -> Result<PathBuf, ProtocolError> {
+    let path = Path::new(&entry.path);
+
+    let is_relative = path.components().all(|component| match component {
+        Component::Normal(_) => true,
+        _ => false,
+    });
+
+    if !is_relative {
+        return Err(ProtocolError::FinalizeError {
+            cause: format!("invalid path in manifest: {}", entry.path),
+        });
+    }
+
+    Ok(target.join(path))
+}
+
+fn set_mode(path: &Path, mode: u32) -> Result<(), ProtocolError> {
+    fs::set_permissions(path, Permissions::from_mode(mode)).map_err(|err| {
+        ProtocolError::StorageError {
+            action: format!("set mode of {:?}", path),
+            err,
+        }
+    })
+}
+
+// Copy one file's worth of data out of a reassembled pack
+//
+// The data is streamed rather than buffered, since the entry's offset and length come from
+// the remote manifest and can't be trusted
+fn unpack_entry(pack: &mut File, entry: &Entry, path: &Path) -> Result<(), ProtocolError> {
+    pack.seek(SeekFrom::Start(entry.offset))
+        .map_err(|err| ProtocolError::StorageError {
+            action: format!("read {} from pack", entry.path),
+            err,
+        })?;
+
+    let mut file = File::create(path).map_err(|err| ProtocolError::StorageError {
+        action: format!("write {:?}", path),
+        err,
+    })?;
+
+    let copied = io::copy(&mut pack.take(entry.length), &mut file).map_err(|err| {
+        ProtocolError::StorageError {
+            action: format!("unpack {} to {:?}", entry.path, path),
+            err,
+        }
+    })?;
+
+    if copied != entry.length {
+        return Err(ProtocolError::FinalizeError {
+            cause: format!("{} extends past the end of its pack", entry.path),
+        });
+    }
+
+    Ok(())
+}
+
+/// Rebuild a received directory at its final location
+///
+/// Every blob is verified against its hash as it's reassembled.
+/// Temporary storage for the manifest and all of its blobs is removed once the
+/// directory has been written.
+pub fn finalize_directory(
+    prefix: &str,
+    hash: &str,
+    target_path: &str,
+    mode: Option<u32>,
+) -> Result<(), ProtocolError> {
+    if !validate_directory(prefix, hash, None)?.is_empty() {
+        return Err(ProtocolError::FinalizeError {
+            cause: "directory missing chunks".to_owned(),
+        });
+    }
+
+    let manifest = load_manifest(prefix, hash)?;
+    let target = Path::new(target_path);
+
+    fs::create_dir_all(target).map_err(|err| ProtocolError::StorageError {
+        action: format!("create directory {}", target_path),
+        err,
+    })?;
+
+    // Create the directory tree first so files have somewhere to go
+    for entry in manifest.entries.iter().filter(|entry| entry.directory) {
+        let path = entry_path(target, entry)?;
+        fs::create_dir_all(&path).map_err(|err| ProtocolError::StorageError {
+            action: format!("create directory {:?}", path),
+            err,
+        })?;
+    }
+
+    for entry in manifest.entries.iter().filter(|entry| entry.blob.is_none()) {
+        if !entry.directory {
+            let path = entry_path(target, entry)?;
+            File::create(&path).map_err(|err| ProtocolError::StorageError {
+                action: format!("create {:?}", path),
+                err,
+            })?;
+            set_mode(&path, entry.mode)?;
+        }
+    }
+
+    for blob in manifest.blobs.iter() {
+        let entries: Vec<&Entry> = manifest
+            .entries
+            .iter()
+            .filter(|entry| entry.blob.as_ref() == Some(&blob.hash))
+            .collect();
+
+        if blob.packed {
+            let pack_path = storage_path(prefix, &blob.hash).join(PACK_FILE);
+            storage::finalize_file(prefix, &blob.hash, &pack_path.to_string_lossy(), None)?;
+
+            let mut pack = File::open(&pack_path).map_err(|err| ProtocolError::StorageError {
+                action: format!("open {:?}", pack_path),
+                err,
+            })?;
+
+            for entry in entries {
+                let path = entry_path(target, entry)?;
+                unpack_entry(&mut pack, entry, &path)?;
+                set_mode(&path, entry.mode)?;
+            }
+        } else if let Some((first, rest)) = entries.split_first() {
+            // Duplicate files share a single blob, so reassemble it once and copy it
+            let first_path = entry_path(target, first)?;
+            storage::finalize_file(
+                prefix,
+                &blob.hash,
+                &first_path.to_string_lossy(),
+                Some(first.mode),
+            )?;
+
+            for entry in rest {
+                let path = entry_path(target, entry)?;
+                fs::copy(&first_path, &path).map_err(|err| ProtocolError::StorageError {
+                    action: format!("copy {:?} to {:?}", first_path, path),
+                    err,
+                })?;
+                set_mode(&path, entry.mode)?;
+            }
+        }
+    }
+
+    // Apply directory modes last, deepest first, in case any of them are read-only
+    for entry in manifest
+        .entries
+        .iter()
+        .rev()
+        .filter(|entry| entry.directory)
+    {
+        set_mode(&entry_path(target, entry)?, entry.mode)?;
+    }
+
+    if let Some(mode) = mode {
+        set_mode(target, mode)?;
+    }
+
+    Ok(())
+}
+
+/// Remove the temporary storage for a directory transfer and all of its blobs
+pub fn delete_directory(prefix: &str, hash: &str) -> Result<(), ProtocolError> {
+    let manifest = load_manifest(prefix, hash)?;
+
+    for blob in manifest.blobs.iter() {
+        if let Err(e) = storage::delete_file(prefix, &blob.hash) {
+            warn!("Failed to delete blob {}: {}", blob.hash, e);
+        }
+    }
+
+    storage::delete_file(prefix, hash)
+}
+
+/// Check whether the given temporary storage belongs to a directory transfer
+pub fn is_directory(prefix: &str, hash: &str) -> bool {
+    storage_path(prefix, hash).join(MANIFEST_FILE).exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    fn create_file(path: &Path, contents: &[u8], mode: u32) {
+        fs::write(path, contents).unwrap();
+        fs::set_permissions(path, Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn initialize_finalize_directory() {
+        let test_dir = TempDir::new().unwrap();
+        let source = test_dir.path().join("source");
+        let target = test_dir.path().join("target");
+        let prefix = test_dir.path().join("storage");
+        let prefix = prefix.to_str().unwrap();
+
+        fs::create_dir_all(source.join("sub/deeper")).unwrap();
+        create_file(&source.join("small"), b"small file", 0o600);
+        create_file(&source.join("sub/small_copy"), b"small file", 0o640);
+        create_file(&source.join("sub/deeper/large"), &[7u8; 5000], 0o755);
+        create_file(&source.join("large_copy"), &[7u8; 5000], 0o644);
+        create_file(&source.join("empty"), b"", 0o600);
+
+        let (hash, _num_chunks, mode) =
+            initialize_directory(prefix, source.to_str().unwrap(), 1024).unwrap();
+
+        let manifest = load_manifest(prefix, &hash).unwrap();
+        // One pack holding the (deduplicated) small file and one blob for both large files
+        assert_eq!(manifest.blobs.len(), 2);
+        assert_eq!(manifest.blobs.iter().filter(|blob| blob.packed).count(), 1);
+
+        assert!(validate_directory(prefix, &hash, None).unwrap().is_empty());
+
+        finalize_directory(prefix, &hash, target.to_str().unwrap(), Some(mode)).unwrap();
+
+        assert_eq!(fs::read(target.join("small")).unwrap(), b"small file");
+        assert_eq!(
+            fs::read(target.join("sub/small_copy")).unwrap(),
+            b"small file"
+        );
+        assert_eq!(
+            fs::read(target.join("sub/deeper/large")).unwrap(),
+            vec![7u8; 5000]
+        );
+        assert_eq!(
+            fs::read(target.join("large_copy")).unwrap(),
+            vec![7u8; 5000]
+        );
+        assert_eq!(fs::read(target.join("empty")).unwrap(), b"");
+
+        let file_mode = |path: &str| fs::metadata(target.join(path)).unwrap().mode() & 0o777;
+        assert_eq!(file_mode("small"), 0o600);
+        assert_eq!(file_mode("sub/small_copy"), 0o640);
+        assert_eq!(file_mode("sub/deeper/large"), 0o755);
+
+        delete_directory(prefix, &hash).unwrap();
+        assert_eq!(
+            fs::read_dir(format!("{}/storage", prefix)).unwrap().count(),
+            0
+        );
+    }
+
+    #[test]
+    fn validate_directory_missing_blob() {
+        let test_dir = TempDir::new().unwrap();
+        let source = test_dir.path().join("source");
+        let prefix = test_dir.path().join("storage");
+        let prefix = prefix.to_str().unwrap();
+
+        fs::create_dir_all(&source).unwrap();
+        create_file(&source.join("large"), &[3u8; 4000], 0o644);
+
+        let (hash, _num_chunks, _mode) =
+            initialize_directory(prefix, source.to_str().unwrap(), 1024).unwrap();
+
+        let manifest = load_manifest(prefix, &hash).unwrap();
+        let blob = &manifest.blobs[0];
+        storage::delete_chunk(prefix, &blob.hash, 1).unwrap();
+
+        assert_eq!(
+            validate_directory(prefix, &hash, None).unwrap(),
+            vec![(blob.hash.clone(), vec![1, 2])]
+        );
+    }
+
+    #[test]
+    fn finalize_directory_bad_path() {
+        let test_dir = TempDir::new().unwrap();
+        let target = test_dir.path().join("target");
+
+        let entry = Entry {
+            path: "../escape".to_owned(),
+            mode: 0o644,
+            directory: false,
+            blob: None,
+            offset: 0,
+            length: 0,
+        };
+
+        assert!(entry_path(&target, &entry).is_err());
+    }
+
+    #[test]
+    fn unpack_entry_past_end() {
+        let test_dir = TempDir::new().unwrap();
+        let pack_path = test_dir.path().join("pack");
+        fs::write(&pack_path, b"0123456789").unwrap();
+        let mut pack = File::open(&pack_path).unwrap();
+
+        let mut entry = Entry {
+            path: "file".to_owned(),
+            mode: 0o644,
+            directory: false,
+            blob: Some("hash".to_owned()),
+            offset: 2,
+            length: 4,
+        };
+        let path = test_dir.path().join("file");
+
+        unpack_entry(&mut pack, &entry, &path).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"2345");
+
+        // A malformed manifest mustn't be able to trigger a huge allocation
+        entry.length = 0xFFFF_FFFF;
+        assert!(unpack_entry(&mut pack, &entry, &path).is_err());
+    }
+}
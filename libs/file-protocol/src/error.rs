@@ -83,6 +83,9 @@ pub enum ProtocolError {
     /// An error was encountered when parsing file storage data
     #[fail(display = "{}", _0)]
     StorageParseError(String),
+    /// An unknown compression algorithm was requested
+    #[fail(display = "Unknown compression algorithm: {}", _0)]
+    UnknownCompression(String),
     /// A timeout occurred when receiving data
     #[fail(display = "A receive timeout was encountered")]
     ReceiveTimeout,
@@ -86,18 +86,26 @@
 
 #![deny(missing_docs)]
 
+mod compression;
+mod directory;
 mod error;
 mod messages;
 mod parsers;
+mod progress;
 pub mod protocol;
+mod queue;
 mod storage;
+mod transmit;
 
+pub use crate::compression::Compression;
 pub use crate::error::ProtocolError;
+pub use crate::progress::{Direction, TransferState, TransferStatus};
 pub use crate::protocol::Protocol as FileProtocol;
 pub use crate::protocol::ProtocolConfig as FileProtocolConfig;
 pub use crate::protocol::State;
+pub use crate::queue::QueueEntry;
 
-pub use crate::parsers::parse_channel_id;
+pub use crate::parsers::{parse_channel_id, parse_message};
 
 /// File protocol message types
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -113,22 +121,50 @@ pub enum Message {
     /// Receiver is missing the specified file data chunks
     NAK(u32, String, Option<Vec<(u32, u32)>>),
     /// (Client Only) Message requesting the recipient to receive the specified file
-    ReqReceive(u32, String, String, Option<u32>),
+    ReqReceive(u32, String, String, Option<u32>, Compression),
     /// (Client Only) Message requesting the recipient to transmit the specified file
-    ReqTransmit(u32, String),
+    ReqTransmit(u32, String, Compression),
+    /// (Client Only) Message requesting the recipient to receive the specified directory
+    ReqReceiveDir(u32, String, String, Option<u32>),
+    /// (Client Only) Message requesting the recipient to transmit the specified directory
+    ReqTransmitDir(u32, String),
     /// (Server Only) Recipient has successfully processed a request to receive a file
     SuccessReceive(u32, String),
     /// (Server Only) Recipient has successfully prepared to transmit a file
-    SuccessTransmit(u32, String, u32, Option<u32>),
+    SuccessTransmit(u32, String, u32, Option<u32>, Compression),
     /// (Server Only) The transmit or receive request has failed to be completed
     Failure(u32, String),
     /// Request Cleanup of either whole storage directory or individual file's storage
     Cleanup(u32, Option<String>),
+    /// (Client Only) Message requesting the status of the recipient's active transfers
+    ReqStatus(u32),
+    /// (Server Only) The status of each of the recipient's active transfers
+    Status(u32, Vec<TransferStatus>),
+    /// (Client Only) Message requesting that a file be added to the recipient's downlink queue,
+    /// with the given priority and optional lifetime (in seconds)
+    ReqEnqueue(u32, String, u32, Option<u64>),
+    /// (Client Only) Message requesting that a downlink queue entry be given a new priority
+    ReqReprioritize(u32, u32, u32),
+    /// (Client Only) Message requesting that a downlink queue entry be removed
+    ReqCancel(u32, u32),
+    /// (Client Only) Message requesting the contents of the recipient's downlink queue
+    ReqQueue(u32),
+    /// (Server Only) The contents of the recipient's downlink queue, in downlink order
+    Queue(u32, Vec<QueueEntry>),
+    /// (Client Only) Message requesting the recipient to transmit the next file in its downlink queue
+    ReqDownlink(u32),
+    /// (Server Only) Recipient has successfully prepared to transmit the next file in its
+    /// downlink queue. Contains the queue entry ID, file path, hash, number of chunks, mode,
+    /// and compression
+    Downlink(u32, u32, String, String, u32, u32, Compression),
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{messages, parsers, Message};
+    use super::{
+        messages, parsers, Compression, Direction, Message, QueueEntry, TransferState,
+        TransferStatus,
+    };
     use serde_cbor::de;
 
     #[test]
@@ -138,13 +174,219 @@ mod tests {
         let target_path = "/path/to/file".to_owned();
         let mode = 0o623;
 
-        let raw = messages::export_request(channel_id, &hash, &target_path, mode).unwrap();
+        let raw =
+            messages::export_request(channel_id, &hash, &target_path, mode, Compression::None)
+                .unwrap();
 
         let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
 
         assert_eq!(
             msg.unwrap(),
-            Message::ReqReceive(channel_id, hash, target_path, Some(mode))
+            Message::ReqReceive(channel_id, hash, target_path, Some(mode), Compression::None)
+        );
+    }
+
+    #[test]
+    fn create_parse_compressed_export_request() {
+        let channel_id = 10;
+        let hash = "abcdedf".to_owned();
+        let target_path = "/path/to/file".to_owned();
+        let mode = 0o623;
+
+        let raw =
+            messages::export_request(channel_id, &hash, &target_path, mode, Compression::Zstd)
+                .unwrap();
+
+        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+
+        assert_eq!(
+            msg.unwrap(),
+            Message::ReqReceive(channel_id, hash, target_path, Some(mode), Compression::Zstd)
+        );
+    }
+
+    #[test]
+    fn create_parse_compressed_import_request() {
+        let channel_id = 10;
+        let source_path = "/path/to/file".to_owned();
+
+        let raw = messages::import_request(channel_id, &source_path, Compression::Gzip).unwrap();
+
+        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+
+        assert_eq!(
+            msg.unwrap(),
+            Message::ReqTransmit(channel_id, source_path, Compression::Gzip)
+        );
+    }
+
+    #[test]
+    fn create_parse_compressed_import_success() {
+        let channel_id = 10;
+        let hash = "abcdedf".to_owned();
+        let num_chunks = 12;
+        let mode = 0o644;
+
+        let raw =
+            messages::import_setup_success(channel_id, &hash, num_chunks, mode, Compression::Lz4)
+                .unwrap();
+
+        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+
+        assert_eq!(
+            msg.unwrap(),
+            Message::SuccessTransmit(channel_id, hash, num_chunks, Some(mode), Compression::Lz4)
+        );
+    }
+
+    #[test]
+    fn create_parse_export_dir_request() {
+        let channel_id = 10;
+        let hash = "abcdedf".to_owned();
+        let target_path = "/path/to/dir".to_owned();
+        let mode = 0o755;
+
+        let raw = messages::export_dir_request(channel_id, &hash, &target_path, mode).unwrap();
+
+        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+
+        assert_eq!(
+            msg.unwrap(),
+            Message::ReqReceiveDir(channel_id, hash, target_path, Some(mode))
+        );
+    }
+
+    #[test]
+    fn create_parse_import_dir_request() {
+        let channel_id = 10;
+        let source_path = "/path/to/dir".to_owned();
+
+        let raw = messages::import_dir_request(channel_id, &source_path).unwrap();
+
+        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+
+        assert_eq!(
+            msg.unwrap(),
+            Message::ReqTransmitDir(channel_id, source_path)
+        );
+    }
+
+    #[test]
+    fn create_parse_status_request() {
+        let channel_id = 10;
+
+        let raw = messages::status_request(channel_id).unwrap();
+        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+
+        assert_eq!(msg.unwrap(), Message::ReqStatus(channel_id));
+    }
+
+    #[test]
+    fn create_parse_status() {
+        let channel_id = 10;
+        let transfers = vec![
+            TransferStatus {
+                channel_id: 123_456,
+                direction: Direction::Transmit,
+                path: "/home/system/file.bin".to_owned(),
+                hash: "abcdefg".to_owned(),
+                chunks_done: 12,
+                chunks_total: 40,
+                bytes_per_sec: 4096,
+                state: TransferState::Transmitting,
+            },
+            TransferStatus {
+                channel_id: 654_321,
+                direction: Direction::Receive,
+                path: "/home/system/upload".to_owned(),
+                hash: "hijklmn".to_owned(),
+                chunks_done: 0,
+                chunks_total: 3,
+                bytes_per_sec: 0,
+                state: TransferState::Starting,
+            },
+        ];
+
+        let raw = messages::status(channel_id, &transfers).unwrap();
+        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+
+        assert_eq!(msg.unwrap(), Message::Status(channel_id, transfers));
+    }
+
+    #[test]
+    fn create_parse_enqueue() {
+        let channel_id = 11;
+        let path = "/home/system/log/payload.log".to_owned();
+
+        let raw = messages::enqueue(channel_id, &path, 5, Some(3600)).unwrap();
+        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+        assert_eq!(
+            msg.unwrap(),
+            Message::ReqEnqueue(channel_id, path.clone(), 5, Some(3600))
+        );
+
+        let raw = messages::enqueue(channel_id, &path, 5, None).unwrap();
+        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+        assert_eq!(msg.unwrap(), Message::ReqEnqueue(channel_id, path, 5, None));
+    }
+
+    #[test]
+    fn create_parse_reprioritize_cancel() {
+        let channel_id = 12;
+
+        let raw = messages::reprioritize(channel_id, 3, 7).unwrap();
+        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+        assert_eq!(msg.unwrap(), Message::ReqReprioritize(channel_id, 3, 7));
+
+        let raw = messages::cancel(channel_id, 3).unwrap();
+        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+        assert_eq!(msg.unwrap(), Message::ReqCancel(channel_id, 3));
+    }
+
+    #[test]
+    fn create_parse_queue() {
+        let channel_id = 13;
+
+        let raw = messages::queue_request(channel_id).unwrap();
+        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+        assert_eq!(msg.unwrap(), Message::ReqQueue(channel_id));
+
+        let entries = vec![
+            QueueEntry {
+                id: 2,
+                path: "/home/system/log/payload.log".to_owned(),
+                priority: 10,
+                expires: Some(1_550_000_000),
+            },
+            QueueEntry {
+                id: 1,
+                path: "/home/system/images/1.png".to_owned(),
+                priority: 0,
+                expires: None,
+            },
+        ];
+
+        let raw = messages::queue(channel_id, &entries).unwrap();
+        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+        assert_eq!(msg.unwrap(), Message::Queue(channel_id, entries));
+    }
+
+    #[test]
+    fn create_parse_downlink() {
+        let channel_id = 14;
+        let path = "/home/system/log/payload.log".to_owned();
+        let hash = "abcdefg".to_owned();
+
+        let raw = messages::downlink_request(channel_id).unwrap();
+        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+        assert_eq!(msg.unwrap(), Message::ReqDownlink(channel_id));
+
+        let raw =
+            messages::downlink(channel_id, 4, &path, &hash, 20, 0o644, Compression::Gzip).unwrap();
+        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+        assert_eq!(
+            msg.unwrap(),
+            Message::Downlink(channel_id, 4, path, hash, 20, 0o644, Compression::Gzip)
         );
     }
 
@@ -209,12 +451,38 @@ mod tests {
         let missing_chunks = vec![0, 1, 4, 10];
         let chunk_ranges: Vec<(u32, u32)> = vec![(0, 1), (4, 10)];
 
-        let raw = messages::nak(channel_id, &hash, &missing_chunks).unwrap();
-        let msg = parsers::parse_message(de::from_slice(&raw).unwrap());
+        let raw = messages::nak(channel_id, &hash, &missing_chunks, 4096).unwrap();
+        assert_eq!(raw.len(), 1);
+        let msg = parsers::parse_message(de::from_slice(&raw[0]).unwrap());
 
         assert_eq!(
             msg.unwrap(),
             Message::NAK(channel_id, hash, Some(chunk_ranges))
         );
     }
+
+    #[test]
+    fn create_parse_nak_many_ranges() {
+        let channel_id = 11;
+        let hash = "abcdefg".to_owned();
+        // 500 single-chunk gaps
+        let missing_chunks: Vec<u32> = (0..500)
+            .flat_map(|num| vec![num * 2, num * 2 + 1])
+            .collect();
+
+        let raw = messages::nak(channel_id, &hash, &missing_chunks, 256).unwrap();
+        assert!(raw.len() > 1);
+
+        let mut chunk_ranges: Vec<(u32, u32)> = vec![];
+        for message in raw.iter() {
+            assert!(message.len() <= 256);
+            match parsers::parse_message(de::from_slice(message).unwrap()).unwrap() {
+                Message::NAK(_, _, Some(ranges)) => chunk_ranges.extend(ranges),
+                other => panic!("Unexpected message: {:?}", other),
+            }
+        }
+
+        let expected: Vec<(u32, u32)> = (0..500).map(|num| (num * 2, num * 2 + 1)).collect();
+        assert_eq!(chunk_ranges, expected);
+    }
 }
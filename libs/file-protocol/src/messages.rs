@@ -14,36 +14,93 @@
 // limitations under the License.
 //
 
+use crate::compression::Compression;
 use crate::error::ProtocolError;
+use crate::progress::TransferStatus;
+use crate::queue::QueueEntry;
 use log::info;
 use serde_cbor::{ser, Value};
 
 // Create export message
+//
+// The compression field is only included if the file's chunks are compressed
 pub fn export_request(
     channel_id: u32,
     hash: &str,
     target_path: &str,
     mode: u32,
+    compression: Compression,
+) -> Result<Vec<u8>, ProtocolError> {
+    info!(
+        "-> {{ {}, export, {}, {}, {}, {} }}",
+        channel_id, hash, target_path, mode, compression
+    );
+
+    let result = match compression {
+        Compression::None => ser::to_vec_packed(&(channel_id, "export", hash, target_path, mode)),
+        _ => ser::to_vec_packed(&(
+            channel_id,
+            "export",
+            hash,
+            target_path,
+            mode,
+            compression.as_str(),
+        )),
+    };
+
+    result.map_err(|err| ProtocolError::MessageCreationError {
+        message: "export".to_owned(),
+        err,
+    })
+}
+
+// Create directory export message
+pub fn export_dir_request(
+    channel_id: u32,
+    hash: &str,
+    target_path: &str,
+    mode: u32,
 ) -> Result<Vec<u8>, ProtocolError> {
     info!(
-        "-> {{ {}, export, {}, {}, {} }}",
+        "-> {{ {}, export_dir, {}, {}, {} }}",
         channel_id, hash, target_path, mode
     );
 
-    ser::to_vec_packed(&(channel_id, "export", hash, target_path, mode)).map_err(|err| {
+    ser::to_vec_packed(&(channel_id, "export_dir", hash, target_path, mode)).map_err(|err| {
         ProtocolError::MessageCreationError {
-            message: "export".to_owned(),
+            message: "export_dir".to_owned(),
             err,
         }
     })
 }
 
 // Create import message
-pub fn import_request(channel_id: u32, source_path: &str) -> Result<Vec<u8>, ProtocolError> {
-    info!("-> {{ import, {} }}", source_path);
-    ser::to_vec_packed(&(channel_id, "import", source_path)).map_err(|err| {
+//
+// The compression field is only included if compression is being requested
+pub fn import_request(
+    channel_id: u32,
+    source_path: &str,
+    compression: Compression,
+) -> Result<Vec<u8>, ProtocolError> {
+    info!("-> {{ import, {}, {} }}", source_path, compression);
+
+    let result = match compression {
+        Compression::None => ser::to_vec_packed(&(channel_id, "import", source_path)),
+        _ => ser::to_vec_packed(&(channel_id, "import", source_path, compression.as_str())),
+    };
+
+    result.map_err(|err| ProtocolError::MessageCreationError {
+        message: "import".to_owned(),
+        err,
+    })
+}
+
+// Create directory import message
+pub fn import_dir_request(channel_id: u32, source_path: &str) -> Result<Vec<u8>, ProtocolError> {
+    info!("-> {{ import_dir, {} }}", source_path);
+    ser::to_vec_packed(&(channel_id, "import_dir", source_path)).map_err(|err| {
         ProtocolError::MessageCreationError {
-            message: "import".to_owned(),
+            message: "import_dir".to_owned(),
             err,
         }
     })
@@ -72,37 +129,63 @@ pub fn ack(channel_id: u32, hash: &str, num_chunks: Option<u32>) -> Result<Vec<u
 }
 
 // Sends a nak with ranges of missing chunks
-pub fn nak(channel_id: u32, hash: &str, missing_chunks: &[u32]) -> Result<Vec<u8>, ProtocolError> {
-    let chunks = if missing_chunks.len() > 20 {
-        &missing_chunks[0..20]
-    } else {
-        &missing_chunks
-    };
+//
+// The ranges are split across as many messages as needed so that no single
+// message is larger than `max_size` bytes
+pub fn nak(
+    channel_id: u32,
+    hash: &str,
+    missing_chunks: &[u32],
+    max_size: usize,
+) -> Result<Vec<Vec<u8>>, ProtocolError> {
+    info!(
+        "-> {{ {}, {}, false, {} missing ranges }}",
+        channel_id,
+        hash,
+        missing_chunks.len() / 2
+    );
 
-    info!("-> {{ {}, {}, false, {:?} }}", channel_id, hash, chunks);
-    let mut vec = ser::to_vec_packed(&(channel_id, hash, false)).map_err(|err| {
+    let header = ser::to_vec_packed(&(channel_id, hash, false)).map_err(|err| {
         ProtocolError::MessageCreationError {
             message: "NAK".to_owned(),
             err,
         }
     })?;
 
+    let mut messages = vec![];
+    let mut vec = header.clone();
     // Make the array indefinite-length
     vec[0] |= 0x1F;
 
-    for chunk in chunks.iter() {
-        // Add the chunk number to the end of the CBOR array
-        vec.append(&mut ser::to_vec_packed(&chunk).map_err(|err| {
-            ProtocolError::MessageCreationError {
-                message: "NAK".to_owned(),
-                err,
-            }
-        })?);
+    for range in missing_chunks.chunks(2) {
+        let mut entry = vec![];
+        for chunk in range.iter() {
+            // Add the chunk number to the end of the CBOR array
+            entry.append(&mut ser::to_vec_packed(&chunk).map_err(|err| {
+                ProtocolError::MessageCreationError {
+                    message: "NAK".to_owned(),
+                    err,
+                }
+            })?);
+        }
+
+        // Leave room for the array break character
+        if vec.len() > header.len() && vec.len() + entry.len() + 1 > max_size {
+            // Add the array break character
+            vec.push(0xFF);
+            messages.push(vec);
+
+            vec = header.clone();
+            vec[0] |= 0x1F;
+        }
+
+        vec.append(&mut entry);
     }
 
     // Add the array break character
     vec.push(0xFF);
-    Ok(vec)
+    messages.push(vec);
+    Ok(messages)
 }
 
 // Create chunk message
@@ -123,22 +206,35 @@ pub fn chunk(
 }
 
 // Create succesful import request response message
+//
+// The compression field is only included if the file's chunks are compressed
 pub fn import_setup_success(
     channel_id: u32,
     hash: &str,
     num_chunks: u32,
     mode: u32,
+    compression: Compression,
 ) -> Result<Vec<u8>, ProtocolError> {
     info!(
-        "-> {{ {}, true, {}, {}, {} }}",
-        channel_id, hash, num_chunks, mode
+        "-> {{ {}, true, {}, {}, {}, {} }}",
+        channel_id, hash, num_chunks, mode, compression
     );
 
-    ser::to_vec_packed(&(channel_id, true, hash, num_chunks, mode)).map_err(|err| {
-        ProtocolError::MessageCreationError {
-            message: "import success".to_owned(),
-            err,
-        }
+    let result = match compression {
+        Compression::None => ser::to_vec_packed(&(channel_id, true, hash, num_chunks, mode)),
+        _ => ser::to_vec_packed(&(
+            channel_id,
+            true,
+            hash,
+            num_chunks,
+            mode,
+            compression.as_str(),
+        )),
+    };
+
+    result.map_err(|err| ProtocolError::MessageCreationError {
+        message: "import success".to_owned(),
+        err,
     })
 }
 
@@ -182,3 +278,174 @@ pub fn cleanup(channel_id: u32, hash: Option<String>) -> Result<Vec<u8>, Protoco
         }
     })
 }
+
+// Create transfer status request message
+pub fn status_request(channel_id: u32) -> Result<Vec<u8>, ProtocolError> {
+    info!("-> {{ {}, status }}", channel_id);
+    ser::to_vec_packed(&(channel_id, "status")).map_err(|err| ProtocolError::MessageCreationError {
+        message: "status request".to_owned(),
+        err,
+    })
+}
+
+// Create transfer status response message.
+// Each transfer is sent as
+// [ channel_id, direction, path, hash, chunks_done, chunks_total, bytes_per_sec, state ]
+pub fn status(channel_id: u32, transfers: &[TransferStatus]) -> Result<Vec<u8>, ProtocolError> {
+    info!(
+        "-> {{ {}, status, {} transfers }}",
+        channel_id,
+        transfers.len()
+    );
+
+    let entries: Vec<_> = transfers
+        .iter()
+        .map(|transfer| {
+            (
+                transfer.channel_id,
+                transfer.direction.as_str(),
+                &transfer.path,
+                &transfer.hash,
+                transfer.chunks_done,
+                transfer.chunks_total,
+                transfer.bytes_per_sec,
+                transfer.state.as_str(),
+            )
+        })
+        .collect();
+
+    ser::to_vec_packed(&(channel_id, "status", entries)).map_err(|err| {
+        ProtocolError::MessageCreationError {
+            message: "status".to_owned(),
+            err,
+        }
+    })
+}
+
+// Create downlink queue enqueue request message
+//
+// The lifetime field is only included if the entry should expire
+pub fn enqueue(
+    channel_id: u32,
+    path: &str,
+    priority: u32,
+    lifetime: Option<u64>,
+) -> Result<Vec<u8>, ProtocolError> {
+    info!(
+        "-> {{ {}, enqueue, {}, {}, {:?} }}",
+        channel_id, path, priority, lifetime
+    );
+
+    let result = match lifetime {
+        Some(lifetime) => ser::to_vec_packed(&(channel_id, "enqueue", path, priority, lifetime)),
+        None => ser::to_vec_packed(&(channel_id, "enqueue", path, priority)),
+    };
+
+    result.map_err(|err| ProtocolError::MessageCreationError {
+        message: "enqueue".to_owned(),
+        err,
+    })
+}
+
+// Create downlink queue reprioritize request message
+pub fn reprioritize(channel_id: u32, id: u32, priority: u32) -> Result<Vec<u8>, ProtocolError> {
+    info!(
+        "-> {{ {}, reprioritize, {}, {} }}",
+        channel_id, id, priority
+    );
+    ser::to_vec_packed(&(channel_id, "reprioritize", id, priority)).map_err(|err| {
+        ProtocolError::MessageCreationError {
+            message: "reprioritize".to_owned(),
+            err,
+        }
+    })
+}
+
+// Create downlink queue cancel request message
+pub fn cancel(channel_id: u32, id: u32) -> Result<Vec<u8>, ProtocolError> {
+    info!("-> {{ {}, cancel, {} }}", channel_id, id);
+    ser::to_vec_packed(&(channel_id, "cancel", id)).map_err(|err| {
+        ProtocolError::MessageCreationError {
+            message: "cancel".to_owned(),
+            err,
+        }
+    })
+}
+
+// Create downlink queue contents request message
+pub fn queue_request(channel_id: u32) -> Result<Vec<u8>, ProtocolError> {
+    info!("-> {{ {}, queue }}", channel_id);
+    ser::to_vec_packed(&(channel_id, "queue")).map_err(|err| ProtocolError::MessageCreationError {
+        message: "queue request".to_owned(),
+        err,
+    })
+}
+
+// Create downlink queue contents response message.
+// Each entry is sent as
+// [ id, path, priority, expires ]
+pub fn queue(channel_id: u32, entries: &[QueueEntry]) -> Result<Vec<u8>, ProtocolError> {
+    info!("-> {{ {}, queue, {} entries }}", channel_id, entries.len());
+
+    let entries: Vec<_> = entries
+        .iter()
+        .map(|entry| (entry.id, &entry.path, entry.priority, entry.expires))
+        .collect();
+
+    ser::to_vec_packed(&(channel_id, "queue", entries)).map_err(|err| {
+        ProtocolError::MessageCreationError {
+            message: "queue".to_owned(),
+            err,
+        }
+    })
+}
+
+// Create downlink request message
+pub fn downlink_request(channel_id: u32) -> Result<Vec<u8>, ProtocolError> {
+    info!("-> {{ {}, downlink }}", channel_id);
+    ser::to_vec_packed(&(channel_id, "downlink")).map_err(|err| {
+        ProtocolError::MessageCreationError {
+            message: "downlink request".to_owned(),
+            err,
+        }
+    })
+}
+
+// Create successful downlink request response message
+//
+// The compression field is only included if the file's chunks are compressed
+pub fn downlink(
+    channel_id: u32,
+    id: u32,
+    path: &str,
+    hash: &str,
+    num_chunks: u32,
+    mode: u32,
+    compression: Compression,
+) -> Result<Vec<u8>, ProtocolError> {
+    info!(
+        "-> {{ {}, downlink, {}, {}, {}, {}, {}, {} }}",
+        channel_id, id, path, hash, num_chunks, mode, compression
+    );
+
+    let result = match compression {
+        Compression::None => {
+            ser::to_vec_packed(&(channel_id, "downlink", id, path, hash, num_chunks, mode))
+        }
+        _ => ser::to_vec_packed(&(
+            channel_id,
+            "downlink",
+            id,
+            path,
+            hash,
+            num_chunks,
+            mode,
+            compression.as_str(),
+        )),
+    };
+
+    result.map_err(|err| ProtocolError::MessageCreationError {
+        message: "downlink".to_owned(),
+        err,
+    })
+}
@@ -15,7 +15,10 @@
 //
 
 use super::Message;
+use crate::compression::Compression;
 use crate::error::ProtocolError;
+use crate::progress::{Direction, TransferState, TransferStatus};
+use crate::queue::QueueEntry;
 use serde_cbor::Value;
 use std::slice::Iter;
 
@@ -48,6 +51,7 @@ pub fn parse_channel_id(message: &Value) -> Result<u32, ProtocolError> {
     }
 }
 
+/// Parse a complete message into its `Message` variant
 pub fn parse_message(message: Value) -> Result<Message, ProtocolError> {
     let raw = match message {
         Value::Array(val) => val.to_owned(),
@@ -72,6 +76,15 @@ pub fn parse_message(message: Value) -> Result<Message, ProtocolError> {
         if let Some(msg) = parse_cleanup_request(channel_id, pieces.to_owned())? {
             return Ok(msg);
         }
+        if let Some(msg) = parse_status(channel_id, pieces.to_owned())? {
+            return Ok(msg);
+        }
+        if let Some(msg) = parse_queue_request(channel_id, pieces.to_owned())? {
+            return Ok(msg);
+        }
+        if let Some(msg) = parse_downlink(channel_id, pieces.to_owned())? {
+            return Ok(msg);
+        }
         if let Some(msg) = parse_export_request(channel_id, pieces.to_owned())? {
             return Ok(msg);
         }
@@ -106,6 +119,19 @@ pub fn parse_message(message: Value) -> Result<Message, ProtocolError> {
     })
 }
 
+// Parse out an optional compression field.
+// A missing field means that the data isn't compressed
+fn parse_compression(message: &str, piece: Option<&Value>) -> Result<Compression, ProtocolError> {
+    match piece {
+        None | Some(Value::Null) => Ok(Compression::None),
+        Some(Value::String(name)) => name.parse(),
+        _ => Err(ProtocolError::InvalidParam(
+            message.to_owned(),
+            "compression".to_owned(),
+        )),
+    }
+}
+
 // Parse out cleanup request
 // { channel_id, "cleanup", [hash] }
 pub fn parse_cleanup_request(
@@ -133,14 +159,260 @@ pub fn parse_cleanup_request(
     Ok(None)
 }
 
+// Parse out status request or response
+// { channel_id, "status" }
+// or
+// { channel_id, "status", [transfer, ...] }
+pub fn parse_status(
+    channel_id: u32,
+    mut pieces: Iter<Value>,
+) -> Result<Option<Message>, ProtocolError> {
+    if let Some(Value::String(op)) = pieces.next() {
+        if op == "status" {
+            let entries = match pieces.next() {
+                None => return Ok(Some(Message::ReqStatus(channel_id))),
+                Some(Value::Array(entries)) => entries,
+                _ => {
+                    return Err(ProtocolError::InvalidParam(
+                        "status".to_owned(),
+                        "transfers".to_owned(),
+                    ));
+                }
+            };
+
+            let transfers = entries
+                .iter()
+                .map(parse_transfer_status)
+                .collect::<Result<Vec<TransferStatus>, ProtocolError>>()?;
+
+            return Ok(Some(Message::Status(channel_id, transfers)));
+        }
+    }
+
+    Ok(None)
+}
+
+// Parse out a single transfer's status
+// [ channel_id, direction, path, hash, chunks_done, chunks_total, bytes_per_sec, state ]
+fn parse_transfer_status(entry: &Value) -> Result<TransferStatus, ProtocolError> {
+    let invalid = |param: &str| ProtocolError::InvalidParam("status".to_owned(), param.to_owned());
+
+    let fields = match entry {
+        Value::Array(fields) if fields.len() == 8 => fields,
+        _ => return Err(invalid("transfer")),
+    };
+
+    let number = |index: usize, param: &str| match fields[index] {
+        Value::U64(val) => Ok(val),
+        _ => Err(invalid(param)),
+    };
+    let string = |index: usize, param: &str| match &fields[index] {
+        Value::String(val) => Ok(val.to_owned()),
+        _ => Err(invalid(param)),
+    };
+
+    Ok(TransferStatus {
+        channel_id: number(0, "channel_id")? as u32,
+        direction: Direction::from_name(&string(1, "direction")?)
+            .ok_or_else(|| invalid("direction"))?,
+        path: string(2, "path")?,
+        hash: string(3, "hash")?,
+        chunks_done: number(4, "chunks_done")? as u32,
+        chunks_total: number(5, "chunks_total")? as u32,
+        bytes_per_sec: number(6, "bytes_per_sec")?,
+        state: TransferState::from_name(&string(7, "state")?).ok_or_else(|| invalid("state"))?,
+    })
+}
+
+// Parse out a required unsigned integer field
+fn parse_number(message: &str, param: &str, piece: Option<&Value>) -> Result<u64, ProtocolError> {
+    match piece {
+        Some(Value::U64(val)) => Ok(*val),
+        Some(_) => Err(ProtocolError::InvalidParam(
+            message.to_owned(),
+            param.to_owned(),
+        )),
+        None => Err(ProtocolError::MissingParam(
+            message.to_owned(),
+            param.to_owned(),
+        )),
+    }
+}
+
+// Parse out a required string field
+fn parse_string(
+    message: &str,
+    param: &str,
+    piece: Option<&Value>,
+) -> Result<String, ProtocolError> {
+    match piece {
+        Some(Value::String(val)) => Ok(val.to_owned()),
+        Some(_) => Err(ProtocolError::InvalidParam(
+            message.to_owned(),
+            param.to_owned(),
+        )),
+        None => Err(ProtocolError::MissingParam(
+            message.to_owned(),
+            param.to_owned(),
+        )),
+    }
+}
+
+// Parse out downlink queue requests and response
+// { channel_id, "enqueue", path, priority [, lifetime] }
+// or
+// { channel_id, "reprioritize", id, priority }
+// or
+// { channel_id, "cancel", id }
+// or
+// { channel_id, "queue" }
+// or
+// { channel_id, "queue", [entry, ...] }
+pub fn parse_queue_request(
+    channel_id: u32,
+    mut pieces: Iter<Value>,
+) -> Result<Option<Message>, ProtocolError> {
+    if let Some(Value::String(op)) = pieces.next() {
+        match op.as_ref() {
+            "enqueue" => {
+                let path = parse_string("enqueue", "path", pieces.next())?;
+                let priority = parse_number("enqueue", "priority", pieces.next())?;
+                let lifetime = match pieces.next() {
+                    None | Some(Value::Null) => None,
+                    Some(Value::U64(val)) => Some(*val),
+                    _ => {
+                        return Err(ProtocolError::InvalidParam(
+                            "enqueue".to_owned(),
+                            "lifetime".to_owned(),
+                        ));
+                    }
+                };
+
+                return Ok(Some(Message::ReqEnqueue(
+                    channel_id,
+                    path,
+                    priority as u32,
+                    lifetime,
+                )));
+            }
+            "reprioritize" => {
+                let id = parse_number("reprioritize", "id", pieces.next())?;
+                let priority = parse_number("reprioritize", "priority", pieces.next())?;
+
+                return Ok(Some(Message::ReqReprioritize(
+                    channel_id,
+                    id as u32,
+                    priority as u32,
+                )));
+            }
+            "cancel" => {
+                let id = parse_number("cancel", "id", pieces.next())?;
+
+                return Ok(Some(Message::ReqCancel(channel_id, id as u32)));
+            }
+            "queue" => {
+                let entries = match pieces.next() {
+                    None => return Ok(Some(Message::ReqQueue(channel_id))),
+                    Some(Value::Array(entries)) => entries,
+                    _ => {
+                        return Err(ProtocolError::InvalidParam(
+                            "queue".to_owned(),
+                            "entries".to_owned(),
+                        ));
+                    }
+                };
+
+                let entries = entries
+                    .iter()
+                    .map(parse_queue_entry)
+                    .collect::<Result<Vec<QueueEntry>, ProtocolError>>()?;
+
+                return Ok(Some(Message::Queue(channel_id, entries)));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(None)
+}
+
+// Parse out a single downlink queue entry
+// [ id, path, priority, expires ]
+fn parse_queue_entry(entry: &Value) -> Result<QueueEntry, ProtocolError> {
+    let fields = match entry {
+        Value::Array(fields) if fields.len() == 4 => fields,
+        _ => {
+            return Err(ProtocolError::InvalidParam(
+                "queue".to_owned(),
+                "entry".to_owned(),
+            ));
+        }
+    };
+
+    let expires = match fields[3] {
+        Value::Null => None,
+        Value::U64(val) => Some(val),
+        _ => {
+            return Err(ProtocolError::InvalidParam(
+                "queue".to_owned(),
+                "expires".to_owned(),
+            ));
+        }
+    };
+
+    Ok(QueueEntry {
+        id: parse_number("queue", "id", fields.first())? as u32,
+        path: parse_string("queue", "path", fields.get(1))?,
+        priority: parse_number("queue", "priority", fields.get(2))? as u32,
+        expires,
+    })
+}
+
+// Parse out downlink request or response
+// { channel_id, "downlink" }
+// or
+// { channel_id, "downlink", id, path, hash, num_chunks, mode [, compression] }
+pub fn parse_downlink(
+    channel_id: u32,
+    mut pieces: Iter<Value>,
+) -> Result<Option<Message>, ProtocolError> {
+    if let Some(Value::String(op)) = pieces.next() {
+        if op == "downlink" {
+            let id = match pieces.next() {
+                None => return Ok(Some(Message::ReqDownlink(channel_id))),
+                piece => parse_number("downlink", "id", piece)?,
+            };
+            let path = parse_string("downlink", "path", pieces.next())?;
+            let hash = parse_string("downlink", "hash", pieces.next())?;
+            let num_chunks = parse_number("downlink", "num chunks", pieces.next())?;
+            let mode = parse_number("downlink", "mode", pieces.next())?;
+            let compression = parse_compression("downlink", pieces.next())?;
+
+            return Ok(Some(Message::Downlink(
+                channel_id,
+                id as u32,
+                path,
+                hash,
+                num_chunks as u32,
+                mode as u32,
+                compression,
+            )));
+        }
+    }
+
+    Ok(None)
+}
+
 // Parse out export request
-// { channel_id, "export", hash, path, [, mode] }
+// { channel_id, "export", hash, path, [, mode [, compression]] }
+// or
+// { channel_id, "export_dir", hash, path, [, mode] }
 pub fn parse_export_request(
     channel_id: u32,
     mut pieces: Iter<Value>,
 ) -> Result<Option<Message>, ProtocolError> {
     if let Some(Value::String(op)) = pieces.next() {
-        if op == "export" {
+        if op == "export" || op == "export_dir" {
             let hash = match pieces.next().ok_or_else(|| {
                 ProtocolError::MissingParam("export".to_owned(), "hash".to_owned())
             })? {
@@ -170,11 +442,23 @@ pub fn parse_export_request(
                 _ => None,
             };
 
+            if op == "export_dir" {
+                return Ok(Some(Message::ReqReceiveDir(
+                    channel_id,
+                    hash.to_owned(),
+                    path.to_owned(),
+                    mode,
+                )));
+            }
+
+            let compression = parse_compression("export", pieces.next())?;
+
             return Ok(Some(Message::ReqReceive(
                 channel_id,
                 hash.to_owned(),
                 path.to_owned(),
                 mode,
+                compression,
             )));
         }
     }
@@ -183,13 +467,15 @@ pub fn parse_export_request(
 }
 
 // Parse out import request
-// { channel_id, "import", path }
+// { channel_id, "import", path [, compression] }
+// or
+// { channel_id, "import_dir", path }
 pub fn parse_import_request(
     channel_id: u32,
     mut pieces: Iter<Value>,
 ) -> Result<Option<Message>, ProtocolError> {
     if let Some(Value::String(op)) = pieces.next() {
-        if op == "import" {
+        if op == "import" || op == "import_dir" {
             let path = match pieces.next().ok_or_else(|| {
                 ProtocolError::MissingParam("export".to_owned(), "hash".to_owned())
             })? {
@@ -201,9 +487,16 @@ pub fn parse_import_request(
                     ));
                 }
             };
+            if op == "import_dir" {
+                return Ok(Some(Message::ReqTransmitDir(channel_id, path.to_owned())));
+            }
+
+            let compression = parse_compression("import", pieces.next())?;
+
             return Ok(Some(Message::ReqTransmit(
                 channel_id as u32,
                 path.to_owned(),
+                compression,
             )));
         }
     }
@@ -240,7 +533,7 @@ pub fn parse_success_receive(
 }
 
 // Parse out success transmit message
-// { channel_id, "true", ..values }
+// { channel_id, "true", hash, num_chunks [, mode [, compression]] }
 pub fn parse_success_transmit(
     channel_id: u32,
     mut pieces: Iter<Value>,
@@ -276,12 +569,15 @@ pub fn parse_success_transmit(
                 _ => None,
             };
 
+            let compression = parse_compression("success", pieces.next())?;
+
             // Return the file info
             return Ok(Some(Message::SuccessTransmit(
                 channel_id,
                 hash.to_string(),
                 num_chunks as u32,
                 mode,
+                compression,
             )));
         }
     }
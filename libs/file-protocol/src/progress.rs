@@ -0,0 +1,296 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+// Progress accounting for file transfers
+//
+// Each transfer tracks which chunks of each of its files (a single file, or a
+// directory's manifest and blobs) are known to be done. The receiver knows this
+// exactly from its temporary storage. The sender counts the chunks it has sent,
+// plus any which the receiver's NAKs show it already had, minus any which the
+// receiver reports as still missing.
+
+use crate::transmit::ChunkRanges;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Instant;
+
+/// Which way a file transfer is going, from the point of view of the reporting side
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+    /// File data is being sent to the remote target
+    Transmit,
+    /// File data is being received from the remote target
+    Receive,
+}
+
+impl Direction {
+    /// The name used for this direction in file protocol messages
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Direction::Transmit => "transmit",
+            Direction::Receive => "receive",
+        }
+    }
+
+    /// Look up a direction by its message name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "transmit" => Some(Direction::Transmit),
+            "receive" => Some(Direction::Receive),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Current stage of a file transfer
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TransferState {
+    /// The transfer has been requested, but no chunks have moved yet
+    Starting,
+    /// File chunks are being sent
+    Transmitting,
+    /// File chunks are being received
+    Receiving,
+    /// All chunks are present and the file is being reassembled
+    Finalizing,
+    /// The transfer completed successfully
+    Done,
+    /// The transfer failed
+    Failed,
+}
+
+impl TransferState {
+    /// The name used for this state in file protocol messages
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TransferState::Starting => "starting",
+            TransferState::Transmitting => "transmitting",
+            TransferState::Receiving => "receiving",
+            TransferState::Finalizing => "finalizing",
+            TransferState::Done => "done",
+            TransferState::Failed => "failed",
+        }
+    }
+
+    /// Look up a state by its message name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "starting" => Some(TransferState::Starting),
+            "transmitting" => Some(TransferState::Transmitting),
+            "receiving" => Some(TransferState::Receiving),
+            "finalizing" => Some(TransferState::Finalizing),
+            "done" => Some(TransferState::Done),
+            "failed" => Some(TransferState::Failed),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TransferState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Snapshot of a file transfer's progress
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransferStatus {
+    /// Transaction identifier
+    pub channel_id: u32,
+    /// Whether we're sending or receiving the file
+    pub direction: Direction,
+    /// Local path of the file being sent, or destination path of the file being received
+    pub path: String,
+    /// BLAKE2s hash of the file (or of the directory's manifest)
+    pub hash: String,
+    /// Number of chunks which have been transferred or were already present
+    pub chunks_done: u32,
+    /// Total number of chunks in the transfer, if known
+    pub chunks_total: u32,
+    /// Average rate of chunk data transferred so far, in bytes per second
+    pub bytes_per_sec: u64,
+    /// Current stage of the transfer
+    pub state: TransferState,
+}
+
+// Running accounting for a single transfer
+pub struct Transfer {
+    channel_id: u32,
+    direction: Direction,
+    path: String,
+    hash: String,
+    state: TransferState,
+    started: Instant,
+    bytes: u64,
+    // Number of chunks in each file which makes up the transfer
+    totals: HashMap<String, u32>,
+    // Chunks of each file which are known to be done
+    done: HashMap<String, ChunkRanges>,
+}
+
+impl Transfer {
+    pub fn new(channel_id: u32, direction: Direction, path: &str, hash: &str) -> Self {
+        Transfer {
+            channel_id,
+            direction,
+            path: path.to_owned(),
+            hash: hash.to_owned(),
+            state: TransferState::Starting,
+            started: Instant::now(),
+            bytes: 0,
+            totals: HashMap::new(),
+            done: HashMap::new(),
+        }
+    }
+
+    pub fn set_state(&mut self, state: TransferState) {
+        self.state = state;
+    }
+
+    // Record the number of chunks in one of the transfer's files
+    pub fn set_total(&mut self, hash: &str, num_chunks: u32) {
+        self.totals.insert(hash.to_owned(), num_chunks);
+    }
+
+    // Record the full list of chunks which are missing from a file.
+    // Everything else is done
+    pub fn set_missing(&mut self, hash: &str, missing: &[(u32, u32)]) {
+        let total = self.totals.get(hash).cloned().unwrap_or(0);
+        let mut done = ChunkRanges::new();
+        done.extend(&[(0, total)]);
+        done.remove(missing);
+        self.done.insert(hash.to_owned(), done);
+    }
+
+    // Record a (possibly partial) list of chunks which the receiver is missing.
+    // Chunks below the end of the list which aren't in it must already be present
+    pub fn report_missing(&mut self, hash: &str, missing: &[(u32, u32)]) {
+        let mut reported = ChunkRanges::new();
+        reported.extend(missing);
+
+        let done = self.done.entry(hash.to_owned()).or_default();
+        if let Some(end) = reported.end() {
+            done.extend(&[(0, end)]);
+        }
+        done.remove(missing);
+    }
+
+    // Record that a chunk has been sent or received
+    pub fn add_chunk(&mut self, hash: &str, index: u32, len: usize) {
+        self.bytes += len as u64;
+        self.done
+            .entry(hash.to_owned())
+            .or_default()
+            .extend(&[(index, index + 1)]);
+    }
+
+    // Mark every chunk of every file as done
+    pub fn complete(&mut self) {
+        for (hash, total) in self.totals.iter() {
+            let mut done = ChunkRanges::new();
+            done.extend(&[(0, *total)]);
+            self.done.insert(hash.clone(), done);
+        }
+    }
+
+    pub fn status(&self) -> TransferStatus {
+        let chunks_total: u32 = self.totals.values().sum();
+        let chunks_done = self
+            .totals
+            .iter()
+            .map(|(hash, total)| match self.done.get(hash) {
+                Some(done) => done.count().min(u64::from(*total)) as u32,
+                None => 0,
+            })
+            .sum();
+
+        let elapsed = self.started.elapsed();
+        let millis = elapsed.as_secs() * 1000 + u64::from(elapsed.subsec_millis());
+        let bytes_per_sec = (self.bytes * 1000).checked_div(millis).unwrap_or(0);
+
+        TransferStatus {
+            channel_id: self.channel_id,
+            direction: self.direction,
+            path: self.path.clone(),
+            hash: self.hash.clone(),
+            chunks_done,
+            chunks_total,
+            bytes_per_sec,
+            state: self.state,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn receiver_progress() {
+        let mut transfer = Transfer::new(1, Direction::Receive, "dest", "hash");
+        transfer.set_total("hash", 10);
+        transfer.set_missing("hash", &[(2, 5), (8, 10)]);
+
+        assert_eq!(transfer.status().chunks_done, 5);
+
+        transfer.add_chunk("hash", 2, 100);
+        transfer.add_chunk("hash", 2, 100);
+        assert_eq!(transfer.status().chunks_done, 6);
+        assert_eq!(transfer.status().chunks_total, 10);
+
+        transfer.complete();
+        assert_eq!(transfer.status().chunks_done, 10);
+    }
+
+    #[test]
+    fn sender_progress() {
+        let mut transfer = Transfer::new(1, Direction::Transmit, "source", "hash");
+        transfer.set_total("hash", 10);
+
+        // The receiver already has chunks 0-3
+        transfer.report_missing("hash", &[(4, 10)]);
+        assert_eq!(transfer.status().chunks_done, 4);
+
+        for index in 4..10 {
+            transfer.add_chunk("hash", index, 100);
+        }
+        assert_eq!(transfer.status().chunks_done, 10);
+
+        // Two of them were lost along the way
+        transfer.report_missing("hash", &[(6, 8)]);
+        assert_eq!(transfer.status().chunks_done, 8);
+    }
+
+    #[test]
+    fn directory_progress() {
+        let mut transfer = Transfer::new(1, Direction::Receive, "dest", "manifest");
+        transfer.set_total("manifest", 1);
+        transfer.set_missing("manifest", &[]);
+        transfer.set_total("blob", 4);
+        transfer.set_missing("blob", &[(0, 4)]);
+
+        let status = transfer.status();
+        assert_eq!(status.chunks_done, 1);
+        assert_eq!(status.chunks_total, 5);
+        assert_eq!(status.hash, "manifest");
+    }
+}
@@ -16,20 +16,31 @@
 
 //! File transfer protocol module
 
+use super::directory;
 use super::messages;
 use super::parsers;
+use super::progress::{Direction, Transfer, TransferState, TransferStatus};
 use super::storage;
+use super::transmit::TransmitState;
+use super::Compression;
 use super::Message;
+use super::QueueEntry;
 use crate::error::ProtocolError;
 use cbor_protocol::Protocol as CborProtocol;
 use log::{info, warn};
 use rand::{self, Rng};
 use serde_cbor::Value;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::net::SocketAddr;
 use std::str;
+use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+// Largest NAK message we'll send. Kept small enough to fit in the receive buffer
+// of a peer using a small chunk size. Longer lists of missing chunks are split
+// across multiple NAKs
+const MAX_NAK_SIZE: usize = 256;
 
 /// Configuration data for Protocol
 #[derive(Clone)]
@@ -39,17 +50,110 @@ pub struct ProtocolConfig {
     // How many times do we read and timeout
     // while in the Hold state before stopping
     hold_count: u16,
+    // Target transmit rate, in bits per second
+    transmit_rate: Option<u32>,
+    // Maximum number of chunks to send before asking the receiver for its status
+    window_size: Option<u32>,
+    // Compression to apply to the files we send
+    compression: Compression,
+    // Called whenever the current transfer's progress changes
+    progress: Option<Arc<dyn Fn(&TransferStatus) + Send + Sync>>,
 }
 
 impl ProtocolConfig {
     /// Creates new ProtocolConfig struct
+    ///
+    /// By default, chunks are sent as quickly as possible and every requested chunk
+    /// is sent before waiting for the receiver's status
     pub fn new(storage_prefix: Option<String>, chunk_size: usize, hold_count: u16) -> Self {
         ProtocolConfig {
             storage_prefix: storage_prefix.unwrap_or_else(|| "file-storage".to_owned()),
             chunk_size,
             hold_count,
+            transmit_rate: None,
+            window_size: None,
+            compression: Compression::None,
+            progress: None,
         }
     }
+
+    /// Set the target rate for sending file chunks, in bits per second
+    ///
+    /// The actual rate will be reduced while the receiver reports lost chunks and will
+    /// recover towards this target once the link is clean again
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use file_protocol::*;
+    ///
+    /// let config = FileProtocolConfig::new(None, 4096, 5).transmit_rate(9600);
+    /// ```
+    ///
+    pub fn transmit_rate(mut self, bits_per_sec: u32) -> Self {
+        self.transmit_rate = Some(bits_per_sec);
+        self
+    }
+
+    /// Set the maximum number of chunks to send before asking the receiver which
+    /// chunks it is still missing
+    ///
+    /// The window shrinks while the receiver reports lost chunks and grows back
+    /// towards this size once the link is clean again
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use file_protocol::*;
+    ///
+    /// let config = FileProtocolConfig::new(None, 4096, 5).window_size(32);
+    /// ```
+    ///
+    pub fn window_size(mut self, chunks: u32) -> Self {
+        self.window_size = Some(chunks.max(1));
+        self
+    }
+
+    /// Set the compression to use for file transfers
+    ///
+    /// Files which are exported are compressed before being split into chunks, and the
+    /// remote target is asked to compress files which are imported. Files which don't
+    /// get any smaller are sent uncompressed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use file_protocol::*;
+    ///
+    /// let config = FileProtocolConfig::new(None, 4096, 5).compression(Compression::Zstd);
+    /// ```
+    ///
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Set a function to be called whenever the progress of a transfer changes
+    ///
+    /// The function is called from within the message engine, so it should return quickly
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use file_protocol::*;
+    ///
+    /// let config = FileProtocolConfig::new(None, 4096, 5).progress(|status| {
+    ///     println!("{}/{} chunks", status.chunks_done, status.chunks_total);
+    /// });
+    /// ```
+    ///
+    pub fn progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&TransferStatus) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
 }
 
 /// File protocol information structure
@@ -57,6 +161,8 @@ pub struct Protocol {
     cbor_proto: CborProtocol,
     remote_addr: Cell<SocketAddr>,
     config: ProtocolConfig,
+    transmit: RefCell<TransmitState>,
+    progress: RefCell<Option<Transfer>>,
 }
 
 /// Current state of the file protocol transaction
@@ -96,6 +202,33 @@ pub enum State {
         /// File mode
         mode: Option<u32>,
     },
+    /// Preparing to receive a directory's chunks
+    StartReceiveDir {
+        /// Destination directory path
+        path: String,
+    },
+    /// Currently receiving a directory
+    ReceivingDir {
+        /// Transaction identifier
+        channel_id: u32,
+        /// Directory manifest hash
+        hash: String,
+        /// Destination directory path
+        path: String,
+        /// Directory mode
+        mode: Option<u32>,
+    },
+    /// All chunks of a directory's manifest and contents have been received
+    ReceivingDirDone {
+        /// Transaction identifier
+        channel_id: u32,
+        /// Directory manifest hash
+        hash: String,
+        /// Destination directory path
+        path: String,
+        /// Directory mode
+        mode: Option<u32>,
+    },
     /// Currenty transmitting a file
     Transmitting,
     /// All file chunks have been transmitted
@@ -131,11 +264,15 @@ impl Protocol {
 
         let c_protocol = CborProtocol::new(&format!("{}:0", host_ip), config.chunk_size);
 
+        let transmit = TransmitState::new(config.transmit_rate, config.window_size);
+
         // Set up the full connection info
         Protocol {
             cbor_proto: c_protocol,
             remote_addr: Cell::new(remote_addr.parse::<SocketAddr>().unwrap()),
             config,
+            transmit: RefCell::new(transmit),
+            progress: RefCell::new(None),
         }
     }
 
@@ -305,21 +442,455 @@ impl Protocol {
         target_path: &str,
         mode: u32,
     ) -> Result<(), ProtocolError> {
+        let compression = storage::load_compression(&self.config.storage_prefix, hash)?;
+
+        self.start_progress(channel_id, Direction::Transmit, target_path, hash);
+        self.track_file(hash);
+
         self.send(&messages::export_request(
             channel_id,
             hash,
             target_path,
             mode,
+            compression,
         )?)?;
 
-        Ok(())
+        Ok(())
+    }
+
+    /// Request a file from a remote target
+    ///
+    /// If compression has been configured, the remote target is asked to compress the file
+    ///
+    /// # Arguments
+    ///
+    /// * source_path - File remote target should send
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, it will return an error message string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use file_protocol::*;
+    ///
+    /// let config = FileProtocolConfig::new(None, 4096, 5);
+    /// let f_protocol = FileProtocol::new("0.0.0.0", "0.0.0.0:7000", config);
+    /// let channel_id = f_protocol.generate_channel().unwrap();
+    ///
+    /// f_protocol.send_import(channel_id, "service.txt");
+    /// ```
+    ///
+    pub fn send_import(&self, channel_id: u32, source_path: &str) -> Result<(), ProtocolError> {
+        self.send(&messages::import_request(
+            channel_id,
+            source_path,
+            self.config.compression,
+        )?)?;
+        Ok(())
+    }
+
+    /// Request remote target to receive a directory from host
+    ///
+    /// # Arguments
+    ///
+    /// * channel_id - Channel ID used for transaction
+    /// * hash - BLAKE2s hash of the directory's manifest
+    /// * target_path - Destination directory path
+    /// * mode - Directory mode
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, it will return an error message string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use file_protocol::*;
+    ///
+    /// let config = FileProtocolConfig::new(None, 4096, 5);
+    /// let f_protocol = FileProtocol::new("0.0.0.0", "0.0.0.0:7000", config);
+    ///
+    /// # ::std::fs::create_dir_all("client_dir").unwrap();
+    ///
+    /// let (hash, _num_chunks, mode) = f_protocol.initialize_directory("client_dir").unwrap();
+    /// let channel_id = f_protocol.generate_channel().unwrap();
+    /// f_protocol.send_export_dir(channel_id, &hash, "final/dir/service_dir", mode);
+    /// ```
+    ///
+    pub fn send_export_dir(
+        &self,
+        channel_id: u32,
+        hash: &str,
+        target_path: &str,
+        mode: u32,
+    ) -> Result<(), ProtocolError> {
+        self.start_progress(channel_id, Direction::Transmit, target_path, hash);
+        self.track_directory(hash, None);
+
+        self.send(&messages::export_dir_request(
+            channel_id,
+            hash,
+            target_path,
+            mode,
+        )?)?;
+
+        Ok(())
+    }
+
+    /// Request a directory from a remote target
+    ///
+    /// # Arguments
+    ///
+    /// * source_path - Directory remote target should send
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, it will return an error message string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use file_protocol::*;
+    ///
+    /// let config = FileProtocolConfig::new(None, 4096, 5);
+    /// let f_protocol = FileProtocol::new("0.0.0.0", "0.0.0.0:7000", config);
+    /// let channel_id = f_protocol.generate_channel().unwrap();
+    ///
+    /// f_protocol.send_import_dir(channel_id, "service_dir");
+    /// ```
+    ///
+    pub fn send_import_dir(&self, channel_id: u32, source_path: &str) -> Result<(), ProtocolError> {
+        self.send(&messages::import_dir_request(channel_id, source_path)?)?;
+        Ok(())
+    }
+
+    /// Request the status of the remote target's active transfers
+    ///
+    /// The remote target will reply with a status message, which can be parsed with
+    /// [`parse_message`](../fn.parse_message.html)
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, it will return an error message string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use file_protocol::*;
+    ///
+    /// let config = FileProtocolConfig::new(None, 4096, 5);
+    /// let f_protocol = FileProtocol::new("0.0.0.0", "0.0.0.0:7000", config);
+    /// let channel_id = f_protocol.generate_channel().unwrap();
+    ///
+    /// f_protocol.send_status_request(channel_id);
+    /// ```
+    ///
+    pub fn send_status_request(&self, channel_id: u32) -> Result<(), ProtocolError> {
+        self.send(&messages::status_request(channel_id)?)
+    }
+
+    /// Send the status of our active transfers to the remote target
+    ///
+    /// # Arguments
+    ///
+    /// * channel_id - Channel ID of the status request
+    /// * transfers - Status of each active transfer
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, it will return an error message string
+    ///
+    pub fn send_status(
+        &self,
+        channel_id: u32,
+        transfers: &[TransferStatus],
+    ) -> Result<(), ProtocolError> {
+        self.send(&messages::status(channel_id, transfers)?)
+    }
+
+    /// Get the progress of the transfer currently being handled by this instance
+    ///
+    /// Returns `None` if no transfer has been started
+    pub fn status(&self) -> Option<TransferStatus> {
+        self.progress
+            .borrow()
+            .as_ref()
+            .map(|transfer| transfer.status())
+    }
+
+    /// Let the remote target know that its request could not be completed
+    ///
+    /// # Arguments
+    ///
+    /// * channel_id - Channel ID of the failed request
+    /// * error_message - Reason for the failure
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, it will return an error message string
+    ///
+    pub fn send_failure(&self, channel_id: u32, error_message: &str) -> Result<(), ProtocolError> {
+        self.send(&messages::operation_failure(channel_id, error_message)?)
+    }
+
+    /// Request that the remote target add a file to its downlink queue
+    ///
+    /// The remote target will reply with the new contents of its queue, which can be
+    /// parsed with [`parse_message`](../fn.parse_message.html)
+    ///
+    /// # Arguments
+    ///
+    /// * channel_id - Channel ID used for transaction
+    /// * path - Path of the file on the remote target
+    /// * priority - Higher priority files are downlinked first
+    /// * lifetime - Number of seconds after which the file should be dropped from the queue,
+    ///   if it still hasn't been downlinked
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, it will return an error message string
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use file_protocol::*;
+    ///
+    /// let config = FileProtocolConfig::new(None, 4096, 5);
+    /// let f_protocol = FileProtocol::new("0.0.0.0", "0.0.0.0:7000", config);
+    /// let channel_id = f_protocol.generate_channel().unwrap();
+    ///
+    /// f_protocol.send_enqueue(channel_id, "/home/system/log/payload.log", 10, Some(86400));
+    /// ```
+    ///
+    pub fn send_enqueue(
+        &self,
+        channel_id: u32,
+        path: &str,
+        priority: u32,
+        lifetime: Option<u64>,
+    ) -> Result<(), ProtocolError> {
+        self.send(&messages::enqueue(channel_id, path, priority, lifetime)?)
+    }
+
+    /// Request that the remote target change the priority of one of its downlink queue entries
+    ///
+    /// The remote target will reply with the new contents of its queue
+    ///
+    /// # Arguments
+    ///
+    /// * channel_id - Channel ID used for transaction
+    /// * id - ID of the queue entry
+    /// * priority - New priority of the entry
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, it will return an error message string
+    ///
+    pub fn send_reprioritize(
+        &self,
+        channel_id: u32,
+        id: u32,
+        priority: u32,
+    ) -> Result<(), ProtocolError> {
+        self.send(&messages::reprioritize(channel_id, id, priority)?)
+    }
+
+    /// Request that the remote target remove an entry from its downlink queue
+    ///
+    /// The remote target will reply with the new contents of its queue
+    ///
+    /// # Arguments
+    ///
+    /// * channel_id - Channel ID used for transaction
+    /// * id - ID of the queue entry
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, it will return an error message string
+    ///
+    pub fn send_cancel(&self, channel_id: u32, id: u32) -> Result<(), ProtocolError> {
+        self.send(&messages::cancel(channel_id, id)?)
+    }
+
+    /// Request the contents of the remote target's downlink queue
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, it will return an error message string
+    ///
+    pub fn send_queue_request(&self, channel_id: u32) -> Result<(), ProtocolError> {
+        self.send(&messages::queue_request(channel_id)?)
+    }
+
+    /// Send the contents of our downlink queue to the remote target
+    ///
+    /// # Arguments
+    ///
+    /// * channel_id - Channel ID of the queue request
+    /// * entries - Queue entries, in downlink order
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, it will return an error message string
+    ///
+    pub fn send_queue(&self, channel_id: u32, entries: &[QueueEntry]) -> Result<(), ProtocolError> {
+        self.send(&messages::queue(channel_id, entries)?)
+    }
+
+    /// Request the next file in the remote target's downlink queue
+    ///
+    /// The remote target will reply with either a downlink message describing the file,
+    /// which should be passed to [`process_message`](#method.process_message) with a
+    /// `StartReceive` state, or a failure message if its queue is empty
+    ///
+    /// # Errors
+    ///
+    /// If this function encounters any errors, it will return an error message string
+    ///
+    pub fn send_downlink_request(&self, channel_id: u32) -> Result<(), ProtocolError> {
+        self.send(&messages::downlink_request(channel_id)?)
+    }
+
+    /// Prepare a downlink queue entry for transmission and tell the remote target about it
+    ///
+    /// Once this succeeds, the transfer should be continued by running the
+    /// [`message_engine`](#method.message_engine) from the `Transmitting` state.
+    /// Any chunks left in temporary storage by a previous, incomplete, downlink of the
+    /// same file are reused
+    ///
+    /// # Arguments
+    ///
+    /// * channel_id - Channel ID of the downlink request
+    /// * entry - Queue entry to downlink
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be prepared, a failure message is sent to the remote target
+    /// and the error is returned
+    ///
+    pub fn send_downlink(&self, channel_id: u32, entry: &QueueEntry) -> Result<(), ProtocolError> {
+        self.start_progress(channel_id, Direction::Transmit, &entry.path, "");
+
+        let prefix = &self.config.storage_prefix;
+        let result = storage::initialize_file(
+            prefix,
+            &entry.path,
+            self.config.chunk_size,
+            self.config.compression,
+        )
+        .and_then(|(hash, num_chunks, mode)| {
+            // The file may have been left uncompressed if it didn't shrink
+            let used = storage::load_compression(prefix, &hash)?;
+            Ok((hash, num_chunks, mode, used))
+        });
+
+        match result {
+            Ok((hash, num_chunks, mode, used)) => {
+                self.start_progress(channel_id, Direction::Transmit, &entry.path, &hash);
+                self.update_progress(|transfer| transfer.set_total(&hash, num_chunks));
+                self.send(&messages::downlink(
+                    channel_id,
+                    entry.id,
+                    &entry.path,
+                    &hash,
+                    num_chunks,
+                    mode,
+                    used,
+                )?)
+            }
+            Err(error) => {
+                self.update_progress(|transfer| transfer.set_state(TransferState::Failed));
+                self.send(&messages::operation_failure(
+                    channel_id,
+                    &format!("{}", error),
+                )?)?;
+                Err(error)
+            }
+        }
+    }
+
+    // Begin tracking the progress of a new transfer
+    fn start_progress(&self, channel_id: u32, direction: Direction, path: &str, hash: &str) {
+        *self.progress.borrow_mut() = Some(Transfer::new(channel_id, direction, path, hash));
+        self.update_progress(|_| {});
     }
 
-    /// Request a file from a remote target
+    // Update the current transfer's progress and report it to any interested party
+    fn update_progress<F>(&self, update: F)
+    where
+        F: FnOnce(&mut Transfer),
+    {
+        let status = match self.progress.borrow_mut().as_mut() {
+            Some(transfer) => {
+                update(transfer);
+                transfer.status()
+            }
+            None => return,
+        };
+
+        if let Some(callback) = &self.config.progress {
+            callback(&status);
+        }
+    }
+
+    // Record the size of a single file transfer
+    fn track_file(&self, hash: &str) {
+        if let Ok(num_chunks) = storage::load_meta(&self.config.storage_prefix, hash) {
+            self.update_progress(|transfer| transfer.set_total(hash, num_chunks));
+        }
+    }
+
+    // Record the size of a directory transfer. When receiving, also record the
+    // missing chunks of each of its incomplete pieces
+    fn track_directory(&self, hash: &str, missing: Option<&[(String, Vec<u32>)]>) {
+        let prefix = &self.config.storage_prefix;
+        let mut totals = vec![];
+        if let Ok(num_chunks) = storage::load_meta(prefix, hash) {
+            totals.push((hash.to_owned(), num_chunks));
+        }
+
+        // The blobs are only known once the manifest has been received
+        let manifest_missing = missing
+            .map(|missing| missing.iter().any(|(piece, _)| piece == hash))
+            .unwrap_or(false);
+        if !manifest_missing {
+            if let Ok(manifest) = directory::load_manifest(prefix, hash) {
+                totals.extend(
+                    manifest
+                        .blobs
+                        .iter()
+                        .map(|blob| (blob.hash.clone(), blob.num_chunks)),
+                );
+            }
+        }
+
+        self.update_progress(|transfer| {
+            for (piece, num_chunks) in totals.iter() {
+                transfer.set_total(piece, *num_chunks);
+
+                if let Some(missing) = missing {
+                    let chunks = missing
+                        .iter()
+                        .find(|(missing_piece, _)| missing_piece == piece)
+                        .map(|(_, chunks)| to_ranges(chunks))
+                        .unwrap_or_else(Vec::new);
+                    transfer.set_missing(piece, &chunks);
+                }
+            }
+        });
+    }
+
+    /// Prepare a file for transfer
+    ///
+    /// Imports the file into temporary storage and calculates the BLAKE2s hash.
+    /// The hash always covers the original file contents, even if the stored
+    /// chunks are compressed
     ///
     /// # Arguments
     ///
-    /// * source_path - File remote target should send
+    /// * source_path - File to initialize for transfer
     ///
     /// # Errors
     ///
@@ -332,23 +903,32 @@ impl Protocol {
     ///
     /// let config = FileProtocolConfig::new(None, 4096, 5);
     /// let f_protocol = FileProtocol::new("0.0.0.0", "0.0.0.0:7000", config);
-    /// let channel_id = f_protocol.generate_channel().unwrap();
     ///
-    /// f_protocol.send_import(channel_id, "service.txt");
+    /// # ::std::fs::File::create("client.txt").unwrap();
+    ///
+    /// let (_hash, _num_chunks, _mode) = f_protocol.initialize_file("client.txt").unwrap();
     /// ```
     ///
-    pub fn send_import(&self, channel_id: u32, source_path: &str) -> Result<(), ProtocolError> {
-        self.send(&messages::import_request(channel_id, source_path)?)?;
-        Ok(())
+    pub fn initialize_file(&self, source_path: &str) -> Result<(String, u32, u32), ProtocolError> {
+        storage::initialize_file(
+            &self.config.storage_prefix,
+            source_path,
+            self.config.chunk_size,
+            self.config.compression,
+        )
     }
 
-    /// Prepare a file for transfer
+    /// Prepare a directory for transfer
+    ///
+    /// Recursively imports the directory's contents into temporary storage and
+    /// builds the manifest describing them. Small files are packed together and
+    /// duplicate contents are only stored once.
     ///
-    /// Imports the file into temporary storage and calculates the BLAKE2s hash
+    /// Returns the manifest's BLAKE2s hash and number of chunks, and the directory's mode
     ///
     /// # Arguments
     ///
-    /// * source_path - File to initialize for transfer
+    /// * source_path - Directory to initialize for transfer
     ///
     /// # Errors
     ///
@@ -362,19 +942,78 @@ impl Protocol {
     /// let config = FileProtocolConfig::new(None, 4096, 5);
     /// let f_protocol = FileProtocol::new("0.0.0.0", "0.0.0.0:7000", config);
     ///
-    /// # ::std::fs::File::create("client.txt").unwrap();
+    /// # ::std::fs::create_dir_all("client_dir").unwrap();
     ///
-    /// let (_hash, _num_chunks, _mode) = f_protocol.initialize_file("client.txt").unwrap();
+    /// let (_hash, _num_chunks, _mode) = f_protocol.initialize_directory("client_dir").unwrap();
     /// ```
     ///
-    pub fn initialize_file(&self, source_path: &str) -> Result<(String, u32, u32), ProtocolError> {
-        storage::initialize_file(
+    pub fn initialize_directory(
+        &self,
+        source_path: &str,
+    ) -> Result<(String, u32, u32), ProtocolError> {
+        directory::initialize_directory(
             &self.config.storage_prefix,
             source_path,
             self.config.chunk_size,
         )
     }
 
+    // Remove the temporary storage for a file or directory transfer
+    fn delete_transfer(&self, hash: &str) -> Result<(), ProtocolError> {
+        if directory::is_directory(&self.config.storage_prefix, hash) {
+            directory::delete_directory(&self.config.storage_prefix, hash)
+        } else {
+            storage::delete_file(&self.config.storage_prefix, hash)
+        }
+    }
+
+    // Check whether all of the pieces of a directory have been received.
+    // Sends an ACK if they have, otherwise sends a NAK for each incomplete piece
+    fn check_directory(
+        &self,
+        channel_id: u32,
+        hash: &str,
+        num_chunks: Option<u32>,
+    ) -> Result<bool, ProtocolError> {
+        let missing = directory::validate_directory(&self.config.storage_prefix, hash, num_chunks)?;
+        self.track_directory(hash, Some(&missing));
+
+        if missing.is_empty() {
+            self.send_ack(channel_id, &hash, num_chunks)?;
+            return Ok(true);
+        }
+
+        for (piece_hash, chunks) in missing.iter() {
+            self.send_nak(channel_id, &piece_hash, &chunks)?;
+        }
+
+        Ok(false)
+    }
+
+    // Rebuild a received directory in its requested permanent location.
+    // Notify the connection peer of the results
+    fn finalize_directory(
+        &self,
+        channel_id: u32,
+        hash: &str,
+        target_path: &str,
+        mode: Option<u32>,
+    ) -> Result<(), ProtocolError> {
+        match directory::finalize_directory(&self.config.storage_prefix, hash, target_path, mode) {
+            Ok(_) => {
+                self.update_progress(|transfer| transfer.set_state(TransferState::Done));
+                self.send(&messages::operation_success(channel_id, hash)?)?;
+                self.delete_transfer(hash)?;
+                Ok(())
+            }
+            Err(e) => {
+                self.update_progress(|transfer| transfer.set_state(TransferState::Failed));
+                self.send(&messages::operation_failure(channel_id, &format!("{}", e))?)?;
+                Err(e)
+            }
+        }
+    }
+
     // Verify the integrity of received file data and then transfer into the requested permanent file location.
     // Notify the connection peer of the results
     //
@@ -391,38 +1030,163 @@ impl Protocol {
     ) -> Result<(), ProtocolError> {
         match storage::finalize_file(&self.config.storage_prefix, hash, target_path, mode) {
             Ok(_) => {
+                self.update_progress(|transfer| transfer.set_state(TransferState::Done));
                 self.send(&messages::operation_success(channel_id, hash)?)?;
                 storage::delete_file(&self.config.storage_prefix, hash)?;
                 Ok(())
             }
             Err(e) => {
+                self.update_progress(|transfer| transfer.set_state(TransferState::Failed));
                 self.send(&messages::operation_failure(channel_id, &format!("{}", e))?)?;
                 Err(e)
             }
         }
     }
 
-    // Send all requested chunks of a file to the remote destination
+    // Set up to receive a file (or directory) which the remote target is ready to send
+    // and ask it for whichever chunks we don't already have
+    fn start_receive(
+        &self,
+        channel_id: u32,
+        hash: &str,
+        num_chunks: u32,
+        mode: Option<u32>,
+        compression: Compression,
+        state: &State,
+    ) -> Result<State, ProtocolError> {
+        match state {
+            State::StartReceive { path } | State::StartReceiveDir { path } => {
+                self.start_progress(channel_id, Direction::Receive, path, hash);
+                self.update_progress(|transfer| transfer.set_total(hash, num_chunks));
+            }
+            _ => {}
+        }
+
+        // TODO: handle channel_id mismatch
+        if let State::StartReceiveDir { path } = state {
+            return Ok(
+                if self.check_directory(channel_id, hash, Some(num_chunks))? {
+                    State::ReceivingDirDone {
+                        channel_id,
+                        hash: hash.to_string(),
+                        path: path.to_string(),
+                        mode,
+                    }
+                } else {
+                    State::ReceivingDir {
+                        channel_id,
+                        hash: hash.to_string(),
+                        path: path.to_string(),
+                        mode,
+                    }
+                },
+            );
+        }
+
+        storage::store_compression(&self.config.storage_prefix, hash, compression)?;
+
+        match storage::validate_file(&self.config.storage_prefix, hash, Some(num_chunks))? {
+            (true, _) => {
+                self.send_ack(channel_id, hash, Some(num_chunks))?;
+                Ok(match state.clone() {
+                    State::StartReceive { path } => State::ReceivingDone {
+                        channel_id,
+                        hash: hash.to_string(),
+                        path,
+                        mode,
+                    },
+                    _ => State::Done,
+                })
+            }
+            (false, chunks) => {
+                self.send_nak(channel_id, hash, &chunks)?;
+                Ok(match state.clone() {
+                    State::StartReceive { path } => State::Receiving {
+                        channel_id,
+                        hash: hash.to_string(),
+                        path,
+                        mode,
+                    },
+                    _ => state.clone(),
+                })
+            }
+        }
+    }
+
+    // Let the remote destination know that we have all of a file's chunks
+    fn send_ack(
+        &self,
+        channel_id: u32,
+        hash: &str,
+        num_chunks: Option<u32>,
+    ) -> Result<(), ProtocolError> {
+        self.send(&messages::ack(channel_id, hash, num_chunks)?)?;
+        self.update_progress(|transfer| {
+            transfer.complete();
+            transfer.set_state(TransferState::Finalizing);
+        });
+        Ok(())
+    }
+
+    // Send the list of missing chunk ranges to the remote destination,
+    // split across as many NAKs as needed
+    fn send_nak(&self, channel_id: u32, hash: &str, chunks: &[u32]) -> Result<(), ProtocolError> {
+        self.update_progress(|transfer| transfer.set_missing(hash, &to_ranges(chunks)));
+
+        for message in messages::nak(channel_id, hash, chunks, MAX_NAK_SIZE)? {
+            self.send(&message)?;
+        }
+        Ok(())
+    }
+
+    // Send requested chunks of a file to the remote destination
+    //
+    // The requested chunks are queued and sent one window at a time, paced to the
+    // current transmit rate. If windowing is enabled, a sync message is sent after
+    // each window so that the receiver immediately replies with its updated status
     fn send_chunks(
         &self,
         channel_id: u32,
         hash: &str,
         chunks: &[(u32, u32)],
     ) -> Result<(), ProtocolError> {
-        for (first, last) in chunks {
-            for chunk_index in *first..*last {
-                match storage::load_chunk(&self.config.storage_prefix, hash, chunk_index) {
-                    Ok(c) => self.send(&messages::chunk(channel_id, hash, chunk_index, &c)?)?,
-                    Err(e) => {
-                        warn!("Failed to load chunk {}:{} : {}", hash, chunk_index, e);
-                        storage::delete_file(&self.config.storage_prefix, hash)?;
-                        return Err(ProtocolError::CorruptFile(hash.to_string()));
-                    }
-                };
+        let (window, windowed) = {
+            let mut transmit = self.transmit.borrow_mut();
+            transmit.request(hash, chunks);
+            (transmit.next_window(hash), transmit.window().is_some())
+        };
+
+        for chunk_index in window.iter() {
+            let start = Instant::now();
+
+            let message = match storage::load_chunk(&self.config.storage_prefix, hash, *chunk_index)
+            {
+                Ok(c) => {
+                    self.update_progress(|transfer| {
+                        transfer.set_state(TransferState::Transmitting);
+                        transfer.add_chunk(hash, *chunk_index, c.len());
+                    });
+                    messages::chunk(channel_id, hash, *chunk_index, &c)?
+                }
+                Err(e) => {
+                    warn!("Failed to load chunk {}:{} : {}", hash, chunk_index, e);
+                    storage::delete_file(&self.config.storage_prefix, hash)?;
+                    return Err(ProtocolError::CorruptFile(hash.to_string()));
+                }
+            };
+
+            self.send(&message)?;
 
-                thread::sleep(Duration::from_millis(1));
+            let delay = self.transmit.borrow().delay(message.len());
+            if let Some(remaining) = delay.checked_sub(start.elapsed()) {
+                thread::sleep(remaining);
             }
         }
+
+        if windowed && !window.is_empty() {
+            self.send(&messages::sync(channel_id, hash)?)?;
+        }
+
         Ok(())
     }
 
@@ -484,7 +1248,7 @@ impl Protocol {
                     } => {
                         match storage::validate_file(&self.config.storage_prefix, &hash, None) {
                             Ok((true, _)) => {
-                                self.send(&messages::ack(channel_id, &hash, None)?)?;
+                                self.send_ack(channel_id, &hash, None)?;
                                 state = State::ReceivingDone {
                                     channel_id,
                                     hash: hash.clone(),
@@ -493,7 +1257,7 @@ impl Protocol {
                                 };
                             }
                             Ok((false, chunks)) => {
-                                self.send(&messages::nak(channel_id, &hash, &chunks)?)?;
+                                self.send_nak(channel_id, &hash, &chunks)?;
                                 state = State::Holding {
                                     count: 0,
                                     prev_state: Box::new(state.clone()),
@@ -529,6 +1293,48 @@ impl Protocol {
                         self.finalize_file(channel_id, &hash, &path, mode)?;
                         return Ok(());
                     }
+                    State::ReceivingDir {
+                        channel_id,
+                        hash,
+                        path,
+                        mode,
+                    } => {
+                        if !self.check_directory(channel_id, &hash, None)? {
+                            state = State::Holding {
+                                count: 0,
+                                prev_state: Box::new(state.clone()),
+                            };
+                            continue;
+                        }
+
+                        match self.finalize_directory(channel_id, &hash, &path, mode) {
+                            Ok(_) => {
+                                return Ok(());
+                            }
+                            Err(e) => {
+                                warn!("Failed to finalize directory {} as {}: {}", hash, path, e);
+                                state = State::Holding {
+                                    count: 0,
+                                    prev_state: Box::new(State::ReceivingDirDone {
+                                        channel_id,
+                                        hash,
+                                        path,
+                                        mode,
+                                    }),
+                                };
+                                continue;
+                            }
+                        }
+                    }
+                    State::ReceivingDirDone {
+                        channel_id,
+                        hash,
+                        path,
+                        mode,
+                    } => {
+                        self.finalize_directory(channel_id, &hash, &path, mode)?;
+                        return Ok(());
+                    }
                     State::Done => {
                         return Ok(());
                     }
@@ -576,6 +1382,17 @@ impl Protocol {
                     self.finalize_file(channel_id, &hash, &path, mode)?;
                     return Ok(());
                 }
+                State::ReceivingDirDone {
+                    channel_id,
+                    hash,
+                    path,
+                    mode,
+                } => {
+                    // We've got the manifest and all of the directory's contents.
+                    // Rebuild the directory tree, verifying each piece's hash
+                    self.finalize_directory(channel_id, &hash, &path, mode)?;
+                    return Ok(());
+                }
                 State::Done => return Ok(()),
                 _ => continue,
             };
@@ -622,7 +1439,52 @@ impl Protocol {
                 match &parsed_message {
                     Message::Sync(channel_id, hash) => {
                         info!("<- {{ {}, {} }}", channel_id, hash);
-                        new_state = state.clone();
+                        // The sender has finished a window of chunks and wants to know
+                        // what we're still missing
+                        new_state = match state {
+                            State::Receiving {
+                                channel_id,
+                                hash,
+                                path,
+                                mode,
+                            } => match storage::validate_file(
+                                &self.config.storage_prefix,
+                                hash,
+                                None,
+                            )? {
+                                (true, _) => {
+                                    self.send_ack(*channel_id, &hash, None)?;
+                                    State::ReceivingDone {
+                                        channel_id: *channel_id,
+                                        hash: hash.to_string(),
+                                        path: path.to_string(),
+                                        mode: *mode,
+                                    }
+                                }
+                                (false, chunks) => {
+                                    self.send_nak(*channel_id, &hash, &chunks)?;
+                                    state.clone()
+                                }
+                            },
+                            State::ReceivingDir {
+                                channel_id,
+                                hash,
+                                path,
+                                mode,
+                            } => {
+                                if self.check_directory(*channel_id, hash, None)? {
+                                    State::ReceivingDirDone {
+                                        channel_id: *channel_id,
+                                        hash: hash.to_string(),
+                                        path: path.to_string(),
+                                        mode: *mode,
+                                    }
+                                } else {
+                                    state.clone()
+                                }
+                            }
+                            _ => state.clone(),
+                        };
                     }
                     Message::Metadata(channel_id, hash, num_chunks) => {
                         info!("<- {{ {}, {}, {} }}", channel_id, hash, num_chunks);
@@ -642,10 +1504,18 @@ impl Protocol {
                             *chunk_num,
                             &data,
                         )?;
+                        self.update_progress(|transfer| {
+                            transfer.set_state(TransferState::Receiving);
+                            transfer.add_chunk(hash, *chunk_num, data.len());
+                        });
                         new_state = state.clone();
                     }
                     Message::ACK(_channel_id, ack_hash) => {
                         info!("<- {{ {}, true }}", ack_hash);
+                        self.update_progress(|transfer| {
+                            transfer.complete();
+                            transfer.set_state(TransferState::Finalizing);
+                        });
                         // TODO: Figure out hash verification here
                         new_state = State::TransmittingDone;
                     }
@@ -654,6 +1524,9 @@ impl Protocol {
                             "<- {{ {}, {}, false, {:?} }}",
                             channel_id, hash, missing_chunks
                         );
+                        self.update_progress(|transfer| {
+                            transfer.report_missing(hash, missing_chunks)
+                        });
                         match self.send_chunks(*channel_id, &hash, &missing_chunks) {
                             Ok(()) => {}
                             Err(error) => self.send(&messages::operation_failure(
@@ -668,17 +1541,26 @@ impl Protocol {
                         // TODO: Maybe trigger a failure?
                         new_state = state.clone();
                     }
-                    Message::ReqReceive(channel_id, hash, path, mode) => {
+                    Message::ReqReceive(channel_id, hash, path, mode, compression) => {
                         info!(
-                            "<- {{ {}, export, {}, {}, {:?} }}",
-                            channel_id, hash, path, mode
+                            "<- {{ {}, export, {}, {}, {:?}, {} }}",
+                            channel_id, hash, path, mode, compression
                         );
+                        // Any chunks we already have are only usable if they were
+                        // compressed the same way
+                        storage::store_compression(
+                            &self.config.storage_prefix,
+                            hash,
+                            *compression,
+                        )?;
+                        self.start_progress(*channel_id, Direction::Receive, path, hash);
+                        self.track_file(hash);
                         // The client wants to send us a file.
                         // See what state the file is currently in on our side
                         match storage::validate_file(&self.config.storage_prefix, hash, None) {
                             Ok((true, _)) => {
                                 // We've already got all the file data in temporary storage
-                                self.send(&messages::ack(*channel_id, &hash, None)?)?;
+                                self.send_ack(*channel_id, &hash, None)?;
 
                                 new_state = State::ReceivingDone {
                                     channel_id: *channel_id,
@@ -689,7 +1571,7 @@ impl Protocol {
                             }
                             Ok((false, chunks)) => {
                                 // We're missing some number of data chunks of the requrested file
-                                self.send(&messages::nak(*channel_id, &hash, &chunks)?)?;
+                                self.send_nak(*channel_id, &hash, &chunks)?;
                                 new_state = State::Receiving {
                                     channel_id: *channel_id,
                                     hash: hash.to_string(),
@@ -700,22 +1582,99 @@ impl Protocol {
                             Err(e) => return Err(e),
                         }
                     }
-                    Message::ReqTransmit(channel_id, path) => {
-                        info!("<- {{ {}, import, {} }}", channel_id, path);
-                        // Set up the requested file for transmission
-                        match self.initialize_file(path) {
+                    Message::ReqReceiveDir(channel_id, hash, path, mode) => {
+                        info!(
+                            "<- {{ {}, export_dir, {}, {}, {:?} }}",
+                            channel_id, hash, path, mode
+                        );
+                        self.start_progress(*channel_id, Direction::Receive, path, hash);
+                        // The client wants to send us a directory.
+                        // Request any pieces of it which we don't already have
+                        new_state = if self.check_directory(*channel_id, hash, None)? {
+                            State::ReceivingDirDone {
+                                channel_id: *channel_id,
+                                hash: hash.to_string(),
+                                path: path.to_string(),
+                                mode: *mode,
+                            }
+                        } else {
+                            State::ReceivingDir {
+                                channel_id: *channel_id,
+                                hash: hash.to_string(),
+                                path: path.to_string(),
+                                mode: *mode,
+                            }
+                        };
+                    }
+                    Message::ReqTransmitDir(channel_id, path) => {
+                        info!("<- {{ {}, import_dir, {} }}", channel_id, path);
+                        // Set up the requested directory for transmission
+                        self.start_progress(*channel_id, Direction::Transmit, path, "");
+                        match self.initialize_directory(path) {
                             Ok((hash, num_chunks, mode)) => {
+                                self.start_progress(*channel_id, Direction::Transmit, path, &hash);
+                                self.track_directory(&hash, None);
+                                self.send(&messages::import_setup_success(
+                                    *channel_id,
+                                    &hash,
+                                    num_chunks,
+                                    mode,
+                                    Compression::None,
+                                )?)?;
+
+                                new_state = State::Transmitting;
+                            }
+                            Err(error) => {
+                                self.update_progress(|transfer| {
+                                    transfer.set_state(TransferState::Failed)
+                                });
+                                self.send(&messages::operation_failure(
+                                    *channel_id,
+                                    &format!("{}", error),
+                                )?)?;
+
+                                new_state = State::Done;
+                            }
+                        }
+                    }
+                    Message::ReqTransmit(channel_id, path, compression) => {
+                        info!("<- {{ {}, import, {}, {} }}", channel_id, path, compression);
+                        self.start_progress(*channel_id, Direction::Transmit, path, "");
+                        // Set up the requested file for transmission, using the
+                        // compression the requester asked for
+                        let result = storage::initialize_file(
+                            &self.config.storage_prefix,
+                            path,
+                            self.config.chunk_size,
+                            *compression,
+                        )
+                        .and_then(|(hash, num_chunks, mode)| {
+                            // The file may have been left uncompressed if it didn't shrink
+                            let used =
+                                storage::load_compression(&self.config.storage_prefix, &hash)?;
+                            Ok((hash, num_chunks, mode, used))
+                        });
+                        match result {
+                            Ok((hash, num_chunks, mode, used)) => {
+                                self.start_progress(*channel_id, Direction::Transmit, path, &hash);
+                                self.update_progress(|transfer| {
+                                    transfer.set_total(&hash, num_chunks)
+                                });
                                 // It worked, let the requester know we're ready to send
                                 self.send(&messages::import_setup_success(
                                     *channel_id,
                                     &hash,
                                     num_chunks,
                                     mode,
+                                    used,
                                 )?)?;
 
                                 new_state = State::Transmitting;
                             }
                             Err(error) => {
+                                self.update_progress(|transfer| {
+                                    transfer.set_state(TransferState::Failed)
+                                });
                                 // It failed. Let the requester know that we can't transmit
                                 // the file they want.
                                 self.send(&messages::operation_failure(
@@ -729,55 +1688,56 @@ impl Protocol {
                     }
                     Message::SuccessReceive(channel_id, hash) => {
                         info!("<- {{ {}, true }}", channel_id);
+                        self.update_progress(|transfer| transfer.set_state(TransferState::Done));
                         new_state = State::Done;
-                        storage::delete_file(&self.config.storage_prefix, hash)?;
+                        self.delete_transfer(hash)?;
                     }
-                    Message::SuccessTransmit(channel_id, hash, num_chunks, mode) => {
+                    Message::SuccessTransmit(channel_id, hash, num_chunks, mode, compression) => {
                         match mode {
                             Some(value) => info!(
-                                "<- {{ {}, true, {}, {}, {} }}",
-                                channel_id, hash, num_chunks, value
+                                "<- {{ {}, true, {}, {}, {}, {} }}",
+                                channel_id, hash, num_chunks, value, compression
                             ),
                             None => {
                                 info!("<- {{ {}, true, {}, {} }}", channel_id, hash, num_chunks)
                             }
                         }
 
-                        // TODO: handle channel_id mismatch
-                        match storage::validate_file(
-                            &self.config.storage_prefix,
+                        new_state = self.start_receive(
+                            *channel_id,
                             hash,
-                            Some(*num_chunks),
-                        ) {
-                            Ok((true, _)) => {
-                                self.send(&messages::ack(*channel_id, &hash, Some(*num_chunks))?)?;
-                                new_state = match state.clone() {
-                                    State::StartReceive { path } => State::ReceivingDone {
-                                        channel_id: *channel_id,
-                                        hash: hash.to_string(),
-                                        path: path.to_string(),
-                                        mode: *mode,
-                                    },
-                                    _ => State::Done,
-                                };
-                            }
-                            Ok((false, chunks)) => {
-                                self.send(&messages::nak(*channel_id, &hash, &chunks)?)?;
-                                new_state = match state.clone() {
-                                    State::StartReceive { path } => State::Receiving {
-                                        channel_id: *channel_id,
-                                        hash: hash.to_string(),
-                                        path: path.to_string(),
-                                        mode: *mode,
-                                    },
-                                    _ => state.clone(),
-                                };
-                            }
-                            Err(e) => return Err(e),
-                        }
+                            *num_chunks,
+                            *mode,
+                            *compression,
+                            state,
+                        )?;
+                    }
+                    Message::Downlink(
+                        channel_id,
+                        id,
+                        path,
+                        hash,
+                        num_chunks,
+                        mode,
+                        compression,
+                    ) => {
+                        info!(
+                            "<- {{ {}, downlink, {}, {}, {}, {}, {}, {} }}",
+                            channel_id, id, path, hash, num_chunks, mode, compression
+                        );
+
+                        new_state = self.start_receive(
+                            *channel_id,
+                            hash,
+                            *num_chunks,
+                            Some(*mode),
+                            *compression,
+                            state,
+                        )?;
                     }
                     Message::Failure(channel_id, error_message) => {
                         info!("<- {{ {}, false, {} }}", channel_id, error_message);
+                        self.update_progress(|transfer| transfer.set_state(TransferState::Failed));
                         return Err(ProtocolError::TransmissionError {
                             channel_id: *channel_id,
                             error_message: error_message.to_string(),
@@ -785,7 +1745,7 @@ impl Protocol {
                     }
                     Message::Cleanup(channel_id, Some(hash)) => {
                         info!("<- {{ {}, cleanup, {} }}", channel_id, hash);
-                        storage::delete_file(&self.config.storage_prefix, hash)?;
+                        self.delete_transfer(hash)?;
                         new_state = State::Done;
                     }
                     Message::Cleanup(channel_id, None) => {
@@ -793,9 +1753,50 @@ impl Protocol {
                         storage::delete_storage(&self.config.storage_prefix)?;
                         new_state = State::Done;
                     }
+                    Message::ReqStatus(channel_id) => {
+                        info!("<- {{ {}, status }}", channel_id);
+                        // We only know about our own transfer
+                        let transfers: Vec<TransferStatus> = self.status().into_iter().collect();
+                        self.send_status(*channel_id, &transfers)?;
+                        new_state = State::Done;
+                    }
+                    Message::ReqEnqueue(channel_id, ..)
+                    | Message::ReqReprioritize(channel_id, ..)
+                    | Message::ReqCancel(channel_id, ..)
+                    | Message::ReqQueue(channel_id)
+                    | Message::ReqDownlink(channel_id) => {
+                        info!("<- {{ {}, downlink queue request }}", channel_id);
+                        // The downlink queue belongs to the file transfer service
+                        self.send(&messages::operation_failure(
+                            *channel_id,
+                            "No downlink queue available",
+                        )?)?;
+                        new_state = State::Done;
+                    }
+                    Message::Queue(channel_id, entries) => {
+                        info!("<- {{ {}, queue, {} entries }}", channel_id, entries.len());
+                        new_state = state.clone();
+                    }
+                    Message::Status(channel_id, transfers) => {
+                        info!(
+                            "<- {{ {}, status, {} transfers }}",
+                            channel_id,
+                            transfers.len()
+                        );
+                        new_state = state.clone();
+                    }
                 }
                 Ok(new_state)
             }
         }
     }
 }
+
+// Convert a flat list of missing chunk range boundaries into pairs
+fn to_ranges(chunks: &[u32]) -> Vec<(u32, u32)> {
+    chunks
+        .chunks(2)
+        .filter(|range| range.len() == 2)
+        .map(|range| (range[0], range[1]))
+        .collect()
+}
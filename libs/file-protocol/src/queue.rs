@@ -0,0 +1,45 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+// Downlink queue entries
+//
+// The queue itself is owned by the file transfer service. The protocol only
+// needs to be able to describe its contents
+
+/// A file waiting to be downlinked
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueueEntry {
+    /// Unique identifier of the entry within its queue
+    pub id: u32,
+    /// Path of the file to downlink
+    pub path: String,
+    /// Higher priority entries are downlinked first
+    pub priority: u32,
+    /// Time (in seconds since the UNIX epoch) after which the entry should be
+    /// discarded rather than downlinked
+    pub expires: Option<u64>,
+}
+
+impl QueueEntry {
+    /// Check whether the entry has expired as of the given time
+    /// (in seconds since the UNIX epoch)
+    pub fn is_expired(&self, now: u64) -> bool {
+        match self.expires {
+            Some(expires) => expires <= now,
+            None => false,
+        }
+    }
+}
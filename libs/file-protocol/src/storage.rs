@@ -14,23 +14,29 @@
 // limitations under the License.
 //
 
+use crate::compression::{self, Compression};
 use crate::error::ProtocolError;
 use blake2_rfc::blake2s::Blake2s;
-use log::warn;
+use log::{info, warn};
 use serde_cbor::{de, to_vec, Value};
 use std::fs;
 use std::fs::File;
 use std::fs::Permissions;
-use std::io::{BufRead, BufReader, Read, Write};
+use std::io::{self, BufRead, BufReader, Read, Write};
 use std::os::unix::fs::MetadataExt;
 use std::os::unix::fs::PermissionsExt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str;
 use std::thread;
 use std::time::Duration;
 use time;
 
-const HASH_SIZE: usize = 16;
+pub const HASH_SIZE: usize = 16;
+
+// Name of the file which records how a file's chunks were compressed
+const COMPRESSION_FILE: &str = "compression";
+// Name of the file used to reassemble a compressed file's chunks
+const COMPRESSED_FILE: &str = ".compressed";
 
 // Save new chunk in a temporary storage file
 pub fn store_chunk(prefix: &str, hash: &str, index: u32, data: &[u8]) -> Result<(), ProtocolError> {
@@ -164,6 +170,79 @@ pub fn load_meta(prefix: &str, hash: &str) -> Result<u32, ProtocolError> {
     Ok(num_chunks as u32)
 }
 
+// Load the compression algorithm which was applied to a file's chunks
+pub fn load_compression(prefix: &str, hash: &str) -> Result<Compression, ProtocolError> {
+    let path = Path::new(&format!("{}/storage", prefix))
+        .join(hash)
+        .join(COMPRESSION_FILE);
+
+    if !path.exists() {
+        return Ok(Compression::None);
+    }
+
+    let name = fs::read_to_string(&path).map_err(|err| ProtocolError::StorageError {
+        action: format!("read {} compression file", hash),
+        err,
+    })?;
+
+    name.trim().parse()
+}
+
+// Record the compression algorithm which was applied to a file's chunks.
+//
+// If the file's chunks were previously stored with a different algorithm,
+// they're no longer usable and are removed
+pub fn store_compression(
+    prefix: &str,
+    hash: &str,
+    compression: Compression,
+) -> Result<(), ProtocolError> {
+    let file_dir = Path::new(&format!("{}/storage", prefix)).join(hash);
+    let path = file_dir.join(COMPRESSION_FILE);
+
+    if file_dir.exists() && load_compression(prefix, hash).ok() != Some(compression) {
+        let entries = fs::read_dir(&file_dir).map_err(|err| ProtocolError::StorageError {
+            action: format!("read {:?} directory", file_dir),
+            err,
+        })?;
+
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let is_chunk = entry
+                .file_name()
+                .to_str()
+                .map(|name| name.parse::<u32>().is_ok())
+                .unwrap_or(false);
+
+            if is_chunk {
+                fs::remove_file(entry.path()).map_err(|err| ProtocolError::StorageError {
+                    action: format!("remove stale chunk {:?}", entry.path()),
+                    err,
+                })?;
+            }
+        }
+    }
+
+    if compression == Compression::None {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|err| ProtocolError::StorageError {
+                action: format!("remove {} compression file", hash),
+                err,
+            })?;
+        }
+        return Ok(());
+    }
+
+    fs::create_dir_all(&file_dir).map_err(|err| ProtocolError::StorageError {
+        action: "create temp storage directory".to_owned(),
+        err,
+    })?;
+
+    fs::write(&path, compression.as_str()).map_err(|err| ProtocolError::StorageError {
+        action: format!("write {} compression file", hash),
+        err,
+    })
+}
+
 // Check if all of a files chunks are present in the temporary directory
 pub fn validate_file(
     prefix: &str,
@@ -216,21 +295,13 @@ pub fn validate_file(
 
     converted_entries.sort();
 
-    let mut max_entries = 186;
     for &entry_num in converted_entries.iter() {
-        //println!("checking {} vs {}", entry_num, prev_entry);
         // Check for non-sequential dir entries to detect missing chunk ranges
         if entry_num - prev_entry > 1 {
             // Add start of range (inclusive)
             missing_ranges.push((prev_entry + 1) as u32);
             // Add end of range (non-inclusive)
             missing_ranges.push(entry_num as u32);
-
-            max_entries -= 1;
-
-            if max_entries == 0 {
-                break;
-            }
         }
 
         prev_entry = entry_num;
@@ -240,7 +311,7 @@ pub fn validate_file(
     // Ex. Last known chunk is 5, but there are 10 chunks.
     //     We will already have added '6', so we need to add '10'
     //     to close it out.
-    if max_entries != 0 && (num_chunks as i32) - prev_entry != 1 {
+    if (num_chunks as i32) - prev_entry != 1 {
         // Add start of range
         missing_ranges.push((prev_entry + 1) as u32);
         // Add end of range
@@ -253,10 +324,14 @@ pub fn validate_file(
 /// Create temporary folder for chunks
 /// Stream copy file from mutable space to immutable space
 /// Move folder to hash of contents
+///
+/// If compression is requested, the chunks hold the compressed data. Files which
+/// don't get any smaller are stored uncompressed instead
 pub fn initialize_file(
     prefix: &str,
     source_path: &str,
     chunk_size: usize,
+    compression: Compression,
 ) -> Result<(String, u32, u32), ProtocolError> {
     let storage_path = format!("{}/storage", prefix);
 
@@ -321,8 +396,11 @@ pub fn initialize_file(
         hash = format!("{}{:02x}", hash, c);
     }
 
-    let mut output = File::open(&temp_path).map_err(|err| ProtocolError::StorageError {
-        action: format!("open temp file {:?}", temp_path),
+    let (chunk_path, compression) = compress_temp(&temp_path, compression)?;
+    store_compression(prefix, &hash, compression)?;
+
+    let mut output = File::open(&chunk_path).map_err(|err| ProtocolError::StorageError {
+        action: format!("open temp file {:?}", chunk_path),
         err,
     })?;
 
@@ -340,7 +418,7 @@ pub fn initialize_file(
             }
             Err(e) => {
                 return Err(ProtocolError::StorageError {
-                    action: format!("read chunk from temp {:?}", temp_path),
+                    action: format!("read chunk from temp {:?}", chunk_path),
                     err: e,
                 });
             }
@@ -348,9 +426,12 @@ pub fn initialize_file(
     }
 
     store_meta(prefix, &hash, index)?;
-    match fs::remove_file(&temp_path) {
-        Ok(_) => {}
-        Err(e) => warn!("Failed to remove temp file {:?} : {}", temp_path, e),
+    for path in [&temp_path, &chunk_path].iter() {
+        if path.exists() {
+            if let Err(e) = fs::remove_file(path) {
+                warn!("Failed to remove temp file {:?} : {}", path, e);
+            }
+        }
     }
 
     if let Ok(meta) = fs::metadata(source_path) {
@@ -360,6 +441,63 @@ pub fn initialize_file(
     }
 }
 
+// Compress a file's temporary copy.
+//
+// Returns the file which should be split into chunks and the compression which was
+// actually used. If compressing doesn't make the file any smaller, the original copy
+// is used as-is
+fn compress_temp(
+    temp_path: &Path,
+    compression: Compression,
+) -> Result<(PathBuf, Compression), ProtocolError> {
+    if compression == Compression::None {
+        return Ok((temp_path.to_path_buf(), Compression::None));
+    }
+
+    let compressed_path = temp_path.with_extension(compression.as_str());
+
+    let mut input = File::open(temp_path).map_err(|err| ProtocolError::StorageError {
+        action: format!("open temp file {:?}", temp_path),
+        err,
+    })?;
+    let original_len = input
+        .metadata()
+        .map_err(|err| ProtocolError::StorageError {
+            action: format!("stat temp file {:?}", temp_path),
+            err,
+        })?
+        .len();
+    let output = File::create(&compressed_path).map_err(|err| ProtocolError::StorageError {
+        action: format!("create/open {:?} for writing", compressed_path),
+        err,
+    })?;
+
+    let compressed_len =
+        compression::compress_file(compression, &mut input, output).map_err(|err| {
+            ProtocolError::StorageError {
+                action: format!("compress {:?}", temp_path),
+                err,
+            }
+        })?;
+
+    if compressed_len < original_len {
+        info!(
+            "Compressed {} bytes to {} with {}",
+            original_len, compressed_len, compression
+        );
+        Ok((compressed_path, compression))
+    } else {
+        info!(
+            "File does not shrink with {} ({} -> {} bytes). Sending uncompressed",
+            compression, original_len, compressed_len
+        );
+        if let Err(e) = fs::remove_file(&compressed_path) {
+            warn!("Failed to remove temp file {:?} : {}", compressed_path, e);
+        }
+        Ok((temp_path.to_path_buf(), Compression::None))
+    }
+}
+
 // Copy temporary data chunks into permanent file?
 pub fn finalize_file(
     prefix: &str,
@@ -378,6 +516,7 @@ pub fn finalize_file(
 
     // Get the total number of chunks we're saving
     let num_chunks = load_meta(prefix, hash)?;
+    let compression = load_compression(prefix, hash)?;
 
     // Q: Do we want to create the parent directories if they don't exist?
     let mut file = File::create(target_path).map_err(|err| ProtocolError::StorageError {
@@ -395,6 +534,22 @@ pub fn finalize_file(
 
     let mut calc_hash = Blake2s::new(HASH_SIZE);
 
+    // Compressed chunks are reassembled in temporary storage and then decompressed
+    // into the target file
+    let compressed_path = Path::new(&format!("{}/storage", prefix))
+        .join(hash)
+        .join(COMPRESSED_FILE);
+    let mut staging = if compression == Compression::None {
+        None
+    } else {
+        Some(
+            File::create(&compressed_path).map_err(|err| ProtocolError::StorageError {
+                action: format!("create/open {:?} for writing", compressed_path),
+                err,
+            })?,
+        )
+    };
+
     let mut load_chunk_err = None;
     for chunk_num in 0..num_chunks {
         let chunk = match load_chunk(prefix, hash, chunk_num) {
@@ -410,6 +565,16 @@ pub fn finalize_file(
             }
         };
 
+        if let Some(staging) = staging.as_mut() {
+            staging
+                .write_all(&chunk)
+                .map_err(|err| ProtocolError::StorageError {
+                    action: format!("write chunk {}", chunk_num),
+                    err,
+                })?;
+            continue;
+        }
+
         // Update our verification hash
         calc_hash.update(&chunk);
         // Write the chunk to the destination file
@@ -424,6 +589,30 @@ pub fn finalize_file(
         return Err(e);
     }
 
+    if staging.is_some() {
+        let source = File::open(&compressed_path).map_err(|err| ProtocolError::StorageError {
+            action: format!("open {:?}", compressed_path),
+            err,
+        })?;
+
+        // Verify the hash against the original, decompressed, data
+        let mut writer = HashWriter {
+            hasher: &mut calc_hash,
+            inner: &mut file,
+        };
+
+        let result = compression::decompress(compression, source, &mut writer);
+        let _ = fs::remove_file(&compressed_path);
+
+        if let Err(err) = result {
+            // The chunks can't be trusted, so we start over
+            delete_file(&prefix, &hash)?;
+            return Err(ProtocolError::FinalizeError {
+                cause: format!("failed to decompress {} data: {}", compression, err),
+            });
+        }
+    }
+
     let calc_hash_str = calc_hash
         .finalize()
         .as_bytes()
@@ -442,6 +631,24 @@ pub fn finalize_file(
     }
 }
 
+// Writer which updates a hash with all of the data passing through it
+struct HashWriter<'a, W: Write> {
+    hasher: &'a mut Blake2s,
+    inner: &'a mut W,
+}
+
+impl<'a, W: Write> Write for HashWriter<'a, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 pub fn delete_chunk(prefix: &str, hash: &str, index: u32) -> Result<(), ProtocolError> {
     let path = Path::new(&format!("{}/storage", prefix))
         .join(hash)
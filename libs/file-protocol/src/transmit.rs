@@ -0,0 +1,381 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+// Rate and window control for outgoing file chunks
+//
+// Requested chunks are queued per file hash and sent in windows. Whenever the
+// receiver reports which chunks it's missing, we compare that against the chunks
+// sent since the last report in order to estimate the link's loss rate.
+// The transmit rate and window size then back off multiplicatively when the loss
+// is high and grow back towards their configured targets while the link is clean.
+
+use log::info;
+use std::collections::HashMap;
+use std::time::Duration;
+
+// Loss rate above which we slow down
+const LOSS_THRESHOLD: f64 = 0.05;
+// Delay between chunks when no transmit rate has been configured
+const DEFAULT_DELAY: Duration = Duration::from_millis(1);
+// Slowest we'll back off to, as a fraction of the target rate
+const MIN_RATE_DIVISOR: u32 = 16;
+// How much of the target rate/window to recover after each clean round
+const RECOVERY_DIVISOR: u32 = 8;
+
+// A sorted list of non-overlapping chunk ranges.
+// Each range is `(first, last)`, where `first` is inclusive and `last` is exclusive
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ChunkRanges(Vec<(u32, u32)>);
+
+impl ChunkRanges {
+    pub fn new() -> Self {
+        ChunkRanges(vec![])
+    }
+
+    // Add ranges to the list, merging any which overlap or touch
+    pub fn extend(&mut self, ranges: &[(u32, u32)]) {
+        self.0
+            .extend(ranges.iter().filter(|(first, last)| first < last));
+        self.0.sort();
+
+        let mut merged: Vec<(u32, u32)> = Vec::with_capacity(self.0.len());
+        for &(first, last) in self.0.iter() {
+            match merged.last_mut() {
+                Some(prev) if first <= prev.1 => prev.1 = prev.1.max(last),
+                _ => merged.push((first, last)),
+            }
+        }
+
+        self.0 = merged;
+    }
+
+    // Total number of chunks covered
+    pub fn count(&self) -> u64 {
+        self.0
+            .iter()
+            .map(|(first, last)| u64::from(last - first))
+            .sum()
+    }
+
+    // Remove the given ranges from the list
+    pub fn remove(&mut self, ranges: &[(u32, u32)]) {
+        let mut removed = ChunkRanges::new();
+        removed.extend(ranges);
+
+        let mut result = vec![];
+        for &(first, last) in self.0.iter() {
+            let mut start = first;
+            for &(r_first, r_last) in removed.0.iter() {
+                if r_last <= start || r_first >= last {
+                    continue;
+                }
+                if r_first > start {
+                    result.push((start, r_first));
+                }
+                start = start.max(r_last);
+            }
+            if start < last {
+                result.push((start, last));
+            }
+        }
+
+        self.0 = result;
+    }
+
+    // Remove and return up to `limit` chunk numbers from the front of the list
+    pub fn take_front(&mut self, limit: Option<u32>) -> Vec<u32> {
+        let mut chunks = vec![];
+        let limit = limit.map(|val| val as usize);
+
+        while let Some(&(first, last)) = self.0.first() {
+            let remaining = limit.map(|val| val - chunks.len());
+            let count = match remaining {
+                Some(0) => break,
+                Some(val) => val.min((last - first) as usize) as u32,
+                None => last - first,
+            };
+
+            chunks.extend(first..first + count);
+
+            if first + count == last {
+                self.0.remove(0);
+            } else {
+                self.0[0].0 = first + count;
+            }
+        }
+
+        chunks
+    }
+
+    // Remove and return all chunks below `limit`
+    pub fn split_below(&mut self, limit: u32) -> ChunkRanges {
+        let mut below = vec![];
+        let mut above = vec![];
+
+        for &(first, last) in self.0.iter() {
+            if last <= limit {
+                below.push((first, last));
+            } else if first >= limit {
+                above.push((first, last));
+            } else {
+                below.push((first, limit));
+                above.push((limit, last));
+            }
+        }
+
+        self.0 = above;
+        ChunkRanges(below)
+    }
+
+    // Number of chunks covered by both lists
+    pub fn overlap(&self, other: &ChunkRanges) -> u64 {
+        let mut count = 0;
+        let mut mine = self.0.iter().peekable();
+        let mut theirs = other.0.iter().peekable();
+
+        while let (Some(&&(a_first, a_last)), Some(&&(b_first, b_last))) =
+            (mine.peek(), theirs.peek())
+        {
+            let first = a_first.max(b_first);
+            let last = a_last.min(b_last);
+            if first < last {
+                count += u64::from(last - first);
+            }
+
+            if a_last < b_last {
+                mine.next();
+            } else {
+                theirs.next();
+            }
+        }
+
+        count
+    }
+
+    // The end of the last range
+    pub fn end(&self) -> Option<u32> {
+        self.0.last().map(|(_first, last)| *last)
+    }
+}
+
+// Transmission bookkeeping for a single protocol instance
+pub struct TransmitState {
+    // Configured target rate, in bits per second
+    target_rate: Option<u32>,
+    // Configured maximum window, in chunks
+    target_window: Option<u32>,
+    // Current rate, in bits per second
+    rate: Option<u32>,
+    // Current window, in chunks
+    window: Option<u32>,
+    // Chunks which have been requested but not sent yet, by file hash
+    pending: HashMap<String, ChunkRanges>,
+    // Chunks which have been sent since the receiver's last status report, by file hash
+    in_flight: HashMap<String, ChunkRanges>,
+}
+
+impl TransmitState {
+    pub fn new(target_rate: Option<u32>, target_window: Option<u32>) -> Self {
+        TransmitState {
+            target_rate,
+            target_window,
+            rate: target_rate,
+            window: target_window,
+            pending: HashMap::new(),
+            in_flight: HashMap::new(),
+        }
+    }
+
+    // Current window size, if windowing is enabled
+    pub fn window(&self) -> Option<u32> {
+        self.window
+    }
+
+    // Process a status report (NAK) from the receiver.
+    //
+    // Queues the missing chunks for transmission and updates our loss estimate
+    pub fn request(&mut self, hash: &str, missing: &[(u32, u32)]) {
+        let mut requested = ChunkRanges::new();
+        requested.extend(missing);
+
+        // NAKs may be split across multiple messages, so only judge the chunks
+        // which are covered by this one
+        if let (Some(end), Some(in_flight)) = (requested.end(), self.in_flight.get_mut(hash)) {
+            let reported = in_flight.split_below(end);
+            let lost = reported.overlap(&requested);
+            let delivered = reported.count() - lost;
+            self.adapt(lost, delivered);
+        }
+
+        self.pending
+            .entry(hash.to_owned())
+            .or_insert_with(ChunkRanges::new)
+            .extend(&requested.0);
+    }
+
+    // Get the next set of chunks which should be sent for a file
+    pub fn next_window(&mut self, hash: &str) -> Vec<u32> {
+        let chunks = match self.pending.get_mut(hash) {
+            Some(pending) => pending.take_front(self.window),
+            None => vec![],
+        };
+
+        let in_flight = self
+            .in_flight
+            .entry(hash.to_owned())
+            .or_insert_with(ChunkRanges::new);
+        let sent: Vec<(u32, u32)> = chunks.iter().map(|chunk| (*chunk, chunk + 1)).collect();
+        in_flight.extend(&sent);
+
+        chunks
+    }
+
+    // How long a message of the given size should take to send at the current rate
+    pub fn delay(&self, message_len: usize) -> Duration {
+        match self.rate {
+            Some(rate) if rate > 0 => {
+                let micros = (message_len as u64 * 8 * 1_000_000) / u64::from(rate);
+                Duration::from_micros(micros)
+            }
+            _ => DEFAULT_DELAY,
+        }
+    }
+
+    // Adjust the rate and window based on the measured loss
+    fn adapt(&mut self, lost: u64, delivered: u64) {
+        let total = lost + delivered;
+        if total == 0 {
+            return;
+        }
+
+        let loss = lost as f64 / total as f64;
+
+        if loss > LOSS_THRESHOLD {
+            if let (Some(rate), Some(target)) = (self.rate, self.target_rate) {
+                self.rate = Some((rate / 2).max(target / MIN_RATE_DIVISOR).max(1));
+            }
+            if let Some(window) = self.window {
+                self.window = Some((window / 2).max(1));
+            }
+            info!(
+                "Measured {:.1}% loss. Backing off to rate {:?}, window {:?}",
+                loss * 100.0,
+                self.rate,
+                self.window
+            );
+        } else if lost == 0 {
+            if let (Some(rate), Some(target)) = (self.rate, self.target_rate) {
+                self.rate = Some((rate + (target / RECOVERY_DIVISOR).max(1)).min(target));
+            }
+            if let (Some(window), Some(target)) = (self.window, self.target_window) {
+                self.window = Some((window + (target / RECOVERY_DIVISOR).max(1)).min(target));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_merge() {
+        let mut ranges = ChunkRanges::new();
+        ranges.extend(&[(5, 7), (0, 2), (2, 3), (6, 10)]);
+
+        assert_eq!(ranges, ChunkRanges(vec![(0, 3), (5, 10)]));
+        assert_eq!(ranges.count(), 8);
+    }
+
+    #[test]
+    fn ranges_take_front() {
+        let mut ranges = ChunkRanges::new();
+        ranges.extend(&[(0, 2), (5, 8)]);
+
+        assert_eq!(ranges.take_front(Some(3)), vec![0, 1, 5]);
+        assert_eq!(ranges, ChunkRanges(vec![(6, 8)]));
+        assert_eq!(ranges.take_front(None), vec![6, 7]);
+        assert_eq!(ranges.count(), 0);
+    }
+
+    #[test]
+    fn ranges_overlap() {
+        let mut first = ChunkRanges::new();
+        first.extend(&[(0, 10), (20, 30)]);
+        let mut second = ChunkRanges::new();
+        second.extend(&[(5, 25)]);
+
+        assert_eq!(first.overlap(&second), 10);
+    }
+
+    #[test]
+    fn ranges_remove() {
+        let mut ranges = ChunkRanges::new();
+        ranges.extend(&[(0, 10), (20, 30)]);
+
+        ranges.remove(&[(2, 4), (8, 22), (29, 40)]);
+
+        assert_eq!(ranges, ChunkRanges(vec![(0, 2), (4, 8), (22, 29)]));
+    }
+
+    #[test]
+    fn ranges_split_below() {
+        let mut ranges = ChunkRanges::new();
+        ranges.extend(&[(0, 10), (20, 30)]);
+
+        let below = ranges.split_below(25);
+
+        assert_eq!(below, ChunkRanges(vec![(0, 10), (20, 25)]));
+        assert_eq!(ranges, ChunkRanges(vec![(25, 30)]));
+    }
+
+    #[test]
+    fn window_backoff_and_recovery() {
+        let mut state = TransmitState::new(Some(80_000), Some(16));
+
+        state.request("hash", &[(0, 100)]);
+        assert_eq!(state.next_window("hash"), (0..16).collect::<Vec<u32>>());
+
+        // Half of the window never arrived
+        state.request("hash", &[(0, 8), (16, 100)]);
+        assert_eq!(state.window(), Some(8));
+        assert_eq!(state.rate, Some(40_000));
+
+        // The next window (the lost chunks) arrives cleanly
+        assert_eq!(state.next_window("hash"), (0..8).collect::<Vec<u32>>());
+        state.request("hash", &[(16, 100)]);
+        assert_eq!(state.window(), Some(10));
+        assert_eq!(state.rate, Some(50_000));
+    }
+
+    #[test]
+    fn unlimited_window() {
+        let mut state = TransmitState::new(None, None);
+
+        state.request("hash", &[(0, 3), (5, 6)]);
+        assert_eq!(state.next_window("hash"), vec![0, 1, 2, 5]);
+        assert_eq!(state.pending["hash"].count(), 0);
+        assert_eq!(state.delay(4096), DEFAULT_DELAY);
+    }
+
+    #[test]
+    fn rate_delay() {
+        let state = TransmitState::new(Some(8_000), None);
+
+        // 1000 bytes at 8 kbps should take one second
+        assert_eq!(state.delay(1000), Duration::from_secs(1));
+    }
+}
@@ -72,7 +72,9 @@ fn main() -> Result<(), Error> {
             .unwrap_or_else(|err| error!("Error starting applications: {}", err));
     }
 
-    Service::new(config, registry, schema::QueryRoot, schema::MutationRoot).start();
+    Service::new(config, registry, schema::QueryRoot, schema::MutationRoot)
+        .version(env!("CARGO_PKG_VERSION"))
+        .start();
 
     Ok(())
 }
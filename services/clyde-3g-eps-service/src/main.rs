@@ -23,6 +23,7 @@
 //! ```toml
 //! [clyde-3g-eps-service]
 //! bus = "/dev/i2c-1"
+//! status_file = "/home/system/kubos/clyde-3g-eps-status.json"
 //!
 //! [clyde-3g-eps-service.addr]
 //! ip = "127.0.0.1"
@@ -33,6 +34,50 @@
 //! service's IP address, and `port` specifies the port on which the service will be
 //! listening for UDP packets.
 //!
+//! `status_file` is optional. If given, the last mutation and any unread errors are saved to this
+//! file, so that they survive a service restart.
+//!
+//! ## Telemetry Polling
+//!
+//! The service can also sample EPS telemetry in the background and send it to the
+//! telemetry service's direct UDP port. Polling is enabled by adding a `poller` section:
+//!
+//! ```toml
+//! [clyde-3g-eps-service.poller]
+//! destination = "127.0.0.1:8020"
+//! subsystem = "eps"
+//!
+//! [[clyde-3g-eps-service.poller.channels]]
+//! board = "motherboard"
+//! channel = "OutputVoltageBattery"
+//! interval = 10.0
+//! red = { low = 6.2 }
+//! yellow = { low = 7.0, high = 8.3 }
+//!
+//! [[clyde-3g-eps-service.poller.channels]]
+//! board = "daughterboard"
+//! channel = "ArrayTempSa4a"
+//! interval = 60.0
+//! red = { high = 90.0 }
+//! yellow = { high = 70.0 }
+//! ```
+//!
+//! - `destination` - The address of the telemetry service's `direct_port`
+//! - `subsystem` - Optional. The subsystem name to use for the telemetry entries. Default: "eps"
+//! - `channels` - The telemetry items to sample
+//!   - `board` - The board to read from: "motherboard" or "daughterboard"
+//!   - `channel` - The name of the telemetry item, as used by the `motherboardTelemetry`
+//!     and `daughterboardTelemetry` queries
+//!   - `interval` - Optional. How often, in seconds, the item should be sampled. Default: 10
+//!   - `red`, `yellow` - Optional. The `low` and/or `high` limits of the item
+//!
+//! Each sample is stored with the parameter name `{board}.{channel}`
+//! (e.g. `motherboard.OutputVoltageBattery`).
+//!
+//! When a value moves outside of its yellow or red limits, an error is recorded so that it
+//! will be returned by the `errors` query. Red limit violations are recorded as errors and
+//! yellow limit violations as warnings. A value which stays out of limits is only reported once.
+//!
 //! # Running the Service
 //!
 //! The service should be started automatically by its init script, but may also be started manually:
@@ -52,6 +97,7 @@
 //! # Panics
 //!
 //! Attempts to grab `bus` from Configuration and will `panic!` if not found.
+//! Will also `panic!` if the `poller` section is present but invalid.
 //!
 //! # GraphQL Schema
 //!
@@ -78,6 +124,20 @@
 //! }
 //! ```
 //!
+//! ### Error Log
+//!
+//! Fetch the most recent errors, including ones which have already been read
+//!
+//! ```json
+//! {
+//! 	errorLog {
+//! 		timestamp: Float,
+//! 		severity: Severity,
+//! 		message: String
+//! 	}
+//! }
+//! ```
+//!
 //! ### Power
 //!
 //! Get the system power status.
@@ -410,10 +470,12 @@ pub mod schema;
 #[cfg(test)]
 mod tests;
 
-use crate::models::subsystem::Subsystem;
+use crate::models::poller::{Poller, PollerConfig};
+use crate::models::subsystem::{Mutations, Subsystem};
 use crate::schema::mutation::Root as MutationRoot;
 use crate::schema::query::Root as QueryRoot;
-use kubos_service::{Config, Service};
+use kubos_service::{CommandStatus, Config, Service};
+use std::thread;
 use syslog::Facility;
 
 fn main() {
@@ -430,7 +492,17 @@ fn main() {
         .expect("No EPS bus device path found in config");
     let bus = bus.as_str().unwrap();
 
-    let subsystem: Box<Subsystem> = Box::new(Subsystem::from_path(bus).unwrap());
+    let status = CommandStatus::from_config(&config, Mutations::None);
+
+    let subsystem: Box<Subsystem> = Box::new(Subsystem::from_path(bus, status).unwrap());
+
+    let poller_config = PollerConfig::from_config(&config).unwrap();
+    if let Some(poller_config) = poller_config {
+        let poller = Poller::new((*subsystem).clone(), poller_config).unwrap();
+        thread::spawn(move || poller.run());
+    }
 
-    Service::new(config, subsystem, QueryRoot, MutationRoot).start();
+    Service::new(config, subsystem, QueryRoot, MutationRoot)
+        .version(env!("CARGO_PKG_VERSION"))
+        .start();
 }
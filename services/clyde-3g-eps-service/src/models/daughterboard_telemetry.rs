@@ -38,6 +38,16 @@ macro_rules! make_telemetry {
             )+
         }
 
+        impl Type {
+            /// Look up a telemetry item by its name (e.g. `"BoardTemperature"`)
+            pub fn from_name(name: &str) -> Option<Type> {
+                match name {
+                    $(stringify!($type) => Some(Type::$type),)+
+                    _ => None,
+                }
+            }
+        }
+
         impl Into<DaughterboardTelemetryType> for Type {
             fn into(self) -> DaughterboardTelemetryType {
                 match self {
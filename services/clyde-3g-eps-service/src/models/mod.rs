@@ -58,6 +58,7 @@ pub mod board_status;
 pub mod daughterboard_telemetry;
 pub mod last_error;
 pub mod motherboard_telemetry;
+pub mod poller;
 pub mod reset_telemetry;
 pub mod subsystem;
 pub mod version;
@@ -0,0 +1,366 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Background telemetry polling and limit monitoring
+//!
+//! Each configured channel is sampled at its own rate and the value is sent to the
+//! telemetry service's direct UDP port. Values are checked against the channel's red
+//! and yellow limits, and an error is recorded whenever a channel moves into a worse
+//! limit state, so that it's reported by the `errors` query. Likewise, a channel which
+//! can't be read is only reported when it first fails, rather than at every interval.
+
+use crate::models::subsystem::Subsystem;
+use crate::models::{daughterboard_telemetry, motherboard_telemetry};
+use failure::Error;
+use kubos_service::{run, Config, Severity};
+use log::{info, warn};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::json;
+use std::cmp;
+use std::net::UdpSocket;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Default sampling interval, in seconds
+const DEFAULT_INTERVAL: f64 = 10.0;
+// Default subsystem name used for telemetry database entries
+const DEFAULT_SUBSYSTEM: &str = "eps";
+
+/// Telemetry poller configuration, read from the `[clyde-3g-eps-service.poller]` section
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PollerConfig {
+    /// Address of the telemetry service's direct UDP port (ex. "127.0.0.1:8020")
+    pub destination: String,
+    /// Subsystem name to use for the telemetry database entries
+    #[serde(default = "default_subsystem")]
+    pub subsystem: String,
+    /// Channels which should be sampled
+    #[serde(default)]
+    pub channels: Vec<ChannelConfig>,
+}
+
+impl PollerConfig {
+    /// Read the poller configuration from the service's config, if it's present
+    pub fn from_config(config: &Config) -> Result<Option<Self>, String> {
+        match config.get("poller") {
+            Some(raw) => raw
+                .try_into()
+                .map(Some)
+                .map_err(|err| format!("Invalid poller config: {}", err)),
+            None => Ok(None),
+        }
+    }
+}
+
+fn default_subsystem() -> String {
+    DEFAULT_SUBSYSTEM.to_owned()
+}
+
+fn default_interval() -> f64 {
+    DEFAULT_INTERVAL
+}
+
+/// EPS board which a channel is read from
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Board {
+    /// Motherboard
+    Motherboard,
+    /// Daughterboard
+    Daughterboard,
+}
+
+/// Configuration for a single polled channel
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ChannelConfig {
+    /// Board the channel is read from
+    pub board: Board,
+    /// Name of the telemetry item (ex. "OutputVoltageBattery")
+    pub channel: String,
+    /// Sampling interval, in seconds
+    #[serde(default = "default_interval")]
+    pub interval: f64,
+    /// Limits which indicate a critical condition
+    pub red: Option<Limits>,
+    /// Limits which indicate a condition that needs attention
+    pub yellow: Option<Limits>,
+}
+
+/// Acceptable range of a channel's value. Either bound may be omitted
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Limits {
+    /// Values below this are out of limits
+    pub low: Option<f64>,
+    /// Values above this are out of limits
+    pub high: Option<f64>,
+}
+
+impl Limits {
+    // Describe how the value violates these limits, if it does
+    fn violation(&self, value: f64) -> Option<String> {
+        if let Some(low) = self.low {
+            if value < low {
+                return Some(format!("{} < {}", value, low));
+            }
+        }
+        if let Some(high) = self.high {
+            if value > high {
+                return Some(format!("{} > {}", value, high));
+            }
+        }
+        None
+    }
+}
+
+/// Limit state of a channel's most recent value
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum LimitState {
+    /// Within all limits
+    Nominal,
+    /// Outside of the yellow limits
+    Yellow,
+    /// Outside of the red limits
+    Red,
+}
+
+/// Check a value against a channel's red and yellow limits
+///
+/// Returns the resulting limit state, along with a description of the violated limit
+pub fn check_limits(
+    value: f64,
+    red: Option<Limits>,
+    yellow: Option<Limits>,
+) -> (LimitState, Option<String>) {
+    if let Some(desc) = red.and_then(|limits| limits.violation(value)) {
+        return (LimitState::Red, Some(desc));
+    }
+    if let Some(desc) = yellow.and_then(|limits| limits.violation(value)) {
+        return (LimitState::Yellow, Some(desc));
+    }
+    (LimitState::Nominal, None)
+}
+
+#[derive(Clone, Debug)]
+enum Channel {
+    Motherboard(motherboard_telemetry::Type),
+    Daughterboard(daughterboard_telemetry::Type),
+}
+
+struct PolledChannel {
+    name: String,
+    channel: Channel,
+    interval: Duration,
+    next: Instant,
+    red: Option<Limits>,
+    yellow: Option<Limits>,
+    state: LimitState,
+    // Whether the most recent read of the channel failed
+    failed: bool,
+}
+
+/// Samples the configured EPS channels and forwards them to the telemetry service
+pub struct Poller {
+    subsystem: Subsystem,
+    socket: UdpSocket,
+    destination: String,
+    db_subsystem: String,
+    channels: Vec<PolledChannel>,
+}
+
+impl Poller {
+    /// Create a new poller. Unknown channels are recorded as errors and skipped.
+    pub fn new(subsystem: Subsystem, config: PollerConfig) -> Result<Self, String> {
+        let socket = UdpSocket::bind("0.0.0.0:0")
+            .map_err(|err| format!("Failed to create poller socket: {}", err))?;
+
+        let now = Instant::now();
+        let mut channels = vec![];
+        for entry in config.channels {
+            let channel = match entry.board {
+                Board::Motherboard => {
+                    motherboard_telemetry::Type::from_name(&entry.channel).map(Channel::Motherboard)
+                }
+                Board::Daughterboard => daughterboard_telemetry::Type::from_name(&entry.channel)
+                    .map(Channel::Daughterboard),
+            };
+
+            let channel = match channel {
+                Some(channel) => channel,
+                None => {
+                    subsystem.status.push_error(
+                        Severity::Error,
+                        format!(
+                            "Unknown {:?} poller channel: {}",
+                            entry.board, entry.channel
+                        ),
+                    );
+                    continue;
+                }
+            };
+
+            if entry.interval.is_nan() || entry.interval <= 0.0 {
+                subsystem.status.push_error(
+                    Severity::Error,
+                    format!(
+                        "Invalid interval for poller channel {}: {}",
+                        entry.channel, entry.interval
+                    ),
+                );
+                continue;
+            }
+
+            channels.push(PolledChannel {
+                name: format!(
+                    "{}.{}",
+                    format!("{:?}", entry.board).to_lowercase(),
+                    entry.channel
+                ),
+                channel,
+                interval: Duration::from_millis((entry.interval * 1000.0) as u64),
+                next: now,
+                red: entry.red,
+                yellow: entry.yellow,
+                state: LimitState::Nominal,
+                failed: false,
+            });
+        }
+
+        Ok(Poller {
+            subsystem,
+            socket,
+            destination: config.destination,
+            db_subsystem: config.subsystem,
+            channels,
+        })
+    }
+
+    /// Sample every channel which is due at the given time
+    ///
+    /// Returns the time at which the next channel will be due
+    pub fn poll(&mut self, now: Instant) -> Option<Instant> {
+        for index in 0..self.channels.len() {
+            if self.channels[index].next <= now {
+                self.sample(index);
+                let channel = &mut self.channels[index];
+                // Schedule from the current time, rather than the previous due time,
+                // so that a slow bus doesn't cause a burst of back-to-back samples
+                channel.next = now + channel.interval;
+            }
+        }
+
+        self.channels.iter().map(|channel| channel.next).min()
+    }
+
+    /// Sample the configured channels forever
+    pub fn run(mut self) {
+        if self.channels.is_empty() {
+            info!("No EPS telemetry channels configured for polling");
+            return;
+        }
+
+        loop {
+            let now = Instant::now();
+            if let Some(next) = self.poll(now) {
+                let now = Instant::now();
+                thread::sleep(cmp::max(next, now) - now);
+            }
+        }
+    }
+
+    fn sample(&mut self, index: usize) {
+        let value = {
+            let eps = self.subsystem.eps.lock().unwrap();
+            match self.channels[index].channel.clone() {
+                Channel::Motherboard(telem) => run!(eps.get_motherboard_telemetry(telem.into())),
+                Channel::Daughterboard(telem) => {
+                    run!(eps.get_daughterboard_telemetry(telem.into()))
+                }
+            }
+        };
+
+        // Only report a channel when it starts failing, so that a channel which can't
+        // be read doesn't flood the error log
+        let channel = &mut self.channels[index];
+        let value = match value {
+            Ok(value) => {
+                if channel.failed {
+                    info!("{} can be read again", channel.name);
+                    channel.failed = false;
+                }
+                value
+            }
+            Err(err) => {
+                if !channel.failed {
+                    self.subsystem.status.push_error(
+                        Severity::Error,
+                        format!("Failed to read {}: {}", channel.name, err),
+                    );
+                    channel.failed = true;
+                }
+                return;
+            }
+        };
+
+        self.send(&self.channels[index].name, value);
+        self.check(index, value);
+    }
+
+    fn send(&self, name: &str, value: f64) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs() as f64 + f64::from(time.subsec_nanos()) / 1e9)
+            .unwrap_or(0.0);
+
+        let message = json!({
+            "timestamp": timestamp,
+            "subsystem": self.db_subsystem,
+            "parameter": name,
+            "value": value.to_string(),
+        });
+
+        if let Err(err) = self
+            .socket
+            .send_to(message.to_string().as_bytes(), &self.destination)
+        {
+            warn!("Failed to send {} to telemetry service: {}", name, err);
+        }
+    }
+
+    fn check(&mut self, index: usize, value: f64) {
+        let channel = &mut self.channels[index];
+        let (state, desc) = check_limits(value, channel.red, channel.yellow);
+
+        // Only report a channel when it gets worse, so that a value which stays out of
+        // limits doesn't flood the error log
+        if state > channel.state {
+            if let Some(desc) = desc {
+                let (severity, color) = match state {
+                    LimitState::Red => (Severity::Error, "red"),
+                    _ => (Severity::Warning, "yellow"),
+                };
+
+                self.subsystem.status.push_error(
+                    severity,
+                    format!("{} outside of {} limits: {}", channel.name, color, desc),
+                );
+            }
+        } else if state == LimitState::Nominal && channel.state != LimitState::Nominal {
+            info!("{} back within limits: {}", channel.name, value);
+        }
+
+        channel.state = state;
+    }
+}
@@ -20,13 +20,15 @@ use crate::models::*;
 use clyde_3g_eps_api::{Checksum, Clyde3gEps, Eps};
 use eps_api::EpsResult;
 use failure::Error;
+use kubos_service::CommandStatus;
 use rust_i2c::*;
-use std::sync::{Arc, Mutex, RwLock};
+use serde_derive::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
 /// Enum for tracking the last mutation executed
-#[derive(Copy, Clone, Debug, Eq, Hash, GraphQLEnum, PartialEq)]
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, GraphQLEnum, PartialEq, Serialize)]
 pub enum Mutations {
     /// No mutation has been run since the service was started
     None,
@@ -56,10 +58,8 @@ fn watchdog_thread(eps: Arc<Mutex<Box<Clyde3gEps + Send>>>) {
 pub struct Subsystem {
     /// Underlying EPS object
     pub eps: Arc<Mutex<Box<Clyde3gEps + Send>>>,
-    /// Last mutation executed
-    pub last_mutation: Arc<RwLock<Mutations>>,
-    /// Errors accumulated over all queries and mutations
-    pub errors: Arc<RwLock<Vec<String>>>,
+    /// Last mutation executed and errors accumulated over all queries and mutations
+    pub status: CommandStatus<Mutations>,
     /// Watchdog kicking thread handle
     pub watchdog_handle: Arc<Mutex<thread::JoinHandle<()>>>,
     /// Last known checksum of EPS ROM
@@ -68,25 +68,24 @@ pub struct Subsystem {
 
 impl Subsystem {
     /// Create a new subsystem instance for the service to use
-    pub fn new(eps: Box<Clyde3gEps + Send>) -> EpsResult<Self> {
+    pub fn new(eps: Box<Clyde3gEps + Send>, status: CommandStatus<Mutations>) -> EpsResult<Self> {
         let eps = Arc::new(Mutex::new(eps));
         let thread_eps = eps.clone();
         let watchdog = thread::spawn(move || watchdog_thread(thread_eps));
 
         Ok(Self {
             eps,
-            last_mutation: Arc::new(RwLock::new(Mutations::None)),
-            errors: Arc::new(RwLock::new(vec![])),
+            status,
             watchdog_handle: Arc::new(Mutex::new(watchdog)),
             checksum: Arc::new(Mutex::new(Checksum::default())),
         })
     }
 
     /// Create the underlying EPS object and then create a new subsystem which will use it
-    pub fn from_path(bus: &str) -> EpsResult<Self> {
+    pub fn from_path(bus: &str, status: CommandStatus<Mutations>) -> EpsResult<Self> {
         let clyde_eps: Box<Clyde3gEps + Send> =
             Box::new(Eps::new(Connection::from_path(bus, 0x2B)));
-        Subsystem::new(clyde_eps)
+        Subsystem::new(clyde_eps, status)
     }
 
     /// Get the requested telemetry item from the motherboard
@@ -99,7 +98,7 @@ impl Subsystem {
                 .lock()
                 .unwrap()
                 .get_motherboard_telemetry(telem_type.into()),
-            self.errors
+            self.status
         )?;
 
         Ok(result)
@@ -113,7 +112,7 @@ impl Subsystem {
         let eps = self.eps.lock().unwrap();
         Ok(run!(
             eps.get_daughterboard_telemetry(telem_type.into()),
-            self.errors
+            self.status
         )?)
     }
 
@@ -123,31 +122,31 @@ impl Subsystem {
         telem_type: reset_telemetry::Type,
     ) -> Result<reset_telemetry::Data, String> {
         let eps = self.eps.lock().unwrap();
-        Ok(run!(eps.get_reset_telemetry(telem_type.into()), self.errors)?.into())
+        Ok(run!(eps.get_reset_telemetry(telem_type.into()), self.status)?.into())
     }
 
     /// Get the current watchdog period setting
     pub fn get_comms_watchdog_period(&self) -> Result<u8, String> {
         let eps = self.eps.lock().unwrap();
-        Ok(run!(eps.get_comms_watchdog_period(), self.errors)?)
+        Ok(run!(eps.get_comms_watchdog_period(), self.status)?)
     }
 
     /// Get the system version information
     pub fn get_version(&self) -> Result<version::VersionData, String> {
         let eps = self.eps.lock().unwrap();
-        Ok(run!(eps.get_version_info(), self.errors)?.into())
+        Ok(run!(eps.get_version_info(), self.status)?.into())
     }
 
     /// Get the current board status
     pub fn get_board_status(&self) -> Result<board_status::BoardData, String> {
         let eps = self.eps.lock().unwrap();
-        Ok(run!(eps.get_board_status(), self.errors)?.into())
+        Ok(run!(eps.get_board_status(), self.status)?.into())
     }
 
     /// Get the last error the EPS encountered
     pub fn get_last_eps_error(&self) -> Result<last_error::ErrorData, String> {
         let eps = self.eps.lock().unwrap();
-        Ok(run!(eps.get_last_error(), self.errors)?.into())
+        Ok(run!(eps.get_last_error(), self.status)?.into())
     }
 
     /// Get the current power state of the EPS
@@ -175,7 +174,7 @@ impl Subsystem {
     /// Trigger a manual reset of the EPS
     pub fn manual_reset(&self) -> Result<MutationResponse, String> {
         let eps = self.eps.lock().unwrap();
-        match run!(eps.manual_reset(), self.errors) {
+        match run!(eps.manual_reset(), self.status) {
             Ok(_v) => Ok(MutationResponse {
                 success: true,
                 errors: "".to_string(),
@@ -190,7 +189,7 @@ impl Subsystem {
     /// Kick the I2C watchdog
     pub fn reset_watchdog(&self) -> Result<MutationResponse, String> {
         let eps = self.eps.lock().unwrap();
-        match run!(eps.reset_comms_watchdog(), self.errors) {
+        match run!(eps.reset_comms_watchdog(), self.status) {
             Ok(_v) => Ok(MutationResponse {
                 success: true,
                 errors: "".to_string(),
@@ -205,7 +204,7 @@ impl Subsystem {
     /// Set the I2C watchdog timeout period
     pub fn set_watchdog_period(&self, period: u8) -> Result<MutationResponse, String> {
         let eps = self.eps.lock().unwrap();
-        match run!(eps.set_comms_watchdog_period(period), self.errors) {
+        match run!(eps.set_comms_watchdog_period(period), self.status) {
             Ok(_v) => Ok(MutationResponse {
                 success: true,
                 errors: "".to_string(),
@@ -220,7 +219,7 @@ impl Subsystem {
     /// Pass raw command values through to the EPS
     pub fn raw_command(&self, command: u8, data: Vec<u8>) -> Result<MutationResponse, String> {
         let eps = self.eps.lock().unwrap();
-        match run!(eps.raw_command(command, data), self.errors) {
+        match run!(eps.raw_command(command, data), self.status) {
             Ok(_v) => Ok(MutationResponse {
                 success: true,
                 errors: "".to_string(),
@@ -235,7 +234,7 @@ impl Subsystem {
     /// Run hardware tests to check system health
     pub fn test_hardware(&self) -> Result<MutationResponse, String> {
         let eps = self.eps.lock().unwrap();
-        match run!(eps.get_checksum(), self.errors) {
+        match run!(eps.get_checksum(), self.status) {
             Ok(new_data) => {
                 let mut errors = vec![];
                 let mut success = true;
@@ -284,23 +283,11 @@ impl Subsystem {
 
     /// Record the last mutation executed by the service
     pub fn set_last_mutation(&self, mutation: Mutations) {
-        if let Ok(mut last_cmd) = self.last_mutation.write() {
-            *last_cmd = mutation;
-        }
+        self.status.set_ack(mutation);
     }
 
-    /// Fetch all errors since the last time this function was called, then clear the errors storage
+    /// Fetch all errors since the last time this function was called, then mark them as read
     pub fn get_errors(&self) -> EpsResult<Vec<String>> {
-        match self.errors.write() {
-            Ok(mut master_vec) => {
-                let current = master_vec.clone();
-                master_vec.clear();
-                master_vec.shrink_to_fit();
-                Ok(current)
-            }
-            _ => Ok(vec![
-                "Error: Failed to borrow master errors vector".to_string()
-            ]),
-        }
+        Ok(self.status.take_error_messages())
     }
 }
@@ -19,6 +19,7 @@
 use crate::models::*;
 use crate::schema::Context;
 use juniper::FieldResult;
+use kubos_service::ErrorRecord;
 
 /// Telemetry query structure
 pub struct Telemetry;
@@ -261,8 +262,7 @@ graphql_object!(Root: Context as "Query" |&self| {
     field ack(&executor) -> FieldResult<subsystem::Mutations>
         as "Last run mutation"
     {
-        let last_cmd = executor.context().subsystem().last_mutation.read()?;
-        Ok(*last_cmd)
+        Ok(executor.context().subsystem().status.ack())
     }
 
     // Get all errors encountered since the last time
@@ -277,6 +277,21 @@ graphql_object!(Root: Context as "Query" |&self| {
         Ok(executor.context().subsystem().get_errors()?)
     }
 
+    // Get the most recent errors, including ones which have already been read
+    //
+    // {
+    //    errorLog {
+    //        timestamp: Float,
+    //        severity: Severity,
+    //        message: String
+    //    }
+    // }
+    field error_log(&executor) -> FieldResult<Vec<ErrorRecord>>
+        as "Most recent errors, including ones which have already been read"
+    {
+        Ok(executor.context().subsystem().status.error_log())
+    }
+
     // Get the system power status
     //
     // {
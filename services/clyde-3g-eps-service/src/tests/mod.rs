@@ -14,12 +14,12 @@
 // limitations under the License.
 //
 
-use crate::models::subsystem::Subsystem;
+use crate::models::subsystem::{Mutations, Subsystem};
 use crate::schema::mutation::Root as MutationRoot;
 use crate::schema::query::Root as QueryRoot;
 use clyde_3g_eps_api::*;
 use eps_api::*;
-use kubos_service::{Config, Service};
+use kubos_service::{CommandStatus, Config, Service};
 use serde_json::json;
 use std::sync::{Arc, Mutex};
 
@@ -191,4 +191,5 @@ macro_rules! test {
 }
 
 mod mutation;
+mod poller;
 mod query;
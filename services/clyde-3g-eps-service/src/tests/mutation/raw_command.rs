@@ -19,7 +19,9 @@ use super::*;
 #[test]
 fn issue_raw_good() {
     let config: Config = Default::default();
-    let subsystem: Box<Subsystem> = Box::new(Subsystem::new(gen_mock_good_eps()).unwrap());
+    let subsystem: Box<Subsystem> = Box::new(
+        Subsystem::new(gen_mock_good_eps(), CommandStatus::new(Mutations::None)).unwrap(),
+    );
     let service = Service::new(config, subsystem, QueryRoot, MutationRoot);
 
     let query = r#"mutation {
@@ -42,7 +44,9 @@ fn issue_raw_good() {
 #[test]
 fn issue_raw_bad() {
     let config: Config = Default::default();
-    let subsystem: Box<Subsystem> = Box::new(Subsystem::new(gen_mock_bad_eps()).unwrap());
+    let subsystem: Box<Subsystem> = Box::new(
+        Subsystem::new(gen_mock_bad_eps(), CommandStatus::new(Mutations::None)).unwrap(),
+    );
     let service = Service::new(config, subsystem, QueryRoot, MutationRoot);
 
     let query = r#"mutation {
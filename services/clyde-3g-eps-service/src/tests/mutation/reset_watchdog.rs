@@ -19,7 +19,9 @@ use super::*;
 #[test]
 fn reset_watchdog_good() {
     let config: Config = Default::default();
-    let subsystem: Box<Subsystem> = Box::new(Subsystem::new(gen_mock_good_eps()).unwrap());
+    let subsystem: Box<Subsystem> = Box::new(
+        Subsystem::new(gen_mock_good_eps(), CommandStatus::new(Mutations::None)).unwrap(),
+    );
     let service = Service::new(config, subsystem, QueryRoot, MutationRoot);
 
     let query = r#"mutation {
@@ -42,7 +44,9 @@ fn reset_watchdog_good() {
 #[test]
 fn reset_watchdog_bad() {
     let config: Config = Default::default();
-    let subsystem: Box<Subsystem> = Box::new(Subsystem::new(gen_mock_bad_eps()).unwrap());
+    let subsystem: Box<Subsystem> = Box::new(
+        Subsystem::new(gen_mock_bad_eps(), CommandStatus::new(Mutations::None)).unwrap(),
+    );
     let service = Service::new(config, subsystem, QueryRoot, MutationRoot);
 
     let query = r#"mutation {
@@ -0,0 +1,311 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+use crate::models::poller::*;
+use std::net::UdpSocket;
+use std::time::{Duration, Instant};
+
+fn limits(low: Option<f64>, high: Option<f64>) -> Option<Limits> {
+    Some(Limits { low, high })
+}
+
+fn channel(board: Board, name: &str, red: Option<Limits>, yellow: Option<Limits>) -> ChannelConfig {
+    ChannelConfig {
+        board,
+        channel: name.to_owned(),
+        interval: 10.0,
+        red,
+        yellow,
+    }
+}
+
+fn setup(channels: Vec<ChannelConfig>) -> (Poller, CommandStatus<Mutations>, UdpSocket) {
+    let (poller, status, listener, _eps) = setup_eps(gen_mock_good_eps(), channels);
+    (poller, status, listener)
+}
+
+// Set up a poller for the given EPS, also returning the shared EPS so that it can be
+// swapped out part way through a test
+fn setup_eps(
+    eps: Box<Clyde3gEps + Send>,
+    channels: Vec<ChannelConfig>,
+) -> (
+    Poller,
+    CommandStatus<Mutations>,
+    UdpSocket,
+    Arc<Mutex<Box<Clyde3gEps + Send>>>,
+) {
+    let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+    listener
+        .set_read_timeout(Some(Duration::from_secs(1)))
+        .unwrap();
+
+    let subsystem = Subsystem::new(eps, CommandStatus::new(Mutations::None)).unwrap();
+    let status = subsystem.status.clone();
+    let eps = subsystem.eps.clone();
+
+    let config = PollerConfig {
+        destination: listener.local_addr().unwrap().to_string(),
+        subsystem: "eps".to_owned(),
+        channels,
+    };
+
+    (
+        Poller::new(subsystem, config).unwrap(),
+        status,
+        listener,
+        eps,
+    )
+}
+
+fn receive(listener: &UdpSocket) -> serde_json::Value {
+    let mut buf = [0; 1024];
+    let (len, _) = listener.recv_from(&mut buf).unwrap();
+    serde_json::from_slice(&buf[0..len]).unwrap()
+}
+
+#[test]
+fn check_limits_nominal() {
+    assert_eq!(
+        check_limits(7.5, limits(Some(6.2), None), limits(Some(7.0), Some(8.3))),
+        (LimitState::Nominal, None)
+    );
+}
+
+#[test]
+fn check_limits_no_limits() {
+    assert_eq!(
+        check_limits(-100.0, None, None),
+        (LimitState::Nominal, None)
+    );
+}
+
+#[test]
+fn check_limits_yellow_low() {
+    assert_eq!(
+        check_limits(6.5, limits(Some(6.2), None), limits(Some(7.0), Some(8.3))),
+        (LimitState::Yellow, Some("6.5 < 7".to_owned()))
+    );
+}
+
+#[test]
+fn check_limits_yellow_high() {
+    assert_eq!(
+        check_limits(8.5, limits(Some(6.2), None), limits(Some(7.0), Some(8.3))),
+        (LimitState::Yellow, Some("8.5 > 8.3".to_owned()))
+    );
+}
+
+#[test]
+fn check_limits_red() {
+    assert_eq!(
+        check_limits(6.0, limits(Some(6.2), None), limits(Some(7.0), Some(8.3))),
+        (LimitState::Red, Some("6 < 6.2".to_owned()))
+    );
+}
+
+#[test]
+fn config_parse() {
+    let config = Config::new_from_str(
+        "clyde-3g-eps-service",
+        r#"
+        [clyde-3g-eps-service.poller]
+        destination = "127.0.0.1:8020"
+
+        [[clyde-3g-eps-service.poller.channels]]
+        board = "motherboard"
+        channel = "OutputVoltageBattery"
+        red = { low = 6.2 }
+        yellow = { low = 7.0, high = 8.3 }
+
+        [[clyde-3g-eps-service.poller.channels]]
+        board = "daughterboard"
+        channel = "ArrayTempSa4a"
+        interval = 60.0
+        "#,
+    );
+
+    let expected = PollerConfig {
+        destination: "127.0.0.1:8020".to_owned(),
+        subsystem: "eps".to_owned(),
+        channels: vec![
+            channel(
+                Board::Motherboard,
+                "OutputVoltageBattery",
+                limits(Some(6.2), None),
+                limits(Some(7.0), Some(8.3)),
+            ),
+            ChannelConfig {
+                board: Board::Daughterboard,
+                channel: "ArrayTempSa4a".to_owned(),
+                interval: 60.0,
+                red: None,
+                yellow: None,
+            },
+        ],
+    };
+
+    assert_eq!(PollerConfig::from_config(&config), Ok(Some(expected)));
+}
+
+#[test]
+fn config_missing() {
+    let config = Config::new_from_str("clyde-3g-eps-service", "");
+
+    assert_eq!(PollerConfig::from_config(&config), Ok(None));
+}
+
+#[test]
+fn config_bad_board() {
+    let config = Config::new_from_str(
+        "clyde-3g-eps-service",
+        r#"
+        [clyde-3g-eps-service.poller]
+        destination = "127.0.0.1:8020"
+
+        [[clyde-3g-eps-service.poller.channels]]
+        board = "backplane"
+        channel = "OutputVoltageBattery"
+        "#,
+    );
+
+    assert!(PollerConfig::from_config(&config).is_err());
+}
+
+#[test]
+fn poll_sends_telemetry() {
+    let (mut poller, status, listener) = setup(vec![
+        channel(Board::Motherboard, "OutputVoltageBattery", None, None),
+        channel(Board::Daughterboard, "ArrayTempSa4a", None, None),
+    ]);
+
+    let now = Instant::now();
+    let next = poller.poll(now);
+    assert_eq!(next, Some(now + Duration::from_secs(10)));
+
+    let first = receive(&listener);
+    assert_eq!(first["subsystem"], "eps");
+    assert_eq!(first["parameter"], "motherboard.OutputVoltageBattery");
+    assert_eq!(first["value"], "105.13");
+    assert!(first["timestamp"].is_f64());
+
+    let second = receive(&listener);
+    assert_eq!(second["parameter"], "daughterboard.ArrayTempSa4a");
+    assert_eq!(second["value"], "101.55");
+
+    assert!(status.take_error_messages().is_empty());
+}
+
+#[test]
+fn poll_not_due() {
+    let (mut poller, _status, listener) = setup(vec![channel(
+        Board::Motherboard,
+        "OutputVoltageBattery",
+        None,
+        None,
+    )]);
+
+    let now = Instant::now();
+    poller.poll(now);
+    receive(&listener);
+
+    listener
+        .set_read_timeout(Some(Duration::from_millis(100)))
+        .unwrap();
+    poller.poll(now + Duration::from_secs(5));
+
+    let mut buf = [0; 1024];
+    assert!(listener.recv_from(&mut buf).is_err());
+}
+
+#[test]
+fn poll_limit_errors() {
+    let (mut poller, status, _listener) = setup(vec![
+        channel(
+            Board::Motherboard,
+            "OutputVoltageBattery",
+            limits(None, Some(105.0)),
+            None,
+        ),
+        channel(
+            Board::Daughterboard,
+            "ArrayTempSa4a",
+            limits(None, Some(110.0)),
+            limits(None, Some(100.0)),
+        ),
+    ]);
+
+    let now = Instant::now();
+    poller.poll(now);
+
+    assert_eq!(
+        status.take_error_messages(),
+        vec![
+            "motherboard.OutputVoltageBattery outside of red limits: 105.13 > 105".to_owned(),
+            "daughterboard.ArrayTempSa4a outside of yellow limits: 101.55 > 100".to_owned(),
+        ]
+    );
+
+    // Values which stay out of limits aren't reported again
+    poller.poll(now + Duration::from_secs(10));
+    assert!(status.take_error_messages().is_empty());
+}
+
+#[test]
+fn poll_read_errors() {
+    let (mut poller, status, listener, eps) = setup_eps(
+        gen_mock_bad_eps(),
+        vec![channel(
+            Board::Motherboard,
+            "OutputVoltageBattery",
+            None,
+            None,
+        )],
+    );
+
+    let now = Instant::now();
+    poller.poll(now);
+
+    let errors = status.take_error_messages();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("Failed to read motherboard.OutputVoltageBattery: "));
+
+    // Channels which still can't be read aren't reported again
+    poller.poll(now + Duration::from_secs(10));
+    assert!(status.take_error_messages().is_empty());
+
+    *eps.lock().unwrap() = gen_mock_good_eps();
+    poller.poll(now + Duration::from_secs(20));
+    assert_eq!(receive(&listener)["value"], "105.13");
+    assert!(status.take_error_messages().is_empty());
+
+    // A new failure is reported once the channel has recovered
+    *eps.lock().unwrap() = gen_mock_bad_eps();
+    poller.poll(now + Duration::from_secs(30));
+    assert_eq!(status.take_error_messages().len(), 1);
+}
+
+#[test]
+fn unknown_channel() {
+    let (_poller, status, _listener) =
+        setup(vec![channel(Board::Motherboard, "NotAChannel", None, None)]);
+
+    assert_eq!(
+        status.take_error_messages(),
+        vec!["Unknown Motherboard poller channel: NotAChannel".to_owned()]
+    );
+}
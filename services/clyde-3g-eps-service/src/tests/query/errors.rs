@@ -19,7 +19,9 @@ use super::*;
 #[test]
 fn query_errors_empty() {
     let config: Config = Default::default();
-    let subsystem: Box<Subsystem> = Box::new(Subsystem::new(gen_mock_bad_eps()).unwrap());
+    let subsystem: Box<Subsystem> = Box::new(
+        Subsystem::new(gen_mock_bad_eps(), CommandStatus::new(Mutations::None)).unwrap(),
+    );
     let service = Service::new(config, subsystem, QueryRoot, MutationRoot);
 
     let query = r#"{
@@ -36,7 +38,9 @@ fn query_errors_empty() {
 #[test]
 fn query_errors_single() {
     let config: Config = Default::default();
-    let subsystem: Box<Subsystem> = Box::new(Subsystem::new(gen_mock_bad_eps()).unwrap());
+    let subsystem: Box<Subsystem> = Box::new(
+        Subsystem::new(gen_mock_bad_eps(), CommandStatus::new(Mutations::None)).unwrap(),
+    );
     let service = Service::new(config, subsystem, QueryRoot, MutationRoot);
 
     let reset = r#"mutation {
@@ -71,7 +75,9 @@ fn query_errors_single() {
 #[test]
 fn query_errors_multiple() {
     let config: Config = Default::default();
-    let subsystem: Box<Subsystem> = Box::new(Subsystem::new(gen_mock_bad_eps()).unwrap());
+    let subsystem: Box<Subsystem> = Box::new(
+        Subsystem::new(gen_mock_bad_eps(), CommandStatus::new(Mutations::None)).unwrap(),
+    );
     let service = Service::new(config, subsystem, QueryRoot, MutationRoot);
 
     let reset = r#"mutation {
@@ -108,7 +114,9 @@ fn query_errors_multiple() {
 #[test]
 fn query_errors_clear_after_query() {
     let config: Config = Default::default();
-    let subsystem: Box<Subsystem> = Box::new(Subsystem::new(gen_mock_bad_eps()).unwrap());
+    let subsystem: Box<Subsystem> = Box::new(
+        Subsystem::new(gen_mock_bad_eps(), CommandStatus::new(Mutations::None)).unwrap(),
+    );
     let service = Service::new(config, subsystem, QueryRoot, MutationRoot);
 
     let reset = r#"mutation {
@@ -131,3 +139,47 @@ fn query_errors_clear_after_query() {
 
     test!(service, query, expected);
 }
+
+#[test]
+fn query_error_log() {
+    let config: Config = Default::default();
+    let subsystem: Box<Subsystem> = Box::new(
+        Subsystem::new(gen_mock_bad_eps(), CommandStatus::new(Mutations::None)).unwrap(),
+    );
+    let service = Service::new(config, subsystem, QueryRoot, MutationRoot);
+
+    let reset = r#"mutation {
+        resetWatchdog {
+            success
+        }
+    }"#;
+
+    request!(service, reset);
+
+    // Reading the errors shouldn't remove them from the log
+    let errors = r#"{
+            errors
+        }"#;
+
+    request!(service, errors);
+
+    let query = r#"{
+            errorLog {
+                message,
+                severity
+            }
+        }"#;
+
+    let result: Vec<u8> = request!(service, query)
+        .body()
+        .iter()
+        .map(|entry| *entry)
+        .collect();
+    let result: serde_json::Value =
+        serde_json::from_str(::std::str::from_utf8(&result).unwrap()).unwrap();
+
+    let log = result["data"]["errorLog"].as_array().unwrap();
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0]["severity"], "ERROR");
+    assert!(log[0]["message"].as_str().unwrap().ends_with("Generic Error"));
+}
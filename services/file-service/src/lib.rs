@@ -14,10 +14,17 @@
 // limitations under the License.
 //
 
-use file_protocol::{FileProtocol, FileProtocolConfig, ProtocolError, State};
+mod queue;
+
+use crate::queue::DownlinkQueue;
+use file_protocol::{
+    FileProtocol, FileProtocolConfig, Message, ProtocolError, QueueEntry, State, TransferStatus,
+};
 use kubos_system::Config as ServiceConfig;
 use log::warn;
 use std::collections::HashMap;
+use std::env;
+use std::path::Path;
 use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
@@ -50,7 +57,19 @@ pub fn recv_loop(config: &ServiceConfig) -> Result<(), failure::Error> {
         None => 5,
     } as u16;
 
-    let f_config = FileProtocolConfig::new(prefix, chunk_size, hold_count);
+    // The downlink queue lives in the storage directory unless told otherwise
+    let queue_dir = prefix.clone().unwrap_or_else(|| "file-storage".to_owned());
+
+    let mut f_config = FileProtocolConfig::new(prefix, chunk_size, hold_count);
+
+    // Get the optional rate and window controls for outgoing chunks
+    if let Some(rate) = config.get("transmit_rate").and_then(|val| val.as_integer()) {
+        f_config = f_config.transmit_rate(rate as u32);
+    }
+
+    if let Some(window) = config.get("window_size").and_then(|val| val.as_integer()) {
+        f_config = f_config.window_size(window as u32);
+    }
 
     let c_protocol = cbor_protocol::Protocol::new(&host.clone(), chunk_size);
 
@@ -62,11 +81,28 @@ pub fn recv_loop(config: &ServiceConfig) -> Result<(), failure::Error> {
         })
         .unwrap_or(Duration::from_secs(2));
 
+    // Load the persistent queue of files waiting to be downlinked
+    let queue_file = config
+        .get("queue_file")
+        .and_then(|val| val.as_str().map(|str| str.to_owned()))
+        .unwrap_or_else(|| {
+            let path = Path::new(&queue_dir).join("downlink-queue");
+            env::current_dir()
+                .map(|dir| dir.join(&path))
+                .unwrap_or(path)
+                .to_string_lossy()
+                .into_owned()
+        });
+    let queue = Arc::new(Mutex::new(DownlinkQueue::open(&queue_file)));
+
     // Setup map of channel IDs to thread channels
     let raw_threads: HashMap<u32, Sender<serde_cbor::Value>> = HashMap::new();
     // Create thread sharable wrapper
     let threads = Arc::new(Mutex::new(raw_threads));
 
+    // Setup map of channel IDs to the progress of their transfers
+    let transfers: Arc<Mutex<HashMap<u32, TransferStatus>>> = Arc::new(Mutex::new(HashMap::new()));
+
     loop {
         // Listen on UDP port
         let (source, first_message) = match c_protocol.recv_message_peer() {
@@ -90,12 +126,76 @@ pub fn recv_loop(config: &ServiceConfig) -> Result<(), failure::Error> {
         };
 
         if !threads.lock().unwrap().contains_key(&channel_id) {
+            let mut downlink = None;
+            match file_protocol::parse_message(first_message.clone()) {
+                // Status requests are answered directly, since they need to see every transfer
+                Ok(Message::ReqStatus(_)) => {
+                    let mut status: Vec<TransferStatus> =
+                        transfers.lock().unwrap().values().cloned().collect();
+                    status.sort_by_key(|transfer| transfer.channel_id);
+
+                    let f_protocol =
+                        FileProtocol::new(&host_ref, &format!("{}", source), config_ref);
+                    if let Err(e) = f_protocol.send_status(channel_id, &status) {
+                        warn!("Error sending transfer status: {}", e);
+                    }
+                    continue;
+                }
+                // Downlink requests get the highest priority file which isn't already
+                // being sent
+                Ok(Message::ReqDownlink(_)) => {
+                    let error = match queue.lock().unwrap().start_next() {
+                        Ok(Some(entry)) => {
+                            downlink = Some(entry);
+                            None
+                        }
+                        Ok(None) => Some("Downlink queue is empty".to_owned()),
+                        Err(e) => Some(format!("{}", e)),
+                    };
+
+                    if let Some(error) = error {
+                        let f_protocol =
+                            FileProtocol::new(&host_ref, &format!("{}", source), config_ref);
+                        if let Err(e) = f_protocol.send_failure(channel_id, &error) {
+                            warn!("Error sending downlink failure: {}", e);
+                        }
+                        continue;
+                    }
+                }
+                // Any other queue operations are also answered directly
+                Ok(message) => {
+                    if let Some(result) = queue_request(&queue, &message) {
+                        let f_protocol =
+                            FileProtocol::new(&host_ref, &format!("{}", source), config_ref);
+                        let sent = match result {
+                            Ok(entries) => f_protocol.send_queue(channel_id, &entries),
+                            Err(e) => f_protocol.send_failure(channel_id, &format!("{}", e)),
+                        };
+                        if let Err(e) = sent {
+                            warn!("Error sending downlink queue reply: {}", e);
+                        }
+                        continue;
+                    }
+                }
+                Err(_) => {}
+            }
+
             let (sender, receiver): (Sender<serde_cbor::Value>, Receiver<serde_cbor::Value>) =
                 mpsc::channel();
             threads.lock().unwrap().insert(channel_id, sender.clone());
             // Break the processing work off into its own thread so we can
             // listen for requests from other clients
             let shared_threads = threads.clone();
+            let shared_transfers = transfers.clone();
+            let shared_queue = queue.clone();
+            let is_downlink = downlink.is_some();
+            let progress_transfers = transfers.clone();
+            let config_ref = config_ref.progress(move |status| {
+                progress_transfers
+                    .lock()
+                    .unwrap()
+                    .insert(status.channel_id, status.clone());
+            });
             thread::spawn(move || {
                 let state = State::Holding {
                     count: 0,
@@ -105,25 +205,62 @@ pub fn recv_loop(config: &ServiceConfig) -> Result<(), failure::Error> {
                 // Set up the file system processor with the reply socket information
                 let f_protocol = FileProtocol::new(&host_ref, &format!("{}", source), config_ref);
 
+                let pump = |d| match receiver.recv_timeout(d) {
+                    Ok(v) => Ok(v),
+                    Err(RecvTimeoutError::Timeout) => Err(ProtocolError::ReceiveTimeout),
+                    Err(e) => Err(ProtocolError::ReceiveError {
+                        err: format!("Error {:?}", e),
+                    }),
+                };
+
                 // Listen, process, and react to the remaining messages in the
                 // requested operation
-                if let Err(e) = f_protocol.message_engine(
-                    |d| match receiver.recv_timeout(d) {
-                        Ok(v) => Ok(v),
-                        Err(RecvTimeoutError::Timeout) => Err(ProtocolError::ReceiveTimeout),
-                        Err(e) => Err(ProtocolError::ReceiveError {
-                            err: format!("Error {:?}", e),
-                        }),
+                let result = match &downlink {
+                    Some(entry) => match f_protocol.send_downlink(channel_id, entry) {
+                        Ok(()) => {
+                            f_protocol.message_engine(&pump, timeout_ref, &State::Transmitting)
+                        }
+                        Err(e) => {
+                            // The file can't be sent, so there's no point keeping it queued
+                            warn!("Dropping {} from downlink queue: {}", entry.path, e);
+                            let _ = shared_queue.lock().unwrap().cancel(entry.id);
+                            Err(e)
+                        }
                     },
-                    timeout_ref,
-                    &state,
-                ) {
+                    None => f_protocol.message_engine(&pump, timeout_ref, &state),
+                };
+
+                if let Err(e) = &result {
                     warn!("Encountered errors while processing transaction: {}", e);
                 }
 
-                // Remove ourselves from threads list if we are finished
+                // Delivered files leave the downlink queue. Anything else will be
+                // resumed from the chunks already in storage at the next request
+                if let Some(entry) = downlink {
+                    if let Err(e) = shared_queue
+                        .lock()
+                        .unwrap()
+                        .finish(entry.id, result.is_ok())
+                    {
+                        warn!("Error updating downlink queue: {}", e);
+                    }
+                }
+
+                // A full cleanup removes the storage directory, and the queue file
+                // along with it
+                if let Err(e) = shared_queue.lock().unwrap().restore() {
+                    warn!("Error saving downlink queue: {}", e);
+                }
+
+                // Remove ourselves from threads and transfers lists if we are finished
                 shared_threads.lock().unwrap().remove(&channel_id);
+                shared_transfers.lock().unwrap().remove(&channel_id);
             });
+
+            // The downlink request itself has already been handled
+            if is_downlink {
+                continue;
+            }
         }
 
         if let Some(sender) = threads.lock().unwrap().get(&channel_id) {
@@ -138,3 +275,23 @@ pub fn recv_loop(config: &ServiceConfig) -> Result<(), failure::Error> {
         }
     }
 }
+
+// Apply a downlink queue operation, returning the resulting queue contents.
+// Returns `None` if the message isn't a queue operation
+fn queue_request(
+    queue: &Mutex<DownlinkQueue>,
+    message: &Message,
+) -> Option<Result<Vec<QueueEntry>, failure::Error>> {
+    let mut queue = queue.lock().unwrap();
+    let result = match message {
+        Message::ReqEnqueue(_, path, priority, lifetime) => {
+            queue.enqueue(path, *priority, *lifetime).map(|_| ())
+        }
+        Message::ReqReprioritize(_, id, priority) => queue.reprioritize(*id, *priority),
+        Message::ReqCancel(_, id) => queue.cancel(*id),
+        Message::ReqQueue(_) => Ok(()),
+        _ => return None,
+    };
+
+    Some(result.and_then(|_| queue.entries()))
+}
@@ -0,0 +1,375 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+// Persistent downlink queue
+//
+// Entries are kept in downlink order: highest priority first, then oldest first.
+// The whole queue is rewritten to disk after every change, so that it survives
+// service restarts and reboots between passes
+
+use failure::{bail, format_err, Error};
+use file_protocol::QueueEntry;
+use log::warn;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// On-disk format: the next ID to assign, followed by the entries as
+// [ id, path, priority, expires ]
+type StoredQueue = (u32, Vec<(u32, String, u32, Option<u64>)>);
+
+pub struct DownlinkQueue {
+    path: PathBuf,
+    next_id: u32,
+    entries: Vec<QueueEntry>,
+    // Entries which are currently being downlinked
+    active: HashSet<u32>,
+}
+
+impl DownlinkQueue {
+    // Load the queue stored at the given path. A missing file is an empty queue.
+    // A file which can't be read or parsed is moved aside to `<path>.bad` so that
+    // it can be inspected later, and we start again with an empty queue
+    pub fn open(path: &str) -> Self {
+        let mut queue = DownlinkQueue {
+            path: PathBuf::from(path),
+            next_id: 1,
+            entries: vec![],
+            active: HashSet::new(),
+        };
+
+        if !queue.path.exists() {
+            return queue;
+        }
+
+        let (next_id, entries) = match load(&queue.path) {
+            Ok(stored) => stored,
+            Err(err) => {
+                let bad = format!("{}.bad", path);
+                warn!(
+                    "Failed to load downlink queue {}: {}. Moving it to {} and starting with an empty queue",
+                    path, err, bad
+                );
+                if let Err(err) = fs::rename(path, &bad) {
+                    warn!("Failed to move downlink queue {}: {}", path, err);
+                }
+                return queue;
+            }
+        };
+
+        queue.next_id = next_id;
+        queue.entries = entries
+            .into_iter()
+            .map(|(id, path, priority, expires)| QueueEntry {
+                id,
+                path,
+                priority,
+                expires,
+            })
+            .collect();
+        queue.sort();
+
+        queue
+    }
+
+    // Rewrite the queue file if it has gone missing, e.g. because a full storage
+    // cleanup removed the directory it lives in
+    pub fn restore(&self) -> Result<(), Error> {
+        if self.path.exists() {
+            return Ok(());
+        }
+
+        self.save()
+    }
+
+    // Get the current contents of the queue, in downlink order
+    pub fn entries(&mut self) -> Result<Vec<QueueEntry>, Error> {
+        self.expire()?;
+        Ok(self.entries.clone())
+    }
+
+    // Add a file to the queue. The lifetime is the number of seconds after which
+    // the entry should be dropped if it still hasn't been downlinked
+    pub fn enqueue(
+        &mut self,
+        path: &str,
+        priority: u32,
+        lifetime: Option<u64>,
+    ) -> Result<QueueEntry, Error> {
+        match fs::metadata(path) {
+            Ok(ref meta) if meta.is_file() => {}
+            Ok(_) => bail!("{} is not a file", path),
+            Err(err) => bail!("Unable to enqueue {}: {}", path, err),
+        }
+
+        let entry = QueueEntry {
+            id: self.next_id,
+            path: path.to_owned(),
+            priority,
+            expires: lifetime.map(|lifetime| now() + lifetime),
+        };
+
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        self.entries.push(entry.clone());
+        self.sort();
+        self.save()?;
+
+        Ok(entry)
+    }
+
+    // Change the priority of an entry
+    pub fn reprioritize(&mut self, id: u32, priority: u32) -> Result<(), Error> {
+        match self.entries.iter_mut().find(|entry| entry.id == id) {
+            Some(entry) => entry.priority = priority,
+            None => bail!("No downlink queue entry with ID {}", id),
+        }
+
+        self.sort();
+        self.save()
+    }
+
+    // Remove an entry from the queue
+    pub fn cancel(&mut self, id: u32) -> Result<(), Error> {
+        if !self.entries.iter().any(|entry| entry.id == id) {
+            bail!("No downlink queue entry with ID {}", id);
+        }
+
+        self.entries.retain(|entry| entry.id != id);
+        self.save()
+    }
+
+    // Get the highest priority entry which isn't already being downlinked,
+    // and mark it as active
+    pub fn start_next(&mut self) -> Result<Option<QueueEntry>, Error> {
+        self.expire()?;
+
+        let active = &self.active;
+        let next = self
+            .entries
+            .iter()
+            .find(|entry| !active.contains(&entry.id))
+            .cloned();
+
+        if let Some(entry) = &next {
+            self.active.insert(entry.id);
+        }
+
+        Ok(next)
+    }
+
+    // Mark a downlink as finished. Delivered files are removed from the queue;
+    // anything else stays queued so that it can be resumed at the next pass
+    pub fn finish(&mut self, id: u32, delivered: bool) -> Result<(), Error> {
+        self.active.remove(&id);
+
+        if delivered && self.entries.iter().any(|entry| entry.id == id) {
+            self.entries.retain(|entry| entry.id != id);
+            self.save()?;
+        }
+
+        Ok(())
+    }
+
+    // Drop any entries which have outlived their expiry time
+    fn expire(&mut self) -> Result<(), Error> {
+        let now = now();
+        let count = self.entries.len();
+        self.entries.retain(|entry| !entry.is_expired(now));
+
+        if self.entries.len() != count {
+            self.save()?;
+        }
+
+        Ok(())
+    }
+
+    fn sort(&mut self) {
+        self.entries
+            .sort_by(|a, b| b.priority.cmp(&a.priority).then(a.id.cmp(&b.id)));
+    }
+
+    // Write the queue out to a temporary file, then move it into place so that
+    // a badly-timed reset can't leave us with a corrupted queue
+    fn save(&self) -> Result<(), Error> {
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .map(|entry| (entry.id, &entry.path, entry.priority, entry.expires))
+            .collect();
+        let data = serde_cbor::ser::to_vec_packed(&(self.next_id, entries))?;
+
+        if let Some(parent) = self.path.parent() {
+            if parent != Path::new("") {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        let temp = self.path.with_extension("tmp");
+        fs::write(&temp, &data)?;
+        fs::rename(&temp, &self.path)?;
+
+        Ok(())
+    }
+}
+
+// Read and parse a stored queue
+fn load(path: &Path) -> Result<StoredQueue, Error> {
+    let data = fs::read(path).map_err(|err| format_err!("Failed to read file: {}", err))?;
+    serde_cbor::from_slice(&data).map_err(|err| format_err!("Failed to parse file: {}", err))
+}
+
+// Current time, in seconds since the UNIX epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn queue_order() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("file");
+        let file = file.to_str().unwrap();
+        fs::write(file, "contents").unwrap();
+
+        let queue_path = dir.path().join("queue");
+        let mut queue = DownlinkQueue::open(queue_path.to_str().unwrap());
+
+        let low = queue.enqueue(file, 1, None).unwrap();
+        let high = queue.enqueue(file, 5, None).unwrap();
+        let low2 = queue.enqueue(file, 1, None).unwrap();
+
+        let ids: Vec<u32> = queue.entries().unwrap().iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![high.id, low.id, low2.id]);
+
+        queue.reprioritize(low2.id, 10).unwrap();
+        let ids: Vec<u32> = queue.entries().unwrap().iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![low2.id, high.id, low.id]);
+
+        // Active downlinks are skipped
+        assert_eq!(queue.start_next().unwrap().unwrap().id, low2.id);
+        assert_eq!(queue.start_next().unwrap().unwrap().id, high.id);
+
+        // Incomplete downlinks stay queued, delivered ones are removed
+        queue.finish(low2.id, false).unwrap();
+        queue.finish(high.id, true).unwrap();
+        let ids: Vec<u32> = queue.entries().unwrap().iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![low2.id, low.id]);
+    }
+
+    #[test]
+    fn queue_persists() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("file");
+        let file = file.to_str().unwrap();
+        fs::write(file, "contents").unwrap();
+
+        let queue_path = dir.path().join("queue");
+        let queue_path = queue_path.to_str().unwrap();
+        let first = {
+            let mut queue = DownlinkQueue::open(queue_path);
+            let first = queue.enqueue(file, 3, Some(3600)).unwrap();
+            let second = queue.enqueue(file, 2, None).unwrap();
+            queue.cancel(second.id).unwrap();
+            first
+        };
+
+        let mut queue = DownlinkQueue::open(queue_path);
+        assert_eq!(queue.entries().unwrap(), vec![first.clone()]);
+
+        // IDs aren't reused
+        let third = queue.enqueue(file, 2, None).unwrap();
+        assert_eq!(third.id, first.id + 2);
+    }
+
+    #[test]
+    fn queue_expiry_and_errors() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("file");
+        let file = file.to_str().unwrap();
+        fs::write(file, "contents").unwrap();
+
+        let queue_path = dir.path().join("queue");
+        let mut queue = DownlinkQueue::open(queue_path.to_str().unwrap());
+
+        queue.enqueue(file, 1, Some(0)).unwrap();
+        assert_eq!(queue.entries().unwrap(), vec![]);
+        assert_eq!(queue.start_next().unwrap(), None);
+
+        assert!(queue
+            .enqueue(dir.path().join("missing").to_str().unwrap(), 1, None)
+            .is_err());
+        assert!(queue
+            .enqueue(dir.path().to_str().unwrap(), 1, None)
+            .is_err());
+        assert!(queue.cancel(100).is_err());
+        assert!(queue.reprioritize(100, 1).is_err());
+    }
+
+    #[test]
+    fn queue_corrupt_file() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("file");
+        let file = file.to_str().unwrap();
+        fs::write(file, "contents").unwrap();
+
+        let queue_path = dir.path().join("queue");
+        let queue_path = queue_path.to_str().unwrap();
+        fs::write(queue_path, "not a queue").unwrap();
+
+        // The bad file is kept for inspection and we start with an empty queue
+        let mut queue = DownlinkQueue::open(queue_path);
+        assert_eq!(queue.entries().unwrap(), vec![]);
+        assert_eq!(
+            fs::read(format!("{}.bad", queue_path)).unwrap(),
+            b"not a queue".to_vec()
+        );
+
+        let entry = queue.enqueue(file, 1, None).unwrap();
+        assert_eq!(
+            DownlinkQueue::open(queue_path).entries().unwrap(),
+            vec![entry]
+        );
+    }
+
+    #[test]
+    fn queue_restore() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("file");
+        let file = file.to_str().unwrap();
+        fs::write(file, "contents").unwrap();
+
+        let storage = dir.path().join("storage");
+        let queue_path = storage.join("queue");
+        let queue_path = queue_path.to_str().unwrap();
+        let mut queue = DownlinkQueue::open(queue_path);
+        let entry = queue.enqueue(file, 1, None).unwrap();
+
+        fs::remove_dir_all(&storage).unwrap();
+        queue.restore().unwrap();
+        assert_eq!(
+            DownlinkQueue::open(queue_path).entries().unwrap(),
+            vec![entry]
+        );
+    }
+}
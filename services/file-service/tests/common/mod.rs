@@ -27,6 +27,9 @@ use std::time::Duration;
 #[macro_export]
 macro_rules! service_new {
     ($port:expr, $chunk_size:expr) => {{
+        service_new!($port, $chunk_size, "")
+    }};
+    ($port:expr, $chunk_size:expr, $extra:expr) => {{
         thread::spawn(move || {
             recv_loop(&ServiceConfig::new_from_str(
                 "file-transfer-service",
@@ -36,11 +39,12 @@ macro_rules! service_new {
                 storage_dir = "service"
                 chunk_size = {}
                 hold_count = 5
+                {}
                 [file-transfer-service.addr]
                 ip = "127.0.0.1"
                 port = {}
                 "#,
-                    $chunk_size, $port
+                    $chunk_size, $extra, $port
                 ),
             ))
             .unwrap();
@@ -210,6 +214,72 @@ pub fn upload_partial(
     Ok(hash.to_owned())
 }
 
+pub fn upload_dir(
+    host_ip: &str,
+    remote_addr: &str,
+    source_path: &str,
+    target_path: &str,
+    prefix: Option<String>,
+    chunk_size: u32,
+) -> Result<String, ProtocolError> {
+    let hold_count = 5;
+    let f_config = FileProtocolConfig::new(prefix, chunk_size as usize, hold_count);
+    let f_protocol = FileProtocol::new(host_ip, remote_addr, f_config);
+
+    // Copy the directory's contents to temp storage and build its manifest
+    let (hash, num_chunks, mode) = f_protocol.initialize_directory(&source_path)?;
+
+    let channel = f_protocol.generate_channel()?;
+
+    // Tell our destination the manifest hash and number of chunks to expect
+    f_protocol.send_metadata(channel, &hash, num_chunks)?;
+
+    // Send export command for the directory
+    f_protocol.send_export_dir(channel, &hash, &target_path, mode)?;
+
+    // Start the engine to send the manifest and content chunks
+    f_protocol.message_engine(
+        |d| f_protocol.recv(Some(d)),
+        Duration::from_secs(2),
+        &State::Transmitting,
+    )?;
+
+    Ok(hash.to_owned())
+}
+
+pub fn download_dir(
+    host_ip: &str,
+    remote_addr: &str,
+    source_path: &str,
+    target_path: &str,
+    prefix: Option<String>,
+    chunk_size: u32,
+) -> Result<(), ProtocolError> {
+    let hold_count = 5;
+    let f_config = FileProtocolConfig::new(prefix, chunk_size as usize, hold_count);
+    let f_protocol = FileProtocol::new(host_ip, remote_addr, f_config);
+
+    let channel = f_protocol.generate_channel()?;
+
+    // Send our directory request to the remote addr and verify that it's
+    // going to be able to send it
+    f_protocol.send_import_dir(channel, source_path)?;
+
+    let reply = match f_protocol.recv(None) {
+        Ok(message) => message,
+        Err(error) => return Err(error),
+    };
+
+    let state = f_protocol.process_message(
+        reply,
+        &State::StartReceiveDir {
+            path: target_path.to_string(),
+        },
+    )?;
+
+    Ok(f_protocol.message_engine(|d| f_protocol.recv(Some(d)), Duration::from_secs(2), &state)?)
+}
+
 pub fn cleanup(
     host_ip: &str,
     remote_addr: &str,
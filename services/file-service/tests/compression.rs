@@ -0,0 +1,191 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+mod common;
+
+use crate::common::*;
+use file_protocol::{Compression, FileProtocol, FileProtocolConfig, State};
+use file_service::recv_loop;
+use kubos_system::Config as ServiceConfig;
+use rand::{thread_rng, Rng};
+use serde_cbor::Value;
+use std::fs;
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+// NOTE: Each test's file contents must be unique. Otherwise the hash is the same, so
+// the same storage directory is used across all of them, creating conflicts
+
+fn compressed_upload(
+    service_port: u16,
+    source: &str,
+    dest: &str,
+    compression: Compression,
+) -> Result<(), file_protocol::ProtocolError> {
+    let f_config =
+        FileProtocolConfig::new(Some("client".to_owned()), 1024, 5).compression(compression);
+    let f_protocol = FileProtocol::new(
+        "127.0.0.1",
+        &format!("127.0.0.1:{}", service_port),
+        f_config,
+    );
+
+    let (hash, num_chunks, mode) = f_protocol.initialize_file(source)?;
+    let channel = f_protocol.generate_channel()?;
+    f_protocol.send_metadata(channel, &hash, num_chunks)?;
+    f_protocol.send_export(channel, &hash, dest, mode)?;
+
+    f_protocol.message_engine(
+        |d| f_protocol.recv(Some(d)),
+        Duration::from_secs(2),
+        &State::Transmitting,
+    )
+}
+
+// Download a file, returning the compression field of the service's import reply
+fn compressed_download(
+    service_port: u16,
+    source: &str,
+    dest: &str,
+    compression: Compression,
+) -> Result<Option<Value>, file_protocol::ProtocolError> {
+    let f_config =
+        FileProtocolConfig::new(Some("client".to_owned()), 1024, 5).compression(compression);
+    let f_protocol = FileProtocol::new(
+        "127.0.0.1",
+        &format!("127.0.0.1:{}", service_port),
+        f_config,
+    );
+
+    let channel = f_protocol.generate_channel()?;
+    f_protocol.send_import(channel, source)?;
+
+    let reply = f_protocol.recv(None)?;
+    let used = reply.as_array().and_then(|fields| fields.get(5).cloned());
+
+    let state = f_protocol.process_message(
+        reply,
+        &State::StartReceive {
+            path: dest.to_owned(),
+        },
+    )?;
+
+    f_protocol.message_engine(|d| f_protocol.recv(Some(d)), Duration::from_secs(2), &state)?;
+
+    Ok(used)
+}
+
+// Upload a compressible, multi-chunk file with each compression algorithm
+#[test]
+fn upload_compressed() {
+    let test_dir = TempDir::new().expect("Failed to create test dir");
+    let test_dir_str = test_dir.path().to_str().unwrap();
+    let service_port = 7012;
+
+    service_new!(service_port, 1024);
+
+    for (num, compression) in [Compression::Gzip, Compression::Zstd, Compression::Lz4]
+        .iter()
+        .enumerate()
+    {
+        let source = format!("{}/source{}", test_dir_str, num);
+        let dest = format!("{}/dest{}", test_dir_str, num);
+        let contents: Vec<u8> = format!("upload compressed {} ", compression)
+            .into_bytes()
+            .into_iter()
+            .cycle()
+            .take(20000)
+            .collect();
+
+        create_test_file(&source, &contents);
+
+        let result = compressed_upload(service_port, &source, &dest, *compression);
+
+        if let Err(err) = &result {
+            println!("Error: {}", err);
+        }
+
+        assert!(result.is_ok());
+
+        // Verify the final file's contents
+        let dest_contents = fs::read(dest).unwrap();
+        assert_eq!(contents, dest_contents);
+    }
+}
+
+// Download a compressible file, asking the service to compress it
+#[test]
+fn download_compressed() {
+    let test_dir = TempDir::new().expect("Failed to create test dir");
+    let test_dir_str = test_dir.path().to_str().unwrap();
+    let source = format!("{}/source", test_dir_str);
+    let dest = format!("{}/dest", test_dir_str);
+    let service_port = 8012;
+
+    let contents: Vec<u8> = b"download compressed "
+        .iter()
+        .cycle()
+        .take(20000)
+        .cloned()
+        .collect();
+
+    create_test_file(&source, &contents);
+
+    service_new!(service_port, 1024);
+
+    let result = compressed_download(service_port, &source, &dest, Compression::Zstd);
+
+    if let Err(err) = &result {
+        println!("Error: {}", err);
+    }
+
+    assert_eq!(result.unwrap(), Some(Value::String("zstd".to_owned())));
+
+    // Verify the final file's contents
+    let dest_contents = fs::read(dest).unwrap();
+    assert_eq!(contents, dest_contents);
+}
+
+// Download a file which won't shrink. The service should fall back to
+// sending it uncompressed
+#[test]
+fn download_incompressible() {
+    let test_dir = TempDir::new().expect("Failed to create test dir");
+    let test_dir_str = test_dir.path().to_str().unwrap();
+    let source = format!("{}/source", test_dir_str);
+    let dest = format!("{}/dest", test_dir_str);
+    let service_port = 8013;
+
+    let mut contents = vec![0u8; 5000];
+    thread_rng().fill(&mut contents[..]);
+
+    create_test_file(&source, &contents);
+
+    service_new!(service_port, 1024);
+
+    let result = compressed_download(service_port, &source, &dest, Compression::Gzip);
+
+    if let Err(err) = &result {
+        println!("Error: {}", err);
+    }
+
+    assert_eq!(result.unwrap(), None);
+
+    // Verify the final file's contents
+    let dest_contents = fs::read(dest).unwrap();
+    assert_eq!(contents, dest_contents);
+}
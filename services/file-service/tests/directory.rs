@@ -0,0 +1,134 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+mod common;
+
+use crate::common::*;
+use file_service::recv_loop;
+use kubos_system::Config as ServiceConfig;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+// NOTE: Each test's file contents must be unique. Otherwise the hash is the same, so
+// the same storage directory is used across all of them, creating conflicts
+
+// Create a small directory tree with a mix of packed and standalone files
+fn create_test_dir(root: &str, tag: &str) {
+    fs::create_dir_all(format!("{}/sub/deeper", root)).unwrap();
+    create_test_file(
+        &format!("{}/small", root),
+        format!("{}_small", tag).as_bytes(),
+    );
+    create_test_file(
+        &format!("{}/sub/other", root),
+        format!("{}_other", tag).as_bytes(),
+    );
+
+    let mut large = vec![0u8; 9000];
+    large[..tag.len()].copy_from_slice(tag.as_bytes());
+    create_test_file(&format!("{}/sub/deeper/large", root), &large);
+    fs::set_permissions(
+        format!("{}/sub/deeper/large", root),
+        fs::Permissions::from_mode(0o755),
+    )
+    .unwrap();
+}
+
+fn verify_test_dir(root: &str, tag: &str) {
+    assert_eq!(
+        fs::read(format!("{}/small", root)).unwrap(),
+        format!("{}_small", tag).as_bytes()
+    );
+    assert_eq!(
+        fs::read(format!("{}/sub/other", root)).unwrap(),
+        format!("{}_other", tag).as_bytes()
+    );
+
+    let large = fs::read(format!("{}/sub/deeper/large", root)).unwrap();
+    assert_eq!(large.len(), 9000);
+    assert_eq!(&large[..tag.len()], tag.as_bytes());
+
+    let mode = fs::metadata(format!("{}/sub/deeper/large", root))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o755);
+}
+
+// Upload a directory tree from scratch
+#[test]
+fn upload_directory() {
+    let test_dir = TempDir::new().expect("Failed to create test dir");
+    let test_dir_str = test_dir.path().to_str().unwrap();
+    let source = format!("{}/source", test_dir_str);
+    let dest = format!("{}/dest", test_dir_str);
+    let service_port = 7010;
+
+    create_test_dir(&source, "upload_directory");
+
+    service_new!(service_port, 4096);
+
+    let result = upload_dir(
+        "127.0.0.1",
+        &format!("127.0.0.1:{}", service_port),
+        &source,
+        &dest,
+        Some("client".to_owned()),
+        4096,
+    );
+
+    if let Err(err) = &result {
+        println!("Error: {}", err);
+    }
+
+    assert!(result.is_ok());
+
+    verify_test_dir(&dest, "upload_directory");
+}
+
+// Download a directory tree from scratch
+#[test]
+fn download_directory() {
+    let test_dir = TempDir::new().expect("Failed to create test dir");
+    let test_dir_str = test_dir.path().to_str().unwrap();
+    let source = format!("{}/source", test_dir_str);
+    let dest = format!("{}/dest", test_dir_str);
+    let service_port = 8010;
+
+    create_test_dir(&source, "download_directory");
+
+    service_new!(service_port, 4096);
+
+    let result = download_dir(
+        "127.0.0.1",
+        &format!("127.0.0.1:{}", service_port),
+        &source,
+        &dest,
+        Some("client".to_owned()),
+        4096,
+    );
+
+    if let Err(err) = &result {
+        println!("Error: {}", err);
+    }
+
+    assert!(result.is_ok());
+
+    verify_test_dir(&dest, "download_directory");
+}
@@ -0,0 +1,220 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+mod common;
+
+use crate::common::*;
+use file_protocol::{FileProtocol, FileProtocolConfig, Message, QueueEntry, State};
+use file_service::recv_loop;
+use kubos_system::Config as ServiceConfig;
+use serde_cbor::{from_slice, ser, Value};
+use std::fs;
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+// NOTE: Each test's file contents must be unique. Otherwise the hash is the same, so
+// the same storage directory is used across all of them, creating conflicts
+
+fn new_client(service_port: u16) -> FileProtocol {
+    let f_config = FileProtocolConfig::new(Some("client".to_owned()), 1024, 5);
+    FileProtocol::new(
+        "127.0.0.1",
+        &format!("127.0.0.1:{}", service_port),
+        f_config,
+    )
+}
+
+// Send a queue operation and return the service's reply
+fn queue_op<F>(service_port: u16, send: F) -> Message
+where
+    F: Fn(&FileProtocol, u32),
+{
+    let f_protocol = new_client(service_port);
+    let channel = f_protocol.generate_channel().unwrap();
+    send(&f_protocol, channel);
+
+    let reply = f_protocol.recv(Some(Duration::from_secs(2))).unwrap();
+    file_protocol::parse_message(reply).unwrap()
+}
+
+fn queue_list(service_port: u16) -> Vec<String> {
+    queue_paths(queue_op(service_port, |f_protocol, channel| {
+        f_protocol.send_queue_request(channel).unwrap()
+    }))
+}
+
+fn queue_paths(message: Message) -> Vec<String> {
+    match message {
+        Message::Queue(_, entries) => entries.into_iter().map(|entry| entry.path).collect(),
+        other => panic!("Unexpected reply: {:?}", other),
+    }
+}
+
+// Downlink the next queued file into `dest`. Returns the path of the file which
+// was sent, or `None` if the queue was empty.
+// If `partial` is set, the client pretends the file is one chunk shorter than
+// it really is, so that the transfer can't complete
+fn downlink(service_port: u16, dest: &str, partial: bool) -> Option<String> {
+    let f_protocol = new_client(service_port);
+    let channel = f_protocol.generate_channel().unwrap();
+    f_protocol.send_downlink_request(channel).unwrap();
+
+    let mut reply = f_protocol.recv(None).unwrap();
+    let path = match file_protocol::parse_message(reply.clone()).unwrap() {
+        Message::Downlink(_, _, path, _, _, _, _) => path,
+        Message::Failure(_, error) => {
+            assert_eq!(error, "Downlink queue is empty");
+            return None;
+        }
+        other => panic!("Unexpected reply: {:?}", other),
+    };
+
+    if partial {
+        let fields = reply.as_array_mut().unwrap();
+        let num_chunks = fields[5].as_u64().unwrap();
+        fields[5] = Value::U64(num_chunks - 1);
+        reply = from_slice(&ser::to_vec_packed(&reply).unwrap()).unwrap();
+    }
+
+    let result = f_protocol
+        .process_message(
+            reply,
+            &State::StartReceive {
+                path: dest.to_owned(),
+            },
+        )
+        .and_then(|state| {
+            f_protocol.message_engine(|d| f_protocol.recv(Some(d)), Duration::from_secs(1), &state)
+        });
+
+    if !partial {
+        assert!(result.is_ok());
+    }
+
+    Some(path)
+}
+
+// Queue up several files, rearrange them, and then downlink them in priority order
+#[test]
+fn downlink_in_priority_order() {
+    let test_dir = TempDir::new().expect("Failed to create test dir");
+    let test_dir_str = test_dir.path().to_str().unwrap();
+    let service_port = 8014;
+
+    let names = ["low", "high", "cancelled"];
+    for name in names.iter() {
+        create_test_file(
+            &format!("{}/{}", test_dir_str, name),
+            format!("downlink in priority order {}", name).as_bytes(),
+        );
+    }
+    let path = |name: &str| format!("{}/{}", test_dir_str, name);
+
+    let queue_config = format!("queue_file = \"{}/queue\"", test_dir_str);
+    service_new!(service_port, 1024, queue_config);
+
+    let reply = queue_op(service_port, |f, ch| {
+        f.send_enqueue(ch, &path("low"), 1, None).unwrap()
+    });
+    assert_eq!(queue_paths(reply), vec![path("low")]);
+    queue_op(service_port, |f, ch| {
+        f.send_enqueue(ch, &path("high"), 5, Some(3600)).unwrap()
+    });
+    let reply = queue_op(service_port, |f, ch| {
+        f.send_enqueue(ch, &path("cancelled"), 3, None).unwrap()
+    });
+    assert_eq!(
+        queue_paths(reply.clone()),
+        vec![path("high"), path("cancelled"), path("low")]
+    );
+
+    let ids: Vec<u32> = match reply {
+        Message::Queue(_, entries) => entries.iter().map(|entry: &QueueEntry| entry.id).collect(),
+        _ => unreachable!(),
+    };
+
+    // Cancel one file and move another to the front of the queue
+    queue_op(service_port, |f, ch| f.send_cancel(ch, ids[1]).unwrap());
+    let reply = queue_op(service_port, |f, ch| {
+        f.send_reprioritize(ch, ids[2], 10).unwrap()
+    });
+    assert_eq!(queue_paths(reply), vec![path("low"), path("high")]);
+
+    // Unknown entries are reported as failures
+    match queue_op(service_port, |f, ch| f.send_cancel(ch, 1000).unwrap()) {
+        Message::Failure(_, _) => {}
+        other => panic!("Unexpected reply: {:?}", other),
+    }
+
+    // Enqueueing files which don't exist is refused
+    match queue_op(service_port, |f, ch| {
+        f.send_enqueue(ch, &path("missing"), 1, None).unwrap()
+    }) {
+        Message::Failure(_, _) => {}
+        other => panic!("Unexpected reply: {:?}", other),
+    }
+
+    for name in ["low", "high"].iter() {
+        let dest = format!("{}/{}-dest", test_dir_str, name);
+        assert_eq!(downlink(service_port, &dest, false), Some(path(name)));
+        assert_eq!(fs::read(path(name)).unwrap(), fs::read(&dest).unwrap());
+    }
+
+    assert_eq!(downlink(service_port, &path("none"), false), None);
+    // The service removes the entry once it hears that the file arrived safely
+    thread::sleep(Duration::from_millis(500));
+    assert!(queue_list(service_port).is_empty());
+}
+
+// A downlink which doesn't complete should stay queued and be resumed at the next request
+#[test]
+fn downlink_resumes() {
+    let test_dir = TempDir::new().expect("Failed to create test dir");
+    let test_dir_str = test_dir.path().to_str().unwrap();
+    let source = format!("{}/source", test_dir_str);
+    let dest = format!("{}/dest", test_dir_str);
+    let service_port = 8015;
+
+    let contents: Vec<u8> = b"downlink resumes "
+        .iter()
+        .cycle()
+        .take(5000)
+        .cloned()
+        .collect();
+    create_test_file(&source, &contents);
+
+    let queue_config = format!("queue_file = \"{}/queue\"", test_dir_str);
+    service_new!(service_port, 1024, queue_config);
+
+    queue_op(service_port, |f, ch| {
+        f.send_enqueue(ch, &source, 1, None).unwrap()
+    });
+
+    assert_eq!(downlink(service_port, &dest, true), Some(source.clone()));
+
+    // Give the service time to give up on the first attempt
+    thread::sleep(Duration::from_secs(13));
+
+    assert_eq!(queue_list(service_port), vec![source.clone()]);
+
+    assert_eq!(downlink(service_port, &dest, false), Some(source.clone()));
+    assert_eq!(fs::read(&dest).unwrap(), contents);
+
+    // The service removes the entry once it hears that the file arrived safely
+    thread::sleep(Duration::from_millis(500));
+    assert!(queue_list(service_port).is_empty());
+}
@@ -0,0 +1,123 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+mod common;
+
+use crate::common::*;
+use file_protocol::{FileProtocol, FileProtocolConfig, State};
+use file_service::recv_loop;
+use kubos_system::Config as ServiceConfig;
+use std::fs;
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+// NOTE: Each test's file contents must be unique. Otherwise the hash is the same, so
+// the same storage directory is used across all of them, creating conflicts
+
+// Upload a multi-chunk file a few chunks at a time, at a limited rate
+#[test]
+fn upload_windowed() {
+    let test_dir = TempDir::new().expect("Failed to create test dir");
+    let test_dir_str = test_dir.path().to_str().unwrap();
+    let source = format!("{}/source", test_dir_str);
+    let dest = format!("{}/dest", test_dir_str);
+    let service_port = 7011;
+
+    let contents = [9; 20000];
+
+    create_test_file(&source, &contents);
+
+    service_new!(service_port, 1024);
+
+    let f_config = FileProtocolConfig::new(Some("client".to_owned()), 1024, 5)
+        .transmit_rate(1_000_000)
+        .window_size(4);
+    let f_protocol = FileProtocol::new(
+        "127.0.0.1",
+        &format!("127.0.0.1:{}", service_port),
+        f_config,
+    );
+
+    let (hash, num_chunks, mode) = f_protocol.initialize_file(&source).unwrap();
+    let channel = f_protocol.generate_channel().unwrap();
+    f_protocol
+        .send_metadata(channel, &hash, num_chunks)
+        .unwrap();
+    f_protocol.send_export(channel, &hash, &dest, mode).unwrap();
+
+    let result = f_protocol.message_engine(
+        |d| f_protocol.recv(Some(d)),
+        Duration::from_secs(2),
+        &State::Transmitting,
+    );
+
+    if let Err(err) = &result {
+        println!("Error: {}", err);
+    }
+
+    assert!(result.is_ok());
+
+    // Verify the final file's contents
+    let dest_contents = fs::read(dest).unwrap();
+    assert_eq!(&contents[..], dest_contents.as_slice());
+}
+
+// Download a file with many missing chunk ranges, which won't fit in a single NAK
+#[test]
+fn download_many_gaps() {
+    let test_dir = TempDir::new().expect("Failed to create test dir");
+    let test_dir_str = test_dir.path().to_str().unwrap();
+    let source = format!("{}/source", test_dir_str);
+    let dest = format!("{}/dest", test_dir_str);
+    let service_port = 8011;
+
+    let contents: Vec<u8> = (0..100_000).map(|val| (val % 251) as u8).collect();
+
+    let hash = create_test_file(&source, &contents);
+
+    // Pre-load every other chunk into the client's storage so that the first
+    // NAK has to list hundreds of gaps
+    let chunk_size = 256;
+    for (index, chunk) in contents.chunks(chunk_size).enumerate() {
+        if index % 2 == 0 {
+            let path = format!("client/storage/{}/{}", hash, index);
+            fs::create_dir_all(format!("client/storage/{}", hash)).unwrap();
+            fs::write(path, chunk).unwrap();
+        }
+    }
+
+    service_new!(service_port, chunk_size);
+
+    let result = download(
+        "127.0.0.1",
+        &format!("127.0.0.1:{}", service_port),
+        &source,
+        &dest,
+        Some("client".to_owned()),
+        chunk_size as u32,
+    );
+
+    if let Err(err) = &result {
+        println!("Error: {}", err);
+    }
+
+    assert!(result.is_ok());
+
+    // Verify the final file's contents
+    let dest_contents = fs::read(dest).unwrap();
+    assert_eq!(&contents[..], dest_contents.as_slice());
+}
@@ -0,0 +1,122 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+mod common;
+
+use crate::common::*;
+use file_protocol::*;
+use file_service::recv_loop;
+use kubos_system::Config as ServiceConfig;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+// NOTE: Each test's file contents must be unique. Otherwise the hash is the same, so
+// the same storage directory is used across all of them, creating conflicts
+
+fn request_status(service_port: u16) -> Vec<TransferStatus> {
+    let f_config = FileProtocolConfig::new(Some("client".to_owned()), 1024, 5);
+    let f_protocol = FileProtocol::new(
+        "127.0.0.1",
+        &format!("127.0.0.1:{}", service_port),
+        f_config,
+    );
+
+    let channel = f_protocol.generate_channel().unwrap();
+    f_protocol.send_status_request(channel).unwrap();
+
+    let reply = f_protocol.recv(Some(Duration::from_secs(1))).unwrap();
+    match parse_message(reply).unwrap() {
+        Message::Status(_, transfers) => transfers,
+        other => panic!("Unexpected reply: {:?}", other),
+    }
+}
+
+// Query the service's transfers while an upload is in progress
+#[test]
+fn status_during_upload() {
+    let test_dir = TempDir::new().expect("Failed to create test dir");
+    let test_dir_str = test_dir.path().to_str().unwrap();
+    let source = format!("{}/source", test_dir_str);
+    let dest = format!("{}/dest", test_dir_str);
+    let service_port = 7013;
+
+    let contents = [11; 40 * 1024];
+
+    let hash = create_test_file(&source, &contents);
+
+    service_new!(service_port, 1024);
+
+    // Nothing should be in progress yet
+    assert_eq!(request_status(service_port), vec![]);
+
+    // Send the file slowly enough that we can check on it along the way
+    let updates = Arc::new(Mutex::new(vec![]));
+    let client_updates = updates.clone();
+    let upload_source = source.clone();
+    let upload_dest = dest.clone();
+    let upload = thread::spawn(move || {
+        let f_config = FileProtocolConfig::new(Some("client".to_owned()), 1024, 5)
+            .transmit_rate(80_000)
+            .progress(move |status| client_updates.lock().unwrap().push(status.clone()));
+        let f_protocol = FileProtocol::new(
+            "127.0.0.1",
+            &format!("127.0.0.1:{}", service_port),
+            f_config,
+        );
+
+        let (hash, num_chunks, mode) = f_protocol.initialize_file(&upload_source)?;
+        let channel = f_protocol.generate_channel()?;
+        f_protocol.send_metadata(channel, &hash, num_chunks)?;
+        f_protocol.send_export(channel, &hash, &upload_dest, mode)?;
+        f_protocol.message_engine(
+            |d| f_protocol.recv(Some(d)),
+            Duration::from_secs(2),
+            &State::Transmitting,
+        )
+    });
+
+    thread::sleep(Duration::from_millis(1500));
+
+    let transfers = request_status(service_port);
+    assert_eq!(transfers.len(), 1);
+    let transfer = &transfers[0];
+    assert_eq!(transfer.direction, Direction::Receive);
+    assert_eq!(transfer.path, dest);
+    assert_eq!(transfer.hash, hash);
+    assert_eq!(transfer.chunks_total, 40);
+    assert!(transfer.chunks_done > 0 && transfer.chunks_done < 40);
+    assert!(transfer.bytes_per_sec > 0);
+    assert_eq!(transfer.state, TransferState::Receiving);
+
+    let result = upload.join().unwrap();
+    if let Err(err) = &result {
+        println!("Error: {}", err);
+    }
+    assert!(result.is_ok());
+
+    // The client saw the transfer through to the end
+    let last = updates.lock().unwrap().last().cloned().unwrap();
+    assert_eq!(last.direction, Direction::Transmit);
+    assert_eq!(last.chunks_done, 40);
+    assert_eq!(last.chunks_total, 40);
+    assert_eq!(last.state, TransferState::Done);
+
+    // Finished transfers are no longer listed
+    thread::sleep(Duration::from_millis(500));
+    assert_eq!(request_status(service_port), vec![]);
+}
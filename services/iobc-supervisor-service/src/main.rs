@@ -115,5 +115,6 @@ fn main() {
         QueryRoot,
         MutationRoot,
     )
+    .version(env!("CARGO_PKG_VERSION"))
     .start();
 }
@@ -32,6 +32,7 @@
 //! 	- `secondary` - Specifies the I2C address of the secondary microcontroller. If no secondary contoller is present, this value should be `"0x00"`.
 //! 	- `antennas` - Specifies the number of antennas present in the system. Expected value: 2 or 4.
 //! 	- `wd_timeout` - Specifies the interval at which the AntS watchdog should be automatically kicked. To disable automatic kicking, this value should be `0`.
+//! 	- `status_file` - Optional. Specifies a file where the last mutation and any unread errors are saved, so that they survive a service restart.
 //!
 //! For example:
 //!
@@ -46,8 +47,52 @@
 //! secondary = "0x32"
 //! antennas = 4
 //! wd_timeout = 10
+//! status_file = "/home/system/kubos/isis-ants-status.json"
 //! ```
 //!
+//! ## Deployment Sequence
+//!
+//! The service can also deploy the antennas automatically, rather than relying on a
+//! mission-specific deployment script. The sequencer is enabled by adding a `deploy` section:
+//!
+//! ```toml
+//! [isis-ants-service.deploy]
+//! state_file = "/home/system/kubos/isis-ants-deploy.json"
+//! auto_start = true
+//! delay = 1800
+//! burn_time = 30
+//! attempts = 3
+//! forced_attempts = 1
+//! ```
+//!
+//! - `state_file` - Optional. Specifies a file where the sequence's progress is saved, so that
+//!   a reboot partway through resumes the sequence rather than restarting it
+//! - `auto_start` - Optional. Whether the sequence should be started when the service starts
+//!   and there's no saved progress. Requires `state_file`. Default: false
+//! - `delay` - Optional. Time to wait, in seconds, before arming the system. Default: 1800
+//! - `burn_time` - Optional. Maximum burn time, in seconds, of each deployment attempt. Default: 30
+//! - `attempts` - Optional. Number of normal deployment attempts for each antenna. Default: 3
+//! - `forced_attempts` - Optional. Number of forced deployment attempts for an antenna once
+//!   its normal attempts have failed. Default: 1
+//!
+//! Once the delay has passed, the system is armed and each antenna is deployed in turn. An
+//! antenna which is already deployed is skipped. After each attempt, the antenna's activation
+//! count and time are recorded. The system is disarmed once every antenna has been deployed
+//! or has run out of attempts.
+//!
+//! An attempt is counted as soon as it's started, so an attempt which is interrupted by a
+//! reboot isn't repeated indefinitely.
+//!
+//! If the state file exists but can't be read, the sequence is not started automatically and a
+//! critical error is recorded, since the antennas may already have been deployed.
+//!
+//! While the sequence is running, the `arm` and `deploy` mutations are rejected. Cancel the
+//! sequence first in order to control the antennas manually.
+//!
+//! # Panics
+//!
+//! Attempts to grab the `deploy` section will `panic!` if it is present but invalid.
+//!
 //! # Starting the Service
 //!
 //! The service should be started automatically by its init script, but may also be started manually:
@@ -91,6 +136,20 @@
 //! }
 //! ```
 //!
+//! ## Error Log
+//!
+//! Get the most recent errors, including ones which have already been read
+//!
+//! ```json
+//! {
+//!     errorLog {
+//!         timestamp: Float,
+//!         severity: Severity,
+//!         message: String
+//!     }
+//! }
+//! ```
+//!
 //! ## Power Status
 //!
 //! Get the current power state and uptime of the system
@@ -208,6 +267,33 @@
 //! }
 //! ```
 //!
+//! ## Deployment Sequence Progress
+//!
+//! Get the progress of the automated deployment sequence.
+//! Returns `null` if the sequencer hasn't been configured.
+//!
+//! ```json
+//! {
+//!     deploymentSequence {
+//!         phase: SequencePhase,
+//!         delay: Int,
+//!         waited: Int,
+//!         currentAntenna: Int,
+//!         antennas {
+//!             antenna: Int,
+//!             deployed: Boolean,
+//!             attempts {
+//!                 forced: Boolean,
+//!                 complete: Boolean,
+//!                 deployed: Boolean,
+//!                 activationCount: Int,
+//!                 activationTime: Int
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+//!
 //! # Mutations
 //!
 //!
@@ -344,6 +430,36 @@
 //! }
 //! ```
 //!
+//! ## Start Deployment Sequence
+//!
+//! Start the automated deployment sequence, discarding the progress of any previous sequence
+//!
+//! - skipDelay: (Default - false) Whether antennas should be deployed without waiting for
+//!   the configured delay
+//!
+//! ```json
+//! mutation {
+//!     startDeploymentSequence(skipDelay: Boolean = false) {
+//!         errors: String,
+//!         success: Boolean
+//!    }
+//! }
+//! ```
+//!
+//! ## Cancel Deployment Sequence
+//!
+//! Cancel the running automated deployment sequence, ending any deployment in progress
+//! and disarming the system
+//!
+//! ```json
+//! mutation {
+//!     cancelDeploymentSequence {
+//!         errors: String,
+//!         success: Boolean
+//!    }
+//! }
+//! ```
+//!
 
 #![deny(missing_docs)]
 #![recursion_limit = "256"]
@@ -354,13 +470,16 @@ extern crate juniper;
 use crate::model::Subsystem;
 pub use crate::objects::*;
 use crate::schema::{MutationRoot, QueryRoot};
+use crate::sequencer::SequenceConfig;
 use isis_ants_api::AntSResult;
-use kubos_service::{Config, Service};
+use kubos_service::{CommandStatus, Config, Service};
+use std::thread;
 use syslog::Facility;
 
 mod model;
 mod objects;
 mod schema;
+mod sequencer;
 #[cfg(test)]
 mod tests;
 
@@ -409,13 +528,21 @@ fn main() -> AntSResult<()> {
         .expect("No 'wd_timeout' value found in 'isis-ants-service' section of config");
     let wd_timeout = wd_timeout.as_integer().unwrap() as u32;
 
-    Service::new(
-        config,
-        Subsystem::new(bus, primary, secondary, antennas, wd_timeout)?,
-        QueryRoot,
-        MutationRoot,
-    )
-    .start();
+    let status = CommandStatus::from_config(&config, AckCommand::None);
+
+    let sequence = SequenceConfig::from_config(&config).unwrap();
+
+    let subsystem = Subsystem::new(
+        bus, primary, secondary, antennas, wd_timeout, status, sequence,
+    )?;
+
+    if let Some(sequencer) = subsystem.sequencer.clone() {
+        thread::spawn(move || sequencer::run(sequencer));
+    }
+
+    Service::new(config, subsystem, QueryRoot, MutationRoot)
+        .version(env!("CARGO_PKG_VERSION"))
+        .start();
 
     Ok(())
 }
@@ -16,20 +16,21 @@
 
 use failure::Error;
 use isis_ants_api::*;
-use kubos_service::{process_errors, push_err, run};
+use kubos_service::{run, CommandStatus, Severity};
 use log::info;
 use std::str;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 
 use crate::objects::*;
+use crate::sequencer::{self, SequenceConfig, Sequencer};
 
 #[derive(Clone)]
 pub struct Subsystem {
     pub ants: Box<IAntS>,
     pub count: u8,
     pub controller: Arc<RwLock<ConfigureController>>,
-    pub errors: Arc<RwLock<Vec<String>>>,
-    pub last_cmd: Arc<RwLock<AckCommand>>,
+    pub status: CommandStatus<AckCommand>,
+    pub sequencer: Option<Arc<Mutex<Sequencer>>>,
 }
 
 impl Subsystem {
@@ -39,8 +40,19 @@ impl Subsystem {
         secondary: u8,
         count: u8,
         timeout: u32,
+        status: CommandStatus<AckCommand>,
+        sequence: Option<SequenceConfig>,
     ) -> AntSResult<Subsystem> {
-        let ants = Box::new(AntS::new(bus, primary, secondary, count, timeout)?);
+        let ants: Box<IAntS> = Box::new(AntS::new(bus, primary, secondary, count, timeout)?);
+
+        let sequencer = sequence.map(|config| {
+            Arc::new(Mutex::new(Sequencer::new(
+                ants.clone(),
+                count,
+                status.clone(),
+                config,
+            )))
+        });
 
         info!("Kubos antenna systems service started");
 
@@ -48,8 +60,8 @@ impl Subsystem {
             ants,
             count,
             controller: Arc::new(RwLock::new(ConfigureController::Primary)),
-            errors: Arc::new(RwLock::new(vec![])),
-            last_cmd: Arc::new(RwLock::new(AckCommand::None)),
+            status,
+            sequencer,
         })
     }
 
@@ -66,7 +78,7 @@ impl Subsystem {
     }
 
     pub fn get_arm_status(&self) -> AntSResult<ArmStatus> {
-        let result = run!(self.ants.get_deploy(), self.errors);
+        let result = run!(self.ants.get_deploy(), self.status);
         let armed = result.unwrap_or_default().sys_armed;
 
         Ok(if armed {
@@ -77,7 +89,7 @@ impl Subsystem {
     }
 
     pub fn get_deploy_status(&self) -> AntSResult<GetDeployResponse> {
-        let result = run!(self.ants.get_deploy(), self.errors);
+        let result = run!(self.ants.get_deploy(), self.status);
 
         let mut status = DeploymentStatus::Error;
 
@@ -144,8 +156,15 @@ impl Subsystem {
         })
     }
 
+    pub fn get_deploy_sequence(&self) -> AntSResult<Option<GetSequenceResponse>> {
+        Ok(self
+            .sequencer
+            .as_ref()
+            .map(|sequencer| sequencer::lock(sequencer).state()))
+    }
+
     pub fn get_power(&self) -> AntSResult<GetPowerResponse> {
-        let result = run!(self.ants.get_uptime(), self.errors);
+        let result = run!(self.ants.get_uptime(), self.status);
         let uptime = result.unwrap_or_default();
 
         let state = match uptime {
@@ -157,31 +176,31 @@ impl Subsystem {
     }
 
     pub fn get_telemetry(&self) -> AntSResult<Telemetry> {
-        let nominal = run!(self.ants.get_system_telemetry(), self.errors).unwrap_or_default();
+        let nominal = run!(self.ants.get_system_telemetry(), self.status).unwrap_or_default();
 
         let debug = TelemetryDebug {
             ant1: AntennaStats {
-                act_count: run!(self.ants.get_activation_count(KANTSAnt::Ant1), self.errors)
+                act_count: run!(self.ants.get_activation_count(KANTSAnt::Ant1), self.status)
                     .unwrap_or_default(),
-                act_time: run!(self.ants.get_activation_time(KANTSAnt::Ant1), self.errors)
+                act_time: run!(self.ants.get_activation_time(KANTSAnt::Ant1), self.status)
                     .unwrap_or_default(),
             },
             ant2: AntennaStats {
-                act_count: run!(self.ants.get_activation_count(KANTSAnt::Ant2), self.errors)
+                act_count: run!(self.ants.get_activation_count(KANTSAnt::Ant2), self.status)
                     .unwrap_or_default(),
-                act_time: run!(self.ants.get_activation_time(KANTSAnt::Ant2), self.errors)
+                act_time: run!(self.ants.get_activation_time(KANTSAnt::Ant2), self.status)
                     .unwrap_or_default(),
             },
             ant3: AntennaStats {
-                act_count: run!(self.ants.get_activation_count(KANTSAnt::Ant3), self.errors)
+                act_count: run!(self.ants.get_activation_count(KANTSAnt::Ant3), self.status)
                     .unwrap_or_default(),
-                act_time: run!(self.ants.get_activation_time(KANTSAnt::Ant3), self.errors)
+                act_time: run!(self.ants.get_activation_time(KANTSAnt::Ant3), self.status)
                     .unwrap_or_default(),
             },
             ant4: AntennaStats {
-                act_count: run!(self.ants.get_activation_count(KANTSAnt::Ant4), self.errors)
+                act_count: run!(self.ants.get_activation_count(KANTSAnt::Ant4), self.status)
                     .unwrap_or_default(),
-                act_time: run!(self.ants.get_activation_time(KANTSAnt::Ant4), self.errors)
+                act_time: run!(self.ants.get_activation_time(KANTSAnt::Ant4), self.status)
                     .unwrap_or_default(),
             },
         };
@@ -199,10 +218,10 @@ impl Subsystem {
     // Mutations
 
     pub fn arm(&self, state: ArmState) -> AntSResult<ArmResponse> {
-        let result = match state {
-            ArmState::Arm => run!(self.ants.arm(), self.errors),
-            ArmState::Disarm => run!(self.ants.disarm(), self.errors),
-        };
+        let result = self.manual(|| match state {
+            ArmState::Arm => run!(self.ants.arm(), self.status),
+            ArmState::Disarm => run!(self.ants.disarm(), self.status),
+        });
 
         Ok(ArmResponse {
             success: result.is_ok(),
@@ -222,7 +241,7 @@ impl Subsystem {
             ConfigureController::Secondary => KANTSController::Secondary,
         };
 
-        let result = run!(self.ants.configure(conv), self.errors);
+        let result = run!(self.ants.configure(conv), self.status);
 
         if result.is_ok() {
             let mut curr_controller = self
@@ -245,7 +264,7 @@ impl Subsystem {
     pub fn control_power(&self, state: PowerState) -> AntSResult<ControlPowerResponse> {
         match state {
             PowerState::Reset => {
-                let result = run!(self.ants.reset(), self.errors);
+                let result = run!(self.ants.reset(), self.status);
 
                 Ok(ControlPowerResponse {
                     power: state,
@@ -257,7 +276,10 @@ impl Subsystem {
                 })
             }
             _ => {
-                push_err!(self.errors, "controlPower: Invalid power state".to_owned());
+                self.status.push_error(
+                    Severity::Error,
+                    "controlPower: Invalid power state".to_owned(),
+                );
 
                 Ok(ControlPowerResponse {
                     power: state,
@@ -271,21 +293,21 @@ impl Subsystem {
     pub fn deploy(&self, ant: DeployType, force: bool, time: i32) -> AntSResult<DeployResponse> {
         let conv = if time > 255 { 255 } else { time as u8 };
 
-        let result = match ant {
-            DeployType::All => run!(self.ants.auto_deploy(conv), self.errors),
+        let result = self.manual(|| match ant {
+            DeployType::All => run!(self.ants.auto_deploy(conv), self.status),
             DeployType::Antenna1 => {
-                run!(self.ants.deploy(KANTSAnt::Ant1, force, conv), self.errors)
+                run!(self.ants.deploy(KANTSAnt::Ant1, force, conv), self.status)
             }
             DeployType::Antenna2 => {
-                run!(self.ants.deploy(KANTSAnt::Ant2, force, conv), self.errors)
+                run!(self.ants.deploy(KANTSAnt::Ant2, force, conv), self.status)
             }
             DeployType::Antenna3 => {
-                run!(self.ants.deploy(KANTSAnt::Ant3, force, conv), self.errors)
+                run!(self.ants.deploy(KANTSAnt::Ant3, force, conv), self.status)
             }
             DeployType::Antenna4 => {
-                run!(self.ants.deploy(KANTSAnt::Ant4, force, conv), self.errors)
+                run!(self.ants.deploy(KANTSAnt::Ant4, force, conv), self.status)
             }
-        };
+        });
 
         Ok(DeployResponse {
             success: result.is_ok(),
@@ -296,8 +318,62 @@ impl Subsystem {
         })
     }
 
+    pub fn start_deploy_sequence(&self, skip_delay: bool) -> AntSResult<SequenceResponse> {
+        self.control_sequence(|sequencer| sequencer.start(skip_delay))
+    }
+
+    pub fn cancel_deploy_sequence(&self) -> AntSResult<SequenceResponse> {
+        self.control_sequence(|sequencer| sequencer.cancel())
+    }
+
+    // Run a manual arm or deploy command. These would interfere with a running
+    // deployment sequence, so they're refused until the sequence has been cancelled.
+    // The sequencer stays locked while the command runs, so that a sequence can't
+    // start part way through it
+    fn manual<F>(&self, action: F) -> Result<(), String>
+    where
+        F: FnOnce() -> Result<(), String>,
+    {
+        let _sequencer = match self.sequencer {
+            Some(ref sequencer) => {
+                let sequencer = sequencer::lock(sequencer);
+                if sequencer.is_running() {
+                    let err = "Deployment sequence is running".to_owned();
+                    self.status.push_error(Severity::Error, err.clone());
+                    return Err(err);
+                }
+                Some(sequencer)
+            }
+            None => None,
+        };
+
+        action()
+    }
+
+    fn control_sequence<F>(&self, action: F) -> AntSResult<SequenceResponse>
+    where
+        F: FnOnce(&mut Sequencer) -> Result<(), String>,
+    {
+        let result = match self.sequencer {
+            Some(ref sequencer) => action(&mut sequencer::lock(sequencer)),
+            None => Err("Deployment sequencer not configured".to_owned()),
+        };
+
+        if let Err(ref err) = result {
+            self.status.push_error(Severity::Error, err.clone());
+        }
+
+        Ok(SequenceResponse {
+            success: result.is_ok(),
+            errors: match result {
+                Ok(_) => "".to_owned(),
+                Err(err) => err,
+            },
+        })
+    }
+
     pub fn integration_test(&self) -> AntSResult<IntegrationTestResults> {
-        let nom_result = run!(self.ants.get_system_telemetry(), self.errors);
+        let nom_result = run!(self.ants.get_system_telemetry(), self.status);
 
         let debug_errors: RwLock<Vec<String>> = RwLock::new(vec![]);
 
@@ -349,7 +425,10 @@ impl Subsystem {
             }
             let concat = debug_errors.join(", ");
             errors.push_str(&format!("Debug: {}", concat));
-            push_err!(self.errors, format!("get_test_results(debug): {}", concat));
+            self.status.push_error(
+                Severity::Error,
+                format!("get_test_results(debug): {}", concat),
+            );
         }
 
         Ok(IntegrationTestResults {
@@ -361,7 +440,7 @@ impl Subsystem {
     }
 
     pub fn noop(&self) -> AntSResult<NoopResponse> {
-        let result = run!(self.ants.watchdog_kick(), self.errors);
+        let result = run!(self.ants.watchdog_kick(), self.status);
 
         Ok(NoopResponse {
             success: result.is_ok(),
@@ -385,7 +464,7 @@ impl Subsystem {
 
         let result = run!(
             self.ants.passthrough(tx.as_slice(), rx.as_mut_slice()),
-            self.errors
+            self.status
         );
 
         // Convert the response hex values into a String for the GraphQL output
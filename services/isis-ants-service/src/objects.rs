@@ -16,6 +16,7 @@
 
 use isis_ants_api::{AntsTelemetry, DeployStatus};
 use juniper::FieldResult;
+use serde_derive::{Deserialize, Serialize};
 
 /// Common response fields structure for requests
 /// which don't return any specific data
@@ -30,7 +31,7 @@ pub struct GenericResponse {
 /// Return field for 'ack' query
 ///
 /// Indicates last mutation executed by the service
-#[derive(GraphQLEnum, Clone, Copy)]
+#[derive(GraphQLEnum, Clone, Copy, Deserialize, Serialize)]
 pub enum AckCommand {
     /// No mutations have been executed
     None,
@@ -48,6 +49,8 @@ pub enum AckCommand {
     Arm,
     /// Antenna/s were deployed
     Deploy,
+    /// The automated deployment sequence was started or cancelled
+    DeploymentSequence,
 }
 
 /// Return field for 'armStatus' query
@@ -447,3 +450,132 @@ graphql_object!(TelemetryDebug: () where Scalar = <S> |&self| {
         i32::from(self.ant4.act_time)
     }
 });
+
+/// Current phase of the automated deployment sequence
+#[derive(GraphQLEnum, Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum SequencePhase {
+    /// The sequence hasn't been started, or was cancelled
+    Idle,
+    /// Waiting for the initial delay to pass before deploying
+    Waiting,
+    /// Deploying the antennas
+    Deploying,
+    /// All antennas have been deployed
+    Complete,
+    /// At least one antenna couldn't be deployed
+    Failed,
+}
+
+impl Default for SequencePhase {
+    fn default() -> Self {
+        SequencePhase::Idle
+    }
+}
+
+/// A single deployment attempt made by the automated deployment sequence
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct DeployAttempt {
+    /// Whether the antenna's current deployment state was overridden
+    pub forced: bool,
+    /// Whether the attempt has finished
+    pub complete: bool,
+    /// Whether the antenna was deployed at the end of the attempt
+    pub deployed: bool,
+    /// Antenna activation count read once the attempt finished
+    pub activation_count: u8,
+    /// Antenna activation time, in 50ms steps, read once the attempt finished
+    pub activation_time: u16,
+}
+
+graphql_object!(DeployAttempt: () where Scalar = <S> |&self| {
+    field forced() -> bool {
+        self.forced
+    }
+
+    field complete() -> bool {
+        self.complete
+    }
+
+    field deployed() -> bool {
+        self.deployed
+    }
+
+    field activation_count() -> i32 {
+        i32::from(self.activation_count)
+    }
+
+    field activation_time() -> i32 {
+        i32::from(self.activation_time)
+    }
+});
+
+/// Deployment progress of a single antenna
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct AntennaProgress {
+    /// Antenna number (1-4)
+    pub antenna: u8,
+    /// Whether the antenna has been deployed
+    pub deployed: bool,
+    /// Deployment attempts made so far
+    pub attempts: Vec<DeployAttempt>,
+}
+
+graphql_object!(AntennaProgress: () where Scalar = <S> |&self| {
+    field antenna() -> i32 {
+        i32::from(self.antenna)
+    }
+
+    field deployed() -> bool {
+        self.deployed
+    }
+
+    field attempts() -> Vec<DeployAttempt> {
+        self.attempts.clone()
+    }
+});
+
+/// Saved state of the automated deployment sequence
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct SequenceState {
+    /// Current phase of the sequence
+    pub phase: SequencePhase,
+    /// Amount of the initial delay which has passed, in seconds
+    pub waited: u32,
+    /// Progress of each antenna
+    pub antennas: Vec<AntennaProgress>,
+}
+
+/// Response fields for 'deploymentSequence' query
+pub struct GetSequenceResponse {
+    /// Current state of the sequence
+    pub state: SequenceState,
+    /// Configured initial delay, in seconds
+    pub delay: u32,
+    /// Antenna which is currently being deployed
+    pub current: Option<u8>,
+}
+
+graphql_object!(GetSequenceResponse: () where Scalar = <S> |&self| {
+    field phase() -> SequencePhase {
+        self.state.phase
+    }
+
+    field delay() -> i32 {
+        self.delay as i32
+    }
+
+    field waited() -> i32 {
+        self.state.waited as i32
+    }
+
+    field current_antenna() -> Option<i32> {
+        self.current.map(i32::from)
+    }
+
+    field antennas() -> Vec<AntennaProgress> {
+        self.state.antennas.clone()
+    }
+});
+
+/// Response fields for 'startDeploymentSequence' and 'cancelDeploymentSequence' mutations
+pub type SequenceResponse = GenericResponse;
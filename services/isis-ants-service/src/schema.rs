@@ -17,7 +17,7 @@
 use crate::model::*;
 use crate::objects::*;
 use juniper::FieldResult;
-use kubos_service;
+use kubos_service::ErrorRecord;
 
 type Context = kubos_service::Context<Subsystem>;
 
@@ -46,8 +46,7 @@ graphql_object!(QueryRoot: Context as "Query" |&self| {
     // }
     field ack(&executor) -> FieldResult<AckCommand>
     {
-        let last_cmd = executor.context().subsystem().last_cmd.read()?;
-        Ok(*last_cmd)
+        Ok(executor.context().subsystem().status.ack())
     }
 
     // Get all errors encountered since the last time this field was queried
@@ -57,15 +56,21 @@ graphql_object!(QueryRoot: Context as "Query" |&self| {
     // }
     field errors(&executor) -> FieldResult<Vec<String>>
     {
-        match executor.context().subsystem().errors.write() {
-            Ok(mut master_vec) => {
-                let current = master_vec.clone();
-                master_vec.clear();
-                master_vec.shrink_to_fit();
-                Ok(current)
-            },
-            _ => Ok(vec!["Error: Failed to borrow master errors vector".to_owned()])
-        }
+        Ok(executor.context().subsystem().status.take_error_messages())
+    }
+
+    // Get the most recent errors, including ones which have already been read
+    //
+    // {
+    //     errorLog {
+    //         timestamp: Float,
+    //         severity: Severity,
+    //         message: String
+    //     }
+    // }
+    field error_log(&executor) -> FieldResult<Vec<ErrorRecord>>
+    {
+        Ok(executor.context().subsystem().status.error_log())
     }
 
     // Get the current power state and uptime of the system
@@ -189,6 +194,34 @@ graphql_object!(QueryRoot: Context as "Query" |&self| {
     {
         Ok(executor.context().subsystem().get_deploy_status()?)
     }
+
+    // Get the progress of the automated deployment sequence
+    //
+    // Returns null if the sequencer hasn't been configured
+    //
+    // {
+    //     deploymentSequence {
+    //         phase: SequencePhase,
+    //         delay: Int,
+    //         waited: Int,
+    //         currentAntenna: Int,
+    //         antennas {
+    //             antenna: Int,
+    //             deployed: Boolean,
+    //             attempts {
+    //                 forced: Boolean,
+    //                 complete: Boolean,
+    //                 deployed: Boolean,
+    //                 activationCount: Int,
+    //                 activationTime: Int
+    //             }
+    //         }
+    //     }
+    // }
+    field deployment_sequence(&executor) -> FieldResult<Option<GetSequenceResponse>>
+    {
+        Ok(executor.context().subsystem().get_deploy_sequence()?)
+    }
 });
 
 pub struct MutationRoot;
@@ -206,10 +239,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field errors(&executor) -> FieldResult<Vec<String>>
     {
-        match executor.context().subsystem().errors.read() {
-            Ok(master_vec) => Ok(master_vec.clone()),
-            _ => Ok(vec!["Error: Failed to borrow master errors vector".to_owned()])
-        }
+        Ok(executor.context().subsystem().status.unread_error_messages())
     }
 
     // Execute a trivial command against the system
@@ -222,8 +252,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field noop(&executor) -> FieldResult<NoopResponse>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::Noop;
+        executor.context().subsystem().status.set_ack(AckCommand::Noop);
         Ok(executor.context().subsystem().noop()?)
     }
 
@@ -241,8 +270,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field control_power(&executor, state: PowerState) -> FieldResult<ControlPowerResponse>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::ControlPower;
+        executor.context().subsystem().status.set_ack(AckCommand::ControlPower);
         Ok(executor.context().subsystem().control_power(state)?)
     }
 
@@ -259,8 +287,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field configure_hardware(&executor, config: ConfigureController) -> FieldResult<ConfigureHardwareResponse>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::ConfigureHardware;
+        executor.context().subsystem().status.set_ack(AckCommand::ConfigureHardware);
         Ok(executor.context().subsystem().configure_hardware(config)?)
     }
 
@@ -285,8 +312,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field test_hardware(&executor, test: TestType) -> FieldResult<TestResults>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::TestHardware;
+        executor.context().subsystem().status.set_ack(AckCommand::TestHardware);
 
         match test {
             TestType::Integration => Ok(TestResults::Integration(executor.context().subsystem().integration_test().unwrap())),
@@ -309,8 +335,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field issue_raw_command(&executor, command: String, rx_len = 0: i32) -> FieldResult<RawCommandResponse>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::IssueRawCommand;
+        executor.context().subsystem().status.set_ack(AckCommand::IssueRawCommand);
         Ok(executor.context().subsystem().passthrough(command, rx_len)?)
     }
 
@@ -328,8 +353,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field arm(&executor, state: ArmState) -> FieldResult<ArmResponse>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::Arm;
+        executor.context().subsystem().status.set_ack(AckCommand::Arm);
         Ok(executor.context().subsystem().arm(state)?)
     }
 
@@ -348,9 +372,39 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field deploy(&executor, ant = (DeployType::All): DeployType, force = false: bool, time: i32) -> FieldResult<DeployResponse>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::Deploy;
+        executor.context().subsystem().status.set_ack(AckCommand::Deploy);
         Ok(executor.context().subsystem().deploy(ant, force, time)?)
     }
 
+    // Start the automated deployment sequence, discarding the progress of any previous sequence
+    //
+    // skipDelay: (Default - false) Whether antennas should be deployed without waiting for
+    //   the configured delay
+    //
+    // mutation {
+    //     startDeploymentSequence(skipDelay: Boolean = false) {
+    //         errors: String,
+    //         success: Boolean
+    //    }
+    // }
+    field start_deployment_sequence(&executor, skip_delay = false: bool) -> FieldResult<SequenceResponse>
+    {
+        executor.context().subsystem().status.set_ack(AckCommand::DeploymentSequence);
+        Ok(executor.context().subsystem().start_deploy_sequence(skip_delay)?)
+    }
+
+    // Cancel the running automated deployment sequence
+    //
+    // mutation {
+    //     cancelDeploymentSequence {
+    //         errors: String,
+    //         success: Boolean
+    //    }
+    // }
+    field cancel_deployment_sequence(&executor) -> FieldResult<SequenceResponse>
+    {
+        executor.context().subsystem().status.set_ack(AckCommand::DeploymentSequence);
+        Ok(executor.context().subsystem().cancel_deploy_sequence()?)
+    }
+
 });
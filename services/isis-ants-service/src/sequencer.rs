@@ -0,0 +1,545 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Automated antenna deployment sequencing
+//!
+//! The sequencer waits for the configured delay, arms the system and then deploys each
+//! antenna in turn. A failed deployment is retried, falling back to forced deployments once
+//! the normal attempts have been used up. The sequence's progress is saved after every
+//! change, so that a reboot partway through resumes the sequence rather than restarting it.
+
+use crate::objects::*;
+use failure::Error;
+use isis_ants_api::{DeployStatus, IAntS, KANTSAnt};
+use kubos_service::{run, CommandStatus, Config, Severity};
+use log::{info, warn};
+use serde_derive::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Interval between sequencer steps
+const STEP_INTERVAL: Duration = Duration::from_secs(1);
+// Extra time given to the AntS to end a burn by itself before the burn is cancelled
+const BURN_MARGIN: Duration = Duration::from_secs(5);
+// How often the progress of the initial delay is saved, in seconds
+const SAVE_INTERVAL: u32 = 10;
+// Number of consecutive failed steps before the sequence gives up
+const MAX_ERRORS: u8 = 5;
+
+/// Deployment sequencer configuration, read from the `[isis-ants-service.deploy]` section
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SequenceConfig {
+    /// File where the sequence's progress is saved
+    pub state_file: Option<String>,
+    /// Whether the sequence should be started if there's no saved progress.
+    /// Requires a state file, so that a completed sequence isn't started again after a reboot
+    #[serde(default = "default_auto_start")]
+    pub auto_start: bool,
+    /// Time to wait before arming the system, in seconds
+    #[serde(default = "default_delay")]
+    pub delay: u32,
+    /// Maximum burn time of each deployment attempt, in seconds
+    #[serde(default = "default_burn_time")]
+    pub burn_time: u8,
+    /// Number of normal deployment attempts for each antenna
+    #[serde(default = "default_attempts")]
+    pub attempts: u8,
+    /// Number of forced deployment attempts once the normal attempts have failed
+    #[serde(default = "default_forced_attempts")]
+    pub forced_attempts: u8,
+}
+
+impl SequenceConfig {
+    /// Read the sequencer configuration from the service's config, if it's present
+    pub fn from_config(config: &Config) -> Result<Option<Self>, String> {
+        let sequence: SequenceConfig = match config.get("deploy") {
+            Some(raw) => raw
+                .try_into()
+                .map_err(|err| format!("Invalid deploy config: {}", err))?,
+            None => return Ok(None),
+        };
+
+        if sequence.burn_time == 0 {
+            return Err("Invalid deploy config: burn_time must be greater than 0".to_owned());
+        }
+        if sequence.attempts == 0 && sequence.forced_attempts == 0 {
+            return Err("Invalid deploy config: at least one attempt must be allowed".to_owned());
+        }
+        if sequence.auto_start && sequence.state_file.is_none() {
+            return Err("Invalid deploy config: auto_start requires a state_file".to_owned());
+        }
+
+        Ok(Some(sequence))
+    }
+}
+
+fn default_auto_start() -> bool {
+    false
+}
+
+fn default_delay() -> u32 {
+    1800
+}
+
+fn default_burn_time() -> u8 {
+    30
+}
+
+fn default_attempts() -> u8 {
+    3
+}
+
+fn default_forced_attempts() -> u8 {
+    1
+}
+
+/// Deploys the antennas according to the configured sequence
+pub struct Sequencer {
+    ants: Box<IAntS>,
+    count: u8,
+    status: CommandStatus<AckCommand>,
+    config: SequenceConfig,
+    state: SequenceState,
+    waited: Duration,
+    saved_wait: u32,
+    last_step: Option<Instant>,
+    burn: Option<Instant>,
+    errors: u8,
+}
+
+impl Sequencer {
+    /// Create a new sequencer, resuming any sequence saved in the state file
+    ///
+    /// If there's no saved sequence and `auto_start` is set, a new sequence is started.
+    /// A state file which can't be read is reported as a critical error instead, since
+    /// the sequence may already have been run.
+    pub fn new(
+        ants: Box<IAntS>,
+        count: u8,
+        status: CommandStatus<AckCommand>,
+        config: SequenceConfig,
+    ) -> Self {
+        let (saved, restored) = match config.state_file {
+            Some(ref path) => match load(path) {
+                Ok(saved) => (saved, true),
+                Err(err) => {
+                    status.push_error(
+                        Severity::Critical,
+                        format!(
+                            "Failed to restore antenna deployment sequence from {}: {}. \
+                             The sequence will not be started automatically",
+                            path, err
+                        ),
+                    );
+                    (None, false)
+                }
+            },
+            None => (None, true),
+        };
+        let resumed = saved.is_some();
+        let state = saved.unwrap_or_default();
+
+        let mut sequencer = Sequencer {
+            ants,
+            count,
+            status,
+            waited: Duration::from_secs(u64::from(state.waited)),
+            saved_wait: state.waited,
+            config,
+            state,
+            last_step: None,
+            burn: None,
+            errors: 0,
+        };
+
+        if resumed {
+            // An attempt which was running when the service stopped is finished off by the
+            // next step, so that its activation count and time are still recorded
+            if sequencer.state.phase == SequencePhase::Deploying
+                && sequencer
+                    .state
+                    .antennas
+                    .iter()
+                    .any(|progress| progress.attempts.iter().any(|attempt| !attempt.complete))
+            {
+                sequencer.burn = Some(Instant::now());
+            }
+            info!(
+                "Resuming antenna deployment sequence: {:?}",
+                sequencer.state.phase
+            );
+        } else if restored && sequencer.config.auto_start {
+            // A new sequence can't already be running
+            let _ = sequencer.start(false);
+        }
+
+        sequencer
+    }
+
+    /// Current state of the sequence
+    pub fn state(&self) -> GetSequenceResponse {
+        GetSequenceResponse {
+            state: self.state.clone(),
+            delay: self.config.delay,
+            current: match self.state.phase {
+                SequencePhase::Deploying => self
+                    .current()
+                    .map(|index| self.state.antennas[index].antenna),
+                _ => None,
+            },
+        }
+    }
+
+    /// Start a new sequence, discarding the progress of any previous one
+    pub fn start(&mut self, skip_delay: bool) -> Result<(), String> {
+        if self.is_running() {
+            return Err("Deployment sequence is already running".to_owned());
+        }
+
+        self.state = SequenceState {
+            phase: if skip_delay || self.config.delay == 0 {
+                SequencePhase::Deploying
+            } else {
+                SequencePhase::Waiting
+            },
+            waited: 0,
+            antennas: (1..=self.count)
+                .map(|antenna| AntennaProgress {
+                    antenna,
+                    ..Default::default()
+                })
+                .collect(),
+        };
+        self.waited = Duration::from_secs(0);
+        self.last_step = None;
+        self.burn = None;
+        self.errors = 0;
+
+        info!("Antenna deployment sequence started");
+        self.save();
+        Ok(())
+    }
+
+    /// Stop the running sequence, ending any deployment which is in progress
+    pub fn cancel(&mut self) -> Result<(), String> {
+        if !self.is_running() {
+            return Err("Deployment sequence is not running".to_owned());
+        }
+
+        // Errors are returned, rather than recorded, since they're reported by the mutation
+        let mut result = Ok(());
+        if self.state.phase == SequencePhase::Deploying {
+            if self.burn.take().is_some() {
+                result = self.ants.cancel_deploy().map_err(|err| err.to_string());
+            }
+            let disarm = self.ants.disarm().map_err(|err| err.to_string());
+            result = result.and(disarm);
+        }
+
+        for attempt in self
+            .state
+            .antennas
+            .iter_mut()
+            .flat_map(|progress| progress.attempts.iter_mut())
+        {
+            attempt.complete = true;
+        }
+
+        info!("Antenna deployment sequence cancelled");
+        self.state.phase = SequencePhase::Idle;
+        self.save();
+        result
+    }
+
+    /// Advance the sequence
+    pub fn step(&mut self, now: Instant) {
+        let elapsed = self
+            .last_step
+            .map(|last| now.duration_since(last))
+            .unwrap_or_default();
+        self.last_step = Some(now);
+
+        let result = match self.state.phase {
+            SequencePhase::Waiting => {
+                self.wait(elapsed);
+                Ok(())
+            }
+            SequencePhase::Deploying => self.deploy(now),
+            _ => Ok(()),
+        };
+
+        // Errors have already been recorded by `run!`. Give up if they persist, rather than
+        // filling the error log
+        if result.is_ok() {
+            self.errors = 0;
+        } else {
+            self.errors += 1;
+            if self.errors >= MAX_ERRORS {
+                self.status.push_error(
+                    Severity::Critical,
+                    format!(
+                        "Antenna deployment sequence stopped after {} consecutive errors",
+                        MAX_ERRORS
+                    ),
+                );
+                self.stop(SequencePhase::Failed);
+            }
+        }
+    }
+
+    /// Whether the sequence is waiting to start deploying or is deploying the antennas
+    pub fn is_running(&self) -> bool {
+        self.state.phase == SequencePhase::Waiting || self.state.phase == SequencePhase::Deploying
+    }
+
+    fn max_attempts(&self) -> usize {
+        usize::from(self.config.attempts) + usize::from(self.config.forced_attempts)
+    }
+
+    // Index of the first antenna which still needs work
+    fn current(&self) -> Option<usize> {
+        let max = self.max_attempts();
+        self.state.antennas.iter().position(|progress| {
+            !progress.deployed
+                && (progress.attempts.len() < max
+                    || progress.attempts.iter().any(|attempt| !attempt.complete))
+        })
+    }
+
+    fn wait(&mut self, elapsed: Duration) {
+        self.waited += elapsed;
+        let waited = self.waited.as_secs() as u32;
+
+        if waited >= self.config.delay {
+            info!("Deployment delay complete, deploying antennas");
+            self.state.waited = self.config.delay;
+            self.state.phase = SequencePhase::Deploying;
+            self.save();
+        } else {
+            self.state.waited = waited;
+            if waited >= self.saved_wait + SAVE_INTERVAL {
+                self.save();
+            }
+        }
+    }
+
+    fn deploy(&mut self, now: Instant) -> Result<(), String> {
+        let index = match self.current() {
+            Some(index) => index,
+            None => {
+                self.finish();
+                return Ok(());
+            }
+        };
+        let antenna = self.state.antennas[index].antenna;
+
+        if let Some(started) = self.burn {
+            let deploy = run!(self.ants.get_deploy(), self.status)?;
+            let (not_deployed, active) = flags(&deploy, antenna);
+            let limit = Duration::from_secs(u64::from(self.config.burn_time)) + BURN_MARGIN;
+
+            if active {
+                if now.duration_since(started) < limit {
+                    return Ok(());
+                }
+                // The AntS should have ended the burn by itself by now
+                run!(self.ants.cancel_deploy(), self.status)?;
+            }
+
+            let activation_count =
+                run!(self.ants.get_activation_count(to_ant(antenna)), self.status)?;
+            let activation_time =
+                run!(self.ants.get_activation_time(to_ant(antenna)), self.status)?;
+            self.burn = None;
+
+            let max = self.max_attempts();
+            let progress = &mut self.state.antennas[index];
+            if let Some(attempt) = progress.attempts.last_mut() {
+                attempt.complete = true;
+                attempt.deployed = !not_deployed;
+                attempt.activation_count = activation_count;
+                attempt.activation_time = activation_time;
+            }
+            progress.deployed = !not_deployed;
+
+            if progress.deployed {
+                info!(
+                    "Antenna {} deployed after {} attempt(s)",
+                    antenna,
+                    progress.attempts.len()
+                );
+            } else if progress.attempts.len() >= max {
+                self.status.push_error(
+                    Severity::Error,
+                    format!(
+                        "Antenna {} failed to deploy after {} attempts",
+                        antenna, max
+                    ),
+                );
+            } else {
+                warn!(
+                    "Antenna {} deployment attempt {} failed",
+                    antenna,
+                    progress.attempts.len()
+                );
+            }
+            self.save();
+        } else {
+            let deploy = run!(self.ants.get_deploy(), self.status)?;
+            let (not_deployed, _) = flags(&deploy, antenna);
+
+            if !not_deployed {
+                info!("Antenna {} is already deployed", antenna);
+                self.state.antennas[index].deployed = true;
+                self.save();
+                return Ok(());
+            }
+
+            if !deploy.sys_armed {
+                run!(self.ants.arm(), self.status)?;
+            }
+
+            let forced =
+                self.state.antennas[index].attempts.len() >= usize::from(self.config.attempts);
+
+            // The attempt is saved before it's started, so that an attempt which causes a
+            // reboot is still counted, rather than being repeated forever
+            self.state.antennas[index].attempts.push(DeployAttempt {
+                forced,
+                ..Default::default()
+            });
+            self.save();
+            self.burn = Some(now);
+
+            info!(
+                "Deploying antenna {} (attempt {}, forced: {})",
+                antenna,
+                self.state.antennas[index].attempts.len(),
+                forced
+            );
+            run!(
+                self.ants
+                    .deploy(to_ant(antenna), forced, self.config.burn_time),
+                self.status
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) {
+        let failed: Vec<String> = self
+            .state
+            .antennas
+            .iter()
+            .filter(|progress| !progress.deployed)
+            .map(|progress| progress.antenna.to_string())
+            .collect();
+
+        if failed.is_empty() {
+            info!("Antenna deployment sequence complete");
+            self.stop(SequencePhase::Complete);
+        } else {
+            self.status.push_error(
+                Severity::Critical,
+                format!(
+                    "Antenna deployment sequence failed. Antennas not deployed: {}",
+                    failed.join(", ")
+                ),
+            );
+            self.stop(SequencePhase::Failed);
+        }
+    }
+
+    fn stop(&mut self, phase: SequencePhase) {
+        // Leave the system disarmed, whatever the outcome. Failures have been recorded
+        let _ = run!(self.ants.disarm(), self.status);
+        self.burn = None;
+        self.state.phase = phase;
+        self.save();
+    }
+
+    fn save(&mut self) {
+        self.saved_wait = self.state.waited;
+
+        let path = match self.config.state_file {
+            Some(ref path) => Path::new(path),
+            None => return,
+        };
+
+        // Write to a temporary file first, so that a reboot in the middle of
+        // saving doesn't corrupt the previous state
+        let temp = path.with_extension("tmp");
+        let result = serde_json::to_vec(&self.state)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| fs::write(&temp, contents).map_err(|err| err.to_string()))
+            .and_then(|_| fs::rename(&temp, path).map_err(|err| err.to_string()));
+
+        if let Err(err) = result {
+            warn!("Failed to save deployment sequence to {:?}: {}", path, err);
+        }
+    }
+}
+
+/// Lock the sequencer, even if a previous holder panicked
+pub fn lock(sequencer: &Mutex<Sequencer>) -> MutexGuard<Sequencer> {
+    match sequencer.lock() {
+        Ok(sequencer) => sequencer,
+        Err(err) => err.into_inner(),
+    }
+}
+
+/// Step the sequencer forever
+pub fn run(sequencer: Arc<Mutex<Sequencer>>) {
+    loop {
+        lock(&sequencer).step(Instant::now());
+        thread::sleep(STEP_INTERVAL);
+    }
+}
+
+// Read the saved sequence. A missing file means there's no sequence to resume
+fn load(path: &str) -> Result<Option<SequenceState>, String> {
+    if !Path::new(path).exists() {
+        return Ok(None);
+    }
+
+    fs::read(path)
+        .map_err(|err| err.to_string())
+        .and_then(|data| serde_json::from_slice(&data).map_err(|err| err.to_string()))
+        .map(Some)
+}
+
+fn to_ant(antenna: u8) -> KANTSAnt {
+    match antenna {
+        1 => KANTSAnt::Ant1,
+        2 => KANTSAnt::Ant2,
+        3 => KANTSAnt::Ant3,
+        _ => KANTSAnt::Ant4,
+    }
+}
+
+// The 'not deployed' and 'active' flags of an antenna
+fn flags(deploy: &DeployStatus, antenna: u8) -> (bool, bool) {
+    match antenna {
+        1 => (deploy.ant_1_not_deployed, deploy.ant_1_active),
+        2 => (deploy.ant_2_not_deployed, deploy.ant_2_active),
+        3 => (deploy.ant_3_not_deployed, deploy.ant_3_active),
+        _ => (deploy.ant_4_not_deployed, deploy.ant_4_active),
+    }
+}
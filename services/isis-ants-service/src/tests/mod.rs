@@ -18,9 +18,9 @@ use crate::objects::*;
 use crate::schema::*;
 #[allow(unused_parens)]
 use isis_ants_api::*;
-use kubos_service::{Config, Service};
+use kubos_service::{CommandStatus, Config, Service};
 use serde_json::json;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 
 /// Structure for interacting with an ISIS Antenna System
 #[derive(Clone)]
@@ -235,8 +235,41 @@ macro_rules! service_new {
                 ants: Box::new($mock),
                 count: 4,
                 controller: Arc::new(RwLock::new(ConfigureController::Primary)),
-                errors: Arc::new(RwLock::new(vec![])),
-                last_cmd: Arc::new(RwLock::new(AckCommand::None)),
+                status: CommandStatus::new(AckCommand::None),
+                sequencer: None,
+            },
+            QueryRoot,
+            MutationRoot,
+        )
+    }};
+}
+
+// Service with a deployment sequencer which is waiting for its 60 second delay
+macro_rules! sequence_service_new {
+    ($mock:ident) => {{
+        let status = CommandStatus::new(AckCommand::None);
+        let sequencer = crate::sequencer::Sequencer::new(
+            Box::new($mock.clone()),
+            4,
+            status.clone(),
+            crate::sequencer::SequenceConfig {
+                state_file: None,
+                auto_start: true,
+                delay: 60,
+                burn_time: 10,
+                attempts: 3,
+                forced_attempts: 1,
+            },
+        );
+
+        Service::new(
+            Config::new("isis-ants-service"),
+            Subsystem {
+                ants: Box::new($mock),
+                count: 4,
+                controller: Arc::new(RwLock::new(ConfigureController::Primary)),
+                status,
+                sequencer: Some(Arc::new(Mutex::new(sequencer))),
             },
             QueryRoot,
             MutationRoot,
@@ -246,6 +279,7 @@ macro_rules! service_new {
 
 mod mutations;
 mod queries;
+mod sequencer;
 
 #[test]
 fn ping() {
@@ -0,0 +1,181 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+
+#[test]
+fn start_sequence_not_configured() {
+    let mock = mock_new!();
+
+    let service = service_new!(mock);
+
+    let query = r#"mutation {
+            startDeploymentSequence {
+                errors,
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "startDeploymentSequence": {
+                "errors": "Deployment sequencer not configured",
+                "success": false
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn start_sequence_running() {
+    let mock = mock_new!();
+
+    let service = sequence_service_new!(mock);
+
+    let query = r#"mutation {
+            startDeploymentSequence(skipDelay: true) {
+                errors,
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "startDeploymentSequence": {
+                "errors": "Deployment sequence is already running",
+                "success": false
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn cancel_then_start_sequence() {
+    let mock = mock_new!();
+
+    let service = sequence_service_new!(mock);
+
+    let query = r#"mutation {
+            cancelDeploymentSequence {
+                errors,
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "cancelDeploymentSequence": {
+                "errors": "",
+                "success": true
+            }
+    });
+
+    test!(service, query, expected);
+
+    let query = r#"mutation {
+            startDeploymentSequence(skipDelay: true) {
+                errors,
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "startDeploymentSequence": {
+                "errors": "",
+                "success": true
+            }
+    });
+
+    test!(service, query, expected);
+
+    let query = r#"
+        {
+            ack,
+            deploymentSequence {
+                phase
+            }
+        }"#;
+
+    let expected = json!({
+            "ack": "DEPLOYMENT_SEQUENCE",
+            "deploymentSequence": {
+                "phase": "DEPLOYING"
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn manual_commands_while_running() {
+    let mut mock = mock_new!();
+    mock.state = true;
+
+    let service = sequence_service_new!(mock);
+
+    let query = r#"mutation {
+            arm(state: ARM) {
+                errors,
+                success
+            },
+            deploy(ant: ALL, time: 5) {
+                errors,
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "arm": {
+                "errors": "Deployment sequence is running",
+                "success": false
+            },
+            "deploy": {
+                "errors": "Deployment sequence is running",
+                "success": false
+            }
+    });
+
+    test!(service, query, expected);
+
+    let query = r#"mutation {
+            cancelDeploymentSequence {
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "cancelDeploymentSequence": {
+                "success": true
+            }
+    });
+
+    test!(service, query, expected);
+
+    let query = r#"mutation {
+            arm(state: DISARM) {
+                errors,
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "arm": {
+                "errors": "",
+                "success": true
+            }
+    });
+
+    test!(service, query, expected);
+}
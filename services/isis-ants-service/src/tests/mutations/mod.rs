@@ -20,6 +20,7 @@ mod arm;
 mod configure_hardware;
 mod control_power;
 mod deploy;
+mod deployment_sequence;
 mod errors;
 mod issue_raw;
 mod noop;
@@ -0,0 +1,78 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+
+#[test]
+fn deployment_sequence_not_configured() {
+    let mock = mock_new!();
+
+    let service = service_new!(mock);
+
+    let query = r#"
+        {
+            deploymentSequence {
+                phase
+            }
+        }"#;
+
+    let expected = json!({
+            "deploymentSequence": null
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn deployment_sequence_waiting() {
+    let mock = mock_new!();
+
+    let service = sequence_service_new!(mock);
+
+    let query = r#"
+        {
+            deploymentSequence {
+                antennas {
+                    antenna,
+                    attempts {
+                        forced
+                    },
+                    deployed
+                },
+                currentAntenna,
+                delay,
+                phase,
+                waited
+            }
+        }"#;
+
+    let expected = json!({
+            "deploymentSequence": {
+                "antennas": [
+                    {"antenna": 1, "attempts": [], "deployed": false},
+                    {"antenna": 2, "attempts": [], "deployed": false},
+                    {"antenna": 3, "attempts": [], "deployed": false},
+                    {"antenna": 4, "attempts": [], "deployed": false},
+                ],
+                "currentAntenna": null,
+                "delay": 60,
+                "phase": "WAITING",
+                "waited": 0
+            }
+    });
+
+    test!(service, query, expected);
+}
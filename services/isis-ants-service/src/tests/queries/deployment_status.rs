@@ -156,8 +156,8 @@ fn deploy_status_deployed_2_antennas() {
         Subsystem {
             ants: Box::new(mock),
             controller: Arc::new(RwLock::new(ConfigureController::Primary)),
-            errors: Arc::new(RwLock::new(vec![])),
-            last_cmd: Arc::new(RwLock::new(AckCommand::None)),
+            status: CommandStatus::new(AckCommand::None),
+            sequencer: None,
             count: 2,
         },
         QueryRoot,
@@ -227,8 +227,8 @@ fn deploy_status_stowed_2_antennas() {
         Subsystem {
             ants: Box::new(mock),
             controller: Arc::new(RwLock::new(ConfigureController::Primary)),
-            errors: Arc::new(RwLock::new(vec![])),
-            last_cmd: Arc::new(RwLock::new(AckCommand::None)),
+            status: CommandStatus::new(AckCommand::None),
+            sequencer: None,
             count: 2,
         },
         QueryRoot,
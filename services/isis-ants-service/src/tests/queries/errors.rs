@@ -54,7 +54,7 @@ fn query_errors_single() {
         }"#;
 
     let expected = json!({
-            "errors": ["watchdog_kick (services/isis-ants-service/src/model.rs:364): Configuration error"]
+            "errors": ["watchdog_kick (services/isis-ants-service/src/model.rs:443): Configuration error"]
     });
 
     test!(service, query, expected);
@@ -81,7 +81,7 @@ fn query_errors_multiple() {
         }"#;
 
     let expected = json!({
-            "errors": ["watchdog_kick (services/isis-ants-service/src/model.rs:364): Configuration error", "watchdog_kick (services/isis-ants-service/src/model.rs:364): Configuration error"]
+            "errors": ["watchdog_kick (services/isis-ants-service/src/model.rs:443): Configuration error", "watchdog_kick (services/isis-ants-service/src/model.rs:443): Configuration error"]
     });
 
     test!(service, query, expected);
@@ -114,3 +114,44 @@ fn query_errors_clear_after_query() {
 
     test!(service, query, expected);
 }
+
+#[test]
+fn query_error_log() {
+    let mut mock = mock_new!();
+    mock.state = false;
+
+    let service = service_new!(mock);
+
+    let noop = r#"mutation {
+            noop {
+                success
+            }
+        }"#;
+
+    request!(service, noop);
+
+    let query = r#"{
+            errors
+        }"#;
+
+    request!(service, query);
+
+    // The log keeps errors which have already been read
+    let query = r#"{
+            errorLog {
+                message,
+                severity
+            }
+        }"#;
+
+    let expected = json!({
+            "errorLog": [
+                {
+                    "message": "watchdog_kick (services/isis-ants-service/src/model.rs:443): Configuration error",
+                    "severity": "ERROR"
+                }
+            ]
+    });
+
+    test!(service, query, expected);
+}
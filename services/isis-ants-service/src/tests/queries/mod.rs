@@ -19,6 +19,7 @@ use super::*;
 mod ack;
 mod arm_status;
 mod config;
+mod deployment_sequence;
 mod deployment_status;
 mod errors;
 mod power;
@@ -0,0 +1,622 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+use crate::sequencer::{SequenceConfig, Sequencer};
+use std::fs;
+use std::sync::MutexGuard;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+#[derive(Default)]
+struct DeviceState {
+    armed: bool,
+    deployed: [bool; 4],
+    active: [bool; 4],
+    // Attempt on which each antenna deploys. `None` means it never deploys
+    deploy_on: [Option<usize>; 4],
+    // Whether bursts should be left active until the sequencer cancels them
+    stuck: bool,
+    fail: bool,
+    counts: [u8; 4],
+    times: [u16; 4],
+    calls: Vec<String>,
+}
+
+// Stateful AntS, which deploys antennas on a chosen attempt
+#[derive(Clone)]
+struct SequenceMock {
+    state: Arc<Mutex<DeviceState>>,
+}
+
+impl SequenceMock {
+    fn new() -> Self {
+        SequenceMock {
+            state: Arc::new(Mutex::new(DeviceState {
+                deploy_on: [Some(1); 4],
+                ..Default::default()
+            })),
+        }
+    }
+
+    fn device(&self) -> MutexGuard<DeviceState> {
+        self.state.lock().unwrap()
+    }
+
+    fn check(&self) -> AntSResult<()> {
+        if self.device().fail {
+            Err(AntsError::GenericError)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn call(&self, call: String) -> AntSResult<()> {
+        self.check()?;
+        self.device().calls.push(call);
+        Ok(())
+    }
+}
+
+fn index(antenna: KANTSAnt) -> usize {
+    match antenna {
+        KANTSAnt::Ant1 => 0,
+        KANTSAnt::Ant2 => 1,
+        KANTSAnt::Ant3 => 2,
+        KANTSAnt::Ant4 => 3,
+    }
+}
+
+impl IAntS for SequenceMock {
+    fn new(
+        _bus: &str,
+        _primary: u8,
+        _secondary: u8,
+        _ant_count: u8,
+        _timeout: u32,
+    ) -> AntSResult<SequenceMock> {
+        Ok(SequenceMock::new())
+    }
+
+    fn configure(&self, _config: KANTSController) -> AntSResult<()> {
+        self.check()
+    }
+
+    fn reset(&self) -> AntSResult<()> {
+        self.check()
+    }
+
+    fn arm(&self) -> AntSResult<()> {
+        self.call("arm".to_owned())?;
+        self.device().armed = true;
+        Ok(())
+    }
+
+    fn disarm(&self) -> AntSResult<()> {
+        self.call("disarm".to_owned())?;
+        self.device().armed = false;
+        Ok(())
+    }
+
+    fn deploy(&self, antenna: KANTSAnt, force: bool, timeout: u8) -> AntSResult<()> {
+        self.call(format!("deploy {:?} {} {}", antenna, force, timeout))?;
+
+        let ant = index(antenna);
+        let mut device = self.device();
+        device.counts[ant] += 1;
+        device.times[ant] += 20;
+        if device.deploy_on[ant] == Some(usize::from(device.counts[ant])) {
+            device.deployed[ant] = true;
+        }
+        device.active[ant] = device.stuck;
+        Ok(())
+    }
+
+    fn auto_deploy(&self, _timeout: u8) -> AntSResult<()> {
+        self.check()
+    }
+
+    fn cancel_deploy(&self) -> AntSResult<()> {
+        self.call("cancel".to_owned())?;
+        self.device().active = [false; 4];
+        Ok(())
+    }
+
+    fn get_deploy(&self) -> AntSResult<DeployStatus> {
+        self.check()?;
+        let device = self.device();
+        Ok(DeployStatus {
+            sys_armed: device.armed,
+            ant_1_not_deployed: !device.deployed[0],
+            ant_1_active: device.active[0],
+            ant_2_not_deployed: !device.deployed[1],
+            ant_2_active: device.active[1],
+            ant_3_not_deployed: !device.deployed[2],
+            ant_3_active: device.active[2],
+            ant_4_not_deployed: !device.deployed[3],
+            ant_4_active: device.active[3],
+            ..Default::default()
+        })
+    }
+
+    fn get_uptime(&self) -> AntSResult<u32> {
+        self.check()?;
+        Ok(10)
+    }
+
+    fn get_system_telemetry(&self) -> AntSResult<AntsTelemetry> {
+        self.check()?;
+        Ok(AntsTelemetry::default())
+    }
+
+    fn get_activation_count(&self, antenna: KANTSAnt) -> AntSResult<u8> {
+        self.check()?;
+        Ok(self.device().counts[index(antenna)])
+    }
+
+    fn get_activation_time(&self, antenna: KANTSAnt) -> AntSResult<u16> {
+        self.check()?;
+        Ok(self.device().times[index(antenna)])
+    }
+
+    fn watchdog_kick(&self) -> AntSResult<()> {
+        self.check()
+    }
+
+    fn watchdog_start(&self) -> AntSResult<()> {
+        self.check()
+    }
+
+    fn watchdog_stop(&self) -> AntSResult<()> {
+        self.check()
+    }
+
+    fn passthrough(&self, _tx: &[u8], _rx_in: &mut [u8]) -> AntSResult<()> {
+        self.check()
+    }
+}
+
+fn config(delay: u32, state_file: Option<String>) -> SequenceConfig {
+    SequenceConfig {
+        state_file,
+        auto_start: true,
+        delay,
+        burn_time: 10,
+        attempts: 3,
+        forced_attempts: 1,
+    }
+}
+
+fn new_sequencer(mock: &SequenceMock, count: u8, config: SequenceConfig) -> Sequencer {
+    Sequencer::new(
+        Box::new(mock.clone()),
+        count,
+        CommandStatus::new(AckCommand::None),
+        config,
+    )
+}
+
+// Step the sequencer once a second until it stops running
+fn run_to_end(sequencer: &mut Sequencer, start: Instant) -> Instant {
+    let mut now = start;
+    for _ in 0..200 {
+        sequencer.step(now);
+        match sequencer.state().state.phase {
+            SequencePhase::Complete | SequencePhase::Failed | SequencePhase::Idle => return now,
+            _ => now += Duration::from_secs(1),
+        }
+    }
+    panic!("Sequence didn't finish");
+}
+
+fn forced_flags(progress: &AntennaProgress) -> Vec<bool> {
+    progress
+        .attempts
+        .iter()
+        .map(|attempt| attempt.forced)
+        .collect()
+}
+
+#[test]
+fn config_defaults() {
+    let config = Config::new_from_str("isis-ants-service", "[isis-ants-service.deploy]");
+
+    assert_eq!(
+        SequenceConfig::from_config(&config),
+        Ok(Some(SequenceConfig {
+            state_file: None,
+            auto_start: false,
+            delay: 1800,
+            burn_time: 30,
+            attempts: 3,
+            forced_attempts: 1,
+        }))
+    );
+}
+
+#[test]
+fn config_missing() {
+    let config = Config::new_from_str("isis-ants-service", "[isis-ants-service]\nbus = \"x\"");
+
+    assert_eq!(SequenceConfig::from_config(&config), Ok(None));
+}
+
+#[test]
+fn config_no_attempts() {
+    let config = Config::new_from_str(
+        "isis-ants-service",
+        "[isis-ants-service.deploy]\nattempts = 0\nforced_attempts = 0",
+    );
+
+    assert!(SequenceConfig::from_config(&config).is_err());
+}
+
+#[test]
+fn config_auto_start_without_state_file() {
+    let config = Config::new_from_str(
+        "isis-ants-service",
+        "[isis-ants-service.deploy]\nauto_start = true",
+    );
+
+    assert_eq!(
+        SequenceConfig::from_config(&config),
+        Err("Invalid deploy config: auto_start requires a state_file".to_owned())
+    );
+}
+
+#[test]
+fn waits_for_delay() {
+    let mock = SequenceMock::new();
+    let mut sequencer = new_sequencer(&mock, 4, config(60, None));
+    let start = Instant::now();
+
+    sequencer.step(start);
+    sequencer.step(start + Duration::from_secs(30));
+
+    let state = sequencer.state();
+    assert_eq!(state.state.phase, SequencePhase::Waiting);
+    assert_eq!(state.state.waited, 30);
+    assert_eq!(state.delay, 60);
+    assert_eq!(state.current, None);
+    assert!(mock.device().calls.is_empty());
+
+    sequencer.step(start + Duration::from_secs(60));
+
+    let state = sequencer.state();
+    assert_eq!(state.state.phase, SequencePhase::Deploying);
+    assert_eq!(state.state.waited, 60);
+    assert_eq!(state.current, Some(1));
+}
+
+#[test]
+fn deploys_each_antenna() {
+    let mock = SequenceMock::new();
+    let mut sequencer = new_sequencer(&mock, 4, config(0, None));
+
+    run_to_end(&mut sequencer, Instant::now());
+
+    let state = sequencer.state().state;
+    assert_eq!(state.phase, SequencePhase::Complete);
+    for (index, progress) in state.antennas.iter().enumerate() {
+        assert_eq!(progress.antenna as usize, index + 1);
+        assert!(progress.deployed);
+        assert_eq!(
+            progress.attempts,
+            vec![DeployAttempt {
+                forced: false,
+                complete: true,
+                deployed: true,
+                activation_count: 1,
+                activation_time: 20,
+            }]
+        );
+    }
+
+    assert_eq!(
+        mock.device().calls,
+        vec![
+            "arm",
+            "deploy Ant1 false 10",
+            "deploy Ant2 false 10",
+            "deploy Ant3 false 10",
+            "deploy Ant4 false 10",
+            "disarm",
+        ]
+    );
+}
+
+#[test]
+fn retries_then_forces() {
+    let mock = SequenceMock::new();
+    mock.device().deploy_on[0] = Some(4);
+    let mut sequencer = new_sequencer(&mock, 2, config(0, None));
+
+    run_to_end(&mut sequencer, Instant::now());
+
+    let state = sequencer.state().state;
+    assert_eq!(state.phase, SequencePhase::Complete);
+    assert_eq!(
+        forced_flags(&state.antennas[0]),
+        vec![false, false, false, true]
+    );
+    assert_eq!(
+        state.antennas[0]
+            .attempts
+            .iter()
+            .map(|attempt| (attempt.deployed, attempt.activation_count))
+            .collect::<Vec<_>>(),
+        vec![(false, 1), (false, 2), (false, 3), (true, 4)]
+    );
+    assert_eq!(state.antennas[1].attempts.len(), 1);
+}
+
+#[test]
+fn fails_after_all_attempts() {
+    let mock = SequenceMock::new();
+    mock.device().deploy_on[1] = None;
+    let status = CommandStatus::new(AckCommand::None);
+    let mut sequencer = Sequencer::new(Box::new(mock.clone()), 4, status.clone(), config(0, None));
+
+    run_to_end(&mut sequencer, Instant::now());
+
+    let state = sequencer.state().state;
+    assert_eq!(state.phase, SequencePhase::Failed);
+    assert!(!state.antennas[1].deployed);
+    assert_eq!(
+        forced_flags(&state.antennas[1]),
+        vec![false, false, false, true]
+    );
+
+    // The remaining antennas are still deployed
+    assert!(state.antennas[2].deployed);
+    assert!(state.antennas[3].deployed);
+    assert_eq!(mock.device().calls.last().unwrap(), "disarm");
+
+    let errors = status.take_error_messages();
+    assert_eq!(
+        errors,
+        vec![
+            "Antenna 2 failed to deploy after 4 attempts".to_owned(),
+            "Antenna deployment sequence failed. Antennas not deployed: 2".to_owned(),
+        ]
+    );
+}
+
+#[test]
+fn skips_deployed_antennas() {
+    let mock = SequenceMock::new();
+    mock.device().deployed = [true, true, false, true];
+    let mut sequencer = new_sequencer(&mock, 4, config(0, None));
+
+    run_to_end(&mut sequencer, Instant::now());
+
+    let state = sequencer.state().state;
+    assert_eq!(state.phase, SequencePhase::Complete);
+    assert!(state.antennas[0].attempts.is_empty());
+    assert_eq!(state.antennas[2].attempts.len(), 1);
+    assert_eq!(
+        mock.device().calls,
+        vec!["arm", "deploy Ant3 false 10", "disarm"]
+    );
+}
+
+#[test]
+fn waits_for_active_burn() {
+    let mock = SequenceMock::new();
+    mock.device().stuck = true;
+    let mut sequencer = new_sequencer(&mock, 2, config(0, None));
+    let start = Instant::now();
+
+    // Start the first attempt
+    sequencer.step(start);
+    sequencer.step(start + Duration::from_secs(14));
+
+    let state = sequencer.state().state;
+    assert!(!state.antennas[0].attempts[0].complete);
+    assert_eq!(mock.device().calls, vec!["arm", "deploy Ant1 false 10"]);
+
+    // Burn time plus the margin has passed, so the burn is ended
+    sequencer.step(start + Duration::from_secs(15));
+
+    let state = sequencer.state().state;
+    assert!(state.antennas[0].attempts[0].complete);
+    assert!(state.antennas[0].deployed);
+    assert_eq!(mock.device().calls.last().unwrap(), "cancel");
+}
+
+#[test]
+fn resumes_after_restart() {
+    let dir = TempDir::new().unwrap();
+    let path = dir
+        .path()
+        .join("deploy.json")
+        .to_string_lossy()
+        .into_owned();
+
+    let mock = SequenceMock::new();
+    mock.device().stuck = true;
+    mock.device().deploy_on[1] = Some(2);
+    let start = Instant::now();
+    {
+        let mut sequencer = new_sequencer(&mock, 2, config(0, Some(path.clone())));
+
+        // Antenna 1 deploys, and the first attempt on antenna 2 is interrupted
+        sequencer.step(start);
+        sequencer.step(start + Duration::from_secs(20));
+        sequencer.step(start + Duration::from_secs(21));
+    }
+
+    // After a reboot, the burn is no longer active
+    mock.device().active = [false; 4];
+    let mut sequencer = new_sequencer(&mock, 2, config(0, Some(path.clone())));
+
+    let state = sequencer.state().state;
+    assert_eq!(state.phase, SequencePhase::Deploying);
+    assert!(state.antennas[0].deployed);
+    assert_eq!(state.antennas[1].attempts.len(), 1);
+    assert!(!state.antennas[1].attempts[0].complete);
+
+    // The interrupted attempt is finished off and counted, rather than restarted
+    sequencer.step(start);
+    let state = sequencer.state().state;
+    assert_eq!(
+        state.antennas[1].attempts[0],
+        DeployAttempt {
+            forced: false,
+            complete: true,
+            deployed: false,
+            activation_count: 1,
+            activation_time: 20,
+        }
+    );
+
+    mock.device().stuck = false;
+    run_to_end(&mut sequencer, start + Duration::from_secs(1));
+
+    let state = sequencer.state().state;
+    assert_eq!(state.phase, SequencePhase::Complete);
+    assert_eq!(state.antennas[0].attempts.len(), 1);
+    assert_eq!(state.antennas[1].attempts.len(), 2);
+
+    // A finished sequence isn't restarted by the next boot
+    let sequencer = new_sequencer(&mock, 2, config(0, Some(path)));
+    assert_eq!(sequencer.state().state, state);
+}
+
+#[test]
+fn resumes_delay_after_restart() {
+    let dir = TempDir::new().unwrap();
+    let path = dir
+        .path()
+        .join("deploy.json")
+        .to_string_lossy()
+        .into_owned();
+
+    let mock = SequenceMock::new();
+    let start = Instant::now();
+    {
+        let mut sequencer = new_sequencer(&mock, 4, config(60, Some(path.clone())));
+        sequencer.step(start);
+        sequencer.step(start + Duration::from_secs(25));
+    }
+
+    // Progress is saved every 10 seconds
+    let mut sequencer = new_sequencer(&mock, 4, config(60, Some(path)));
+    assert_eq!(sequencer.state().state.waited, 25);
+
+    sequencer.step(start);
+    sequencer.step(start + Duration::from_secs(35));
+    assert_eq!(sequencer.state().state.phase, SequencePhase::Deploying);
+}
+
+#[test]
+fn no_auto_start() {
+    let mock = SequenceMock::new();
+    let mut config = config(0, None);
+    config.auto_start = false;
+    let mut sequencer = new_sequencer(&mock, 4, config);
+
+    sequencer.step(Instant::now());
+
+    assert_eq!(sequencer.state().state.phase, SequencePhase::Idle);
+    assert!(mock.device().calls.is_empty());
+
+    assert_eq!(sequencer.start(true), Ok(()));
+    assert_eq!(sequencer.state().state.phase, SequencePhase::Deploying);
+    assert_eq!(sequencer.state().state.antennas.len(), 4);
+}
+
+#[test]
+fn corrupt_state_file() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("deploy.json");
+    fs::write(&path, "{ \"phase\": ").unwrap();
+
+    let mock = SequenceMock::new();
+    let status = CommandStatus::new(AckCommand::None);
+    let mut sequencer = Sequencer::new(
+        Box::new(mock.clone()),
+        4,
+        status.clone(),
+        config(0, Some(path.to_str().unwrap().to_owned())),
+    );
+    sequencer.step(Instant::now());
+
+    // The sequence may already have been run, so it isn't started again
+    assert_eq!(sequencer.state().state.phase, SequencePhase::Idle);
+    assert!(mock.device().calls.is_empty());
+
+    let errors = status.take_error_messages();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with(&format!(
+        "Failed to restore antenna deployment sequence from {}",
+        path.to_str().unwrap()
+    )));
+}
+
+#[test]
+fn start_while_running() {
+    let mock = SequenceMock::new();
+    let mut sequencer = new_sequencer(&mock, 4, config(60, None));
+
+    assert_eq!(
+        sequencer.start(false),
+        Err("Deployment sequence is already running".to_owned())
+    );
+}
+
+#[test]
+fn cancel_during_burn() {
+    let mock = SequenceMock::new();
+    mock.device().stuck = true;
+    let mut sequencer = new_sequencer(&mock, 4, config(0, None));
+
+    sequencer.step(Instant::now());
+    assert_eq!(sequencer.cancel(), Ok(()));
+
+    let state = sequencer.state().state;
+    assert_eq!(state.phase, SequencePhase::Idle);
+    assert!(state.antennas[0].attempts[0].complete);
+    assert_eq!(
+        mock.device().calls,
+        vec!["arm", "deploy Ant1 false 10", "cancel", "disarm"]
+    );
+
+    assert_eq!(
+        sequencer.cancel(),
+        Err("Deployment sequence is not running".to_owned())
+    );
+}
+
+#[test]
+fn gives_up_after_errors() {
+    let mock = SequenceMock::new();
+    mock.device().fail = true;
+    let status = CommandStatus::new(AckCommand::None);
+    let mut sequencer = Sequencer::new(Box::new(mock.clone()), 4, status.clone(), config(0, None));
+    let start = Instant::now();
+
+    for second in 0..4 {
+        sequencer.step(start + Duration::from_secs(second));
+    }
+    assert_eq!(sequencer.state().state.phase, SequencePhase::Deploying);
+
+    sequencer.step(start + Duration::from_secs(4));
+    assert_eq!(sequencer.state().state.phase, SequencePhase::Failed);
+    assert!(status
+        .take_error_messages()
+        .contains(&"Antenna deployment sequence stopped after 5 consecutive errors".to_owned()));
+}
@@ -0,0 +1,470 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+// Persistent log of the mutations executed by a service, exposed through the
+// reserved `commandHistory` query field
+
+use juniper::{
+    Arguments, DefaultScalarValue, ExecutionResult, Executor, FromInputValue, GraphQLObject,
+    InputValue,
+};
+use kubos_system::Config;
+use log::warn;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Name of the query field reserved for the service's command history
+pub const HISTORY_FIELD: &str = "commandHistory";
+
+// Default size which the history file may reach before it is rotated, in bytes
+const DEFAULT_MAX_SIZE: u64 = 1024 * 1024;
+// Default number of rotated history files to keep
+const DEFAULT_MAX_FILES: usize = 3;
+// Default number of records returned by a single `commandHistory` query
+pub(crate) const DEFAULT_LIMIT: i32 = 50;
+
+/// A mutation executed by the service, as recorded in its command history
+#[derive(Clone, Debug, Deserialize, GraphQLObject, PartialEq, Serialize)]
+pub struct CommandRecord {
+    /// Time the mutation was received, in seconds since the UNIX epoch
+    pub timestamp: f64,
+    /// Name of the mutation field
+    pub mutation: String,
+    /// Arguments the mutation was called with, as a JSON object
+    pub arguments: String,
+    /// Address of the client which sent the mutation, if known
+    pub source: Option<String>,
+    /// Time taken to execute the mutation, in milliseconds
+    pub duration: f64,
+    /// Whether the mutation completed without returning an error
+    pub success: bool,
+    /// The value returned by the mutation (as JSON), or its error message
+    pub result: String,
+}
+
+// Criteria used to select records from the command history
+#[derive(Clone, Debug, Default)]
+pub(crate) struct HistoryFilter {
+    pub mutation: Option<String>,
+    pub since: Option<f64>,
+    pub until: Option<f64>,
+    pub success: Option<bool>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl HistoryFilter {
+    fn matches(&self, record: &CommandRecord) -> bool {
+        self.mutation
+            .as_ref()
+            .map_or(true, |mutation| *mutation == record.mutation)
+            && self.since.map_or(true, |since| record.timestamp >= since)
+            && self.until.map_or(true, |until| record.timestamp <= until)
+            && self
+                .success
+                .map_or(true, |success| success == record.success)
+    }
+}
+
+// On-disk command history. Records are stored as JSON lines. Once the file grows past
+// `max_size` it is renamed to `<path>.1` (and any older files shifted along), keeping at most
+// `max_files` old files
+pub(crate) struct History {
+    path: PathBuf,
+    max_size: u64,
+    max_files: usize,
+    lock: Mutex<()>,
+}
+
+impl History {
+    pub fn new<P: Into<PathBuf>>(path: P, max_size: u64, max_files: usize) -> Self {
+        History {
+            path: path.into(),
+            max_size,
+            max_files,
+            lock: Mutex::new(()),
+        }
+    }
+
+    // Create the history described by the service's `history_file`, `history_max_size`
+    // and `history_files` config values. Returns `None` if no history file is configured
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let path = config
+            .get("history_file")
+            .and_then(|path| path.as_str().map(|path| path.to_owned()))?;
+        let max_size = config
+            .get("history_max_size")
+            .and_then(|size| size.as_integer())
+            .map(|size| size as u64)
+            .unwrap_or(DEFAULT_MAX_SIZE);
+        let max_files = config
+            .get("history_files")
+            .and_then(|count| count.as_integer())
+            .map(|count| count as usize)
+            .unwrap_or(DEFAULT_MAX_FILES);
+
+        Some(History::new(path, max_size, max_files))
+    }
+
+    // Add a record to the end of the history. Failures are logged, rather than returned,
+    // so that they don't affect the mutation being recorded
+    pub fn record(&self, record: &CommandRecord) {
+        let _guard = match self.lock.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        };
+
+        let mut line = match serde_json::to_string(record) {
+            Ok(line) => line,
+            Err(err) => {
+                warn!("Unable to record command history: {}", err);
+                return;
+            }
+        };
+        line.push('\n');
+
+        let size = fs::metadata(&self.path).map(|meta| meta.len()).unwrap_or(0);
+        if size > 0 && size + line.len() as u64 > self.max_size {
+            self.rotate();
+        }
+
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()));
+        if let Err(err) = result {
+            warn!(
+                "Unable to write command history to {}: {}",
+                self.path.display(),
+                err
+            );
+        }
+    }
+
+    // Get the records matching the filter, newest first
+    pub fn query(&self, filter: &HistoryFilter) -> Vec<CommandRecord> {
+        let _guard = match self.lock.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        };
+
+        let mut records = vec![];
+        for index in 0..=self.max_files {
+            let mut file_records = read_records(&self.rotated_path(index));
+            file_records.retain(|record| filter.matches(record));
+            file_records.reverse();
+            records.append(&mut file_records);
+
+            if records.len() >= filter.offset + filter.limit {
+                break;
+            }
+        }
+
+        records
+            .into_iter()
+            .skip(filter.offset)
+            .take(filter.limit)
+            .collect()
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        if index == 0 {
+            return self.path.clone();
+        }
+
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        path.into()
+    }
+
+    fn rotate(&self) {
+        if self.max_files == 0 {
+            if let Err(err) = fs::remove_file(&self.path) {
+                warn!("Unable to rotate command history: {}", err);
+            }
+            return;
+        }
+
+        for index in (0..self.max_files).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                if let Err(err) = fs::rename(&from, self.rotated_path(index + 1)) {
+                    warn!("Unable to rotate command history: {}", err);
+                }
+            }
+        }
+    }
+}
+
+// Read all of the records from a history file, oldest first.
+// Lines which can't be parsed (e.g. a partial write) are skipped
+fn read_records(path: &Path) -> Vec<CommandRecord> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(_) => return vec![],
+    };
+
+    let mut records = vec![];
+    for line in BufReader::new(file).lines() {
+        match line {
+            Ok(line) => {
+                if let Ok(record) = serde_json::from_str(&line) {
+                    records.push(record);
+                }
+            }
+            Err(_) => break,
+        }
+    }
+
+    records
+}
+
+// Juniper doesn't provide a way to iterate over a field's arguments, or to fetch them
+// without converting them to a concrete type, so this wrapper accepts any value as-is
+struct RawInput(InputValue);
+
+impl FromInputValue for RawInput {
+    fn from_input_value(value: &InputValue) -> Option<Self> {
+        Some(RawInput(value.clone()))
+    }
+}
+
+// Convert the arguments of a field into a JSON object. The argument names come from the
+// schema, since `Arguments` can't be iterated over. Arguments which weren't given are left out
+pub(crate) fn arguments_json<CtxT>(
+    executor: &Executor<CtxT>,
+    type_name: &str,
+    field_name: &str,
+    arguments: &Arguments,
+) -> String {
+    let names = executor
+        .schema()
+        .concrete_type_by_name(type_name)
+        .and_then(|meta| meta.field_by_name(field_name))
+        .and_then(|field| field.arguments.as_ref())
+        .map(|args| args.iter().map(|arg| arg.name.clone()).collect())
+        .unwrap_or_else(Vec::new);
+
+    let mut json = Map::new();
+    for name in names {
+        if let Some(RawInput(value)) = arguments.get::<RawInput>(&name) {
+            if !value.is_null() {
+                json.insert(name, serde_json::to_value(&value).unwrap_or(Value::Null));
+            }
+        }
+    }
+
+    Value::Object(json).to_string()
+}
+
+// Convert the result of a mutation into the success flag and result string of a record
+pub(crate) fn result_json(result: &ExecutionResult<DefaultScalarValue>) -> (bool, String) {
+    match *result {
+        Ok(ref value) => (
+            true,
+            serde_json::to_string(value).unwrap_or_else(|err| err.to_string()),
+        ),
+        Err(ref err) => (false, err.message().to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::udp::Encoding;
+    use crate::{Context, Service};
+    use juniper::{graphql_object, FieldResult};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn record(timestamp: f64, mutation: &str, success: bool) -> CommandRecord {
+        CommandRecord {
+            timestamp,
+            mutation: mutation.to_owned(),
+            arguments: "{}".to_owned(),
+            source: None,
+            duration: 1.0,
+            success,
+            result: "true".to_owned(),
+        }
+    }
+
+    #[test]
+    fn query_filter() {
+        let dir = TempDir::new().unwrap();
+        let history = History::new(dir.path().join("history"), DEFAULT_MAX_SIZE, 1);
+
+        history.record(&record(1.0, "noop", true));
+        history.record(&record(2.0, "reset", false));
+        history.record(&record(3.0, "noop", false));
+        history.record(&record(4.0, "noop", true));
+
+        let all = HistoryFilter {
+            limit: 10,
+            ..Default::default()
+        };
+        let timestamps: Vec<f64> = history
+            .query(&all)
+            .iter()
+            .map(|record| record.timestamp)
+            .collect();
+        assert_eq!(timestamps, vec![4.0, 3.0, 2.0, 1.0]);
+
+        let filter = HistoryFilter {
+            mutation: Some("noop".to_owned()),
+            since: Some(2.0),
+            success: Some(true),
+            limit: 10,
+            ..Default::default()
+        };
+        assert_eq!(history.query(&filter), vec![record(4.0, "noop", true)]);
+
+        let page = HistoryFilter {
+            offset: 1,
+            limit: 2,
+            ..Default::default()
+        };
+        let timestamps: Vec<f64> = history
+            .query(&page)
+            .iter()
+            .map(|record| record.timestamp)
+            .collect();
+        assert_eq!(timestamps, vec![3.0, 2.0]);
+    }
+
+    #[test]
+    fn rotate_files() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history");
+        let line_len = serde_json::to_string(&record(1.0, "noop", true))
+            .unwrap()
+            .len() as u64
+            + 1;
+
+        // Two records fit in each file, and two old files are kept
+        let history = History::new(&path, line_len * 2, 2);
+        for num in 0..7 {
+            history.record(&record(f64::from(num), "noop", true));
+        }
+
+        assert_eq!(read_records(&path).len(), 1);
+        assert!(dir.path().join("history.2").exists());
+        assert!(!dir.path().join("history.3").exists());
+
+        let all = HistoryFilter {
+            limit: 10,
+            ..Default::default()
+        };
+        let timestamps: Vec<f64> = history
+            .query(&all)
+            .iter()
+            .map(|record| record.timestamp)
+            .collect();
+        assert_eq!(timestamps, vec![6.0, 5.0, 4.0, 3.0, 2.0]);
+    }
+
+    struct Query;
+
+    graphql_object!(Query: Context<()> as "Query" |&self| {
+        field ping() -> String {
+            "pong".to_owned()
+        }
+    });
+
+    struct Mutation;
+
+    graphql_object!(Mutation: Context<()> as "Mutation" |&self| {
+        field set_mode(mode: String, delay: Option<i32>) -> FieldResult<String> {
+            if mode == "safe" {
+                Ok(mode)
+            } else {
+                Err(format!("Unknown mode: {}", mode).into())
+            }
+        }
+    });
+
+    fn execute(service: &Service, query: &str) -> serde_json::Value {
+        let request = serde_json::from_value(json!({ "query": query })).unwrap();
+        let response = (service.udp)(&request, Encoding::Json, "10.0.0.5:4000".parse().unwrap());
+        serde_json::from_slice(&response).unwrap()
+    }
+
+    #[test]
+    fn service_history() {
+        let dir = TempDir::new().unwrap();
+        let config = Config::new_from_str(
+            "history-service",
+            &format!(
+                "[history-service]\nhistory_file = \"{}\"",
+                dir.path().join("history").display()
+            ),
+        );
+        let service = Service::new(config, (), Query, Mutation);
+
+        execute(&service, r#"mutation { setMode(mode: "safe", delay: 5) }"#);
+        execute(&service, r#"mutation { setMode(mode: "fast") }"#);
+        execute(&service, "{ ping }");
+
+        let response = execute(
+            &service,
+            "{ commandHistory { arguments mutation result source success } }",
+        );
+        assert_eq!(
+            response,
+            json!({ "data": { "commandHistory": [
+                {
+                    "arguments": r#"{"mode":"fast"}"#,
+                    "mutation": "setMode",
+                    "result": "Unknown mode: fast",
+                    "source": "10.0.0.5:4000",
+                    "success": false,
+                },
+                {
+                    "arguments": r#"{"delay":5,"mode":"safe"}"#,
+                    "mutation": "setMode",
+                    "result": r#""safe""#,
+                    "source": "10.0.0.5:4000",
+                    "success": true,
+                },
+            ] } })
+        );
+
+        let response = execute(
+            &service,
+            "{ commandHistory(success: true, limit: 5) { mutation } }",
+        );
+        assert_eq!(
+            response,
+            json!({ "data": { "commandHistory": [{ "mutation": "setMode" }] } })
+        );
+    }
+
+    #[test]
+    fn service_history_disabled() {
+        let service = Service::new(Default::default(), (), Query, Mutation);
+
+        let response = execute(&service, "{ commandHistory { mutation } }");
+        assert_eq!(
+            response["errors"][0]["message"],
+            "Command history is not enabled for this service"
+        );
+    }
+}
@@ -59,6 +59,78 @@
 //! Note - the `service-name` used in the sections must match the name used when creating
 //! the `Config` instance inside your service.
 //!
+//! ## Built-in Queries
+//!
+//! Every service automatically provides a `kubos` query, which reports the service's
+//! uptime, request and error counts, per-field latencies, and most recent errors.
+//! The service's version can be included by calling `Service::version` before starting it.
+//! If the service's config section has a `metrics_interval` value (in seconds), these values
+//! are also sent to the telemetry service at that interval.
+//!
+//! ## Scheduled Commands
+//!
+//! If the service's config section has a `schedule_file` value, requests can be scheduled
+//! to run at a later time (ex. while the spacecraft is out of contact) with the built-in
+//! `scheduleCommand` mutation. The request is run against the same schema and context as
+//! any other request, and its response is kept for later retrieval with the
+//! `scheduledCommands` query. Pending commands can be cancelled with the
+//! `cancelScheduledCommand` mutation.
+//!
+//! ```graphql,ignore
+//! mutation {
+//!     scheduleCommand(time: Float!, request: String!, variables: String): ScheduledCommand
+//!     cancelScheduledCommand(id: Int!): ScheduledCommand
+//! }
+//!
+//! {
+//!     scheduledCommands(id: Int, status: ScheduleStatus): [ScheduledCommand]
+//! }
+//! ```
+//!
+//! The queue is saved to the schedule file after every change, so scheduled commands survive
+//! restarts. Commands which became due while the service was stopped are run as soon as it
+//! starts. A command which was running when the service stopped is marked as failed, rather
+//! than being run again. The 100 most recently finished commands are kept.
+//!
+//! ```toml,ignore
+//! [service-name]
+//! schedule_file = "/home/system/var/service-name/schedule.json"
+//! ```
+//!
+//! ## UDP Requests
+//!
+//! Requests are normally sent to a service over HTTP. To avoid the HTTP overhead (ex. when
+//! talking to a service over a radio link), a service can also accept requests as single UDP
+//! datagrams by adding a `udp_port` value to its config section. The datagram may contain:
+//!
+//! - A plain GraphQL query (ex. `{ ping }`), or a JSON request object
+//!   (ex. `{"query": "...", "variables": {...}}`). The response is sent back as JSON.
+//! - A CBOR map with the same fields as a JSON request object. The response is sent back as CBOR.
+//!
+//! Responses larger than the `udp_max_payload` config value (default: 1024 bytes) are split into
+//! fragments. Each fragment starts with a six byte header:
+//!
+//! - `0xFF` ([`FRAGMENT_MARKER`](constant.FRAGMENT_MARKER.html))
+//! - Response ID, which changes with each response
+//! - Fragment index, starting at 0 (big-endian `u16`)
+//! - Total number of fragments (big-endian `u16`)
+//!
+//! A complete JSON or CBOR response never starts with `0xFF`, so clients can tell the two apart.
+//! A [`Reassembler`](struct.Reassembler.html) can be used to rebuild fragmented responses.
+//!
+//! ```toml,ignore
+//! [service-name]
+//! udp_port = 8090
+//! udp_max_payload = 512
+//! ```
+//!
+//! ## Subscriptions
+//!
+//! Services can push events to clients, rather than waiting to be polled, by creating a
+//! [`Publisher`](struct.Publisher.html) and passing it to `Service::publisher`.
+//! Clients subscribe over a WebSocket connection to the `/subscriptions` path, or by sending a
+//! subscription request over UDP.
+//!
 //! ### Examples
 //!
 //! # Creating and starting a simple service.
@@ -93,6 +165,27 @@
 //! ).start();
 //! ```
 //!
+//! # Validating the service config and reading it into a typed structure.
+//!
+//! `Config::new` falls back to the default config if the file is missing or invalid.
+//! Services which would rather refuse to start can use `Config::try_new` instead,
+//! and `Config::section` converts the service's section into a `serde` structure,
+//! logging a warning for any keys which the structure doesn't use.
+//!
+//! ```rust,ignore
+//! use kubos_service::{Config, Service};
+//! use serde_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Deserialize, Serialize)]
+//! struct ExampleConfig {
+//!     bus: String,
+//! }
+//!
+//! let config = Config::try_new("example-service")?;
+//! let settings: ExampleConfig = config.section()?;
+//! let subsystem = Subsystem::new(&settings.bus);
+//! ```
+//!
 //! # Running a service with the default config file (`/home/system/etc/config.toml`).
 //!
 //! ```bash
@@ -105,8 +198,22 @@
 //! $ ./example-service -c config.toml
 //! ```
 
+mod history;
 mod macros;
+mod metrics;
+mod reload;
+mod schedule;
 mod service;
+mod status;
+mod subscriptions;
+mod udp;
 
+pub use crate::history::CommandRecord;
+pub use crate::metrics::{FieldMetrics, ServiceError, ServiceMetrics};
+pub use crate::reload::{ConfigReload, ReloadResponse};
+pub use crate::schedule::{ScheduleStatus, ScheduledCommand};
 pub use crate::service::{Context, Service};
-pub use kubos_system::Config;
+pub use crate::status::{CommandStatus, ErrorRecord, ErrorStore, Severity, MAX_ERRORS};
+pub use crate::subscriptions::{Publisher, UDP_SUBSCRIPTION_TIMEOUT};
+pub use crate::udp::{Reassembler, FRAGMENT_MARKER};
+pub use kubos_system::{Config, ConfigError};
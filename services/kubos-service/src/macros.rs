@@ -59,7 +59,7 @@
 #[macro_export]
 macro_rules! process_errors {
     ($err:ident) => {
-        process_errors!($err, ", ")
+        $crate::process_errors!($err, ", ")
     };
     ($err:ident, $delim:expr) => {{
         {
@@ -83,7 +83,11 @@ macro_rules! process_errors {
     }};
 }
 
-/// Convenience macro to push an error string onto the master errors vector
+/// Convenience macro to push an error string onto the master errors storage.
+///
+/// The storage can be anything which implements [`ErrorStore`](trait.ErrorStore.html),
+/// such as a [`CommandStatus`](struct.CommandStatus.html) or a `RwLock<Vec<String>>`.
+/// The error is recorded with `Severity::Error`.
 ///
 /// # Examples
 ///
@@ -105,14 +109,10 @@ macro_rules! process_errors {
 #[macro_export]
 macro_rules! push_err {
     ($master:expr, $err:expr) => {{
-        // Send the error to syslog
-        log::error!("{}", $err);
-
-        if let Ok(mut master_vec) = $master.write() {
-            master_vec.push($err);
-        } else {
-            log::error!("Unable to add error to master list");
-        }
+        // The storage is also responsible for sending the error to syslog,
+        // so that it's logged with the matching level
+        use $crate::ErrorStore;
+        $master.store_error($crate::Severity::Error, $err);
     }};
 }
 
@@ -174,7 +174,7 @@ macro_rules! push_err {
 #[macro_export]
 macro_rules! run {
     ($func:expr) => {{
-        $func.map_err(|err| process_errors!(err))
+        $func.map_err(|err| $crate::process_errors!(err))
     }};
     ($func:expr, $master:expr) => {{
         {
@@ -191,7 +191,7 @@ macro_rules! run {
                 // called from
                 let mut name = stringify!($func).split('(').next().unwrap();
                 name = name.split(&[':', '.'][..]).last().unwrap();
-                push_err!(
+                $crate::push_err!(
                     $master,
                     format!(
                         "{} ({}:{}): {}",
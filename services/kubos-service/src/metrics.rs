@@ -0,0 +1,637 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+// Built-in health and metrics information, which every service exposes
+// under the reserved `kubos` query field
+
+use crate::history::{self, CommandRecord, History, HistoryFilter, HISTORY_FIELD};
+use crate::schedule::{self, Scheduler, CANCEL_FIELD, SCHEDULED_FIELD, SCHEDULE_FIELD};
+use crate::service::Context;
+use juniper::meta::MetaType;
+use juniper::{
+    Arguments, DefaultScalarValue, ExecutionResult, Executor, FieldError, GraphQLObject,
+    GraphQLType, Registry,
+};
+use kubos_system::Config;
+use log::{info, warn};
+use serde_json::json;
+use std::collections::{BTreeMap, VecDeque};
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Name of the query field reserved for the built-in service information
+pub const BUILTIN_FIELD: &str = "kubos";
+
+// Number of recent errors to keep
+const ERROR_HISTORY: usize = 10;
+
+/// Request statistics for a single top-level query or mutation field
+#[derive(Clone, Debug, GraphQLObject, PartialEq)]
+pub struct FieldMetrics {
+    /// Field name, prefixed with the operation type (e.g. `Query.ping`)
+    pub name: String,
+    /// Number of times the field has been requested
+    pub calls: f64,
+    /// Number of requests which returned an error
+    pub errors: f64,
+    /// Average time taken to resolve the field, in milliseconds
+    pub average_latency: f64,
+    /// Longest time taken to resolve the field, in milliseconds
+    pub max_latency: f64,
+}
+
+/// An error returned by one of the service's fields
+#[derive(Clone, Debug, GraphQLObject, PartialEq)]
+pub struct ServiceError {
+    /// Time the error occurred, in seconds since the UNIX epoch
+    pub timestamp: f64,
+    /// Field which returned the error (e.g. `Mutation.noop`)
+    pub field: String,
+    /// Error message
+    pub message: String,
+}
+
+/// Built-in information about the service, returned by the `kubos` query
+#[derive(Clone, Debug, GraphQLObject, PartialEq)]
+#[graphql(name = "KubosService")]
+pub struct ServiceMetrics {
+    /// Name of the service
+    pub name: String,
+    /// Version of the service, if it was provided
+    pub version: Option<String>,
+    /// Time since the service was started, in seconds
+    pub uptime: f64,
+    /// Number of GraphQL requests received
+    pub requests: f64,
+    /// Number of field requests which returned an error
+    pub error_count: f64,
+    /// Statistics for each top-level field which has been requested
+    pub fields: Vec<FieldMetrics>,
+    /// The most recent errors, oldest first
+    pub recent_errors: Vec<ServiceError>,
+}
+
+#[derive(Default)]
+struct FieldStats {
+    calls: u64,
+    errors: u64,
+    total_latency: Duration,
+    max_latency: Duration,
+}
+
+struct MetricsData {
+    version: Option<String>,
+    requests: u64,
+    error_count: u64,
+    fields: BTreeMap<String, FieldStats>,
+    recent_errors: VecDeque<ServiceError>,
+}
+
+// Shared collection point for the service's metrics
+pub(crate) struct Metrics {
+    name: String,
+    started: Instant,
+    data: Mutex<MetricsData>,
+}
+
+impl Metrics {
+    pub fn new(name: &str) -> Self {
+        Metrics {
+            name: name.to_owned(),
+            started: Instant::now(),
+            data: Mutex::new(MetricsData {
+                version: None,
+                requests: 0,
+                error_count: 0,
+                fields: BTreeMap::new(),
+                recent_errors: VecDeque::new(),
+            }),
+        }
+    }
+
+    pub fn set_version(&self, version: &str) {
+        if let Ok(mut data) = self.data.lock() {
+            data.version = Some(version.to_owned());
+        }
+    }
+
+    pub fn record_request(&self) {
+        if let Ok(mut data) = self.data.lock() {
+            data.requests += 1;
+        }
+    }
+
+    pub fn record_field(&self, field: String, latency: Duration, error: Option<&str>) {
+        let mut data = match self.data.lock() {
+            Ok(data) => data,
+            Err(_) => return,
+        };
+
+        {
+            let stats = data.fields.entry(field.clone()).or_default();
+            stats.calls += 1;
+            stats.total_latency += latency;
+            if latency > stats.max_latency {
+                stats.max_latency = latency;
+            }
+            if error.is_some() {
+                stats.errors += 1;
+            }
+        }
+
+        if let Some(message) = error {
+            data.error_count += 1;
+            if data.recent_errors.len() >= ERROR_HISTORY {
+                data.recent_errors.pop_front();
+            }
+            data.recent_errors.push_back(ServiceError {
+                timestamp: now(),
+                field,
+                message: message.to_owned(),
+            });
+        }
+    }
+
+    pub fn snapshot(&self) -> ServiceMetrics {
+        let uptime = duration_secs(self.started.elapsed());
+        let data = match self.data.lock() {
+            Ok(data) => data,
+            Err(err) => err.into_inner(),
+        };
+
+        ServiceMetrics {
+            name: self.name.clone(),
+            version: data.version.clone(),
+            uptime,
+            requests: data.requests as f64,
+            error_count: data.error_count as f64,
+            fields: data
+                .fields
+                .iter()
+                .map(|(name, stats)| FieldMetrics {
+                    name: name.clone(),
+                    calls: stats.calls as f64,
+                    errors: stats.errors as f64,
+                    average_latency: duration_secs(stats.total_latency) * 1000.0
+                        / stats.calls.max(1) as f64,
+                    max_latency: duration_secs(stats.max_latency) * 1000.0,
+                })
+                .collect(),
+            recent_errors: data.recent_errors.iter().cloned().collect(),
+        }
+    }
+
+    // Periodically send the service's health information to the telemetry database
+    // using its direct UDP port
+    pub fn push_telemetry(metrics: Arc<Metrics>, config: &Config, interval: Duration) {
+        let telemetry = match config.path() {
+            Some(path) => Config::new_from_path(kubos_system::SERVICE_TELEMETRY, path.to_owned()),
+            None => Config::new(kubos_system::SERVICE_TELEMETRY),
+        };
+
+        let port = match telemetry
+            .get("direct_port")
+            .and_then(|port| port.as_integer())
+        {
+            Some(port) => port as u16,
+            None => {
+                warn!("Unable to push metrics: telemetry service has no direct_port configured");
+                return;
+            }
+        };
+
+        let mut addr = match telemetry.hosturl().parse::<SocketAddr>() {
+            Ok(addr) => addr,
+            Err(err) => {
+                warn!("Unable to push metrics: {}", err);
+                return;
+            }
+        };
+        addr.set_port(port);
+
+        let socket = match UdpSocket::bind("0.0.0.0:0") {
+            Ok(socket) => socket,
+            Err(err) => {
+                warn!("Unable to push metrics: {}", err);
+                return;
+            }
+        };
+
+        info!("Pushing service metrics to {} every {:?}", addr, interval);
+
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+
+            let snapshot = metrics.snapshot();
+            let values = vec![
+                ("uptime", snapshot.uptime),
+                ("requests", snapshot.requests),
+                ("error_count", snapshot.error_count),
+            ];
+
+            for (parameter, value) in values {
+                let message = json!({
+                    "subsystem": snapshot.name,
+                    "parameter": format!("{}.{}", BUILTIN_FIELD, parameter),
+                    "value": value.to_string(),
+                });
+
+                if let Err(err) = socket.send_to(message.to_string().as_bytes(), addr) {
+                    warn!("Failed to push metrics: {}", err);
+                }
+            }
+        });
+    }
+}
+
+// Wrapper around a service's query root, which adds the built-in `kubos`, `commandHistory`
+// and `scheduledCommands` fields and records the metrics for all other fields.
+// Clones share the same root, so that it can be served by multiple transports
+pub(crate) struct QueryRoot<Q> {
+    root: Arc<Q>,
+    metrics: Arc<Metrics>,
+    history: Option<Arc<History>>,
+    scheduler: Option<Arc<Scheduler>>,
+}
+
+impl<Q> QueryRoot<Q> {
+    pub fn new(
+        root: Q,
+        metrics: Arc<Metrics>,
+        history: Option<Arc<History>>,
+        scheduler: Option<Arc<Scheduler>>,
+    ) -> Self {
+        QueryRoot {
+            root: Arc::new(root),
+            metrics,
+            history,
+            scheduler,
+        }
+    }
+
+    fn command_history(&self, arguments: &Arguments) -> Result<Vec<CommandRecord>, FieldError> {
+        let history = match self.history {
+            Some(ref history) => history,
+            None => return Err("Command history is not enabled for this service".into()),
+        };
+
+        let filter = HistoryFilter {
+            mutation: arguments.get("mutation"),
+            since: arguments.get("since"),
+            until: arguments.get("until"),
+            success: arguments.get("success"),
+            offset: arguments.get::<i32>("offset").unwrap_or(0).max(0) as usize,
+            limit: arguments
+                .get::<i32>("limit")
+                .unwrap_or(history::DEFAULT_LIMIT)
+                .max(0) as usize,
+        };
+
+        Ok(history.query(&filter))
+    }
+}
+
+impl<Q> Clone for QueryRoot<Q> {
+    fn clone(&self) -> Self {
+        QueryRoot {
+            root: self.root.clone(),
+            metrics: self.metrics.clone(),
+            history: self.history.clone(),
+            scheduler: self.scheduler.clone(),
+        }
+    }
+}
+
+impl<Q> GraphQLType for QueryRoot<Q>
+where
+    Q: GraphQLType<TypeInfo = ()>,
+{
+    type Context = Q::Context;
+    type TypeInfo = ();
+
+    fn name(info: &()) -> Option<&str> {
+        Q::name(info)
+    }
+
+    fn meta<'r>(info: &(), registry: &mut Registry<'r>) -> MetaType<'r>
+    where
+        DefaultScalarValue: 'r,
+    {
+        let mut meta = Q::meta(info, registry);
+
+        if let MetaType::Object(ref mut object) = meta {
+            for reserved in &[BUILTIN_FIELD, HISTORY_FIELD, SCHEDULED_FIELD] {
+                if object.fields.iter().any(|field| field.name == *reserved) {
+                    panic!(
+                        "The `{}` query field is reserved for built-in service information",
+                        reserved
+                    );
+                }
+            }
+
+            let field = registry
+                .field::<ServiceMetrics>(BUILTIN_FIELD, &())
+                .description("Built-in service health and metrics information");
+            object.fields.push(field);
+
+            let field = registry
+                .field::<Vec<CommandRecord>>(HISTORY_FIELD, &())
+                .description("Mutations executed by the service, newest first")
+                .argument(
+                    registry
+                        .arg::<Option<String>>("mutation", &())
+                        .description("Only return records for this mutation"),
+                )
+                .argument(
+                    registry
+                        .arg::<Option<f64>>("since", &())
+                        .description("Only return records at or after this UNIX timestamp"),
+                )
+                .argument(
+                    registry
+                        .arg::<Option<f64>>("until", &())
+                        .description("Only return records at or before this UNIX timestamp"),
+                )
+                .argument(
+                    registry
+                        .arg::<Option<bool>>("success", &())
+                        .description("Only return successful (or failed) mutations"),
+                )
+                .argument(
+                    registry
+                        .arg_with_default::<i32>("offset", &0, &())
+                        .description("Number of matching records to skip"),
+                )
+                .argument(
+                    registry
+                        .arg_with_default::<i32>("limit", &history::DEFAULT_LIMIT, &())
+                        .description("Maximum number of records to return"),
+                );
+            object.fields.push(field);
+
+            object.fields.extend(schedule::query_fields(registry));
+        }
+
+        meta
+    }
+
+    fn resolve_field(
+        &self,
+        info: &(),
+        field_name: &str,
+        arguments: &Arguments,
+        executor: &Executor<Self::Context>,
+    ) -> ExecutionResult {
+        if field_name == BUILTIN_FIELD {
+            return executor.resolve_with_ctx(&(), &self.metrics.snapshot());
+        }
+        if field_name == HISTORY_FIELD {
+            return executor.resolve_with_ctx(&(), &self.command_history(arguments)?);
+        }
+        if field_name == SCHEDULED_FIELD {
+            return schedule::resolve_query(
+                self.scheduler.as_ref().map(|s| &**s),
+                arguments,
+                executor,
+            );
+        }
+
+        resolve_field(
+            &*self.root,
+            &self.metrics,
+            info,
+            field_name,
+            arguments,
+            executor,
+        )
+    }
+}
+
+// Wrapper around a service's mutation root, which adds the built-in `scheduleCommand` and
+// `cancelScheduledCommand` fields, records the metrics for all other fields, and adds every
+// mutation to the command history, if enabled
+pub(crate) struct MutationRoot<M> {
+    root: Arc<M>,
+    metrics: Arc<Metrics>,
+    history: Option<Arc<History>>,
+    scheduler: Option<Arc<Scheduler>>,
+}
+
+impl<M> MutationRoot<M> {
+    pub fn new(
+        root: M,
+        metrics: Arc<Metrics>,
+        history: Option<Arc<History>>,
+        scheduler: Option<Arc<Scheduler>>,
+    ) -> Self {
+        MutationRoot {
+            root: Arc::new(root),
+            metrics,
+            history,
+            scheduler,
+        }
+    }
+}
+
+impl<M> Clone for MutationRoot<M> {
+    fn clone(&self) -> Self {
+        MutationRoot {
+            root: self.root.clone(),
+            metrics: self.metrics.clone(),
+            history: self.history.clone(),
+            scheduler: self.scheduler.clone(),
+        }
+    }
+}
+
+impl<M, S> GraphQLType for MutationRoot<M>
+where
+    M: GraphQLType<Context = Context<S>, TypeInfo = ()>,
+{
+    type Context = M::Context;
+    type TypeInfo = ();
+
+    fn name(info: &()) -> Option<&str> {
+        M::name(info)
+    }
+
+    fn meta<'r>(info: &(), registry: &mut Registry<'r>) -> MetaType<'r>
+    where
+        DefaultScalarValue: 'r,
+    {
+        let mut meta = M::meta(info, registry);
+
+        if let MetaType::Object(ref mut object) = meta {
+            for reserved in &[SCHEDULE_FIELD, CANCEL_FIELD] {
+                if object.fields.iter().any(|field| field.name == *reserved) {
+                    panic!(
+                        "The `{}` mutation field is reserved for built-in service commands",
+                        reserved
+                    );
+                }
+            }
+
+            object.fields.extend(schedule::mutation_fields(registry));
+        }
+
+        meta
+    }
+
+    fn resolve_field(
+        &self,
+        info: &(),
+        field_name: &str,
+        arguments: &Arguments,
+        executor: &Executor<Self::Context>,
+    ) -> ExecutionResult {
+        let timestamp = now();
+        let start = Instant::now();
+        let result = if field_name == SCHEDULE_FIELD || field_name == CANCEL_FIELD {
+            schedule::resolve_mutation(
+                self.scheduler.as_ref().map(|s| &**s),
+                field_name,
+                arguments,
+                executor,
+            )
+        } else {
+            resolve_field(
+                &*self.root,
+                &self.metrics,
+                info,
+                field_name,
+                arguments,
+                executor,
+            )
+        };
+
+        let history = match self.history {
+            Some(ref history) => history,
+            None => return result,
+        };
+
+        let duration = duration_secs(start.elapsed()) * 1000.0;
+
+        let (success, result_json) = history::result_json(&result);
+        history.record(&CommandRecord {
+            timestamp,
+            mutation: field_name.to_owned(),
+            arguments: history::arguments_json(
+                executor,
+                M::name(info).unwrap_or(""),
+                field_name,
+                arguments,
+            ),
+            source: executor.context().source().map(|source| source.to_string()),
+            duration,
+            success,
+            result: result_json,
+        });
+
+        result
+    }
+}
+
+// Resolve a field of the wrapped root, timing it and recording any error it returns
+fn resolve_field<T>(
+    root: &T,
+    metrics: &Metrics,
+    info: &(),
+    field_name: &str,
+    arguments: &Arguments,
+    executor: &Executor<T::Context>,
+) -> ExecutionResult
+where
+    T: GraphQLType<TypeInfo = ()>,
+{
+    let start = Instant::now();
+    let result = root.resolve_field(info, field_name, arguments, executor);
+
+    let name = format!("{}.{}", T::name(info).unwrap_or(""), field_name);
+    metrics.record_field(
+        name,
+        start.elapsed(),
+        result.as_ref().err().map(|err| err.message()),
+    );
+
+    result
+}
+
+fn duration_secs(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1_000_000_000.0
+}
+
+// Current time, in seconds since the UNIX epoch
+pub(crate) fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(duration_secs)
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_fields() {
+        let metrics = Metrics::new("test-service");
+        metrics.record_request();
+        metrics.record_field("Query.ping".to_owned(), Duration::from_millis(2), None);
+        metrics.record_field("Query.ping".to_owned(), Duration::from_millis(4), None);
+        metrics.record_field(
+            "Mutation.noop".to_owned(),
+            Duration::from_millis(1),
+            Some("failed"),
+        );
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.name, "test-service");
+        assert_eq!(snapshot.requests, 1.0);
+        assert_eq!(snapshot.error_count, 1.0);
+        assert_eq!(
+            snapshot.fields[1],
+            FieldMetrics {
+                name: "Query.ping".to_owned(),
+                calls: 2.0,
+                errors: 0.0,
+                average_latency: 3.0,
+                max_latency: 4.0,
+            }
+        );
+        assert_eq!(snapshot.fields[0].errors, 1.0);
+        assert_eq!(snapshot.recent_errors[0].field, "Mutation.noop");
+        assert_eq!(snapshot.recent_errors[0].message, "failed");
+    }
+
+    #[test]
+    fn error_history_limit() {
+        let metrics = Metrics::new("test-service");
+        for num in 0..(ERROR_HISTORY + 5) {
+            metrics.record_field(
+                "Mutation.noop".to_owned(),
+                Duration::from_millis(1),
+                Some(&num.to_string()),
+            );
+        }
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.error_count, (ERROR_HISTORY + 5) as f64);
+        assert_eq!(snapshot.recent_errors.len(), ERROR_HISTORY);
+        assert_eq!(snapshot.recent_errors[0].message, "5");
+    }
+}
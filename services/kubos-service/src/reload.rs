@@ -0,0 +1,286 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use failure::Error;
+use juniper::GraphQLObject;
+use kubos_system::Config;
+use log::{info, warn};
+use std::fs;
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use toml::Value;
+
+/// Callback trait used to hand a reloaded configuration to a service's subsystem
+///
+/// Implement this for a subsystem and create the service with
+/// [`Service::new_reloadable`](struct.Service.html#method.new_reloadable)
+/// to allow the service's configuration to be changed without restarting it.
+pub trait ConfigReload {
+    /// Keys which are only read at startup, as dotted paths (e.g. `bus` or `device.addr`).
+    /// A key also covers everything underneath it.
+    ///
+    /// The `addr` section always requires a restart, since the service is already listening.
+    fn restart_keys(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Apply a new configuration. Returning an error rejects the configuration, and the
+    /// service keeps using the previous one.
+    fn reload(&self, config: &Config) -> Result<(), Error>;
+}
+
+/// Response fields for the `reloadConfig` mutation
+#[derive(Clone, Debug, GraphQLObject, PartialEq)]
+pub struct ReloadResponse {
+    /// Any errors encountered by the request
+    pub errors: String,
+    /// Request completion success or failure
+    pub success: bool,
+    /// Keys which were added, removed, or changed
+    pub changed: Vec<String>,
+    /// Changed keys which won't take effect until the service is restarted
+    pub restart_required: Vec<String>,
+}
+
+impl ReloadResponse {
+    fn failure(errors: String) -> Self {
+        ReloadResponse {
+            errors,
+            success: false,
+            changed: vec![],
+            restart_required: vec![],
+        }
+    }
+}
+
+type ApplyFn = Box<dyn Fn(&Config) -> Result<(), Error> + Send + Sync>;
+
+// Keeps track of the active configuration and hands new versions of it to the subsystem
+pub(crate) struct Reloader {
+    config: RwLock<Config>,
+    restart_keys: Vec<String>,
+    apply: ApplyFn,
+}
+
+impl Reloader {
+    pub fn new<S>(config: Config, subsystem: S) -> Self
+    where
+        S: ConfigReload + Send + Sync + 'static,
+    {
+        let mut restart_keys = subsystem.restart_keys();
+        restart_keys.push("addr".to_owned());
+
+        Reloader {
+            config: RwLock::new(config),
+            restart_keys,
+            apply: Box::new(move |config| subsystem.reload(config)),
+        }
+    }
+
+    // Re-read the config file and pass the new configuration to the subsystem
+    pub fn reload(&self) -> ReloadResponse {
+        let mut config = match self.config.write() {
+            Ok(config) => config,
+            Err(err) => return ReloadResponse::failure(err.to_string()),
+        };
+
+        let path = match config.path() {
+            Some(path) => path.to_owned(),
+            None => {
+                return ReloadResponse::failure(
+                    "Service config was not loaded from a file".to_owned(),
+                );
+            }
+        };
+
+        let new_config = match Config::try_new_from_path(config.name(), path) {
+            Ok(new_config) => new_config,
+            Err(err) => return ReloadResponse::failure(err.to_string()),
+        };
+
+        let mut changed = vec![];
+        diff_keys("", &config.raw(), &new_config.raw(), &mut changed);
+        changed.sort();
+
+        if changed.is_empty() {
+            return ReloadResponse {
+                errors: "".to_owned(),
+                success: true,
+                changed,
+                restart_required: vec![],
+            };
+        }
+
+        if let Err(err) = (self.apply)(&new_config) {
+            return ReloadResponse::failure(err.to_string());
+        }
+
+        let restart_required = changed
+            .iter()
+            .filter(|key| {
+                self.restart_keys
+                    .iter()
+                    .any(|restart| *key == restart || key.starts_with(&format!("{}.", restart)))
+            })
+            .cloned()
+            .collect();
+
+        *config = new_config;
+
+        ReloadResponse {
+            errors: "".to_owned(),
+            success: true,
+            changed,
+            restart_required,
+        }
+    }
+
+    // Reload the configuration whenever the config file is modified
+    pub fn watch(reloader: Arc<Reloader>, interval: Duration) {
+        let path = match reloader.config.read() {
+            Ok(config) => config.path().map(|path| path.to_owned()),
+            Err(_) => None,
+        };
+
+        let path = match path {
+            Some(path) => path,
+            None => {
+                warn!("Unable to watch service config: not loaded from a file");
+                return;
+            }
+        };
+
+        let modified = |path: &str| -> Option<SystemTime> {
+            fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .ok()
+        };
+
+        thread::spawn(move || {
+            let mut last = modified(&path);
+            loop {
+                thread::sleep(interval);
+
+                let current = modified(&path);
+                if current == last {
+                    continue;
+                }
+                last = current;
+
+                let result = reloader.reload();
+                if !result.success {
+                    warn!("Failed to reload config: {}", result.errors);
+                } else if !result.changed.is_empty() {
+                    info!("Reloaded config. Changed keys: {:?}", result.changed);
+                    if !result.restart_required.is_empty() {
+                        warn!(
+                            "Restart required to apply config keys: {:?}",
+                            result.restart_required
+                        );
+                    }
+                }
+            }
+        });
+    }
+}
+
+// Collect the dotted paths of all values which differ between two configurations
+fn diff_keys(prefix: &str, old: &Value, new: &Value, changed: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Table(old), Value::Table(new)) => {
+            for (key, old_value) in old.iter() {
+                let path = format!("{}{}", prefix, key);
+                match new.get(key) {
+                    Some(new_value) => {
+                        diff_keys(&format!("{}.", path), old_value, new_value, changed)
+                    }
+                    None => changed.push(path),
+                }
+            }
+            for key in new.keys() {
+                if !old.contains_key(key) {
+                    changed.push(format!("{}{}", prefix, key));
+                }
+            }
+        }
+        // The default config isn't a table. Treat it as empty
+        (Value::Table(_), _) | (_, Value::Table(_)) if prefix.is_empty() => {
+            let empty = Value::Table(Default::default());
+            let (old, new) = match old {
+                Value::Table(_) => (old, &empty),
+                _ => (&empty, new),
+            };
+            diff_keys(prefix, old, new, changed)
+        }
+        _ => {
+            if old != new {
+                changed.push(prefix.trim_end_matches('.').to_owned());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(old: &str, new: &str) -> Vec<String> {
+        let mut changed = vec![];
+        diff_keys(
+            "",
+            &Config::new_from_str("test", old).raw(),
+            &Config::new_from_str("test", new).raw(),
+            &mut changed,
+        );
+        changed.sort();
+        changed
+    }
+
+    #[test]
+    fn diff_nested_keys() {
+        let old = r#"
+            [test]
+            bus = "/dev/ttyS1"
+            timeout = 5
+            [test.limits]
+            min = 1
+            max = 2
+            "#;
+        let new = r#"
+            [test]
+            bus = "/dev/ttyS2"
+            retries = 3
+            [test.limits]
+            min = 1
+            max = 3
+            "#;
+
+        assert_eq!(diff(old, new), vec!["bus", "limits.max", "retries", "timeout"]);
+        assert!(diff(old, old).is_empty());
+    }
+
+    #[test]
+    fn diff_missing_section() {
+        let config = r#"
+            [test]
+            bus = "/dev/ttyS1"
+            "#;
+
+        assert_eq!(diff("", config), vec!["bus"]);
+        assert_eq!(diff(config, ""), vec!["bus"]);
+    }
+}
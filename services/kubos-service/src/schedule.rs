@@ -0,0 +1,705 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+// Time-tagged requests, which are stored until their execution time and then run against
+// the service's schema. Exposed through the reserved `scheduleCommand` and
+// `cancelScheduledCommand` mutation fields and the `scheduledCommands` query field
+
+use crate::metrics::now;
+use juniper::http::GraphQLRequest;
+use juniper::meta::Field;
+use juniper::{
+    Arguments, DefaultScalarValue, ExecutionResult, Executor, FieldError, GraphQLEnum,
+    GraphQLObject, InputValue, Registry,
+};
+use kubos_system::Config;
+use log::{info, warn};
+use serde_derive::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+/// Name of the mutation field reserved for scheduling a request
+pub const SCHEDULE_FIELD: &str = "scheduleCommand";
+/// Name of the mutation field reserved for cancelling a scheduled request
+pub const CANCEL_FIELD: &str = "cancelScheduledCommand";
+/// Name of the query field reserved for listing the scheduled requests
+pub const SCHEDULED_FIELD: &str = "scheduledCommands";
+
+// Number of finished commands to keep, so that their results can be retrieved later
+const MAX_FINISHED: usize = 100;
+// Longest time to wait before checking the queue again. Guards against the system clock
+// being changed while waiting
+const MAX_WAIT: f64 = 60.0;
+
+const DISABLED: &str = "Command scheduling is not enabled for this service";
+
+/// Execution state of a scheduled command
+#[derive(Clone, Copy, Debug, Deserialize, Eq, GraphQLEnum, PartialEq, Serialize)]
+pub enum ScheduleStatus {
+    /// Waiting for its execution time
+    Pending,
+    /// Currently being executed
+    Running,
+    /// Executed without any errors
+    Completed,
+    /// Executed, but the response contained errors (or the service stopped while running it)
+    Failed,
+    /// Cancelled before it was executed
+    Cancelled,
+}
+
+/// A GraphQL request which the service will execute at a given time
+#[derive(Clone, Debug, Deserialize, GraphQLObject, PartialEq, Serialize)]
+pub struct ScheduledCommand {
+    /// Unique ID of the command, used to cancel it
+    pub id: i32,
+    /// Time to execute the command, in seconds since the UNIX epoch
+    pub time: f64,
+    /// GraphQL request to execute
+    pub request: String,
+    /// Variables for the request, as a JSON object
+    pub variables: Option<String>,
+    /// Time the command was scheduled, in seconds since the UNIX epoch
+    pub created: f64,
+    /// Current state of the command
+    pub status: ScheduleStatus,
+    /// Time the command was executed (or cancelled), in seconds since the UNIX epoch
+    pub finished: Option<f64>,
+    /// The GraphQL response returned by the command, as JSON
+    pub response: Option<String>,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct ScheduleData {
+    next_id: i32,
+    commands: Vec<ScheduledCommand>,
+}
+
+// Executes a scheduled request, returning whether it succeeded along with the JSON response
+pub(crate) type Runner = Arc<dyn Fn(&GraphQLRequest) -> (bool, String) + Send + Sync>;
+
+// Persistent queue of scheduled commands. The queue is saved to its file after every change
+pub(crate) struct Scheduler {
+    path: PathBuf,
+    data: Mutex<ScheduleData>,
+    wakeup: Condvar,
+}
+
+impl Scheduler {
+    // Create a scheduler which is saved to the given file, restoring any previously
+    // scheduled commands from it
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        let path = path.into();
+        let mut data = ScheduleData::default();
+
+        if path.exists() {
+            match fs::read(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|data| serde_json::from_slice(&data).map_err(|err| err.to_string()))
+            {
+                Ok(saved) => data = saved,
+                Err(err) => warn!(
+                    "Failed to restore scheduled commands from {:?}: {}",
+                    path, err
+                ),
+            }
+        }
+
+        // Commands which were running when the service stopped may have caused it to stop
+        // (ex. a reset), so they aren't run again
+        for command in data
+            .commands
+            .iter_mut()
+            .filter(|command| command.status == ScheduleStatus::Running)
+        {
+            command.status = ScheduleStatus::Failed;
+            command.response =
+                Some(json_error("The service stopped before the command finished").to_string());
+        }
+
+        let scheduler = Scheduler {
+            path,
+            data: Mutex::new(data),
+            wakeup: Condvar::new(),
+        };
+        scheduler.save(&scheduler.lock());
+        scheduler
+    }
+
+    // Create the scheduler described by the service's `schedule_file` config value.
+    // Returns `None` if no file is configured
+    pub fn from_config(config: &Config) -> Option<Self> {
+        config
+            .get("schedule_file")
+            .and_then(|path| path.as_str().map(|path| path.to_owned()))
+            .map(Scheduler::new)
+    }
+
+    pub fn schedule(
+        &self,
+        time: f64,
+        request: String,
+        variables: Option<String>,
+    ) -> Result<ScheduledCommand, String> {
+        if !time.is_finite() {
+            return Err("Invalid execution time".to_owned());
+        }
+        if request.trim().is_empty() {
+            return Err("Scheduled request is empty".to_owned());
+        }
+        if let Some(ref variables) = variables {
+            parse_variables(variables)?;
+        }
+
+        let mut data = self.lock();
+        data.next_id += 1;
+        let command = ScheduledCommand {
+            id: data.next_id,
+            time,
+            request,
+            variables,
+            created: timestamp(),
+            status: ScheduleStatus::Pending,
+            finished: None,
+            response: None,
+        };
+        data.commands.push(command.clone());
+        self.save(&data);
+        self.wakeup.notify_all();
+
+        info!("Scheduled command {} for {}", command.id, command.time);
+        Ok(command)
+    }
+
+    pub fn cancel(&self, id: i32) -> Result<ScheduledCommand, String> {
+        let mut data = self.lock();
+        let command = {
+            let command = data
+                .commands
+                .iter_mut()
+                .find(|command| command.id == id)
+                .ok_or_else(|| format!("No scheduled command with ID {}", id))?;
+            if command.status != ScheduleStatus::Pending {
+                return Err(format!("Command {} is no longer pending", id));
+            }
+
+            command.status = ScheduleStatus::Cancelled;
+            command.finished = Some(timestamp());
+            command.clone()
+        };
+
+        prune(&mut data);
+        self.save(&data);
+        Ok(command)
+    }
+
+    // Get the scheduled commands, in execution order
+    pub fn list(&self, id: Option<i32>, status: Option<ScheduleStatus>) -> Vec<ScheduledCommand> {
+        let mut commands: Vec<ScheduledCommand> = self
+            .lock()
+            .commands
+            .iter()
+            .filter(|command| id.map_or(true, |id| command.id == id))
+            .filter(|command| status.map_or(true, |status| command.status == status))
+            .cloned()
+            .collect();
+        commands.sort_by(|a, b| {
+            a.time
+                .partial_cmp(&b.time)
+                .unwrap_or(::std::cmp::Ordering::Equal)
+                .then(a.id.cmp(&b.id))
+        });
+        commands
+    }
+
+    // Execute all of the pending commands which are due at the given time, in execution order.
+    // Returns the number of commands executed
+    pub fn run_due(&self, runner: &Runner, time: f64) -> usize {
+        let mut count = 0;
+
+        while let Some(command) = self.next_due(time) {
+            // The lock isn't held while running the command, since the request may
+            // use the scheduler itself
+            let (success, response) = match parse_request(&command) {
+                Ok(request) => runner(&request),
+                Err(err) => (false, json_error(&err).to_string()),
+            };
+
+            let mut data = self.lock();
+            if let Some(entry) = data
+                .commands
+                .iter_mut()
+                .find(|entry| entry.id == command.id)
+            {
+                entry.status = if success {
+                    ScheduleStatus::Completed
+                } else {
+                    ScheduleStatus::Failed
+                };
+                entry.finished = Some(timestamp());
+                entry.response = Some(response);
+            }
+            prune(&mut data);
+            self.save(&data);
+            count += 1;
+        }
+
+        count
+    }
+
+    // Run scheduled commands as they become due. This function runs without return
+    pub fn run(scheduler: Arc<Scheduler>, runner: Runner) {
+        thread::spawn(move || loop {
+            scheduler.run_due(&runner, now());
+
+            let data = scheduler.lock();
+            let wait = data
+                .commands
+                .iter()
+                .filter(|command| command.status == ScheduleStatus::Pending)
+                .map(|command| command.time - now())
+                .fold(MAX_WAIT, f64::min)
+                .max(0.0);
+
+            let _ = scheduler
+                .wakeup
+                .wait_timeout(data, Duration::from_millis((wait * 1000.0) as u64));
+        });
+    }
+
+    // Find the earliest pending command which is due, and mark it as running
+    fn next_due(&self, time: f64) -> Option<ScheduledCommand> {
+        let mut data = self.lock();
+        let command = {
+            let command = data
+                .commands
+                .iter_mut()
+                .filter(|command| command.status == ScheduleStatus::Pending && command.time <= time)
+                .min_by(|a, b| {
+                    a.time
+                        .partial_cmp(&b.time)
+                        .unwrap_or(::std::cmp::Ordering::Equal)
+                })?;
+            command.status = ScheduleStatus::Running;
+            command.clone()
+        };
+
+        // Save before running, so that a command which stops the service isn't run again
+        self.save(&data);
+        info!("Running scheduled command {}", command.id);
+        Some(command)
+    }
+
+    fn lock(&self) -> MutexGuard<ScheduleData> {
+        match self.data.lock() {
+            Ok(data) => data,
+            Err(err) => err.into_inner(),
+        }
+    }
+
+    fn save(&self, data: &ScheduleData) {
+        // Write to a temporary file first, so that a reboot in the middle of
+        // saving doesn't corrupt the previous state
+        let temp = self.path.with_extension("tmp");
+        let result = serde_json::to_vec(data)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| fs::write(&temp, contents).map_err(|err| err.to_string()))
+            .and_then(|_| fs::rename(&temp, &self.path).map_err(|err| err.to_string()));
+
+        if let Err(err) = result {
+            warn!(
+                "Failed to save scheduled commands to {:?}: {}",
+                self.path, err
+            );
+        }
+    }
+}
+
+// Current time, rounded to the millisecond. Timestamps with full precision don't always
+// come back unchanged after the queue has been saved as JSON and restored
+fn timestamp() -> f64 {
+    (now() * 1000.0).round() / 1000.0
+}
+
+// Discard the oldest finished commands once there are more than `MAX_FINISHED`
+fn prune(data: &mut ScheduleData) {
+    let finished = |command: &ScheduledCommand| {
+        command.status != ScheduleStatus::Pending && command.status != ScheduleStatus::Running
+    };
+
+    let mut excess = data
+        .commands
+        .iter()
+        .filter(|command| finished(command))
+        .count()
+        .saturating_sub(MAX_FINISHED);
+
+    data.commands.retain(|command| {
+        if excess > 0 && finished(command) {
+            excess -= 1;
+            false
+        } else {
+            true
+        }
+    });
+}
+
+fn parse_variables(variables: &str) -> Result<InputValue, String> {
+    let value: InputValue = serde_json::from_str(variables)
+        .map_err(|err| format!("Invalid request variables: {}", err))?;
+    if value.to_object_value().is_none() {
+        return Err("Request variables must be a JSON object".to_owned());
+    }
+    Ok(value)
+}
+
+fn parse_request(command: &ScheduledCommand) -> Result<GraphQLRequest, String> {
+    let variables = match command.variables {
+        Some(ref variables) => Some(parse_variables(variables)?),
+        None => None,
+    };
+    Ok(GraphQLRequest::new(
+        command.request.clone(),
+        None,
+        variables,
+    ))
+}
+
+fn json_error(message: &str) -> serde_json::Value {
+    serde_json::json!({ "errors": [{ "message": message }] })
+}
+
+// Fields added to the service's mutation root
+pub(crate) fn mutation_fields<'r>(
+    registry: &mut Registry<'r>,
+) -> Vec<Field<'r, DefaultScalarValue>> {
+    vec![
+        registry
+            .field::<ScheduledCommand>(SCHEDULE_FIELD, &())
+            .description("Schedule a GraphQL request to be executed at the given time")
+            .argument(
+                registry
+                    .arg::<f64>("time", &())
+                    .description("Time to execute the request, in seconds since the UNIX epoch"),
+            )
+            .argument(
+                registry
+                    .arg::<String>("request", &())
+                    .description("GraphQL request to execute"),
+            )
+            .argument(
+                registry
+                    .arg::<Option<String>>("variables", &())
+                    .description("Variables for the request, as a JSON object"),
+            ),
+        registry
+            .field::<ScheduledCommand>(CANCEL_FIELD, &())
+            .description("Cancel a pending scheduled command")
+            .argument(registry.arg::<i32>("id", &())),
+    ]
+}
+
+// Fields added to the service's query root
+pub(crate) fn query_fields<'r>(registry: &mut Registry<'r>) -> Vec<Field<'r, DefaultScalarValue>> {
+    vec![registry
+        .field::<Vec<ScheduledCommand>>(SCHEDULED_FIELD, &())
+        .description("Scheduled commands, in execution order")
+        .argument(
+            registry
+                .arg::<Option<i32>>("id", &())
+                .description("Only return the command with this ID"),
+        )
+        .argument(
+            registry
+                .arg::<Option<ScheduleStatus>>("status", &())
+                .description("Only return commands with this status"),
+        )]
+}
+
+pub(crate) fn resolve_mutation<CtxT>(
+    scheduler: Option<&Scheduler>,
+    field_name: &str,
+    arguments: &Arguments,
+    executor: &Executor<CtxT>,
+) -> ExecutionResult {
+    let scheduler = scheduler.ok_or_else(|| FieldError::from(DISABLED))?;
+
+    let command = if field_name == SCHEDULE_FIELD {
+        scheduler.schedule(
+            arguments.get("time").unwrap_or(::std::f64::NAN),
+            arguments.get("request").unwrap_or_default(),
+            arguments.get("variables"),
+        )
+    } else {
+        scheduler.cancel(arguments.get("id").unwrap_or_default())
+    }?;
+
+    executor.resolve_with_ctx(&(), &command)
+}
+
+pub(crate) fn resolve_query<CtxT>(
+    scheduler: Option<&Scheduler>,
+    arguments: &Arguments,
+    executor: &Executor<CtxT>,
+) -> ExecutionResult {
+    let scheduler = scheduler.ok_or_else(|| FieldError::from(DISABLED))?;
+    let commands = scheduler.list(arguments.get("id"), arguments.get("status"));
+    executor.resolve_with_ctx(&(), &commands)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::udp::Encoding;
+    use crate::{Context, Service};
+    use juniper::{graphql_object, FieldResult};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    // Commands succeed unless their request mentions "fail"
+    fn runner() -> Runner {
+        Arc::new(|request| {
+            let request = serde_json::to_value(request).unwrap();
+            let query = request["query"].as_str().unwrap().to_owned();
+            (
+                !query.contains("fail"),
+                json!({ "data": query }).to_string(),
+            )
+        })
+    }
+
+    #[test]
+    fn run_due_commands() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("schedule");
+        let scheduler = Scheduler::new(&path);
+
+        let first = scheduler.schedule(20.0, "second".to_owned(), None).unwrap();
+        let second = scheduler.schedule(10.0, "fail".to_owned(), None).unwrap();
+        let third = scheduler.schedule(30.0, "third".to_owned(), None).unwrap();
+
+        let ids: Vec<i32> = scheduler.list(None, None).iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![second.id, first.id, third.id]);
+
+        assert_eq!(scheduler.run_due(&runner(), 25.0), 2);
+        assert_eq!(scheduler.run_due(&runner(), 25.0), 0);
+
+        let failed = scheduler.list(Some(second.id), None);
+        assert_eq!(failed[0].status, ScheduleStatus::Failed);
+        let completed = scheduler.list(None, Some(ScheduleStatus::Completed));
+        assert_eq!(completed.len(), 1);
+        assert_eq!(
+            completed[0].response,
+            Some(r#"{"data":"second"}"#.to_owned())
+        );
+
+        // The queue survives a restart
+        let restored = Scheduler::new(&path);
+        assert_eq!(restored.list(None, None), scheduler.list(None, None));
+        assert_eq!(restored.run_due(&runner(), 35.0), 1);
+        assert_eq!(
+            restored.list(Some(third.id), None)[0].status,
+            ScheduleStatus::Completed
+        );
+    }
+
+    #[test]
+    fn cancel_command() {
+        let dir = TempDir::new().unwrap();
+        let scheduler = Scheduler::new(dir.path().join("schedule"));
+
+        let command = scheduler.schedule(10.0, "deploy".to_owned(), None).unwrap();
+        assert_eq!(
+            scheduler.cancel(command.id).unwrap().status,
+            ScheduleStatus::Cancelled
+        );
+        assert_eq!(scheduler.run_due(&runner(), 20.0), 0);
+        assert_eq!(
+            scheduler.cancel(command.id),
+            Err(format!("Command {} is no longer pending", command.id))
+        );
+        assert_eq!(
+            scheduler.cancel(99),
+            Err("No scheduled command with ID 99".to_owned())
+        );
+    }
+
+    #[test]
+    fn invalid_command() {
+        let dir = TempDir::new().unwrap();
+        let scheduler = Scheduler::new(dir.path().join("schedule"));
+
+        assert!(scheduler.schedule(10.0, " ".to_owned(), None).is_err());
+        assert!(scheduler
+            .schedule(10.0, "deploy".to_owned(), Some("[1]".to_owned()))
+            .is_err());
+        assert!(scheduler.list(None, None).is_empty());
+    }
+
+    #[test]
+    fn interrupted_command() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("schedule");
+        {
+            let scheduler = Scheduler::new(&path);
+            scheduler.schedule(10.0, "reset".to_owned(), None).unwrap();
+            scheduler.next_due(10.0).unwrap();
+        }
+
+        let scheduler = Scheduler::new(&path);
+        assert_eq!(scheduler.run_due(&runner(), 20.0), 0);
+        assert_eq!(scheduler.list(None, None)[0].status, ScheduleStatus::Failed);
+    }
+
+    #[test]
+    fn prune_finished() {
+        let dir = TempDir::new().unwrap();
+        let scheduler = Scheduler::new(dir.path().join("schedule"));
+
+        for num in 0..(MAX_FINISHED + 5) {
+            scheduler
+                .schedule(num as f64, "noop".to_owned(), None)
+                .unwrap();
+        }
+        let pending = scheduler.schedule(1000.0, "noop".to_owned(), None).unwrap();
+        scheduler.run_due(&runner(), 500.0);
+
+        let commands = scheduler.list(None, None);
+        assert_eq!(commands.len(), MAX_FINISHED + 1);
+        assert_eq!(commands[0].time, 5.0);
+        assert_eq!(commands[MAX_FINISHED].id, pending.id);
+    }
+
+    struct Query;
+
+    graphql_object!(Query: Context<()> as "Query" |&self| {
+        field ping() -> String {
+            "pong".to_owned()
+        }
+    });
+
+    struct Mutation;
+
+    graphql_object!(Mutation: Context<()> as "Mutation" |&self| {
+        field deploy(delay: i32) -> String {
+            format!("deployed after {}", delay)
+        }
+
+        field arm() -> FieldResult<String> {
+            Err(FieldError::from("Unable to arm"))
+        }
+    });
+
+    fn execute(service: &Service, query: &str) -> serde_json::Value {
+        let request = serde_json::from_value(json!({ "query": query })).unwrap();
+        let response = (service.udp)(&request, Encoding::Json, "10.0.0.5:4000".parse().unwrap());
+        serde_json::from_slice(&response).unwrap()
+    }
+
+    #[test]
+    fn service_schedule() {
+        let dir = TempDir::new().unwrap();
+        let config = Config::new_from_str(
+            "schedule-service",
+            &format!(
+                "[schedule-service]\nschedule_file = \"{}\"",
+                dir.path().join("schedule").display()
+            ),
+        );
+        let service = Service::new(config, (), Query, Mutation);
+
+        let response = execute(
+            &service,
+            r#"mutation {
+                scheduleCommand(
+                    time: 10.0,
+                    request: "mutation Deploy($delay: Int!) { deploy(delay: $delay) }",
+                    variables: "{\"delay\": 5}"
+                ) { id status }
+            }"#,
+        );
+        assert_eq!(
+            response,
+            json!({ "data": { "scheduleCommand": { "id": 1, "status": "PENDING" } } })
+        );
+
+        let (ref scheduler, ref runner) = *service.schedule.as_ref().unwrap();
+        assert_eq!(scheduler.run_due(runner, 20.0), 1);
+
+        let response = execute(
+            &service,
+            "{ scheduledCommands(status: COMPLETED) { id response } }",
+        );
+        assert_eq!(
+            response,
+            json!({ "data": { "scheduledCommands": [{
+                "id": 1,
+                "response": r#"{"data":{"deploy":"deployed after 5"}}"#,
+            }] } })
+        );
+
+        let response = execute(
+            &service,
+            "mutation { cancelScheduledCommand(id: 1) { id } }",
+        );
+        assert_eq!(
+            response["errors"][0]["message"],
+            "Command 1 is no longer pending"
+        );
+    }
+
+    #[test]
+    fn service_schedule_field_error() {
+        let dir = TempDir::new().unwrap();
+        let config = Config::new_from_str(
+            "schedule-service",
+            &format!(
+                "[schedule-service]\nschedule_file = \"{}\"",
+                dir.path().join("schedule").display()
+            ),
+        );
+        let service = Service::new(config, (), Query, Mutation);
+
+        execute(
+            &service,
+            r#"mutation {
+                scheduleCommand(time: 10.0, request: "mutation { arm }") { id }
+            }"#,
+        );
+
+        let (ref scheduler, ref runner) = *service.schedule.as_ref().unwrap();
+        assert_eq!(scheduler.run_due(runner, 20.0), 1);
+
+        let response = execute(&service, "{ scheduledCommands { id status response } }");
+        let command = &response["data"]["scheduledCommands"][0];
+        assert_eq!(command["status"], "FAILED");
+
+        let result: serde_json::Value =
+            serde_json::from_str(command["response"].as_str().unwrap()).unwrap();
+        assert_eq!(result["errors"][0]["message"], "Unable to arm");
+    }
+
+    #[test]
+    fn service_schedule_disabled() {
+        let service = Service::new(Default::default(), (), Query, Mutation);
+
+        let response = execute(&service, "{ scheduledCommands { id } }");
+        assert_eq!(response["errors"][0]["message"], DISABLED);
+    }
+}
@@ -14,12 +14,20 @@
 // limitations under the License.
 //
 
+use crate::history::History;
+use crate::metrics::{self, Metrics};
+use crate::reload::{ConfigReload, ReloadResponse, Reloader};
+use crate::schedule::{Runner, Scheduler};
+use crate::subscriptions::{self, Publisher};
+use crate::udp::{self, Handler};
 use juniper::{Context as JuniperContext, GraphQLType, RootNode};
 use kubos_system::Config;
 use log::info;
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::net::{SocketAddr, UdpSocket};
 use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::Duration;
 
 use warp::{filters::BoxedFilter, Filter};
 
@@ -31,6 +39,8 @@ pub struct Context<T> {
     pub subsystem: T,
     ///
     pub storage: Arc<RwLock<HashMap<String, String>>>,
+    reloader: Option<Arc<Reloader>>,
+    source: Option<SocketAddr>,
 }
 
 impl<T> JuniperContext for Context<T> {}
@@ -41,6 +51,11 @@ impl<T> Context<T> {
         &self.subsystem
     }
 
+    /// Returns the address of the client which sent the current request, if it is known
+    pub fn source(&self) -> Option<SocketAddr> {
+        self.source
+    }
+
     /// Attempts to get a value from the context's storage
     ///
     /// # Arguments
@@ -79,12 +94,39 @@ impl<T> Context<T> {
     pub fn clear_all(&self) {
         self.storage.write().unwrap().clear();
     }
+
+    /// Re-reads the service's config file and hands the new configuration to the subsystem.
+    /// Intended to back a `reloadConfig` mutation.
+    ///
+    /// Only available for services created with
+    /// [`Service::new_reloadable`](struct.Service.html#method.new_reloadable)
+    ///
+    /// ### Examples
+    ///
+    /// ```rust,ignore
+    /// field reload_config(&executor) -> FieldResult<ReloadResponse>
+    ///     as "Reload the service's configuration"
+    /// {
+    ///     Ok(executor.context().reload_config())
+    /// }
+    /// ```
+    pub fn reload_config(&self) -> ReloadResponse {
+        match self.reloader {
+            Some(ref reloader) => reloader.reload(),
+            None => ReloadResponse {
+                errors: "Config reloading is not enabled for this service".to_owned(),
+                success: false,
+                changed: vec![],
+                restart_required: vec![],
+            },
+        }
+    }
 }
 
 /// This structure represents a hardware service.
 ///
 /// Specifically the functionality provided by this struct
-/// exists to provide a GraphQL interface over UDP, a means
+/// exists to provide a GraphQL interface over HTTP and UDP, a means
 /// of exposing a subsystem to GraphQL queries and means
 /// for persistence throughout GraphQL queries.
 ///
@@ -104,6 +146,11 @@ impl<T> Context<T> {
 /// ```
 pub struct Service {
     config: Config,
+    reloader: Option<Arc<Reloader>>,
+    metrics: Arc<Metrics>,
+    publisher: Option<Publisher>,
+    pub(crate) udp: Handler,
+    pub(crate) schedule: Option<(Arc<Scheduler>, Runner)>,
     ///
     pub filter: BoxedFilter<(warp::http::response::Response<std::vec::Vec<u8>>,)>,
 }
@@ -128,14 +175,112 @@ impl Service {
         Mutation: GraphQLType<Context = Context<S>, TypeInfo = ()> + Send + Sync + 'static,
         S: Send + Sync + Clone + 'static,
     {
-        let root_node = RootNode::new(query, mutation);
+        Self::build(config, subsystem, None, query, mutation)
+    }
+
+    /// Creates a new service instance whose configuration can be reloaded at runtime,
+    /// either with [`Context::reload_config`](struct.Context.html#method.reload_config) or by
+    /// watching the config file with [`watch_config`](#method.watch_config)
+    ///
+    /// New configurations are validated with `Config::try_new_from_path` and then passed
+    /// to the subsystem's [`ConfigReload`](trait.ConfigReload.html) implementation.
+    /// Clones of the subsystem are expected to share their state.
+    ///
+    /// # Arguments
+    ///
+    /// `config` - The service's configuration. It must have been loaded from a file
+    /// `subsystem` - An instance of the subsystem struct. This one instance will be used by all queries.
+    /// `query` - The root query struct holding all other GraphQL queries.
+    /// `mutation` - The root mutation struct holding all other GraphQL mutations.
+    pub fn new_reloadable<Query, Mutation, S>(
+        config: Config,
+        subsystem: S,
+        query: Query,
+        mutation: Mutation,
+    ) -> Self
+    where
+        Query: GraphQLType<Context = Context<S>, TypeInfo = ()> + Send + Sync + 'static,
+        Mutation: GraphQLType<Context = Context<S>, TypeInfo = ()> + Send + Sync + 'static,
+        S: ConfigReload + Send + Sync + Clone + 'static,
+    {
+        let reloader = Arc::new(Reloader::new(config.clone(), subsystem.clone()));
+        Self::build(config, subsystem, Some(reloader), query, mutation)
+    }
+
+    fn build<Query, Mutation, S>(
+        config: Config,
+        subsystem: S,
+        reloader: Option<Arc<Reloader>>,
+        query: Query,
+        mutation: Mutation,
+    ) -> Self
+    where
+        Query: GraphQLType<Context = Context<S>, TypeInfo = ()> + Send + Sync + 'static,
+        Mutation: GraphQLType<Context = Context<S>, TypeInfo = ()> + Send + Sync + 'static,
+        S: Send + Sync + Clone + 'static,
+    {
+        let metrics = Arc::new(Metrics::new(config.name()));
+        let history = History::from_config(&config).map(Arc::new);
+        let scheduler = Scheduler::from_config(&config).map(Arc::new);
+
+        // Wrap the service's schema in order to add the built-in queries and mutations,
+        // to collect metrics for every request, and to record every mutation
+        let query =
+            metrics::QueryRoot::new(query, metrics.clone(), history.clone(), scheduler.clone());
+        let mutation =
+            metrics::MutationRoot::new(mutation, metrics.clone(), history, scheduler.clone());
         let context = Context {
             subsystem,
             storage: Arc::new(RwLock::new(HashMap::new())),
+            reloader: reloader.clone(),
+            source: None,
         };
 
+        // Requests received over UDP are run against a second copy of the schema,
+        // which shares the same roots and context
+        let udp_root = RootNode::new(query.clone(), mutation.clone());
+        let udp_context = context.clone();
+        let udp_metrics = metrics.clone();
+        let udp: Handler = Arc::new(move |request, encoding, peer| {
+            udp_metrics.record_request();
+            let mut context = udp_context.clone();
+            context.source = Some(peer);
+            udp::encode(&request.execute(&udp_root, &context), encoding)
+        });
+
+        // Scheduled requests are also run against their own copy of the schema
+        let schedule = scheduler.map(|scheduler| {
+            let schedule_root = RootNode::new(query.clone(), mutation.clone());
+            let schedule_context = context.clone();
+            let runner: Runner = Arc::new(move |request| {
+                let response = request.execute(&schedule_root, &schedule_context);
+                match serde_json::to_value(&response) {
+                    // `is_ok` only says whether execution started. Errors from the fields
+                    // themselves are returned alongside the data
+                    Ok(json) => {
+                        let field_errors = json["errors"]
+                            .as_array()
+                            .map_or(false, |errors| !errors.is_empty());
+                        (response.is_ok() && !field_errors, json.to_string())
+                    }
+                    Err(err) => (false, err.to_string()),
+                }
+            });
+            (scheduler, runner)
+        });
+
+        let root_node = RootNode::new(query, mutation);
+
         // Make the subsystem and other persistent data available to all endpoints
-        let context = warp::any().map(move || context.clone()).boxed();
+        let request_metrics = metrics.clone();
+        let context = warp::addr::remote()
+            .map(move |source| {
+                request_metrics.record_request();
+                let mut context = context.clone();
+                context.source = source;
+                context
+            })
+            .boxed();
 
         let graphql_filter = juniper_warp::make_graphql_filter(root_node, context);
 
@@ -148,12 +293,70 @@ impl Service {
             .unify()
             .boxed();
 
-        Service { config, filter }
+        Service {
+            config,
+            reloader,
+            metrics,
+            publisher: None,
+            udp,
+            schedule,
+            filter,
+        }
+    }
+
+    /// Sets the version reported by the built-in `kubos` query.
+    /// Services will usually pass `env!("CARGO_PKG_VERSION")`
+    pub fn version(self, version: &str) -> Self {
+        self.metrics.set_version(version);
+        self
+    }
+
+    /// Enables GraphQL subscriptions, using the given publisher as the source of events.
+    ///
+    /// Clients can subscribe with a WebSocket connection to the service's `/subscriptions`
+    /// path, using the `graphql-ws` protocol. If the service accepts UDP requests, subscription
+    /// requests sent over UDP will also be accepted. UDP subscriptions expire after
+    /// [`UDP_SUBSCRIPTION_TIMEOUT`](constant.UDP_SUBSCRIPTION_TIMEOUT.html) seconds, unless
+    /// they are sent again.
+    pub fn publisher(mut self, publisher: Publisher) -> Self {
+        self.publisher = Some(publisher);
+        self
+    }
+
+    /// Reloads the service's configuration whenever its config file changes.
+    /// The file's modification time is checked at the given interval.
+    ///
+    /// Has no effect unless the service was created with
+    /// [`new_reloadable`](#method.new_reloadable)
+    pub fn watch_config(self, interval: Duration) -> Self {
+        match self.reloader {
+            Some(ref reloader) => Reloader::watch(reloader.clone(), interval),
+            None => log::warn!("Config reloading is not enabled for this service"),
+        }
+        self
     }
 
     /// Starts the service's GraphQL/UDP server. This function runs
     /// without return.
     ///
+    /// Requests are served over HTTP on the port given in the service's `addr` section.
+    /// If the service's config section contains a `udp_port` value, requests are also accepted
+    /// as single UDP datagrams on that port. Responses larger than the `udp_max_payload` value
+    /// (default: 1024 bytes) are split into fragments.
+    ///
+    /// If the service's config section contains a `metrics_interval` value (in seconds),
+    /// the service's uptime, request count, and error count will also be sent to the
+    /// telemetry service at that interval.
+    ///
+    /// If the service's config section contains a `history_file` value, every mutation is
+    /// recorded in that file and can be read back with the `commandHistory` query.
+    /// The file is rotated once it reaches `history_max_size` bytes (default: 1 MiB), keeping
+    /// `history_files` old files (default: 3).
+    ///
+    /// If the service's config section contains a `schedule_file` value, requests can be
+    /// scheduled for later execution with the `scheduleCommand` mutation. The queue of
+    /// scheduled requests is kept in that file.
+    ///
     /// # Panics
     ///
     /// The UDP interface will panic if the ip address and port provided
@@ -163,6 +366,51 @@ impl Service {
         let addr = self.config.hosturl().parse::<SocketAddr>().unwrap();
         info!("Listening on: {}", addr);
 
-        warp::serve(self.filter).run(addr);
+        if let Some(interval) = self
+            .config
+            .get("metrics_interval")
+            .and_then(|interval| interval.as_integer())
+        {
+            if interval > 0 {
+                Metrics::push_telemetry(
+                    self.metrics.clone(),
+                    &self.config,
+                    Duration::from_secs(interval as u64),
+                );
+            }
+        }
+
+        if let Some(port) = self
+            .config
+            .get("udp_port")
+            .and_then(|port| port.as_integer())
+        {
+            let mut udp_addr = addr;
+            udp_addr.set_port(port as u16);
+            let max_payload = self
+                .config
+                .get("udp_max_payload")
+                .and_then(|size| size.as_integer())
+                .map(|size| size as usize)
+                .unwrap_or(udp::DEFAULT_MAX_PAYLOAD);
+
+            let socket = UdpSocket::bind(udp_addr).unwrap();
+            info!("Listening for UDP requests on: {}", udp_addr);
+
+            let handler = self.udp.clone();
+            let publisher = self.publisher.clone();
+            thread::spawn(move || udp::serve(socket, handler, max_payload, publisher));
+        }
+
+        if let Some((scheduler, runner)) = self.schedule {
+            Scheduler::run(scheduler, runner);
+        }
+
+        match self.publisher {
+            Some(publisher) => {
+                warp::serve(subscriptions::filter(publisher).or(self.filter)).run(addr)
+            }
+            None => warp::serve(self.filter).run(addr),
+        }
     }
 }
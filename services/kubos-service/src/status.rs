@@ -0,0 +1,380 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use juniper::{GraphQLEnum, GraphQLObject};
+use kubos_system::Config;
+use log::{error, warn};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Maximum number of errors kept by a [`CommandStatus`](struct.CommandStatus.html).
+/// Once reached, the oldest errors are discarded.
+pub const MAX_ERRORS: usize = 100;
+
+/// Severity of an error recorded by a service
+#[derive(Clone, Copy, Debug, Deserialize, Eq, GraphQLEnum, PartialEq, Serialize)]
+pub enum Severity {
+    /// Something unexpected happened, but the request still completed
+    Warning,
+    /// A request failed
+    Error,
+    /// The service can no longer talk to its hardware and needs attention (e.g. a restart)
+    Critical,
+}
+
+/// An error recorded by a service
+#[derive(Clone, Debug, Deserialize, GraphQLObject, PartialEq, Serialize)]
+pub struct ErrorRecord {
+    /// Time the error was recorded, in seconds since the UNIX epoch
+    pub timestamp: f64,
+    /// Severity of the error
+    pub severity: Severity,
+    /// Error message
+    pub message: String,
+}
+
+/// Common interface for storing the errors encountered by a service.
+/// Used by the [`push_err!`](macro.push_err.html) and [`run!`](macro.run.html) macros.
+pub trait ErrorStore {
+    /// Record a new error
+    fn store_error(&self, severity: Severity, message: String);
+}
+
+impl ErrorStore for RwLock<Vec<String>> {
+    fn store_error(&self, _severity: Severity, message: String) {
+        error!("{}", message);
+        if let Ok(mut master_vec) = self.write() {
+            master_vec.push(message);
+        } else {
+            error!("Unable to add error to master list");
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct StatusData<A> {
+    ack: A,
+    ack_time: Option<f64>,
+    errors: VecDeque<ErrorRecord>,
+    // Number of errors at the end of the list which haven't been read yet
+    unread: usize,
+}
+
+/// Tracks the last mutation executed by a service, along with the errors it has encountered.
+///
+/// This provides the state behind the `ack` and `errors` queries used by the hardware
+/// services. `A` is the service's enum of mutations (usually named `AckCommand`).
+///
+/// If a status file is given, the state is saved after every change and restored when the
+/// service starts, so that errors aren't lost if the service restarts before they're read.
+///
+/// Clones share the same state.
+///
+/// ### Examples
+///
+/// ```rust,ignore
+/// use kubos_service::{CommandStatus, Severity};
+///
+/// let status = CommandStatus::from_config(&config, AckCommand::None);
+///
+/// status.set_ack(AckCommand::Noop);
+/// status.push_error(Severity::Warning, "Noop took longer than expected".to_owned());
+///
+/// // Fetches the errors which haven't been read yet
+/// let errors = status.take_errors();
+/// ```
+#[derive(Clone)]
+pub struct CommandStatus<A> {
+    data: Arc<Mutex<StatusData<A>>>,
+    path: Option<PathBuf>,
+}
+
+impl<A> CommandStatus<A>
+where
+    A: Copy + Serialize + DeserializeOwned,
+{
+    /// Create a new status tracker which is only kept in memory
+    ///
+    /// # Arguments
+    ///
+    /// `none` - Value returned by `ack` until a mutation has been run
+    pub fn new(none: A) -> Self {
+        CommandStatus {
+            data: Arc::new(Mutex::new(StatusData {
+                ack: none,
+                ack_time: None,
+                errors: VecDeque::new(),
+                unread: 0,
+            })),
+            path: None,
+        }
+    }
+
+    /// Create a status tracker which is saved to the given file, restoring any
+    /// previous state from it. If the file can't be read, a warning is logged and
+    /// the tracker starts out empty.
+    ///
+    /// # Arguments
+    ///
+    /// `path` - File used to store the status
+    /// `none` - Value returned by `ack` until a mutation has been run
+    pub fn persistent<P: Into<PathBuf>>(path: P, none: A) -> Self {
+        let path = path.into();
+        let mut status = CommandStatus::new(none);
+
+        if path.exists() {
+            match fs::read(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|data| serde_json::from_slice(&data).map_err(|err| err.to_string()))
+            {
+                Ok(data) => status.data = Arc::new(Mutex::new(data)),
+                Err(err) => warn!("Failed to restore status from {:?}: {}", path, err),
+            }
+        }
+
+        status.path = Some(path);
+        status
+    }
+
+    /// Create a status tracker using the `status_file` value from the service's
+    /// configuration. If no file is configured, the status is only kept in memory.
+    pub fn from_config(config: &Config, none: A) -> Self {
+        match config
+            .get("status_file")
+            .and_then(|val| val.as_str().map(|path| path.to_owned()))
+        {
+            Some(path) => CommandStatus::persistent(path, none),
+            None => CommandStatus::new(none),
+        }
+    }
+
+    /// Record the last mutation executed by the service
+    pub fn set_ack(&self, ack: A) {
+        let mut data = self.lock();
+        data.ack = ack;
+        data.ack_time = Some(now());
+        self.save(&data);
+    }
+
+    /// Get the last mutation executed by the service
+    pub fn ack(&self) -> A {
+        self.lock().ack
+    }
+
+    /// Get the time the last mutation was executed, in seconds since the UNIX epoch
+    pub fn ack_time(&self) -> Option<f64> {
+        self.lock().ack_time
+    }
+
+    /// Record a new error and send it to the system log
+    pub fn push_error(&self, severity: Severity, message: String) {
+        match severity {
+            Severity::Warning => warn!("{}", message),
+            _ => error!("{}", message),
+        }
+
+        let mut data = self.lock();
+        if data.errors.len() >= MAX_ERRORS {
+            data.errors.pop_front();
+        }
+        data.errors.push_back(ErrorRecord {
+            timestamp: now(),
+            severity,
+            message,
+        });
+        data.unread = (data.unread + 1).min(data.errors.len());
+        self.save(&data);
+    }
+
+    /// Get the errors which haven't been read yet, without marking them as read
+    pub fn unread_errors(&self) -> Vec<ErrorRecord> {
+        let data = self.lock();
+        let start = data.errors.len() - data.unread;
+        data.errors.iter().skip(start).cloned().collect()
+    }
+
+    /// Get the messages of the errors which haven't been read yet, without marking them as read
+    pub fn unread_error_messages(&self) -> Vec<String> {
+        self.unread_errors()
+            .into_iter()
+            .map(|record| record.message)
+            .collect()
+    }
+
+    /// Fetch all errors recorded since the last time the errors were read
+    pub fn take_errors(&self) -> Vec<ErrorRecord> {
+        let mut data = self.lock();
+        let start = data.errors.len() - data.unread;
+        let errors = data.errors.iter().skip(start).cloned().collect();
+        if data.unread > 0 {
+            data.unread = 0;
+            self.save(&data);
+        }
+        errors
+    }
+
+    /// Fetch the messages of all errors recorded since the last time the errors were read.
+    /// Intended to back a service's `errors` query.
+    pub fn take_error_messages(&self) -> Vec<String> {
+        self.take_errors()
+            .into_iter()
+            .map(|record| record.message)
+            .collect()
+    }
+
+    /// Get the most recent errors, including ones which have already been read
+    pub fn error_log(&self) -> Vec<ErrorRecord> {
+        self.lock().errors.iter().cloned().collect()
+    }
+
+    fn lock(&self) -> MutexGuard<StatusData<A>> {
+        // The data is always left in a consistent state, so it's still usable
+        // if another thread panicked while holding the lock
+        match self.data.lock() {
+            Ok(data) => data,
+            Err(err) => err.into_inner(),
+        }
+    }
+
+    // Write the status out to the status file, if there is one
+    fn save(&self, data: &StatusData<A>) {
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return,
+        };
+
+        // Write to a temporary file first, so that a reboot in the middle of
+        // saving doesn't corrupt the previous state
+        let temp = path.with_extension("tmp");
+        let result = serde_json::to_vec(data)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| fs::write(&temp, contents).map_err(|err| err.to_string()))
+            .and_then(|_| fs::rename(&temp, path).map_err(|err| err.to_string()));
+
+        if let Err(err) = result {
+            warn!("Failed to save status to {:?}: {}", path, err);
+        }
+    }
+}
+
+impl<A> ErrorStore for CommandStatus<A>
+where
+    A: Copy + Serialize + DeserializeOwned,
+{
+    fn store_error(&self, severity: Severity, message: String) {
+        self.push_error(severity, message);
+    }
+}
+
+// Current time, in seconds since the UNIX epoch
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs() as f64 + f64::from(time.subsec_nanos()) / 1_000_000_000.0)
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+    enum AckCommand {
+        None,
+        Noop,
+    }
+
+    #[test]
+    fn take_errors() {
+        let status = CommandStatus::new(AckCommand::None);
+        assert_eq!(status.ack(), AckCommand::None);
+        assert_eq!(status.ack_time(), None);
+
+        status.set_ack(AckCommand::Noop);
+        status.push_error(Severity::Error, "first".to_owned());
+        status.push_error(Severity::Warning, "second".to_owned());
+
+        assert_eq!(status.ack(), AckCommand::Noop);
+        assert_eq!(status.unread_error_messages(), vec!["first", "second"]);
+        assert!(status.ack_time().is_some());
+        assert_eq!(status.take_error_messages(), vec!["first", "second"]);
+        assert!(status.take_errors().is_empty());
+
+        status.push_error(Severity::Critical, "third".to_owned());
+        let errors = status.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].severity, Severity::Critical);
+
+        // The log keeps errors which have already been read
+        assert_eq!(status.error_log().len(), 3);
+    }
+
+    #[test]
+    fn error_limit() {
+        let status = CommandStatus::new(AckCommand::None);
+        for num in 0..(MAX_ERRORS + 5) {
+            status.push_error(Severity::Error, num.to_string());
+        }
+
+        let errors = status.take_errors();
+        assert_eq!(errors.len(), MAX_ERRORS);
+        assert_eq!(errors[0].message, "5");
+    }
+
+    #[test]
+    fn persistent_status() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("status.json");
+
+        {
+            let status = CommandStatus::persistent(&path, AckCommand::None);
+            status.set_ack(AckCommand::Noop);
+            status.push_error(Severity::Error, "first".to_owned());
+            status.push_error(Severity::Error, "second".to_owned());
+            assert_eq!(status.take_errors().len(), 2);
+            status.push_error(Severity::Error, "third".to_owned());
+        }
+
+        // Simulate a service restart
+        let status = CommandStatus::persistent(&path, AckCommand::None);
+        assert_eq!(status.ack(), AckCommand::Noop);
+        assert_eq!(status.take_error_messages(), vec!["third"]);
+        assert_eq!(status.error_log().len(), 3);
+    }
+
+    #[test]
+    fn persistent_status_corrupt() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("status.json");
+        fs::write(&path, "not json").unwrap();
+
+        let status = CommandStatus::persistent(&path, AckCommand::None);
+        assert_eq!(status.ack(), AckCommand::None);
+        assert!(status.take_errors().is_empty());
+
+        // The file is replaced on the next change
+        status.push_error(Severity::Error, "first".to_owned());
+        let status = CommandStatus::persistent(&path, AckCommand::None);
+        assert_eq!(status.take_error_messages(), vec!["first"]);
+    }
+}
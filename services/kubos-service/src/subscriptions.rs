@@ -0,0 +1,965 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use crate::udp::{self, Encoding};
+use futures::sync::mpsc::{self, UnboundedSender};
+use log::{debug, warn};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use warp::filters::ws::{Message, WebSocket, Ws2};
+use warp::{Filter, Future, Rejection, Reply, Stream};
+
+/// Number of seconds a UDP subscription lasts, unless it is renewed by sending it again
+pub const UDP_SUBSCRIPTION_TIMEOUT: u64 = 300;
+
+// Argument which sets the minimum time (in seconds) between events sent to a subscriber
+const MIN_INTERVAL: &str = "minInterval";
+
+// A field requested by a subscription
+#[derive(Clone, Debug, PartialEq)]
+struct Selection {
+    // Name to use in the response (the field's alias, if it has one)
+    key: String,
+    name: String,
+    // Requested sub-fields. If empty, the whole value is returned
+    fields: Vec<Selection>,
+}
+
+// A field along with its arguments
+type Field = (Selection, HashMap<String, Value>);
+
+// A single event stream requested by a subscription
+#[derive(Debug)]
+struct Topic {
+    selection: Selection,
+    min_interval: Option<Duration>,
+    last_sent: Option<Instant>,
+}
+
+// Where a subscriber's events are sent
+enum Destination {
+    WebSocket {
+        id: String,
+        tx: UnboundedSender<Message>,
+    },
+    Udp {
+        socket: Arc<UdpSocket>,
+        peer: SocketAddr,
+        encoding: Encoding,
+        max_payload: usize,
+    },
+}
+
+impl Destination {
+    // Send the data for a single event. Returns false if the subscriber has gone away
+    fn send(&self, data: Value) -> bool {
+        match *self {
+            Destination::WebSocket { ref id, ref tx } => {
+                let message = json!({ "type": "data", "id": id, "payload": { "data": data } });
+                tx.unbounded_send(Message::text(message.to_string()))
+                    .is_ok()
+            }
+            Destination::Udp {
+                ref socket,
+                peer,
+                encoding,
+                max_payload,
+            } => {
+                let message = udp::encode(&json!({ "data": data }), encoding);
+                udp::send(socket, peer, &message, encoding, max_payload);
+                true
+            }
+        }
+    }
+}
+
+struct Subscriber {
+    key: usize,
+    topics: Vec<Topic>,
+    destination: Destination,
+    expires: Option<Instant>,
+}
+
+struct PublisherData {
+    // Each topic, along with the fields its events contain
+    topics: Vec<(String, Vec<Selection>)>,
+    subscribers: Mutex<Vec<Subscriber>>,
+    next_key: AtomicUsize,
+}
+
+/// Sends events published by a service to the clients which have subscribed to them.
+///
+/// Each topic is made available as a field of a GraphQL subscription, and published events
+/// can be any type which implements `Serialize`. Subscribers select the event fields they want,
+/// just like a normal query, and may limit how often they receive events with the
+/// `minInterval` argument (in seconds). Events which arrive sooner are skipped.
+///
+/// Topics are registered along with the names of their events' fields, so that subscriptions
+/// which ask for a field the events don't have are rejected. The names must match the
+/// serialized events. Fields of nested objects (or of the objects in a list) are given as a
+/// dotted path, e.g. `cells.temp`.
+///
+/// Clones share the same subscribers, so a clone can be handed to the service's subsystem
+/// (ex. to a thread which reads telemetry from the device) while another is given to the
+/// service with [`Service::publisher`](struct.Service.html#method.publisher).
+///
+/// ### Examples
+///
+/// ```rust,ignore
+/// use kubos_service::{Publisher, Service};
+///
+/// let publisher = Publisher::new(&[("telemetry", &["voltage", "current", "cells.temp"])]);
+/// let subsystem = Subsystem::new(publisher.clone());
+///
+/// Service::new(config, subsystem, QueryRoot, MutationRoot)
+///     .publisher(publisher)
+///     .start();
+///
+/// // Then, from within the subsystem
+/// publisher.publish("telemetry", &telemetry);
+/// ```
+///
+/// A client could then request
+///
+/// ```graphql
+/// subscription {
+///     telemetry(minInterval: 10.0) {
+///         voltage,
+///         current
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Publisher {
+    data: Arc<PublisherData>,
+}
+
+impl Publisher {
+    /// Create a new publisher
+    ///
+    /// # Arguments
+    ///
+    /// `topics` - Names of the subscription fields events can be published to, each with
+    /// the fields of its events
+    pub fn new(topics: &[(&str, &[&str])]) -> Self {
+        Publisher {
+            data: Arc::new(PublisherData {
+                topics: topics
+                    .iter()
+                    .map(|(topic, fields)| ((*topic).to_owned(), field_tree(fields)))
+                    .collect(),
+                subscribers: Mutex::new(vec![]),
+                next_key: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Send an event to everyone subscribed to the given topic
+    pub fn publish<T: Serialize>(&self, topic: &str, event: &T) {
+        if !self.data.topics.iter().any(|(name, _)| name == topic) {
+            warn!("Unable to publish to unknown topic '{}'", topic);
+            return;
+        }
+
+        let now = Instant::now();
+        let mut subscribers = self.lock();
+        subscribers.retain(|subscriber| subscriber.expires.map_or(true, |expires| expires > now));
+
+        // Skip converting the event if no one is going to receive it
+        if !subscribers.iter().any(|subscriber| {
+            subscriber
+                .topics
+                .iter()
+                .any(|entry| entry.selection.name == topic)
+        }) {
+            return;
+        }
+
+        let event = match serde_json::to_value(event) {
+            Ok(event) => event,
+            Err(err) => {
+                warn!("Unable to publish event to '{}': {}", topic, err);
+                return;
+            }
+        };
+
+        let mut closed = vec![];
+        for subscriber in subscribers.iter_mut() {
+            for entry in subscriber.topics.iter_mut() {
+                if entry.selection.name != topic || is_limited(entry, now) {
+                    continue;
+                }
+                entry.last_sent = Some(now);
+
+                let mut data = Map::new();
+                data.insert(
+                    entry.selection.key.clone(),
+                    project(&event, &entry.selection.fields),
+                );
+                if !subscriber.destination.send(Value::Object(data)) {
+                    closed.push(subscriber.key);
+                }
+            }
+        }
+        subscribers.retain(|subscriber| !closed.contains(&subscriber.key));
+    }
+
+    /// Get the number of current subscribers
+    pub fn subscriber_count(&self) -> usize {
+        self.lock().len()
+    }
+
+    // Add a new subscriber. Returns the key which can be used to remove it
+    fn subscribe(
+        &self,
+        request: &Value,
+        destination: Destination,
+        expires: Option<Instant>,
+    ) -> Result<usize, String> {
+        let query = request["query"]
+            .as_str()
+            .ok_or_else(|| "Request has no query".to_owned())?;
+
+        let topics = parse_subscription(query, &request["variables"])?
+            .into_iter()
+            .map(|(selection, min_interval)| {
+                let known = self
+                    .data
+                    .topics
+                    .iter()
+                    .find(|(name, _)| *name == selection.name)
+                    .map(|(_, fields)| fields)
+                    .ok_or_else(|| format!("Unknown subscription field '{}'", selection.name))?;
+                check_fields(&selection.fields, known, &selection.name)?;
+
+                Ok(Topic {
+                    selection,
+                    min_interval,
+                    last_sent: None,
+                })
+            })
+            .collect::<Result<Vec<Topic>, String>>()?;
+
+        let key = self.data.next_key.fetch_add(1, Ordering::Relaxed);
+        self.lock().push(Subscriber {
+            key,
+            topics,
+            destination,
+            expires,
+        });
+        Ok(key)
+    }
+
+    /// Add a subscriber whose events are sent over UDP. A peer only has one subscription,
+    /// so subscribing again replaces (and renews) the previous one.
+    pub(crate) fn subscribe_udp(
+        &self,
+        request: &Value,
+        socket: Arc<UdpSocket>,
+        peer: SocketAddr,
+        encoding: Encoding,
+        max_payload: usize,
+    ) -> Result<(), String> {
+        let expires = Instant::now() + Duration::from_secs(UDP_SUBSCRIPTION_TIMEOUT);
+        let destination = Destination::Udp {
+            socket,
+            peer,
+            encoding,
+            max_payload,
+        };
+
+        let key = self.subscribe(request, destination, Some(expires))?;
+        self.lock()
+            .retain(|subscriber| match subscriber.destination {
+                Destination::Udp { peer: old, .. } => old != peer || subscriber.key == key,
+                _ => true,
+            });
+        Ok(())
+    }
+
+    fn unsubscribe(&self, key: usize) {
+        self.lock().retain(|subscriber| subscriber.key != key);
+    }
+
+    fn lock(&self) -> MutexGuard<Vec<Subscriber>> {
+        match self.data.subscribers.lock() {
+            Ok(subscribers) => subscribers,
+            Err(err) => err.into_inner(),
+        }
+    }
+}
+
+fn is_limited(entry: &Topic, now: Instant) -> bool {
+    match (entry.min_interval, entry.last_sent) {
+        (Some(min), Some(last)) => now.duration_since(last) < min,
+        _ => false,
+    }
+}
+
+/// Check whether a decoded request is a subscription
+pub(crate) fn is_subscription(request: &Value) -> bool {
+    request["query"].as_str().map_or(false, |query| {
+        query.trim_start().starts_with("subscription")
+    })
+}
+
+// Build the tree of an event's fields from their dotted paths
+fn field_tree(paths: &[&str]) -> Vec<Selection> {
+    let mut tree: Vec<Selection> = vec![];
+
+    for path in paths {
+        let mut fields = &mut tree;
+        for name in path.split('.') {
+            let pos = match fields.iter().position(|field| field.name == name) {
+                Some(pos) => pos,
+                None => {
+                    fields.push(Selection {
+                        key: name.to_owned(),
+                        name: name.to_owned(),
+                        fields: vec![],
+                    });
+                    fields.len() - 1
+                }
+            };
+            fields = &mut fields[pos].fields;
+        }
+    }
+
+    tree
+}
+
+// Make sure every requested field is one which the events actually contain
+fn check_fields(requested: &[Selection], known: &[Selection], parent: &str) -> Result<(), String> {
+    for field in requested {
+        let path = format!("{}.{}", parent, field.name);
+        let known = known
+            .iter()
+            .find(|known| known.name == field.name)
+            .ok_or_else(|| format!("Unknown field '{}'", path))?;
+
+        if !field.fields.is_empty() && known.fields.is_empty() {
+            return Err(format!("Field '{}' has no sub-fields", path));
+        }
+        check_fields(&field.fields, &known.fields, &path)?;
+    }
+
+    Ok(())
+}
+
+// Pick the requested fields out of an event
+fn project(value: &Value, fields: &[Selection]) -> Value {
+    if fields.is_empty() {
+        return value.clone();
+    }
+
+    match *value {
+        Value::Array(ref items) => items.iter().map(|item| project(item, fields)).collect(),
+        Value::Object(ref object) => Value::Object(
+            fields
+                .iter()
+                .map(|field| {
+                    let value = object.get(&field.name).unwrap_or(&Value::Null);
+                    (field.key.clone(), project(value, &field.fields))
+                })
+                .collect(),
+        ),
+        ref other => other.clone(),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Punct(char),
+    Name(String),
+    Number(f64),
+    Str(String),
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = query.chars().peekable();
+
+    while let Some(next) = chars.next() {
+        match next {
+            // Commas are insignificant in GraphQL
+            ' ' | '\t' | '\n' | '\r' | ',' => {}
+            '#' => {
+                while chars.peek().map_or(false, |next| *next != '\n') {
+                    chars.next();
+                }
+            }
+            '{' | '}' | '(' | ')' | ':' | '$' | '!' | '[' | ']' | '=' => {
+                tokens.push(Token::Punct(next))
+            }
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => value.extend(chars.next()),
+                        Some(other) => value.push(other),
+                        None => return Err("Unterminated string".to_owned()),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            '-' | '0'..='9' => {
+                let mut number = next.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_ascii_digit() || next == '.' || next == 'e' || next == 'E' {
+                        number.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let number = number
+                    .parse()
+                    .map_err(|_| format!("Invalid number '{}'", number))?;
+                tokens.push(Token::Number(number));
+            }
+            '_' | 'a'..='z' | 'A'..='Z' => {
+                let mut name = next.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next == '_' || next.is_ascii_alphanumeric() {
+                        name.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Name(name));
+            }
+            other => return Err(format!("Unexpected character '{}'", other)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    variables: &'a Value,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<Token, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| "Unexpected end of subscription".to_owned())?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, punct: char) -> Result<(), String> {
+        match self.next()? {
+            Token::Punct(next) if next == punct => Ok(()),
+            other => Err(format!("Expected '{}', found {:?}", punct, other)),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        match self.next()? {
+            Token::Name(name) => Ok(name),
+            other => Err(format!("Expected a name, found {:?}", other)),
+        }
+    }
+
+    // `{ field field(arg: value) { ... } }`, returning each field along with its arguments
+    fn selection_set(&mut self) -> Result<Vec<Field>, String> {
+        self.expect('{')?;
+        let mut fields = vec![];
+
+        while self.peek() != Some(&Token::Punct('}')) {
+            let key = self.name()?;
+            let name = if self.peek() == Some(&Token::Punct(':')) {
+                self.pos += 1;
+                self.name()?
+            } else {
+                key.clone()
+            };
+
+            let mut arguments = HashMap::new();
+            if self.peek() == Some(&Token::Punct('(')) {
+                self.pos += 1;
+                while self.peek() != Some(&Token::Punct(')')) {
+                    let arg = self.name()?;
+                    self.expect(':')?;
+                    let value = self.value()?;
+                    arguments.insert(arg, value);
+                }
+                self.pos += 1;
+            }
+
+            let mut selection = Selection {
+                key,
+                name,
+                fields: vec![],
+            };
+            if self.peek() == Some(&Token::Punct('{')) {
+                for (field, arguments) in self.selection_set()? {
+                    if !arguments.is_empty() {
+                        return Err(format!(
+                            "Arguments are not supported for field '{}'",
+                            field.name
+                        ));
+                    }
+                    selection.fields.push(field);
+                }
+            }
+
+            fields.push((selection, arguments));
+        }
+
+        self.pos += 1;
+        if fields.is_empty() {
+            return Err("Selection set is empty".to_owned());
+        }
+        Ok(fields)
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        match self.next()? {
+            Token::Punct('$') => {
+                let name = self.name()?;
+                match self.variables.get(&name) {
+                    Some(value) => Ok(value.clone()),
+                    None => Err(format!("Variable '${}' is not defined", name)),
+                }
+            }
+            Token::Number(number) => Ok(json!(number)),
+            Token::Str(value) => Ok(Value::String(value)),
+            Token::Name(ref name) if name == "true" => Ok(Value::Bool(true)),
+            Token::Name(ref name) if name == "false" => Ok(Value::Bool(false)),
+            Token::Name(ref name) if name == "null" => Ok(Value::Null),
+            Token::Name(name) => Ok(Value::String(name)),
+            other => Err(format!("Expected a value, found {:?}", other)),
+        }
+    }
+}
+
+// Parse a subscription document into the requested topics and their minimum intervals
+fn parse_subscription(
+    query: &str,
+    variables: &Value,
+) -> Result<Vec<(Selection, Option<Duration>)>, String> {
+    let mut parser = Parser {
+        tokens: tokenize(query)?,
+        pos: 0,
+        variables,
+    };
+
+    match parser.peek() {
+        Some(Token::Name(name)) if name == "subscription" => {
+            parser.pos += 1;
+            if let Some(&Token::Name(_)) = parser.peek() {
+                parser.pos += 1;
+            }
+            // The variables' types aren't needed, since they're only used as argument values
+            if parser.peek() == Some(&Token::Punct('(')) {
+                while parser.next()? != Token::Punct(')') {}
+            }
+        }
+        _ => return Err("Request is not a subscription".to_owned()),
+    }
+
+    let topics = parser
+        .selection_set()?
+        .into_iter()
+        .map(|(selection, mut arguments)| {
+            let min_interval = match arguments.remove(MIN_INTERVAL) {
+                Some(Value::Null) | None => None,
+                Some(value) => match value.as_f64() {
+                    Some(secs) if secs >= 0.0 => {
+                        Some(Duration::from_millis((secs * 1000.0) as u64))
+                    }
+                    _ => return Err(format!("Invalid '{}' value: {}", MIN_INTERVAL, value)),
+                },
+            };
+
+            if let Some(arg) = arguments.keys().next() {
+                return Err(format!("Unknown argument '{}'", arg));
+            }
+            Ok((selection, min_interval))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    if parser.pos != parser.tokens.len() {
+        return Err("Unexpected content after subscription".to_owned());
+    }
+    Ok(topics)
+}
+
+// Handle a single message from a WebSocket client, following the `graphql-ws` protocol
+fn handle_message(
+    publisher: &Publisher,
+    tx: &UnboundedSender<Message>,
+    active: &mut HashMap<String, usize>,
+    message: &Message,
+) {
+    // Pings and close messages are handled by the WebSocket library
+    let text = match message.to_str() {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+
+    let reply = |message: Value| {
+        let _ = tx.unbounded_send(Message::text(message.to_string()));
+    };
+
+    let message: Value = match serde_json::from_str(text) {
+        Ok(message) => message,
+        Err(err) => {
+            reply(json!({
+                "type": "connection_error",
+                "payload": { "message": format!("Invalid message: {}", err) }
+            }));
+            return;
+        }
+    };
+
+    let id = match message["id"] {
+        Value::String(ref id) => id.clone(),
+        Value::Null => String::new(),
+        ref other => other.to_string(),
+    };
+
+    match message["type"].as_str().unwrap_or("") {
+        "connection_init" => reply(json!({ "type": "connection_ack" })),
+        "start" => {
+            if active.contains_key(&id) {
+                reply(json!({
+                    "type": "error",
+                    "id": id,
+                    "payload": { "message": "Subscription ID is already in use" }
+                }));
+                return;
+            }
+
+            let destination = Destination::WebSocket {
+                id: id.clone(),
+                tx: tx.clone(),
+            };
+            match publisher.subscribe(&message["payload"], destination, None) {
+                Ok(key) => {
+                    active.insert(id, key);
+                }
+                Err(err) => {
+                    reply(json!({ "type": "error", "id": id, "payload": { "message": err } }))
+                }
+            }
+        }
+        "stop" => {
+            if let Some(key) = active.remove(&id) {
+                publisher.unsubscribe(key);
+            }
+            reply(json!({ "type": "complete", "id": id }));
+        }
+        "connection_terminate" => {
+            for (_, key) in active.drain() {
+                publisher.unsubscribe(key);
+            }
+        }
+        other => reply(json!({
+            "type": "connection_error",
+            "payload": { "message": format!("Unknown message type '{}'", other) }
+        })),
+    }
+}
+
+// Handle a WebSocket client until it disconnects
+fn connection(publisher: Publisher, socket: WebSocket) -> impl Future<Item = (), Error = ()> {
+    let (ws_tx, ws_rx) = socket.split();
+    let (tx, rx) = mpsc::unbounded();
+
+    // Events are queued up by the publishing thread and then forwarded to the client
+    warp::spawn(
+        rx.map_err(|()| -> warp::Error { unreachable!("unbounded rx never errors") })
+            .forward(ws_tx)
+            .map(|_| ())
+            .map_err(|err| debug!("Subscription connection closed: {}", err)),
+    );
+
+    let cleanup = publisher.clone();
+    ws_rx
+        .map_err(|err| debug!("Subscription connection closed: {}", err))
+        .fold(HashMap::new(), move |mut active, message| {
+            handle_message(&publisher, &tx, &mut active, &message);
+            Ok(active)
+        })
+        .map(move |active| {
+            for key in active.values() {
+                cleanup.unsubscribe(*key);
+            }
+        })
+}
+
+/// Filter which accepts WebSocket subscription connections on the `/subscriptions` path
+pub(crate) fn filter(
+    publisher: Publisher,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone + Send + Sync + 'static {
+    warp::path("subscriptions")
+        .and(warp::path::end())
+        .and(warp::ws2())
+        .map(move |ws: Ws2| {
+            let publisher = publisher.clone();
+            ws.on_upgrade(move |socket| connection(publisher, socket))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::sync::mpsc::UnboundedReceiver;
+    use serde_derive::Serialize;
+
+    const TELEMETRY_FIELDS: &[&str] = &["voltage", "current", "cells.temp", "cells.charge"];
+
+    #[derive(Serialize)]
+    struct Telemetry {
+        voltage: f64,
+        current: f64,
+        cells: Vec<Cell>,
+    }
+
+    #[derive(Serialize)]
+    struct Cell {
+        temp: i32,
+        charge: f64,
+    }
+
+    fn telemetry() -> Telemetry {
+        Telemetry {
+            voltage: 8.2,
+            current: 0.5,
+            cells: vec![
+                Cell {
+                    temp: 20,
+                    charge: 0.9,
+                },
+                Cell {
+                    temp: 21,
+                    charge: 0.8,
+                },
+            ],
+        }
+    }
+
+    fn subscribe(
+        publisher: &Publisher,
+        query: &str,
+        variables: Value,
+    ) -> Result<UnboundedReceiver<Message>, String> {
+        let (tx, rx) = mpsc::unbounded();
+        let destination = Destination::WebSocket {
+            id: "1".to_owned(),
+            tx,
+        };
+        publisher.subscribe(
+            &json!({ "query": query, "variables": variables }),
+            destination,
+            None,
+        )?;
+        Ok(rx)
+    }
+
+    fn received(rx: UnboundedReceiver<Message>) -> Vec<Value> {
+        rx.wait()
+            .map(|message| {
+                let message: Value =
+                    serde_json::from_str(message.unwrap().to_str().unwrap()).unwrap();
+                assert_eq!(message["type"], "data");
+                message["payload"]["data"].clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_errors() {
+        let publisher = Publisher::new(&[("telemetry", TELEMETRY_FIELDS)]);
+
+        for (query, error) in &[
+            ("{ telemetry }", "Request is not a subscription"),
+            (
+                "subscription { power }",
+                "Unknown subscription field 'power'",
+            ),
+            (
+                "subscription { telemetry { voltage, humidity } }",
+                "Unknown field 'telemetry.humidity'",
+            ),
+            (
+                "subscription { telemetry { cells { temp, humidity } } }",
+                "Unknown field 'telemetry.cells.humidity'",
+            ),
+            (
+                "subscription { telemetry { voltage { value } } }",
+                "Field 'telemetry.voltage' has no sub-fields",
+            ),
+            (
+                "subscription { telemetry(rate: 1) }",
+                "Unknown argument 'rate'",
+            ),
+            (
+                "subscription { telemetry(minInterval: $rate) }",
+                "Variable '$rate' is not defined",
+            ),
+            (
+                "subscription { telemetry { cells(first: 1) { temp } } }",
+                "Arguments are not supported for field 'cells'",
+            ),
+            (
+                "subscription { telemetry } }",
+                "Unexpected content after subscription",
+            ),
+        ] {
+            assert_eq!(
+                subscribe(&publisher, query, Value::Null).err(),
+                Some(error.to_string())
+            );
+        }
+        assert_eq!(publisher.subscriber_count(), 0);
+    }
+
+    #[test]
+    fn publish_selection() {
+        let publisher = Publisher::new(&[("telemetry", TELEMETRY_FIELDS), ("faults", &[])]);
+        let all = subscribe(&publisher, "subscription { telemetry }", Value::Null).unwrap();
+        let some = subscribe(
+            &publisher,
+            "subscription Power {\n  power: telemetry { voltage, cells { temp } } # Comment\n}",
+            Value::Null,
+        )
+        .unwrap();
+        let faults = subscribe(&publisher, "subscription { faults }", Value::Null).unwrap();
+
+        publisher.publish("telemetry", &telemetry());
+        drop(publisher);
+
+        assert_eq!(
+            received(all),
+            vec![json!({ "telemetry": serde_json::to_value(telemetry()).unwrap() })]
+        );
+        assert_eq!(
+            received(some),
+            vec![json!({ "power": { "voltage": 8.2, "cells": [{ "temp": 20 }, { "temp": 21 }] } })]
+        );
+        assert!(received(faults).is_empty());
+    }
+
+    #[test]
+    fn publish_rate_limit() {
+        let publisher = Publisher::new(&[("telemetry", TELEMETRY_FIELDS)]);
+        let limited = subscribe(
+            &publisher,
+            "subscription Limited($rate: Float) { telemetry(minInterval: $rate) { current } }",
+            json!({ "rate": 60 }),
+        )
+        .unwrap();
+        let unlimited = subscribe(
+            &publisher,
+            "subscription { telemetry { current } }",
+            Value::Null,
+        )
+        .unwrap();
+
+        for _ in 0..3 {
+            publisher.publish("telemetry", &telemetry());
+        }
+        drop(publisher);
+
+        assert_eq!(received(limited).len(), 1);
+        assert_eq!(received(unlimited).len(), 3);
+    }
+
+    #[test]
+    fn publish_closed() {
+        let publisher = Publisher::new(&[("telemetry", TELEMETRY_FIELDS)]);
+        let rx = subscribe(&publisher, "subscription { telemetry }", Value::Null).unwrap();
+        assert_eq!(publisher.subscriber_count(), 1);
+
+        drop(rx);
+        publisher.publish("telemetry", &telemetry());
+        assert_eq!(publisher.subscriber_count(), 0);
+    }
+
+    #[test]
+    fn websocket_protocol() {
+        let publisher = Publisher::new(&[("telemetry", TELEMETRY_FIELDS)]);
+        let (tx, rx) = mpsc::unbounded();
+        let mut active = HashMap::new();
+        let mut send = |message: Value| {
+            handle_message(
+                &publisher,
+                &tx,
+                &mut active,
+                &Message::text(message.to_string()),
+            )
+        };
+
+        send(json!({ "type": "connection_init" }));
+        send(json!({
+            "id": "a",
+            "type": "start",
+            "payload": { "query": "subscription { telemetry { voltage } }" }
+        }));
+        send(json!({
+            "id": "b",
+            "type": "start",
+            "payload": { "query": "subscription { power }" }
+        }));
+        publisher.publish("telemetry", &telemetry());
+        send(json!({ "id": "a", "type": "stop" }));
+        publisher.publish("telemetry", &telemetry());
+        assert_eq!(publisher.subscriber_count(), 0);
+
+        drop(tx);
+        let messages: Vec<Value> = rx
+            .wait()
+            .map(|message| serde_json::from_str(message.unwrap().to_str().unwrap()).unwrap())
+            .collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                json!({ "type": "connection_ack" }),
+                json!({
+                    "type": "error",
+                    "id": "b",
+                    "payload": { "message": "Unknown subscription field 'power'" }
+                }),
+                json!({
+                    "type": "data",
+                    "id": "a",
+                    "payload": { "data": { "telemetry": { "voltage": 8.2 } } }
+                }),
+                json!({ "type": "complete", "id": "a" }),
+            ]
+        );
+    }
+}
@@ -0,0 +1,516 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use crate::subscriptions::{self, Publisher};
+use juniper::http::GraphQLRequest;
+use log::{debug, warn};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// First byte of every fragment of a UDP response.
+/// A complete JSON or CBOR response never starts with this value.
+pub const FRAGMENT_MARKER: u8 = 0xFF;
+
+/// Default maximum size of a UDP response datagram, used if the service's config doesn't
+/// have a `udp_max_payload` value
+pub(crate) const DEFAULT_MAX_PAYLOAD: usize = 1024;
+
+// Marker, response ID, fragment index (u16), and fragment count (u16)
+const HEADER_LEN: usize = 6;
+// Largest request datagram which will be accepted
+const MAX_REQUEST: usize = 65_507;
+
+// Source of the IDs in fragment headers. Shared by responses and subscription events,
+// which may be sent from different threads
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Encoding used for a request and its response
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Encoding {
+    Json,
+    Cbor,
+}
+
+/// Executes a GraphQL request against the service's schema, returning the encoded response
+pub(crate) type Handler =
+    Arc<dyn Fn(&GraphQLRequest, Encoding, SocketAddr) -> Vec<u8> + Send + Sync>;
+
+// Figure out the encoding of a request datagram and decode it
+pub(crate) fn decode_request(data: &[u8]) -> Result<(Value, Encoding), (String, Encoding)> {
+    match data.first() {
+        // CBOR maps start with 0xA0-0xBF, which can't be the start of a UTF-8 string
+        Some(0xA0..=0xBF) => serde_cbor::from_slice(data)
+            .map(|request| (request, Encoding::Cbor))
+            .map_err(|err| {
+                (
+                    format!("Failed to parse CBOR request: {}", err),
+                    Encoding::Cbor,
+                )
+            }),
+        Some(_) => {
+            let text = ::std::str::from_utf8(data)
+                .map_err(|err| {
+                    (
+                        format!("Request is not valid UTF-8: {}", err),
+                        Encoding::Json,
+                    )
+                })?
+                .trim();
+
+            // A JSON request object also starts with a '{', so try parsing it as one
+            // before falling back to treating the whole message as a query
+            let request = match serde_json::from_str(text) {
+                Ok(Value::Object(request)) => Value::Object(request),
+                _ => json!({ "query": text }),
+            };
+            Ok((request, Encoding::Json))
+        }
+        None => Err(("Empty request".to_owned(), Encoding::Json)),
+    }
+}
+
+/// Encode a response using the same encoding as its request
+pub(crate) fn encode<T: Serialize>(response: &T, encoding: Encoding) -> Vec<u8> {
+    let result = match encoding {
+        Encoding::Json => serde_json::to_vec(response).map_err(|err| err.to_string()),
+        Encoding::Cbor => serde_cbor::to_vec(response).map_err(|err| err.to_string()),
+    };
+
+    result.unwrap_or_else(|err| {
+        error_response(&format!("Failed to encode response: {}", err), encoding)
+    })
+}
+
+// Build a GraphQL error response for a request which couldn't be executed
+pub(crate) fn error_response(message: &str, encoding: Encoding) -> Vec<u8> {
+    let response = json!({ "errors": [{ "message": message }] });
+    match encoding {
+        Encoding::Json => serde_json::to_vec(&response).unwrap_or_default(),
+        Encoding::Cbor => serde_cbor::to_vec(&response).unwrap_or_default(),
+    }
+}
+
+// Execute a decoded request
+fn execute(handler: &Handler, request: Value, encoding: Encoding, peer: SocketAddr) -> Vec<u8> {
+    match serde_json::from_value::<GraphQLRequest>(request) {
+        Ok(request) => handler(&request, encoding, peer),
+        Err(err) => error_response(&format!("Invalid request: {}", err), encoding),
+    }
+}
+
+/// Split a response into datagrams no larger than `max_payload`
+pub(crate) fn fragment(message: &[u8], id: u8, max_payload: usize) -> Result<Vec<Vec<u8>>, String> {
+    if message.len() <= max_payload {
+        return Ok(vec![message.to_vec()]);
+    }
+
+    let chunk_size = max_payload.saturating_sub(HEADER_LEN).max(1);
+    let count = (message.len() + chunk_size - 1) / chunk_size;
+    if count > usize::from(u16::MAX) {
+        return Err(format!(
+            "Response is too large to send ({} bytes)",
+            message.len()
+        ));
+    }
+
+    Ok(message
+        .chunks(chunk_size)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let mut datagram = Vec::with_capacity(HEADER_LEN + chunk.len());
+            datagram.extend_from_slice(&[
+                FRAGMENT_MARKER,
+                id,
+                (index >> 8) as u8,
+                index as u8,
+                (count >> 8) as u8,
+                count as u8,
+            ]);
+            datagram.extend_from_slice(chunk);
+            datagram
+        })
+        .collect())
+}
+
+/// Send an encoded message, splitting it into fragments if needed
+pub(crate) fn send(
+    socket: &UdpSocket,
+    peer: SocketAddr,
+    message: &[u8],
+    encoding: Encoding,
+    max_payload: usize,
+) {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed) as u8;
+    let datagrams = fragment(message, id, max_payload).unwrap_or_else(|err| {
+        warn!("{}", err);
+        vec![error_response(&err, encoding)]
+    });
+
+    for datagram in datagrams {
+        if let Err(err) = socket.send_to(&datagram, peer) {
+            warn!("Failed to send UDP message to {}: {}", peer, err);
+            break;
+        }
+    }
+}
+
+/// Receive requests on the given socket until it fails. Requests are handled one at a time.
+/// Subscription requests are handed to the publisher, if there is one.
+pub(crate) fn serve(
+    socket: UdpSocket,
+    handler: Handler,
+    max_payload: usize,
+    publisher: Option<Publisher>,
+) {
+    let mut buf = vec![0; MAX_REQUEST];
+
+    // Subscription events are sent from the publishing thread, so they need their own handle
+    let events = publisher.and_then(|publisher| match socket.try_clone() {
+        Ok(events) => Some((publisher, Arc::new(events))),
+        Err(err) => {
+            warn!("Unable to enable UDP subscriptions: {}", err);
+            None
+        }
+    });
+
+    loop {
+        let (size, peer) = match socket.recv_from(&mut buf) {
+            Ok(data) => data,
+            Err(err) => {
+                warn!("Failed to receive UDP request: {}", err);
+                continue;
+            }
+        };
+
+        let (response, encoding) = match decode_request(&buf[0..size]) {
+            Ok((request, encoding)) => match events {
+                Some((ref publisher, ref events)) if subscriptions::is_subscription(&request) => {
+                    // Successful subscriptions aren't acknowledged. The events themselves
+                    // are the response
+                    match publisher.subscribe_udp(
+                        &request,
+                        events.clone(),
+                        peer,
+                        encoding,
+                        max_payload,
+                    ) {
+                        Ok(()) => continue,
+                        Err(err) => (error_response(&err, encoding), encoding),
+                    }
+                }
+                _ => (execute(&handler, request, encoding, peer), encoding),
+            },
+            Err((message, encoding)) => {
+                debug!("Rejected UDP request: {}", message);
+                (error_response(&message, encoding), encoding)
+            }
+        };
+
+        send(&socket, peer, &response, encoding, max_payload);
+    }
+}
+
+/// Rebuilds UDP responses which were split into fragments
+///
+/// ### Examples
+///
+/// ```rust,no_run
+/// use kubos_service::Reassembler;
+/// use std::net::UdpSocket;
+///
+/// let socket = UdpSocket::bind("0.0.0.0:0").unwrap();
+/// socket.send_to(b"{ ping }", "127.0.0.1:8090").unwrap();
+///
+/// let mut reassembler = Reassembler::new();
+/// let mut buf = [0; 2048];
+/// let response = loop {
+///     let (size, _) = socket.recv_from(&mut buf).unwrap();
+///     if let Some(response) = reassembler.push(&buf[0..size]) {
+///         break response;
+///     }
+/// };
+/// ```
+#[derive(Debug, Default)]
+pub struct Reassembler {
+    id: Option<u8>,
+    fragments: Vec<Option<Vec<u8>>>,
+    received: usize,
+}
+
+impl Reassembler {
+    /// Create a new, empty reassembler
+    pub fn new() -> Self {
+        Reassembler::default()
+    }
+
+    /// Add a received datagram. Returns the complete response once all of its fragments
+    /// have been received. Datagrams which aren't fragments are returned immediately.
+    ///
+    /// A fragment belonging to a different response than the one currently being
+    /// rebuilt (ex. because some fragments were lost) discards the partial response.
+    pub fn push(&mut self, datagram: &[u8]) -> Option<Vec<u8>> {
+        if datagram.first() != Some(&FRAGMENT_MARKER) || datagram.len() < HEADER_LEN {
+            return Some(datagram.to_vec());
+        }
+
+        let id = datagram[1];
+        let index = usize::from(datagram[2]) << 8 | usize::from(datagram[3]);
+        let count = usize::from(datagram[4]) << 8 | usize::from(datagram[5]);
+        if index >= count {
+            return None;
+        }
+
+        // A new response is starting
+        if self.id != Some(id) || count != self.fragments.len() {
+            self.id = Some(id);
+            self.fragments = vec![None; count];
+            self.received = 0;
+        }
+
+        if self.fragments[index].is_none() {
+            self.received += 1;
+        }
+        self.fragments[index] = Some(datagram[HEADER_LEN..].to_vec());
+
+        if self.received < count {
+            return None;
+        }
+
+        let response = self
+            .fragments
+            .drain(..)
+            .flat_map(|fragment| fragment.unwrap_or_default())
+            .collect();
+        self.id = None;
+        self.received = 0;
+        Some(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Context, Service};
+    use juniper::graphql_object;
+    use serde_derive::{Deserialize, Serialize};
+    use std::thread;
+
+    struct Query;
+
+    graphql_object!(Query: Context<()> as "Query" |&self| {
+        field ping() -> String {
+            "pong".to_owned()
+        }
+
+        field items(count: i32) -> Vec<String> {
+            (0..count).map(|num| format!("item {}", num)).collect()
+        }
+    });
+
+    struct Mutation;
+
+    graphql_object!(Mutation: Context<()> as "Mutation" |&self| {
+        field noop() -> bool {
+            true
+        }
+    });
+
+    fn service() -> Service {
+        Service::new(Default::default(), (), Query, Mutation)
+    }
+
+    // Handle a single request datagram, returning the encoded response
+    fn process(handler: &Handler, data: &[u8]) -> (Vec<u8>, Encoding) {
+        match decode_request(data) {
+            Ok((request, encoding)) => {
+                let peer = "127.0.0.1:0".parse().unwrap();
+                (execute(handler, request, encoding, peer), encoding)
+            }
+            Err((message, encoding)) => {
+                debug!("Rejected UDP request: {}", message);
+                (error_response(&message, encoding), encoding)
+            }
+        }
+    }
+
+    // Echo the request's query back as the response
+    fn echo_handler() -> Handler {
+        Arc::new(|request, encoding, _| {
+            let request = serde_json::to_value(request).unwrap();
+            encode(&json!({ "data": { "query": request["query"] } }), encoding)
+        })
+    }
+
+    #[derive(Deserialize, Serialize)]
+    struct CborRequest {
+        query: String,
+        variables: serde_json::Value,
+    }
+
+    #[test]
+    fn plain_query() {
+        let (response, encoding) = process(&echo_handler(), b"{ ping }\n");
+        assert_eq!(encoding, Encoding::Json);
+        let response: serde_json::Value = serde_json::from_slice(&response).unwrap();
+        assert_eq!(response, json!({ "data": { "query": "{ ping }" } }));
+    }
+
+    #[test]
+    fn json_request() {
+        let (response, _) = process(&echo_handler(), br#"{"query": "{ ping }"}"#);
+        let response: serde_json::Value = serde_json::from_slice(&response).unwrap();
+        assert_eq!(response, json!({ "data": { "query": "{ ping }" } }));
+    }
+
+    #[test]
+    fn cbor_request() {
+        let request = serde_cbor::to_vec(&CborRequest {
+            query: "{ ping }".to_owned(),
+            variables: json!({}),
+        })
+        .unwrap();
+
+        let (response, encoding) = process(&echo_handler(), &request);
+        assert_eq!(encoding, Encoding::Cbor);
+
+        let response: serde_json::Value = serde_cbor::from_slice(&response).unwrap();
+        assert_eq!(response, json!({ "data": { "query": "{ ping }" } }));
+    }
+
+    #[test]
+    fn bad_request() {
+        let (response, encoding) = process(&echo_handler(), &[0xA1, 0x00]);
+        assert_eq!(encoding, Encoding::Cbor);
+        let response: serde_json::Value = serde_cbor::from_slice(&response).unwrap();
+        assert!(response["errors"][0]["message"].is_string());
+
+        let (response, _) = process(&echo_handler(), &[]);
+        let response: serde_json::Value = serde_json::from_slice(&response).unwrap();
+        assert_eq!(response["errors"][0]["message"], "Empty request");
+    }
+
+    #[test]
+    fn fragment_small() {
+        let datagrams = fragment(b"{\"data\":null}", 1, 100).unwrap();
+        assert_eq!(datagrams, vec![b"{\"data\":null}".to_vec()]);
+    }
+
+    #[test]
+    fn fragment_reassemble() {
+        let message: Vec<u8> = (0..250).map(|num| num as u8).collect();
+        let datagrams = fragment(&message, 1, 25).unwrap();
+
+        assert_eq!(datagrams.len(), 14);
+        assert!(datagrams.iter().all(|datagram| datagram.len() <= 25));
+        assert_eq!(
+            &datagrams[1][0..HEADER_LEN],
+            &[FRAGMENT_MARKER, 1, 0, 1, 0, 14]
+        );
+
+        // Fragments may arrive out of order
+        let mut reassembler = Reassembler::new();
+        for datagram in datagrams.iter().rev().skip(1) {
+            assert_eq!(reassembler.push(datagram), None);
+        }
+        assert_eq!(reassembler.push(&datagrams[13]), Some(message.clone()));
+
+        // A response with a lost fragment is discarded when the next response starts
+        for datagram in datagrams.iter().skip(1) {
+            reassembler.push(datagram);
+        }
+        let datagrams = fragment(&message, 2, 25).unwrap();
+        let mut result = None;
+        for datagram in datagrams.iter() {
+            result = reassembler.push(datagram);
+        }
+        assert_eq!(result, Some(message));
+    }
+
+    #[test]
+    fn service_query() {
+        let service = service();
+
+        let (response, _) = process(&service.udp, b"{ ping }");
+        let response: serde_json::Value = serde_json::from_slice(&response).unwrap();
+        assert_eq!(response, json!({ "data": { "ping": "pong" } }));
+
+        let (response, _) = process(&service.udp, b"mutation { noop }");
+        let response: serde_json::Value = serde_json::from_slice(&response).unwrap();
+        assert_eq!(response, json!({ "data": { "noop": true } }));
+    }
+
+    #[test]
+    fn service_socket() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        let handler = service().udp.clone();
+        thread::spawn(move || serve(server, handler, 64, None));
+
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let request = serde_cbor::to_vec(&CborRequest {
+            query: "query Items($count: Int!) { items(count: $count) }".to_owned(),
+            variables: json!({ "count": 20 }),
+        })
+        .unwrap();
+        client.send_to(&request, addr).unwrap();
+
+        let mut reassembler = Reassembler::new();
+        let mut buf = [0; 128];
+        let response = loop {
+            let (size, _) = client.recv_from(&mut buf).unwrap();
+            assert!(size <= 64);
+            if let Some(response) = reassembler.push(&buf[0..size]) {
+                break response;
+            }
+        };
+
+        let response: serde_json::Value = serde_cbor::from_slice(&response).unwrap();
+        let items = response["data"]["items"].as_array().unwrap();
+        assert_eq!(items.len(), 20);
+        assert_eq!(items[19], "item 19");
+    }
+
+    #[test]
+    fn service_subscription() {
+        let publisher = Publisher::new(&[("telemetry", &["voltage"])]);
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        let handler = service().udp.clone();
+        let server_publisher = publisher.clone();
+        thread::spawn(move || serve(server, handler, 64, Some(server_publisher)));
+
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client
+            .send_to(b"subscription { telemetry { voltage } }", addr)
+            .unwrap();
+        while publisher.subscriber_count() == 0 {
+            thread::sleep(::std::time::Duration::from_millis(1));
+        }
+
+        publisher.publish("telemetry", &json!({ "voltage": 8.2, "current": 0.5 }));
+
+        let mut buf = [0; 64];
+        let (size, _) = client.recv_from(&mut buf).unwrap();
+        let event: serde_json::Value = serde_json::from_slice(&buf[0..size]).unwrap();
+        assert_eq!(
+            event,
+            json!({ "data": { "telemetry": { "voltage": 8.2 } } })
+        );
+    }
+}
@@ -24,6 +24,9 @@
 //! The service can be configured in the `/home/system/etc/config.toml` with the following fields:
 //!
 //! ```toml
+//! [mai400-service]
+//! status_file = "/home/system/kubos/mai400-status.json"
+//!
 //! [mai400-service.addr]
 //! ip = "127.0.0.1"
 //! port = 8082
@@ -31,6 +34,9 @@
 //!
 //! Where `ip` specifies the service's IP address, and `port` specifies the port which UDP requests should be sent to.
 //!
+//! `status_file` is optional. If given, the last mutation and any unread errors are saved to this
+//! file, so that they survive a service restart.
+//!
 //! # Starting the Service
 //!
 //! The service should be started automatically by its init script, but may also be started manually:
@@ -74,6 +80,20 @@
 //! }
 //! ```
 //!
+//! ## Error Log
+//!
+//! Get the most recent errors, including ones which have already been read
+//!
+//! ```json
+//! {
+//!     errorLog {
+//!         timestamp: Float,
+//!         severity: Severity,
+//!         message: String
+//!     }
+//! }
+//! ```
+//!
 //! ## Power Status
 //!
 //! Get the current power state and uptime of the system
@@ -91,9 +111,48 @@
 //!
 //! Get the current configuration of the system
 //!
+//! Note: The configuration values are reported by the rotating telemetry variables,
+//! so the full set is updated every six seconds
+//!
 //! ```json
 //! {
-//!     config: "Not Implemented"
+//!     config {
+//!         gains {
+//!             bdot: [f64; 3],
+//!             proportional: [f64; 3],
+//!             derivative: [f64; 3],
+//!             unload: [f64; 3],
+//!             dipole: [f64; 3],
+//!             qbXFilter: f64,
+//!             qbXDipole: f64
+//!         },
+//!         calibration {
+//!             cssBias: [i32; 6],
+//!             cssGain: [f64; 6],
+//!             magBias: [f64; 3],
+//!             magGain: [f64; 3],
+//!             wheelSpeedBias: [i32; 3]
+//!         },
+//!         orbit {
+//!             propMode: i32,
+//!             epoch: i32,
+//!             trueAnomalyEpoch: f64,
+//!             epochNext: i32,
+//!             posEciEpoch: [f64; 3],
+//!             velEciEpoch: [f64; 3],
+//!             keplerElem {...}
+//!         },
+//!         limits {
+//!             sunMagAlignThresh: f64,
+//!             unloadAngThresh: f64,
+//!             qSat: f64,
+//!             rwaTrqMax: f64,
+//!             qbXWheelSpeed: i32
+//!         },
+//!         majorVersion: i32,
+//!         minorVersion: i32,
+//!         maiSn: i32
+//!     }
 //! }
 //! ```
 //!
@@ -177,7 +236,7 @@
 //!                 kUnload: [f64; 3],
 //!                 cssBias: [i32; 6],
 //!                 magBias: [i32; 3],
-//!                 rwsVolt: i32,
+//!                 rwsVolt: f64,
 //!                 rwsPress: i32,
 //!                 attDetMode: i32,
 //!                 rwsResetCntr: [i32; 3],
@@ -205,8 +264,8 @@
 //!                 unloadAngThresh: f64,
 //!                 qSat: f64,
 //!                 rwaTrqMax: f64,
-//!                 rwsMotorCurrent: [i32; 3],
-//!                 rwsMotorTemp: i32,
+//!                 rwsMotorCurrent: [f64; 3],
+//!                 rwsMotorTemp: f64,
 //!             }
 //!         }
 //!     }
@@ -418,7 +477,7 @@ mod tests;
 use crate::model::{ReadData, Subsystem};
 pub use crate::objects::*;
 use crate::schema::{MutationRoot, QueryRoot};
-use kubos_service::{Config, Service};
+use kubos_service::{CommandStatus, Config, Service};
 use mai400_api::MAIResult;
 use std::sync::Arc;
 use syslog::Facility;
@@ -431,12 +490,16 @@ fn main() -> MAIResult<()> {
     )
     .unwrap();
 
+    let config = Config::new("mai400-service");
+    let status = CommandStatus::from_config(&config, AckCommand::None);
+
     Service::new(
-        Config::new("mai400-service"),
-        Subsystem::new("/dev/ttyS5", Arc::new(ReadData::new()))?,
+        config,
+        Subsystem::new("/dev/ttyS5", Arc::new(ReadData::new()), status)?,
         QueryRoot,
         MutationRoot,
     )
+    .version(env!("CARGO_PKG_VERSION"))
     .start();
 
     Ok(())
@@ -15,12 +15,12 @@
 //
 
 use failure::{bail, Error};
-use kubos_service::{process_errors, push_err, run};
+use kubos_service::{process_errors, run, CommandStatus, Severity};
 use log::{error, info};
 use mai400_api::*;
 use std::sync::mpsc::channel;
 use std::sync::mpsc::{Receiver, Sender, TryRecvError};
-use std::sync::{Arc, Mutex, RwLock};
+use std::sync::{Arc, Mutex};
 use std::thread::{sleep, spawn};
 use std::time::Duration;
 
@@ -130,14 +130,17 @@ pub fn read_thread(mai: MAI400, data: Arc<ReadData>, sender: Sender<String>) {
 #[derive(Clone)]
 pub struct Subsystem {
     pub mai: MAI400,
-    pub last_cmd: Arc<RwLock<AckCommand>>,
-    pub errors: Arc<RwLock<Vec<String>>>,
+    pub status: CommandStatus<AckCommand>,
     pub persistent: Arc<ReadData>,
     pub receiver: Arc<Mutex<Receiver<String>>>,
 }
 
 impl Subsystem {
-    pub fn new(bus: &'static str, data: Arc<ReadData>) -> MAIResult<Subsystem> {
+    pub fn new(
+        bus: &'static str,
+        data: Arc<ReadData>,
+        status: CommandStatus<AckCommand>,
+    ) -> MAIResult<Subsystem> {
         let mai = MAI400::new(bus)?;
 
         let data_ref = data.clone();
@@ -151,8 +154,7 @@ impl Subsystem {
 
         Ok(Subsystem {
             mai,
-            last_cmd: Arc::new(RwLock::new(AckCommand::None)),
-            errors: Arc::new(RwLock::new(vec![])),
+            status,
             persistent: data.clone(),
             receiver: Arc::new(Mutex::new(receiver)),
         })
@@ -162,19 +164,20 @@ impl Subsystem {
         let receiver = match self.receiver.lock() {
             Ok(v) => v,
             Err(err) => {
-                push_err!(
-                    self.errors,
-                    format!("Failed to get read thread receiver mutex: {:?}", err)
+                self.status.push_error(
+                    Severity::Error,
+                    format!("Failed to get read thread receiver mutex: {:?}", err),
                 );
                 return;
             }
         };
         match receiver.try_recv() {
+            // The read thread only reports errors which require a service restart
             Ok(msg) => {
-                push_err!(self.errors, msg);
+                self.status.push_error(Severity::Critical, msg);
 
                 while let Ok(err) = receiver.try_recv() {
-                    push_err!(self.errors, err);
+                    self.status.push_error(Severity::Critical, err);
                 }
             }
             Err(err) => match err {
@@ -182,9 +185,9 @@ impl Subsystem {
                 TryRecvError::Empty => {}
                 // The sky is falling. The read thread panicked somehow.
                 TryRecvError::Disconnected => {
-                    push_err!(
-                        self.errors,
-                        "Read thread panicked. Service restart required.".to_owned()
+                    self.status.push_error(
+                        Severity::Critical,
+                        "Read thread panicked. Service restart required.".to_owned(),
                     );
                 }
             },
@@ -246,6 +249,11 @@ impl Subsystem {
         Ok(Mode::from(raw))
     }
 
+    pub fn get_config(&self) -> Result<Configuration, Error> {
+        let rotating = self.persistent.rotating.lock().unwrap();
+        Ok(Configuration(rotating.config()))
+    }
+
     pub fn get_spin(&self) -> Result<Spin, Error> {
         let rotating = self.persistent.rotating.lock().unwrap();
         Ok(Spin {
@@ -268,9 +276,9 @@ impl Subsystem {
         let (success, errors) = if new_ctr != old_ctr {
             (true, "".to_owned())
         } else {
-            push_err!(
-                self.errors,
-                "Noop: Unable to communicate with MAI400".to_owned()
+            self.status.push_error(
+                Severity::Error,
+                "Noop: Unable to communicate with MAI400".to_owned(),
             );
             (false, "Unable to communicate with MAI400".to_owned())
         };
@@ -281,7 +289,7 @@ impl Subsystem {
     pub fn control_power(&self, state: PowerState) -> Result<ControlPowerResponse, Error> {
         match state {
             PowerState::Reset => {
-                let result = run!(self.mai.reset(), self.errors);
+                let result = run!(self.mai.reset(), self.status);
 
                 Ok(ControlPowerResponse {
                     power: state,
@@ -293,7 +301,10 @@ impl Subsystem {
                 })
             }
             _ => {
-                push_err!(self.errors, "controlPower: Invalid power state".to_owned());
+                self.status.push_error(
+                    Severity::Error,
+                    "controlPower: Invalid power state".to_owned(),
+                );
 
                 Ok(ControlPowerResponse {
                     power: state,
@@ -313,7 +324,7 @@ impl Subsystem {
             .map(|chunk| u8::from_str_radix(::std::str::from_utf8(chunk).unwrap(), 16).unwrap())
             .collect();
 
-        let result = run!(self.mai.passthrough(tx.as_slice()), self.errors);
+        let result = run!(self.mai.passthrough(tx.as_slice()), self.status);
 
         Ok(GenericResponse {
             success: result.is_ok(),
@@ -339,7 +350,7 @@ impl Subsystem {
                     qbi_cmd[3] as i16,
                 ],
             ),
-            self.errors
+            self.status
         );
 
         Ok(GenericResponse {
@@ -359,7 +370,7 @@ impl Subsystem {
     ) -> Result<GenericResponse, Error> {
         let result = run!(
             self.mai.set_mode_sun(mode, sun_angle_enable, sun_rot_angle),
-            self.errors
+            self.status
         );
 
         Ok(GenericResponse {
@@ -380,7 +391,7 @@ impl Subsystem {
         let mut errors = "".to_owned();
 
         if let Some(time) = gps_time {
-            let result = run!(self.mai.set_gps_time(time as u32), self.errors);
+            let result = run!(self.mai.set_gps_time(time as u32), self.status);
             success &= result.is_ok();
             if let Err(err) = result {
                 errors.push_str(&format!("update(gpsTime): {}", err));
@@ -410,7 +421,7 @@ impl Subsystem {
                     ],
                     params.time_epoch as u32,
                 ),
-                self.errors
+                self.status
             );
             success &= result.is_ok();
             if let Err(err) = result {
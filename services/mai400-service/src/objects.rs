@@ -15,6 +15,7 @@
 //
 
 use mai400_api::*;
+use serde_derive::{Deserialize, Serialize};
 
 /// Common response fields structure for requests
 /// which don't return any specific data
@@ -29,7 +30,7 @@ pub struct GenericResponse {
 /// Return field for 'ack' query
 ///
 /// Indicates last mutation executed by the service
-#[derive(GraphQLEnum, Clone, Copy)]
+#[derive(GraphQLEnum, Clone, Copy, Deserialize, Serialize)]
 pub enum AckCommand {
     /// No mutations have been executed
     None,
@@ -573,8 +574,8 @@ graphql_object!(Rotating: () where Scalar = <S> |&self| {
         self.0.mag_bias.iter().map(|&elem| i32::from(elem)).collect()
     }
 
-    field rws_volt() -> i32 {
-        i32::from(self.0.rws_volt)
+    field rws_volt() -> f64 {
+        f64::from(self.0.wheels().bus_voltage)
     }
 
     field rws_press() -> i32 {
@@ -685,12 +686,12 @@ graphql_object!(Rotating: () where Scalar = <S> |&self| {
         f64::from(self.0.rwa_trq_max)
     }
 
-    field rws_motor_current() -> Vec<i32> {
-        self.0.rws_motor_current.iter().map(|&elem| i32::from(elem)).collect()
+    field rws_motor_current() -> Vec<f64> {
+        self.0.wheels().motor_current.iter().map(|&elem| f64::from(elem)).collect()
     }
 
-    field rws_motor_temp() -> i32 {
-        i32::from(self.0.rws_motor_temp)
+    field rws_motor_temp() -> f64 {
+        f64::from(self.0.wheels().motor_temp)
     }
 
 });
@@ -724,3 +725,159 @@ graphql_object!(Kepler: () where Scalar = <S> |&self| {
         f64::from(self.0.true_anomoly)
     }
 });
+
+/// ADACS configuration values.
+/// These are assembled from the rotating telemetry values, so the full set
+/// is updated every six seconds.
+#[derive(Debug, Default, PartialEq)]
+pub struct Configuration(pub ADACSConfig);
+
+graphql_object!(Configuration: () where Scalar = <S> |&self| {
+    field gains() -> Gains {
+        Gains(self.0.gains.clone())
+    }
+
+    field calibration() -> Calibration {
+        Calibration(self.0.calibration.clone())
+    }
+
+    field orbit() -> Orbit {
+        Orbit(self.0.orbit.clone())
+    }
+
+    field limits() -> Limits {
+        Limits(self.0.limits.clone())
+    }
+
+    field major_version() -> i32 {
+        i32::from(self.0.major_version)
+    }
+
+    field minor_version() -> i32 {
+        i32::from(self.0.minor_version)
+    }
+
+    field mai_sn() -> i32 {
+        i32::from(self.0.mai_sn)
+    }
+});
+
+/// Attitude control gains
+#[derive(Debug, Default, PartialEq)]
+pub struct Gains(pub ControlGains);
+
+graphql_object!(Gains: () where Scalar = <S> |&self| {
+    field bdot() -> Vec<f64> {
+        self.0.bdot.iter().map(|&elem| f64::from(elem)).collect()
+    }
+
+    field proportional() -> Vec<f64> {
+        self.0.proportional.iter().map(|&elem| f64::from(elem)).collect()
+    }
+
+    field derivative() -> Vec<f64> {
+        self.0.derivative.iter().map(|&elem| f64::from(elem)).collect()
+    }
+
+    field unload() -> Vec<f64> {
+        self.0.unload.iter().map(|&elem| f64::from(elem)).collect()
+    }
+
+    field dipole() -> Vec<f64> {
+        self.0.dipole.iter().map(|&elem| f64::from(elem)).collect()
+    }
+
+    field qb_x_filter() -> f64 {
+        f64::from(self.0.qb_x_filter)
+    }
+
+    field qb_x_dipole() -> f64 {
+        f64::from(self.0.qb_x_dipole)
+    }
+});
+
+/// Sensor calibration parameters
+#[derive(Debug, Default, PartialEq)]
+pub struct Calibration(pub SensorCalibration);
+
+graphql_object!(Calibration: () where Scalar = <S> |&self| {
+    field css_bias() -> Vec<i32> {
+        self.0.css_bias.iter().map(|&elem| i32::from(elem)).collect()
+    }
+
+    field css_gain() -> Vec<f64> {
+        self.0.css_gain.iter().map(|&elem| f64::from(elem)).collect()
+    }
+
+    field mag_bias() -> Vec<f64> {
+        self.0.mag_bias.iter().map(|&elem| f64::from(elem)).collect()
+    }
+
+    field mag_gain() -> Vec<f64> {
+        self.0.mag_gain.iter().map(|&elem| f64::from(elem)).collect()
+    }
+
+    field wheel_speed_bias() -> Vec<i32> {
+        self.0.wheel_speed_bias.iter().map(|&elem| i32::from(elem)).collect()
+    }
+});
+
+/// Orbit propagation parameters
+#[derive(Debug, Default, PartialEq)]
+pub struct Orbit(pub OrbitParams);
+
+graphql_object!(Orbit: () where Scalar = <S> |&self| {
+    field prop_mode() -> i32 {
+        i32::from(self.0.prop_mode)
+    }
+
+    field epoch() -> i32 {
+        self.0.epoch as i32
+    }
+
+    field true_anomaly_epoch() -> f64 {
+        f64::from(self.0.true_anomaly_epoch)
+    }
+
+    field epoch_next() -> i32 {
+        self.0.epoch_next as i32
+    }
+
+    field pos_eci_epoch() -> Vec<f64> {
+        self.0.pos_eci_epoch.iter().map(|&elem| f64::from(elem)).collect()
+    }
+
+    field vel_eci_epoch() -> Vec<f64> {
+        self.0.vel_eci_epoch.iter().map(|&elem| f64::from(elem)).collect()
+    }
+
+    field kepler_elem() -> Kepler {
+        Kepler(self.0.kepler_elem.clone())
+    }
+});
+
+/// Attitude control limits and thresholds
+#[derive(Debug, Default, PartialEq)]
+pub struct Limits(pub ControlLimits);
+
+graphql_object!(Limits: () where Scalar = <S> |&self| {
+    field sun_mag_align_thresh() -> f64 {
+        f64::from(self.0.sun_mag_align_thresh)
+    }
+
+    field unload_ang_thresh() -> f64 {
+        f64::from(self.0.unload_ang_thresh)
+    }
+
+    field q_sat() -> f64 {
+        f64::from(self.0.q_sat)
+    }
+
+    field rwa_trq_max() -> f64 {
+        f64::from(self.0.rwa_trq_max)
+    }
+
+    field qb_x_wheel_speed() -> i32 {
+        i32::from(self.0.qb_x_wheel_speed)
+    }
+});
@@ -17,7 +17,7 @@
 use crate::model::*;
 use crate::objects::*;
 use juniper::FieldResult;
-use kubos_service;
+use kubos_service::ErrorRecord;
 
 type Context = kubos_service::Context<Subsystem>;
 
@@ -46,8 +46,7 @@ graphql_object!(QueryRoot: Context as "Query" |&self| {
     // }
     field ack(&executor) -> FieldResult<AckCommand>
     {
-        let last_cmd = executor.context().subsystem().last_cmd.read()?;
-        Ok(*last_cmd)
+        Ok(executor.context().subsystem().status.ack())
     }
 
     // Get all errors encountered since the last time this field was queried
@@ -59,15 +58,23 @@ graphql_object!(QueryRoot: Context as "Query" |&self| {
     {
         executor.context().subsystem().get_read_health();
 
-        match executor.context().subsystem().errors.write() {
-            Ok(mut master_vec) => {
-                let current = master_vec.clone();
-                master_vec.clear();
-                master_vec.shrink_to_fit();
-                Ok(current)
-            },
-            _ => Ok(vec!["Error: Failed to borrow master errors vector".to_owned()])
-        }
+        Ok(executor.context().subsystem().status.take_error_messages())
+    }
+
+    // Get the most recent errors, including ones which have already been read
+    //
+    // {
+    //     errorLog {
+    //         timestamp: Float,
+    //         severity: Severity,
+    //         message: String
+    //     }
+    // }
+    field error_log(&executor) -> FieldResult<Vec<ErrorRecord>>
+    {
+        executor.context().subsystem().get_read_health();
+
+        Ok(executor.context().subsystem().status.error_log())
     }
 
     // Get the current power state and uptime of the system
@@ -85,12 +92,51 @@ graphql_object!(QueryRoot: Context as "Query" |&self| {
 
     // Get the current configuration of the system
     //
+    // Note: The configuration values are reported by the rotating telemetry variables,
+    // so the full set is updated every six seconds
+    //
     // {
-    //     config: "Not Implemented"
+    //     config {
+    //         gains {
+    //             bdot: [f64; 3],
+    //             proportional: [f64; 3],
+    //             derivative: [f64; 3],
+    //             unload: [f64; 3],
+    //             dipole: [f64; 3],
+    //             qbXFilter: f64,
+    //             qbXDipole: f64
+    //         },
+    //         calibration {
+    //             cssBias: [i32; 6],
+    //             cssGain: [f64; 6],
+    //             magBias: [f64; 3],
+    //             magGain: [f64; 3],
+    //             wheelSpeedBias: [i32; 3]
+    //         },
+    //         orbit {
+    //             propMode: i32,
+    //             epoch: i32,
+    //             trueAnomalyEpoch: f64,
+    //             epochNext: i32,
+    //             posEciEpoch: [f64; 3],
+    //             velEciEpoch: [f64; 3],
+    //             keplerElem {...}
+    //         },
+    //         limits {
+    //             sunMagAlignThresh: f64,
+    //             unloadAngThresh: f64,
+    //             qSat: f64,
+    //             rwaTrqMax: f64,
+    //             qbXWheelSpeed: i32
+    //         },
+    //         majorVersion: i32,
+    //         minorVersion: i32,
+    //         maiSn: i32
+    //     }
     // }
-    field config(&executor) -> FieldResult<String>
+    field config(&executor) -> FieldResult<Configuration>
     {
-        Ok(String::from("Not Implemented"))
+        Ok(executor.context().subsystem().get_config()?)
     }
 
     // Get current telemetry information for the system
@@ -170,7 +216,7 @@ graphql_object!(QueryRoot: Context as "Query" |&self| {
     //                 kUnload: [f64; 3],
     //                 cssBias: [i32; 6],
     //                 magBias: [i32; 3],
-    //                 rwsVolt: i32,
+    //                 rwsVolt: f64,
     //                 rwsPress: i32,
     //                 attDetMode: i32,
     //                 rwsResetCntr: [i32; 3],
@@ -198,8 +244,8 @@ graphql_object!(QueryRoot: Context as "Query" |&self| {
     //                 unloadAngThresh: f64,
     //                 qSat: f64,
     //                 rwaTrqMax: f64,
-    //                 rwsMotorCurrent: [i32; 3],
-    //                 rwsMotorTemp: i32,
+    //                 rwsMotorCurrent: [f64; 3],
+    //                 rwsMotorTemp: f64,
     //             }
     //         }
     //     }
@@ -274,10 +320,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field errors(&executor) -> FieldResult<Vec<String>>
     {
-        match executor.context().subsystem().errors.read() {
-            Ok(master_vec) => Ok(master_vec.clone()),
-            _ => Ok(vec!["Error: Failed to borrow master errors vector".to_owned()])
-        }
+        Ok(executor.context().subsystem().status.unread_error_messages())
     }
 
     // Execute a trivial command against the system
@@ -290,8 +333,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field noop(&executor) -> FieldResult<GenericResponse>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::Noop;
+        executor.context().subsystem().status.set_ack(AckCommand::Noop);
         Ok(executor.context().subsystem().noop()?)
     }
 
@@ -309,8 +351,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field control_power(&executor, state: PowerState) -> FieldResult<ControlPowerResponse>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::ControlPower;
+        executor.context().subsystem().status.set_ack(AckCommand::ControlPower);
         Ok(executor.context().subsystem().control_power(state)?)
     }
 
@@ -321,8 +362,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field configure_hardware(&executor) -> FieldResult<String>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::ConfigureHardware;
+        executor.context().subsystem().status.set_ack(AckCommand::ConfigureHardware);
         Ok(String::from("Not Implemented"))
     }
 
@@ -347,8 +387,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field test_hardware(&executor, test: TestType) -> FieldResult<TestResults>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::TestHardware;
+        executor.context().subsystem().status.set_ack(AckCommand::TestHardware);
 
         match test {
             TestType::Integration => Ok(TestResults::Integration(executor.context().subsystem()
@@ -372,8 +411,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field issue_raw_command(&executor, command: String) -> FieldResult<GenericResponse>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::IssueRawCommand;
+        executor.context().subsystem().status.set_ack(AckCommand::IssueRawCommand);
         Ok(executor.context().subsystem().passthrough(command)?)
     }
 
@@ -399,8 +437,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
         sun_angle_enable = false: bool,
         sun_rot_angle = 0.0: f64)
     -> FieldResult<GenericResponse> {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::SetMode;
+        executor.context().subsystem().status.set_ack(AckCommand::SetMode);
         match mode {
             Mode::NormalSun | Mode::LatLongSun => Ok(executor.context().subsystem().set_mode_sun(
                     mode as u8, sun_angle_enable as i16, sun_rot_angle as f32)?),
@@ -426,8 +463,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field update(&executor, gps_time: Option<i32>, rv: Option<RVInput>)
     -> FieldResult<GenericResponse> {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::Update;
+        executor.context().subsystem().status.set_ack(AckCommand::Update);
         Ok(executor.context().subsystem().update(gps_time, rv)?)
     }
 
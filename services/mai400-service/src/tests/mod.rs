@@ -33,7 +33,7 @@ macro_rules! service_new {
     ($mock:ident, $data:ident) => {{
         use crate::objects::AckCommand;
         use mai400_api::Connection;
-        use std::sync::{Arc, Mutex, RwLock};
+        use std::sync::{Arc, Mutex};
         use std::thread;
 
         let (sender, receiver) = channel();
@@ -55,8 +55,7 @@ macro_rules! service_new {
             Config::new("mai400-service"),
             Subsystem {
                 mai,
-                last_cmd: Arc::new(RwLock::new(AckCommand::None)),
-                errors: Arc::new(RwLock::new(vec![])),
+                status: CommandStatus::new(AckCommand::None),
                 persistent: $data.clone(),
                 receiver: Arc::new(Mutex::new(receiver)),
             },
@@ -71,7 +70,7 @@ macro_rules! service_new_with_read {
     ($mock:ident, $data:ident) => {{
         use crate::objects::AckCommand;
         use mai400_api::Connection;
-        use std::sync::{Arc, Mutex, RwLock};
+        use std::sync::{Arc, Mutex};
         use std::thread;
 
         let (sender, receiver) = channel();
@@ -91,8 +90,7 @@ macro_rules! service_new_with_read {
             Config::new("mai400-service"),
             Subsystem {
                 mai,
-                last_cmd: Arc::new(RwLock::new(AckCommand::None)),
-                errors: Arc::new(RwLock::new(vec![])),
+                status: CommandStatus::new(AckCommand::None),
                 persistent: $data.clone(),
                 receiver: Arc::new(Mutex::new(receiver)),
             },
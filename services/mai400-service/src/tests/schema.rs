@@ -331,6 +331,46 @@ fn query_errors_clear_after_query() {
     test!(service, query, expected);
 }
 
+#[test]
+fn query_error_log() {
+    let mock = MockStream::default();
+
+    let service = service_new!(mock);
+
+    let noop = r#"mutation {
+            noop {
+                success
+            }
+        }"#;
+
+    request!(service, noop);
+
+    let query = r#"{
+            errors
+        }"#;
+
+    request!(service, query);
+
+    // The log keeps errors which have already been read
+    let query = r#"{
+            errorLog {
+                message,
+                severity
+            }
+        }"#;
+
+    let expected = json!({
+            "errorLog": [
+                {
+                    "message": "Noop: Unable to communicate with MAI400",
+                    "severity": "ERROR"
+                }
+            ]
+    });
+
+    test!(service, query, expected);
+}
+
 #[test]
 fn get_power_on() {
     let mock = MockStream::default();
@@ -401,11 +441,87 @@ fn config() {
     let service = service_new!(mock);
 
     let query = r#"{
-            config
+            config {
+                calibration {
+                    cssBias,
+                    cssGain,
+                    magBias,
+                    magGain,
+                    wheelSpeedBias
+                },
+                gains {
+                    bdot,
+                    derivative,
+                    dipole,
+                    proportional,
+                    qbXDipole,
+                    qbXFilter,
+                    unload
+                },
+                limits {
+                    qSat,
+                    qbXWheelSpeed,
+                    rwaTrqMax,
+                    sunMagAlignThresh,
+                    unloadAngThresh
+                },
+                maiSn,
+                majorVersion,
+                minorVersion,
+                orbit {
+                    epoch,
+                    epochNext,
+                    keplerElem {
+                        semiMajorAxis
+                    },
+                    posEciEpoch,
+                    propMode,
+                    trueAnomalyEpoch,
+                    velEciEpoch
+                }
+            }
         }"#;
 
     let expected = json!({
-            "config": "Not Implemented"
+            "config": {
+                "calibration": {
+                    "cssBias": [0, 0, 0, 0, 0, 0],
+                    "cssGain": [1.0, 1.0, 1.0, 1.0, 1.0, 1.0],
+                    "magBias": [10280.0, 0.0, 0.0],
+                    "magGain": [1.0, 1.0, 1.0],
+                    "wheelSpeedBias": [16256, 16256, 16256]
+                },
+                "gains": {
+                    "bdot": [-100000.0, -100000.0, -100000.0],
+                    "derivative": [-12.100000381469727, -12.0, -4.0],
+                    "dipole": [1.0, 1.0, 1.0],
+                    "proportional": [-1.1100000143051148, -1.100000023841858, -0.25],
+                    "qbXDipole": 1.0,
+                    "qbXFilter": 1.0,
+                    "unload": [-5000000.0, -5000000.0, -5000000.0]
+                },
+                "limits": {
+                    "qSat": 0.30000001192092898,
+                    "qbXWheelSpeed": 0,
+                    "rwaTrqMax": 0.20000000298023225,
+                    "sunMagAlignThresh": 8.109610557556152,
+                    "unloadAngThresh": 0.05000000074505806
+                },
+                "maiSn": 120,
+                "majorVersion": 2,
+                "minorVersion": 24,
+                "orbit": {
+                    "epoch": 511358571,
+                    "epochNext": 1,
+                    "keplerElem": {
+                        "semiMajorAxis": 6787.47021484375
+                    },
+                    "posEciEpoch": [6787.47021484375, 0.0, 0.0],
+                    "propMode": 0,
+                    "trueAnomalyEpoch": 0.0,
+                    "velEciEpoch": [0.0, 5.418765068054199, 5.418765068054199]
+                }
+            }
     });
 
     test!(service, query, expected);
@@ -822,11 +938,11 @@ fn telemetry_debug_rotating() {
                         "qbXFilterGain": 1.0,
                         "qbXWheelSpeed": 0,
                         "rwaTrqMax": 0.20000000298023225,
-                        "rwsMotorCurrent": [4, 4, 4004],
-                        "rwsMotorTemp": 12,
+                        "rwsMotorCurrent": [0.0014652014942839742, 0.0014652014942839742, 1.4666666984558105],
+                        "rwsMotorTemp": -49.516483306884766,
                         "rwsPress": 0,
                         "rwsResetCntr": [1, 1, 2],
-                        "rwsVolt": 0,
+                        "rwsVolt": 0.0,
                         "scPosEci": [6720.96533203125, 669.7313842773438, 669.7247314453125],
                         "scPosEciEpoch": [6787.47021484375, 0.0, 0.0],
                         "scVelEci": [-0.20792292058467866, 5.416772842407227, 5.4167680740356449],
@@ -1119,11 +1235,11 @@ fn test_results() {
                         "qbXFilterGain": 1.0,
                         "qbXWheelSpeed": 0,
                         "rwaTrqMax": 0.20000000298023225,
-                        "rwsMotorCurrent": [4, 4, 4004],
-                        "rwsMotorTemp": 12,
+                        "rwsMotorCurrent": [0.0014652014942839742, 0.0014652014942839742, 1.4666666984558105],
+                        "rwsMotorTemp": -49.516483306884766,
                         "rwsPress": 0,
                         "rwsResetCntr": [1, 1, 2],
-                        "rwsVolt": 0,
+                        "rwsVolt": 0.0,
                         "scPosEci": [6720.96533203125, 669.7313842773438, 669.7247314453125],
                         "scPosEciEpoch": [6787.47021484375, 0.0, 0.0],
                         "scVelEci": [-0.20792292058467866, 5.416772842407227, 5.4167680740356449],
@@ -1732,11 +1848,11 @@ fn test_hardware_integration() {
                         "qbXFilterGain": 1.0,
                         "qbXWheelSpeed": 0,
                         "rwaTrqMax": 0.20000000298023225,
-                        "rwsMotorCurrent": [4, 4, 4004],
-                        "rwsMotorTemp": 12,
+                        "rwsMotorCurrent": [0.0014652014942839742, 0.0014652014942839742, 1.4666666984558105],
+                        "rwsMotorTemp": -49.516483306884766,
                         "rwsPress": 0,
                         "rwsResetCntr": [1, 1, 2],
-                        "rwsVolt": 0,
+                        "rwsVolt": 0.0,
                         "scPosEci": [6720.96533203125, 669.7313842773438, 669.7247314453125],
                         "scPosEciEpoch": [6787.47021484375, 0.0, 0.0],
                         "scVelEci": [-0.20792292058467866, 5.416772842407227, 5.4167680740356449],
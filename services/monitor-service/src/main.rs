@@ -75,5 +75,7 @@ fn main() {
 
     let config = Config::new("monitor-service");
 
-    Service::new(config, (), QueryRoot, MutationRoot).start();
+    Service::new(config, (), QueryRoot, MutationRoot)
+        .version(env!("CARGO_PKG_VERSION"))
+        .start();
 }
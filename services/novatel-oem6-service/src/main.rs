@@ -24,6 +24,12 @@
 //! The service can be configured in the `/home/system/etc/config.toml` with the following fields:
 //!
 //! - `bus` - Specifies the UART bus the OEM6 is connected to
+//! - `log_format` - Optional. Format of the log messages being output by the OEM6. One of
+//!   `binary` (default), `ascii`, or `nmea`. Command responses are always expected in the binary
+//!   format. In `nmea` mode, GGA, RMC, GSA, and ZDA sentences are accepted and used to update the
+//!   `geodetic`, `groundVelocity`, and `clock` lock information.
+//! - `status_file` - Optional. Specifies a file where the last mutation and any unread errors are
+//!   saved, so that they survive a service restart
 //! - `ip` - Specifies the service's IP address
 //! - `port` - Specifies the port on which the service will be listening for UDP packets
 //!
@@ -32,6 +38,8 @@
 //! ```toml
 //! [novatel-oem6-service]
 //! bus = "/dev/ttyS4"
+//! log_format = "binary"
+//! status_file = "/home/system/kubos/novatel-oem6-status.json"
 //!
 //! [novatel-oem6-service.addr]
 //! ip = "127.0.0.1"
@@ -81,6 +89,20 @@
 //! }
 //! ```
 //!
+//! ## Error Log
+//!
+//! Get the most recent errors, including ones which have already been read
+//!
+//! ```json
+//! {
+//!     errorLog {
+//!         timestamp: Float,
+//!         severity: Severity,
+//!         message: String
+//!     }
+//! }
+//! ```
+//!
 //! ## Power Status
 //!
 //! Get the current power state of the system
@@ -159,11 +181,36 @@
 //!
 //! ## Lock Information
 //!
-//! Get the last known good position information
+//! Get the last known good position, velocity, and clock information
 //!
 //! ```json
 //! {
 //!     lockInfo {
+//!        clock {
+//!            offset: Float,
+//!            offsetStd: Float,
+//!            time { ms: Int, week: Int },
+//!            utc { day: Int, hour: Int, minute: Int, month: Int, ms: Int, year: Int },
+//!            utcOffset: Float,
+//!            utcStatus: Int
+//!        },
+//!        geodetic {
+//!            datumId: Int,
+//!            deviation: Vec<Float>,
+//!            height: Float,
+//!            lat: Float,
+//!            lon: Float,
+//!            numSatVehicles: Int,
+//!            time { ms: Int, week: Int },
+//!            undulation: Float
+//!        },
+//!        groundVelocity {
+//!            horizontalSpeed: Float,
+//!            latency: Float,
+//!            time { ms: Int, week: Int },
+//!            trackGround: Float,
+//!            verticalSpeed: Float
+//!        },
 //!        position: Vec<Float>,
 //!        time {
 //!            ms: Int,
@@ -195,8 +242,27 @@
 //!             numComponents: Int
 //!         },
 //!         nominal{
+//!             ephemerides [{
+//!                 prn: Int,
+//!                 time { ms: Int, week: Int },
+//!                 toe: Float,
+//!                 semiMajorAxis: Float,
+//!                 eccentricity: Float,
+//!                 ...
+//!             }],
 //!             lockInfo {...},
 //!             lockStatus {...},
+//!             range {
+//!                 observations [{
+//!                     adr: Float,
+//!                     carrierNoise: Float,
+//!                     doppler: Float,
+//!                     prn: Int,
+//!                     psr: Float,
+//!                     ...
+//!                 }],
+//!                 time { ms: Int, week: Int }
+//!             },
 //!             systemStatus: {
 //!                errors: Vec<String>,
 //!                status: Vec<String>
@@ -257,16 +323,39 @@
 //!           For `UNLOG_ALL` requests, specifies whether the 'hold' value in previous
 //!           `LOG_*` requests should be ignored.
 //!   - interval: Interval at which log messages should be generated.
-//!               Note: Only applies to `LOG_POSITION_DATA` requests. Ignored otherwise
+//!               Note: Does not apply to `LOG_ERROR_DATA` or `LOG_EPHEMERIS_DATA` requests,
+//!               which are generated whenever new data is available. Ignored otherwise
 //!   - offset: Offset of interval at which log messages should be generated.
-//!             Note: Only applies to `LOG_POSITION_DATA` requests. Ignored otherwise
+//!             Note: Does not apply to `LOG_ERROR_DATA` or `LOG_EPHEMERIS_DATA` requests.
+//!             Ignored otherwise
+//!   - fix: For `FIX` requests, the position constraint to apply
+//!     (fixType, lat, lon, height)
+//!   - assign: For `ASSIGN_CHANNEL` requests, the channel assignment to make
+//!     (channel, state, prn, doppler, dopplerWindow)
+//!   - serial: For `SERIAL_CONFIG` requests, the serial port settings to apply
+//!     (port, baud, parity, dataBits, stopBits, handshake)
+//!   - interfaceMode: For `INTERFACE_MODE` requests, the port protocols to apply
+//!     (port, rxMode, txMode, responses)
+//!   - cutoff: For `ELEVATION_CUTOFF` requests, the elevation angle in degrees
+//!   - resetDelay: For `RESET` requests, the number of seconds to wait before resetting
+//!   - resetTarget: For `FACTORY_RESET` requests, the data which should be cleared
+//!
+//! The requests are applied in order as a single transaction. Every request is checked for
+//! missing or negative parameters before anything is sent, and nothing is sent if any of them
+//! are invalid. If a request fails, the remaining requests are not sent to the device.
+//! The outcome of each request is reported in `results`
 //!
 //! ```json
 //! mutation {
-//!     configureHardware(config: [{option: ConfigOption, hold: Boolean, interval: Float, offset: Float},...]) {
+//!     configureHardware(config: [{option: ConfigOption, hold: Boolean, interval: Float, offset: Float, ...},...]) {
 //!         config: String
 //!         errors: String,
 //!         success: Boolean,
+//!         results: [{
+//!             option: ConfigOption,
+//!             status: ConfigStatus,
+//!             errors: String
+//!         }]
 //!     }
 //! }
 //! ```
@@ -327,8 +416,8 @@ mod tests;
 use crate::model::{LockData, Subsystem};
 pub use crate::objects::*;
 use crate::schema::{MutationRoot, QueryRoot};
-use kubos_service::{Config, Service};
-use novatel_oem6_api::OEMResult;
+use kubos_service::{CommandStatus, Config, Service};
+use novatel_oem6_api::{LogFormat, OEMResult};
 use std::sync::Arc;
 use syslog::Facility;
 
@@ -346,9 +435,25 @@ fn main() -> OEMResult<()> {
         .expect("No 'bus' value found in 'novatel-oem6-service' section of config");
     let bus = bus.as_str().unwrap();
 
-    let subsystem = Subsystem::new(bus, Arc::new(LockData::new()))?;
+    let format = match config.get("log_format") {
+        Some(format) => match format.as_str() {
+            Some("binary") => LogFormat::Binary,
+            Some("ascii") => LogFormat::Ascii,
+            Some("nmea") => LogFormat::Nmea,
+            _ => panic!(
+                "Invalid 'log_format' value found in 'novatel-oem6-service' section of config"
+            ),
+        },
+        None => LogFormat::Binary,
+    };
+
+    let status = CommandStatus::from_config(&config, AckCommand::None);
+
+    let subsystem = Subsystem::new(bus, format, Arc::new(LockData::new()), status)?;
 
-    Service::new(config, subsystem, QueryRoot, MutationRoot).start();
+    Service::new(config, subsystem, QueryRoot, MutationRoot)
+        .version(env!("CARGO_PKG_VERSION"))
+        .start();
 
     Ok(())
 }
@@ -15,12 +15,12 @@
 //
 
 use failure::Error;
-use kubos_service::{process_errors, push_err, run};
+use kubos_service::{process_errors, run, CommandStatus, Severity};
 use log::{error, info};
 use novatel_oem6_api::Log::*;
 use novatel_oem6_api::*;
 use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TryRecvError, TrySendError};
-use std::sync::{Arc, Mutex, RwLock};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
@@ -31,6 +31,8 @@ pub const RECV_TIMEOUT: Duration = Duration::from_millis(350);
 pub struct LockData {
     pub status: Mutex<LockStatus>,
     pub info: Mutex<LockInfo>,
+    pub range: Mutex<Option<RangeInfo>>,
+    pub ephemerides: Mutex<Vec<Ephemeris>>,
 }
 
 impl LockData {
@@ -38,6 +40,8 @@ impl LockData {
         LockData {
             status: Mutex::new(LockStatus::default()),
             info: Mutex::new(LockInfo::default()),
+            range: Mutex::new(None),
+            ephemerides: Mutex::new(vec![]),
         }
     }
 
@@ -46,18 +50,35 @@ impl LockData {
         *local = status;
     }
 
-    pub fn update_info(&self, info: LockInfo) {
+    // Each log message only covers part of the lock information,
+    // so update the relevant fields in place
+    pub fn update_info<F: FnOnce(&mut LockInfo)>(&self, update: F) {
         let mut local = self.info.lock().unwrap();
-        *local = info;
+        update(&mut local);
+    }
+
+    pub fn update_range(&self, range: RangeInfo) {
+        let mut local = self.range.lock().unwrap();
+        *local = Some(range);
+    }
+
+    // Keep the latest ephemeris for each satellite
+    pub fn update_ephemeris(&self, ephemeris: Ephemeris) {
+        let mut local = self.ephemerides.lock().unwrap();
+        match local.binary_search_by_key(&ephemeris.prn, |entry| entry.prn) {
+            Ok(index) => local[index] = ephemeris,
+            Err(index) => local.insert(index, ephemeris),
+        }
     }
 }
 
 // Listen for log messages from the OEM6 and route data to the appropriate
 // listener or structure.
 //
-// The OEM6 will send us one of three log messages:
-// - Lock information. The OEM6 will likely be set up to output this data
-//   once per second.
+// The OEM6 will send us one of these log messages:
+// - Lock information (position, velocity, and time). The OEM6 will likely be
+//   set up to output this data once per second.
+// - Range and ephemeris information. Used for orbit determination, if requested.
 // - Version information. This data will be output immediately upon request by
 //   the `noop` and `get_test_results` functions
 // - Error information. If enabled, this will be output by the OEM6 when an
@@ -75,13 +96,13 @@ pub fn log_thread(
         }) {
             BestXYZ(log) => {
                 if log.pos_status == 0 && log.vel_status == 0 {
-                    data.update_info(LockInfo {
-                        time: OEMTime {
+                    data.update_info(|info| {
+                        info.time = OEMTime {
                             week: i32::from(log.week),
                             ms: log.ms,
-                        },
-                        position: log.position,
-                        velocity: log.velocity,
+                        };
+                        info.position = log.position;
+                        info.velocity = log.velocity;
                     });
                 }
                 data.update_status(LockStatus {
@@ -96,6 +117,26 @@ pub fn log_thread(
                     velocity_type: log.vel_type,
                 });
             }
+            BestPos(log) => {
+                if log.pos_status == 0 {
+                    data.update_info(|info| info.geodetic = Some(log.into()));
+                }
+            }
+            BestVel(log) => {
+                if log.vel_status == 0 {
+                    data.update_info(|info| info.ground_velocity = Some(log.into()));
+                }
+            }
+            Time(log) => {
+                // A clock status of 0 means the clock model is valid
+                if log.clock_status == 0 {
+                    data.update_info(|info| info.clock = Some(log.into()));
+                }
+            }
+            // DOP information isn't currently reported by the service
+            GpsDop(_) => {}
+            Range(log) => data.update_range(log.into()),
+            GpsEphem(log) => data.update_ephemeris(log.into()),
             RxStatusEvent(log) => error_send
                 .try_send(log)
                 .or_else::<TrySendError<RxStatusEventLog>, _>(|err| match err {
@@ -129,22 +170,26 @@ pub fn log_thread(
 #[derive(Clone)]
 pub struct Subsystem {
     pub oem: OEM6,
-    pub last_cmd: Arc<RwLock<AckCommand>>,
-    pub errors: Arc<RwLock<Vec<String>>>,
+    pub status: CommandStatus<AckCommand>,
     pub lock_data: Arc<LockData>,
     pub error_recv: Arc<Mutex<Receiver<RxStatusEventLog>>>,
     pub version_recv: Arc<Mutex<Receiver<VersionLog>>>,
 }
 
 impl Subsystem {
-    pub fn new(bus: &str, data: Arc<LockData>) -> OEMResult<Subsystem> {
+    pub fn new(
+        bus: &str,
+        format: LogFormat,
+        data: Arc<LockData>,
+        status: CommandStatus<AckCommand>,
+    ) -> OEMResult<Subsystem> {
         let (log_send, log_recv) = sync_channel(5);
         let (response_send, response_recv) = sync_channel(5);
 
         let oem = OEM6::new(bus, BaudRate::Baud9600, log_recv, response_recv)?;
 
         let rx_conn = oem.conn.clone();
-        thread::spawn(move || read_thread(&rx_conn, &log_send, &response_send));
+        thread::spawn(move || read_thread_with_format(&rx_conn, &log_send, &response_send, format));
 
         let (error_send, error_recv) = sync_channel(10);
         let (version_send, version_recv) = sync_channel(1);
@@ -157,8 +202,7 @@ impl Subsystem {
 
         Ok(Subsystem {
             oem,
-            last_cmd: Arc::new(RwLock::new(AckCommand::None)),
-            errors: Arc::new(RwLock::new(vec![])),
+            status,
             lock_data: data.clone(),
             error_recv: Arc::new(Mutex::new(error_recv)),
             version_recv: Arc::new(Mutex::new(version_recv)),
@@ -192,21 +236,21 @@ impl Subsystem {
         if let Ok(recv) = self.error_recv.lock() {
             match recv.try_recv() {
                 Ok(msg) => {
-                    push_err!(
-                        self.errors,
+                    self.status.push_error(
+                        Severity::Error,
                         format!(
                             "RxStatusEvent({}, {}, {}): {}",
                             msg.word, msg.bit, msg.event, msg.description
-                        )
+                        ),
                     );
 
                     while let Ok(err) = recv.try_recv() {
-                        push_err!(
-                            self.errors,
+                        self.status.push_error(
+                            Severity::Error,
                             format!(
                                 "RxStatusEvent({}, {}, {}): {}",
                                 err.word, err.bit, err.event, err.description
-                            )
+                            ),
                         );
                     }
                 }
@@ -215,12 +259,18 @@ impl Subsystem {
                     TryRecvError::Empty => {}
                     // We've lost connection with the errors channel
                     TryRecvError::Disconnected => {
-                        push_err!(self.errors, "Errors sender disconnected".to_owned());
+                        self.status.push_error(
+                            Severity::Critical,
+                            "Errors sender disconnected".to_owned(),
+                        );
                     }
                 },
             }
         } else {
-            push_err!(self.errors, "Failed to obtain error_recv mutex".to_owned());
+            self.status.push_error(
+                Severity::Error,
+                "Failed to obtain error_recv mutex".to_owned(),
+            );
         }
     }
 
@@ -237,13 +287,7 @@ impl Subsystem {
     pub fn get_system_status(&self) -> Result<SystemStatus, Error> {
         self.get_errors();
 
-        let mut errors = match self.errors.read() {
-            Ok(master_vec) => master_vec.clone(),
-            _ => {
-                error!("get_system_status - Failed to borrow master errors vector");
-                vec!["Error: Failed to borrow master errors vector".to_owned()]
-            }
-        };
+        let mut errors = self.status.unread_error_messages();
 
         let status = match self.get_version_log() {
             Ok(log) => log.recv_status,
@@ -285,13 +329,7 @@ impl Subsystem {
     pub fn get_telemetry(&self) -> Result<Telemetry, Error> {
         self.get_errors();
 
-        let mut errors = match self.errors.read() {
-            Ok(master_vec) => master_vec.clone(),
-            _ => {
-                error!("get_telemetry - Failed to borrow master errors vector");
-                vec!["Error: Failed to borrow master errors vector".to_owned()]
-            }
-        };
+        let mut errors = self.status.unread_error_messages();
 
         let (status, version_info) = match self.get_version_log() {
             Ok(log) => (
@@ -308,13 +346,15 @@ impl Subsystem {
             Err(err) => {
                 let temp = format!("Get Telemetry: {}", err);
                 errors.push(temp.clone());
-                push_err!(self.errors, temp);
+                self.status.push_error(Severity::Error, temp);
                 (ReceiverStatusFlags::all(), None)
             }
         };
 
         let lock_status = self.get_lock_status().ok();
         let lock_info = self.get_lock_info().ok();
+        let range = self.lock_data.range.lock().unwrap().clone();
+        let ephemerides = self.lock_data.ephemerides.lock().unwrap().clone();
 
         Ok(Telemetry {
             nominal: TelemetryNominal {
@@ -324,6 +364,8 @@ impl Subsystem {
                 },
                 lock_status,
                 lock_info,
+                range,
+                ephemerides,
             },
             debug: version_info,
         })
@@ -340,7 +382,7 @@ impl Subsystem {
             Ok(_) => "".to_owned(),
             Err(err) => {
                 let temp = format!("Noop: {}", err);
-                push_err!(self.errors, temp);
+                self.status.push_error(Severity::Error, temp);
                 err
             }
         };
@@ -352,51 +394,180 @@ impl Subsystem {
         &self,
         input: Vec<ConfigStruct>,
     ) -> Result<ConfigureHardwareResponse, Error> {
-        let mut success = true;
         let mut errors = "".to_owned();
         let mut config = "".to_owned();
-
-        for entry in input.iter() {
-            let result = run!(
-                match entry.option {
-                    ConfigOption::LogErrorData => self.oem.request_errors(entry.hold),
-                    ConfigOption::LogPositionData => {
-                        self.oem
-                            .request_position(entry.interval, entry.offset, entry.hold)
-                    }
-                    ConfigOption::UnlogAll => self.oem.request_unlog_all(entry.hold),
-                    ConfigOption::UnlogErrorData => {
-                        self.oem.request_unlog(MessageID::RxStatusEvent)
+        let mut results = vec![];
+
+        // Every option is checked before anything is sent, so that a bad option can't
+        // leave the device partially configured
+        let checks: Vec<Result<(), String>> = input.iter().map(check_config).collect();
+        let mut success = checks.iter().all(|check| check.is_ok());
+
+        // The options are applied in order as a single transaction. Once one of them fails,
+        // the remaining options are skipped rather than being sent to the device
+        for (entry, check) in input.iter().zip(checks) {
+            let result = match check {
+                Err(err) => {
+                    self.status
+                        .push_error(Severity::Error, format!("{:?}: {}", entry.option, err));
+                    Err(err)
+                }
+                Ok(_) if success => self.apply_config(entry),
+                Ok(_) => Ok(()),
+            };
+
+            let (status, step_errors) = match result {
+                Ok(_) if !success => (ConfigStatus::Skipped, "".to_owned()),
+                Ok(_) => (ConfigStatus::Applied, "".to_owned()),
+                Err(err) => {
+                    success = false;
+                    if !errors.is_empty() {
+                        errors.push_str(". ");
                     }
-                    ConfigOption::UnlogPositionData => self.oem.request_unlog(MessageID::BestXYZ),
-                },
-                self.errors
-            );
-
-            success &= result.is_ok();
-            if let Err(err) = result {
-                if !errors.is_empty() {
-                    errors.push_str(". ");
+                    errors.push_str(&format!("{:?}: {}", entry.option, err));
+                    (ConfigStatus::Failed, err)
                 }
-                errors.push_str(&format!("{:?}: {}", entry.option, err));
-            }
+            };
+
+            results.push(ConfigResult {
+                option: entry.option,
+                status,
+                errors: step_errors,
+            });
 
             if !config.is_empty() {
                 config.push_str(", ");
             }
-            config.push_str(&format!("{:?}(Hold: {})", entry.option, entry.hold));
-            if entry.interval != 0.0 {
-                config.push_str(&format!(": {}+{}sec", entry.interval, entry.offset));
-            }
+            config.push_str(&describe_config(entry));
         }
 
         Ok(ConfigureHardwareResponse {
             success,
             errors,
             config,
+            results,
         })
     }
 
+    // Send a single configuration option to the device.
+    // The option should already have been checked with `check_config`
+    fn apply_config(&self, entry: &ConfigStruct) -> Result<(), String> {
+        match entry.option {
+            ConfigOption::LogErrorData => run!(self.oem.request_errors(entry.hold), self.status),
+            ConfigOption::LogPositionData => run!(
+                self.oem
+                    .request_position(entry.interval, entry.offset, entry.hold),
+                self.status
+            ),
+            ConfigOption::LogGeodeticPositionData => run!(
+                self.oem
+                    .request_best_position(entry.interval, entry.offset, entry.hold),
+                self.status
+            ),
+            ConfigOption::LogGroundVelocityData => run!(
+                self.oem
+                    .request_best_velocity(entry.interval, entry.offset, entry.hold),
+                self.status
+            ),
+            ConfigOption::LogTimeData => run!(
+                self.oem
+                    .request_time(entry.interval, entry.offset, entry.hold),
+                self.status
+            ),
+            ConfigOption::LogRangeData => run!(
+                self.oem
+                    .request_range(entry.interval, entry.offset, entry.hold),
+                self.status
+            ),
+            ConfigOption::LogEphemerisData => {
+                run!(self.oem.request_ephemeris(entry.hold), self.status)
+            }
+            ConfigOption::UnlogAll => run!(self.oem.request_unlog_all(entry.hold), self.status),
+            ConfigOption::UnlogErrorData => run!(
+                self.oem.request_unlog(MessageID::RxStatusEvent),
+                self.status
+            ),
+            ConfigOption::UnlogPositionData => {
+                run!(self.oem.request_unlog(MessageID::BestXYZ), self.status)
+            }
+            ConfigOption::UnlogGeodeticPositionData => {
+                run!(self.oem.request_unlog(MessageID::BestPos), self.status)
+            }
+            ConfigOption::UnlogGroundVelocityData => {
+                run!(self.oem.request_unlog(MessageID::BestVel), self.status)
+            }
+            ConfigOption::UnlogTimeData => {
+                run!(self.oem.request_unlog(MessageID::Time), self.status)
+            }
+            ConfigOption::UnlogRangeData => {
+                run!(self.oem.request_unlog(MessageID::Range), self.status)
+            }
+            ConfigOption::UnlogEphemerisData => {
+                run!(self.oem.request_unlog(MessageID::GpsEphem), self.status)
+            }
+            ConfigOption::Fix => match entry.fix {
+                Some(ref fix) => run!(
+                    self.oem
+                        .set_fix(fix.fix_type.into(), fix.lat, fix.lon, fix.height),
+                    self.status
+                ),
+                None => missing("fix"),
+            },
+            ConfigOption::AssignChannel => match entry.assign {
+                Some(ref assign) => run!(
+                    self.oem.assign_channel(
+                        unsigned("channel", assign.channel)?,
+                        assign.state.into(),
+                        unsigned("prn", assign.prn)?,
+                        assign.doppler,
+                        unsigned("dopplerWindow", assign.doppler_window)?,
+                    ),
+                    self.status
+                ),
+                None => missing("assign"),
+            },
+            ConfigOption::SerialConfig => match entry.serial {
+                Some(ref serial) => run!(
+                    self.oem.set_serial_config(
+                        serial.port.into(),
+                        unsigned("baud", serial.baud)?,
+                        serial.parity.into(),
+                        unsigned("dataBits", serial.data_bits)?,
+                        unsigned("stopBits", serial.stop_bits)?,
+                        serial.handshake.into(),
+                    ),
+                    self.status
+                ),
+                None => missing("serial"),
+            },
+            ConfigOption::InterfaceMode => match entry.interface_mode {
+                Some(ref mode) => run!(
+                    self.oem.set_interface_mode(
+                        mode.port.into(),
+                        mode.rx_mode.into(),
+                        mode.tx_mode.into(),
+                        mode.responses,
+                    ),
+                    self.status
+                ),
+                None => missing("interfaceMode"),
+            },
+            ConfigOption::ElevationCutoff => match entry.cutoff {
+                Some(cutoff) => run!(self.oem.set_elevation_cutoff(cutoff as f32), self.status),
+                None => missing("cutoff"),
+            },
+            ConfigOption::SaveConfig => run!(self.oem.save_config(), self.status),
+            ConfigOption::Reset => run!(
+                self.oem.reset(unsigned("resetDelay", entry.reset_delay)?),
+                self.status
+            ),
+            ConfigOption::FactoryReset => match entry.reset_target {
+                Some(target) => run!(self.oem.factory_reset(target.into()), self.status),
+                None => missing("resetTarget"),
+            },
+        }
+    }
+
     pub fn passthrough(&self, command: String) -> Result<GenericResponse, Error> {
         // Convert the hex values in the string into actual hex values
         // Ex. "c3c2" -> [0xc3, 0xc2]
@@ -406,7 +577,7 @@ impl Subsystem {
             .map(|chunk| u8::from_str_radix(::std::str::from_utf8(chunk).unwrap(), 16).unwrap())
             .collect();
 
-        let result = run!(self.oem.passthrough(tx.as_slice()), self.errors);
+        let result = run!(self.oem.passthrough(tx.as_slice()), self.status);
 
         Ok(GenericResponse {
             success: result.is_ok(),
@@ -417,3 +588,104 @@ impl Subsystem {
         })
     }
 }
+
+// Make sure a configuration option has all of the parameters it needs, and that
+// they're in range, before anything is sent to the device
+fn check_config(entry: &ConfigStruct) -> Result<(), String> {
+    match entry.option {
+        ConfigOption::Fix if entry.fix.is_none() => missing("fix"),
+        ConfigOption::AssignChannel => match entry.assign {
+            Some(ref assign) => {
+                unsigned("channel", assign.channel)?;
+                unsigned("prn", assign.prn)?;
+                unsigned("dopplerWindow", assign.doppler_window)?;
+                Ok(())
+            }
+            None => missing("assign"),
+        },
+        ConfigOption::SerialConfig => match entry.serial {
+            Some(ref serial) => {
+                unsigned("baud", serial.baud)?;
+                unsigned("dataBits", serial.data_bits)?;
+                unsigned("stopBits", serial.stop_bits)?;
+                Ok(())
+            }
+            None => missing("serial"),
+        },
+        ConfigOption::InterfaceMode if entry.interface_mode.is_none() => missing("interfaceMode"),
+        ConfigOption::ElevationCutoff if entry.cutoff.is_none() => missing("cutoff"),
+        ConfigOption::Reset => unsigned("resetDelay", entry.reset_delay).map(|_| ()),
+        ConfigOption::FactoryReset if entry.reset_target.is_none() => missing("resetTarget"),
+        _ => Ok(()),
+    }
+}
+
+fn missing<T>(param: &str) -> Result<T, String> {
+    Err(format!("Missing '{}' parameter", param))
+}
+
+// GraphQL only has signed integers, so make sure that unsigned parameters
+// aren't negative
+fn unsigned(param: &str, value: i32) -> Result<u32, String> {
+    if value < 0 {
+        Err(format!("Invalid '{}' parameter: {}", param, value))
+    } else {
+        Ok(value as u32)
+    }
+}
+
+// Create a short description of a requested configuration option
+fn describe_config(entry: &ConfigStruct) -> String {
+    match entry.option {
+        ConfigOption::Fix => match entry.fix {
+            Some(ref fix) => format!(
+                "Fix({:?}: {}, {}, {}m)",
+                fix.fix_type, fix.lat, fix.lon, fix.height
+            ),
+            None => "Fix".to_owned(),
+        },
+        ConfigOption::AssignChannel => match entry.assign {
+            Some(ref assign) => format!(
+                "AssignChannel({}: {:?}, PRN {})",
+                assign.channel, assign.state, assign.prn
+            ),
+            None => "AssignChannel".to_owned(),
+        },
+        ConfigOption::SerialConfig => match entry.serial {
+            Some(ref serial) => format!(
+                "SerialConfig({:?}: {} {:?} {}/{} {:?})",
+                serial.port,
+                serial.baud,
+                serial.parity,
+                serial.data_bits,
+                serial.stop_bits,
+                serial.handshake
+            ),
+            None => "SerialConfig".to_owned(),
+        },
+        ConfigOption::InterfaceMode => match entry.interface_mode {
+            Some(ref mode) => format!(
+                "InterfaceMode({:?}: {:?}/{:?})",
+                mode.port, mode.rx_mode, mode.tx_mode
+            ),
+            None => "InterfaceMode".to_owned(),
+        },
+        ConfigOption::ElevationCutoff => match entry.cutoff {
+            Some(cutoff) => format!("ElevationCutoff({}deg)", cutoff),
+            None => "ElevationCutoff".to_owned(),
+        },
+        ConfigOption::SaveConfig => "SaveConfig".to_owned(),
+        ConfigOption::Reset => format!("Reset({}sec)", entry.reset_delay),
+        ConfigOption::FactoryReset => match entry.reset_target {
+            Some(target) => format!("FactoryReset({:?})", target),
+            None => "FactoryReset".to_owned(),
+        },
+        _ => {
+            let mut desc = format!("{:?}(Hold: {})", entry.option, entry.hold);
+            if entry.interval != 0.0 {
+                desc.push_str(&format!(": {}+{}sec", entry.interval, entry.offset));
+            }
+            desc
+        }
+    }
+}
@@ -16,7 +16,11 @@
 
 use juniper::parser::{ParseError, ScalarToken, Token};
 use juniper::{ParseScalarResult, Value};
-use novatel_oem6_api::{Component, ReceiverStatusFlags};
+use novatel_oem6_api::{
+    BestPosLog, BestVelLog, Component, GpsEphemLog, RangeLog, RangeObservation,
+    ReceiverStatusFlags, TimeLog,
+};
+use serde_derive::{Deserialize, Serialize};
 
 /// Common response fields structure for requests
 /// which don't return any specific data
@@ -31,7 +35,7 @@ pub struct GenericResponse {
 /// Return field for 'ack' query
 ///
 /// Indicates last mutation executed by the service
-#[derive(GraphQLEnum, Clone, Copy)]
+#[derive(GraphQLEnum, Clone, Copy, Deserialize, Serialize)]
 pub enum AckCommand {
     /// No mutations have been executed
     None,
@@ -64,23 +68,331 @@ pub struct ConfigStruct {
     /// messages should be generated by the system
     #[graphql(default = "0.0")]
     pub offset: f64,
+    /// (For the "Fix" config option) The position constraint to apply
+    pub fix: Option<FixInput>,
+    /// (For the "AssignChannel" config option) The channel assignment to make
+    pub assign: Option<AssignInput>,
+    /// (For the "SerialConfig" config option) The serial port settings to apply
+    pub serial: Option<SerialConfigInput>,
+    /// (For the "InterfaceMode" config option) The port protocols to apply
+    pub interface_mode: Option<InterfaceModeInput>,
+    /// (For the "Reset" config option) The number of seconds to wait before resetting
+    #[graphql(default = "0")]
+    pub reset_delay: i32,
+    /// (For the "FactoryReset" config option) The data which should be cleared
+    pub reset_target: Option<ResetTarget>,
+    /// (For the "ElevationCutoff" config option) The elevation, in degrees, below which
+    /// satellites should not be tracked
+    pub cutoff: Option<f64>,
+}
+
+/// Parameters for the `FIX` configuration option
+#[derive(GraphQLInputObject, Debug)]
+pub struct FixInput {
+    /// Type of constraint to apply
+    pub fix_type: FixType,
+    /// Latitude to fix, in degrees. Only used by `POSITION` constraints
+    #[graphql(default = "0.0")]
+    pub lat: f64,
+    /// Longitude to fix, in degrees. Only used by `POSITION` constraints
+    #[graphql(default = "0.0")]
+    pub lon: f64,
+    /// Height above mean sea level to fix, in meters. Only used by `HEIGHT` and
+    /// `POSITION` constraints
+    #[graphql(default = "0.0")]
+    pub height: f64,
+}
+
+/// Parameters for the `ASSIGN_CHANNEL` configuration option
+#[derive(GraphQLInputObject, Debug)]
+pub struct AssignInput {
+    /// Receiver channel number
+    pub channel: i32,
+    /// Tracking state to put the channel in
+    pub state: ChannelState,
+    /// PRN of the satellite to track. Only used by the `ACTIVE` state
+    #[graphql(default = "0")]
+    pub prn: i32,
+    /// Expected Doppler offset of the satellite, in Hz
+    #[graphql(default = "0")]
+    pub doppler: i32,
+    /// Search window around the expected Doppler offset, in Hz
+    #[graphql(default = "0")]
+    pub doppler_window: i32,
+}
+
+/// Parameters for the `SERIAL_CONFIG` configuration option
+#[derive(GraphQLInputObject, Debug)]
+pub struct SerialConfigInput {
+    /// Serial port to configure
+    pub port: ComPort,
+    /// Communication data rate
+    pub baud: i32,
+    /// Parity setting
+    #[graphql(default = "Parity::None")]
+    pub parity: Parity,
+    /// Number of data bits
+    #[graphql(default = "8")]
+    pub data_bits: i32,
+    /// Number of stop bits
+    #[graphql(default = "1")]
+    pub stop_bits: i32,
+    /// Handshaking setting
+    #[graphql(default = "Handshake::None")]
+    pub handshake: Handshake,
+}
+
+/// Parameters for the `INTERFACE_MODE` configuration option
+#[derive(GraphQLInputObject, Debug)]
+pub struct InterfaceModeInput {
+    /// Serial port to configure
+    pub port: ComPort,
+    /// Protocol for incoming data
+    pub rx_mode: InterfaceMode,
+    /// Protocol for outgoing data
+    pub tx_mode: InterfaceMode,
+    /// Whether command responses should be sent from the port
+    #[graphql(default = "true")]
+    pub responses: bool,
+}
+
+/// Position constraint types
+#[derive(GraphQLEnum, Clone, Copy, Debug)]
+pub enum FixType {
+    /// Remove any previous constraint
+    None,
+    /// Fix the height at the last calculated value, if the receiver is moving slowly
+    Auto,
+    /// Fix the height to the given value
+    Height,
+    /// Fix the position to the given value
+    Position,
+}
+
+impl From<FixType> for novatel_oem6_api::FixType {
+    fn from(t: FixType) -> novatel_oem6_api::FixType {
+        match t {
+            FixType::None => novatel_oem6_api::FixType::None,
+            FixType::Auto => novatel_oem6_api::FixType::Auto,
+            FixType::Height => novatel_oem6_api::FixType::Height,
+            FixType::Position => novatel_oem6_api::FixType::Position,
+        }
+    }
+}
+
+/// Receiver channel tracking states
+#[derive(GraphQLEnum, Clone, Copy, Debug)]
+pub enum ChannelState {
+    /// Channel will not track any satellite
+    Idle,
+    /// Channel will track the assigned satellite
+    Active,
+    /// Channel is returned to automatic satellite selection
+    Auto,
+}
+
+impl From<ChannelState> for novatel_oem6_api::ChannelState {
+    fn from(t: ChannelState) -> novatel_oem6_api::ChannelState {
+        match t {
+            ChannelState::Idle => novatel_oem6_api::ChannelState::Idle,
+            ChannelState::Active => novatel_oem6_api::ChannelState::Active,
+            ChannelState::Auto => novatel_oem6_api::ChannelState::Auto,
+        }
+    }
+}
+
+/// Receiver serial ports
+#[derive(GraphQLEnum, Clone, Copy, Debug)]
+pub enum ComPort {
+    /// COM1
+    Com1,
+    /// COM2
+    Com2,
+    /// COM3
+    Com3,
+    /// The port the service is connected to
+    ThisPort,
+}
+
+impl From<ComPort> for novatel_oem6_api::ComPort {
+    fn from(t: ComPort) -> novatel_oem6_api::ComPort {
+        match t {
+            ComPort::Com1 => novatel_oem6_api::ComPort::Com1,
+            ComPort::Com2 => novatel_oem6_api::ComPort::Com2,
+            ComPort::Com3 => novatel_oem6_api::ComPort::Com3,
+            ComPort::ThisPort => novatel_oem6_api::ComPort::ThisPort,
+        }
+    }
+}
+
+/// Serial port parity settings
+#[derive(GraphQLEnum, Clone, Copy, Debug)]
+pub enum Parity {
+    /// No parity
+    None,
+    /// Even parity
+    Even,
+    /// Odd parity
+    Odd,
+}
+
+impl From<Parity> for novatel_oem6_api::Parity {
+    fn from(t: Parity) -> novatel_oem6_api::Parity {
+        match t {
+            Parity::None => novatel_oem6_api::Parity::None,
+            Parity::Even => novatel_oem6_api::Parity::Even,
+            Parity::Odd => novatel_oem6_api::Parity::Odd,
+        }
+    }
+}
+
+/// Serial port handshaking settings
+#[derive(GraphQLEnum, Clone, Copy, Debug)]
+pub enum Handshake {
+    /// No handshaking
+    None,
+    /// XON/XOFF software handshaking
+    XOnXOff,
+    /// CTS/RTS hardware handshaking
+    CtsRts,
+}
+
+impl From<Handshake> for novatel_oem6_api::Handshake {
+    fn from(t: Handshake) -> novatel_oem6_api::Handshake {
+        match t {
+            Handshake::None => novatel_oem6_api::Handshake::None,
+            Handshake::XOnXOff => novatel_oem6_api::Handshake::XOnXOff,
+            Handshake::CtsRts => novatel_oem6_api::Handshake::CtsRts,
+        }
+    }
+}
+
+/// Serial port protocols
+#[derive(GraphQLEnum, Clone, Copy, Debug)]
+pub enum InterfaceMode {
+    /// No data will be accepted or sent
+    None,
+    /// NovAtel ASCII or binary commands and logs
+    NovAtel,
+    /// RTCM corrections
+    Rtcm,
+    /// RTCA corrections
+    Rtca,
+    /// CMR corrections
+    Cmr,
+    /// RTCM corrections, without CR/LF appended
+    RtcmNoCr,
+    /// NovAtel binary commands and logs only
+    NovAtelBinary,
+    /// RTCM version 3 corrections
+    RtcmV3,
+}
+
+impl From<InterfaceMode> for novatel_oem6_api::InterfaceMode {
+    fn from(t: InterfaceMode) -> novatel_oem6_api::InterfaceMode {
+        match t {
+            InterfaceMode::None => novatel_oem6_api::InterfaceMode::None,
+            InterfaceMode::NovAtel => novatel_oem6_api::InterfaceMode::NovAtel,
+            InterfaceMode::Rtcm => novatel_oem6_api::InterfaceMode::Rtcm,
+            InterfaceMode::Rtca => novatel_oem6_api::InterfaceMode::Rtca,
+            InterfaceMode::Cmr => novatel_oem6_api::InterfaceMode::Cmr,
+            InterfaceMode::RtcmNoCr => novatel_oem6_api::InterfaceMode::RtcmNoCr,
+            InterfaceMode::NovAtelBinary => novatel_oem6_api::InterfaceMode::NovAtelBinary,
+            InterfaceMode::RtcmV3 => novatel_oem6_api::InterfaceMode::RtcmV3,
+        }
+    }
+}
+
+/// Data which can be cleared by a factory reset
+#[derive(GraphQLEnum, Clone, Copy, Debug)]
+pub enum ResetTarget {
+    /// Clear all stored data and restore the factory default configuration
+    Standard,
+    /// Clear the saved configuration
+    Command,
+    /// Clear the stored GPS almanac
+    GpsAlmanac,
+    /// Clear the stored GPS ephemerides
+    GpsEphemeris,
+    /// Clear the stored GLONASS ephemerides
+    GlonassEphemeris,
+    /// Clear the currently selected model
+    Model,
+    /// Clear the stored clock calibration
+    ClockCalibration,
+    /// Clear the stored SBAS almanac
+    SbasAlmanac,
+    /// Clear the last known position
+    LastPosition,
+}
+
+impl From<ResetTarget> for novatel_oem6_api::ResetTarget {
+    fn from(t: ResetTarget) -> novatel_oem6_api::ResetTarget {
+        match t {
+            ResetTarget::Standard => novatel_oem6_api::ResetTarget::Standard,
+            ResetTarget::Command => novatel_oem6_api::ResetTarget::Command,
+            ResetTarget::GpsAlmanac => novatel_oem6_api::ResetTarget::GpsAlmanac,
+            ResetTarget::GpsEphemeris => novatel_oem6_api::ResetTarget::GpsEphemeris,
+            ResetTarget::GlonassEphemeris => novatel_oem6_api::ResetTarget::GlonassEphemeris,
+            ResetTarget::Model => novatel_oem6_api::ResetTarget::Model,
+            ResetTarget::ClockCalibration => novatel_oem6_api::ResetTarget::ClockCalibration,
+            ResetTarget::SbasAlmanac => novatel_oem6_api::ResetTarget::SbasAlmanac,
+            ResetTarget::LastPosition => novatel_oem6_api::ResetTarget::LastPosition,
+        }
+    }
 }
 
 /// Input field for 'configureHardware' mutation
 ///
 /// Indicates which configuration operation should be performed
-#[derive(GraphQLEnum, Debug)]
+#[derive(GraphQLEnum, Clone, Copy, Debug)]
 pub enum ConfigOption {
     /// Configure system to output error data when errors or events occur
     LogErrorData,
     /// Configure system to output position data at a requested interval
     LogPositionData,
+    /// Configure system to output geodetic position data at a requested interval
+    LogGeodeticPositionData,
+    /// Configure system to output ground velocity data at a requested interval
+    LogGroundVelocityData,
+    /// Configure system to output clock and UTC time data at a requested interval
+    LogTimeData,
+    /// Configure system to output satellite range measurements at a requested interval
+    LogRangeData,
+    /// Configure system to output GPS ephemeris data whenever it changes
+    LogEphemerisData,
     /// Stop generation of all output data from device
     UnlogAll,
     /// Stop generation of error data from device
     UnlogErrorData,
     /// Stop generation of position data from device
     UnlogPositionData,
+    /// Stop generation of geodetic position data from device
+    UnlogGeodeticPositionData,
+    /// Stop generation of ground velocity data from device
+    UnlogGroundVelocityData,
+    /// Stop generation of clock and UTC time data from device
+    UnlogTimeData,
+    /// Stop generation of satellite range measurements from device
+    UnlogRangeData,
+    /// Stop generation of GPS ephemeris data from device
+    UnlogEphemerisData,
+    /// Constrain the position solution (FIX)
+    Fix,
+    /// Assign a satellite to a receiver channel (ASSIGN)
+    AssignChannel,
+    /// Configure one of the device's serial ports (SERIALCONFIG)
+    SerialConfig,
+    /// Set the protocols used by one of the device's serial ports (INTERFACEMODE)
+    InterfaceMode,
+    /// Set the elevation below which satellites will not be tracked (ECUTOFF)
+    ElevationCutoff,
+    /// Save the current configuration to non-volatile memory (SAVECONFIG)
+    SaveConfig,
+    /// Reset the device (RESET)
+    Reset,
+    /// Clear data stored in non-volatile memory and reset the device (FRESET)
+    FactoryReset,
 }
 
 /// Response fields for 'configureHardware' mutation
@@ -92,6 +404,31 @@ pub struct ConfigureHardwareResponse {
     pub errors: String,
     /// Request completion success or failure
     pub success: bool,
+    /// Outcome of each requested configuration option, in the order they were requested
+    pub results: Vec<ConfigResult>,
+}
+
+/// Outcome of a single configuration option within a 'configureHardware' request
+#[derive(GraphQLObject, Clone)]
+pub struct ConfigResult {
+    /// Configuration option which was requested
+    pub option: ConfigOption,
+    /// Whether the option was applied
+    pub status: ConfigStatus,
+    /// Any errors encountered while applying the option
+    pub errors: String,
+}
+
+/// Status of a single configuration option within a 'configureHardware' request
+#[derive(GraphQLEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ConfigStatus {
+    /// The device accepted the option
+    Applied,
+    /// The option could not be applied
+    Failed,
+    /// The option was not attempted, because another option was invalid or an earlier
+    /// option failed
+    Skipped,
 }
 
 /// Input field for 'testHardware' mutation
@@ -409,12 +746,18 @@ graphql_object!(LockStatus: () where Scalar = <S> | &self | {
 /// the `lockInfo` query
 #[derive(Clone, Default)]
 pub struct LockInfo {
-    /// Timestamp when the other fields were last updated
+    /// Timestamp when the position and velocity fields were last updated
     pub time: OEMTime,
     /// Last known good position
     pub position: [f64; 3],
     /// Last known good velocity
     pub velocity: [f64; 3],
+    /// Last known good geodetic position
+    pub geodetic: Option<GeodeticPosition>,
+    /// Last known good ground velocity
+    pub ground_velocity: Option<GroundVelocity>,
+    /// Last known good clock information
+    pub clock: Option<ClockInfo>,
 }
 
 graphql_object!(LockInfo: ()  where Scalar = <S> | &self | {
@@ -429,8 +772,327 @@ graphql_object!(LockInfo: ()  where Scalar = <S> | &self | {
     field velocity() -> Vec<f64> {
         self.velocity.to_vec()
     }
+
+    field geodetic() -> Option<GeodeticPosition> {
+        self.geodetic.clone()
+    }
+
+    field ground_velocity() -> Option<GroundVelocity> {
+        self.ground_velocity.clone()
+    }
+
+    field clock() -> Option<ClockInfo> {
+        self.clock.clone()
+    }
 });
 
+/// Geodetic position information. Used in the `geodetic` field of the `lockInfo` response
+#[derive(Clone, Default, GraphQLObject)]
+pub struct GeodeticPosition {
+    /// Timestamp of the position data
+    pub time: OEMTime,
+    /// Latitude (degrees)
+    pub lat: f64,
+    /// Longitude (degrees)
+    pub lon: f64,
+    /// Height above mean sea level (m)
+    pub height: f64,
+    /// Undulation - the relationship between the geoid and the WGS84 ellipsoid (m)
+    pub undulation: f64,
+    /// Datum ID number
+    pub datum_id: i32,
+    /// Standard deviation of {latitude, longitude, height} (m)
+    pub deviation: Vec<f64>,
+    /// Number of satellite vehicles used in solution
+    pub num_sat_vehicles: i32,
+}
+
+impl From<BestPosLog> for GeodeticPosition {
+    fn from(log: BestPosLog) -> GeodeticPosition {
+        GeodeticPosition {
+            time: OEMTime {
+                week: i32::from(log.week),
+                ms: log.ms,
+            },
+            lat: log.lat,
+            lon: log.lon,
+            height: log.height,
+            undulation: f64::from(log.undulation),
+            datum_id: log.datum_id as i32,
+            deviation: log
+                .pos_deviation
+                .iter()
+                .map(|val| f64::from(*val))
+                .collect(),
+            num_sat_vehicles: i32::from(log.num_sat_vehicles),
+        }
+    }
+}
+
+/// Velocity over ground. Used in the `groundVelocity` field of the `lockInfo` response
+#[derive(Clone, Default, GraphQLObject)]
+pub struct GroundVelocity {
+    /// Timestamp of the velocity data
+    pub time: OEMTime,
+    /// Horizontal speed over ground (m/s)
+    pub horizontal_speed: f64,
+    /// Direction of motion over ground with respect to true north (degrees)
+    pub track_ground: f64,
+    /// Vertical speed, where positive values indicate increasing altitude (m/s)
+    pub vertical_speed: f64,
+    /// Latency of the velocity time tag (seconds)
+    pub latency: f64,
+}
+
+impl From<BestVelLog> for GroundVelocity {
+    fn from(log: BestVelLog) -> GroundVelocity {
+        GroundVelocity {
+            time: OEMTime {
+                week: i32::from(log.week),
+                ms: log.ms,
+            },
+            horizontal_speed: log.hor_speed,
+            track_ground: log.track_ground,
+            vertical_speed: log.vert_speed,
+            latency: f64::from(log.latency),
+        }
+    }
+}
+
+/// Receiver clock information. Used in the `clock` field of the `lockInfo` response
+#[derive(Clone, Default, GraphQLObject)]
+pub struct ClockInfo {
+    /// Timestamp of the clock data
+    pub time: OEMTime,
+    /// Receiver clock offset from GPS system time (seconds)
+    pub offset: f64,
+    /// Standard deviation of the receiver clock offset (seconds)
+    pub offset_std: f64,
+    /// Offset of GPS system time from UTC time (seconds)
+    pub utc_offset: f64,
+    /// UTC time matching the timestamp
+    pub utc: UtcTime,
+    /// UTC status. 0 = Invalid, 1 = Valid, 2 = Warning
+    pub utc_status: i32,
+}
+
+impl From<TimeLog> for ClockInfo {
+    fn from(log: TimeLog) -> ClockInfo {
+        ClockInfo {
+            time: OEMTime {
+                week: i32::from(log.week),
+                ms: log.ms,
+            },
+            offset: log.offset,
+            offset_std: log.offset_std,
+            utc_offset: log.utc_offset,
+            utc: UtcTime {
+                year: log.utc_year as i32,
+                month: i32::from(log.utc_month),
+                day: i32::from(log.utc_day),
+                hour: i32::from(log.utc_hour),
+                minute: i32::from(log.utc_min),
+                ms: log.utc_ms as i32,
+            },
+            utc_status: log.utc_status as i32,
+        }
+    }
+}
+
+/// UTC time structure for the `clock` response field
+#[derive(Clone, Default, GraphQLObject)]
+pub struct UtcTime {
+    /// Year
+    pub year: i32,
+    /// Month (0-12). A value of 0 means UTC time is unknown
+    pub month: i32,
+    /// Day (0-31). A value of 0 means UTC time is unknown
+    pub day: i32,
+    /// Hour (0-23)
+    pub hour: i32,
+    /// Minute (0-59)
+    pub minute: i32,
+    /// Milliseconds into the minute (0-60999). Values over 59999 indicate a leap second
+    pub ms: i32,
+}
+
+/// Latest satellite range measurements. Used in the `range` field of the `telemetry` response
+#[derive(Clone, Default, GraphQLObject)]
+pub struct RangeInfo {
+    /// Timestamp of the measurements
+    pub time: OEMTime,
+    /// Measurements for each tracked satellite signal
+    pub observations: Vec<Observation>,
+}
+
+impl From<RangeLog> for RangeInfo {
+    fn from(log: RangeLog) -> RangeInfo {
+        RangeInfo {
+            time: OEMTime {
+                week: i32::from(log.week),
+                ms: log.ms,
+            },
+            observations: log.observations.iter().map(Observation::from).collect(),
+        }
+    }
+}
+
+/// Range measurements for a single satellite signal
+#[derive(Clone, Default, GraphQLObject)]
+pub struct Observation {
+    /// Satellite PRN number
+    pub prn: i32,
+    /// GLONASS frequency + 7. Zero for all other satellite systems
+    pub glonass_freq: i32,
+    /// Pseudorange measurement (m)
+    pub psr: f64,
+    /// Pseudorange measurement standard deviation (m)
+    pub psr_std: f64,
+    /// Carrier phase, in cycles (accumulated Doppler range)
+    pub adr: f64,
+    /// Estimated carrier phase standard deviation (cycles)
+    pub adr_std: f64,
+    /// Instantaneous carrier Doppler frequency (Hz)
+    pub doppler: f64,
+    /// Carrier to noise density ratio (dB-Hz)
+    pub carrier_noise: f64,
+    /// Number of seconds of continuous tracking (no cycle slipping)
+    pub lock_time: f64,
+    /// Tracking status
+    pub tracking_status: i32,
+}
+
+impl<'a> From<&'a RangeObservation> for Observation {
+    fn from(obs: &RangeObservation) -> Observation {
+        Observation {
+            prn: i32::from(obs.prn),
+            glonass_freq: i32::from(obs.glonass_freq),
+            psr: obs.psr,
+            psr_std: f64::from(obs.psr_std),
+            adr: obs.adr,
+            adr_std: f64::from(obs.adr_std),
+            doppler: f64::from(obs.doppler),
+            carrier_noise: f64::from(obs.carrier_noise),
+            lock_time: f64::from(obs.lock_time),
+            tracking_status: obs.tracking_status as i32,
+        }
+    }
+}
+
+/// Decoded GPS ephemeris parameters. Used in the `ephemerides` field of the `telemetry` response
+#[derive(Clone, Default, GraphQLObject)]
+pub struct Ephemeris {
+    /// Timestamp when the ephemeris was received
+    pub time: OEMTime,
+    /// Satellite PRN number
+    pub prn: i32,
+    /// Time stamp of subframe 1 (seconds)
+    pub tow: f64,
+    /// Health status - a 6-bit health code as defined in ICD-GPS-200
+    pub health: i32,
+    /// Issue of ephemeris data 1
+    pub iode1: i32,
+    /// Issue of ephemeris data 2
+    pub iode2: i32,
+    /// GPS reference week number of the ephemeris
+    pub week: i32,
+    /// Z count week number
+    pub z_week: i32,
+    /// Reference time for ephemeris (seconds)
+    pub toe: f64,
+    /// Semi-major axis (m)
+    pub semi_major_axis: f64,
+    /// Mean motion difference (radians/second)
+    pub delta_n: f64,
+    /// Mean anomaly of reference time (radians)
+    pub mean_anomaly: f64,
+    /// Eccentricity
+    pub eccentricity: f64,
+    /// Argument of perigee (radians)
+    pub arg_perigee: f64,
+    /// Amplitude of cosine harmonic correction term to the argument of latitude (radians)
+    pub cuc: f64,
+    /// Amplitude of sine harmonic correction term to the argument of latitude (radians)
+    pub cus: f64,
+    /// Amplitude of cosine harmonic correction term to the orbit radius (m)
+    pub crc: f64,
+    /// Amplitude of sine harmonic correction term to the orbit radius (m)
+    pub crs: f64,
+    /// Amplitude of cosine harmonic correction term to the angle of inclination (radians)
+    pub cic: f64,
+    /// Amplitude of sine harmonic correction term to the angle of inclination (radians)
+    pub cis: f64,
+    /// Inclination angle at reference time (radians)
+    pub inclination: f64,
+    /// Rate of inclination angle (radians/second)
+    pub inclination_rate: f64,
+    /// Right ascension (radians)
+    pub right_ascension: f64,
+    /// Rate of right ascension (radians/second)
+    pub right_ascension_rate: f64,
+    /// Issue of data clock
+    pub iodc: i32,
+    /// SV clock correction term (seconds)
+    pub toc: f64,
+    /// Estimated group delay difference (seconds)
+    pub tgd: f64,
+    /// Clock aging parameter (seconds)
+    pub af0: f64,
+    /// Clock aging parameter (seconds/second)
+    pub af1: f64,
+    /// Clock aging parameter (seconds/second/second)
+    pub af2: f64,
+    /// Anti-spoofing on
+    pub anti_spoofing: bool,
+    /// Corrected mean motion (radians/second)
+    pub corrected_mean_motion: f64,
+    /// User Range Accuracy variance (m^2)
+    pub ura: f64,
+}
+
+impl From<GpsEphemLog> for Ephemeris {
+    fn from(log: GpsEphemLog) -> Ephemeris {
+        Ephemeris {
+            time: OEMTime {
+                week: i32::from(log.week),
+                ms: log.ms,
+            },
+            prn: log.prn as i32,
+            tow: log.tow,
+            health: log.health as i32,
+            iode1: log.iode1 as i32,
+            iode2: log.iode2 as i32,
+            week: log.ephem_week as i32,
+            z_week: log.z_week as i32,
+            toe: log.toe,
+            semi_major_axis: log.semi_major_axis,
+            delta_n: log.delta_n,
+            mean_anomaly: log.mean_anomaly,
+            eccentricity: log.eccentricity,
+            arg_perigee: log.arg_perigee,
+            cuc: log.cuc,
+            cus: log.cus,
+            crc: log.crc,
+            crs: log.crs,
+            cic: log.cic,
+            cis: log.cis,
+            inclination: log.inclination,
+            inclination_rate: log.inclination_rate,
+            right_ascension: log.right_ascension,
+            right_ascension_rate: log.right_ascension_rate,
+            iodc: log.iodc as i32,
+            toc: log.toc,
+            tgd: log.tgd,
+            af0: log.af0,
+            af1: log.af1,
+            af2: log.af2,
+            anti_spoofing: log.anti_spoofing,
+            corrected_mean_motion: log.corrected_mean_motion,
+            ura: log.ura,
+        }
+    }
+}
+
 /// Response field for 'power' query
 #[derive(GraphQLEnum, Clone, Eq, PartialEq, Debug)]
 pub enum PowerState {
@@ -508,6 +1170,10 @@ pub struct TelemetryNominal {
     pub lock_status: Option<LockStatus>,
     /// Last known good lock information
     pub lock_info: Option<LockInfo>,
+    /// Latest satellite range measurements
+    pub range: Option<RangeInfo>,
+    /// Latest ephemeris for each GPS satellite, ordered by PRN
+    pub ephemerides: Vec<Ephemeris>,
 }
 
 /// Version information about the device, returned as the
@@ -17,7 +17,7 @@
 use crate::model::*;
 use crate::objects::*;
 use juniper::FieldResult;
-use kubos_service;
+use kubos_service::ErrorRecord;
 
 type Context = kubos_service::Context<Subsystem>;
 
@@ -46,8 +46,7 @@ graphql_object!(QueryRoot: Context as "Query" |&self| {
     // }
     field ack(&executor) -> FieldResult<AckCommand>
     {
-        let last_cmd = executor.context().subsystem().last_cmd.read()?;
-        Ok(*last_cmd)
+        Ok(executor.context().subsystem().status.ack())
     }
 
     // Get all errors encountered since the last time this field was queried
@@ -59,15 +58,23 @@ graphql_object!(QueryRoot: Context as "Query" |&self| {
     {
         executor.context().subsystem().get_errors();
 
-        match executor.context().subsystem().errors.write() {
-            Ok(mut master_vec) => {
-                let current = master_vec.clone();
-                master_vec.clear();
-                master_vec.shrink_to_fit();
-                Ok(current)
-            },
-            _ => Ok(vec!["Error: Failed to borrow master errors vector".to_owned()])
-        }
+        Ok(executor.context().subsystem().status.take_error_messages())
+    }
+
+    // Get the most recent errors, including ones which have already been read
+    //
+    // {
+    //     errorLog {
+    //         timestamp: Float,
+    //         severity: Severity,
+    //         message: String
+    //     }
+    // }
+    field error_log(&executor) -> FieldResult<Vec<ErrorRecord>>
+    {
+        executor.context().subsystem().get_errors();
+
+        Ok(executor.context().subsystem().status.error_log())
     }
 
     // Get the current power state of the system
@@ -145,10 +152,35 @@ graphql_object!(QueryRoot: Context as "Query" |&self| {
         Ok(executor.context().subsystem().get_lock_status()?)
     }
 
-    // Get the last known good position information
+    // Get the last known good position, velocity, and clock information
     //
     // {
     //     lockInfo {
+    //        clock {
+    //            offset: Float,
+    //            offsetStd: Float,
+    //            time { ms: Int, week: Int },
+    //            utc { day: Int, hour: Int, minute: Int, month: Int, ms: Int, year: Int },
+    //            utcOffset: Float,
+    //            utcStatus: Int
+    //        },
+    //        geodetic {
+    //            datumId: Int,
+    //            deviation: Vec<Float>,
+    //            height: Float,
+    //            lat: Float,
+    //            lon: Float,
+    //            numSatVehicles: Int,
+    //            time { ms: Int, week: Int },
+    //            undulation: Float
+    //        },
+    //        groundVelocity {
+    //            horizontalSpeed: Float,
+    //            latency: Float,
+    //            time { ms: Int, week: Int },
+    //            trackGround: Float,
+    //            verticalSpeed: Float
+    //        },
     //        position: Vec<Float>,
     //        time {
     //            ms: Int,
@@ -171,7 +203,7 @@ graphql_object!(QueryRoot: Context as "Query" |&self| {
     //                 bootVersion: String,
     //                 compType: Int,
     //                 compileDate: String,
-    //                 compileTime: String, 
+    //                 compileTime: String,
     //                 hwVersion: String,
     //                 model: String,
     //                 serialNum: String,
@@ -180,8 +212,27 @@ graphql_object!(QueryRoot: Context as "Query" |&self| {
     //             numComponents: Int
     //         },
     //         nominal{
+    //             ephemerides [{
+    //                 prn: Int,
+    //                 time { ms: Int, week: Int },
+    //                 toe: Float,
+    //                 semiMajorAxis: Float,
+    //                 eccentricity: Float,
+    //                 ...
+    //             }],
     //             lockInfo {...},
     //             lockStatus {...},
+    //             range {
+    //                 observations [{
+    //                     adr: Float,
+    //                     carrierNoise: Float,
+    //                     doppler: Float,
+    //                     prn: Int,
+    //                     psr: Float,
+    //                     ...
+    //                 }],
+    //                 time { ms: Int, week: Int }
+    //             },
     //             systemStatus: {
     //                errors: Vec<String>,
     //                status: Vec<String>
@@ -211,10 +262,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     {
         executor.context().subsystem().get_errors();
 
-        match executor.context().subsystem().errors.read() {
-            Ok(master_vec) => Ok(master_vec.clone()),
-            _ => Ok(vec!["Error: Failed to borrow master errors vector".to_owned()])
-        }
+        Ok(executor.context().subsystem().status.unread_error_messages())
     }
 
     // Execute a trivial command against the system
@@ -227,8 +275,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field noop(&executor) -> FieldResult<GenericResponse>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::Noop;
+        executor.context().subsystem().status.set_ack(AckCommand::Noop);
         Ok(executor.context().subsystem().noop()?)
     }
 
@@ -241,8 +288,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field control_power(&executor) -> FieldResult<String>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::ControlPower;
+        executor.context().subsystem().status.set_ack(AckCommand::ControlPower);
         Ok(String::from("Not Implemented"))
     }
 
@@ -255,15 +301,37 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     //           For `UNLOG_ALL` requests, specifies whether the 'hold' value in previous
     //           `LOG_*` requests should be ignored.
     //   - interval: Interval at which log messages should be generated.
-    //               Note: Only applies to `LOG_POSITION_DATA` requests. Ignored otherwise
+    //               Note: Does not apply to `LOG_ERROR_DATA` or `LOG_EPHEMERIS_DATA` requests,
+    //               which are generated whenever new data is available. Ignored otherwise
     //   - offset: Offset of interval at which log messages should be generated.
-    //             Note: Only applies to `LOG_POSITION_DATA` requests. Ignored otherwise
+    //             Note: Does not apply to `LOG_ERROR_DATA` or `LOG_EPHEMERIS_DATA` requests.
+    //             Ignored otherwise
+    //   - fix: For `FIX` requests, the position constraint to apply
+    //     (fixType, lat, lon, height)
+    //   - assign: For `ASSIGN_CHANNEL` requests, the channel assignment to make
+    //     (channel, state, prn, doppler, dopplerWindow)
+    //   - serial: For `SERIAL_CONFIG` requests, the serial port settings to apply
+    //     (port, baud, parity, dataBits, stopBits, handshake)
+    //   - interfaceMode: For `INTERFACE_MODE` requests, the port protocols to apply
+    //     (port, rxMode, txMode, responses)
+    //   - cutoff: For `ELEVATION_CUTOFF` requests, the elevation angle in degrees
+    //   - resetDelay: For `RESET` requests, the number of seconds to wait before resetting
+    //   - resetTarget: For `FACTORY_RESET` requests, the data which should be cleared
+    //
+    // The requests are applied in order as a single transaction. If one of them fails, the
+    // remaining requests are not sent to the device. The outcome of each request is
+    // reported in `results`
     //
     // mutation {
-    //     configureHardware(config: [{option: ConfigOption, hold: Boolean, interval: Float, offset: Float},...]) {
+    //     configureHardware(config: [{option: ConfigOption, hold: Boolean, interval: Float, offset: Float, ...},...]) {
     //         config: String
     //         errors: String,
     //         success: Boolean,
+    //         results: [{
+    //             option: ConfigOption,
+    //             status: ConfigStatus,
+    //             errors: String
+    //         }]
     //     }
     // }
     field configure_hardware(
@@ -271,8 +339,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
         config: Vec<ConfigStruct>,
     ) -> FieldResult<ConfigureHardwareResponse>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::ConfigureHardware;
+        executor.context().subsystem().status.set_ack(AckCommand::ConfigureHardware);
         Ok(executor.context().subsystem().configure_hardware(config)?)
     }
 
@@ -297,8 +364,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field test_hardware(&executor, test: TestType) -> FieldResult<TestResults>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::TestHardware;
+        executor.context().subsystem().status.set_ack(AckCommand::TestHardware);
         match test {
             TestType::Integration => Ok(TestResults::Integration(executor.context().subsystem()
                     .get_test_results().unwrap())),
@@ -321,8 +387,7 @@ graphql_object!(MutationRoot: Context as "Mutation" |&self| {
     // }
     field issue_raw_command(&executor, command: String) -> FieldResult<GenericResponse>
     {
-        let mut last_cmd = executor.context().subsystem().last_cmd.write()?;
-        *last_cmd = AckCommand::IssueRawCommand;
+        executor.context().subsystem().status.set_ack(AckCommand::IssueRawCommand);
         Ok(executor.context().subsystem().passthrough(command)?)
     }
 });
@@ -20,9 +20,13 @@ use novatel_oem6_api::*;
 #[macro_export]
 macro_rules! service_new {
     ($mock:ident) => {{
+        service_new!($mock, LogFormat::Binary)
+    }};
+    ($mock:ident, $format:expr) => {{
         use crate::objects::AckCommand;
+        use kubos_service::CommandStatus;
         use novatel_oem6_api::Connection;
-        use std::sync::{Arc, Mutex, RwLock};
+        use std::sync::{Arc, Mutex};
         use std::thread;
         use std::time::Duration;
 
@@ -44,7 +48,9 @@ macro_rules! service_new {
 
         let rx_conn = oem.conn.clone();
 
-        thread::spawn(move || read_thread(&rx_conn, &log_send, &response_send));
+        thread::spawn(move || {
+            read_thread_with_format(&rx_conn, &log_send, &response_send, $format)
+        });
 
         let data = Arc::new(LockData::new());
         let (error_send, error_recv) = sync_channel(10);
@@ -64,8 +70,7 @@ macro_rules! service_new {
             Config::new("novatel-oem6-service"),
             Subsystem {
                 oem,
-                last_cmd: Arc::new(RwLock::new(AckCommand::None)),
-                errors: Arc::new(RwLock::new(vec![])),
+                status: CommandStatus::new(AckCommand::None),
                 lock_data: data.clone(),
                 error_recv: Arc::new(Mutex::new(error_recv)),
                 version_recv: Arc::new(Mutex::new(version_recv)),
@@ -59,7 +59,7 @@ fn configure_hardware_bad_multi() {
     let expected = json!({
             "configureHardware": {
                 "config": "LogErrorData(Hold: false), LogPositionData(Hold: false)",
-                "errors": "LogErrorData: UART Error, Generic Error. LogPositionData: UART Error, Generic Error",
+                "errors": "LogErrorData: UART Error, Generic Error",
                 "success": false
             }
     });
@@ -429,3 +429,287 @@ fn configure_hardware_unlog_position() {
 
     test!(service, query, expected);
 }
+
+#[test]
+fn configure_hardware_log_geodetic_position_default() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(LOG_GEODETIC_POSITION_COMMAND.to_vec());
+
+    mock.read.set_output(LOG_RESPONSE_GOOD.to_vec());
+
+    let service = service_new!(mock);
+
+    let query = r#"mutation {
+            configureHardware(config: [{option: LOG_GEODETIC_POSITION_DATA}]) {
+                config,
+                errors,
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "configureHardware": {
+                "config": "LogGeodeticPositionData(Hold: false)",
+                "errors": "",
+                "success": true
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn configure_hardware_log_ephemeris_hold() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(LOG_EPHEMERIS_COMMAND.to_vec());
+
+    mock.read.set_output(LOG_RESPONSE_GOOD.to_vec());
+
+    let service = service_new!(mock);
+
+    let query = r#"mutation {
+            configureHardware(config: [{option: LOG_EPHEMERIS_DATA, hold: true}]) {
+                config,
+                errors,
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "configureHardware": {
+                "config": "LogEphemerisData(Hold: true)",
+                "errors": "",
+                "success": true
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn configure_hardware_multi_good() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(ECUTOFF_COMMAND.to_vec());
+    mock.write.set_input(SAVE_CONFIG_COMMAND.to_vec());
+
+    let mut output = ECUTOFF_RESPONSE_GOOD.to_vec();
+    output.extend_from_slice(&SAVE_CONFIG_RESPONSE_GOOD);
+    mock.read.set_output(output);
+
+    let service = service_new!(mock);
+
+    let query = r#"mutation {
+            configureHardware(config: [{option: ELEVATION_CUTOFF, cutoff: 5.0}, {option: SAVE_CONFIG}]) {
+                config,
+                errors,
+                results {
+                    errors,
+                    option,
+                    status
+                },
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "configureHardware": {
+                "config": "ElevationCutoff(5deg), SaveConfig",
+                "errors": "",
+                "results": [
+                    {
+                        "errors": "",
+                        "option": "ELEVATION_CUTOFF",
+                        "status": "APPLIED"
+                    },
+                    {
+                        "errors": "",
+                        "option": "SAVE_CONFIG",
+                        "status": "APPLIED"
+                    }
+                ],
+                "success": true
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn configure_hardware_multi_command_error() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(FIX_COMMAND.to_vec());
+
+    mock.read.set_output(FIX_RESPONSE_BAD.to_vec());
+
+    let service = service_new!(mock);
+
+    let query = r#"mutation {
+            configureHardware(config: [
+                {option: FIX, fix: {fixType: POSITION, lat: 51.125, lon: -114.0625, height: 1064.5}},
+                {option: SAVE_CONFIG}
+            ]) {
+                config,
+                errors,
+                results {
+                    errors,
+                    option,
+                    status
+                },
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "configureHardware": {
+                "config": "Fix(Position: 51.125, -114.0625, 1064.5m), SaveConfig",
+                "errors": "Fix: Command Error(ErrorField): Field lat out of range",
+                "results": [
+                    {
+                        "errors": "Command Error(ErrorField): Field lat out of range",
+                        "option": "FIX",
+                        "status": "FAILED"
+                    },
+                    {
+                        "errors": "",
+                        "option": "SAVE_CONFIG",
+                        "status": "SKIPPED"
+                    }
+                ],
+                "success": false
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn configure_hardware_missing_parameter() {
+    let mut mock = MockStream::default();
+
+    let service = service_new!(mock);
+
+    let query = r#"mutation {
+            configureHardware(config: [
+                {option: ELEVATION_CUTOFF, cutoff: 5.0},
+                {option: SERIAL_CONFIG},
+                {option: SAVE_CONFIG}
+            ]) {
+                errors,
+                results {
+                    errors,
+                    option,
+                    status
+                },
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "configureHardware": {
+                "errors": "SerialConfig: Missing 'serial' parameter",
+                "results": [
+                    {
+                        "errors": "",
+                        "option": "ELEVATION_CUTOFF",
+                        "status": "SKIPPED"
+                    },
+                    {
+                        "errors": "Missing 'serial' parameter",
+                        "option": "SERIAL_CONFIG",
+                        "status": "FAILED"
+                    },
+                    {
+                        "errors": "",
+                        "option": "SAVE_CONFIG",
+                        "status": "SKIPPED"
+                    }
+                ],
+                "success": false
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn configure_hardware_negative_parameter() {
+    let mut mock = MockStream::default();
+
+    let service = service_new!(mock);
+
+    let query = r#"mutation {
+            configureHardware(config: [
+                {option: ELEVATION_CUTOFF, cutoff: 5.0},
+                {option: ASSIGN_CHANNEL, assign: {channel: -1, state: ACTIVE, prn: 5}}
+            ]) {
+                errors,
+                results {
+                    option,
+                    status
+                },
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "configureHardware": {
+                "errors": "AssignChannel: Invalid 'channel' parameter: -1",
+                "results": [
+                    {
+                        "option": "ELEVATION_CUTOFF",
+                        "status": "SKIPPED"
+                    },
+                    {
+                        "option": "ASSIGN_CHANNEL",
+                        "status": "FAILED"
+                    }
+                ],
+                "success": false
+            }
+    });
+
+    test!(service, query, expected);
+
+    let query = r#"{
+            errors
+        }"#;
+
+    let expected = json!({
+            "errors": ["AssignChannel: Invalid 'channel' parameter: -1"]
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn configure_hardware_serial_config() {
+    let mut mock = MockStream::default();
+
+    mock.write.set_input(SERIAL_CONFIG_COMMAND.to_vec());
+
+    mock.read.set_output(SERIAL_CONFIG_RESPONSE_GOOD.to_vec());
+
+    let service = service_new!(mock);
+
+    let query = r#"mutation {
+            configureHardware(config: [{option: SERIAL_CONFIG, serial: {port: COM2, baud: 115200}}]) {
+                config,
+                errors,
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "configureHardware": {
+                "config": "SerialConfig(Com2: 115200 None 8/1 None)",
+                "errors": "",
+                "success": true
+            }
+    });
+
+    test!(service, query, expected);
+}
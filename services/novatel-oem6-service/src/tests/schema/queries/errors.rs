@@ -196,3 +196,37 @@ fn query_errors_clear_after_query() {
 
     test!(service, query, expected);
 }
+
+#[test]
+fn query_error_log() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(ERROR_LOG.to_vec());
+
+    let service = service_new!(mock);
+
+    let query = r#"{
+            errors
+        }"#;
+
+    request!(service, query);
+
+    // The log keeps errors which have already been read
+    let query = r#"{
+            errorLog {
+                message,
+                severity
+            }
+        }"#;
+
+    let expected = json!({
+            "errorLog": [
+                {
+                    "message": "RxStatusEvent(1, 19, 1): No Valid Position Calculated",
+                    "severity": "ERROR"
+                }
+            ]
+    });
+
+    test!(service, query, expected);
+}
@@ -147,3 +147,219 @@ fn get_lock_info_nolock_after_good() {
 
     test!(service, query, expected);
 }
+
+#[test]
+fn get_lock_info_geodetic() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(BEST_POS_LOG_GOOD.to_vec());
+
+    let service = service_new!(mock);
+
+    let query = r#"{
+            lockInfo {
+                geodetic {
+                    datumId,
+                    deviation,
+                    height,
+                    lat,
+                    lon,
+                    numSatVehicles,
+                    time {
+                        ms,
+                        week
+                    },
+                    undulation
+                }
+            }
+        }"#;
+
+    let expected = json!({
+            "lockInfo": {
+                "geodetic": {
+                    "datumId": 61,
+                    "deviation": [1.5, 1.25, 3.0],
+                    "height": 1064.5,
+                    "lat": 51.125,
+                    "lon": -114.0625,
+                    "numSatVehicles": 9,
+                    "time": {
+                        "ms": 334713000,
+                        "week": 2025
+                    },
+                    "undulation": -16.25
+                }
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn get_lock_info_ground_velocity() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(BEST_VEL_LOG_GOOD.to_vec());
+
+    let service = service_new!(mock);
+
+    let query = r#"{
+            lockInfo {
+                groundVelocity {
+                    horizontalSpeed,
+                    latency,
+                    time {
+                        ms,
+                        week
+                    },
+                    trackGround,
+                    verticalSpeed
+                }
+            }
+        }"#;
+
+    let expected = json!({
+            "lockInfo": {
+                "groundVelocity": {
+                    "horizontalSpeed": 7612.25,
+                    "latency": 0.125,
+                    "time": {
+                        "ms": 334713000,
+                        "week": 2025
+                    },
+                    "trackGround": 312.5,
+                    "verticalSpeed": -3.75
+                }
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn get_lock_info_clock() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(TIME_LOG_GOOD.to_vec());
+
+    let service = service_new!(mock);
+
+    let query = r#"{
+            lockInfo {
+                clock {
+                    offset,
+                    offsetStd,
+                    time {
+                        ms,
+                        week
+                    },
+                    utc {
+                        day,
+                        hour,
+                        minute,
+                        month,
+                        ms,
+                        year
+                    },
+                    utcOffset,
+                    utcStatus
+                }
+            }
+        }"#;
+
+    let expected = json!({
+            "lockInfo": {
+                "clock": {
+                    "offset": -0.5,
+                    "offsetStd": 0.25,
+                    "time": {
+                        "ms": 334713000,
+                        "week": 2025
+                    },
+                    "utc": {
+                        "day": 31,
+                        "hour": 20,
+                        "minute": 58,
+                        "month": 10,
+                        "ms": 15000,
+                        "year": 2018
+                    },
+                    "utcOffset": -18.0,
+                    "utcStatus": 1
+                }
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn get_lock_info_no_geodetic() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(POSITION_LOG_GOOD.to_vec());
+
+    let service = service_new!(mock);
+
+    let query = r#"{
+            lockInfo {
+                clock {
+                    offset
+                },
+                geodetic {
+                    lat
+                },
+                groundVelocity {
+                    horizontalSpeed
+                }
+            }
+        }"#;
+
+    let expected = json!({
+            "lockInfo": {
+                "clock": null,
+                "geodetic": null,
+                "groundVelocity": null
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn get_lock_info_nmea() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(
+        b"$GPGGA,202530.00,5107.5000,N,11403.7500,W,2,9,0.9,1064.5,M,-16.25,M,2.0,0123*4F\r\n"
+            .to_vec(),
+    );
+
+    let service = service_new!(mock, LogFormat::Nmea);
+
+    let query = r#"{
+            lockInfo {
+                geodetic {
+                    height,
+                    lat,
+                    lon,
+                    numSatVehicles,
+                    undulation
+                }
+            }
+        }"#;
+
+    let expected = json!({
+            "lockInfo": {
+                "geodetic": {
+                    "height": 1064.5,
+                    "lat": 51.125,
+                    "lon": -114.0625,
+                    "numSatVehicles": 9,
+                    "undulation": -16.25
+                }
+            }
+    });
+
+    test!(service, query, expected);
+}
@@ -358,3 +358,104 @@ fn get_telemetry_empty() {
 
     test!(service, query, expected);
 }
+
+#[test]
+fn get_telemetry_range() {
+    let mut mock = MockStream::default();
+
+    mock.read.set_output(RANGE_LOG_GOOD.to_vec());
+
+    let service = service_new!(mock);
+
+    let query = r#"{
+            telemetry{
+                nominal{
+                    range {
+                        observations {
+                            adr,
+                            carrierNoise,
+                            doppler,
+                            lockTime,
+                            prn,
+                            psr
+                        },
+                        time {
+                            ms,
+                            week
+                        }
+                    }
+                }
+            }
+        }"#;
+
+    let expected = json!({
+            "telemetry": {
+                "nominal": {
+                    "range": {
+                        "observations": [{
+                            "adr": -110183623.5,
+                            "carrierNoise": 47.25,
+                            "doppler": -2894.5,
+                            "lockTime": 1523.5,
+                            "prn": 3,
+                            "psr": 20967354.125
+                        }],
+                        "time": {
+                            "ms": 334713000,
+                            "week": 2025
+                        }
+                    }
+                }
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn get_telemetry_ephemerides() {
+    let mut mock = MockStream::default();
+
+    let mut output = GPS_EPHEM_LOG_PRN_22.to_vec();
+    output.extend_from_slice(&GPS_EPHEM_LOG_PRN_3);
+    output.extend_from_slice(&GPS_EPHEM_LOG_PRN_3_UPDATED);
+    mock.read.set_output(output);
+
+    let service = service_new!(mock);
+
+    let query = r#"{
+            telemetry{
+                nominal{
+                    ephemerides {
+                        prn,
+                        semiMajorAxis,
+                        toe,
+                        week
+                    }
+                }
+            }
+        }"#;
+
+    let expected = json!({
+            "telemetry": {
+                "nominal": {
+                    "ephemerides": [
+                        {
+                            "prn": 3,
+                            "semiMajorAxis": 26560240.25,
+                            "toe": 410400.0,
+                            "week": 2025
+                        },
+                        {
+                            "prn": 22,
+                            "semiMajorAxis": 26559978.5,
+                            "toe": 403200.0,
+                            "week": 2025
+                        }
+                    ]
+                }
+            }
+    });
+
+    test!(service, query, expected);
+}
@@ -84,3 +84,149 @@ pub const POSITION_LOG_GOOD: [u8; 144] = [
     0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
     0x0, 0x4A, 0xF9, 0x0D, 0x23,
 ];
+pub const BEST_POS_LOG_GOOD: [u8; 104] = [
+    0xAA, 0x44, 0x12, 0x1C, 0x2A, 0x0, 0x0, 0x20, 0x48, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7, 0xA8,
+    0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x0, 0x0, 0x0, 0x0, 0x10, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x90, 0x49, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x84, 0x5C, 0xC0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0xA2, 0x90, 0x40, 0x0, 0x0, 0x82, 0xC1, 0x3D, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0xC0, 0x3F, 0x0, 0x0, 0xA0, 0x3F, 0x0, 0x0, 0x40, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0xA, 0x9, 0x9, 0x0, 0x0, 0x6, 0x0, 0x1, 0x73, 0xBB, 0xE2, 0xCB,
+];
+
+pub const BEST_VEL_LOG_GOOD: [u8; 76] = [
+    0xAA, 0x44, 0x12, 0x1C, 0x63, 0x0, 0x0, 0x20, 0x2C, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7, 0xA8,
+    0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x0, 0x0, 0x0, 0x0, 0x10, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x3E, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x40, 0xBC, 0xBD, 0x40, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x88, 0x73, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xE, 0xC0, 0x0, 0x0, 0x0,
+    0x0, 0xD2, 0x17, 0xE0, 0xEA,
+];
+
+pub const TIME_LOG_GOOD: [u8; 76] = [
+    0xAA, 0x44, 0x12, 0x1C, 0x65, 0x0, 0x0, 0x20, 0x2C, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7, 0xA8,
+    0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0xE0, 0xBF, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xD0, 0x3F, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x32, 0xC0, 0xE2, 0x7, 0x0, 0x0, 0xA, 0x1F, 0x14, 0x3A, 0x98, 0x3A, 0x0, 0x0, 0x1, 0x0,
+    0x0, 0x0, 0xBD, 0x41, 0xF7, 0x15,
+];
+
+pub const RANGE_LOG_GOOD: [u8; 80] = [
+    0xAA, 0x44, 0x12, 0x1C, 0x2B, 0x0, 0x0, 0x20, 0x30, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7, 0xA8,
+    0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x1, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0xA2, 0xFB, 0xFE, 0x73, 0x41, 0x0, 0x0, 0x80, 0x3D, 0x0, 0x0, 0x0, 0x1E,
+    0x13, 0x45, 0x9A, 0xC1, 0x0, 0x0, 0x0, 0x3C, 0x0, 0xE8, 0x34, 0xC5, 0x0, 0x0, 0x3D, 0x42, 0x0,
+    0x70, 0xBE, 0x44, 0x4, 0x9C, 0x10, 0x8, 0xCF, 0xF1, 0x6A, 0xDF,
+];
+
+pub const GPS_EPHEM_LOG_PRN_22: [u8; 256] = [
+    0xAA, 0x44, 0x12, 0x1C, 0x7, 0x0, 0x0, 0x20, 0xE0, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7, 0xA8,
+    0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x16, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0xE0, 0x47, 0x18, 0x41, 0x0, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0xE9,
+    0x7, 0x0, 0x0, 0xE9, 0x7, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x9C, 0x18, 0x41, 0x0, 0x0, 0x0,
+    0xA8, 0x5E, 0x54, 0x79, 0x41, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x9C, 0x18, 0x41, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x10, 0x40, 0x7B, 0x3D, 0xC0, 0xE6,
+];
+
+pub const GPS_EPHEM_LOG_PRN_3: [u8; 256] = [
+    0xAA, 0x44, 0x12, 0x1C, 0x7, 0x0, 0x0, 0x20, 0xE0, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7, 0xA8,
+    0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0xE0, 0x47, 0x18, 0x41, 0x0, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0xE9,
+    0x7, 0x0, 0x0, 0xE9, 0x7, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x9C, 0x18, 0x41, 0x0, 0x0, 0x0,
+    0x92, 0x6E, 0x54, 0x79, 0x41, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x9C, 0x18, 0x41, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x10, 0x40, 0x6B, 0xB4, 0xA2, 0x9,
+];
+
+pub const GPS_EPHEM_LOG_PRN_3_UPDATED: [u8; 256] = [
+    0xAA, 0x44, 0x12, 0x1C, 0x7, 0x0, 0x0, 0x20, 0xE0, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7, 0xA8,
+    0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0xE0, 0x47, 0x18, 0x41, 0x0, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0xE9,
+    0x7, 0x0, 0x0, 0xE9, 0x7, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x80, 0xC, 0x19, 0x41, 0x0, 0x0, 0x0,
+    0x4, 0x6F, 0x54, 0x79, 0x41, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x80, 0xC, 0x19, 0x41, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x10, 0x40, 0x47, 0xE0, 0x1E, 0x40,
+];
+
+pub const LOG_GEODETIC_POSITION_COMMAND: [u8; 64] = [
+    0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x0, 0xC0, 0x20, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x20, 0x0, 0x0, 0x0, 0x2A, 0x0, 0x0, 0x0,
+    0x4, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x8B, 0x95, 0x95, 0x35,
+];
+
+pub const LOG_EPHEMERIS_COMMAND: [u8; 64] = [
+    0xAA, 0x44, 0x12, 0x1C, 0x1, 0x0, 0x0, 0xC0, 0x20, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x20, 0x0, 0x0, 0x0, 0x7, 0x0, 0x0, 0x0, 0x1,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x1, 0x0, 0x0, 0x0, 0xB7, 0x4B, 0x78, 0x59,
+];
+
+pub const ECUTOFF_COMMAND: [u8; 36] = [
+    0xAA, 0x44, 0x12, 0x1C, 0x32, 0x0, 0x0, 0xC0, 0x4, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xA0, 0x40, 0x1F, 0x99, 0x29, 0xC1,
+];
+
+pub const ECUTOFF_RESPONSE_GOOD: [u8; 38] = [
+    0xAA, 0x44, 0x12, 0x1C, 0x32, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7, 0xA8,
+    0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x1, 0x0, 0x0, 0x0, 0x4F, 0x4B,
+    0x5D, 0xC9, 0x41, 0xBB,
+];
+
+pub const SAVE_CONFIG_COMMAND: [u8; 32] = [
+    0xAA, 0x44, 0x12, 0x1C, 0x13, 0x0, 0x0, 0xC0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xD0, 0x17, 0xF9, 0xF0,
+];
+
+pub const SAVE_CONFIG_RESPONSE_GOOD: [u8; 38] = [
+    0xAA, 0x44, 0x12, 0x1C, 0x13, 0x0, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7, 0xA8,
+    0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x1, 0x0, 0x0, 0x0, 0x4F, 0x4B,
+    0x51, 0x52, 0xF9, 0x66,
+];
+
+pub const FIX_COMMAND: [u8; 60] = [
+    0xAA, 0x44, 0x12, 0x1C, 0x2C, 0x0, 0x0, 0xC0, 0x1C, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x90, 0x49, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x84, 0x5C, 0xC0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0xA2, 0x90, 0x40, 0x4A, 0x1D, 0xC6, 0xA7,
+];
+
+pub const FIX_RESPONSE_BAD: [u8; 58] = [
+    0xAA, 0x44, 0x12, 0x1C, 0x2C, 0x0, 0x80, 0x20, 0x1A, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7,
+    0xA8, 0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0xB, 0x0, 0x0, 0x0, 0x46,
+    0x69, 0x65, 0x6C, 0x64, 0x20, 0x6C, 0x61, 0x74, 0x20, 0x6F, 0x75, 0x74, 0x20, 0x6F, 0x66, 0x20,
+    0x72, 0x61, 0x6E, 0x67, 0x65, 0x94, 0xC7, 0x2C, 0x60,
+];
+
+pub const SERIAL_CONFIG_COMMAND: [u8; 60] = [
+    0xAA, 0x44, 0x12, 0x1C, 0xDE, 0x4, 0x0, 0xC0, 0x1C, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x2, 0x0, 0x0, 0x0, 0x0, 0xC2, 0x1, 0x0,
+    0x0, 0x0, 0x0, 0x0, 0x8, 0x0, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+    0x0, 0xEC, 0xFE, 0x4B, 0x3F,
+];
+
+pub const SERIAL_CONFIG_RESPONSE_GOOD: [u8; 38] = [
+    0xAA, 0x44, 0x12, 0x1C, 0xDE, 0x4, 0x80, 0x20, 0x6, 0x0, 0x0, 0x0, 0x6E, 0xB4, 0xE9, 0x7, 0xA8,
+    0x50, 0xF3, 0x13, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xFD, 0xFB, 0x1, 0x0, 0x0, 0x0, 0x4F, 0x4B,
+    0x72, 0x5B, 0x40, 0xED,
+];
@@ -0,0 +1,180 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//!
+//! Radio functionality for use in conjunction with the communications service library.
+//!
+//! Uplinked packets are received by the modem as files or messages, which wait in its upload
+//! queues until they are read. Downlinked packets are added to the modem's download queue as
+//! files, which the modem sends to the ground the next time it makes a connection.
+//!
+
+use comms_service::{CommsResult, CommsServiceError};
+use failure::bail;
+use log::{error, info};
+use nsl_duplex_d2::{DuplexD2, File};
+use radio_api::{RadioResult, Stream};
+use rust_uart::{Connection, UartResult};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+// How often to check the modem's upload queues for new packets
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+// How long to wait for more response bytes from the modem
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Connection to the radio, shared between the communications service and the GraphQL interface
+pub type DuplexConn = Arc<Mutex<DuplexComms>>;
+
+/// Duplex-D2 radio, along with the state needed to send packets through it
+pub struct DuplexComms {
+    /// The underlying radio
+    pub radio: DuplexD2,
+    // Number of packets downlinked so far. Used to give each download file a unique name
+    downlink_count: u32,
+}
+
+impl DuplexComms {
+    /// Create a new connection using the given radio
+    pub fn new(radio: DuplexD2) -> Self {
+        DuplexComms {
+            radio,
+            downlink_count: 0,
+        }
+    }
+}
+
+/// Byte stream used to talk to the radio over a UART connection
+pub struct UartStream {
+    conn: Connection,
+}
+
+impl UartStream {
+    /// Create a stream using an existing UART connection
+    pub fn new(conn: Connection) -> Self {
+        UartStream { conn }
+    }
+
+    /// Open the given serial device using the radio's UART settings
+    pub fn from_path(bus: &str) -> UartResult<Self> {
+        let settings = serial::PortSettings {
+            baud_rate: serial::Baud38400,
+            char_size: serial::Bits8,
+            parity: serial::ParityNone,
+            stop_bits: serial::Stop1,
+            flow_control: serial::FlowNone,
+        };
+
+        Ok(UartStream::new(Connection::from_path(
+            bus,
+            settings,
+            Duration::from_secs(1),
+        )?))
+    }
+}
+
+impl Stream for UartStream {
+    fn write(&self, data: &[u8]) -> RadioResult<()> {
+        Ok(self.conn.write(data)?)
+    }
+
+    // Read everything the radio has sent, stopping once no more bytes arrive
+    fn read(&self) -> RadioResult<Vec<u8>> {
+        let mut response = vec![];
+        loop {
+            match self.conn.read(1, READ_TIMEOUT) {
+                Ok(mut data) => response.append(&mut data),
+                Err(err) => {
+                    if response.is_empty() {
+                        return Err(err.into());
+                    }
+                    return Ok(response);
+                }
+            }
+        }
+    }
+}
+
+fn lock(conn: &DuplexConn) -> CommsResult<MutexGuard<DuplexComms>> {
+    conn.lock()
+        .map_err(|_| CommsServiceError::MutexPoisoned.into())
+}
+
+// Fetch the next packet waiting in the modem's upload queues, if there is one.
+// Files are checked before messages
+fn poll_upload(conn: &DuplexConn) -> CommsResult<Option<Vec<u8>>> {
+    let comms = lock(conn)?;
+
+    if comms.radio.get_uploaded_file_count()? > 0 {
+        return Ok(Some(comms.radio.get_uploaded_file()?.body));
+    }
+
+    if comms.radio.get_uploaded_message_count()? > 0 {
+        return Ok(Some(comms.radio.get_uploaded_message()?.body));
+    }
+
+    Ok(None)
+}
+
+// Check the modem for an uplinked packet.
+// Errors are retried by the caller, rather than being returned to the communications
+// service, which would retry and record them as fast as it could. Each error is only
+// logged when it first appears, so that a dead modem doesn't flood the log
+pub(crate) fn poll(conn: &DuplexConn, last_error: &mut Option<String>) -> Option<Vec<u8>> {
+    match poll_upload(conn) {
+        Ok(packet) => {
+            if last_error.take().is_some() {
+                info!("Radio is responding again");
+            }
+            packet
+        }
+        Err(err) => {
+            let err = err.to_string();
+            if last_error.as_ref() != Some(&err) {
+                error!("Failed to check radio for uplinked packets: {}", err);
+                *last_error = Some(err);
+            }
+            None
+        }
+    }
+}
+
+/// Read the next uplinked packet, waiting until one is available
+pub fn read(conn: &DuplexConn) -> CommsResult<Vec<u8>> {
+    let mut last_error = None;
+    loop {
+        if let Some(packet) = poll(conn, &mut last_error) {
+            return Ok(packet);
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Add a packet to the modem's download queue
+pub fn write(conn: &DuplexConn, data: &[u8]) -> CommsResult<()> {
+    let mut comms = lock(conn)?;
+
+    let file = File::new(&format!("{:08}.udp", comms.downlink_count), data);
+    comms.downlink_count = comms.downlink_count.wrapping_add(1);
+
+    if !comms.radio.put_download_file(&file)? {
+        bail!("Radio rejected downlink packet");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,190 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+#![deny(missing_docs)]
+#![deny(warnings)]
+
+//! Kubos Service for using the [NSL EyeStar-D2 Duplex radio](https://nearspacelaunch.com/product/eyestar-d2/)
+//! as a communications link to the ground.
+//!
+//! The service starts a communication service which passes UDP packets through the radio,
+//! along with a GraphQL interface for the radio's state of health and geolocation.
+//!
+//! Packets uplinked from the ground arrive as files or messages in the modem's upload queues.
+//! The service polls these queues and hands each packet to the communication service.
+//! Packets being downlinked are added to the modem's download queue as files.
+//!
+//! # Configuration
+//!
+//! The service must be configured in `/home/system/etc/config.toml` with the following fields:
+//!
+//! - `[nsl-duplex-service.addr]`
+//!
+//!     - `ip` - Specifies the service's IP address
+//!     - `port` - Specifies the port on which the service will be listening for UDP packets
+//!
+//! - `[nsl-duplex-service]`
+//!
+//!     - `bus` - Specifies the UART bus the radio is connected to
+//!
+//! - `[nsl-duplex-service.comms]`
+//!
+//!     - The communication service settings. See the `comms-service` documentation for details.
+//!
+//! For example:
+//!
+//! ```toml
+//! [nsl-duplex-service.addr]
+//! ip = "0.0.0.0"
+//! port = 8140
+//!
+//! [nsl-duplex-service]
+//! bus = "/dev/ttyUSB0"
+//!
+//! [nsl-duplex-service.comms]
+//! max_num_handlers = 50
+//! downlink_ports = [14011]
+//! ground_port = 14001
+//! timeout = 1500
+//! ground_ip = "192.168.8.1"
+//! satellite_ip = "192.168.8.2"
+//! ```
+//!
+//! # Starting the Service
+//!
+//! The service should be started automatically by its init script, but may also be started manually:
+//!
+//! ```shell
+//! $ nsl-duplex-service
+//! Listening on: 0.0.0.0:8140
+//! ```
+//!
+//! # Queries
+//!
+//! ```json
+//! {
+//!     ping: "pong"
+//!     alive: Boolean!
+//!     stateOfHealth {
+//!         resetCount: Int!
+//!         currentTime: Int!
+//!         currentRssi: Int!
+//!         connectionStatus: Int!
+//!         globalstarGateway: Int!
+//!         lastContactTime: Int!
+//!         lastAttemptTime: Int!
+//!         callAttemptsSinceReset: Int!
+//!         successfulConnectsSinceReset: Int!
+//!         averageConnectionDuration: Int!
+//!         connectionDurationStdDev: Int!
+//!     }
+//!     geolocation {
+//!         lat: Float!
+//!         lon: Float!
+//!         time: Float!
+//!         maxError: Int!
+//!     }
+//!     queues {
+//!         uploadedFiles: Int!
+//!         uploadedMessages: Int!
+//!         downloadFiles: Int!
+//!     }
+//!     failedPacketsUp: Int!
+//!     failedPacketsDown: Int!
+//!     packetsUp: Int!
+//!     packetsDown: Int!
+//!     errors: [String]
+//! }
+//! ```
+//!
+//! # Mutations
+//!
+//! ```json
+//! mutation {
+//!     noop {
+//!         errors: String!
+//!         success: Boolean!
+//!     }
+//!     clearDownloadQueue: Int!
+//! }
+//! ```
+
+mod comms;
+mod model;
+mod objects;
+mod schema;
+#[cfg(test)]
+mod tests;
+
+use crate::comms::*;
+use crate::model::Subsystem;
+use crate::schema::{MutationRoot, QueryRoot};
+use comms_service::*;
+use failure::Error;
+use kubos_service::{Config, Service};
+use log::info;
+use nsl_duplex_d2::DuplexD2;
+use std::sync::{Arc, Mutex};
+use syslog::Facility;
+
+type DuplexServiceResult<T> = Result<T, Error>;
+
+fn main() -> DuplexServiceResult<()> {
+    syslog::init(
+        Facility::LOG_DAEMON,
+        log::LevelFilter::Debug,
+        Some("nsl-duplex-service"),
+    )
+    .unwrap();
+
+    let service_config = Config::new("nsl-duplex-service");
+
+    let bus = service_config
+        .get("bus")
+        .expect("No 'bus' value found in 'nsl-duplex-service' section of config");
+    let bus = bus.as_str().unwrap();
+
+    // Read configuration from config file.
+    let comms_config = CommsConfig::new(service_config.clone())?;
+
+    // Open the radio's serial port.
+    let stream = UartStream::from_path(bus)?;
+    let radio = DuplexD2::new(radio_api::Connection::new(Box::new(stream)));
+    let conn = Arc::new(Mutex::new(DuplexComms::new(radio)));
+
+    // Control block to configure communication service.
+    let controls = CommsControlBlock::new(
+        Some(Arc::new(read)),
+        vec![Arc::new(write)],
+        conn.clone(),
+        conn.clone(),
+        comms_config,
+    )?;
+
+    // Initialize new `CommsTelemetry` object.
+    let telem = Arc::new(Mutex::new(CommsTelemetry::default()));
+
+    // Start communication service.
+    info!("NSL Duplex Communications Service starting on {}", bus);
+    CommsService::start(controls, &telem)?;
+
+    let subsystem = Subsystem::new(conn, telem);
+    Service::new(service_config, subsystem, QueryRoot, MutationRoot)
+        .version(env!("CARGO_PKG_VERSION"))
+        .start();
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//!
+//! Data model used to expose the radio and the communications service
+//! telemetry over the GraphQL interface.
+//!
+
+use crate::comms::{DuplexComms, DuplexConn};
+use crate::objects::*;
+use comms_service::CommsTelemetry;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[derive(Clone)]
+pub struct Subsystem {
+    conn: DuplexConn,
+    telem: Arc<Mutex<CommsTelemetry>>,
+}
+
+impl Subsystem {
+    pub fn new(conn: DuplexConn, telem: Arc<Mutex<CommsTelemetry>>) -> Subsystem {
+        Subsystem { conn, telem }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<DuplexComms>, String> {
+        self.conn
+            .lock()
+            .map_err(|_| "Failed to lock radio".to_owned())
+    }
+
+    fn telemetry(&self) -> Result<MutexGuard<CommsTelemetry>, String> {
+        self.telem
+            .lock()
+            .map_err(|_| "Failed to lock telemetry".to_owned())
+    }
+
+    pub fn alive(&self) -> Result<bool, String> {
+        self.lock()?
+            .radio
+            .get_alive()
+            .map_err(|err| err.to_string())
+    }
+
+    pub fn state_of_health(&self) -> Result<StateOfHealth, String> {
+        self.lock()?
+            .radio
+            .get_state_of_health_for_modem()
+            .map(|soh| soh.into())
+            .map_err(|err| err.to_string())
+    }
+
+    pub fn geolocation(&self) -> Result<GeoRecord, String> {
+        self.lock()?
+            .radio
+            .get_geolocation_position_estimate()
+            .map(|geo| geo.into())
+            .map_err(|err| err.to_string())
+    }
+
+    pub fn queues(&self) -> Result<QueueCounts, String> {
+        let comms = self.lock()?;
+        let counts = || -> Result<QueueCounts, failure::Error> {
+            Ok(QueueCounts {
+                uploaded_files: comms.radio.get_uploaded_file_count()? as i32,
+                uploaded_messages: comms.radio.get_uploaded_message_count()? as i32,
+                download_files: comms.radio.get_download_file_count()? as i32,
+            })
+        };
+        counts().map_err(|err| err.to_string())
+    }
+
+    pub fn noop(&self) -> MutationResponse {
+        match self.alive() {
+            Ok(true) => MutationResponse {
+                errors: "".to_owned(),
+                success: true,
+            },
+            Ok(false) => MutationResponse {
+                errors: "Radio did not acknowledge".to_owned(),
+                success: false,
+            },
+            Err(errors) => MutationResponse {
+                errors,
+                success: false,
+            },
+        }
+    }
+
+    pub fn clear_download_queue(&self) -> Result<i32, String> {
+        self.lock()?
+            .radio
+            .delete_download_files()
+            .map(|count| count as i32)
+            .map_err(|err| err.to_string())
+    }
+
+    pub fn failed_packets_up(&self) -> Result<i32, String> {
+        Ok(self.telemetry()?.failed_packets_up)
+    }
+
+    pub fn failed_packets_down(&self) -> Result<i32, String> {
+        Ok(self.telemetry()?.failed_packets_down)
+    }
+
+    pub fn packets_up(&self) -> Result<i32, String> {
+        Ok(self.telemetry()?.packets_up)
+    }
+
+    pub fn packets_down(&self) -> Result<i32, String> {
+        Ok(self.telemetry()?.packets_down)
+    }
+
+    pub fn errors(&self) -> Result<Vec<String>, String> {
+        Ok(self.telemetry()?.errors.to_owned())
+    }
+}
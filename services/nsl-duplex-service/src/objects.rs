@@ -0,0 +1,112 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//!
+//! GraphQL objects used to expose the radio's state of health and geolocation
+//!
+
+use juniper::GraphQLObject;
+use nsl_duplex_d2 as api;
+
+/// Record of information regarding the modem's functioning
+#[derive(Clone, Debug, GraphQLObject, PartialEq)]
+pub struct StateOfHealth {
+    /// Current epoch reset count. Incremented for each power system reset
+    pub reset_count: i32,
+    /// Current time (seconds) from start of most recent reset
+    pub current_time: i32,
+    /// Current RSSI (Received Signal Strength Indicator), 0 to 4
+    pub current_rssi: i32,
+    /// Connection status, 0 (connected) or 1 (disconnected)
+    pub connection_status: i32,
+    /// Globalstar gateway connected to, proprietary ID, 0 to 255
+    pub globalstar_gateway: i32,
+    /// Last contact time, seconds since latest reset
+    pub last_contact_time: i32,
+    /// Last attempt time, seconds since latest reset
+    pub last_attempt_time: i32,
+    /// Count of call attempts since latest reset
+    pub call_attempts_since_reset: i32,
+    /// Count of successful connects since latest reset
+    pub successful_connects_since_reset: i32,
+    /// Average connection duration (seconds)
+    pub average_connection_duration: i32,
+    /// Connection duration standard deviation (seconds)
+    pub connection_duration_std_dev: i32,
+}
+
+impl From<api::StateOfHealth> for StateOfHealth {
+    fn from(soh: api::StateOfHealth) -> StateOfHealth {
+        StateOfHealth {
+            reset_count: soh.reset_count as i32,
+            current_time: soh.current_time as i32,
+            current_rssi: i32::from(soh.current_rssi),
+            connection_status: i32::from(soh.connection_status),
+            globalstar_gateway: i32::from(soh.globalstar_gateway),
+            last_contact_time: soh.last_contact_time as i32,
+            last_attempt_time: soh.last_attempt_time as i32,
+            call_attempts_since_reset: soh.call_attempts_since_reset as i32,
+            successful_connects_since_reset: soh.successful_connects_since_reset as i32,
+            average_connection_duration: soh.average_connection_duration as i32,
+            connection_duration_std_dev: soh.connection_duration_std_dev as i32,
+        }
+    }
+}
+
+/// Estimate of the modem's position at the time of its last connection
+#[derive(Clone, Debug, GraphQLObject, PartialEq)]
+pub struct GeoRecord {
+    /// Latitude, in degrees
+    pub lat: f64,
+    /// Longitude, in degrees (negative values are west)
+    pub lon: f64,
+    /// Time of the estimate, in seconds since the UNIX epoch
+    pub time: f64,
+    /// Maximum error of the estimate, in meters
+    pub max_error: i32,
+}
+
+impl From<api::GeoRecord> for GeoRecord {
+    fn from(geo: api::GeoRecord) -> GeoRecord {
+        GeoRecord {
+            lat: f64::from(geo.lat),
+            lon: f64::from(geo.lon),
+            time: geo.time as f64,
+            max_error: geo.max_error as i32,
+        }
+    }
+}
+
+/// Number of files and messages waiting in the modem's queues
+#[derive(Clone, Debug, GraphQLObject, PartialEq)]
+pub struct QueueCounts {
+    /// Files received from the ground which haven't been read yet
+    pub uploaded_files: i32,
+    /// Messages received from the ground which haven't been read yet
+    pub uploaded_messages: i32,
+    /// Files waiting to be sent to the ground
+    pub download_files: i32,
+}
+
+/// Common response fields structure for requests
+/// which don't return any specific data
+#[derive(Clone, Debug, GraphQLObject, PartialEq)]
+pub struct MutationResponse {
+    /// Any errors encountered by the request
+    pub errors: String,
+    /// Request completion success or failure
+    pub success: bool,
+}
@@ -0,0 +1,180 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//!
+//! GraphQL schema for exposing the radio's state of health, geolocation,
+//! and communications service telemetry.
+//!
+
+use crate::model::Subsystem;
+use crate::objects::*;
+use juniper::{graphql_object, FieldResult};
+
+type Context = kubos_service::Context<Subsystem>;
+
+pub struct QueryRoot;
+
+graphql_object!(QueryRoot: Context as "Query" |&self| {
+    // Test query to verify service is running without attempting
+    // to communicate with the underlying subsystem
+    //
+    // {
+    //     ping: "pong"
+    // }
+    field ping() -> FieldResult<String>
+    {
+        Ok(String::from("pong"))
+    }
+
+    // Check whether the modem is powered and able to respond to commands
+    //
+    // {
+    //     alive: Boolean!
+    // }
+    field alive(&executor) -> FieldResult<bool>
+    {
+        Ok(executor.context().subsystem().alive()?)
+    }
+
+    // Get the modem's state of health record
+    //
+    // {
+    //     stateOfHealth {
+    //         resetCount: Int!
+    //         currentTime: Int!
+    //         currentRssi: Int!
+    //         connectionStatus: Int!
+    //         globalstarGateway: Int!
+    //         lastContactTime: Int!
+    //         lastAttemptTime: Int!
+    //         callAttemptsSinceReset: Int!
+    //         successfulConnectsSinceReset: Int!
+    //         averageConnectionDuration: Int!
+    //         connectionDurationStdDev: Int!
+    //     }
+    // }
+    field state_of_health(&executor) -> FieldResult<StateOfHealth>
+    {
+        Ok(executor.context().subsystem().state_of_health()?)
+    }
+
+    // Get the estimate of the modem's position at the time of its last connection
+    //
+    // {
+    //     geolocation {
+    //         lat: Float!
+    //         lon: Float!
+    //         time: Float!
+    //         maxError: Int!
+    //     }
+    // }
+    field geolocation(&executor) -> FieldResult<GeoRecord>
+    {
+        Ok(executor.context().subsystem().geolocation()?)
+    }
+
+    // Get the number of files and messages waiting in the modem's queues
+    //
+    // {
+    //     queues {
+    //         uploadedFiles: Int!
+    //         uploadedMessages: Int!
+    //         downloadFiles: Int!
+    //     }
+    // }
+    field queues(&executor) -> FieldResult<QueueCounts>
+    {
+        Ok(executor.context().subsystem().queues()?)
+    }
+
+    // Request number of bad uplink packets
+    //
+    // {
+    //     failedPacketsUp: Int!
+    // }
+    field failed_packets_up(&executor) -> FieldResult<i32>
+    {
+        Ok(executor.context().subsystem().failed_packets_up()?)
+    }
+
+    // Request number of bad downlink packets
+    //
+    // {
+    //     failedPacketsDown: Int!
+    // }
+    field failed_packets_down(&executor) -> FieldResult<i32>
+    {
+        Ok(executor.context().subsystem().failed_packets_down()?)
+    }
+
+    // Request number of packets successfully uplinked
+    //
+    // {
+    //     packetsUp: Int!
+    // }
+    field packets_up(&executor) -> FieldResult<i32>
+    {
+        Ok(executor.context().subsystem().packets_up()?)
+    }
+
+    // Request number of packets successfully downlinked
+    //
+    // {
+    //     packetsDown: Int!
+    // }
+    field packets_down(&executor) -> FieldResult<i32>
+    {
+        Ok(executor.context().subsystem().packets_down()?)
+    }
+
+    // Request errors that have occured
+    //
+    // {
+    //     errors: [String]
+    // }
+    field errors(&executor) -> FieldResult<Vec<String>>
+    {
+        Ok(executor.context().subsystem().errors()?)
+    }
+});
+
+pub struct MutationRoot;
+
+graphql_object!(MutationRoot: Context as "Mutation" |&self| {
+    // Execute a trivial command against the system
+    //
+    // mutation {
+    //     noop {
+    //         errors: String,
+    //         success: Boolean!
+    //    }
+    // }
+    field noop(&executor) -> FieldResult<MutationResponse>
+    {
+        Ok(executor.context().subsystem().noop())
+    }
+
+    // Delete all of the files waiting in the modem's download queue.
+    // Returns the number of files deleted
+    //
+    // mutation {
+    //     clearDownloadQueue: Int!
+    // }
+    field clear_download_queue(&executor) -> FieldResult<i32>
+    {
+        Ok(executor.context().subsystem().clear_download_queue()?)
+    }
+});
@@ -0,0 +1,97 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+use nsl_duplex_d2::File;
+
+#[test]
+fn read_uploaded_file() {
+    let file = File::new("upload.udp", b"packet data").encode();
+
+    let mut output = b"GU\x00\x00\x00\x01".to_vec();
+    output.extend_from_slice(&file);
+
+    let conn = mock_conn(vec![b"GUGETUFC", b"GUGET_UF", b"GU\x06"], &output);
+
+    assert_eq!(read(&conn).unwrap(), b"packet data".to_vec());
+}
+
+#[test]
+fn read_uploaded_message() {
+    let message = File::new("msg", b"short packet").encode();
+
+    let mut output = b"GU\x00\x00\x00\x00GU\x00\x00\x00\x01".to_vec();
+    output.extend_from_slice(&message);
+
+    let conn = mock_conn(
+        vec![b"GUGETUFC", b"GUGETUMC", b"GUGET_UM", b"GU\x06"],
+        &output,
+    );
+
+    assert_eq!(read(&conn).unwrap(), b"short packet".to_vec());
+}
+
+#[test]
+fn poll_radio_error() {
+    let conn = mock_conn(vec![b"GUGETUFC", b"GUGETUFC"], b"");
+    let mut last_error = None;
+
+    // Errors are kept, rather than returned, so that they're only logged once
+    assert_eq!(poll(&conn, &mut last_error), None);
+    let first = last_error.clone();
+    assert!(first.is_some());
+
+    assert_eq!(poll(&conn, &mut last_error), None);
+    assert_eq!(last_error, first);
+}
+
+#[test]
+fn poll_recovers() {
+    let conn = mock_conn(
+        vec![b"GUGETUFC", b"GUGETUMC"],
+        b"GU\x00\x00\x00\x00GU\x00\x00\x00\x00",
+    );
+    let mut last_error = Some("Radio timed out".to_owned());
+
+    assert_eq!(poll(&conn, &mut last_error), None);
+    assert_eq!(last_error, None);
+}
+
+#[test]
+fn write_packets() {
+    let first = File::new("00000000.udp", b"first").encode();
+    let second = File::new("00000001.udp", b"second").encode();
+
+    let conn = mock_conn(
+        vec![b"GUPUT_DF", &first, b"GUPUT_DF", &second],
+        b"GU\x06GU\x06",
+    );
+
+    write(&conn, b"first").unwrap();
+    write(&conn, b"second").unwrap();
+}
+
+#[test]
+fn write_rejected() {
+    let file = File::new("00000000.udp", b"packet").encode();
+
+    let conn = mock_conn(vec![b"GUPUT_DF", &file], b"GU\x0f");
+
+    assert_eq!(
+        write(&conn, b"packet").unwrap_err().to_string(),
+        "Radio rejected downlink packet"
+    );
+}
@@ -0,0 +1,103 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use crate::comms::*;
+use crate::model::*;
+use crate::schema::*;
+use comms_service::CommsTelemetry;
+use kubos_service::{Config, Service};
+use nsl_duplex_d2::DuplexD2;
+use rust_uart::mock::MockStream;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+// Create a radio connection which expects the given writes, in order,
+// and responds with the given output
+fn mock_conn(writes: Vec<&[u8]>, output: &[u8]) -> DuplexConn {
+    let mut mock = MockStream::default();
+    for write in writes {
+        mock.write.set_input(write.to_vec());
+    }
+    mock.read.set_output(output.to_vec());
+
+    let stream = UartStream::new(rust_uart::Connection::new(Box::new(mock)));
+    let radio = DuplexD2::new(radio_api::Connection::new(Box::new(stream)));
+    Arc::new(Mutex::new(DuplexComms::new(radio)))
+}
+
+macro_rules! request {
+    ($service:ident, $query:ident) => {{
+        // Warp doesn't like control characters (ie. new line characters)
+        // so we need to remove them before we send the request
+        let query = $query.replace("\n", "");
+        warp::test::request()
+            .header("Content-Type", "application/json")
+            .method("POST")
+            .body(format!("{{\"query\": \"{}\"}}", query))
+            .reply(&$service.filter)
+    }};
+}
+
+macro_rules! wrap {
+    ($result:ident) => {{
+        &json!({ "data": $result }).to_string()
+    }};
+}
+
+macro_rules! test {
+    ($service:ident, $query:ident, $expected:ident) => {{
+        let res = request!($service, $query);
+
+        assert_eq!(res.body(), wrap!($expected));
+    }};
+}
+
+macro_rules! service_new {
+    ($conn:ident, $telem:ident) => {{
+        Service::new(
+            Config::new("nsl-duplex-service"),
+            Subsystem::new($conn, $telem),
+            QueryRoot,
+            MutationRoot,
+        )
+    }};
+    ($conn:ident) => {{
+        let telem = Arc::new(Mutex::new(CommsTelemetry::default()));
+        service_new!($conn, telem)
+    }};
+}
+
+mod comms;
+mod mutations;
+mod queries;
+
+#[test]
+fn ping() {
+    let conn = mock_conn(vec![], b"");
+
+    let service = service_new!(conn);
+
+    let query = r#"
+        {
+            ping
+        }"#;
+
+    let expected = json!({
+            "ping": "pong"
+    });
+
+    test!(service, query, expected);
+}
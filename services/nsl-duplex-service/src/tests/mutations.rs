@@ -0,0 +1,83 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+
+#[test]
+fn noop_good() {
+    let conn = mock_conn(vec![b"GUGETALV"], b"GU\x06");
+
+    let service = service_new!(conn);
+
+    let query = r#"
+        mutation {
+            noop {
+                errors,
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "noop": {
+                "errors": "",
+                "success": true
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn noop_nak() {
+    let conn = mock_conn(vec![b"GUGETALV"], b"GU\x0f");
+
+    let service = service_new!(conn);
+
+    let query = r#"
+        mutation {
+            noop {
+                errors,
+                success
+            }
+        }"#;
+
+    let expected = json!({
+            "noop": {
+                "errors": "Radio did not acknowledge",
+                "success": false
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn clear_download_queue() {
+    let conn = mock_conn(vec![b"GUDLTQDF"], b"GU\x00\x00\x00\x05");
+
+    let service = service_new!(conn);
+
+    let query = r#"
+        mutation {
+            clearDownloadQueue
+        }"#;
+
+    let expected = json!({
+            "clearDownloadQueue": 5
+    });
+
+    test!(service, query, expected);
+}
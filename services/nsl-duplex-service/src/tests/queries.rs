@@ -0,0 +1,189 @@
+//
+// Copyright (C) 2019 Kubos Corporation
+//
+// Licensed under the Apache License, Version 2.0 (the "License")
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use super::*;
+
+#[test]
+fn alive() {
+    let conn = mock_conn(vec![b"GUGETALV"], b"GU\x06");
+
+    let service = service_new!(conn);
+
+    let query = r#"
+        {
+            alive
+        }"#;
+
+    let expected = json!({
+            "alive": true
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn state_of_health() {
+    let conn = mock_conn(
+        vec![b"GUGETSOH"],
+        b"GU\x00\x00\x00\x01\x00\x00\x00\x02\x03\x04\x05\x00\x00\x00\x06\x00\x00\x00\x07\x00\x00\x00\x08\x00\x00\x00\x09\x00\x00\x00\x0a\x00\x00\x00\x0b",
+    );
+
+    let service = service_new!(conn);
+
+    let query = r#"
+        {
+            stateOfHealth {
+                averageConnectionDuration,
+                callAttemptsSinceReset,
+                connectionDurationStdDev,
+                connectionStatus,
+                currentRssi,
+                currentTime,
+                globalstarGateway,
+                lastAttemptTime,
+                lastContactTime,
+                resetCount,
+                successfulConnectsSinceReset
+            }
+        }"#;
+
+    let expected = json!({
+            "stateOfHealth": {
+                "averageConnectionDuration": 10,
+                "callAttemptsSinceReset": 8,
+                "connectionDurationStdDev": 11,
+                "connectionStatus": 4,
+                "currentRssi": 3,
+                "currentTime": 2,
+                "globalstarGateway": 5,
+                "lastAttemptTime": 7,
+                "lastContactTime": 6,
+                "resetCount": 1,
+                "successfulConnectsSinceReset": 9
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn state_of_health_fail() {
+    let conn = mock_conn(vec![], b"");
+
+    let service = service_new!(conn);
+
+    let query = r#"
+        {
+            stateOfHealth {
+                resetCount
+            }
+        }"#;
+
+    let res = request!(service, query);
+    let body: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+
+    assert_eq!(body["data"], serde_json::Value::Null);
+    assert!(body["errors"].is_array());
+}
+
+#[test]
+fn geolocation() {
+    let conn = mock_conn(
+        vec![b"GUGETGEO"],
+        b"GU\nN: 040 28 57\nW: 085 29 38\nTIME: 02 01 2018 13:10:42\nERR: < 5 km\n\nOK\n                     extra",
+    );
+
+    let service = service_new!(conn);
+
+    let query = r#"
+        {
+            geolocation {
+                maxError,
+                time
+            }
+        }"#;
+
+    let expected = json!({
+            "geolocation": {
+                "maxError": 5000,
+                "time": 1514898642.0
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn queues() {
+    let conn = mock_conn(
+        vec![b"GUGETUFC", b"GUGETUMC", b"GUGETDFC"],
+        b"GU\x00\x00\x00\x01GU\x00\x00\x00\x02GU\x00\x00\x00\x03",
+    );
+
+    let service = service_new!(conn);
+
+    let query = r#"
+        {
+            queues {
+                downloadFiles,
+                uploadedFiles,
+                uploadedMessages
+            }
+        }"#;
+
+    let expected = json!({
+            "queues": {
+                "downloadFiles": 3,
+                "uploadedFiles": 1,
+                "uploadedMessages": 2
+            }
+    });
+
+    test!(service, query, expected);
+}
+
+#[test]
+fn comms_telemetry() {
+    let conn = mock_conn(vec![], b"");
+    let telem = Arc::new(Mutex::new(CommsTelemetry {
+        packets_up: 4,
+        packets_down: 3,
+        failed_packets_up: 2,
+        failed_packets_down: 1,
+        errors: vec!["Radio rejected downlink packet".to_owned()],
+    }));
+
+    let service = service_new!(conn, telem);
+
+    let query = r#"
+        {
+            errors,
+            failedPacketsDown,
+            failedPacketsUp,
+            packetsDown,
+            packetsUp
+        }"#;
+
+    let expected = json!({
+            "errors": ["Radio rejected downlink packet"],
+            "failedPacketsDown": 1,
+            "failedPacketsUp": 2,
+            "packetsDown": 3,
+            "packetsUp": 4
+    });
+
+    test!(service, query, expected);
+}
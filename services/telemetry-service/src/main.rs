@@ -232,5 +232,6 @@ fn main() {
         QueryRoot,
         MutationRoot,
     )
+    .version(env!("CARGO_PKG_VERSION"))
     .start();
 }
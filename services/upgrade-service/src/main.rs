@@ -0,0 +1,143 @@
+/*
+ * Copyright (C) 2019 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+#![deny(warnings)]
+
+//! Service for installing new versions of KubOS Linux
+//!
+//! New kpack images are uploaded to the OBC (for example, with the file transfer service),
+//! staged into the upgrade partition and verified, and then selected as the next boot target.
+//! After rebooting into the new version, it should be committed, which resets the U-Boot boot
+//! count so that the system doesn't fall back to the previous version. If the new version
+//! misbehaves, the previous version can be selected with the `rollback` mutation instead.
+//!
+//! Checksums are BLAKE2s hashes with a 16-byte digest, given as hex strings. This is the same hash
+//! which the file transfer service reports for an uploaded file.
+//!
+//! # Configuration
+//!
+//! The service can be configured in the `/home/system/etc/config.toml` with the following fields:
+//!
+//! ```toml
+//! [upgrade-service]
+//! upgrade-dir = "/upgrade"
+//! fw-printenv = "/usr/sbin/fw_printenv"
+//! fw-setenv = "/usr/sbin/fw_setenv"
+//!
+//! [upgrade-service.addr]
+//! ip = "127.0.0.1"
+//! port = 8013
+//! ```
+//!
+//! # GraphQL Schema
+//!
+//! ```graphql
+//! schema {
+//!     query: Query
+//!     mutation: Mutation
+//! }
+//!
+//! type Query {
+//!     ping: String!
+//!     versions: Versions!
+//!     images: [Image!]!
+//! }
+//!
+//! type Mutation {
+//!     stageImage(path: String!, checksum: String!): UpgradeResponse!
+//!     verifyImage(name: String!, checksum: String!): UpgradeResponse!
+//!     setNextBoot(name: String!): UpgradeResponse!
+//!     clearNextBoot: UpgradeResponse!
+//!     commit: UpgradeResponse!
+//!     rollback: UpgradeResponse!
+//!     reloadConfig: ReloadResponse!
+//! }
+//!
+//! type Versions {
+//!     current: String
+//!     previous: String
+//!     nextBoot: String
+//!     bootCount: Int
+//!     bootLimit: Int
+//! }
+//!
+//! type Image {
+//!     name: String!
+//!     size: Float!
+//! }
+//!
+//! type UpgradeResponse {
+//!     errors: String!
+//!     success: Boolean!
+//!     image: String
+//! }
+//!
+//! type ReloadResponse {
+//!     errors: String!
+//!     success: Boolean!
+//!     changed: [String!]!
+//!     restartRequired: [String!]!
+//! }
+//! ```
+
+#[macro_use]
+extern crate juniper;
+
+mod objects;
+mod schema;
+#[cfg(test)]
+mod tests;
+mod upgrade;
+
+use crate::schema::{MutationRoot, QueryRoot};
+use crate::upgrade::Upgrader;
+use failure::{bail, Error};
+use getopts::Options;
+use kubos_service::{Config, Service};
+use std::env;
+use syslog::Facility;
+
+fn main() -> Result<(), Error> {
+    syslog::init(
+        Facility::LOG_DAEMON,
+        log::LevelFilter::Debug,
+        Some("kubos-upgrade-service"),
+    )
+    .unwrap();
+
+    let args: Vec<String> = env::args().collect();
+    let mut opts = Options::new();
+
+    opts.optopt("c", "config", "Path to config file", "CONFIG");
+    let matches = match opts.parse(&args[1..]) {
+        Ok(m) => m,
+        Err(err) => {
+            bail!("Unable to parse command options: {}", err);
+        }
+    };
+
+    let config = match matches.opt_str("c") {
+        Some(file) => Config::new_from_path("upgrade-service", file),
+        None => Config::new("upgrade-service"),
+    };
+
+    let upgrader = Upgrader::from_config(&config)?;
+
+    Service::new_reloadable(config, upgrader, QueryRoot, MutationRoot)
+        .version(env!("CARGO_PKG_VERSION"))
+        .start();
+
+    Ok(())
+}
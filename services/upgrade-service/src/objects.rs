@@ -0,0 +1,90 @@
+/*
+ * Copyright (C) 2019 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::upgrade::{ImageInfo, VersionInfo};
+
+/// Response fields for the upgrade mutations
+#[derive(GraphQLObject)]
+pub struct UpgradeResponse {
+    /// Any errors encountered by the request
+    pub errors: String,
+    /// Request completion success or failure
+    pub success: bool,
+    /// The image affected by the request
+    pub image: Option<String>,
+}
+
+impl UpgradeResponse {
+    pub fn new(result: Result<Option<String>, failure::Error>) -> Self {
+        match result {
+            Ok(image) => UpgradeResponse {
+                errors: "".to_owned(),
+                success: true,
+                image,
+            },
+            Err(error) => UpgradeResponse {
+                errors: error.to_string(),
+                success: false,
+                image: None,
+            },
+        }
+    }
+}
+
+/// An image in the upgrade partition
+#[derive(GraphQLObject)]
+pub struct Image {
+    /// File name of the image
+    pub name: String,
+    /// Size of the image, in bytes
+    pub size: f64,
+}
+
+impl From<ImageInfo> for Image {
+    fn from(info: ImageInfo) -> Self {
+        Image {
+            name: info.name,
+            size: info.size as f64,
+        }
+    }
+}
+
+/// The KubOS versions known to U-Boot
+#[derive(GraphQLObject)]
+pub struct Versions {
+    /// The currently running version of KubOS
+    pub current: Option<String>,
+    /// The previous version of KubOS, which the system can be rolled back to
+    pub previous: Option<String>,
+    /// The image which will be installed at the next boot, if any
+    pub next_boot: Option<String>,
+    /// The number of boots attempted since the last successful commit
+    pub boot_count: Option<i32>,
+    /// The number of boot attempts allowed before U-Boot falls back to recovery
+    pub boot_limit: Option<i32>,
+}
+
+impl From<VersionInfo> for Versions {
+    fn from(info: VersionInfo) -> Self {
+        Versions {
+            current: info.current,
+            previous: info.previous,
+            next_boot: info.next_boot,
+            boot_count: info.boot_count.map(|count| count as i32),
+            boot_limit: info.boot_limit.map(|limit| limit as i32),
+        }
+    }
+}
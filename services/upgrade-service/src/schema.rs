@@ -0,0 +1,117 @@
+/*
+ * Copyright (C) 2019 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::objects::*;
+use crate::upgrade::Upgrader;
+use juniper::FieldResult;
+use kubos_service::ReloadResponse;
+
+type Context = kubos_service::Context<Upgrader>;
+
+pub struct QueryRoot;
+
+// Base GraphQL query model
+graphql_object!(QueryRoot: Context as "Query" |&self| {
+    // Test query to verify service is running without
+    // attempting to communicate with the underlying subsystem
+    //
+    // {
+    //     ping: "pong"
+    // }
+    field ping() -> FieldResult<String>
+        as "Test service query"
+    {
+        Ok(String::from("pong"))
+    }
+
+    field versions(&executor) -> FieldResult<Versions>
+        as "Current, previous, and next boot versions of KubOS"
+    {
+        Ok(executor.context().subsystem().versions().into())
+    }
+
+    field images(&executor) -> FieldResult<Vec<Image>>
+        as "Images in the upgrade partition"
+    {
+        Ok(executor
+            .context()
+            .subsystem()
+            .images()?
+            .into_iter()
+            .map(|image| image.into())
+            .collect())
+    }
+});
+
+pub struct MutationRoot;
+
+// Base GraphQL mutation model
+graphql_object!(MutationRoot: Context as "Mutation" |&self| {
+
+    field stage_image(&executor, path: String, checksum: String) -> FieldResult<UpgradeResponse>
+        as "Copy an uploaded image into the upgrade partition"
+    {
+        Ok(UpgradeResponse::new(
+            executor.context().subsystem().stage(&path, &checksum).map(|image| Some(image.name))
+        ))
+    }
+
+    field verify_image(&executor, name: String, checksum: String) -> FieldResult<UpgradeResponse>
+        as "Verify the checksum of a staged image"
+    {
+        Ok(UpgradeResponse::new(
+            executor.context().subsystem().verify(&name, &checksum).map(|_| Some(name))
+        ))
+    }
+
+    field set_next_boot(&executor, name: String) -> FieldResult<UpgradeResponse>
+        as "Install a staged image at the next boot"
+    {
+        Ok(UpgradeResponse::new(
+            executor.context().subsystem().set_next_boot(&name).map(|_| Some(name))
+        ))
+    }
+
+    field clear_next_boot(&executor) -> FieldResult<UpgradeResponse>
+        as "Cancel a pending upgrade or rollback"
+    {
+        Ok(UpgradeResponse::new(
+            executor.context().subsystem().clear_next_boot().map(|_| None)
+        ))
+    }
+
+    field commit(&executor) -> FieldResult<UpgradeResponse>
+        as "Accept the currently running version of KubOS"
+    {
+        Ok(UpgradeResponse::new(
+            executor.context().subsystem().commit().map(Some)
+        ))
+    }
+
+    field rollback(&executor) -> FieldResult<UpgradeResponse>
+        as "Install the previous version of KubOS at the next boot"
+    {
+        Ok(UpgradeResponse::new(
+            executor.context().subsystem().rollback().map(Some)
+        ))
+    }
+
+    field reload_config(&executor) -> FieldResult<ReloadResponse>
+        as "Reload the service's configuration"
+    {
+        Ok(executor.context().reload_config())
+    }
+});
@@ -0,0 +1,227 @@
+/*
+ * Copyright (C) 2019 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::*;
+use crate::schema;
+use crate::upgrade::Upgrader;
+use kubos_service::{Config, Service};
+use serde_json::json;
+
+const BASE_ENV: &str = "kubos_curr_version=kpack-2019.02.01.itb
+kubos_prev_version=kpack-base.itb
+bootcount=2
+bootlimit=3
+";
+
+#[test]
+fn versions() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, BASE_ENV);
+    let service = mock_service!(test_dir);
+
+    let query = r#"{
+        versions {
+            bootCount,
+            bootLimit,
+            current,
+            nextBoot,
+            previous
+        }
+    }"#;
+    let expected = json!({
+        "versions": {
+            "current": "kpack-2019.02.01.itb",
+            "previous": "kpack-base.itb",
+            "nextBoot": null,
+            "bootCount": 2,
+            "bootLimit": 3
+        }
+    });
+    test!(service, query, expected);
+}
+
+#[test]
+fn set_next_boot() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, BASE_ENV);
+    let service = mock_service!(test_dir);
+
+    fs::write(test_dir.path().join("upgrade/kpack-2019.03.01.itb"), "image").unwrap();
+
+    let query = r#"mutation {
+        setNextBoot(name: \"kpack-2019.03.01.itb\") {
+            errors,
+            image,
+            success
+        }
+    }"#;
+    let expected = json!({
+        "setNextBoot": {
+            "success": true,
+            "errors": "",
+            "image": "kpack-2019.03.01.itb"
+        }
+    });
+    test!(service, query, expected);
+
+    assert_eq!(
+        get_var(&test_dir, "kubos_updatefile"),
+        Some("kpack-2019.03.01.itb".to_owned())
+    );
+    assert_eq!(get_var(&test_dir, "bootcount"), Some("0".to_owned()));
+
+    let query = r#"mutation {
+        clearNextBoot {
+            success
+        }
+    }"#;
+    let expected = json!({
+        "clearNextBoot": {
+            "success": true
+        }
+    });
+    test!(service, query, expected);
+
+    assert_eq!(get_var(&test_dir, "kubos_updatefile"), None);
+}
+
+#[test]
+fn set_next_boot_not_staged() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, BASE_ENV);
+    let service = mock_service!(test_dir);
+
+    let query = r#"mutation {
+        setNextBoot(name: \"kpack-2019.03.01.itb\") {
+            errors,
+            success
+        }
+    }"#;
+    let expected = json!({
+        "setNextBoot": {
+            "success": false,
+            "errors": "Image kpack-2019.03.01.itb has not been staged"
+        }
+    });
+    test!(service, query, expected);
+
+    assert_eq!(get_var(&test_dir, "kubos_updatefile"), None);
+    assert_eq!(get_var(&test_dir, "bootcount"), Some("2".to_owned()));
+}
+
+#[test]
+fn commit() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(
+        &test_dir,
+        &format!("{}kubos_curr_tried=1\nkubos_updatefile=kpack-2019.02.01.itb\n", BASE_ENV),
+    );
+    let service = mock_service!(test_dir);
+
+    let query = r#"mutation {
+        commit {
+            errors,
+            image,
+            success
+        }
+    }"#;
+    let expected = json!({
+        "commit": {
+            "success": true,
+            "errors": "",
+            "image": "kpack-2019.02.01.itb"
+        }
+    });
+    test!(service, query, expected);
+
+    assert_eq!(get_var(&test_dir, "bootcount"), Some("0".to_owned()));
+    assert_eq!(get_var(&test_dir, "kubos_curr_tried"), None);
+    assert_eq!(get_var(&test_dir, "kubos_updatefile"), None);
+}
+
+#[test]
+fn rollback() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, BASE_ENV);
+    let service = mock_service!(test_dir);
+
+    fs::write(test_dir.path().join("upgrade/kpack-base.itb"), "image").unwrap();
+
+    let query = r#"mutation {
+        rollback {
+            errors,
+            image,
+            success
+        }
+    }"#;
+    let expected = json!({
+        "rollback": {
+            "success": true,
+            "errors": "",
+            "image": "kpack-base.itb"
+        }
+    });
+    test!(service, query, expected);
+
+    assert_eq!(
+        get_var(&test_dir, "kubos_updatefile"),
+        Some("kpack-base.itb".to_owned())
+    );
+}
+
+#[test]
+fn rollback_missing_image() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, BASE_ENV);
+    let service = mock_service!(test_dir);
+
+    let query = r#"mutation {
+        rollback {
+            errors,
+            success
+        }
+    }"#;
+    let expected = json!({
+        "rollback": {
+            "success": false,
+            "errors": "Image kpack-base.itb has not been staged"
+        }
+    });
+    test!(service, query, expected);
+
+    assert_eq!(get_var(&test_dir, "kubos_updatefile"), None);
+}
+
+#[test]
+fn rollback_no_previous() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, "kubos_curr_version=kpack-base.itb\n");
+    let service = mock_service!(test_dir);
+
+    let query = r#"mutation {
+        rollback {
+            errors,
+            success
+        }
+    }"#;
+    let expected = json!({
+        "rollback": {
+            "success": false,
+            "errors": "No previous version to roll back to"
+        }
+    });
+    test!(service, query, expected);
+}
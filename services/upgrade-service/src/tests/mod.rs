@@ -0,0 +1,131 @@
+/*
+ * Copyright (C) 2019 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use tempfile::TempDir;
+
+// Fake printenv/setenv pair which keep their variables in a `name=value` file
+// next to the scripts
+const FAKE_PRINTENV: &str = r#"#!/bin/bash
+ENV_FILE="$(dirname "$0")/env"
+LINE=$(grep "^$2=" "$ENV_FILE" 2>/dev/null) || exit 1
+echo "${LINE#*=}"
+"#;
+
+const FAKE_SETENV: &str = r#"#!/bin/bash
+ENV_FILE="$(dirname "$0")/env"
+[[ "$1" == "-s" && "$2" == "-" ]] || exit 1
+touch "$ENV_FILE"
+cp "$ENV_FILE" "$ENV_FILE.new"
+while read -r NAME VALUE; do
+    grep -v "^$NAME=" "$ENV_FILE.new" > "$ENV_FILE.tmp"
+    [[ -n "$VALUE" ]] && echo "$NAME=$VALUE" >> "$ENV_FILE.tmp"
+    mv "$ENV_FILE.tmp" "$ENV_FILE.new"
+done
+mv "$ENV_FILE.new" "$ENV_FILE"
+"#;
+
+fn write_script(dir: &Path, name: &str, contents: &str) {
+    let path = dir.join(name);
+
+    let mut file = fs::File::create(&path).unwrap();
+    file.write_all(contents.as_bytes())
+        .expect("Failed to write fake script");
+
+    let mut perms = file.metadata().unwrap().permissions();
+    perms.set_mode(0o755);
+    file.set_permissions(perms)
+        .expect("Failed to change file permissions");
+}
+
+// Create the upgrade partition and fake U-Boot environment for a test.
+// The environment starts out with the given `name=value` lines
+pub fn setup_env(test_dir: &TempDir, env: &str) {
+    let bin_dir = test_dir.path().join("bin");
+    fs::create_dir(&bin_dir).unwrap();
+    write_script(&bin_dir, "fw_printenv", FAKE_PRINTENV);
+    write_script(&bin_dir, "fw_setenv", FAKE_SETENV);
+    fs::write(bin_dir.join("env"), env).unwrap();
+
+    fs::create_dir(test_dir.path().join("upgrade")).unwrap();
+}
+
+// Read back a variable from the fake U-Boot environment
+pub fn get_var(test_dir: &TempDir, name: &str) -> Option<String> {
+    let env = fs::read_to_string(test_dir.path().join("bin/env")).unwrap();
+    env.lines()
+        .find(|line| line.starts_with(&format!("{}=", name)))
+        .map(|line| line[name.len() + 1..].to_owned())
+}
+
+macro_rules! mock_service {
+    ($test_dir:ident) => {{
+        let config = format!(
+            r#"
+            [upgrade-service]
+            upgrade-dir = "{dir}/upgrade"
+            fw-printenv = "{dir}/bin/fw_printenv"
+            fw-setenv = "{dir}/bin/fw_setenv"
+            [upgrade-service.addr]
+            ip = "127.0.0.1"
+            port = 9999"#,
+            dir = $test_dir.path().to_str().unwrap(),
+        );
+        let config = Config::new_from_str("upgrade-service", &config);
+
+        Service::new(
+            config.clone(),
+            Upgrader::from_config(&config).unwrap(),
+            schema::QueryRoot,
+            schema::MutationRoot,
+        )
+    }};
+}
+
+macro_rules! request {
+    ($service:ident, $query:ident) => {{
+        // Warp doesn't like control characters (ie. new line characters)
+        // so we need to remove them before we send the request
+        let query = $query.replace("\n", "");
+        warp::test::request()
+            .header("Content-Type", "application/json")
+            .method("POST")
+            .body(format!("{{\"query\": \"{}\"}}", query))
+            .reply(&$service.filter)
+    }};
+}
+
+macro_rules! wrap {
+    ($result:ident) => {{
+        &json!({ "data": $result }).to_string()
+    }};
+}
+
+macro_rules! test {
+    ($service:ident, $query:ident, $expected:ident) => {{
+        let res = request!($service, $query);
+
+        assert_eq!(res.body(), wrap!($expected));
+    }};
+}
+
+mod boot_control;
+mod reload_config;
+mod service_metrics;
+mod stage_image;
@@ -0,0 +1,118 @@
+/*
+ * Copyright (C) 2019 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::*;
+use crate::schema;
+use crate::upgrade::Upgrader;
+use kubos_service::{Config, Service};
+use serde_json::json;
+
+fn write_config(test_dir: &TempDir, upgrade_dir: &str, port: u16) -> String {
+    let dir = test_dir.path().to_str().unwrap();
+    let path = format!("{}/config.toml", dir);
+    let config = format!(
+        r#"
+        [upgrade-service]
+        upgrade-dir = "{}/{}"
+        fw-printenv = "{}/bin/fw_printenv"
+        fw-setenv = "{}/bin/fw_setenv"
+
+        [upgrade-service.addr]
+        ip = "127.0.0.1"
+        port = {}
+        "#,
+        dir, upgrade_dir, dir, dir, port
+    );
+    fs::write(&path, config).unwrap();
+    path
+}
+
+#[test]
+fn reload_config() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, "");
+    fs::create_dir(test_dir.path().join("upgrade2")).unwrap();
+    fs::write(test_dir.path().join("upgrade2/kpack-2019.03.01.itb"), "image").unwrap();
+
+    let path = write_config(&test_dir, "upgrade", 9999);
+    let config = Config::new_from_path("upgrade-service", path);
+    let upgrader = Upgrader::from_config(&config).unwrap();
+    let service =
+        Service::new_reloadable(config, upgrader, schema::QueryRoot, schema::MutationRoot);
+
+    let query = r#"{ images { name } }"#;
+    let expected = json!({ "images": [] });
+    test!(service, query, expected);
+
+    write_config(&test_dir, "upgrade2", 9998);
+
+    let query = r#"mutation {
+        reloadConfig {
+            changed,
+            errors,
+            restartRequired,
+            success
+        }
+    }"#;
+    let expected = json!({
+        "reloadConfig": {
+            "changed": ["addr.port", "upgrade-dir"],
+            "errors": "",
+            "restartRequired": ["addr.port"],
+            "success": true
+        }
+    });
+    test!(service, query, expected);
+
+    // The new upgrade directory is used from now on
+    let query = r#"{ images { name } }"#;
+    let expected = json!({ "images": [{ "name": "kpack-2019.03.01.itb" }] });
+    test!(service, query, expected);
+}
+
+#[test]
+fn reload_config_invalid() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, "");
+
+    let path = write_config(&test_dir, "upgrade", 9999);
+    let config = Config::new_from_path("upgrade-service", path.clone());
+    let upgrader = Upgrader::from_config(&config).unwrap();
+    let service =
+        Service::new_reloadable(config, upgrader, schema::QueryRoot, schema::MutationRoot);
+
+    fs::write(&path, "[upgrade-service]\nupgrade-dir = 5\n").unwrap();
+
+    let query = r#"mutation {
+        reloadConfig {
+            errors,
+            success
+        }
+    }"#;
+    let expected = json!({
+        "reloadConfig": {
+            "errors": "Invalid [upgrade-service] section: invalid type: integer `5`, expected a string for key `upgrade-dir`",
+            "success": false
+        }
+    });
+    test!(service, query, expected);
+
+    // The old settings stay in place
+    fs::write(test_dir.path().join("upgrade/kpack-2019.03.01.itb"), "image").unwrap();
+    let query = r#"{ images { name } }"#;
+    let expected = json!({ "images": [{ "name": "kpack-2019.03.01.itb" }] });
+    test!(service, query, expected);
+}
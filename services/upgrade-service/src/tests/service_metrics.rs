@@ -0,0 +1,107 @@
+/*
+ * Copyright (C) 2019 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::*;
+use crate::schema;
+use crate::upgrade::Upgrader;
+use kubos_service::{Config, Service};
+use serde_json::json;
+
+#[test]
+fn service_metrics() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, "");
+    let service = mock_service!(test_dir).version("1.2.3");
+
+    let query = r#"{ ping }"#;
+    request!(service, query);
+
+    let query =
+        r#"mutation { verifyImage(name: \"kpack-2019.03.01.itb\", checksum: \"00\") { success } }"#;
+    request!(service, query);
+
+    let query = r#"{
+        kubos {
+            errorCount,
+            fields {
+                calls,
+                errors,
+                name
+            },
+            name,
+            recentErrors {
+                field
+            },
+            requests,
+            version
+        }
+    }"#;
+    let expected = json!({
+        "kubos": {
+            "errorCount": 0.0,
+            "fields": [
+                { "calls": 1.0, "errors": 0.0, "name": "Mutation.verifyImage" },
+                { "calls": 1.0, "errors": 0.0, "name": "Query.ping" },
+            ],
+            "name": "upgrade-service",
+            "recentErrors": [],
+            "requests": 3.0,
+            "version": "1.2.3"
+        }
+    });
+    test!(service, query, expected);
+}
+
+#[test]
+fn service_metrics_errors() {
+    let test_dir = TempDir::new().unwrap();
+    // Images can't be listed once the upgrade partition is gone
+    setup_env(&test_dir, "");
+    fs::remove_dir(test_dir.path().join("upgrade")).unwrap();
+    let service = mock_service!(test_dir);
+
+    let query = r#"{ images { name } }"#;
+    request!(service, query);
+
+    let query = r#"{
+        kubos {
+            errorCount,
+            fields {
+                calls,
+                errors,
+                name
+            },
+            recentErrors {
+                field,
+                message
+            },
+            version
+        }
+    }"#;
+    let expected = json!({
+        "kubos": {
+            "errorCount": 1.0,
+            "fields": [
+                { "calls": 1.0, "errors": 1.0, "name": "Query.images" },
+            ],
+            "recentErrors": [
+                { "field": "Query.images", "message": "No such file or directory (os error 2)" },
+            ],
+            "version": null
+        }
+    });
+    test!(service, query, expected);
+}
@@ -0,0 +1,225 @@
+/*
+ * Copyright (C) 2019 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::*;
+use crate::schema;
+use crate::upgrade::Upgrader;
+use kubos_service::{Config, Service};
+use serde_json::json;
+
+const IMAGE: &[u8] = b"kpack upgrade image";
+const IMAGE_HASH: &str = "9f4ede4a67d874806d87f0ad80c23de5";
+
+fn stage_query(path: &str, checksum: &str) -> String {
+    format!(
+        r#"mutation {{
+        stageImage(path: \"{}\", checksum: \"{}\") {{
+            errors,
+            image,
+            success
+        }}
+    }}"#,
+        path, checksum
+    )
+}
+
+#[test]
+fn stage_image_good() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, "");
+    let service = mock_service!(test_dir);
+
+    let source = test_dir.path().join("kpack-2019.03.01.itb");
+    fs::write(&source, IMAGE).unwrap();
+
+    // Checksums aren't case-sensitive
+    let query = stage_query(source.to_str().unwrap(), &IMAGE_HASH.to_uppercase());
+    let expected = json!({
+        "stageImage": {
+            "success": true,
+            "errors": "",
+            "image": "kpack-2019.03.01.itb"
+        }
+    });
+    test!(service, query, expected);
+
+    let staged = test_dir.path().join("upgrade/kpack-2019.03.01.itb");
+    assert_eq!(fs::read(staged).unwrap(), IMAGE);
+
+    let query = r#"{ images { name, size } }"#;
+    let expected = json!({
+        "images": [{ "name": "kpack-2019.03.01.itb", "size": IMAGE.len() as f64 }]
+    });
+    test!(service, query, expected);
+}
+
+#[test]
+fn stage_image_bad_checksum() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, "");
+    let service = mock_service!(test_dir);
+
+    let source = test_dir.path().join("kpack-2019.03.01.itb");
+    fs::write(&source, IMAGE).unwrap();
+
+    let query = stage_query(source.to_str().unwrap(), "00112233445566778899aabbccddeeff");
+    let expected = json!({
+        "stageImage": {
+            "success": false,
+            "errors": format!(
+                "Checksum mismatch for kpack-2019.03.01.itb: expected 00112233445566778899aabbccddeeff, got {}",
+                IMAGE_HASH
+            ),
+            "image": null
+        }
+    });
+    test!(service, query, expected);
+
+    // Nothing should have been left behind in the upgrade partition
+    assert_eq!(
+        fs::read_dir(test_dir.path().join("upgrade")).unwrap().count(),
+        0
+    );
+}
+
+#[test]
+fn stage_image_unreadable() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, "");
+    let service = mock_service!(test_dir);
+
+    // A directory can be opened, but not read
+    let source = test_dir.path().join("kpack-2019.03.01.itb");
+    fs::create_dir(&source).unwrap();
+
+    let query = stage_query(source.to_str().unwrap(), IMAGE_HASH);
+    let expected = json!({
+        "stageImage": {
+            "success": false,
+            "errors": "Is a directory (os error 21)",
+            "image": null
+        }
+    });
+    test!(service, query, expected);
+
+    // The partial copy should have been removed
+    assert_eq!(
+        fs::read_dir(test_dir.path().join("upgrade")).unwrap().count(),
+        0
+    );
+}
+
+#[test]
+fn stage_image_in_place() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, "");
+    let service = mock_service!(test_dir);
+
+    // The image was uploaded directly into the upgrade partition
+    let source = test_dir.path().join("upgrade/kpack-2019.03.01.itb");
+    fs::write(&source, b"kpack staged in place").unwrap();
+
+    let query = stage_query(source.to_str().unwrap(), "a0694dc888962419db912a801ede0962");
+    let expected = json!({
+        "stageImage": {
+            "success": true,
+            "errors": "",
+            "image": "kpack-2019.03.01.itb"
+        }
+    });
+    test!(service, query, expected);
+}
+
+#[test]
+fn stage_image_bad_name() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, "");
+    let service = mock_service!(test_dir);
+
+    let source = test_dir.path().join("image.tar");
+    fs::write(&source, IMAGE).unwrap();
+
+    let query = stage_query(source.to_str().unwrap(), IMAGE_HASH);
+    let expected = json!({
+        "stageImage": {
+            "success": false,
+            "errors": "Invalid image name image.tar: expected a kpack *.itb file",
+            "image": null
+        }
+    });
+    test!(service, query, expected);
+}
+
+#[test]
+fn stage_image_existing() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, "");
+    let service = mock_service!(test_dir);
+
+    let source = test_dir.path().join("kpack-base.itb");
+    fs::write(&source, IMAGE).unwrap();
+    fs::write(test_dir.path().join("upgrade/kpack-base.itb"), b"base image").unwrap();
+
+    let query = stage_query(source.to_str().unwrap(), IMAGE_HASH);
+    let expected = json!({
+        "stageImage": {
+            "success": false,
+            "errors": "Image kpack-base.itb already exists in the upgrade partition",
+            "image": null
+        }
+    });
+    test!(service, query, expected);
+}
+
+#[test]
+fn verify_image() {
+    let test_dir = TempDir::new().unwrap();
+    setup_env(&test_dir, "");
+    let service = mock_service!(test_dir);
+
+    fs::write(test_dir.path().join("upgrade/kpack-2019.03.01.itb"), IMAGE).unwrap();
+
+    let query = format!(
+        r#"mutation {{
+        verifyImage(name: \"kpack-2019.03.01.itb\", checksum: \"{}\") {{
+            image,
+            success
+        }}
+    }}"#,
+        IMAGE_HASH
+    );
+    let expected = json!({
+        "verifyImage": {
+            "success": true,
+            "image": "kpack-2019.03.01.itb"
+        }
+    });
+    test!(service, query, expected);
+
+    let query = r#"mutation {
+        verifyImage(name: \"kpack-missing.itb\", checksum: \"00\") {
+            errors,
+            success
+        }
+    }"#;
+    let expected = json!({
+        "verifyImage": {
+            "success": false,
+            "errors": "Image kpack-missing.itb has not been staged"
+        }
+    });
+    test!(service, query, expected);
+}
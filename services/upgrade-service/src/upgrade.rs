@@ -0,0 +1,334 @@
+/*
+ * Copyright (C) 2019 Kubos Corporation
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use blake2_rfc::blake2s::Blake2s;
+use failure::{bail, format_err, Error};
+use kubos_system::{
+    UBootUpdate, UBootVars, VAR_BOOT_COUNT, VAR_KUBOS_CURR_TRIED, VAR_KUBOS_CURR_VERSION,
+    VAR_KUBOS_UPDATE_FILE,
+};
+use kubos_service::{Config, ConfigReload};
+use serde_derive::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Default location of the upgrade partition
+pub const DEFAULT_UPGRADE_DIR: &str = "/upgrade";
+
+// Matches the hash size used by the file transfer protocol, so that the checksum
+// reported at the end of an upload can be passed straight to the service
+const HASH_SIZE: usize = 16;
+const CHUNK_SIZE: usize = 4096;
+
+/// Information about an image in the upgrade partition
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageInfo {
+    pub name: String,
+    pub size: u64,
+}
+
+/// The state of the U-Boot variables which control which image is booted
+#[derive(Clone, Debug, PartialEq)]
+pub struct VersionInfo {
+    pub current: Option<String>,
+    pub previous: Option<String>,
+    pub next_boot: Option<String>,
+    pub boot_count: Option<u32>,
+    pub boot_limit: Option<u32>,
+}
+
+/// Service configuration options
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct UpgradeConfig {
+    pub upgrade_dir: String,
+    pub fw_printenv: String,
+    pub fw_setenv: String,
+}
+
+impl Default for UpgradeConfig {
+    fn default() -> Self {
+        UpgradeConfig {
+            upgrade_dir: DEFAULT_UPGRADE_DIR.to_owned(),
+            fw_printenv: "/usr/sbin/fw_printenv".to_owned(),
+            fw_setenv: "/usr/sbin/fw_setenv".to_owned(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Settings {
+    dir: PathBuf,
+    vars: UBootVars,
+}
+
+impl Settings {
+    fn from_config(config: &Config) -> Result<Self, Error> {
+        let config: UpgradeConfig = config.section()?;
+
+        Ok(Settings {
+            dir: PathBuf::from(config.upgrade_dir),
+            vars: UBootVars::new_from_paths(&config.fw_printenv, &config.fw_setenv),
+        })
+    }
+}
+
+/// Manages the KubOS images kept in the upgrade partition
+#[derive(Clone)]
+pub struct Upgrader {
+    // Shared between all clones, so that reloaded settings are seen by every request
+    settings: Arc<RwLock<Settings>>,
+}
+
+impl Upgrader {
+    /// Create a new upgrader using the paths from the service's configuration
+    pub fn from_config(config: &Config) -> Result<Self, Error> {
+        Ok(Upgrader {
+            settings: Arc::new(RwLock::new(Settings::from_config(config)?)),
+        })
+    }
+
+    fn dir(&self) -> PathBuf {
+        self.settings.read().unwrap().dir.clone()
+    }
+
+    fn vars(&self) -> UBootVars {
+        self.settings.read().unwrap().vars.clone()
+    }
+
+    /// Get the current and previous versions of KubOS, along with the
+    /// image which has been selected for the next boot
+    pub fn versions(&self) -> VersionInfo {
+        VersionInfo {
+            current: self.vars().get_str(VAR_KUBOS_CURR_VERSION),
+            previous: self.vars().alternate_version(),
+            next_boot: self.vars().next_boot(),
+            boot_count: self.vars().boot_count(),
+            boot_limit: self.vars().boot_limit(),
+        }
+    }
+
+    /// List the images currently in the upgrade partition
+    pub fn images(&self) -> Result<Vec<ImageInfo>, Error> {
+        let mut images = vec![];
+
+        for entry in fs::read_dir(self.dir())? {
+            let entry = entry?;
+            let meta = entry.metadata()?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if meta.is_file() && is_image_name(&name) {
+                images.push(ImageInfo {
+                    name,
+                    size: meta.len(),
+                });
+            }
+        }
+
+        images.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(images)
+    }
+
+    /// Copy an uploaded image into the upgrade partition, verifying its checksum
+    /// along the way. The image is only moved into place once the checksum matches,
+    /// so a bad upload can never be selected for booting.
+    pub fn stage(&self, path: &str, checksum: &str) -> Result<ImageInfo, Error> {
+        let source = Path::new(path);
+        let name = source
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| format_err!("Invalid image path: {}", path))?;
+        self.check_name(&name)?;
+
+        let dest = self.dir().join(&name);
+
+        // The image may have been uploaded straight into the upgrade partition.
+        // Otherwise, refuse to overwrite an existing image, since it might be one
+        // that U-Boot relies on for recovery
+        if dest.exists() {
+            if fs::canonicalize(&dest)? != fs::canonicalize(source)? {
+                bail!("Image {} already exists in the upgrade partition", name);
+            }
+            self.verify(&name, checksum)?;
+        } else {
+            let temp = self.dir().join(format!(".{}.tmp", name));
+            // Don't leave a partial copy behind if the source can't be read
+            // or the partition fills up
+            let hash = match copy_and_hash(source, &temp) {
+                Ok(hash) => hash,
+                Err(err) => {
+                    let _ = fs::remove_file(&temp);
+                    return Err(err);
+                }
+            };
+            if !checksum_matches(&hash, checksum) {
+                let _ = fs::remove_file(&temp);
+                bail!(
+                    "Checksum mismatch for {}: expected {}, got {}",
+                    name,
+                    checksum,
+                    hash
+                );
+            }
+            fs::rename(&temp, &dest)?;
+        }
+
+        Ok(ImageInfo {
+            name,
+            size: fs::metadata(&dest)?.len(),
+        })
+    }
+
+    /// Check that a staged image matches the given checksum
+    pub fn verify(&self, name: &str, checksum: &str) -> Result<(), Error> {
+        let path = self.image_path(name)?;
+        let hash = hash_file(&path)?;
+
+        if !checksum_matches(&hash, checksum) {
+            bail!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                name,
+                checksum,
+                hash
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Select a staged image to be installed at the next boot
+    pub fn set_next_boot(&self, name: &str) -> Result<(), Error> {
+        self.image_path(name)?;
+        self.vars().set_next_boot(name)
+    }
+
+    /// Cancel a pending upgrade or rollback
+    pub fn clear_next_boot(&self) -> Result<(), Error> {
+        self.vars().clear_next_boot()
+    }
+
+    /// Accept the currently running image after a successful boot. This resets
+    /// the boot count and recovery flags, so that U-Boot won't fall back to the
+    /// previous version. Returns the committed version.
+    pub fn commit(&self) -> Result<String, Error> {
+        let current = self
+            .vars()
+            .get_str(VAR_KUBOS_CURR_VERSION)
+            .ok_or_else(|| format_err!("Unable to determine the current version"))?;
+
+        self.vars().apply(
+            &UBootUpdate::new()
+                .set_u32(VAR_BOOT_COUNT, 0)
+                .unset(VAR_KUBOS_CURR_TRIED)
+                .unset(VAR_KUBOS_UPDATE_FILE),
+        )?;
+
+        Ok(current)
+    }
+
+    /// Select the previous version of KubOS to be installed at the next boot.
+    /// Returns the name of the selected image.
+    pub fn rollback(&self) -> Result<String, Error> {
+        let prev = self
+            .vars()
+            .alternate_version()
+            .ok_or_else(|| format_err!("No previous version to roll back to"))?;
+
+        // Like any other upgrade, U-Boot can only install an image which is
+        // actually present in the upgrade partition
+        self.image_path(&prev)?;
+        self.vars().set_next_boot(&prev)?;
+        Ok(prev)
+    }
+
+    fn check_name(&self, name: &str) -> Result<(), Error> {
+        if !is_image_name(name) || name.contains('/') {
+            bail!("Invalid image name {}: expected a kpack *.itb file", name);
+        }
+        Ok(())
+    }
+
+    // Get the path to an existing image in the upgrade partition
+    fn image_path(&self, name: &str) -> Result<PathBuf, Error> {
+        self.check_name(name)?;
+
+        let path = self.dir().join(name);
+        if !path.is_file() {
+            bail!("Image {} has not been staged", name);
+        }
+
+        Ok(path)
+    }
+}
+
+impl ConfigReload for Upgrader {
+    fn reload(&self, config: &Config) -> Result<(), Error> {
+        let settings = Settings::from_config(config)?;
+        *self.settings.write().unwrap() = settings;
+        Ok(())
+    }
+}
+
+fn is_image_name(name: &str) -> bool {
+    !name.starts_with('.') && name.ends_with(".itb")
+}
+
+fn checksum_matches(hash: &str, checksum: &str) -> bool {
+    hash.eq_ignore_ascii_case(checksum.trim())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn hash_file(path: &Path) -> Result<String, Error> {
+    let mut input = File::open(path)?;
+    let mut hasher = Blake2s::new(HASH_SIZE);
+    let mut buffer = [0; CHUNK_SIZE];
+
+    loop {
+        let count = input.read(&mut buffer)?;
+        if count == 0 {
+            break;
+        }
+        hasher.update(&buffer[0..count]);
+    }
+
+    Ok(hex(hasher.finalize().as_bytes()))
+}
+
+fn copy_and_hash(source: &Path, dest: &Path) -> Result<String, Error> {
+    let mut input =
+        File::open(source).map_err(|err| format_err!("Unable to open {:?}: {}", source, err))?;
+    let mut output = File::create(dest)?;
+    let mut hasher = Blake2s::new(HASH_SIZE);
+    let mut buffer = [0; CHUNK_SIZE];
+
+    loop {
+        let count = input.read(&mut buffer)?;
+        if count == 0 {
+            break;
+        }
+        hasher.update(&buffer[0..count]);
+        output.write_all(&buffer[0..count])?;
+    }
+
+    // Make sure the image is actually on disk before it can be selected for booting
+    output.sync_all()?;
+
+    Ok(hex(hasher.finalize().as_bytes()))
+}